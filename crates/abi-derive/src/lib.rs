@@ -0,0 +1,150 @@
+//! Derive macros for the `essential_types::abi` encoding.
+//!
+//! Structs are encoded as their fields in declaration order. Enums are
+//! encoded as the index of the variant followed by its fields.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Fields, Generics, Ident};
+
+/// Derive `essential_types::abi::Encode`.
+#[proc_macro_derive(Encode)]
+pub fn derive_encode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let generics = add_bounds(
+        input.generics.clone(),
+        quote!(::essential_types::abi::Encode),
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let (pattern, encode) = encode_fields(&data.fields);
+            quote! {
+                let Self #pattern = self;
+                #encode
+            }
+        }
+        Data::Enum(data) => {
+            let arms = data.variants.iter().enumerate().map(|(ix, variant)| {
+                let ident = &variant.ident;
+                let tag = ix as i64;
+                let (pattern, encode) = encode_fields(&variant.fields);
+                quote! {
+                    Self::#ident #pattern => {
+                        words.push(#tag);
+                        #encode
+                    }
+                }
+            });
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(_) => return unsupported(&input, "unions"),
+    };
+    quote! {
+        impl #impl_generics ::essential_types::abi::Encode for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn encode(&self, words: &mut Vec<::essential_types::Word>) {
+                #body
+            }
+        }
+    }
+    .into()
+}
+
+/// Derive `essential_types::abi::Decode`.
+#[proc_macro_derive(Decode)]
+pub fn derive_decode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let generics = add_bounds(
+        input.generics.clone(),
+        quote!(::essential_types::abi::Decode),
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let fields = decode_fields(&data.fields);
+            quote!(Ok(Self #fields))
+        }
+        Data::Enum(data) => {
+            let arms = data.variants.iter().enumerate().map(|(ix, variant)| {
+                let ident = &variant.ident;
+                let tag = ix as i64;
+                let fields = decode_fields(&variant.fields);
+                quote!(#tag => Ok(Self::#ident #fields),)
+            });
+            quote! {
+                match ::essential_types::abi::take_word(words)? {
+                    #(#arms)*
+                    tag => Err(::essential_types::abi::DecodeError::InvalidTag(tag)),
+                }
+            }
+        }
+        Data::Union(_) => return unsupported(&input, "unions"),
+    };
+    quote! {
+        impl #impl_generics ::essential_types::abi::Decode for #name #ty_generics #where_clause {
+            fn decode(
+                words: &mut &[::essential_types::Word],
+            ) -> Result<Self, ::essential_types::abi::DecodeError> {
+                #body
+            }
+        }
+    }
+    .into()
+}
+
+/// Bound each of the type parameters by the given trait.
+fn add_bounds(mut generics: Generics, bound: TokenStream2) -> Generics {
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(#bound));
+    }
+    generics
+}
+
+/// The pattern binding each of the fields, and the encoding of the bindings.
+fn encode_fields(fields: &Fields) -> (TokenStream2, TokenStream2) {
+    let bindings: Vec<Ident> = (0..fields.len())
+        .map(|ix| format_ident!("field_{ix}"))
+        .collect();
+    let pattern = match fields {
+        Fields::Named(fields) => {
+            let names = fields.named.iter().map(|field| &field.ident);
+            quote!({ #(#names: #bindings),* })
+        }
+        Fields::Unnamed(_) => quote!(( #(#bindings),* )),
+        Fields::Unit => quote!(),
+    };
+    let encode = quote! {
+        #(::essential_types::abi::Encode::encode(#bindings, words);)*
+    };
+    (pattern, encode)
+}
+
+/// The constructor fields, each decoded in declaration order.
+fn decode_fields(fields: &Fields) -> TokenStream2 {
+    let decode = quote!(::essential_types::abi::Decode::decode(words)?);
+    match fields {
+        Fields::Named(fields) => {
+            let names = fields.named.iter().map(|field| &field.ident);
+            quote!({ #(#names: #decode),* })
+        }
+        Fields::Unnamed(fields) => {
+            let decodes = fields.unnamed.iter().map(|_| &decode);
+            quote!(( #(#decodes),* ))
+        }
+        Fields::Unit => quote!(),
+    }
+}
+
+fn unsupported(input: &DeriveInput, what: &str) -> TokenStream {
+    syn::Error::new_spanned(&input.ident, format!("{what} are not supported"))
+        .to_compile_error()
+        .into()
+}
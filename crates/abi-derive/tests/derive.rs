@@ -0,0 +1,75 @@
+use essential_types::{
+    abi::{Decode, DecodeError, Encode},
+    ContentAddress, Word,
+};
+
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+struct Account {
+    owner: ContentAddress,
+    balance: Word,
+    nonce: Option<u32>,
+    name: String,
+    history: Vec<(bool, Word)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+enum Order {
+    Cancelled,
+    Open(Word, [u8; 2]),
+    Filled { by: Account },
+}
+
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+struct Wrapper<T>(T);
+
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+struct Unit;
+
+fn account() -> Account {
+    Account {
+        owner: ContentAddress([0; 32]),
+        balance: 100,
+        nonce: Some(7),
+        name: "a".to_string(),
+        history: vec![(true, 1), (false, -1)],
+    }
+}
+
+#[test]
+fn derive_struct() {
+    let account = account();
+    let words = account.to_words();
+    let mut expected = vec![0; 4];
+    expected.extend([100, 1, 7, 1, 0x61 << 56, 2, 1, 1, 0, -1]);
+    assert_eq!(words, expected);
+    assert_eq!(Account::from_words(&words), Ok(account));
+
+    assert_eq!(Wrapper(3i64).to_words(), vec![3]);
+    assert_eq!(Wrapper::<Word>::from_words(&[3]), Ok(Wrapper(3)));
+    assert!(Unit.to_words().is_empty());
+    assert_eq!(Unit::from_words(&[]), Ok(Unit));
+}
+
+#[test]
+fn derive_enum() {
+    let orders = vec![
+        Order::Cancelled,
+        Order::Open(5, [1, 2]),
+        Order::Filled { by: account() },
+    ];
+    assert_eq!(orders[0].to_words(), vec![0]);
+    assert_eq!(orders[1].to_words(), vec![1, 5, 1, 2]);
+    let words = orders.to_words();
+    assert_eq!(Vec::<Order>::from_words(&words), Ok(orders));
+
+    assert_eq!(Order::from_words(&[3]), Err(DecodeError::InvalidTag(3)));
+    assert_eq!(
+        Order::from_words(&[1, 5, 1, 256]),
+        Err(DecodeError::OutOfRange(256))
+    );
+    assert_eq!(Order::from_words(&[1, 5]), Err(DecodeError::UnexpectedEnd));
+    assert_eq!(
+        Order::from_words(&[0, 0]),
+        Err(DecodeError::TrailingWords(1))
+    );
+}
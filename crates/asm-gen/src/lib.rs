@@ -170,6 +170,8 @@ fn op_enum_decl(name: &str, group: &Group) -> syn::ItemEnum {
     let item_enum = syn::parse_quote! {
         #[doc = #docs]
         #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+        #[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+        #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
         pub enum #ident {
             #variants
         }
@@ -185,6 +187,8 @@ fn opcode_enum_decl(name: &str, group: &Group) -> syn::ItemEnum {
     let item_enum = syn::parse_quote! {
         #[doc = #docs]
         #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+        #[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+        #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
         #[repr(u8)]
         pub enum #ident {
             #variants
@@ -650,6 +654,74 @@ fn opcode_enum_impl_tryfrom_u8(name: &str, group: &Group) -> syn::ItemImpl {
     }
 }
 
+/// Generate the short name (mnemonic) for an op.
+fn op_short_name(names: &[String], op: &Op) -> String {
+    if op.short.is_empty() {
+        names.last().unwrap().to_uppercase()
+    } else {
+        op.short.clone()
+    }
+}
+
+/// Generate the `stack_out` expression for an op.
+fn stack_out_expr(stack_out: &StackOut) -> syn::Expr {
+    match stack_out {
+        StackOut::Fixed(words) => syn::parse_quote! {
+            StackOut::Fixed(&[#(#words),*])
+        },
+        StackOut::Dynamic(out) => {
+            let elem = &out.elem;
+            let len = &out.len;
+            syn::parse_quote! {
+                StackOut::Dynamic { elem: #elem, len: #len }
+            }
+        }
+    }
+}
+
+/// Generate the implementation providing spec information for the top-level opcode enum.
+fn opcode_enum_impl_spec(name: &str, group: &Group) -> syn::ItemImpl {
+    let name = syn::Ident::new(name, Span::call_site());
+    let mut short_arms: Vec<syn::Arm> = vec![];
+    let mut stack_in_arms: Vec<syn::Arm> = vec![];
+    let mut stack_out_arms: Vec<syn::Arm> = vec![];
+    let mut names = vec!["Self".to_string()];
+    visit::ops_filtered_recurse(&group.tree, &|_| true, &mut names, &mut |names, op| {
+        let opcode_expr = opcode_expr_from_names(names);
+        let short = op_short_name(names, op);
+        let stack_in = &op.stack_in;
+        let stack_out = stack_out_expr(&op.stack_out);
+        short_arms.push(syn::parse_quote! { #opcode_expr => #short, });
+        stack_in_arms.push(syn::parse_quote! { #opcode_expr => &[#(#stack_in),*], });
+        stack_out_arms.push(syn::parse_quote! { #opcode_expr => #stack_out, });
+    });
+    syn::parse_quote! {
+        impl #name {
+            /// The short name of the operation, as used by the [`short`][crate::short] consts.
+            pub const fn short(&self) -> &'static str {
+                match self {
+                    #(#short_arms)*
+                }
+            }
+
+            /// The names of the words the operation expects on the stack, as
+            /// declared in the spec.
+            pub const fn stack_in(&self) -> &'static [&'static str] {
+                match self {
+                    #(#stack_in_arms)*
+                }
+            }
+
+            /// The words the operation pushes onto the stack, as declared in the spec.
+            pub const fn stack_out(&self) -> StackOut {
+                match self {
+                    #(#stack_out_arms)*
+                }
+            }
+        }
+    }
+}
+
 /// Generate the implementations for the given op group enum.
 fn op_enum_impls(names: &[String], group: &Group) -> Vec<syn::ItemImpl> {
     let name = names.last().unwrap();
@@ -681,11 +753,7 @@ fn op_const_expr(names: &[String], insert_word: bool) -> syn::Expr {
 
 /// Generate the const declarations for the given op.
 fn op_consts(names: &[String], op: &Op) -> Vec<syn::Item> {
-    let const_name = if op.short.is_empty() {
-        syn::Ident::new(&names.last().unwrap().to_uppercase(), Span::call_site())
-    } else {
-        syn::Ident::new(&op.short, Span::call_site())
-    };
+    let const_name = syn::Ident::new(&op_short_name(names, op), Span::call_site());
     let docs = format!("## {}\n\n{}", names.last().unwrap(), op_docs(op));
 
     if op.num_arg_bytes == 0 {
@@ -791,10 +859,187 @@ fn all_opcode_enum_impls(tree: &Tree) -> Vec<syn::Item> {
                 .into_iter()
                 .map(syn::Item::Impl),
         );
+        // Spec information is only provided for the top-level opcode enum.
+        if name.len() == 1 {
+            let name = name.last().unwrap();
+            items.push(syn::Item::Impl(opcode_enum_impl_spec(name, group)));
+        }
     });
     items
 }
 
+/// Word immediates that are always exercised by the generated roundtrip tests.
+const EDGE_IMMEDIATES: &[essential_types::Word] = &[
+    0,
+    1,
+    -1,
+    0xFF,
+    0x100,
+    0x0102_0304_0506_0708,
+    -0x0102_0304_0506_0708,
+    essential_types::Word::MIN,
+    essential_types::Word::MAX,
+];
+
+/// The number of pseudo-random immediates exercised by the generated roundtrip tests.
+const NUM_RANDOM_IMMEDIATES: usize = 64;
+
+/// Produce the immediates used by the generated roundtrip tests.
+///
+/// The random immediates are produced at expansion time with a fixed seed so
+/// that the generated tests are deterministic.
+fn roundtrip_immediates() -> Vec<essential_types::Word> {
+    let mut state: u64 = 0x5EED;
+    let mut next = move || {
+        // SplitMix64
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    };
+    let mut words = EDGE_IMMEDIATES.to_vec();
+    words.extend((0..NUM_RANDOM_IMMEDIATES).map(|_| next() as essential_types::Word));
+    words
+}
+
+/// Convert an op's nested names into a snake case test name.
+/// E.g. `[Op, Stack, DupFrom]` becomes `roundtrip_stack_dup_from`.
+fn roundtrip_test_ident(names: &[String]) -> syn::Ident {
+    let mut name = "roundtrip".to_string();
+    for n in &names[1..] {
+        name.push('_');
+        for (i, c) in n.chars().enumerate() {
+            if c.is_uppercase() && i != 0 {
+                name.push('_');
+            }
+            name.push(c.to_ascii_lowercase());
+        }
+    }
+    syn::Ident::new(&name, Span::call_site())
+}
+
+/// Generate a roundtrip test for a single op.
+fn op_roundtrip_test(names: &[String], op: &Op) -> syn::ItemFn {
+    let test_ident = roundtrip_test_ident(names);
+    let opcode = op.opcode;
+    let num_arg_bytes = op.num_arg_bytes as usize;
+    if op.num_arg_bytes == 0 {
+        let expr = op_const_expr(names, false);
+        syn::parse_quote! {
+            #[test]
+            fn #test_ident() {
+                roundtrip_op(Op::#expr, #opcode, #num_arg_bytes);
+            }
+        }
+    } else {
+        let expr = op_const_expr(names, true);
+        syn::parse_quote! {
+            #[test]
+            fn #test_ident() {
+                for word in IMMEDIATES {
+                    roundtrip_op(Op::#expr, #opcode, #num_arg_bytes);
+                }
+            }
+        }
+    }
+}
+
+/// Generate the hidden module of exhaustive roundtrip tests.
+fn op_roundtrip_tests_mod(tree: &Tree) -> syn::ItemMod {
+    let immediates = roundtrip_immediates();
+    let num_immediates = immediates.len();
+    let mut tests = vec![];
+    let mut all_ops: Vec<syn::Expr> = vec![];
+    let mut opcodes = vec![];
+    let mut immediate_ixs = (0..num_immediates).cycle();
+    visit::ops(tree, &mut |names, op| {
+        tests.push(op_roundtrip_test(names, op));
+        opcodes.push(op.opcode);
+        if op.num_arg_bytes == 0 {
+            let expr = op_const_expr(names, false);
+            all_ops.push(syn::parse_quote!(Op::#expr));
+        } else {
+            // Interleave a different immediate for each occurrence.
+            for _ in 0..3 {
+                let ix = immediate_ixs.next().unwrap();
+                let expr = op_const_expr(names, true);
+                all_ops.push(syn::parse_quote!({
+                    let word = IMMEDIATES[#ix];
+                    Op::#expr
+                }));
+            }
+        }
+    });
+    syn::parse_quote! {
+        #[cfg(test)]
+        #[doc(hidden)]
+        mod generated_roundtrip_tests {
+            extern crate std;
+            use super::*;
+            use std::vec::Vec;
+
+            /// Word immediates including edge cases and fixed-seed pseudo-random values.
+            const IMMEDIATES: [essential_types::Word; #num_immediates] = [#(#immediates),*];
+
+            /// All opcodes declared within the spec.
+            const OPCODES: &[u8] = &[#(#opcodes),*];
+
+            /// Roundtrip a single op through its bytes and opcode conversions.
+            fn roundtrip_op(op: Op, opcode: u8, num_arg_bytes: usize) {
+                // `to_bytes` and `try_from_bytes`.
+                let bytes: Vec<u8> = op.to_bytes().into_iter().collect();
+                assert_eq!(bytes.len(), 1 + num_arg_bytes, "{op:?}");
+                assert_eq!(bytes[0], opcode, "{op:?}");
+                let mut iter = bytes.iter().copied();
+                let parsed = Op::try_from_bytes(&mut iter)
+                    .expect("expected an op")
+                    .expect("failed to parse op");
+                assert_eq!(parsed, op);
+                assert!(iter.next().is_none(), "{op:?} left trailing bytes");
+
+                // Truncated bytes must never produce an op.
+                for len in 1..bytes.len() {
+                    let mut iter = bytes[..len].iter().copied();
+                    match Op::try_from_bytes(&mut iter) {
+                        Some(Err(FromBytesError::NotEnoughBytes(_))) => (),
+                        res => panic!("{op:?} truncated to {len} bytes: {res:?}"),
+                    }
+                }
+
+                // `u8` conversions.
+                let op_opcode = op.to_opcode();
+                assert_eq!(u8::from(op_opcode), opcode);
+                assert_eq!(Opcode::try_from(opcode).unwrap(), op_opcode);
+            }
+
+            #(#tests)*
+
+            #[test]
+            fn roundtrip_all_ops_sequence() {
+                let ops: Vec<Op> = std::vec![#(#all_ops),*];
+                let bytes: Vec<u8> = to_bytes(ops.iter().copied()).collect();
+                let parsed = from_bytes(bytes)
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap();
+                assert_eq!(ops, parsed);
+            }
+
+            #[test]
+            fn undeclared_opcodes_are_invalid() {
+                for byte in 0..=u8::MAX {
+                    let res = Opcode::try_from(byte);
+                    if OPCODES.contains(&byte) {
+                        assert_eq!(u8::from(res.unwrap()), byte);
+                    } else {
+                        assert!(matches!(res, Err(InvalidOpcodeError(b)) if b == byte));
+                    }
+                }
+            }
+        }
+    }
+}
+
 const DOCS_TABLE_HEADER: &str = "\n\n\
     | Opcode | Op | Short Description |\n\
     | --- | --- | --- |\n";
@@ -885,3 +1130,13 @@ pub fn gen_all_op_consts(_input: TokenStream) -> TokenStream {
 
     token_stream_from_items(items)
 }
+
+/// Generate a hidden `#[cfg(test)]` module that exhaustively roundtrips every
+/// operation (and a range of word immediates) through its byte and opcode
+/// conversions.
+#[proc_macro]
+pub fn gen_op_roundtrip_tests(_input: TokenStream) -> TokenStream {
+    let tree = essential_asm_spec::tree();
+    let item = op_roundtrip_tests_mod(&tree);
+    item.into_token_stream().into()
+}
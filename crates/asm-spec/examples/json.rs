@@ -0,0 +1,9 @@
+//! Print the ASM spec as JSON.
+//!
+//! ```text
+//! cargo run -p essential-asm-spec --example json > crates/asm-spec/asm.json
+//! ```
+
+fn main() {
+    println!("{}", essential_asm_spec::to_json());
+}
@@ -1,14 +1,28 @@
 //! A small crate that exports the [ASM_YAML] spec string and provides a
 //! structured [Tree] model representing its deserialized form.
+//!
+//! The tree may also be exported as JSON with [to_json] for tooling that would
+//! rather not parse the YAML. The generated [ASM_JSON] artifact is included for
+//! convenience.
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 mod de;
+mod ser;
 pub mod visit;
 
 /// The raw YAML specification string.
 pub const ASM_YAML: &str = include_str!("./../asm.yml");
 
+/// The spec exported as pretty-printed JSON.
+///
+/// This is generated from [ASM_YAML] with [to_json]. Regenerate with:
+///
+/// ```text
+/// cargo run -p essential-asm-spec --example json > crates/asm-spec/asm.json
+/// ```
+pub const ASM_JSON: &str = include_str!("./../asm.json");
+
 /// Operations are laid out in a rose tree.
 /// Nodes are ordered by their opcode, ensured during deserialisation.
 #[derive(Debug)]
@@ -22,7 +36,7 @@ pub enum Node {
 }
 
 /// A group of related operations and subgroups.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Group {
     pub description: String,
     #[serde(rename = "group")]
@@ -32,7 +46,7 @@ pub struct Group {
 /// A single operation.
 ///
 /// For the meaning of each of these fields, refer to the `essential-asm-spec` crate README.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Op {
     pub opcode: u8,
     pub description: String,
@@ -56,7 +70,7 @@ pub enum StackOut {
 }
 
 /// The stack output size is dynamic, dependent on a `stack_in` value.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct StackOutDynamic {
     pub elem: String,
     pub len: String,
@@ -93,6 +107,30 @@ pub fn tree() -> Tree {
         .expect("ASM_YAML is a const and should never fail to deserialize")
 }
 
+/// Serialize the top-level op tree to pretty-printed JSON.
+///
+/// The JSON mirrors the layout of the YAML, with the following differences:
+///
+/// - Entries are ordered by opcode.
+/// - All optional fields are present with their defaults.
+/// - An omitted `short` name is resolved to the uppercase op name.
+pub fn to_json() -> String {
+    let mut tree = tree();
+    resolve_short_names(&mut tree);
+    serde_json::to_string_pretty(&tree).expect("the op tree should always serialize to JSON")
+}
+
+/// Default any omitted `short` names to the uppercase op name.
+fn resolve_short_names(tree: &mut Tree) {
+    for (name, node) in &mut tree.0 {
+        match node {
+            Node::Op(op) if op.short.is_empty() => op.short = name.to_uppercase(),
+            Node::Op(_) => (),
+            Node::Group(group) => resolve_short_names(&mut group.tree),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -119,6 +157,27 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_json_matches_yaml() {
+        // If this fails, `asm.json` must be regenerated. See `ASM_JSON`.
+        assert_eq!(ASM_JSON.trim_end(), to_json());
+    }
+
+    #[test]
+    fn test_json_layout() {
+        let json: serde_json::Value = serde_json::from_str(ASM_JSON).unwrap();
+        let push = &json["Op"]["group"]["Stack"]["group"]["Push"];
+        assert_eq!(push["opcode"], 0x01);
+        assert_eq!(push["short"], "PUSH");
+        assert_eq!(push["num_arg_bytes"], 8);
+        assert_eq!(push["stack_in"], serde_json::json!([]));
+        assert_eq!(push["stack_out"], serde_json::json!(["value"]));
+        let dupf = &json["Op"]["group"]["Stack"]["group"]["DupFrom"];
+        assert_eq!(dupf["short"], "DUPF");
+        // Entries are in opcode order rather than alphabetical.
+        assert!(ASM_JSON.find("\"Push\"") < ASM_JSON.find("\"Pop\""));
+    }
+
     #[test]
     fn test_visit_ordered_by_opcode() {
         let tree = tree();
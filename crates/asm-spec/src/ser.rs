@@ -0,0 +1,45 @@
+//! Custom serialize implementations for enums and the `Tree` type.
+//!
+//! These mirror the layout of the YAML so that the serialized form may be
+//! consumed in the same manner as the spec itself.
+
+use crate::{Node, StackOut, Tree};
+use serde::{ser::SerializeMap, Serialize, Serializer};
+
+impl Serialize for Tree {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Serialize as a mapping, preserving the opcode order of the entries.
+        let mut map = s.serialize_map(Some(self.len()))?;
+        for (name, node) in self.iter() {
+            map.serialize_entry(name, node)?;
+        }
+        map.end()
+    }
+}
+
+impl Serialize for Node {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Node::Op(op) => op.serialize(s),
+            Node::Group(group) => group.serialize(s),
+        }
+    }
+}
+
+impl Serialize for StackOut {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            StackOut::Fixed(fixed) => fixed.serialize(s),
+            StackOut::Dynamic(dynamic) => dynamic.serialize(s),
+        }
+    }
+}
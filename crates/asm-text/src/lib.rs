@@ -0,0 +1,271 @@
+//! A human-readable text format for Essential assembly.
+//!
+//! Provides a [`parse`] function for parsing assembly text into a sequence of
+//! [`Op`]s, and a [`to_string`] function for printing a sequence of [`Op`]s
+//! back to text.
+//!
+//! # Format
+//!
+//! - Each line contains at most one operation, written as its mnemonic (e.g.
+//!   `PUSH`, `ADD`, `JMPIF`) followed by any immediate argument. Mnemonics
+//!   are the short names from the [op table][essential_asm] and are case
+//!   insensitive.
+//! - Comments begin with `;` and continue to the end of the line.
+//! - Numeric literals may be decimal (`42`, `-7`) or hex (`0x2A`). Hex
+//!   literals are the two's complement bits of the word, so
+//!   `0xFFFFFFFFFFFFFFFF` is `-1`. Underscores may be used as separators.
+//! - A label is declared with `name:`, either on its own line or preceding an
+//!   operation, and marks the index of the next operation.
+//! - A label may be referenced as the argument to `PUSH` with `@name`. The
+//!   reference resolves to the relative distance from the next `JMPIF`
+//!   following the `PUSH` to the label, i.e. the distance expected by that
+//!   `JMPIF`.
+//!
+//! ```
+//! use essential_asm::short::*;
+//!
+//! let src = "
+//!     PUSH 0x01 ; a comment
+//!     PUSH @end
+//!     PUSH 1
+//!     JMPIF
+//!     PUSH 2
+//!     end: PUSH 3
+//! ";
+//! let ops = essential_asm_text::parse(src).unwrap();
+//! let expected = [PUSH(1), PUSH(2), PUSH(1), JMPIF, PUSH(2), PUSH(3)];
+//! assert_eq!(ops, expected);
+//! ```
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+use essential_asm::{
+    label::{Assembler, LabelErrorKind},
+    opcode::ParseOp,
+    Op, Opcode, ToBytes, ToOpcode,
+};
+use essential_types::{convert::word_from_bytes, Word};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::LazyLock,
+};
+use thiserror::Error;
+
+/// An error occurring while parsing assembly text.
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("line {line}: {kind}")]
+pub struct ParseError {
+    /// The 1-based line number at which the error occurred.
+    pub line: usize,
+    /// The kind of error.
+    pub kind: ParseErrorKind,
+}
+
+/// The kinds of errors that may occur while parsing assembly text.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// The mnemonic does not match any operation.
+    #[error("unknown mnemonic `{0}`")]
+    UnknownMnemonic(String),
+    /// The operation requires an argument, but none was given.
+    #[error("`{0}` expects an argument")]
+    MissingArg(String),
+    /// An unexpected token followed the operation.
+    #[error("unexpected token `{0}`")]
+    UnexpectedToken(String),
+    /// A numeric literal was invalid.
+    #[error("invalid literal `{0}`")]
+    InvalidLiteral(String),
+    /// A label name was invalid.
+    #[error("invalid label `{0}`")]
+    InvalidLabel(String),
+    /// A label was declared more than once.
+    #[error("duplicate label `{0}`")]
+    DuplicateLabel(String),
+    /// A referenced label was never declared.
+    #[error("undefined label `{0}`")]
+    UndefinedLabel(String),
+    /// A label was referenced, but no `JMPIF` follows the reference.
+    #[error("reference to label `{0}` is not followed by a `JMPIF`")]
+    MissingJump(String),
+}
+
+/// The mnemonic table derived from the assembly spec.
+struct Mnemonics {
+    /// Uppercase mnemonic to opcode and number of argument bytes.
+    by_name: HashMap<String, (u8, u8)>,
+    /// Opcode to mnemonic.
+    by_opcode: HashMap<u8, String>,
+}
+
+/// The argument to an operation.
+enum Arg {
+    Word(Word),
+    Label(String),
+}
+
+static MNEMONICS: LazyLock<Mnemonics> = LazyLock::new(|| {
+    let mut by_name = HashMap::new();
+    let mut by_opcode = HashMap::new();
+    essential_asm_spec::visit::ops(&essential_asm_spec::tree(), &mut |names, op| {
+        let name = if op.short.is_empty() {
+            names.last().unwrap().to_uppercase()
+        } else {
+            op.short.clone()
+        };
+        by_name.insert(name.clone(), (op.opcode, op.num_arg_bytes));
+        by_opcode.insert(op.opcode, name);
+    });
+    Mnemonics { by_name, by_opcode }
+});
+
+/// The mnemonic for the given operation.
+pub fn mnemonic(op: &Op) -> &'static str {
+    let opcode = u8::from(op.to_opcode());
+    MNEMONICS
+        .by_opcode
+        .get(&opcode)
+        .expect("all opcodes are declared in the spec")
+}
+
+/// Parse the given assembly text into a sequence of operations.
+///
+/// Label references are resolved with the [`Assembler`], as for the
+/// [`essential_asm::ops`] macro.
+pub fn parse(src: &str) -> Result<Vec<Op>, ParseError> {
+    let push = u8::from(Op::from(essential_asm::Stack::Push(0)).to_opcode());
+    let mut asm = Assembler::new();
+    let mut labels: HashSet<&str> = HashSet::new();
+    // The line number of each operation.
+    let mut op_lines: Vec<usize> = vec![];
+
+    for (ix, text) in src.lines().enumerate() {
+        let number = ix + 1;
+        let err = |kind| ParseError { line: number, kind };
+        let mut text = text.split(';').next().unwrap_or_default().trim();
+
+        // Declare any labels preceding the operation.
+        while let Some((label, rest)) = split_label(text) {
+            if !is_ident(label) {
+                return Err(err(ParseErrorKind::InvalidLabel(label.to_string())));
+            }
+            if !labels.insert(label) {
+                return Err(err(ParseErrorKind::DuplicateLabel(label.to_string())));
+            }
+            asm.label(label);
+            text = rest.trim_start();
+        }
+
+        let mut tokens = text.split_whitespace();
+        let Some(name) = tokens.next() else {
+            continue;
+        };
+        let &(opcode, num_arg_bytes) = MNEMONICS
+            .by_name
+            .get(&name.to_uppercase())
+            .ok_or_else(|| err(ParseErrorKind::UnknownMnemonic(name.to_string())))?;
+        let arg = if num_arg_bytes == 0 {
+            None
+        } else {
+            let token = tokens
+                .next()
+                .ok_or_else(|| err(ParseErrorKind::MissingArg(name.to_string())))?;
+            let arg = match token.strip_prefix('@') {
+                Some(label) if is_ident(label) && opcode == push => Arg::Label(label.to_string()),
+                Some(_) => return Err(err(ParseErrorKind::InvalidLabel(token.to_string()))),
+                None => Arg::Word(
+                    parse_word(token)
+                        .ok_or_else(|| err(ParseErrorKind::InvalidLiteral(token.to_string())))?,
+                ),
+            };
+            Some(arg)
+        };
+        if let Some(token) = tokens.next() {
+            return Err(err(ParseErrorKind::UnexpectedToken(token.to_string())));
+        }
+        op_lines.push(number);
+        let word = match arg {
+            Some(Arg::Label(label)) => {
+                asm.push_label(label);
+                continue;
+            }
+            Some(Arg::Word(word)) => word,
+            None => 0,
+        };
+        let arg_bytes = word.to_be_bytes();
+        let mut bytes = arg_bytes[8 - num_arg_bytes as usize..].iter().copied();
+        let opcode = Opcode::try_from(opcode).expect("opcodes in the table are valid");
+        let op = opcode
+            .parse_op(&mut bytes)
+            .expect("argument bytes are provided");
+        asm.op(op);
+    }
+
+    asm.finish().map_err(|err| {
+        // Duplicates are caught above, so the error is at an operation.
+        let line = op_lines.get(err.index).copied().unwrap_or_default();
+        let kind = match err.kind {
+            LabelErrorKind::DuplicateLabel => ParseErrorKind::DuplicateLabel(err.label),
+            LabelErrorKind::UndefinedLabel => ParseErrorKind::UndefinedLabel(err.label),
+            LabelErrorKind::MissingJump => ParseErrorKind::MissingJump(err.label),
+        };
+        ParseError { line, kind }
+    })
+}
+
+/// Write the given operations to the given writer as assembly text.
+///
+/// Each operation is written on its own line.
+pub fn write(w: &mut impl fmt::Write, ops: &[Op]) -> fmt::Result {
+    for op in ops {
+        w.write_str(mnemonic(op))?;
+        let arg: Vec<u8> = op.to_bytes().skip(1).collect();
+        if !arg.is_empty() {
+            let mut bytes = [0u8; 8];
+            bytes[8 - arg.len()..].copy_from_slice(&arg);
+            write!(w, " {}", word_from_bytes(bytes))?;
+        }
+        writeln!(w)?;
+    }
+    Ok(())
+}
+
+/// Print the given operations as assembly text.
+///
+/// The result may be parsed back into the same operations with [`parse`].
+pub fn to_string(ops: &[Op]) -> String {
+    let mut s = String::new();
+    write(&mut s, ops).expect("writing to a `String` cannot fail");
+    s
+}
+
+/// Split a leading `label:` from the given text.
+fn split_label(text: &str) -> Option<(&str, &str)> {
+    let (label, rest) = text.split_once(':')?;
+    (!label.contains(char::is_whitespace)).then_some((label, rest))
+}
+
+/// Whether the given string is a valid label name.
+fn is_ident(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Parse a decimal or hex word literal.
+fn parse_word(token: &str) -> Option<Word> {
+    let token = token.replace('_', "");
+    match token
+        .strip_prefix("0x")
+        .or_else(|| token.strip_prefix("0X"))
+    {
+        Some(hex) if !hex.is_empty() && hex.len() <= 16 => {
+            u64::from_str_radix(hex, 16).ok().map(|u| u as Word)
+        }
+        Some(_) => None,
+        None => token.parse().ok(),
+    }
+}
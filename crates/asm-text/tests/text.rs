@@ -0,0 +1,174 @@
+use essential_asm::{opcode::ParseOp, short::*, Op, Opcode};
+use essential_asm_text::{parse, to_string, ParseError, ParseErrorKind};
+
+fn parse_err(src: &str) -> ParseError {
+    parse(src).unwrap_err()
+}
+
+#[test]
+fn parse_mnemonics_and_literals() {
+    let src = "
+        push 42
+        PUSH -7
+        PUSH 0x2A
+        PUSH 0xFFFF_FFFF_FFFF_FFFF
+        PUSH 1_000
+        Add
+        POP
+    ";
+    let ops = parse(src).unwrap();
+    assert_eq!(
+        ops,
+        [PUSH(42), PUSH(-7), PUSH(42), PUSH(-1), PUSH(1000), ADD, POP]
+    );
+}
+
+#[test]
+fn parse_comments_and_blank_lines() {
+    let src = "
+        ; A leading comment.
+
+        PUSH 1 ; A trailing comment.
+        ;PUSH 2
+    ";
+    assert_eq!(parse(src).unwrap(), [PUSH(1)]);
+    assert_eq!(parse("").unwrap(), []);
+}
+
+#[test]
+fn parse_labels() {
+    // Sum the numbers 1 to 5.
+    let src = "
+        PUSH 0          ; sum
+        PUSH 5          ; counter
+        loop:
+            DUP
+            PUSH 2
+            DUPF
+            ADD
+            PUSH 2
+            SWAPI
+            POP
+            PUSH 1
+            SUB
+            PUSH @loop
+            PUSH 1
+            DUPF
+            PUSH 0
+            GT
+            JMPIF
+        POP
+    ";
+    let ops = parse(src).unwrap();
+    // `loop` is at index 2 and the `JMPIF` at index 16.
+    assert_eq!(ops[11], PUSH(-14));
+    assert_eq!(ops.len(), 18);
+    let expected = essential_asm::ops![
+        PUSH(0),
+        PUSH(5),
+        top: DUP,
+        PUSH(2),
+        DUPF,
+        ADD,
+        PUSH(2),
+        SWAPI,
+        POP,
+        PUSH(1),
+        SUB,
+        PUSH(@top),
+        PUSH(1),
+        DUPF,
+        PUSH(0),
+        GT,
+        JMPIF,
+        POP,
+    ];
+    assert_eq!(Ok(ops), expected);
+
+    // Forward references and labels preceding an operation.
+    let src = "
+        PUSH @end
+        PUSH 1
+        JMPIF
+        PUSH 2
+        end: PUSH 3
+        a: b: PUSH 4
+    ";
+    let ops = parse(src).unwrap();
+    assert_eq!(ops, [PUSH(2), PUSH(1), JMPIF, PUSH(2), PUSH(3), PUSH(4)]);
+}
+
+#[test]
+fn parse_errors() {
+    let err = parse_err("PUSH 1\nFOO");
+    assert_eq!(err.line, 2);
+    assert_eq!(err.kind, ParseErrorKind::UnknownMnemonic("FOO".into()));
+
+    let err = parse_err("PUSH");
+    assert_eq!(err.kind, ParseErrorKind::MissingArg("PUSH".into()));
+
+    let err = parse_err("POP 1");
+    assert_eq!(err.kind, ParseErrorKind::UnexpectedToken("1".into()));
+
+    let err = parse_err("PUSH 1 2");
+    assert_eq!(err.kind, ParseErrorKind::UnexpectedToken("2".into()));
+
+    let err = parse_err("PUSH 0x");
+    assert_eq!(err.kind, ParseErrorKind::InvalidLiteral("0x".into()));
+
+    let err = parse_err("PUSH 0x1FFFFFFFFFFFFFFFF");
+    assert_eq!(
+        err.kind,
+        ParseErrorKind::InvalidLiteral("0x1FFFFFFFFFFFFFFFF".into())
+    );
+
+    let err = parse_err("PUSH 9223372036854775808");
+    assert!(matches!(err.kind, ParseErrorKind::InvalidLiteral(_)));
+
+    let err = parse_err("1a: POP");
+    assert_eq!(err.kind, ParseErrorKind::InvalidLabel("1a".into()));
+
+    let err = parse_err("PUSH @1a");
+    assert_eq!(err.kind, ParseErrorKind::InvalidLabel("@1a".into()));
+
+    let err = parse_err("a:\na:");
+    assert_eq!(err.line, 2);
+    assert_eq!(err.kind, ParseErrorKind::DuplicateLabel("a".into()));
+
+    let err = parse_err("PUSH @a\nPUSH 1\nJMPIF");
+    assert_eq!(err.line, 1);
+    assert_eq!(err.kind, ParseErrorKind::UndefinedLabel("a".into()));
+
+    let err = parse_err("a: PUSH @a");
+    assert_eq!(err.kind, ParseErrorKind::MissingJump("a".into()));
+
+    let err = parse_err("POP\n\nPUSH @a\na: POP");
+    assert_eq!(err.line, 3);
+    assert_eq!(err.kind, ParseErrorKind::MissingJump("a".into()));
+
+    assert_eq!(
+        parse_err("PUSH 1\nFOO").to_string(),
+        "line 2: unknown mnemonic `FOO`"
+    );
+}
+
+#[test]
+fn print() {
+    let ops = [PUSH(-1), PUSH(42), ADD, JMPIF];
+    assert_eq!(to_string(&ops), "PUSH -1\nPUSH 42\nADD\nJMPIF\n");
+}
+
+#[test]
+fn roundtrip_all_ops() {
+    let ops: Vec<Op> = (0..=u8::MAX)
+        .filter_map(|byte| Opcode::try_from(byte).ok())
+        .flat_map(|opcode| {
+            [0, 1, -1, i64::MIN, i64::MAX].map(|word| {
+                let mut bytes = word.to_be_bytes().into_iter();
+                opcode.parse_op(&mut bytes).unwrap()
+            })
+        })
+        .collect();
+    let text = to_string(&ops);
+    assert_eq!(parse(&text).unwrap(), ops);
+}
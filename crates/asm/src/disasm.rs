@@ -0,0 +1,206 @@
+//! Disassembly of bytecode into an annotated listing of operations.
+//!
+//! Useful for inspecting programs where only the bytecode is available, e.g.
+//! programs produced by a compiler.
+//!
+//! ```
+//! use essential_asm::short::*;
+//!
+//! let bytes: Vec<u8> = essential_asm::to_bytes([PUSH(6), PUSH(7), MUL]).collect();
+//! let listing = essential_asm::disassemble(&bytes).unwrap();
+//! assert_eq!(listing.instructions[2].offset, 18);
+//! println!("{listing}");
+//! ```
+//!
+//! The listing displays each operation's byte offset, short name and any
+//! immediate, followed by a comment describing its stack effect from the spec:
+//!
+//! ```text
+//! 0000  PUSH 6  ; [] -> [value]
+//! 0009  PUSH 7  ; [] -> [value]
+//! 0012  MUL     ; [lhs, rhs] -> [lhs * rhs]
+//! ```
+
+use crate::{opcode::Words, FromBytesError, Op, Opcode, ToBytes, ToOpcode, TryFromBytes, Word};
+use alloc::{format, string::String, vec::Vec};
+use core::fmt;
+use essential_types::convert::word_from_bytes;
+
+/// A single decoded operation within a [`Disassembly`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Instruction {
+    /// The byte offset of the operation's opcode within the bytecode.
+    pub offset: usize,
+    /// The decoded operation.
+    pub op: Op,
+}
+
+/// The annotated listing of operations decoded from bytecode.
+///
+/// The [`Display`][fmt::Display] implementation produces one line per
+/// operation with its offset, short name, immediate and stack effect.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Disassembly {
+    /// The decoded operations in order of their offset.
+    pub instructions: Vec<Instruction>,
+}
+
+/// Disassembly failed part way through the bytecode.
+#[derive(Debug)]
+pub struct DisassembleError {
+    /// The byte offset of the operation that failed to decode.
+    pub offset: usize,
+    /// The decoding error.
+    pub error: FromBytesError,
+    /// The operations successfully decoded prior to the error.
+    pub disassembly: Disassembly,
+}
+
+impl Instruction {
+    /// The opcode of the operation.
+    pub fn opcode(&self) -> Opcode {
+        self.op.to_opcode()
+    }
+
+    /// The immediate value encoded in the bytecode following the opcode, if any.
+    pub fn immediate(&self) -> Option<Word> {
+        let mut bytes = self.op.to_bytes().skip(1);
+        let mut arg = [0u8; 8];
+        let mut len = 0;
+        for (b, byte) in arg.iter_mut().zip(bytes.by_ref()) {
+            *b = byte;
+            len += 1;
+        }
+        debug_assert!(bytes.next().is_none(), "immediates are at most one word");
+        (len > 0).then(|| word_from_bytes(arg))
+    }
+
+    /// The size of the operation in bytes, including any immediate.
+    pub fn size(&self) -> usize {
+        self.op.to_bytes().count()
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.opcode().short())?;
+        if let Some(word) = self.immediate() {
+            write!(f, " {word}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Disassembly {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ops: Vec<String> = self
+            .instructions
+            .iter()
+            .map(|inst| format!("{inst}"))
+            .collect();
+        let width = ops.iter().map(|s| s.len()).max().unwrap_or(0);
+        for (inst, op) in self.instructions.iter().zip(&ops) {
+            let opcode = inst.opcode();
+            writeln!(
+                f,
+                "{:04X}  {op:<width$}  ; [{}] -> {}",
+                inst.offset,
+                Words(opcode.stack_in()),
+                opcode.stack_out(),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DisassembleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "failed to disassemble at offset {}: {}",
+            self.offset, self.error
+        )
+    }
+}
+
+impl core::error::Error for DisassembleError {}
+
+/// Disassemble the given bytecode into an annotated listing of operations.
+///
+/// Returns an error containing the operations decoded so far in the case that
+/// an invalid opcode is encountered or an immediate is truncated.
+pub fn disassemble(bytes: &[u8]) -> Result<Disassembly, DisassembleError> {
+    let mut disassembly = Disassembly::default();
+    let mut iter = bytes.iter().copied();
+    let mut offset = 0;
+    while let Some(res) = Op::try_from_bytes(&mut iter) {
+        match res {
+            Ok(op) => {
+                let inst = Instruction { offset, op };
+                offset += inst.size();
+                disassembly.instructions.push(inst);
+            }
+            Err(error) => {
+                return Err(DisassembleError {
+                    offset,
+                    error,
+                    disassembly,
+                })
+            }
+        }
+    }
+    Ok(disassembly)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{opcode::StackOut, short::*};
+
+    #[test]
+    fn offsets_and_immediates() {
+        let bytes: Vec<u8> = crate::to_bytes([PUSH(-1), POP, PUSH(42), EQRA]).collect();
+        let listing = disassemble(&bytes).unwrap();
+        let offsets: Vec<_> = listing.instructions.iter().map(|i| i.offset).collect();
+        assert_eq!(offsets, [0, 9, 10, 19]);
+        let immediates: Vec<_> = listing.instructions.iter().map(|i| i.immediate()).collect();
+        assert_eq!(immediates, [Some(-1), None, Some(42), None]);
+        assert_eq!(listing.instructions[1].op, POP);
+    }
+
+    #[test]
+    fn spec_annotations() {
+        assert_eq!(PUSH(0).to_opcode().short(), "PUSH");
+        assert_eq!(DUPF.to_opcode().short(), "DUPF");
+        assert_eq!(ADD.to_opcode().stack_in(), ["lhs", "rhs"]);
+        assert_eq!(ADD.to_opcode().stack_out(), StackOut::Fixed(&["lhs + rhs"]));
+        assert_eq!(POP.to_opcode().stack_out(), StackOut::Fixed(&[]));
+    }
+
+    #[test]
+    fn display() {
+        let bytes: Vec<u8> = crate::to_bytes([PUSH(6), PUSH(7), MUL]).collect();
+        let listing = disassemble(&bytes).unwrap();
+        let expected = "\
+            0000  PUSH 6  ; [] -> [value]\n\
+            0009  PUSH 7  ; [] -> [value]\n\
+            0012  MUL     ; [lhs, rhs] -> [lhs * rhs]\n";
+        assert_eq!(listing.to_string(), expected);
+    }
+
+    #[test]
+    fn invalid_bytecode() {
+        let mut bytes: Vec<u8> = crate::to_bytes([PUSH(1), POP]).collect();
+        bytes.push(0xFF);
+        let err = disassemble(&bytes).unwrap_err();
+        assert_eq!(err.offset, 10);
+        assert!(matches!(err.error, FromBytesError::InvalidOpcode(_)));
+        assert_eq!(err.disassembly.instructions.len(), 2);
+
+        // Truncated immediate.
+        let bytes: Vec<u8> = crate::to_bytes([POP, PUSH(1)]).take(5).collect();
+        let err = disassemble(&bytes).unwrap_err();
+        assert_eq!(err.offset, 1);
+        assert!(matches!(err.error, FromBytesError::NotEnoughBytes(_)));
+    }
+}
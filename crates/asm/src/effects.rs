@@ -1,13 +1,13 @@
-use crate::{Access, Op, Stack, StateRead, ToOpcode};
+use crate::{Access, Compute, Memory, Op, Stack, StateRead};
 use bitflags::bitflags;
 
 /// Flags representing the set of effects caused by a given slice of operations.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub struct Effects(u8);
+pub struct Effects(u16);
 
 bitflags! {
-    impl Effects: u8 {
-        /// Flag for [´StateRead::KeyRange´]
+    impl Effects: u16 {
+        /// Flag for the pre state reads of this contract's state, e.g. [´StateRead::KeyRange´]
         const KeyRange = 1 << 0;
         /// Flag for [´StateRead::KeyRangeExtern´]
         const KeyRangeExtern = 1 << 1;
@@ -15,10 +15,50 @@ bitflags! {
         const ThisAddress = 1 << 2;
         /// Flag for [´Access::ThisContractAddress´]
         const ThisContractAddress = 1 << 3;
-        /// Flag for [´StateRead::PostKeyRange´]
+        /// Flag for the post state reads of this contract's state, e.g. [´StateRead::PostKeyRange´]
         const PostKeyRange = 1 << 4;
         /// Flag for [´StateRead::PostKeyRangeExtern´]
         const PostKeyRangeExtern = 1 << 5;
+        /// Flag for [´Access::ThisProgramAddress´]
+        const ThisProgramAddress = 1 << 6;
+        /// Flag for any [`Op::Crypto`] operation.
+        const Crypto = 1 << 7;
+        /// Flag for [`Stack::Repeat`].
+        const Repeat = 1 << 8;
+        /// Flag for [`Memory::Alloc`].
+        const Alloc = 1 << 9;
+        /// Flag for [`Compute::Compute`].
+        const Compute = 1 << 10;
+        /// Flag for any [`Op::ParentMemory`] operation.
+        const ParentMemory = 1 << 11;
+
+        /// Flags for reads of the pre-state.
+        const PreStateRead = Self::KeyRange.bits() | Self::KeyRangeExtern.bits();
+        /// Flags for reads of the post-state.
+        const PostStateRead = Self::PostKeyRange.bits() | Self::PostKeyRangeExtern.bits();
+    }
+}
+
+/// The effects of a single operation.
+pub fn op_effects(op: &Op) -> Effects {
+    match op {
+        Op::StateRead(
+            StateRead::KeyRange | StateRead::KeyRangeBatch | StateRead::KeysWithPrefix,
+        ) => Effects::KeyRange,
+        Op::StateRead(StateRead::KeyRangeExtern) => Effects::KeyRangeExtern,
+        Op::StateRead(
+            StateRead::PostKeyRange | StateRead::PostKeyRangeBatch | StateRead::PostKeysWithPrefix,
+        ) => Effects::PostKeyRange,
+        Op::StateRead(StateRead::PostKeyRangeExtern) => Effects::PostKeyRangeExtern,
+        Op::Access(Access::ThisAddress) => Effects::ThisAddress,
+        Op::Access(Access::ThisContractAddress) => Effects::ThisContractAddress,
+        Op::Access(Access::ThisProgramAddress) => Effects::ThisProgramAddress,
+        Op::Crypto(_) => Effects::Crypto,
+        Op::Stack(Stack::Repeat) => Effects::Repeat,
+        Op::Memory(Memory::Alloc) => Effects::Alloc,
+        Op::Compute(Compute::Compute) => Effects::Compute,
+        Op::ParentMemory(_) => Effects::ParentMemory,
+        _ => Effects::empty(),
     }
 }
 
@@ -27,13 +67,7 @@ pub fn analyze(ops: &[Op]) -> Effects {
     let mut effects = Effects::empty();
 
     for op in ops {
-        match op {
-            Op::StateRead(StateRead::KeyRangeExtern) => effects |= Effects::KeyRangeExtern,
-            Op::StateRead(StateRead::KeyRange) => effects |= Effects::KeyRange,
-            Op::Access(Access::ThisAddress) => effects |= Effects::ThisAddress,
-            Op::Access(Access::ThisContractAddress) => effects |= Effects::ThisContractAddress,
-            _ => {}
-        }
+        effects |= op_effects(op);
 
         // Short circuit if all flags are found.
         if effects == Effects::all() {
@@ -43,47 +77,29 @@ pub fn analyze(ops: &[Op]) -> Effects {
     effects
 }
 
+/// Determine the effects of the given bytecode.
+///
+/// Decoding stops at the first invalid operation, as the VM would refuse to
+/// execute the program.
+pub fn program_effects(bytes: &[u8]) -> Effects {
+    let mut effects = Effects::empty();
+    for op in crate::from_bytes(bytes.iter().copied()).map_while(Result::ok) {
+        effects |= op_effects(&op);
+        if effects == Effects::all() {
+            break;
+        }
+    }
+    effects
+}
+
 /// Analyze a slice of bytes to determine if it contains any of the effects.
 ///
 /// This is a short-circuiting function that will return true if any of the effects
 /// are found in the byte slice.
 pub fn bytes_contains_any(bytes: &[u8], effects: Effects) -> bool {
-    let krng_byte: u8 = Op::StateRead(StateRead::KeyRange).to_opcode().into();
-    let krng_extern_byte: u8 = Op::StateRead(StateRead::KeyRangeExtern).to_opcode().into();
-    let post_krng_byte: u8 = Op::StateRead(StateRead::PostKeyRange).to_opcode().into();
-    let post_krng_extern_byte: u8 = Op::StateRead(StateRead::PostKeyRangeExtern)
-        .to_opcode()
-        .into();
-    let this_address_byte: u8 = Op::Access(Access::ThisAddress).to_opcode().into();
-    let this_contract_address_byte: u8 = Op::Access(Access::ThisContractAddress).to_opcode().into();
-
-    let push: u8 = Op::Stack(Stack::Push(0)).to_opcode().into();
-
-    let mut iter = bytes.iter();
-    while let Some(byte) = iter.next() {
-        match byte {
-            b if *b == krng_byte && effects.contains(Effects::KeyRange) => return true,
-            b if *b == krng_extern_byte && effects.contains(Effects::KeyRangeExtern) => {
-                return true
-            }
-            b if *b == post_krng_byte && effects.contains(Effects::PostKeyRange) => return true,
-            b if *b == post_krng_extern_byte && effects.contains(Effects::PostKeyRangeExtern) => {
-                return true
-            }
-            b if *b == this_address_byte && effects.contains(Effects::ThisAddress) => return true,
-            b if *b == this_contract_address_byte
-                && effects.contains(Effects::ThisContractAddress) =>
-            {
-                return true
-            }
-            b if *b == push => {
-                // Consume pushes arguments
-                iter.by_ref().take(8).for_each(|_| ());
-            }
-            _ => {}
-        }
-    }
-    false
+    crate::from_bytes(bytes.iter().copied())
+        .map_while(Result::ok)
+        .any(|op| op_effects(&op).intersects(effects))
 }
 
 #[cfg(test)]
@@ -92,7 +108,7 @@ mod test {
 
     use crate::{effects::bytes_contains_any, ToOpcode};
 
-    use super::{analyze, Access, Effects, Op, StateRead};
+    use super::{analyze, program_effects, Access, Effects, Op, StateRead};
 
     #[test]
     fn none() {
@@ -107,6 +123,22 @@ mod test {
         assert!(effects.contains(Effects::KeyRange));
     }
 
+    #[test]
+    fn key_range_batch() {
+        let ops = &[Op::StateRead(StateRead::KeyRangeBatch)];
+        assert_eq!(analyze(ops), Effects::KeyRange);
+        let ops = &[Op::StateRead(StateRead::PostKeyRangeBatch)];
+        assert_eq!(analyze(ops), Effects::PostKeyRange);
+    }
+
+    #[test]
+    fn keys_with_prefix() {
+        let ops = &[Op::StateRead(StateRead::KeysWithPrefix)];
+        assert_eq!(analyze(ops), Effects::KeyRange);
+        let ops = &[Op::StateRead(StateRead::PostKeysWithPrefix)];
+        assert_eq!(analyze(ops), Effects::PostKeyRange);
+    }
+
     #[test]
     fn key_range_extern() {
         let ops = &[Op::StateRead(StateRead::KeyRangeExtern)];
@@ -128,6 +160,13 @@ mod test {
         assert!(effects.contains(Effects::ThisContractAddress));
     }
 
+    #[test]
+    fn this_program_address() {
+        let ops = &[Op::Access(Access::ThisProgramAddress)];
+        let effects = analyze(ops);
+        assert!(effects.contains(Effects::ThisProgramAddress));
+    }
+
     #[test]
     fn all_effects() {
         let ops = &[
@@ -143,6 +182,41 @@ mod test {
         assert!(effects.contains(Effects::ThisContractAddress));
     }
 
+    #[test]
+    fn classified_effects() {
+        use crate::short::*;
+        assert_eq!(analyze(&[PKRNG, PKREX]), Effects::PostStateRead);
+        assert_eq!(analyze(&[KRNG, KREX]), Effects::PreStateRead);
+        assert_eq!(analyze(&[SHA2]), Effects::Crypto);
+        assert_eq!(analyze(&[VRFYED, RSECP]), Effects::Crypto);
+        assert_eq!(analyze(&[REP, REPE]), Effects::Repeat);
+        assert_eq!(analyze(&[ALOC, FREE]), Effects::Alloc);
+        assert_eq!(analyze(&[COM, COME]), Effects::Compute);
+        assert_eq!(analyze(&[LODP, LODPR]), Effects::ParentMemory);
+        assert_eq!(analyze(&[PUSH(1), POP, ADD, JMPIF]), Effects::empty());
+    }
+
+    #[test]
+    fn test_program_effects() {
+        use crate::short::*;
+        let to_bytes = |ops: &[Op]| crate::to_bytes(ops.iter().copied()).collect::<Vec<u8>>();
+
+        assert_eq!(program_effects(&[]), Effects::empty());
+        let ops = [PUSH(1), REP, SHA2, REPE, ALOC, KRNG];
+        assert_eq!(program_effects(&to_bytes(&ops)), analyze(&ops));
+
+        // Opcodes within push arguments are not effects.
+        let key: u8 = KRNG.to_opcode().into();
+        let word = word_from_bytes([key; 8]);
+        assert_eq!(program_effects(&to_bytes(&[PUSH(word)])), Effects::empty());
+
+        // Decoding stops at the first invalid opcode.
+        let mut bytes = to_bytes(&[SHA2]);
+        bytes.push(0xFF);
+        bytes.extend(to_bytes(&[KRNG]));
+        assert_eq!(program_effects(&bytes), Effects::Crypto);
+    }
+
     #[test]
     fn test_bytes_contains_any() {
         use crate::short::*;
@@ -191,11 +265,20 @@ mod test {
         let effects = Effects::ThisContractAddress;
         assert!(bytes_contains_any(&to_bytes(&[THISC]), effects));
 
+        // This program address
+        let effects = Effects::ThisProgramAddress;
+        assert!(bytes_contains_any(&to_bytes(&[THISP]), effects));
+
         // Empty
         let effects = Effects::empty();
         assert!(!bytes_contains_any(&[], effects));
 
         let effects = Effects::KeyRange;
         assert!(!bytes_contains_any(&[], effects));
+
+        // Newly classified effects
+        let effects = Effects::Crypto | Effects::Compute;
+        assert!(bytes_contains_any(&to_bytes(&[POP, COM]), effects));
+        assert!(!bytes_contains_any(&to_bytes(&[POP, REP]), effects));
     }
 }
@@ -0,0 +1,264 @@
+//! Assembling operations with symbolic labels in place of jump distances.
+//!
+//! Hand-computing the distance for a `JMPIF` is fragile, as inserting or
+//! removing an operation between the jump and its target silently changes the
+//! meaning of the program. The [`Assembler`] and the [`ops!`][crate::ops]
+//! macro allow for declaring labels and referencing them instead.
+//!
+//! A label marks the index of the operation that follows it. A reference to a
+//! label pushes the distance from the next `JMPIF` following the reference to
+//! the label, i.e. the distance expected by that `JMPIF`.
+//!
+//! ```
+//! use essential_asm::{ops, short::*};
+//!
+//! let ops = ops![
+//!     PUSH(@end),
+//!     PUSH(1),
+//!     JMPIF,
+//!     PUSH(2),
+//!     end: PUSH(3),
+//! ]
+//! .unwrap();
+//! assert_eq!(ops, [PUSH(2), PUSH(1), JMPIF, PUSH(2), PUSH(3)]);
+//! ```
+
+use crate::{Op, Stack, TotalControlFlow, Word};
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use core::fmt;
+
+/// Builds a sequence of operations, resolving label references on [`finish`][Self::finish].
+#[derive(Clone, Debug, Default)]
+pub struct Assembler {
+    items: Vec<Item>,
+    labels: Vec<(String, usize)>,
+}
+
+/// An error occurring while resolving labels.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LabelError {
+    /// The index of the operation at which the error occurred.
+    ///
+    /// For [`LabelErrorKind::DuplicateLabel`] this is the index marked by the
+    /// second declaration.
+    pub index: usize,
+    /// The label in question.
+    pub label: String,
+    /// The kind of error.
+    pub kind: LabelErrorKind,
+}
+
+/// The kinds of errors that may occur while resolving labels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LabelErrorKind {
+    /// A label was declared more than once.
+    DuplicateLabel,
+    /// A referenced label was never declared.
+    UndefinedLabel,
+    /// A label was referenced, but no `JMPIF` follows the reference.
+    MissingJump,
+}
+
+/// An operation or a reference to a label yet to be resolved.
+#[derive(Clone, Debug)]
+enum Item {
+    Op(Op),
+    PushLabel(String),
+}
+
+impl Assembler {
+    /// Create an empty assembler.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an operation.
+    pub fn op(&mut self, op: impl Into<Op>) -> &mut Self {
+        self.items.push(Item::Op(op.into()));
+        self
+    }
+
+    /// Append a `PUSH` of the distance from the next `JMPIF` to the given label.
+    pub fn push_label(&mut self, label: impl Into<String>) -> &mut Self {
+        self.items.push(Item::PushLabel(label.into()));
+        self
+    }
+
+    /// Declare a label marking the index of the next operation.
+    pub fn label(&mut self, label: impl Into<String>) -> &mut Self {
+        self.labels.push((label.into(), self.items.len()));
+        self
+    }
+
+    /// The number of operations appended so far.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Whether or not no operations have been appended.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Resolve all label references, producing the sequence of operations.
+    pub fn finish(self) -> Result<Vec<Op>, LabelError> {
+        let mut labels = BTreeMap::new();
+        for (label, index) in self.labels {
+            if labels.contains_key(&label) {
+                let kind = LabelErrorKind::DuplicateLabel;
+                return Err(LabelError { index, label, kind });
+            }
+            labels.insert(label, index);
+        }
+
+        let jump_if = Op::from(TotalControlFlow::JumpIf);
+        let mut ops = Vec::with_capacity(self.items.len());
+        for (index, item) in self.items.iter().enumerate() {
+            let op = match item {
+                Item::Op(op) => *op,
+                Item::PushLabel(label) => {
+                    let err = |kind| LabelError {
+                        index,
+                        label: label.clone(),
+                        kind,
+                    };
+                    let target = *labels
+                        .get(label)
+                        .ok_or_else(|| err(LabelErrorKind::UndefinedLabel))?;
+                    let jump = self.items[index + 1..]
+                        .iter()
+                        .position(|item| matches!(item, Item::Op(op) if *op == jump_if))
+                        .map(|pos| index + 1 + pos)
+                        .ok_or_else(|| err(LabelErrorKind::MissingJump))?;
+                    Op::Stack(Stack::Push(target as Word - jump as Word))
+                }
+            };
+            ops.push(op);
+        }
+        Ok(ops)
+    }
+}
+
+impl fmt::Display for LabelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Self { index, label, kind } = self;
+        match kind {
+            LabelErrorKind::DuplicateLabel => write!(f, "duplicate label `{label}`")?,
+            LabelErrorKind::UndefinedLabel => write!(f, "undefined label `{label}`")?,
+            LabelErrorKind::MissingJump => write!(
+                f,
+                "reference to label `{label}` is not followed by a `JMPIF`"
+            )?,
+        }
+        write!(f, " at op {index}")
+    }
+}
+
+impl core::error::Error for LabelError {}
+
+/// Construct a `Result<Vec<Op>, LabelError>` from a list of operations with
+/// symbolic labels.
+///
+/// Operations are any expression convertible into an [`Op`][crate::Op],
+/// separated by commas. An operation may be preceded by any number of label
+/// declarations of the form `name:`, and `PUSH(@name)` pushes the distance from
+/// the next `JMPIF` to the label `name`. See the [`label`][crate::label]
+/// module for details.
+///
+/// Returns a [`LabelError`][crate::label::LabelError] if a label is declared
+/// twice, if a referenced label is never declared, or if a reference is not
+/// followed by a `JMPIF`.
+#[macro_export]
+macro_rules! ops {
+    ($($tt:tt)*) => {{
+        let mut asm = $crate::label::Assembler::new();
+        $crate::__ops!(asm; $($tt)*);
+        asm.finish()
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __ops {
+    ($asm:ident;) => {};
+    ($asm:ident; $label:ident : $($rest:tt)*) => {
+        $asm.label(stringify!($label));
+        $crate::__ops!($asm; $($rest)*);
+    };
+    ($asm:ident; PUSH(@ $label:ident) $(, $($rest:tt)*)?) => {
+        $asm.push_label(stringify!($label));
+        $crate::__ops!($asm; $($($rest)*)?);
+    };
+    ($asm:ident; $op:expr $(, $($rest:tt)*)?) => {
+        $asm.op($op);
+        $crate::__ops!($asm; $($($rest)*)?);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::short::*;
+
+    #[test]
+    fn backward_and_forward_references() {
+        // Count down from 3, then skip the final push.
+        let ops = crate::ops![
+            PUSH(3),
+            top:
+            PUSH(1),
+            SUB,
+            PUSH(@top),
+            PUSH(1),
+            DUPF,
+            PUSH(0),
+            GT,
+            JMPIF,
+            PUSH(@end),
+            PUSH(1),
+            JMPIF,
+            PUSH(42),
+            end: a: POP,
+        ]
+        .unwrap();
+        assert_eq!(ops[3], PUSH(-7));
+        assert_eq!(ops[9], PUSH(2));
+        assert_eq!(ops.len(), 14);
+    }
+
+    #[test]
+    fn assembler() {
+        let mut asm = Assembler::new();
+        asm.push_label("end").op(PUSH(1)).op(JMPIF).label("end");
+        assert_eq!(asm.len(), 3);
+        assert_eq!(asm.finish().unwrap(), [PUSH(1), PUSH(1), JMPIF]);
+    }
+
+    #[test]
+    fn errors() {
+        let mut asm = Assembler::new();
+        asm.label("a").op(POP).label("a");
+        let err = asm.finish().unwrap_err();
+        assert_eq!(err.kind, LabelErrorKind::DuplicateLabel);
+        assert_eq!(err.index, 1);
+
+        let mut asm = Assembler::new();
+        asm.op(POP).push_label("a").op(JMPIF);
+        let err = asm.finish().unwrap_err();
+        assert_eq!(err.kind, LabelErrorKind::UndefinedLabel);
+        assert_eq!(err.index, 1);
+        assert_eq!(err.to_string(), "undefined label `a` at op 1");
+
+        let mut asm = Assembler::new();
+        asm.label("a").push_label("a").op(POP);
+        let err = asm.finish().unwrap_err();
+        assert_eq!(err.kind, LabelErrorKind::MissingJump);
+    }
+
+    #[test]
+    fn ops_undefined_label() {
+        let err = crate::ops![PUSH(@b), PUSH(1), JMPIF].unwrap_err();
+        assert_eq!(err.kind, LabelErrorKind::UndefinedLabel);
+        assert_eq!(err.to_string(), "undefined label `b` at op 0");
+    }
+}
@@ -2,20 +2,31 @@
 //!
 //! # Op Table
 #![doc = essential_asm_gen::gen_ops_docs_table!()]
-#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
+extern crate alloc;
+
 use core::fmt;
 #[doc(inline)]
+pub use disasm::{disassemble, DisassembleError, Disassembly, Instruction};
+#[doc(inline)]
 pub use essential_types::Word;
 #[doc(inline)]
+pub use label::{Assembler, LabelError};
+#[doc(inline)]
 pub use op::{Op, *};
 #[doc(inline)]
 pub use opcode::{InvalidOpcodeError, NotEnoughBytesError, Op as Opcode};
+#[doc(inline)]
+pub use optimize::optimize;
 
+pub mod disasm;
 /// Determine the effects of a program.
 pub mod effects;
+pub mod label;
+pub mod optimize;
 
 /// Typed representation of an operation its associated data.
 mod op {
@@ -85,6 +96,20 @@ pub mod opcode {
     #[derive(Debug)]
     pub struct InvalidOpcodeError(pub u8);
 
+    /// The stack output of an operation, as declared in the spec.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum StackOut {
+        /// The operation pushes a fixed number of named words.
+        Fixed(&'static [&'static str]),
+        /// The number of words pushed depends on the value of the `len` stack input.
+        Dynamic {
+            /// The name of each element.
+            elem: &'static str,
+            /// The name of the stack input word that determines the length.
+            len: &'static str,
+        },
+    }
+
     /// An error occurring within `Opcode::parse_op` in the case that the
     /// provided bytes iterator contains insufficient bytes for the expected
     /// associated operation data.
@@ -103,11 +128,33 @@ pub mod opcode {
         }
     }
 
-    #[cfg(feature = "std")]
-    impl std::error::Error for InvalidOpcodeError {}
+    impl fmt::Display for StackOut {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                Self::Fixed(words) => write!(f, "[{}]", Words(words)),
+                Self::Dynamic { elem, len } => write!(f, "[{elem}; {len}]"),
+            }
+        }
+    }
+
+    /// Displays a slice of words separated by commas.
+    pub(crate) struct Words<'a>(pub(crate) &'a [&'a str]);
 
-    #[cfg(feature = "std")]
-    impl std::error::Error for NotEnoughBytesError {}
+    impl fmt::Display for Words<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            for (ix, word) in self.0.iter().enumerate() {
+                if ix > 0 {
+                    f.write_str(", ")?;
+                }
+                f.write_str(word)?;
+            }
+            Ok(())
+        }
+    }
+
+    impl core::error::Error for InvalidOpcodeError {}
+
+    impl core::error::Error for NotEnoughBytesError {}
 
     essential_asm_gen::gen_all_opcode_decls!();
     essential_asm_gen::gen_all_opcode_impls!();
@@ -132,8 +179,7 @@ impl fmt::Display for FromBytesError {
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for FromBytesError {}
+impl core::error::Error for FromBytesError {}
 
 impl From<InvalidOpcodeError> for FromBytesError {
     fn from(err: InvalidOpcodeError) -> Self {
@@ -165,6 +211,8 @@ pub fn to_bytes(ops: impl IntoIterator<Item = Op>) -> impl Iterator<Item = u8> {
     ops.into_iter().flat_map(|op| op.to_bytes())
 }
 
+essential_asm_gen::gen_op_roundtrip_tests!();
+
 #[cfg(test)]
 mod tests {
     use super::*;
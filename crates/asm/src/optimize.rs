@@ -0,0 +1,176 @@
+//! A peephole optimizer for sequences of operations.
+//!
+//! Compiler output frequently contains short sequences of operations that can
+//! be removed or evaluated ahead of time, e.g. a `PUSH` immediately followed by
+//! a `POP`, or arithmetic over two constants. Each of these costs gas when
+//! executed. [`optimize`] rewrites these sequences while preserving the
+//! behaviour of the program under the VM.
+//!
+//! ```
+//! use essential_asm::short::*;
+//!
+//! let ops = [PUSH(6), PUSH(7), MUL, PUSH(0), NOT, PUSH(1), POP, DUP];
+//! assert_eq!(essential_asm::optimize(&ops), [PUSH(42), PUSH(1), DUP]);
+//! ```
+//!
+//! # Rewrites
+//!
+//! - `PUSH x; POP`, `DUP; POP` and `SWAP; SWAP` are removed.
+//! - `NOP` is removed.
+//! - `PUSH a; PUSH b; <op>` is folded to `PUSH c` for the ALU and binary
+//!   predicate operations, but only where `<op>` would not error (e.g. on
+//!   overflow or division by zero).
+//! - `PUSH a; NOT` is folded to `PUSH c`.
+//!
+//! Rewrites are applied until no more apply, so folded constants may be
+//! folded again.
+//!
+//! # Equivalence
+//!
+//! Any program that executes successfully produces the same stack, memory and
+//! outputs once optimized, while spending no more gas. As the optimized
+//! program never uses more stack than the original, it may succeed where the
+//! original would have exceeded the stack size limit.
+//!
+//! `JMPIF` distances are relative to the jump's position and are computed at
+//! runtime, so cannot be adjusted in general. Programs containing a `JMPIF`
+//! are returned unchanged.
+
+use crate::{Alu, Op, Pred, Stack, TotalControlFlow, Word};
+use alloc::vec::Vec;
+
+/// Optimize the given operations, returning an equivalent sequence.
+///
+/// See the [module-level documentation][self] for the rewrites applied.
+pub fn optimize(ops: &[Op]) -> Vec<Op> {
+    if ops.contains(&Op::TotalControlFlow(TotalControlFlow::JumpIf)) {
+        return ops.to_vec();
+    }
+    let mut out: Vec<Op> = Vec::with_capacity(ops.len());
+    for &op in ops {
+        out.push(op);
+        while rewrite_tail(&mut out) {}
+    }
+    out
+}
+
+/// Attempt a single rewrite of the end of the given operations.
+///
+/// Returns whether or not a rewrite occurred.
+fn rewrite_tail(ops: &mut Vec<Op>) -> bool {
+    match ops[..] {
+        [.., Op::TotalControlFlow(TotalControlFlow::Noop)] => {
+            ops.pop();
+            true
+        }
+        [.., Op::Stack(Stack::Push(_) | Stack::Dup), Op::Stack(Stack::Pop)]
+        | [.., Op::Stack(Stack::Swap), Op::Stack(Stack::Swap)] => {
+            ops.truncate(ops.len() - 2);
+            true
+        }
+        [.., Op::Stack(Stack::Push(a)), Op::Pred(Pred::Not)] => {
+            ops.truncate(ops.len() - 2);
+            ops.push(Op::Stack(Stack::Push(Word::from(a == 0))));
+            true
+        }
+        [.., Op::Stack(Stack::Push(a)), Op::Stack(Stack::Push(b)), op] => {
+            let Some(c) = fold(op, a, b) else {
+                return false;
+            };
+            ops.truncate(ops.len() - 3);
+            ops.push(Op::Stack(Stack::Push(c)));
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Evaluate the binary operation over the given constants, matching the VM.
+///
+/// Returns `None` if the operation is not a foldable binary operation or if
+/// it would error.
+fn fold(op: Op, a: Word, b: Word) -> Option<Word> {
+    let c = match op {
+        Op::Alu(op) => match op {
+            Alu::Add => a.checked_add(b)?,
+            Alu::Sub => a.checked_sub(b)?,
+            Alu::Mul => a.checked_mul(b)?,
+            Alu::Div => a.checked_div(b)?,
+            Alu::Mod => a.checked_rem(b)?,
+            Alu::Shl => a.checked_shl(shift(b)?)?,
+            Alu::Shr => (a as u64).checked_shr(shift(b)?)? as Word,
+            Alu::ShrI => a.checked_shr(shift(b)?)?,
+        },
+        Op::Pred(op) => match op {
+            Pred::Eq => Word::from(a == b),
+            Pred::Gt => Word::from(a > b),
+            Pred::Lt => Word::from(a < b),
+            Pred::Gte => Word::from(a >= b),
+            Pred::Lte => Word::from(a <= b),
+            Pred::And => Word::from(a != 0 && b != 0),
+            Pred::Or => Word::from(a != 0 || b != 0),
+            Pred::BitAnd => a & b,
+            Pred::BitOr => a | b,
+            Pred::EqRange | Pred::EqSet | Pred::Not => return None,
+        },
+        _ => return None,
+    };
+    Some(c)
+}
+
+/// The shift amount, if within the bounds accepted by the VM.
+fn shift(b: Word) -> Option<u32> {
+    u32::try_from(b).ok().filter(|&b| b < Word::BITS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::optimize;
+    use crate::short::*;
+
+    #[test]
+    fn dead_pushes_and_pops() {
+        assert_eq!(optimize(&[PUSH(1), POP]), []);
+        assert_eq!(optimize(&[PUSH(1), DUP, POP]), [PUSH(1)]);
+        assert_eq!(
+            optimize(&[PUSH(1), PUSH(2), SWAP, SWAP]),
+            [PUSH(1), PUSH(2)]
+        );
+        assert_eq!(optimize(&[POP, PUSH(1), SWAP]), [POP, PUSH(1), SWAP]);
+        assert_eq!(optimize(&[PUSH(1), NOP, POP, NOP]), []);
+    }
+
+    #[test]
+    fn constant_folding() {
+        assert_eq!(optimize(&[PUSH(1), PUSH(2), ADD, PUSH(4), MUL]), [PUSH(12)]);
+        assert_eq!(optimize(&[PUSH(7), PUSH(2), SUB]), [PUSH(5)]);
+        assert_eq!(optimize(&[PUSH(7), PUSH(2), DIV]), [PUSH(3)]);
+        assert_eq!(optimize(&[PUSH(7), PUSH(2), MOD]), [PUSH(1)]);
+        assert_eq!(optimize(&[PUSH(-1), PUSH(63), SHR]), [PUSH(1)]);
+        assert_eq!(optimize(&[PUSH(-1), PUSH(63), SHRI]), [PUSH(-1)]);
+        assert_eq!(optimize(&[PUSH(1), PUSH(2), GT]), [PUSH(0)]);
+        assert_eq!(optimize(&[PUSH(3), PUSH(0), OR]), [PUSH(1)]);
+        assert_eq!(optimize(&[PUSH(0), NOT]), [PUSH(1)]);
+        assert_eq!(optimize(&[PUSH(5), NOT]), [PUSH(0)]);
+        // Folding cascades into dead push removal.
+        assert_eq!(optimize(&[PUSH(1), PUSH(2), ADD, POP]), []);
+    }
+
+    #[test]
+    fn errors_are_not_folded() {
+        let ops = [PUSH(i64::MAX), PUSH(1), ADD];
+        assert_eq!(optimize(&ops), ops);
+        let ops = [PUSH(1), PUSH(0), DIV];
+        assert_eq!(optimize(&ops), ops);
+        let ops = [PUSH(1), PUSH(64), SHL];
+        assert_eq!(optimize(&ops), ops);
+        let ops = [PUSH(1), PUSH(-1), SHR];
+        assert_eq!(optimize(&ops), ops);
+    }
+
+    #[test]
+    fn jumps_are_unchanged() {
+        let ops = [PUSH(1), POP, PUSH(2), PUSH(1), JMPIF, PUSH(1), PUSH(2), ADD];
+        assert_eq!(optimize(&ops), ops);
+    }
+}
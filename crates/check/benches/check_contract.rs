@@ -9,7 +9,7 @@ use essential_types::{
     contract::{Contract, SignedContract},
     predicate::{Edge, Node, Predicate, Program},
     solution::{Mutation, Solution, SolutionSet},
-    ContentAddress, Key, PredicateAddress, Word,
+    ContentAddress, Key, PredicateAddress, Value, Word,
 };
 use essential_vm::{StateRead, StateReads};
 use secp256k1::{PublicKey, Secp256k1, SecretKey};
@@ -77,9 +77,9 @@ fn create(
 }
 
 #[derive(Clone, Debug)]
-pub struct State(BTreeMap<ContentAddress, BTreeMap<Key, Vec<Word>>>);
+pub struct State(BTreeMap<ContentAddress, BTreeMap<Key, Value>>);
 
-pub type Kv = (Key, Vec<Word>);
+pub type Kv = (Key, Value);
 
 impl State {
     // Empry state, fine for tests unrelated to reading state.
@@ -99,7 +99,7 @@ impl State {
     }
 
     // Update the value at the given key within the given contract address.
-    pub fn set(&mut self, contract_addr: ContentAddress, key: &Key, value: Vec<Word>) {
+    pub fn set(&mut self, contract_addr: ContentAddress, key: &Key, value: Value) {
         let contract = self.0.entry(contract_addr).or_default();
         if value.is_empty() {
             contract.remove(key);
@@ -118,21 +118,7 @@ impl State {
         contract_addr: ContentAddress,
         mut key: Key,
         num_words: usize,
-    ) -> Result<Vec<Vec<Word>>, String> {
-        // Get the key that follows this one.
-        fn next_key(mut key: Key) -> Option<Key> {
-            for w in key.iter_mut().rev() {
-                match *w {
-                    Word::MAX => *w = Word::MIN,
-                    _ => {
-                        *w += 1;
-                        return Some(key);
-                    }
-                }
-            }
-            None
-        }
-
+    ) -> Result<Vec<Value>, String> {
         // If the predicate does not exist yet, assume `None`s as though predicate hasn't been deployed yet?
         let contract = match self.get(&contract_addr) {
             None => return Err("".to_string()),
@@ -144,7 +130,7 @@ impl State {
         for _ in 0..num_words {
             let opt = contract.get(&key).cloned().unwrap_or_default();
             words.push(opt);
-            key = next_key(key).ok_or("".to_string())?;
+            key = key.next_key().ok_or("".to_string())?;
         }
         Ok(words)
     }
@@ -164,7 +150,7 @@ impl State {
 }
 
 impl core::ops::Deref for State {
-    type Target = BTreeMap<ContentAddress, BTreeMap<Key, Vec<Word>>>;
+    type Target = BTreeMap<ContentAddress, BTreeMap<Key, Value>>;
     fn deref(&self) -> &Self::Target {
         &self.0
     }
@@ -177,9 +163,27 @@ impl StateRead for State {
         contract_addr: ContentAddress,
         key: Key,
         num_words: usize,
-    ) -> Result<Vec<Vec<Word>>, Self::Error> {
+    ) -> Result<Vec<Value>, Self::Error> {
         self.key_range(contract_addr, key, num_words)
     }
+
+    fn keys_with_prefix(
+        &self,
+        contract_addr: ContentAddress,
+        prefix: Key,
+        limit: usize,
+    ) -> Result<Vec<Key>, Self::Error> {
+        let Some(contract) = self.get(&contract_addr) else {
+            return Ok(vec![]);
+        };
+        Ok(contract
+            .range(prefix.clone()..)
+            .map(|(key, _)| key)
+            .take_while(|key| key.starts_with(&prefix))
+            .take(limit)
+            .cloned()
+            .collect())
+    }
 }
 
 impl StateReads for State {
@@ -249,6 +253,7 @@ fn test_predicate_42(entropy: Word) -> (HashMap<ContentAddress, Arc<Program>>, P
     let node = |program_address, edge_start| Node {
         program_address,
         edge_start,
+        max_gas: None,
     };
     let nodes = vec![
         node(a_ca.clone(), 0),
@@ -299,10 +304,10 @@ fn test_predicate_42_solution_pair(
                 contract: contract_addr.clone(),
                 predicate: essential_hash::content_addr(signed_contract.contract.get(i).unwrap()),
             },
-            predicate_data: vec![vec![42]],
+            predicate_data: vec![[42].into()],
             state_mutations: vec![Mutation {
-                key: vec![0, 0, 0, 0],
-                value: vec![42],
+                key: [0, 0, 0, 0].into(),
+                value: [42].into(),
             }],
         })
         .collect();
@@ -10,10 +10,13 @@
 //! - [`predicate::check_signed_contract`] validates a signed contract.
 //! - [`predicate::check_contract`] validates a contract.
 //! - [`predicate::check`] validate an individual predicate.
+//! - [`predicate::check_constraint_nodes`] validates an individual predicate,
+//!   requiring its leaf nodes are pure constraint programs.
 //!
 //! ## Solution Validation
 //!
-//! - [`solution::check_set`] validates a solution set.
+//! - [`solution::check_set`] validates a solution set. Deployments may tune
+//!   the limits with [`solution::check_set_with_limits`].
 //! - [`solution::check_solutions`] validates a solution set's `solutions` slice.
 //! - [`solution::check_set_state_mutations`] validates a solution's state mutation slice.
 //!
@@ -21,6 +24,32 @@
 //!
 //! - [`solution::check_set_predicates`] validates a set of solutions against their associated predicates.
 //! - [`solution::check_predicate`] validates a single solution against its associated predicate.
+//! - [`solution::check_predicate_with_report`] additionally reports on the execution of
+//!   each of the predicate's nodes.
+//! - [`solution::run_program`] runs the program of a single node as it would be
+//!   run while checking.
+//! - [`solution::recheck_set_predicates`] rechecks a set of solutions after a
+//!   state change, rerunning only the nodes that read the changed keys.
+//! - [`solution::check_set_predicates_with_hints`] checks a set of solutions
+//!   using solver-provided outputs in place of running some of their programs.
+//!
+//! ## Program Sharing
+//!
+//! - [`program::ProgramRegistry`] interns programs by content address so that
+//!   identical bytecode is held in memory once.
+//!
+//! ## Light Validation
+//!
+//! - [`state_proof::VerifiedStateRead`] verifies state read from a
+//!   [`state_proof::ProvenStateRead`] against a state root, allowing for
+//!   validation without holding the full state.
+//!
+//! ## Simulation
+//!
+//! - [`solution::compute_solution_set_outputs`] previews the state mutations a
+//!   solution set would make without checking it.
+//! - [`scenario::run`] runs a deterministic sequence of solution sets against
+//!   the checker, reporting state evolution and gas.
 
 #![deny(missing_docs)]
 #![deny(unsafe_code)]
@@ -33,4 +62,7 @@ pub use essential_types as types;
 pub use essential_vm as vm;
 
 pub mod predicate;
+pub mod program;
+pub mod scenario;
 pub mod solution;
+pub mod state_proof;
@@ -1,9 +1,20 @@
 //! Items related to the validation of [`Predicate`]s.
 
-use crate::sign::secp256k1;
-#[cfg(feature = "tracing")]
+use crate::{
+    sign::secp256k1,
+    solution::GetProgram,
+    vm::asm::{
+        self,
+        effects::{self, Effects},
+        FromBytesError, Op,
+    },
+};
 use essential_hash::content_addr;
-use essential_types::{contract, predicate::Predicate};
+use essential_types::{
+    contract,
+    predicate::{Edge, Predicate},
+    ContentAddress,
+};
 use thiserror::Error;
 
 /// [`check_signed_contract`] error.
@@ -28,26 +39,96 @@ pub enum InvalidContract {
     Predicate(usize, InvalidPredicate),
 }
 
+/// [`check_contract_metadata`] error.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum InvalidContractMetadata {
+    /// A field of the metadata exceeds the length limit.
+    #[error("the length of `{field}` ({len}) exceeds the limit ({MAX_METADATA_FIELD_LEN})")]
+    FieldTooLong {
+        /// The name of the field.
+        field: &'static str,
+        /// The length of the field in bytes.
+        len: usize,
+    },
+    /// The content address of the metadata is not the expected address.
+    #[error("the metadata address {found} does not match the expected address {expected}")]
+    AddressMismatch {
+        /// The expected content address.
+        expected: ContentAddress,
+        /// The content address of the contract's metadata.
+        found: ContentAddress,
+    },
+}
+
 /// [`check`] error.
 #[derive(Debug, Error)]
 pub enum InvalidPredicate {
     /// The number of nodes in the predicate exceeds the limit.
     #[error(
-        "the number of nodes ({0}) exceeds the limit ({})",
-        Predicate::MAX_NODES
+        "the number of nodes ({0}) exceeds the limit ({max})",
+        max = Predicate::MAX_NODES
     )]
     TooManyNodes(usize),
     /// The number of edges in the predicate exceeds the limit.
     #[error(
-        "the number of edges ({0}) exceeds the limit ({})",
-        Predicate::MAX_EDGES
+        "the number of edges ({0}) exceeds the limit ({max})",
+        max = Predicate::MAX_EDGES
     )]
     TooManyEdges(usize),
+    /// The program of the leaf node at the given index is not a valid constraint program.
+    #[error("the program of leaf node {0} is not a valid constraint program: {1}")]
+    ConstraintNode(usize, InvalidConstraintProgram),
+    /// The predicate's graph of nodes is invalid.
+    #[error("invalid graph: {0}")]
+    Graph(#[from] InvalidGraph),
+}
+
+/// [`validate_graph`] error.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum InvalidGraph {
+    /// The edges of the node at the given index could not be retrieved, e.g.
+    /// its `edge_start` is out of bounds.
+    #[error("failed to retrieve edges for node {0}")]
+    InvalidNodeEdges(usize),
+    /// An edge of the node at the given index refers to a node that does not exist.
+    #[error("node {node} has an edge to non-existent node {edge}")]
+    InvalidEdge {
+        /// The index of the node with the edge.
+        node: usize,
+        /// The invalid edge.
+        edge: Edge,
+    },
+    /// The graph contains a cycle, given as the path of node indices around
+    /// the cycle. The last node has an edge to the first.
+    #[error("the graph contains a cycle: {0:?}")]
+    Cycle(Vec<u16>),
+}
+
+/// [`check_constraint_program`] error.
+#[derive(Debug, Error)]
+pub enum InvalidConstraintProgram {
+    /// The op at the given index could not be decoded.
+    #[error("failed to decode op at index {0}: {1}")]
+    Decode(usize, FromBytesError),
+    /// The op at the given index is not permitted within a constraint program.
+    #[error("op at index {0} is not permitted within a constraint program: {1:?}")]
+    InvalidOp(usize, Op),
 }
 
 /// Maximum number of predicates in a contract.
 pub const MAX_PREDICATES: usize = 100;
 
+/// Maximum length in bytes of each text field of a contract's metadata.
+pub const MAX_METADATA_FIELD_LEN: usize = 256;
+
+/// The effects of ops that are not permitted within a constraint program.
+///
+/// Constraint programs must be pure: they may not read state, nor spawn
+/// parallel computation.
+pub const CONSTRAINT_FORBIDDEN_EFFECTS: Effects = Effects::PreStateRead
+    .union(Effects::PostStateRead)
+    .union(Effects::Compute);
+
 /// Validate a signed contract of predicates.
 ///
 /// Verifies the signature and then validates the contract.
@@ -73,6 +154,40 @@ pub fn check_contract(predicates: &[Predicate]) -> Result<(), InvalidContract> {
     Ok(())
 }
 
+/// Verify the metadata paired with a contract.
+///
+/// Checks that the metadata's text fields are within
+/// [`MAX_METADATA_FIELD_LEN`] and that its content address is the given
+/// address, e.g. one published alongside the contract's deployment.
+///
+/// The metadata is not covered by the contract's content address or
+/// signature, so the expected address must come from a trusted source.
+pub fn check_contract_metadata(
+    contract: &contract::ContractWithMetadata,
+    expected: &ContentAddress,
+) -> Result<(), InvalidContractMetadata> {
+    let metadata = &contract.metadata;
+    let fields = [
+        ("name", Some(&metadata.name)),
+        ("version", Some(&metadata.version)),
+        ("source_commit", metadata.source_commit.as_ref()),
+    ];
+    for (field, value) in fields {
+        let len = value.map_or(0, |value| value.len());
+        if len > MAX_METADATA_FIELD_LEN {
+            return Err(InvalidContractMetadata::FieldTooLong { field, len });
+        }
+    }
+    let found = content_addr(metadata);
+    if found != *expected {
+        return Err(InvalidContractMetadata::AddressMismatch {
+            expected: expected.clone(),
+            found,
+        });
+    }
+    Ok(())
+}
+
 /// Validate a single predicate.
 ///
 /// Validates the slots, state reads, and constraints.
@@ -83,6 +198,114 @@ pub fn check(predicate: &Predicate) -> Result<(), InvalidPredicate> {
     if predicate.edges.len() > Predicate::MAX_EDGES.into() {
         return Err(InvalidPredicate::TooManyEdges(predicate.edges.len()));
     }
-    // FIXME: Update this to check DAG validity.
+    validate_graph(predicate)?;
+    Ok(())
+}
+
+/// Validate the structure of a predicate's graph of nodes.
+///
+/// Checks that the edges of each node may be retrieved, that each edge refers
+/// to an existing node and that the graph is acyclic.
+pub fn validate_graph(predicate: &Predicate) -> Result<(), InvalidGraph> {
+    let mut children = Vec::with_capacity(predicate.nodes.len());
+    for node in 0..predicate.nodes.len() {
+        let edges = predicate
+            .node_edges(node)
+            .ok_or(InvalidGraph::InvalidNodeEdges(node))?;
+        if let Some(&edge) = edges
+            .iter()
+            .find(|&&edge| usize::from(edge) >= predicate.nodes.len())
+        {
+            return Err(InvalidGraph::InvalidEdge { node, edge });
+        }
+        children.push(edges);
+    }
+    match find_cycle(&children) {
+        Some(cycle) => Err(InvalidGraph::Cycle(cycle)),
+        None => Ok(()),
+    }
+}
+
+/// Find a cycle within the graph given by the children of each node,
+/// returning the path of node indices around the cycle.
+fn find_cycle(children: &[&[Edge]]) -> Option<Vec<u16>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Visit {
+        New,
+        InPath,
+        Done,
+    }
+    let mut visits = vec![Visit::New; children.len()];
+    // The path from the current root, along with the next child to visit of each node.
+    let mut path: Vec<(u16, usize)> = vec![];
+    for root in 0..children.len() {
+        if visits[root] != Visit::New {
+            continue;
+        }
+        visits[root] = Visit::InPath;
+        path.push((root as u16, 0));
+        while let Some(&(node, next)) = path.last() {
+            let Some(&child) = children[usize::from(node)].get(next) else {
+                visits[usize::from(node)] = Visit::Done;
+                path.pop();
+                continue;
+            };
+            if let Some((_, next)) = path.last_mut() {
+                *next += 1;
+            }
+            match visits[usize::from(child)] {
+                Visit::New => {
+                    visits[usize::from(child)] = Visit::InPath;
+                    path.push((child, 0));
+                }
+                Visit::InPath => {
+                    let start = path.iter().position(|&(n, _)| n == child)?;
+                    return Some(path[start..].iter().map(|&(n, _)| n).collect());
+                }
+                Visit::Done => (),
+            }
+        }
+    }
+    None
+}
+
+/// Validate that the given bytecode is a pure constraint program.
+///
+/// Checks that the bytecode decodes and contains none of the
+/// [`CONSTRAINT_FORBIDDEN_EFFECTS`].
+pub fn check_constraint_program(bytecode: &[u8]) -> Result<(), InvalidConstraintProgram> {
+    for (ix, res) in asm::from_bytes(bytecode.iter().copied()).enumerate() {
+        let op = res.map_err(|e| InvalidConstraintProgram::Decode(ix, e))?;
+        if effects::op_effects(&op).intersects(CONSTRAINT_FORBIDDEN_EFFECTS) {
+            return Err(InvalidConstraintProgram::InvalidOp(ix, op));
+        }
+    }
+    Ok(())
+}
+
+/// Validate a single predicate, requiring that all leaf nodes are constraint programs.
+///
+/// Performs the same validation as [`check`], then validates the program of
+/// each leaf node with [`check_constraint_program`].
+///
+/// As during solution checking, a leaf is any node with an empty range of
+/// [`Predicate::node_edges`], whether or not its `edge_start` is [`Edge::MAX`]. This allows for
+/// rejecting predicates whose leaf constraints read state at deploy time,
+/// rather than when the first solution is checked.
+pub fn check_constraint_nodes(
+    predicate: &Predicate,
+    get_program: &impl GetProgram,
+) -> Result<(), InvalidPredicate> {
+    check(predicate)?;
+    let leaves = predicate
+        .nodes
+        .iter()
+        .enumerate()
+        .filter(|&(ix, _)| predicate.node_edges(ix).is_some_and(|e| e.is_empty()));
+    for (ix, node) in leaves {
+        let program = get_program.get_program(&node.program_address);
+        check_constraint_program(&program.0)
+            .map_err(|e| InvalidPredicate::ConstraintNode(ix, e))?;
+    }
     Ok(())
 }
@@ -0,0 +1,98 @@
+//! Items related to sharing [`Program`]s between predicates and checks.
+
+use crate::solution::GetProgram;
+use essential_hash::content_addr;
+use essential_types::{predicate::Program, ContentAddress};
+use std::{collections::HashMap, sync::Arc};
+
+/// Interns [`Program`]s by content address, so that predicates and checks
+/// referring to identical bytecode share a single copy of it.
+///
+/// ```
+/// use essential_check::{program::ProgramRegistry, solution::GetProgram, types::Program};
+/// use std::sync::Arc;
+///
+/// let mut registry = ProgramRegistry::new();
+/// let (ca, a) = registry.intern(Program(vec![1, 2, 3]));
+/// let (_, b) = registry.intern(Program(vec![1, 2, 3]));
+/// assert!(Arc::ptr_eq(&a, &b));
+/// assert!(Arc::ptr_eq(&a, &registry.get_program(&ca)));
+/// assert_eq!(registry.len(), 1);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ProgramRegistry {
+    programs: HashMap<ContentAddress, Arc<Program>>,
+}
+
+impl ProgramRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Intern the given program, returning its content address and a handle
+    /// to the registry's copy of it.
+    ///
+    /// If an identical program is already interned, the given program is
+    /// dropped in favour of the existing copy.
+    pub fn intern(&mut self, program: Program) -> (ContentAddress, Arc<Program>) {
+        let ca = content_addr(&program);
+        let program = self
+            .programs
+            .entry(ca.clone())
+            .or_insert_with(|| Arc::new(program))
+            .clone();
+        (ca, program)
+    }
+
+    /// The program with the given content address, if it is interned.
+    pub fn get(&self, ca: &ContentAddress) -> Option<&Arc<Program>> {
+        self.programs.get(ca)
+    }
+
+    /// Whether a program with the given content address is interned.
+    pub fn contains(&self, ca: &ContentAddress) -> bool {
+        self.programs.contains_key(ca)
+    }
+
+    /// The number of interned programs.
+    pub fn len(&self) -> usize {
+        self.programs.len()
+    }
+
+    /// Whether the registry is empty.
+    pub fn is_empty(&self) -> bool {
+        self.programs.is_empty()
+    }
+
+    /// Remove the programs no longer referred to by any handle outside of the
+    /// registry, returning the number removed.
+    pub fn prune(&mut self) -> usize {
+        let len = self.programs.len();
+        self.programs
+            .retain(|_, program| Arc::strong_count(program) > 1);
+        len - self.programs.len()
+    }
+}
+
+impl FromIterator<Program> for ProgramRegistry {
+    fn from_iter<I: IntoIterator<Item = Program>>(programs: I) -> Self {
+        let mut registry = Self::new();
+        registry.extend(programs);
+        registry
+    }
+}
+
+impl Extend<Program> for ProgramRegistry {
+    fn extend<I: IntoIterator<Item = Program>>(&mut self, programs: I) {
+        for program in programs {
+            self.intern(program);
+        }
+    }
+}
+
+impl GetProgram for ProgramRegistry {
+    fn get_program(&self, ca: &ContentAddress) -> Arc<Program> {
+        self.programs[ca].clone()
+    }
+}
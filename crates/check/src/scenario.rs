@@ -0,0 +1,374 @@
+//! A deterministic scenario harness for simulating sequences of solution sets.
+//!
+//! The harness repeatedly asks a [`Strategy`] for the next [`SolutionSet`] to
+//! submit, checks it against the reference checker (computing its state
+//! mutations via [`check_and_compute_solution_set_two_pass`]) and, if valid,
+//! applies the resulting mutations to an in-memory [`SimState`].
+//!
+//! All randomness is sourced from a seeded [`SimRng`], so a scenario run with
+//! the same seed, strategy and initial state always produces the same
+//! [`ScenarioReport`]. This makes it possible to stress-test the economics of
+//! a contract (e.g. how state and gas evolve under adversarial or random
+//! usage) directly against the checker.
+
+use crate::{
+    solution::{
+        self, check_and_compute_solution_set_two_pass, CheckPredicateConfig, GetPredicate,
+        GetProgram, InvalidSolutionSet, PredicatesError,
+    },
+    types::{solution::SolutionSet, ContentAddress, Key, Value, Word},
+    vm::{Gas, StateRead},
+};
+use std::{
+    collections::BTreeMap,
+    convert::Infallible,
+    ops::{Deref, Range},
+    sync::Arc,
+};
+
+/// A small, deterministic pseudo-random number generator for simulations.
+///
+/// Uses the SplitMix64 algorithm. It is **not** cryptographically secure and
+/// is intended only for reproducible scenario generation.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SimRng(u64);
+
+/// A strategy for producing the solution set submitted at each step of a scenario.
+pub trait Strategy {
+    /// Produce the solution set to submit at the given step.
+    ///
+    /// The set should contain no state mutations, as these are computed by
+    /// the checker. Returning `None` ends the scenario early.
+    fn next_solution_set(
+        &mut self,
+        step: usize,
+        state: &SimState,
+        rng: &mut SimRng,
+    ) -> Option<SolutionSet>;
+}
+
+/// An in-memory state used to track state evolution throughout a scenario.
+///
+/// Contract => Key => Value
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SimState(Arc<BTreeMap<ContentAddress, BTreeMap<Key, Value>>>);
+
+/// Configuration for a scenario run.
+#[derive(Clone, Debug)]
+pub struct ScenarioConfig {
+    /// The seed for the scenario's [`SimRng`].
+    pub seed: u64,
+    /// The maximum number of steps to run.
+    pub steps: usize,
+    /// The configuration passed to the checker for each solution set.
+    pub check: Arc<CheckPredicateConfig>,
+}
+
+/// A single state mutation applied during a scenario step.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AppliedMutation {
+    /// The contract whose state was mutated.
+    pub contract: ContentAddress,
+    /// The mutated key.
+    pub key: Key,
+    /// The value prior to the mutation (empty if there was none).
+    pub prev: Value,
+    /// The new value (empty if the key was removed).
+    pub value: Value,
+}
+
+/// The outcome of checking the solution set submitted at a single step.
+#[derive(Debug)]
+pub enum StepOutcome {
+    /// The set was valid and its mutations were applied to the state.
+    Accepted {
+        /// The gas spent checking the set.
+        gas: Gas,
+        /// The mutations applied to the state in the order they were applied.
+        mutations: Vec<AppliedMutation>,
+    },
+    /// The set failed validation independently of its predicates.
+    Invalid(InvalidSolutionSet),
+    /// The set failed to satisfy its predicates.
+    Rejected(PredicatesError<Infallible>),
+}
+
+/// The report for a single step of a scenario.
+#[derive(Debug)]
+pub struct StepReport {
+    /// The index of the step.
+    pub step: usize,
+    /// The number of solutions in the submitted set.
+    pub solutions: usize,
+    /// The outcome of checking the set.
+    pub outcome: StepOutcome,
+}
+
+/// The report for a full scenario run.
+#[derive(Debug)]
+pub struct ScenarioReport {
+    /// A report for each step in the order they were run.
+    pub steps: Vec<StepReport>,
+    /// The state at the end of the scenario.
+    pub final_state: SimState,
+}
+
+impl SimRng {
+    /// Create a new RNG from the given seed.
+    pub const fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// Produce the next `u64`.
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Produce the next [`Word`], uniformly distributed over all words.
+    pub fn next_word(&mut self) -> Word {
+        Word::from_ne_bytes(self.next_u64().to_ne_bytes())
+    }
+
+    /// Produce a [`Word`] within the given half-open range.
+    ///
+    /// Panics if the range is empty.
+    pub fn gen_range(&mut self, range: Range<Word>) -> Word {
+        assert!(range.start < range.end, "empty range");
+        let span = range.end.abs_diff(range.start);
+        let offset = self.next_u64() % span;
+        range.start.wrapping_add_unsigned(offset)
+    }
+
+    /// Returns `true` with the given probability.
+    ///
+    /// The probability is clamped to `0.0..=1.0`.
+    pub fn gen_bool(&mut self, p: f64) -> bool {
+        // Use the top 53 bits to produce a uniform float in `0.0..1.0`.
+        let f = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        f < p.clamp(0.0, 1.0)
+    }
+
+    /// Choose an element of the given slice, or `None` if it is empty.
+    pub fn choose<'a, T>(&mut self, slice: &'a [T]) -> Option<&'a T> {
+        if slice.is_empty() {
+            return None;
+        }
+        let ix = (self.next_u64() % slice.len() as u64) as usize;
+        slice.get(ix)
+    }
+}
+
+impl<F> Strategy for F
+where
+    F: FnMut(usize, &SimState, &mut SimRng) -> Option<SolutionSet>,
+{
+    fn next_solution_set(
+        &mut self,
+        step: usize,
+        state: &SimState,
+        rng: &mut SimRng,
+    ) -> Option<SolutionSet> {
+        (*self)(step, state, rng)
+    }
+}
+
+impl SimState {
+    /// Construct the state from the given contract key-value pairs.
+    pub fn new(contracts: impl IntoIterator<Item = (ContentAddress, Vec<(Key, Value)>)>) -> Self {
+        let map = contracts
+            .into_iter()
+            .map(|(addr, kvs)| (addr, kvs.into_iter().collect()))
+            .collect();
+        Self(Arc::new(map))
+    }
+
+    /// Get the value at the given key, if any.
+    pub fn get(&self, contract: &ContentAddress, key: &Key) -> Option<&Value> {
+        self.0.get(contract)?.get(key)
+    }
+
+    /// Set the value at the given key, returning the previous value.
+    ///
+    /// An empty value removes the key.
+    pub fn set(&mut self, contract: ContentAddress, key: Key, value: Value) -> Option<Value> {
+        let map = Arc::make_mut(&mut self.0);
+        if value.is_empty() {
+            let prev = map.get_mut(&contract)?.remove(&key);
+            if map.get(&contract).is_some_and(|c| c.is_empty()) {
+                map.remove(&contract);
+            }
+            prev
+        } else {
+            map.entry(contract).or_default().insert(key, value)
+        }
+    }
+}
+
+impl Deref for SimState {
+    type Target = BTreeMap<ContentAddress, BTreeMap<Key, Value>>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl StateRead for SimState {
+    type Error = Infallible;
+
+    fn key_range(
+        &self,
+        contract_addr: ContentAddress,
+        mut key: Key,
+        num_values: usize,
+    ) -> Result<Vec<Value>, Self::Error> {
+        let mut values = Vec::with_capacity(num_values);
+        let contract = self.0.get(&contract_addr);
+        for _ in 0..num_values {
+            let value = contract.and_then(|c| c.get(&key)).cloned();
+            values.push(value.unwrap_or_default());
+            key = match key.next_key() {
+                Some(key) => key,
+                None => break,
+            };
+        }
+        Ok(values)
+    }
+
+    fn keys_with_prefix(
+        &self,
+        contract_addr: ContentAddress,
+        prefix: Key,
+        limit: usize,
+    ) -> Result<Vec<Key>, Self::Error> {
+        let Some(contract) = self.0.get(&contract_addr) else {
+            return Ok(vec![]);
+        };
+        Ok(contract
+            .range(prefix.clone()..)
+            .map(|(key, _)| key)
+            .take_while(|key| key.starts_with(&prefix))
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+}
+
+impl StepOutcome {
+    /// The gas spent, if the set was accepted.
+    pub fn gas(&self) -> Option<Gas> {
+        match self {
+            Self::Accepted { gas, .. } => Some(*gas),
+            _ => None,
+        }
+    }
+
+    /// Whether the set was accepted.
+    pub fn is_accepted(&self) -> bool {
+        matches!(self, Self::Accepted { .. })
+    }
+}
+
+impl ScenarioReport {
+    /// The total gas spent across all accepted steps.
+    pub fn total_gas(&self) -> Gas {
+        self.steps
+            .iter()
+            .filter_map(|s| s.outcome.gas())
+            .fold(0, Gas::saturating_add)
+    }
+
+    /// The number of accepted steps.
+    pub fn accepted(&self) -> usize {
+        self.steps
+            .iter()
+            .filter(|s| s.outcome.is_accepted())
+            .count()
+    }
+}
+
+/// Run a scenario starting from the given state.
+///
+/// At each step the `strategy` produces a solution set which is validated
+/// with [`solution::check_set`] and then checked and computed with
+/// [`check_and_compute_solution_set_two_pass`]. Mutations of accepted sets are
+/// applied to the state before the next step.
+///
+/// The scenario ends after `config.steps` steps or once the strategy returns `None`.
+pub fn run(
+    mut state: SimState,
+    mut strategy: impl Strategy,
+    get_predicate: impl GetPredicate + Sync + Clone,
+    get_program: impl 'static + Clone + GetProgram + Send + Sync,
+    config: &ScenarioConfig,
+) -> ScenarioReport {
+    let mut rng = SimRng::new(config.seed);
+    let mut steps = Vec::with_capacity(config.steps);
+    for step in 0..config.steps {
+        let Some(set) = strategy.next_solution_set(step, &state, &mut rng) else {
+            break;
+        };
+        let solutions = set.solutions.len();
+        let outcome = run_step(
+            &mut state,
+            set,
+            get_predicate.clone(),
+            get_program.clone(),
+            config.check.clone(),
+        );
+        steps.push(StepReport {
+            step,
+            solutions,
+            outcome,
+        });
+    }
+    ScenarioReport {
+        steps,
+        final_state: state,
+    }
+}
+
+/// Check a single set and apply its mutations to the state if accepted.
+fn run_step(
+    state: &mut SimState,
+    set: SolutionSet,
+    get_predicate: impl GetPredicate + Sync + Clone,
+    get_program: impl 'static + Clone + GetProgram + Send + Sync,
+    config: Arc<CheckPredicateConfig>,
+) -> StepOutcome {
+    if let Err(err) = solution::check_set(&set) {
+        return StepOutcome::Invalid(err);
+    }
+    let (gas, set) = match check_and_compute_solution_set_two_pass(
+        state,
+        set,
+        get_predicate,
+        get_program,
+        config,
+    ) {
+        Ok(ok) => ok,
+        Err(err) => return StepOutcome::Rejected(err),
+    };
+    let mut mutations = vec![];
+    for solution in set.solutions {
+        let contract = solution.predicate_to_solve.contract;
+        for mutation in solution.state_mutations {
+            let prev = state
+                .set(
+                    contract.clone(),
+                    mutation.key.clone(),
+                    mutation.value.clone(),
+                )
+                .unwrap_or_default();
+            mutations.push(AppliedMutation {
+                contract: contract.clone(),
+                key: mutation.key,
+                prev,
+                value: mutation.value,
+            });
+        }
+    }
+    StepOutcome::Accepted { gas, mutations }
+}
@@ -3,7 +3,7 @@
 use crate::{
     types::{
         predicate::Predicate,
-        solution::{Solution, SolutionIndex, SolutionSet},
+        solution::{Mutation, Solution, SolutionIndex, SolutionSet},
         Key, PredicateAddress, Word,
     },
     vm::{
@@ -17,14 +17,24 @@ use essential_hash::content_addr;
 use essential_types::{predicate::Program, ContentAddress, Value};
 use essential_vm::{StateRead, StateReads};
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
     fmt,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
 };
 use thiserror::Error;
 
 use rayon::prelude::*;
 
+use dedup::DedupReads;
+pub use recheck::RecheckCache;
+use recheck::{RecordReads, RecordedNodes};
+
+mod dedup;
+mod recheck;
 #[cfg(test)]
 mod tests;
 
@@ -44,6 +54,454 @@ pub struct CheckPredicateConfig {
     ///
     /// Default: `false`
     pub collect_all_failures: bool,
+    /// The maximum number of words of parent program outputs (their stack and
+    /// memory) that may be held at once while checking a solution set.
+    ///
+    /// While the budget is exceeded, solutions and nodes are checked
+    /// sequentially rather than in parallel, and parent outputs that are only
+    /// used within a predicate are released as soon as all of their children
+    /// have run. This bounds memory usage for solution sets containing many
+    /// memory-heavy programs at the cost of parallelism.
+    ///
+    /// Default: `None` (unbounded)
+    pub memory_budget: Option<usize>,
+    /// The maximum number of operations any single program may execute.
+    ///
+    /// Bounds the CPU time spent on each program independently of gas, e.g.
+    /// for programs with deeply nested repeats. See
+    /// [`Vm::max_ops`][vm::Vm::max_ops].
+    ///
+    /// Default: `None` (unbounded)
+    pub max_ops_per_program: Option<u64>,
+    /// The number of operations preceding a program failure to include within
+    /// its [`ExecFailure::trace`].
+    ///
+    /// Recording the trace has a small cost for every operation executed.
+    ///
+    /// Default: `0` (no trace is recorded)
+    pub error_trace_len: usize,
+    /// The gas schedule and limits enforced while checking.
+    pub gas: GasConfig,
+    /// The maximum time that may be spent checking a solution set.
+    ///
+    /// Checking is cooperative, so the deadline is checked before each
+    /// solution is checked and before each set of parallel nodes is run.
+    /// Solutions that have not finished by the deadline are abandoned and
+    /// checking fails with [`PredicatesError::Timeout`]. Programs that have
+    /// already started are run to completion, bounded by their gas.
+    ///
+    /// Default: `None` (unbounded)
+    pub timeout: Option<Duration>,
+    /// A dedicated thread pool on which to check solutions and execute
+    /// programs, isolating checking from other parallel work.
+    ///
+    /// Default: `None` (the current rayon thread pool)
+    pub thread_pool: Option<ThreadPool>,
+    /// A cache of mapped programs shared between checks, so that frequently
+    /// used programs are mapped from their bytecode once rather than once per
+    /// solution set.
+    ///
+    /// Default: `None` (programs are mapped once per solution set)
+    pub program_cache: Option<ProgramCache>,
+    /// A callback receiving [`Progress`] events as solutions are checked,
+    /// giving visibility into long running checks.
+    ///
+    /// Events may be emitted concurrently from multiple threads. No events are
+    /// emitted for solutions sharing the result of an identical solution.
+    ///
+    /// Default: `None`
+    pub progress: Option<ProgressSink>,
+    /// How the [`OutputHints`] given to [`check_set_predicates_with_hints`]
+    /// are verified.
+    pub hints: HintVerification,
+}
+
+/// An event describing the progress of checking a solution set, see
+/// [`CheckPredicateConfig::progress`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Progress {
+    /// Checking of a solution started.
+    SolutionStarted {
+        /// The pass being run.
+        run_mode: RunMode,
+        /// The index of the solution.
+        solution_index: SolutionIndex,
+    },
+    /// A node's program finished executing.
+    NodeFinished {
+        /// The pass being run.
+        run_mode: RunMode,
+        /// The index of the solution.
+        solution_index: SolutionIndex,
+        /// The index of the node within the solution's predicate.
+        node: u16,
+        /// Whether or not the program executed successfully.
+        ok: bool,
+        /// The gas spent by the node's program. Zero if it failed.
+        gas: Gas,
+        /// The gas spent by the solution's nodes so far during this pass.
+        solution_gas: Gas,
+    },
+    /// Checking of a solution finished.
+    SolutionFinished {
+        /// The pass being run.
+        run_mode: RunMode,
+        /// The index of the solution.
+        solution_index: SolutionIndex,
+        /// Whether or not the solution's predicate was satisfied.
+        ok: bool,
+        /// The gas spent by the solution's nodes during this pass.
+        gas: Gas,
+    },
+}
+
+/// A shared handle to a progress callback for [`CheckPredicateConfig::progress`].
+///
+/// Handles are compared by identity, i.e. two handles are equal if they refer
+/// to the same callback.
+#[derive(Clone)]
+pub struct ProgressSink(pub Arc<dyn Fn(&Progress) + Send + Sync>);
+
+impl ProgressSink {
+    /// Create a handle to the given callback.
+    pub fn new(f: impl Fn(&Progress) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+
+    /// Emit the given event to the callback.
+    fn emit(&self, progress: Progress) {
+        (self.0)(&progress)
+    }
+}
+
+impl fmt::Debug for ProgressSink {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("ProgressSink").finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for ProgressSink {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for ProgressSink {}
+
+impl core::hash::Hash for ProgressSink {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).cast::<()>().hash(state)
+    }
+}
+
+/// Gas configuration for [`CheckPredicateConfig::gas`].
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct GasConfig {
+    /// The cost of each operation.
+    ///
+    /// Default: [`vm::GasSchedule::default`]
+    pub schedule: vm::GasSchedule,
+    /// The maximum gas that may be spent by any single program.
+    ///
+    /// Applies in addition to each node's [`max_gas`][essential_types::predicate::Node::max_gas],
+    /// i.e. the lesser of the two limits is enforced.
+    ///
+    /// Default: `None` (unbounded)
+    pub limit_per_program: Option<Gas>,
+    /// The maximum total gas that may be spent by all programs of a single
+    /// solution's predicate, constraints included, during a single run.
+    ///
+    /// As nodes may run in parallel, the limit is checked after each set of
+    /// parallel nodes completes. Exceeding it fails with [`PredicateError::OutOfGas`].
+    ///
+    /// Default: `None` (unbounded)
+    pub limit_per_solution: Option<Gas>,
+}
+
+/// Precomputed outputs of parent nodes, provided by a solver alongside a
+/// solution set so that their programs need not be run, see
+/// [`check_set_predicates_with_hints`].
+///
+/// Hints are self-attested. Only those selected by the
+/// [`CheckPredicateConfig::hints`] verification are checked by re-executing
+/// their programs. Hints for leaf nodes, and for nodes without a maximum gas
+/// (see [`GasConfig::limit_per_program`]), are ignored.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OutputHints(HashMap<SolutionIndex, HashMap<u16, Arc<(Stack, Memory)>>>);
+
+/// Verification of [`OutputHints`] for [`CheckPredicateConfig::hints`].
+///
+/// A hinted node is verified by running its program and comparing the output
+/// with the hint. Unverified hints are used in place of running the program.
+/// Either way, a hinted node is charged its maximum gas, so the gas of a check
+/// does not depend on which nodes are sampled.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct HintVerification {
+    /// The percentage of hinted nodes to verify, from `0` to `100`.
+    ///
+    /// Default: `100` (all hints are verified)
+    pub sample_percent: u8,
+    /// The seed from which the sampled nodes are selected.
+    ///
+    /// A node is sampled if the first 8 bytes of the SHA-256 of the seed,
+    /// solution index and node index, each big-endian, modulo `100` are
+    /// less than [`sample_percent`][Self::sample_percent]. The selection is
+    /// therefore the same for every validator using the same seed.
+    ///
+    /// The seed **must** be kept secret from solvers, e.g. chosen randomly per
+    /// validator or per check. A solver that knows the seed knows which hints
+    /// go unverified, and so which hints may be forged without detection.
+    ///
+    /// Default: `0`
+    pub seed: u64,
+    /// Whether or not hinted nodes whose programs read state are always
+    /// verified, as their outputs depend upon state the solver may not have
+    /// observed.
+    ///
+    /// Default: `true`
+    pub verify_state_reads: bool,
+}
+
+impl OutputHints {
+    /// Create an empty set of hints.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hint the stack and memory output by the given node of the given solution.
+    pub fn insert(
+        &mut self,
+        solution_index: SolutionIndex,
+        node: u16,
+        stack: Stack,
+        memory: Memory,
+    ) {
+        self.0
+            .entry(solution_index)
+            .or_default()
+            .insert(node, Arc::new((stack, memory)));
+    }
+
+    /// The hinted output of the given node of the given solution.
+    pub fn get(&self, solution_index: SolutionIndex, node: u16) -> Option<&Arc<(Stack, Memory)>> {
+        self.0.get(&solution_index)?.get(&node)
+    }
+
+    /// The number of hinted node outputs.
+    pub fn len(&self) -> usize {
+        self.0.values().map(HashMap::len).sum()
+    }
+
+    /// Whether or not there are no hints.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl HintVerification {
+    /// Whether or not the hinted output of the given node is verified.
+    fn verifies(&self, solution_index: SolutionIndex, node: u16, program: &Program) -> bool {
+        if self.verify_state_reads
+            && asm::effects::bytes_contains_any(
+                &program.0,
+                asm::effects::Effects::PreStateRead | asm::effects::Effects::PostStateRead,
+            )
+        {
+            return true;
+        }
+        let hash = essential_hash::hash_bytes_iter([
+            &self.seed.to_be_bytes()[..],
+            &solution_index.to_be_bytes()[..],
+            &node.to_be_bytes()[..],
+        ]);
+        let sample = u64::from_be_bytes(hash[..8].try_into().expect("hash is 32 bytes"));
+        sample % 100 < u64::from(self.sample_percent)
+    }
+}
+
+impl Default for HintVerification {
+    fn default() -> Self {
+        Self {
+            sample_percent: 100,
+            seed: 0,
+            verify_state_reads: true,
+        }
+    }
+}
+
+impl GasConfig {
+    /// The maximum gas that may be spent by the given node's program, if any.
+    fn node_max_gas(&self, node: &essential_types::predicate::Node) -> Option<Gas> {
+        match (node.max_gas, self.limit_per_program) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+/// A shared handle to a [`rayon::ThreadPool`] for [`CheckPredicateConfig::thread_pool`].
+///
+/// Handles are compared by identity, i.e. two handles are equal if they refer
+/// to the same pool.
+#[derive(Clone, Debug)]
+pub struct ThreadPool(pub Arc<rayon::ThreadPool>);
+
+impl ThreadPool {
+    /// Run the given function on this pool.
+    pub fn install<R: Send>(&self, f: impl FnOnce() -> R + Send) -> R {
+        self.0.install(f)
+    }
+}
+
+impl From<rayon::ThreadPool> for ThreadPool {
+    fn from(pool: rayon::ThreadPool) -> Self {
+        Self(Arc::new(pool))
+    }
+}
+
+impl From<Arc<rayon::ThreadPool>> for ThreadPool {
+    fn from(pool: Arc<rayon::ThreadPool>) -> Self {
+        Self(pool)
+    }
+}
+
+impl PartialEq for ThreadPool {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for ThreadPool {}
+
+impl core::hash::Hash for ThreadPool {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).hash(state)
+    }
+}
+
+/// A least-recently-used cache of programs mapped from their bytecode, indexed
+/// by address, for [`CheckPredicateConfig::program_cache`].
+///
+/// Handles are cheap to clone and share the same cache. Handles are compared
+/// by identity, i.e. two handles are equal if they refer to the same cache.
+#[derive(Clone)]
+pub struct ProgramCache(Arc<Mutex<Lru>>);
+
+/// The state of a [`ProgramCache`].
+struct Lru {
+    /// The maximum number of programs retained.
+    capacity: usize,
+    /// Incremented on each access to order entries by recency.
+    tick: u64,
+    /// The mapped programs, along with the tick at which they were last used.
+    entries: HashMap<ContentAddress, (Arc<MappedProgram>, u64)>,
+}
+
+impl ProgramCache {
+    /// Create an empty cache retaining at most `capacity` mapped programs.
+    ///
+    /// A capacity of zero retains nothing.
+    pub fn new(capacity: usize) -> Self {
+        Self(Arc::new(Mutex::new(Lru {
+            capacity,
+            tick: 0,
+            entries: HashMap::new(),
+        })))
+    }
+
+    /// The maximum number of mapped programs retained.
+    pub fn capacity(&self) -> usize {
+        self.lock().capacity
+    }
+
+    /// The number of mapped programs currently retained.
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Whether or not the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether or not the program at the given address is retained.
+    pub fn contains(&self, address: &ContentAddress) -> bool {
+        self.lock().entries.contains_key(address)
+    }
+
+    /// Remove all mapped programs.
+    pub fn clear(&self) {
+        self.lock().entries.clear();
+    }
+
+    /// The mapped program at the given address, marking it as most recently used.
+    fn get(&self, address: &ContentAddress) -> Option<Arc<MappedProgram>> {
+        let mut lru = self.lock();
+        lru.tick += 1;
+        let tick = lru.tick;
+        let (mapped, used) = lru.entries.get_mut(address)?;
+        *used = tick;
+        Some(mapped.clone())
+    }
+
+    /// Retain the given mapped program, evicting the least recently used
+    /// program if at capacity.
+    fn insert(&self, address: ContentAddress, mapped: Arc<MappedProgram>) {
+        let mut lru = self.lock();
+        if lru.capacity == 0 {
+            return;
+        }
+        if lru.entries.len() >= lru.capacity && !lru.entries.contains_key(&address) {
+            let oldest = lru
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(ca, _)| ca.clone());
+            if let Some(oldest) = oldest {
+                lru.entries.remove(&oldest);
+            }
+        }
+        lru.tick += 1;
+        let tick = lru.tick;
+        lru.entries.insert(address, (mapped, tick));
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Lru> {
+        // The cache is always left in a valid state.
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl fmt::Debug for ProgramCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let lru = self.lock();
+        f.debug_struct("ProgramCache")
+            .field("capacity", &lru.capacity)
+            .field("len", &lru.entries.len())
+            .finish()
+    }
+}
+
+impl PartialEq for ProgramCache {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for ProgramCache {}
+
+impl core::hash::Hash for ProgramCache {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).hash(state)
+    }
+}
+
+/// Run the given function on the configured thread pool, if any.
+fn in_pool<R: Send>(config: &CheckPredicateConfig, f: impl FnOnce() -> R + Send) -> R {
+    match &config.thread_pool {
+        Some(pool) => pool.install(f),
+        None => f(),
+    }
 }
 
 /// Required impl for retrieving access to any [`Solution`]'s [`Predicate`]s during check.
@@ -79,14 +537,108 @@ pub struct Ctx<'a> {
 }
 
 /// Cache of parent outputs, indexed by node index for a predicate.
+///
+/// A child node's stack and memory are initialised from the outputs of its
+/// parents in order of the parents' node indices. See
+/// [`Vm::with_parent_outputs`][vm::Vm::with_parent_outputs].
 pub type Cache = HashMap<u16, Arc<(Stack, Memory)>>;
 
-/// The node context in which a `Program` is evaluated (see [`run_program`]).
-struct ProgramCtx {
+/// The caches of parent outputs for each solution in a set, indexed by
+/// solution index.
+///
+/// Only the outputs of nodes whose children are deferred to the checks pass
+/// (i.e. nodes with post-state reads and their descendants) are retained
+/// between the outputs and checks passes.
+pub type SolutionCaches = HashMap<SolutionIndex, Cache>;
+
+/// Tracks the words of parent outputs held in memory against the optional
+/// [`CheckPredicateConfig::memory_budget`].
+#[derive(Debug, Default)]
+struct MemoryBudget {
+    /// The maximum number of words, if any.
+    limit: Option<usize>,
+    /// The number of words currently held.
+    held: AtomicUsize,
+}
+
+/// The outputs of a solution's nodes, keyed by program address, whether or not
+/// the node is a leaf and the hash of the node's inputs.
+///
+/// Only programs that appear at more than one of the predicate's nodes are
+/// retained, so that a program shared by several nodes is run once for each
+/// distinct set of inputs rather than once per node.
+struct NodeMemo {
+    /// The programs appearing at more than one node.
+    shared: HashSet<ContentAddress>,
+    /// The inputs, output and gas of each run of a shared program.
+    outputs: Mutex<HashMap<(ContentAddress, bool, u64), Vec<MemoEntry>>>,
+}
+
+/// A run of a program retained by the [`NodeMemo`].
+struct MemoEntry {
+    inputs: Vec<Arc<(Stack, Memory)>>,
+    output: Output,
+    gas: Gas,
+}
+
+/// A predicate's local cache of parent outputs that releases the words it
+/// holds from the budget when dropped.
+struct LocalCache<'a> {
+    cache: Cache,
+    budget: &'a MemoryBudget,
+}
+
+/// Caches shared by all programs checked against a solution set.
+struct SetCaches {
+    /// The solutions accessed by the VM, shared rather than cloned per program.
+    solutions: Arc<Vec<Solution>>,
+    /// Values derived from the solution set by the VM.
+    vm: Arc<vm::LazyCache>,
+    /// Programs mapped from their bytecode.
+    programs: Arc<SetPrograms>,
+}
+
+/// Programs mapped from their bytecode, indexed by address.
+///
+/// Each program is mapped at most once per solution set, rather than once per
+/// node run for every solution that references it. Mappings are shared with
+/// the [`CheckPredicateConfig::program_cache`], if any.
+struct SetPrograms {
+    /// The programs mapped for this solution set.
+    mapped: Mutex<HashMap<ContentAddress, Arc<MappedProgram>>>,
+    /// The cache shared between solution sets.
+    shared: Option<ProgramCache>,
+}
+
+/// A program mapped over its shared bytecode without copying it.
+type MappedProgram = vm::bytecode::BytecodeMapped<asm::Op, ProgramBytes>;
+
+/// The bytecode of a shared [`Program`].
+#[derive(Clone)]
+struct ProgramBytes(Arc<Program>);
+
+/// The node context in which a `Program` is evaluated (see [`exec_program`]).
+struct ProgramCtx<'a> {
+    /// The solutions of the set being checked.
+    solutions: Arc<Vec<Solution>>,
+    /// The address of the program being evaluated.
+    address: ContentAddress,
+    /// The maximum gas that may be spent evaluating the program, if any.
+    max_gas: Option<Gas>,
+    /// The cost of each operation.
+    gas_schedule: &'a vm::GasSchedule,
+    /// The maximum number of operations the program may execute, if any.
+    max_ops: Option<u64>,
+    /// The number of operations to record for the trace of a failure.
+    trace_len: usize,
     /// The outputs from the parent nodes.
     parents: Vec<Arc<(Stack, Memory)>>,
     /// If this node is a leaf.
     leaf: bool,
+    /// The VM cache shared by all programs checked against the solution set.
+    cache: Arc<vm::LazyCache>,
+    /// The mapped programs shared by all programs checked against the solution set.
+    programs: Arc<SetPrograms>,
 }
 
 /// The outputs of checking a solution set.
@@ -105,11 +657,62 @@ pub struct DataFromSolution {
     pub solution_index: SolutionIndex,
     /// The data output from the solution.
     pub data: Vec<DataOutput>,
+    /// The total gas spent checking the solution.
+    pub gas: Gas,
+    /// The gas spent by each node of the solution's predicate that was run,
+    /// keyed by node index.
+    ///
+    /// Nodes are only run for the [`RunMode`] they apply to, so a node may
+    /// be absent.
+    pub node_gas: BTreeMap<u16, Gas>,
 }
 
-/// The output of a program execution.
-#[derive(Debug, PartialEq)]
-enum ProgramOutput {
+/// A report of a single node's execution, see [`check_predicate_with_report`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeReport {
+    /// The gas spent by the node's program.
+    ///
+    /// Zero if the program failed.
+    pub gas: Gas,
+    /// The kind of output the node's program produced.
+    pub output: NodeOutputKind,
+    /// The time spent executing the node's program.
+    pub elapsed: Duration,
+}
+
+/// The kind of output produced by a node's program.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NodeOutputKind {
+    /// A parent node, whose stack and memory are passed to its children.
+    Parent,
+    /// A leaf node's constraint, along with whether or not it was satisfied.
+    Constraint {
+        /// Whether or not the constraint was satisfied.
+        satisfied: bool,
+    },
+    /// A leaf node that output data.
+    Data,
+    /// The program failed to execute.
+    Failed,
+}
+
+/// Reports for each node that was run, keyed by node index.
+pub type NodeReports = BTreeMap<u16, NodeReport>;
+
+/// The outputs of checking a single predicate.
+#[derive(Clone, Debug)]
+struct PredicateOutputs {
+    /// The total gas spent.
+    gas: Gas,
+    /// The gas spent by each node that was run.
+    node_gas: BTreeMap<u16, Gas>,
+    /// The data outputs from the leaf nodes.
+    data: Vec<DataOutput>,
+}
+
+/// The output of a leaf program execution.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ProgramOutput {
     /// The program output is a boolean value
     /// indicating whether the constraint was satisfied.
     Satisfied(bool),
@@ -118,21 +721,107 @@ enum ProgramOutput {
 }
 
 /// Types of data output from a program.
-#[derive(Debug, PartialEq)]
+///
+/// A leaf program outputs its memory as data by halting with either `[2]` on
+/// the stack, producing a [`DataOutput::Memory`], or `[kind, 3]`, producing a
+/// [`DataOutput::Tagged`] of the given kind. Untagged memory is treated as
+/// state mutations.
+///
+/// Outputs are decoded by kind with a [`DecodeOutput`] implementation, so new
+/// kinds of output may be added without changes to this type.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
 pub enum DataOutput {
-    /// The program output is the memory.
+    /// The program output is the memory, holding encoded state mutations.
     Memory(Memory),
+    /// The program output is the memory, tagged with the kind of its data.
+    Tagged(Word, Memory),
+}
+
+/// Decodes [`DataOutput`]s of a particular kind.
+///
+/// Implement this to support new kinds of output, e.g. events or receipts.
+pub trait DecodeOutput {
+    /// The kind of output decoded, see [`DataOutput::kind`].
+    const KIND: Word;
+    /// The decoded output.
+    type Output;
+    /// The error produced when the output fails to decode.
+    type Error;
+    /// Decode the memory of an output of this kind.
+    fn decode(memory: &[Word]) -> Result<Self::Output, Self::Error>;
+}
+
+/// Decodes [`DataOutput::MUTATIONS`] outputs into state mutations.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DecodeMutations;
+
+impl DataOutput {
+    /// The kind of output holding encoded state mutations.
+    pub const MUTATIONS: Word = essential_types::solution::Output::MUTATIONS;
+    /// The kind of output holding logs.
+    pub const LOGS: Word = essential_types::solution::Output::LOGS;
+    /// The kind of output holding ephemeral data that is not persisted.
+    pub const EPHEMERAL: Word = essential_types::solution::Output::EPHEMERAL;
+
+    /// The kind of the output. Untagged memory is [`Self::MUTATIONS`].
+    pub fn kind(&self) -> Word {
+        match self {
+            Self::Memory(_) => Self::MUTATIONS,
+            Self::Tagged(kind, _) => *kind,
+        }
+    }
+
+    /// The memory output by the program.
+    pub fn memory(&self) -> &Memory {
+        match self {
+            Self::Memory(memory) | Self::Tagged(_, memory) => memory,
+        }
+    }
+
+    /// Decode the output with the given decoder.
+    ///
+    /// Returns `None` if the output is not of the decoder's kind.
+    pub fn decode<D: DecodeOutput>(&self) -> Option<Result<D::Output, D::Error>> {
+        (self.kind() == D::KIND).then(|| D::decode(self.memory()))
+    }
+}
+
+impl DecodeOutput for DecodeMutations {
+    const KIND: Word = DataOutput::MUTATIONS;
+    type Output = Vec<Mutation>;
+    type Error = essential_types::solution::decode::MutationDecodeError;
+
+    fn decode(memory: &[Word]) -> Result<Self::Output, Self::Error> {
+        essential_types::solution::decode::decode_mutations(memory)
+    }
 }
 
 /// The output of a program depends on
 /// whether it is a leaf or a parent.
-enum Output {
+#[derive(Clone, Debug)]
+pub enum Output {
     /// Leaf nodes output bools or data.
     Leaf(ProgramOutput),
     /// Parent nodes output a stack and memory.
     Parent(Arc<(Stack, Memory)>),
 }
 
+/// The value at a key before and after a solution set's mutations are applied.
+///
+/// An empty value represents the absence of a value.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ValueDiff {
+    /// The value prior to the solution set.
+    pub pre: Value,
+    /// The value after the solution set's mutations are applied.
+    pub post: Value,
+}
+
+/// The change to each key mutated by a solution set, by contract address and
+/// key, see [`check_and_compute_solution_set_two_pass_with_diff`].
+pub type StateDiff = BTreeMap<ContentAddress, BTreeMap<Key, ValueDiff>>;
+
 /// The mode the check is running in.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub enum RunMode {
@@ -161,16 +850,16 @@ pub enum InvalidSolution {
     #[error("must be at least one solution")]
     Empty,
     /// The number of solutions exceeds the limit.
-    #[error("the number of solutions ({0}) exceeds the limit ({MAX_SOLUTIONS})")]
+    #[error("the number of solutions ({0}) exceeds the limit")]
     TooMany(usize),
     /// A solution's predicate data length exceeds the limit.
-    #[error("solution {0}'s predicate data length exceeded {1} (limit: {MAX_PREDICATE_DATA})")]
+    #[error("solution {0}'s predicate data length {1} exceeds the limit")]
     PredicateDataLenExceeded(usize, usize),
     /// Invalid state mutation entry.
     #[error("Invalid state mutation entry: {0}")]
     StateMutationEntry(KvError),
     /// Predicate data value too large.
-    #[error("Predicate data value len {0} exceeds limit {MAX_VALUE_SIZE}")]
+    #[error("Predicate data value len {0} exceeds the limit")]
     PredDataValueTooLarge(usize),
 }
 
@@ -178,10 +867,10 @@ pub enum InvalidSolution {
 #[derive(Debug, Error)]
 pub enum KvError {
     /// The key is too large.
-    #[error("key with length {0} exceeds limit {MAX_KEY_SIZE}")]
+    #[error("key with length {0} exceeds the limit")]
     KeyTooLarge(usize),
     /// The value is too large.
-    #[error("value with length {0} exceeds limit {MAX_VALUE_SIZE}")]
+    #[error("value with length {0} exceeds the limit")]
     ValueTooLarge(usize),
 }
 
@@ -189,7 +878,7 @@ pub enum KvError {
 #[derive(Debug, Error)]
 pub enum InvalidSetStateMutations {
     /// The number of state mutations exceeds the limit.
-    #[error("the number of state mutations ({0}) exceeds the limit ({MAX_STATE_MUTATIONS})")]
+    #[error("the number of state mutations ({0}) exceeds the limit")]
     TooMany(usize),
     /// Discovered multiple mutations to the same slot.
     #[error("attempt to apply multiple mutations to the same slot: {0:?} {1:?}")]
@@ -208,6 +897,21 @@ pub enum PredicatesError<E> {
     /// Tried to compute mutations on solution set with existing mutations.
     #[error("tried to compute mutations on solution set with existing mutations")]
     ExistingMutations,
+    /// Checking exceeded the [`CheckPredicateConfig::timeout`].
+    #[error("{0}")]
+    Timeout(#[from] Timeout),
+    /// Failed to read the pre-state.
+    #[error("failed to read the pre-state: {0}")]
+    StateRead(E),
+}
+
+/// Checking exceeded the [`CheckPredicateConfig::timeout`].
+#[derive(Debug, Error)]
+#[error("checking timed out with {} solutions finished", finished.len())]
+pub struct Timeout {
+    /// The index of each solution whose check finished, successfully or
+    /// otherwise, prior to the deadline.
+    pub finished: Vec<SolutionIndex>,
 }
 
 /// Predicate checking failed for the solution at the given indices.
@@ -220,6 +924,9 @@ pub enum PredicateError<E> {
     /// Failed to retrieve edges for a node, indicating that the predicate's graph is invalid.
     #[error("failed to retrieve edges for node {0} indicating an invalid graph")]
     InvalidNodeEdges(usize),
+    /// The predicate's graph is invalid. See [`predicate::validate_graph`][crate::predicate::validate_graph].
+    #[error("invalid graph: {0}")]
+    InvalidGraph(#[from] crate::predicate::InvalidGraph),
     /// The execution of one or more programs failed.
     #[error("one or more program execution errors occurred: {0}")]
     ProgramErrors(#[from] ProgramErrors<E>),
@@ -229,6 +936,18 @@ pub enum PredicateError<E> {
     /// One or more of the mutations were invalid.
     #[error(transparent)]
     Mutations(#[from] MutationsError),
+    /// Checking exceeded the [`CheckPredicateConfig::timeout`].
+    #[error("the check timeout was exceeded")]
+    Timeout,
+    /// The programs of the predicate spent more than the
+    /// [`GasConfig::limit_per_solution`].
+    #[error("predicate gas limit of {limit} exceeded: {spent} gas spent")]
+    OutOfGas {
+        /// The predicate gas limit.
+        limit: Gas,
+        /// The gas spent prior to checking was stopped.
+        spent: Gas,
+    },
 }
 
 /// Program execution failed for the programs at the given node indices.
@@ -249,7 +968,37 @@ pub enum ProgramError<E> {
     ParentMemoryConcatOverflow(#[from] vm::error::MemoryError),
     /// VM execution resulted in an error.
     #[error("VM execution error: {0}")]
-    Vm(#[from] vm::error::ExecError<E>),
+    Vm(Box<ExecFailure<E>>),
+    /// The program's output differs from its hinted output, see [`OutputHints`].
+    #[error("the program's output does not match its hinted output")]
+    InvalidHint,
+}
+
+impl<E> From<vm::error::ParentOutputsError> for ProgramError<E> {
+    fn from(err: vm::error::ParentOutputsError) -> Self {
+        match err {
+            vm::error::ParentOutputsError::Stack(e) => Self::ParentStackConcatOverflow(e),
+            vm::error::ParentOutputsError::Memory(e) => Self::ParentMemoryConcatOverflow(e),
+        }
+    }
+}
+
+/// A program failed during execution, along with the context of the failure.
+#[derive(Debug)]
+pub struct ExecFailure<E> {
+    /// The content address of the program.
+    pub program: ContentAddress,
+    /// The operation that failed, or `None` if it could not be decoded.
+    pub op: Option<asm::Op>,
+    /// The stack at the time of the failure.
+    pub stack: Stack,
+    /// The last operations executed and their program counters, oldest first,
+    /// ending with the operation that failed if it began executing.
+    ///
+    /// Bounded by [`CheckPredicateConfig::error_trace_len`].
+    pub trace: Vec<(usize, asm::Op)>,
+    /// The execution error.
+    pub error: vm::error::ExecError<E>,
 }
 
 /// The index of each constraint that was not satisfied.
@@ -274,9 +1023,48 @@ pub const MAX_SOLUTIONS: usize = 100;
 /// Maximum number of state mutations of a solution.
 pub const MAX_STATE_MUTATIONS: usize = 1000;
 /// Maximum number of words in a slot value.
-pub const MAX_VALUE_SIZE: usize = 10_000;
+pub const MAX_VALUE_SIZE: usize = Value::MAX_SIZE;
 /// Maximum number of words in a slot key.
-pub const MAX_KEY_SIZE: usize = 1000;
+pub const MAX_KEY_SIZE: usize = Key::MAX_SIZE;
+
+/// Limits applied when validating a solution set with [`check_set_with_limits`].
+///
+/// The [`Default`] limits are the `MAX_*` constants of this module.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Limits {
+    /// Maximum number of solutions within a solution set.
+    ///
+    /// Default: [`MAX_SOLUTIONS`]
+    pub max_solutions: usize,
+    /// Maximum number of predicate data of a solution.
+    ///
+    /// Default: [`MAX_PREDICATE_DATA`]
+    pub max_predicate_data: usize,
+    /// Maximum number of state mutations of a solution set.
+    ///
+    /// Default: [`MAX_STATE_MUTATIONS`]
+    pub max_state_mutations: usize,
+    /// Maximum number of words in a slot value or predicate data value.
+    ///
+    /// Default: [`MAX_VALUE_SIZE`]
+    pub max_value_size: usize,
+    /// Maximum number of words in a slot key.
+    ///
+    /// Default: [`MAX_KEY_SIZE`]
+    pub max_key_size: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_solutions: MAX_SOLUTIONS,
+            max_predicate_data: MAX_PREDICATE_DATA as usize,
+            max_state_mutations: MAX_STATE_MUTATIONS,
+            max_value_size: MAX_VALUE_SIZE,
+            max_key_size: MAX_KEY_SIZE,
+        }
+    }
+}
 
 impl<E: fmt::Display + fmt::Debug> fmt::Display for PredicateErrors<E> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -292,7 +1080,23 @@ impl<E: fmt::Display + fmt::Debug> fmt::Display for ProgramErrors<E> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.write_str("the programs at the following node indices failed: \n")?;
         for (node_ix, err) in &self.0 {
-            f.write_str(&format!("  {node_ix}: {:#?}\n", err))?;
+            f.write_str(&format!("  {node_ix}: {err}\n"))?;
+        }
+        Ok(())
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for ExecFailure<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.error)?;
+        write!(f, "\n    program: {}", self.program)?;
+        match &self.op {
+            Some(op) => write!(f, "\n    op:      {op:?}")?,
+            None => write!(f, "\n    op:      unknown")?,
+        }
+        write!(f, "\n    stack:   {:?}", self.stack)?;
+        for (pc, op) in &self.trace {
+            write!(f, "\n      {pc}: {op:?}")?;
         }
         Ok(())
     }
@@ -353,24 +1157,32 @@ impl<T: GetProgram> GetProgram for Arc<T> {
 /// Validate a solution set, to the extent it can be validated without reference to
 /// its associated predicates.
 ///
-/// This includes solutions and state mutations.
-#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(solution = %content_addr(set)), err))]
+/// This includes solutions and state mutations, checked against the default
+/// [`Limits`].
 pub fn check_set(set: &SolutionSet) -> Result<(), InvalidSolutionSet> {
-    check_solutions(&set.solutions)?;
-    check_set_state_mutations(set)?;
+    check_set_with_limits(set, &Limits::default())
+}
+
+/// Validate a solution set against the given [`Limits`].
+///
+/// See [`check_set`].
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(solution = %content_addr(set)), err))]
+pub fn check_set_with_limits(set: &SolutionSet, limits: &Limits) -> Result<(), InvalidSolutionSet> {
+    check_solutions_with_limits(&set.solutions, limits)?;
+    check_set_state_mutations_with_limits(set, limits)?;
     Ok(())
 }
 
-fn check_value_size(value: &[Word]) -> Result<(), KvError> {
-    if value.len() > MAX_VALUE_SIZE {
+fn check_value_size(value: &[Word], limits: &Limits) -> Result<(), KvError> {
+    if value.len() > limits.max_value_size {
         Err(KvError::ValueTooLarge(value.len()))
     } else {
         Ok(())
     }
 }
 
-fn check_key_size(value: &[Word]) -> Result<(), KvError> {
-    if value.len() > MAX_KEY_SIZE {
+fn check_key_size(value: &[Word], limits: &Limits) -> Result<(), KvError> {
+    if value.len() > limits.max_key_size {
         Err(KvError::KeyTooLarge(value.len()))
     } else {
         Ok(())
@@ -379,27 +1191,36 @@ fn check_key_size(value: &[Word]) -> Result<(), KvError> {
 
 /// Validate the solution set's slice of [`Solution`]s.
 pub fn check_solutions(solutions: &[Solution]) -> Result<(), InvalidSolution> {
+    check_solutions_with_limits(solutions, &Limits::default())
+}
+
+/// Validate the solution set's slice of [`Solution`]s against the given [`Limits`].
+pub fn check_solutions_with_limits(
+    solutions: &[Solution],
+    limits: &Limits,
+) -> Result<(), InvalidSolution> {
     // Validate solution.
     // Ensure that at solution has at least one solution.
     if solutions.is_empty() {
         return Err(InvalidSolution::Empty);
     }
     // Ensure that solution length is below limit length.
-    if solutions.len() > MAX_SOLUTIONS {
+    if solutions.len() > limits.max_solutions {
         return Err(InvalidSolution::TooMany(solutions.len()));
     }
 
     // Check whether the predicate data length has been exceeded.
     for (solution_ix, solution) in solutions.iter().enumerate() {
         // Ensure the length limit is not exceeded.
-        if solution.predicate_data.len() > MAX_PREDICATE_DATA as usize {
+        if solution.predicate_data.len() > limits.max_predicate_data {
             return Err(InvalidSolution::PredicateDataLenExceeded(
                 solution_ix,
                 solution.predicate_data.len(),
             ));
         }
         for v in &solution.predicate_data {
-            check_value_size(v).map_err(|_| InvalidSolution::PredDataValueTooLarge(v.len()))?;
+            check_value_size(v, limits)
+                .map_err(|_| InvalidSolution::PredDataValueTooLarge(v.len()))?;
         }
     }
     Ok(())
@@ -407,9 +1228,17 @@ pub fn check_solutions(solutions: &[Solution]) -> Result<(), InvalidSolution> {
 
 /// Validate the solution set's state mutations.
 pub fn check_set_state_mutations(set: &SolutionSet) -> Result<(), InvalidSolutionSet> {
+    check_set_state_mutations_with_limits(set, &Limits::default())
+}
+
+/// Validate the solution set's state mutations against the given [`Limits`].
+pub fn check_set_state_mutations_with_limits(
+    set: &SolutionSet,
+    limits: &Limits,
+) -> Result<(), InvalidSolutionSet> {
     // Validate state mutations.
     // Ensure that the solution set's state mutations length is below limit length.
-    if set.state_mutations_len() > MAX_STATE_MUTATIONS {
+    if set.state_mutations_len() > limits.max_state_mutations {
         return Err(InvalidSetStateMutations::TooMany(set.state_mutations_len()).into());
     }
 
@@ -425,9 +1254,10 @@ pub fn check_set_state_mutations(set: &SolutionSet) -> Result<(), InvalidSolutio
                 .into());
             }
             // Check key length.
-            check_key_size(&mutation.key).map_err(InvalidSolution::StateMutationEntry)?;
+            check_key_size(&mutation.key, limits).map_err(InvalidSolution::StateMutationEntry)?;
             // Check value length.
-            check_value_size(&mutation.value).map_err(InvalidSolution::StateMutationEntry)?;
+            check_value_size(&mutation.value, limits)
+                .map_err(InvalidSolution::StateMutationEntry)?;
         }
     }
 
@@ -447,18 +1277,18 @@ fn decode_mutations<E>(
         // Set to check for duplicate mutations.
         let mut mut_set = HashSet::new();
 
-        // For each memory output decode the mutations and apply them.
+        // For each mutations output decode the mutations and apply them.
+        // Outputs of other kinds are left to their consumers.
         for data in output.data {
-            match data {
-                DataOutput::Memory(memory) => {
-                    for mutation in essential_types::solution::decode::decode_mutations(&memory)
-                        .map_err(|e| {
-                            PredicatesError::Failed(PredicateErrors(vec![(
-                                output.solution_index,
-                                PredicateError::Mutations(MutationsError::DecodeError(e)),
-                            )]))
-                        })?
-                    {
+            match data.decode::<DecodeMutations>() {
+                None => (),
+                Some(mutations) => {
+                    for mutation in mutations.map_err(|e| {
+                        PredicatesError::Failed(PredicateErrors(vec![(
+                            output.solution_index,
+                            PredicateError::Mutations(MutationsError::DecodeError(e)),
+                        )]))
+                    })? {
                         // Check for duplicate mutation keys.
                         if !mut_set.insert(mutation.key.clone()) {
                             return Err(PredicatesError::Failed(PredicateErrors(vec![(
@@ -504,9 +1334,18 @@ where
         contract_addr: ContentAddress,
         key: Key,
         num_values: usize,
-    ) -> Result<Vec<Vec<essential_types::Word>>, Self::Error> {
+    ) -> Result<Vec<Value>, Self::Error> {
         read_or_fallback(&self.0, &self.1, contract_addr, key, num_values)
     }
+
+    fn keys_with_prefix(
+        &self,
+        contract_addr: ContentAddress,
+        prefix: Key,
+        limit: usize,
+    ) -> Result<Vec<Key>, Self::Error> {
+        keys_with_prefix_or_fallback(&self.0, &self.1, contract_addr, prefix, limit)
+    }
 }
 
 fn read_or_fallback<S: StateRead>(
@@ -515,7 +1354,7 @@ fn read_or_fallback<S: StateRead>(
     contract_addr: ContentAddress,
     mut key: Key,
     num_values: usize,
-) -> Result<Vec<Vec<Word>>, S::Error> {
+) -> Result<Vec<Value>, S::Error> {
     let mut out = Vec::with_capacity(num_values);
     match post.state.get(&contract_addr) {
         Some(contract_state) => {
@@ -527,7 +1366,7 @@ fn read_or_fallback<S: StateRead>(
                         out.push(value.pop().unwrap_or_default());
                     }
                 }
-                match next_key(key) {
+                match key.next_key() {
                     Some(next_key) => key = next_key,
                     None => break,
                 }
@@ -540,18 +1379,36 @@ fn read_or_fallback<S: StateRead>(
     Ok(out)
 }
 
-/// Get the next key in the range of keys.
-fn next_key(mut key: Key) -> Option<Key> {
-    for w in key.iter_mut().rev() {
-        match *w {
-            Word::MAX => *w = Word::MIN,
-            _ => {
-                *w += 1;
-                return Some(key);
-            }
+/// Merge the keys with the given prefix in the post state mutations with those
+/// in the pre state, where empty values are deletions.
+fn keys_with_prefix_or_fallback<S: StateRead>(
+    post: &PostState,
+    state: &S,
+    contract_addr: ContentAddress,
+    prefix: Key,
+    limit: usize,
+) -> Result<Vec<Key>, S::Error> {
+    let Some(contract_state) = post.state.get(&contract_addr) else {
+        return state.keys_with_prefix(contract_addr, prefix, limit);
+    };
+    let mutated: Vec<(&Key, &Value)> = contract_state
+        .iter()
+        .filter(|(key, _)| key.starts_with(&prefix))
+        .collect();
+    // Each mutation may delete at most one of the pre state keys.
+    let pre_limit = limit.saturating_add(mutated.len());
+    let mut keys: BTreeSet<Key> = state
+        .keys_with_prefix(contract_addr, prefix, pre_limit)?
+        .into_iter()
+        .collect();
+    for (key, value) in mutated {
+        if value.is_empty() {
+            keys.remove(key);
+        } else {
+            keys.insert(key.clone());
         }
     }
-    None
+    Ok(keys.into_iter().take(limit).collect())
 }
 
 /// Check the given solution set against the given predicates and
@@ -560,6 +1417,7 @@ fn next_key(mut key: Key) -> Option<Key> {
 /// This is a two-pass check. The first pass generates the outputs
 /// and does not run any post state reads.
 /// The second pass checks the outputs and runs the post state reads.
+/// It is skipped when none of the set's predicates read the post state.
 #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
 pub fn check_and_compute_solution_set_two_pass<S>(
     state: &S,
@@ -572,26 +1430,76 @@ where
     S: Clone + StateRead + Send + Sync + 'static,
     S::Error: Send + Sync + 'static,
 {
-    // Create an empty post state,
-    let post_state = PostStateArc(Arc::new(PostState::default()), state.clone());
-
-    // Create an empty cache.
-    let mut cache = HashMap::new();
+    two_pass(state, solution_set, get_predicate, get_program, config)
+        .map(|(gas, solution_set, _)| (gas, solution_set))
+}
 
-    // Generate the outputs
-    let (mut gas, solution_set) = check_and_compute_solution_set(
-        &(state.clone(), post_state.clone()),
-        solution_set,
-        get_predicate.clone(),
-        get_program.clone(),
+/// [`check_and_compute_solution_set_two_pass`], additionally returning the
+/// [`StateDiff`] of the computed mutations.
+///
+/// The `pre` value of each mutated key is read from the given state.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub fn check_and_compute_solution_set_two_pass_with_diff<S>(
+    state: &S,
+    solution_set: SolutionSet,
+    get_predicate: impl GetPredicate + Sync + Clone,
+    get_program: impl 'static + Clone + GetProgram + Send + Sync,
+    config: Arc<CheckPredicateConfig>,
+) -> Result<(Gas, SolutionSet, StateDiff), PredicatesError<S::Error>>
+where
+    S: Clone + StateRead + Send + Sync + 'static,
+    S::Error: Send + Sync + 'static,
+{
+    let (gas, solution_set, post_state) =
+        two_pass(state, solution_set, get_predicate, get_program, config)?;
+
+    // Pair the value at each mutated key with its value prior to the set.
+    let mut diff = StateDiff::new();
+    for (contract, post) in post_state.state {
+        let contract_diff = diff.entry(contract.clone()).or_default();
+        for (key, post) in post {
+            let pre = state
+                .key_range(contract.clone(), key.clone(), 1)
+                .map_err(PredicatesError::StateRead)?
+                .pop()
+                .unwrap_or_default();
+            contract_diff.insert(key, ValueDiff { pre, post });
+        }
+    }
+    Ok((gas, solution_set, diff))
+}
+
+/// Performs both passes of [`check_and_compute_solution_set_two_pass`],
+/// returning the post state along with the gas and computed solution set.
+fn two_pass<S>(
+    state: &S,
+    solution_set: SolutionSet,
+    get_predicate: impl GetPredicate + Sync + Clone,
+    get_program: impl 'static + Clone + GetProgram + Send + Sync,
+    config: Arc<CheckPredicateConfig>,
+) -> Result<(Gas, SolutionSet, PostState), PredicatesError<S::Error>>
+where
+    S: Clone + StateRead + Send + Sync + 'static,
+    S::Error: Send + Sync + 'static,
+{
+    // The checks pass only runs the nodes that read the post state.
+    let checks_pass = reads_post_state(&solution_set, &get_predicate, &get_program);
+
+    // Create an empty cache.
+    let mut cache = HashMap::new();
+
+    // Generate the outputs
+    let (mut gas, solution_set) = compute_outputs(
+        state,
+        solution_set,
+        get_predicate.clone(),
+        get_program.clone(),
         config.clone(),
-        RunMode::Outputs,
         &mut cache,
     )?;
 
-    // Get the post state back.
-    let mut post_state =
-        Arc::try_unwrap(post_state.0).expect("post state should have one reference");
+    // Start the post state from empty.
+    let mut post_state = PostState::default();
 
     // Apply the state mutations to the post state.
     for solution in &solution_set.solutions {
@@ -604,6 +1512,11 @@ where
         }
     }
 
+    // Skip the checks pass if there is nothing for it to run.
+    if !checks_pass {
+        return Ok((gas, solution_set, post_state));
+    }
+
     // Put the post state back into an arc.
     let post_state = PostStateArc(Arc::new(post_state), state.clone());
 
@@ -621,8 +1534,99 @@ where
     // Add the total gas
     gas = gas.saturating_add(g);
 
+    // Get the post state back.
+    let post_state = Arc::try_unwrap(post_state.0).expect("post state should have one reference");
+
     // Return solutions set
-    Ok((gas, solution_set))
+    Ok((gas, solution_set, post_state))
+}
+
+/// Whether or not any node of the predicates solved by the set reads the post state.
+fn reads_post_state(
+    set: &SolutionSet,
+    get_predicate: &impl GetPredicate,
+    get_program: &impl GetProgram,
+) -> bool {
+    let mut seen = HashSet::new();
+    set.solutions
+        .iter()
+        .filter(|solution| seen.insert(&solution.predicate_to_solve))
+        .any(|solution| {
+            get_predicate
+                .get_predicate(&solution.predicate_to_solve)
+                .nodes
+                .iter()
+                .any(|node| node_reads_post_state(node, get_program))
+        })
+}
+
+/// Whether or not the node's program reads the post state.
+fn node_reads_post_state(
+    node: &essential_types::predicate::Node,
+    get_program: &impl GetProgram,
+) -> bool {
+    asm::effects::bytes_contains_any(
+        &get_program.get_program(&node.program_address).0,
+        asm::effects::Effects::PostKeyRange | asm::effects::Effects::PostKeyRangeExtern,
+    )
+}
+
+/// Compute the post state mutations for the given solution set without
+/// checking them, i.e. a dry run.
+///
+/// Only the [`RunMode::Outputs`] pass of
+/// [`check_and_compute_solution_set_two_pass`] is run. Nodes that read the post
+/// state, along with their descendants, are skipped. As a result, a solution
+/// set for which this succeeds may still fail to check. This is useful for
+/// previewing the state changes a candidate solution set would make.
+///
+/// Returns the gas spent and the solution set with its decoded mutations.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub fn compute_solution_set_outputs<S>(
+    state: &S,
+    solution_set: SolutionSet,
+    get_predicate: impl GetPredicate + Sync,
+    get_program: impl 'static + Clone + GetProgram + Send + Sync,
+    config: Arc<CheckPredicateConfig>,
+) -> Result<(Gas, SolutionSet), PredicatesError<S::Error>>
+where
+    S: Clone + StateRead + Send + Sync + 'static,
+    S::Error: Send + Sync + 'static,
+{
+    compute_outputs(
+        state,
+        solution_set,
+        get_predicate,
+        get_program,
+        config,
+        &mut HashMap::new(),
+    )
+}
+
+/// Run the [`RunMode::Outputs`] pass against an empty post state.
+fn compute_outputs<S>(
+    state: &S,
+    solution_set: SolutionSet,
+    get_predicate: impl GetPredicate + Sync,
+    get_program: impl 'static + Clone + GetProgram + Send + Sync,
+    config: Arc<CheckPredicateConfig>,
+    cache: &mut SolutionCaches,
+) -> Result<(Gas, SolutionSet), PredicatesError<S::Error>>
+where
+    S: Clone + StateRead + Send + Sync + 'static,
+    S::Error: Send + Sync + 'static,
+{
+    // Post state reads are deferred to the checks pass, so the post state is empty.
+    let post_state = PostStateArc(Arc::new(PostState::default()), state.clone());
+    check_and_compute_solution_set(
+        &(state.clone(), post_state),
+        solution_set,
+        get_predicate,
+        get_program,
+        config,
+        RunMode::Outputs,
+        cache,
+    )
 }
 
 /// Check the given solution set against the given predicates and
@@ -635,7 +1639,7 @@ pub fn check_and_compute_solution_set<S>(
     get_program: impl 'static + Clone + GetProgram + Send + Sync,
     config: Arc<CheckPredicateConfig>,
     run_mode: RunMode,
-    cache: &mut HashMap<SolutionIndex, Cache>,
+    cache: &mut SolutionCaches,
 ) -> Result<(Gas, SolutionSet), PredicatesError<S::Error>>
 where
     S: Clone + StateReads + Send + Sync + 'static,
@@ -679,6 +1683,15 @@ where
 /// - `pre_state` must provide access to state *prior to* mutations being applied.
 /// - `post_state` must provide access to state *post* mutations being applied.
 ///
+/// Solutions are checked on the [`CheckPredicateConfig::thread_pool`] if one
+/// is specified. Each program is mapped from its bytecode at most once and the
+/// mapping is shared between all solutions that reference it, as well as
+/// between calls when a [`CheckPredicateConfig::program_cache`] is specified.
+/// Identical pre-state key range reads issued by any of the set's programs
+/// are performed against `state` only once. Solutions identical to an earlier
+/// solution of the set share its outputs and gas rather than being checked
+/// again, unless it failed.
+///
 /// Returns the total gas spent.
 pub fn check_set_predicates<S>(
     state: &S,
@@ -687,7 +1700,134 @@ pub fn check_set_predicates<S>(
     get_program: impl 'static + Clone + GetProgram + Send + Sync,
     config: Arc<CheckPredicateConfig>,
     run_mode: RunMode,
-    cache: &mut HashMap<SolutionIndex, Cache>,
+    cache: &mut SolutionCaches,
+) -> Result<Outputs, PredicatesError<S::Error>>
+where
+    S: Clone + StateReads + Send + Sync + 'static,
+    S::Error: Send,
+{
+    let pool_config = config.clone();
+    in_pool(&pool_config, || {
+        check_set_predicates_in_pool(
+            state,
+            solution_set,
+            &get_predicate,
+            get_program,
+            config,
+            run_mode,
+            cache,
+            None,
+            None,
+        )
+    })
+}
+
+/// [`check_set_predicates`], using the given solver-provided [`OutputHints`]
+/// in place of running the programs of hinted parent nodes.
+///
+/// Hints are verified according to [`CheckPredicateConfig::hints`]. A verified
+/// hint that does not match its program's output fails the predicate with
+/// [`ProgramError::InvalidHint`]. Unverified hints are trusted, so a solution
+/// set that passes with hints may fail without them.
+///
+/// Each hinted node is charged its maximum gas whether or not it is verified,
+/// so the gas reported does not depend on the sampling seed. Validity does:
+/// with a [`sample_percent`][HintVerification::sample_percent] below `100`,
+/// two validators with different seeds may disagree on whether a solution set
+/// with a forged hint is valid. The result is therefore probabilistic and
+/// must not be used for consensus. Use this for dry runs and by solvers, and
+/// [`check_set_predicates`] for consensus.
+#[allow(clippy::too_many_arguments)]
+pub fn check_set_predicates_with_hints<S>(
+    state: &S,
+    solution_set: Arc<SolutionSet>,
+    get_predicate: impl GetPredicate + Sync,
+    get_program: impl 'static + Clone + GetProgram + Send + Sync,
+    config: Arc<CheckPredicateConfig>,
+    run_mode: RunMode,
+    cache: &mut SolutionCaches,
+    hints: &OutputHints,
+) -> Result<Outputs, PredicatesError<S::Error>>
+where
+    S: Clone + StateReads + Send + Sync + 'static,
+    S::Error: Send,
+{
+    let pool_config = config.clone();
+    in_pool(&pool_config, || {
+        check_set_predicates_in_pool(
+            state,
+            solution_set,
+            &get_predicate,
+            get_program,
+            config,
+            run_mode,
+            cache,
+            None,
+            Some(hints),
+        )
+    })
+}
+
+/// [`check_set_predicates`], reusing the outputs of nodes from previous checks
+/// of the same solution set that are unaffected by the given state changes.
+///
+/// The outputs of each node run are recorded within `recheck` along with the
+/// pre-state keys it read. Nodes that read any of the `changed` keys, given by
+/// contract address, are rerun, as are their descendants if the rerun
+/// produced different outputs. All other nodes reuse their recorded outputs
+/// and gas. Nodes that read the post-state are always rerun.
+///
+/// The first check of a solution set records the outputs of all nodes. This
+/// is useful for revalidating the same solution sets as the state advances.
+///
+/// Outputs recorded for a different solution set are discarded. The given
+/// predicates and programs must be the same as those of previous checks.
+#[allow(clippy::too_many_arguments)]
+pub fn recheck_set_predicates<S>(
+    state: &S,
+    solution_set: Arc<SolutionSet>,
+    get_predicate: impl GetPredicate + Sync,
+    get_program: impl 'static + Clone + GetProgram + Send + Sync,
+    config: Arc<CheckPredicateConfig>,
+    run_mode: RunMode,
+    cache: &mut SolutionCaches,
+    changed: &HashMap<ContentAddress, HashSet<Key>>,
+    recheck: &mut RecheckCache,
+) -> Result<Outputs, PredicatesError<S::Error>>
+where
+    S: Clone + StateReads + Send + Sync + 'static,
+    S::Error: Send,
+{
+    recheck.invalidate(changed);
+    let recorded = recheck.solutions(&solution_set);
+    let pool_config = config.clone();
+    in_pool(&pool_config, || {
+        check_set_predicates_in_pool(
+            state,
+            solution_set,
+            &get_predicate,
+            get_program,
+            config,
+            run_mode,
+            cache,
+            Some(recorded),
+            None,
+        )
+    })
+}
+
+/// [`check_set_predicates`] within the current thread pool.
+#[allow(clippy::too_many_arguments)]
+fn check_set_predicates_in_pool<S>(
+    state: &S,
+    solution_set: Arc<SolutionSet>,
+    get_predicate: &(impl GetPredicate + Sync),
+    get_program: impl 'static + Clone + GetProgram + Send + Sync,
+    config: Arc<CheckPredicateConfig>,
+    run_mode: RunMode,
+    cache: &mut SolutionCaches,
+    recorded: Option<&[Arc<RecordedNodes>]>,
+    hints: Option<&OutputHints>,
 ) -> Result<Outputs, PredicatesError<S::Error>>
 where
     S: Clone + StateReads + Send + Sync + 'static,
@@ -702,22 +1842,33 @@ where
             core::mem::take(cache)
         })
         .collect();
-    // Check each solution in parallel.
-    let (ok, failed): (Vec<_>, Vec<_>) = solution_set
-        .solutions
-        .par_iter()
-        .zip(caches)
-        .enumerate()
-        .map(|(solution_index, (solution, mut cache))| {
-            let predicate = get_predicate.get_predicate(&solution.predicate_to_solve);
-            let solution_set = solution_set.clone();
-            let state = state.clone();
-            let config = config.clone();
-            let get_program = get_program.clone();
-
-            let res = check_predicate(
+
+    // Account for the outputs already held within the caches.
+    let budget = MemoryBudget::new(config.memory_budget);
+    let deadline = config.timeout.map(|timeout| Instant::now() + timeout);
+
+    // Values derived from the solution set and mapped programs are shared by
+    // all programs.
+    let set_caches = SetCaches::new(&solution_set, config.program_cache.clone());
+    caches
+        .iter()
+        .flat_map(|cache| cache.values())
+        .for_each(|output| budget.acquire(output_words(output)));
+
+    // Identical pre-state key range reads are performed at most once per set.
+    let state = DedupReads::new(state.clone());
+
+    let check_solution = |(solution_index, (solution, mut cache)): (usize, (&Solution, Cache))| {
+        let predicate = get_predicate.get_predicate(&solution.predicate_to_solve);
+        let state = state.clone();
+        let config = config.clone();
+        let get_program = get_program.clone();
+
+        let res = if is_expired(deadline) {
+            Err(PredicateError::Timeout)
+        } else {
+            check_predicate_budgeted(
                 &state,
-                solution_set,
                 predicate,
                 get_program,
                 solution_index
@@ -728,14 +1879,107 @@ where
                     run_mode,
                     cache: &mut cache,
                 },
+                &budget,
+                &set_caches,
+                deadline,
+                None,
+                recorded.map(|recorded| &*recorded[solution_index]),
+                hints,
+            )
+        };
+
+        match res {
+            Ok(ok) => Ok((solution_index as u16, ok, cache)),
+            Err(e) => Err((solution_index as u16, e)),
+        }
+    };
+
+    // Check each solution in parallel. When a memory budget is specified,
+    // solutions are checked in batches so that the budget can be consulted
+    // between them, falling back to checking one at a time while exceeded.
+    let check_all = |solutions: Vec<(usize, (&Solution, Cache))>| {
+        let mut solutions = solutions.into_iter();
+        let mut results = Vec::with_capacity(solutions.len());
+        loop {
+            let batch_len = match budget.limit {
+                None => usize::MAX,
+                Some(_) if budget.exceeded() => 1,
+                Some(_) => rayon::current_num_threads(),
+            };
+            let batch: Vec<_> = solutions.by_ref().take(batch_len).collect();
+            if batch.is_empty() {
+                break;
+            }
+            results.extend(
+                batch
+                    .into_par_iter()
+                    .map(check_solution)
+                    .collect::<Vec<_>>(),
             );
+        }
+        results
+    };
 
-            match res {
-                Ok(ok) => Ok((solution_index as u16, ok, cache)),
-                Err(e) => Err((solution_index as u16, e)),
+    // Solutions identical to an earlier solution of the set, e.g. within batch
+    // settlements, share its result rather than being checked again.
+    let duplicate_of = match (recorded, hints) {
+        (None, None) => find_duplicates(&solution_set.solutions),
+        _ => vec![None; solution_set.solutions.len()],
+    };
+    let (unique, duplicates): (Vec<_>, Vec<_>) = solution_set
+        .solutions
+        .iter()
+        .zip(caches)
+        .enumerate()
+        .partition(|(ix, _)| duplicate_of[*ix].is_none());
+    let mut results = check_all(unique);
+
+    // The duplicates of failed solutions are checked in full so that their
+    // own errors are reported.
+    if !duplicates.is_empty() {
+        let result_ix = |res: &Result<_, (u16, _)>| match res {
+            Ok((ix, _, _)) | Err((ix, _)) => *ix,
+        };
+        let position: HashMap<u16, usize> = results
+            .iter()
+            .enumerate()
+            .map(|(pos, res)| (result_ix(res), pos))
+            .collect();
+        let mut shared = Vec::with_capacity(duplicates.len());
+        let mut rerun = vec![];
+        for (ix, solution) in duplicates {
+            let original = duplicate_of[ix].expect("solution is a duplicate") as u16;
+            match &results[position[&original]] {
+                Ok((_, outputs, cache)) => {
+                    shared.push(Ok((ix as u16, outputs.clone(), cache.clone())));
+                }
+                Err((_, PredicateError::Timeout)) => {
+                    shared.push(Err((ix as u16, PredicateError::Timeout)));
+                }
+                Err(_) => rerun.push((ix, solution)),
             }
-        })
-        .partition(Result::is_ok);
+        }
+        results.extend(shared);
+        results.extend(check_all(rerun));
+        results.sort_by_key(result_ix);
+    }
+
+    // If any solutions were abandoned, identify those that finished.
+    let timed_out = |res: &Result<_, (u16, PredicateError<_>)>| {
+        matches!(res, Err((_, PredicateError::Timeout)))
+    };
+    if results.iter().any(timed_out) {
+        let finished = results
+            .iter()
+            .filter(|res| !timed_out(res))
+            .map(|res| match res {
+                Ok((ix, _, _)) | Err((ix, _)) => *ix,
+            })
+            .collect();
+        return Err(Timeout { finished }.into());
+    }
+
+    let (ok, failed): (Vec<_>, Vec<_>) = results.into_iter().partition(Result::is_ok);
 
     // If any predicates failed, return an error.
     if !failed.is_empty() {
@@ -747,10 +1991,17 @@ where
     let outputs = ok
         .into_iter()
         .map(Result::unwrap)
-        .map(|(solution_index, (gas, data_outputs), c)| {
+        .map(|(solution_index, outputs, c)| {
+            let PredicateOutputs {
+                gas,
+                node_gas,
+                data,
+            } = outputs;
             let output = DataFromSolution {
                 solution_index,
-                data: data_outputs,
+                data,
+                gas,
+                node_gas,
             };
             total_gas = total_gas.saturating_add(gas);
             *cache.get_mut(&solution_index).expect("cache should exist") = c;
@@ -769,6 +2020,18 @@ where
 /// Spawns a rayon task for each of the predicate's nodes to execute in parallel
 /// once their inputs are ready.
 ///
+/// Each node's program is limited to the node's [`max_gas`][essential_types::predicate::Node::max_gas],
+/// if one is specified. Exceeding it fails the predicate.
+///
+/// Values derived from the solution set by the VM (see [`vm::LazyCache`]) are
+/// computed at most once and shared between all of the predicate's programs.
+/// Likewise, each program is mapped from its bytecode at most once, and a
+/// program appearing at several nodes is run once for each distinct set of
+/// parent outputs, with its gas charged to each node.
+///
+/// Nodes are executed on the [`CheckPredicateConfig::thread_pool`] if one is
+/// specified.
+///
 /// **NOTE:** This assumes that the given `SolutionSet` and `Predicate` have been
 /// independently validated using [`solution::check_set`][check_set]
 /// and [`predicate::check`][crate::predicate::check] respectively.
@@ -790,33 +2053,451 @@ pub fn check_predicate<S>(
     config: &CheckPredicateConfig,
     ctx: Ctx,
 ) -> Result<(Gas, Vec<DataOutput>), PredicateError<S::Error>>
+where
+    S: Clone + StateReads + Send + Sync + 'static,
+    S::Error: Send,
+{
+    check_predicate_reporting(
+        state,
+        solution_set,
+        predicate,
+        get_program,
+        solution_index,
+        config,
+        ctx,
+        None,
+    )
+}
+
+/// [`check_predicate`], additionally reporting on the execution of each of the
+/// predicate's nodes.
+///
+/// Returns the result of [`check_predicate`] along with a [`NodeReport`] for
+/// each node that was run, including those that ran before checking failed.
+/// Nodes that were not run, e.g. due to the [`RunMode`] or an earlier failure,
+/// are absent from the reports.
+#[allow(clippy::type_complexity)]
+pub fn check_predicate_with_report<S>(
+    state: &S,
+    solution_set: Arc<SolutionSet>,
+    predicate: Arc<Predicate>,
+    get_program: impl GetProgram + Send + Sync + 'static,
+    solution_index: SolutionIndex,
+    config: &CheckPredicateConfig,
+    ctx: Ctx,
+) -> (
+    Result<(Gas, Vec<DataOutput>), PredicateError<S::Error>>,
+    NodeReports,
+)
+where
+    S: Clone + StateReads + Send + Sync + 'static,
+    S::Error: Send,
+{
+    let mut reports = NodeReports::new();
+    let res = check_predicate_reporting(
+        state,
+        solution_set,
+        predicate,
+        get_program,
+        solution_index,
+        config,
+        ctx,
+        Some(&mut reports),
+    );
+    (res, reports)
+}
+
+/// Shared implementation of [`check_predicate`] and [`check_predicate_with_report`].
+#[allow(clippy::too_many_arguments)]
+fn check_predicate_reporting<S>(
+    state: &S,
+    solution_set: Arc<SolutionSet>,
+    predicate: Arc<Predicate>,
+    get_program: impl GetProgram + Send + Sync + 'static,
+    solution_index: SolutionIndex,
+    config: &CheckPredicateConfig,
+    ctx: Ctx,
+    reports: Option<&mut NodeReports>,
+) -> Result<(Gas, Vec<DataOutput>), PredicateError<S::Error>>
+where
+    S: Clone + StateReads + Send + Sync + 'static,
+    S::Error: Send,
+{
+    let budget = MemoryBudget::new(config.memory_budget);
+    let set_caches = SetCaches::new(&solution_set, config.program_cache.clone());
+    let deadline = config.timeout.map(|timeout| Instant::now() + timeout);
+    in_pool(config, || {
+        check_predicate_budgeted(
+            state,
+            predicate,
+            get_program,
+            solution_index,
+            config,
+            ctx,
+            &budget,
+            &set_caches,
+            deadline,
+            reports,
+            None,
+            None,
+        )
+    })
+    .map(|outputs| (outputs.gas, outputs.data))
+}
+
+/// [`check_predicate`], but with a memory budget and set caches that may be
+/// shared between solutions.
+#[allow(clippy::too_many_arguments)]
+fn check_predicate_budgeted<S>(
+    state: &S,
+    predicate: Arc<Predicate>,
+    get_program: impl GetProgram + Send + Sync + 'static,
+    solution_index: SolutionIndex,
+    config: &CheckPredicateConfig,
+    ctx: Ctx,
+    budget: &MemoryBudget,
+    set_caches: &SetCaches,
+    deadline: Option<Instant>,
+    reports: Option<&mut NodeReports>,
+    recorded: Option<&RecordedNodes>,
+    hints: Option<&OutputHints>,
+) -> Result<PredicateOutputs, PredicateError<S::Error>>
 where
     S: Clone + StateReads + Send + Sync + 'static,
     S::Error: Send,
 {
     let p = predicate.clone();
+    let run_mode = ctx.run_mode;
+    let progress = config.progress.as_ref();
+    let solution_gas = AtomicU64::new(0);
+    if let Some(progress) = progress {
+        progress.emit(Progress::SolutionStarted {
+            run_mode,
+            solution_index,
+        });
+    }
+
+    // Programs shared by several nodes are run once per distinct set of inputs.
+    let memo = NodeMemo::new(&predicate);
 
     // Run all nodes that have all their inputs in parallel
-    let run = |ix: u16, parents: Vec<Arc<(Stack, Memory)>>| {
-        let program = get_program.get_program(&predicate.nodes[ix as usize].program_address);
+    let exec = |ix: u16, parents: Vec<Arc<(Stack, Memory)>>| {
+        // Reuse the node's recorded output if its inputs are unchanged.
+        if let Some(out) = recorded.and_then(|recorded| recorded.reuse(ix, &parents)) {
+            return (ix, Ok(out));
+        }
+        let inputs = recorded.map(|_| parents.clone());
+        let node = &predicate.nodes[ix as usize];
+        let address = node.program_address.clone();
+        let program = get_program.get_program(&address);
+        let leaf = predicate
+            .node_edges(ix as usize)
+            .expect("This is already checked")
+            .is_empty();
+        let max_gas = config.gas.node_max_gas(node);
+        // Use the node's hinted output unless it is selected for verification.
+        // Hinted nodes are charged their max gas either way, so that gas does
+        // not depend on the sampling seed.
+        let hint = hints
+            .and_then(|hints| hints.get(solution_index, ix))
+            .filter(|_| !leaf)
+            .zip(max_gas);
+        if let Some((hint, max_gas)) = hint {
+            if !config.hints.verifies(solution_index, ix, &program) {
+                return (ix, Ok((Output::Parent(hint.clone()), max_gas)));
+            }
+        }
+        // Reuse the output of an identical run of the same program. The gas
+        // of the original run is spent again so that gas is unaffected.
+        let memo_key = recorded
+            .is_none()
+            .then(|| memo.key(&address, leaf, &parents))
+            .flatten();
+        let memo_inputs = memo_key.as_ref().map(|_| parents.clone());
+        let reused = memo_key
+            .as_ref()
+            .and_then(|key| memo.get(key, &parents))
+            .filter(|(_, gas)| max_gas.is_none_or(|max| *gas <= max));
         let ctx = ProgramCtx {
+            solutions: set_caches.solutions.clone(),
+            address,
+            max_gas,
+            gas_schedule: &config.gas.schedule,
+            max_ops: config.max_ops_per_program,
+            trace_len: config.error_trace_len,
             parents,
-            leaf: predicate
-                .node_edges(ix as usize)
-                .expect("This is already checked")
-                .is_empty(),
+            leaf,
+            cache: set_caches.vm.clone(),
+            programs: set_caches.programs.clone(),
         };
-        let res = run_program(
-            state.clone(),
-            solution_set.clone(),
-            solution_index,
-            program,
-            ctx,
-        );
+        let res = match (reused, recorded, inputs) {
+            (Some(reused), _, _) => Ok(reused),
+            (None, Some(recorded), Some(inputs)) => {
+                let state = RecordReads::new(state.clone());
+                let res = exec_program(state.clone(), solution_index, program, ctx);
+                if let Ok((output, gas)) = &res {
+                    recorded.record(ix, inputs, output, *gas, state.take_reads());
+                }
+                res
+            }
+            _ => {
+                let res = exec_program(state.clone(), solution_index, program, ctx);
+                if let (Ok((output, gas)), Some(key), Some(inputs)) = (&res, memo_key, memo_inputs)
+                {
+                    memo.insert(key, inputs, output, *gas);
+                }
+                res
+            }
+        };
+        let res = match (res, hint) {
+            (Ok((Output::Parent(out), _)), Some((hint, _))) if out != *hint => {
+                Err(ProgramError::InvalidHint)
+            }
+            (Ok((output, _)), Some((_, max_gas))) => Ok((output, max_gas)),
+            (res, _) => res,
+        };
+        (ix, res)
+    };
+
+    // Report each node as it finishes.
+    let run = |ix: u16, parents: Vec<Arc<(Stack, Memory)>>| {
+        let (ix, res) = exec(ix, parents);
+        if let Some(progress) = progress {
+            let gas = res.as_ref().map_or(0, |(_, gas)| *gas);
+            let so_far = solution_gas.fetch_add(gas, Ordering::Relaxed) + gas;
+            progress.emit(Progress::NodeFinished {
+                run_mode,
+                solution_index,
+                node: ix,
+                ok: res.is_ok(),
+                gas,
+                solution_gas: so_far,
+            });
+        }
         (ix, res)
     };
 
-    check_predicate_inner(run, p, config, &get_program, ctx)
+    let res = check_predicate_inner(run, p, config, &get_program, ctx, budget, deadline, reports);
+    if let Some(progress) = progress {
+        progress.emit(Progress::SolutionFinished {
+            run_mode,
+            solution_index,
+            ok: res.is_ok(),
+            gas: solution_gas.load(Ordering::Relaxed),
+        });
+    }
+    res
+}
+
+/// Whether the given deadline, if any, has passed.
+fn is_expired(deadline: Option<Instant>) -> bool {
+    deadline.is_some_and(|deadline| Instant::now() >= deadline)
+}
+
+impl SetCaches {
+    /// Create empty caches for checking the given solution set, sharing mapped
+    /// programs with the given program cache, if any.
+    fn new(solution_set: &SolutionSet, program_cache: Option<ProgramCache>) -> Self {
+        Self {
+            solutions: Arc::new(solution_set.solutions.clone()),
+            vm: Default::default(),
+            programs: Arc::new(SetPrograms {
+                mapped: Default::default(),
+                shared: program_cache,
+            }),
+        }
+    }
+}
+
+impl SetPrograms {
+    /// The mapping of the program at the given address, mapping the given
+    /// program if it has not yet been mapped.
+    fn get_or_map(
+        &self,
+        address: &ContentAddress,
+        program: Arc<Program>,
+    ) -> Result<Arc<MappedProgram>, FromBytesError> {
+        if let Some(mapped) = self.lock().get(address) {
+            return Ok(mapped.clone());
+        }
+        let shared = self.shared.as_ref().and_then(|cache| cache.get(address));
+        let mapped = match shared {
+            Some(mapped) => mapped,
+            // Map without holding the lock. Programs mapped concurrently are
+            // identical, so either may be retained.
+            None => {
+                let mapped = Arc::new(MappedProgram::try_from_bytes(ProgramBytes(program))?);
+                if let Some(cache) = &self.shared {
+                    cache.insert(address.clone(), mapped.clone());
+                }
+                mapped
+            }
+        };
+        let mapped = self.lock().entry(address.clone()).or_insert(mapped).clone();
+        Ok(mapped)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<ContentAddress, Arc<MappedProgram>>> {
+        // The map is always left in a valid state.
+        self.mapped.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl core::ops::Deref for ProgramBytes {
+    type Target = [u8];
+    fn deref(&self) -> &Self::Target {
+        &self.0 .0
+    }
+}
+
+impl MemoryBudget {
+    /// Create a budget with the given limit in words.
+    fn new(limit: Option<usize>) -> Self {
+        Self {
+            limit,
+            held: AtomicUsize::new(0),
+        }
+    }
+
+    /// Account for the given number of words now being held.
+    fn acquire(&self, words: usize) {
+        if self.limit.is_some() {
+            self.held.fetch_add(words, Ordering::Relaxed);
+        }
+    }
+
+    /// Account for the given number of words having been released.
+    fn release(&self, words: usize) {
+        if self.limit.is_some() {
+            let _ = self
+                .held
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |held| {
+                    Some(held.saturating_sub(words))
+                });
+        }
+    }
+
+    /// Whether the words held exceed the limit.
+    fn exceeded(&self) -> bool {
+        self.limit
+            .is_some_and(|limit| self.held.load(Ordering::Relaxed) > limit)
+    }
+}
+
+impl NodeMemo {
+    /// An empty memo for the programs shared by the nodes of the given predicate.
+    fn new(predicate: &Predicate) -> Self {
+        let mut seen = HashSet::new();
+        let shared = predicate
+            .nodes
+            .iter()
+            .filter(|node| !seen.insert(&node.program_address))
+            .map(|node| node.program_address.clone())
+            .collect();
+        Self {
+            shared,
+            outputs: Default::default(),
+        }
+    }
+
+    /// The key for a run of the given program, if the program is shared.
+    fn key(
+        &self,
+        address: &ContentAddress,
+        leaf: bool,
+        inputs: &[Arc<(Stack, Memory)>],
+    ) -> Option<(ContentAddress, bool, u64)> {
+        use core::hash::{Hash, Hasher};
+        if !self.shared.contains(address) {
+            return None;
+        }
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        for input in inputs {
+            input.0[..].hash(&mut hasher);
+            input.1[..].hash(&mut hasher);
+        }
+        Some((address.clone(), leaf, hasher.finish()))
+    }
+
+    /// The output and gas of a previous run with the same key and inputs.
+    fn get(
+        &self,
+        key: &(ContentAddress, bool, u64),
+        inputs: &[Arc<(Stack, Memory)>],
+    ) -> Option<(Output, Gas)> {
+        let outputs = self.lock();
+        let entry = outputs.get(key)?.iter().find(|entry| {
+            entry.inputs.len() == inputs.len()
+                && entry
+                    .inputs
+                    .iter()
+                    .zip(inputs)
+                    .all(|(a, b)| Arc::ptr_eq(a, b) || a == b)
+        })?;
+        Some((entry.output.clone(), entry.gas))
+    }
+
+    /// Retain the output and gas of a run.
+    fn insert(
+        &self,
+        key: (ContentAddress, bool, u64),
+        inputs: Vec<Arc<(Stack, Memory)>>,
+        output: &Output,
+        gas: Gas,
+    ) {
+        self.lock().entry(key).or_default().push(MemoEntry {
+            inputs,
+            output: output.clone(),
+            gas,
+        });
+    }
+
+    fn lock(
+        &self,
+    ) -> std::sync::MutexGuard<'_, HashMap<(ContentAddress, bool, u64), Vec<MemoEntry>>> {
+        // The outputs are always left in a valid state.
+        self.outputs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<'a> LocalCache<'a> {
+    fn new(budget: &'a MemoryBudget) -> Self {
+        Self {
+            cache: Cache::new(),
+            budget,
+        }
+    }
+
+    fn get(&self, node: &u16) -> Option<&Arc<(Stack, Memory)>> {
+        self.cache.get(node)
+    }
+
+    /// Insert an output whose words have already been acquired from the budget.
+    fn insert(&mut self, node: u16, output: Arc<(Stack, Memory)>) {
+        if let Some(prev) = self.cache.insert(node, output) {
+            self.budget.release(output_words(&prev));
+        }
+    }
+
+    /// Remove an output, releasing its words from the budget.
+    fn remove(&mut self, node: u16) {
+        if let Some(output) = self.cache.remove(&node) {
+            self.budget.release(output_words(&output));
+        }
+    }
+}
+
+impl Drop for LocalCache<'_> {
+    fn drop(&mut self) {
+        let words = self.cache.values().map(|o| output_words(o)).sum();
+        self.budget.release(words);
+    }
+}
+
+/// The number of words held by a parent program's output.
+fn output_words(output: &(Stack, Memory)) -> usize {
+    let (stack, memory) = output;
+    stack.len() + memory[..].len()
 }
 
 /// Includes nodes with no parents
@@ -905,9 +2586,10 @@ fn parallel_topo_sort<E>(
     while !in_degrees.is_empty() {
         let current_level = find_nodes_with_no_parents(&in_degrees);
         if current_level.is_empty() {
-            // Cycle detected
-            // TODO: Change error
-            return Err(PredicateError::InvalidNodeEdges(0));
+            // Cycle detected. Validate the graph for a precise error.
+            let err = crate::predicate::validate_graph(predicate)
+                .expect_err("a graph in which every node has a parent contains a cycle");
+            return Err(err.into());
         }
 
         out.push(current_level.clone());
@@ -924,6 +2606,19 @@ fn parallel_topo_sort<E>(
     Ok(out)
 }
 
+/// For each solution, the index of the first identical solution preceding it, if any.
+fn find_duplicates(solutions: &[Solution]) -> Vec<Option<usize>> {
+    let mut first = HashMap::new();
+    solutions
+        .iter()
+        .enumerate()
+        .map(|(ix, solution)| {
+            let first_ix = *first.entry(solution).or_insert(ix);
+            (first_ix != ix).then_some(first_ix)
+        })
+        .collect()
+}
+
 fn find_deferred<F>(predicate: &Predicate, is_deferred: F) -> HashSet<u16>
 where
     F: Fn(&essential_types::predicate::Node) -> bool,
@@ -981,14 +2676,18 @@ fn remove_not_deferred(nodes: Vec<Vec<u16>>, deferred: &HashSet<u16>) -> Vec<Vec
 /// - Sorts the nodes into parallel topological order.
 /// - Sets up for the run type.
 /// - Runs the programs in parallel where appropriate.
-/// - Collects the outputs and gas.
+/// - Collects the outputs and gas, and optionally a report for each node.
+#[allow(clippy::too_many_arguments)]
 fn check_predicate_inner<F, E>(
     run: F,
     predicate: Arc<Predicate>,
     config: &CheckPredicateConfig,
     get_program: &(impl GetProgram + Send + Sync + 'static),
     ctx: Ctx<'_>,
-) -> Result<(Gas, Vec<DataOutput>), PredicateError<E>>
+    budget: &MemoryBudget,
+    deadline: Option<Instant>,
+    mut reports: Option<&mut NodeReports>,
+) -> Result<PredicateOutputs, PredicateError<E>>
 where
     F: Fn(u16, Vec<Arc<(Stack, Memory)>>) -> (u16, Result<(Output, u64), ProgramError<E>>)
         + Send
@@ -1006,12 +2705,8 @@ where
     let sorted_nodes = parallel_topo_sort(&predicate, &parent_map)?;
 
     // Filter for which nodes are deferred. This is nodes with a post state read.
-    let deferred_filter = |node: &essential_types::predicate::Node| -> bool {
-        asm::effects::bytes_contains_any(
-            &get_program.get_program(&node.program_address).0,
-            asm::effects::Effects::PostKeyRange | asm::effects::Effects::PostKeyRangeExtern,
-        )
-    };
+    let deferred_filter =
+        |node: &essential_types::predicate::Node| node_reads_post_state(node, get_program);
 
     // Get the set of deferred nodes.
     let deferred = find_deferred(&predicate, deferred_filter);
@@ -1023,19 +2718,36 @@ where
     };
 
     // Setup a local cache for the outputs.
-    let mut local_cache = Cache::new();
+    let mut local_cache = LocalCache::new(budget);
+
+    // When bounded, track the children yet to run for each node so that
+    // local outputs can be released as soon as they are no longer needed.
+    let mut pending_children: HashMap<u16, usize> = match budget.limit {
+        None => HashMap::new(),
+        Some(_) => (0..predicate.nodes.len())
+            .filter_map(|ix| {
+                let children = predicate.node_edges(ix)?.len();
+                Some((ix as u16, children))
+            })
+            .collect(),
+    };
 
     // The outputs from a run.
     let mut failed: Vec<(_, _)> = vec![];
     let mut total_gas: Gas = 0;
+    let mut node_gas = BTreeMap::new();
     let mut unsatisfied = Vec::new();
     let mut data_outputs = Vec::new();
 
     // Run each set of parallel nodes.
     for parallel_nodes in sorted_nodes {
-        // Run 1 or no length in serial to avoid overhead.
-        let outputs: BTreeMap<u16, Result<(Output, Gas), _>> =
-            if parallel_nodes.len() == 1 || parallel_nodes.is_empty() {
+        if is_expired(deadline) {
+            return Err(PredicateError::Timeout);
+        }
+
+        // Run 1 or no length in serial to avoid overhead, or if over the memory budget.
+        let outputs: BTreeMap<u16, (Result<_, _>, Duration)> =
+            if parallel_nodes.len() == 1 || parallel_nodes.is_empty() || budget.exceeded() {
                 parallel_nodes
                     .into_iter()
                     .map(|ix| {
@@ -1052,7 +2764,7 @@ where
                             .collect();
 
                         // Run the program.
-                        run(ix, inputs)
+                        timed(run, ix, inputs)
                     })
                     .collect()
             } else {
@@ -1072,22 +2784,41 @@ where
                             .collect();
 
                         // Run the program.
-                        run(ix, inputs)
+                        timed(run, ix, inputs)
                     })
                     .collect()
             };
-        for (node, res) in outputs {
+        for (node, (res, elapsed)) in outputs {
+            if let Some(reports) = reports.as_deref_mut() {
+                reports.insert(node, node_report(&res, elapsed));
+            }
+            // Release any local parent outputs that are no longer required.
+            if budget.limit.is_some() {
+                for parent in &parent_map[&node] {
+                    let Some(pending) = pending_children.get_mut(parent) else {
+                        continue;
+                    };
+                    *pending = pending.saturating_sub(1);
+                    if *pending == 0 {
+                        local_cache.remove(*parent);
+                    }
+                }
+            }
             match res {
                 Ok((Output::Parent(o), gas)) => {
                     // Check if we should add this output to the global or local cache.
+                    budget.acquire(output_words(&o));
                     if should_cache(node, &predicate, &deferred) {
-                        cache.insert(node, o.clone());
+                        if let Some(prev) = cache.insert(node, o.clone()) {
+                            budget.release(output_words(&prev));
+                        }
                     } else {
                         local_cache.insert(node, o.clone());
                     }
 
                     // Add to the total gas
                     total_gas = total_gas.saturating_add(gas);
+                    node_gas.insert(node, gas);
                 }
                 Ok((Output::Leaf(o), gas)) => {
                     match o {
@@ -1104,6 +2835,7 @@ where
 
                     // Add to the total gas
                     total_gas = total_gas.saturating_add(gas);
+                    node_gas.insert(node, gas);
                 }
                 Err(e) => {
                     failed.push((node as usize, e));
@@ -1114,6 +2846,16 @@ where
                 }
             }
         }
+
+        // Stop once the predicate's gas limit is exceeded.
+        if let Some(limit) = config.gas.limit_per_solution {
+            if total_gas > limit {
+                return Err(PredicateError::OutOfGas {
+                    limit,
+                    spent: total_gas,
+                });
+            }
+        }
     }
 
     // If there are any failed constraints, return an error.
@@ -1126,58 +2868,188 @@ where
         return Err(ConstraintsUnsatisfied(unsatisfied).into());
     }
 
-    Ok((total_gas, data_outputs))
+    Ok(PredicateOutputs {
+        gas: total_gas,
+        node_gas,
+        data: data_outputs,
+    })
 }
 
-/// Map the given program's bytecode and evaluate it.
+/// Run the node's program, timing its execution.
+fn timed<F, T>(run: F, ix: u16, inputs: Vec<Arc<(Stack, Memory)>>) -> (u16, (T, Duration))
+where
+    F: Fn(u16, Vec<Arc<(Stack, Memory)>>) -> (u16, T),
+{
+    let start = Instant::now();
+    let (ix, res) = run(ix, inputs);
+    (ix, (res, start.elapsed()))
+}
+
+/// Summarise the result of running a node's program.
+fn node_report<E>(res: &Result<(Output, Gas), E>, elapsed: Duration) -> NodeReport {
+    let (output, gas) = match res {
+        Ok((Output::Parent(_), gas)) => (NodeOutputKind::Parent, *gas),
+        Ok((Output::Leaf(ProgramOutput::Satisfied(satisfied)), gas)) => (
+            NodeOutputKind::Constraint {
+                satisfied: *satisfied,
+            },
+            *gas,
+        ),
+        Ok((Output::Leaf(ProgramOutput::DataOutput(_)), gas)) => (NodeOutputKind::Data, *gas),
+        Err(_) => (NodeOutputKind::Failed, 0),
+    };
+    NodeReport {
+        gas,
+        output,
+        elapsed,
+    }
+}
+
+/// Records the last operations executed by a program.
+struct TraceTail {
+    len: usize,
+    ops: VecDeque<(usize, asm::Op)>,
+}
+
+impl TraceTail {
+    fn new(len: usize) -> Self {
+        Self {
+            len,
+            ops: VecDeque::with_capacity(len),
+        }
+    }
+}
+
+impl vm::ExecHooks for TraceTail {
+    fn before_op(&mut self, pc: usize, op: &asm::Op, _stack: &Stack, _memory: &Memory) {
+        if self.len == 0 {
+            return;
+        }
+        if self.ops.len() == self.len {
+            self.ops.pop_front();
+        }
+        self.ops.push_back((pc, *op));
+    }
+}
+
+/// Run the program of a single node as it would be run while checking the
+/// solution at `solution_index` against its predicate.
 ///
-/// If the program is a constraint, returns `Some(bool)` indicating whether or not the constraint
-/// was satisfied, otherwise returns `None`.
-fn run_program<S>(
+/// The `parents` are the outputs of the node's parents in the order in which
+/// their edges appear within the predicate. When `leaf` is `true` the output
+/// is interpreted as a constraint or data, otherwise the node's stack and
+/// memory are output for its children.
+///
+/// The node's gas limit is enforced along with the gas schedule, limits and
+/// trace length of the given config. Programs are mapped through the
+/// [`CheckPredicateConfig::program_cache`], if any. Values derived from the
+/// solutions by the VM are not shared between calls.
+///
+/// This is useful for tools that step through a predicate one node at a
+/// time, e.g. REPLs and debuggers.
+///
+/// Returns the output and the gas spent.
+#[allow(clippy::too_many_arguments)]
+pub fn run_program<S>(
     state: S,
-    solution_set: Arc<SolutionSet>,
+    solutions: Arc<Vec<Solution>>,
     solution_index: SolutionIndex,
+    node: &essential_types::predicate::Node,
     program: Arc<Program>,
-    ctx: ProgramCtx,
+    parents: Vec<Arc<(Stack, Memory)>>,
+    leaf: bool,
+    config: &CheckPredicateConfig,
 ) -> Result<(Output, Gas), ProgramError<S::Error>>
 where
     S: StateReads,
 {
-    let ProgramCtx { parents, leaf } = ctx;
+    let ctx = ProgramCtx {
+        solutions,
+        address: node.program_address.clone(),
+        max_gas: config.gas.node_max_gas(node),
+        gas_schedule: &config.gas.schedule,
+        max_ops: config.max_ops_per_program,
+        trace_len: config.error_trace_len,
+        parents,
+        leaf,
+        cache: Default::default(),
+        programs: Arc::new(SetPrograms {
+            mapped: Default::default(),
+            shared: config.program_cache.clone(),
+        }),
+    };
+    exec_program(state, solution_index, program, ctx)
+}
 
-    // Pull ops into memory.
-    let ops = asm::from_bytes(program.0.iter().copied()).collect::<Result<Vec<_>, _>>()?;
+/// Map the given program's bytecode and evaluate it.
+///
+/// Leaf programs output whether or not their constraint was satisfied, or
+/// data. Other programs output their stack and memory.
+fn exec_program<S>(
+    state: S,
+    solution_index: SolutionIndex,
+    program: Arc<Program>,
+    ctx: ProgramCtx<'_>,
+) -> Result<(Output, Gas), ProgramError<S::Error>>
+where
+    S: StateReads,
+{
+    let ProgramCtx {
+        solutions,
+        address,
+        max_gas,
+        gas_schedule,
+        max_ops,
+        trace_len,
+        parents,
+        leaf,
+        cache,
+        programs,
+    } = ctx;
 
-    // Create a new VM.
-    let mut vm = vm::Vm::default();
+    // Map the program's ops, or reuse an existing mapping.
+    let mapped = programs.get_or_map(&address, program)?;
 
     // Use the results of the parent execution to initialise our stack and memory.
-    for parent_result in parents {
-        let (parent_stack, parent_memory) = Arc::unwrap_or_clone(parent_result);
-        // Extend the stack.
-        let mut stack: Vec<Word> = std::mem::take(&mut vm.stack).into();
-        stack.append(&mut parent_stack.into());
-        vm.stack = stack.try_into()?;
-
-        // Extend the memory.
-        let mut memory: Vec<Word> = std::mem::take(&mut vm.memory).into();
-        memory.append(&mut parent_memory.into());
-        vm.memory = memory.try_into()?;
-    }
+    let mut vm = vm::Vm::with_parent_outputs(&parents)?;
+    vm.max_ops = max_ops;
+    vm.cache = cache;
 
     // Setup solution access for execution.
-    let access = Access::new(Arc::new(solution_set.solutions.clone()), solution_index);
+    let access = Access::new(solutions, solution_index).with_this_program(address.clone());
 
-    // FIXME: Provide these from Config.
-    let gas_cost = |_: &asm::Op| 1;
-    let gas_limit = GasLimit::UNLIMITED;
+    let gas_limit = GasLimit {
+        total: max_gas.unwrap_or(GasLimit::UNLIMITED.total),
+        ..GasLimit::UNLIMITED
+    };
 
-    // Read the state into the VM's memory.
-    let gas_spent = vm.exec_ops(&ops, access, &state, &gas_cost, gas_limit)?;
+    // Execute the program, recording the tail of its trace for failures.
+    let mut trace = TraceTail::new(trace_len);
+    let gas_spent = vm
+        .exec_with_hooks(
+            access,
+            &state,
+            mapped.clone(),
+            gas_schedule,
+            gas_limit,
+            &mut trace,
+        )
+        .map_err(|error| {
+            ProgramError::Vm(Box::new(ExecFailure {
+                program: address,
+                op: vm::OpAccess::op_access(&*mapped, error.0).and_then(Result::ok),
+                stack: vm.stack.clone(),
+                trace: trace.ops.into(),
+                error,
+            }))
+        })?;
 
     let out = if leaf {
         match vm.stack[..] {
             [2] => Output::Leaf(ProgramOutput::DataOutput(DataOutput::Memory(vm.memory))),
+            [kind, 3] => Output::Leaf(ProgramOutput::DataOutput(DataOutput::Tagged(
+                kind, vm.memory,
+            ))),
             [1] => Output::Leaf(ProgramOutput::Satisfied(true)),
             _ => Output::Leaf(ProgramOutput::Satisfied(false)),
         }
@@ -0,0 +1,125 @@
+//! Coalescing of duplicate pre-state reads within a solution set.
+
+use crate::{
+    types::{ContentAddress, Key, Value},
+    vm::{StateRead, StateReads},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+/// A key range read by contract address, key and number of values.
+type KeyRange = (ContentAddress, Key, usize);
+
+/// The values of a key range read, once read.
+type Entry = Arc<Mutex<Option<Vec<Value>>>>;
+
+/// Memoizes the pre-state key range reads performed by the programs of a
+/// solution set so that identical reads issued by different nodes or
+/// solutions are performed against the underlying state only once.
+///
+/// Concurrent identical reads wait upon the first rather than each reading
+/// the underlying state. Failed reads are not memoized.
+#[derive(Clone)]
+pub(super) struct DedupReads<S> {
+    state: S,
+    reads: Arc<Mutex<HashMap<KeyRange, Entry>>>,
+}
+
+impl<S> DedupReads<S> {
+    /// Deduplicate the pre-state reads performed through the given state.
+    pub(super) fn new(state: S) -> Self {
+        Self {
+            state,
+            reads: Default::default(),
+        }
+    }
+
+    /// The entry for the given read, inserting an empty entry if necessary.
+    fn entry(&self, read: KeyRange) -> Entry {
+        lock(&self.reads).entry(read).or_default().clone()
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // The entries are always left in a valid state.
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl<S: StateReads> StateRead for DedupReads<S> {
+    type Error = S::Error;
+
+    fn key_range(
+        &self,
+        contract_addr: ContentAddress,
+        key: Key,
+        num_values: usize,
+    ) -> Result<Vec<Value>, Self::Error> {
+        let entry = self.entry((contract_addr.clone(), key.clone(), num_values));
+        let mut values = lock(&entry);
+        if let Some(values) = &*values {
+            return Ok(values.clone());
+        }
+        let read = self.state.pre().key_range(contract_addr, key, num_values)?;
+        *values = Some(read.clone());
+        Ok(read)
+    }
+
+    fn keys_with_prefix(
+        &self,
+        contract_addr: ContentAddress,
+        prefix: Key,
+        limit: usize,
+    ) -> Result<Vec<Key>, Self::Error> {
+        self.state
+            .pre()
+            .keys_with_prefix(contract_addr, prefix, limit)
+    }
+
+    fn key_ranges(
+        &self,
+        reads: Vec<(ContentAddress, Key, usize)>,
+    ) -> Result<Vec<Vec<Value>>, Self::Error> {
+        let entries: Vec<Entry> = reads.iter().map(|read| self.entry(read.clone())).collect();
+
+        // Perform the reads not yet memoized in a single batch.
+        let mut seen = HashSet::new();
+        let missing: Vec<usize> = (0..reads.len())
+            .filter(|&ix| lock(&entries[ix]).is_none() && seen.insert(&reads[ix]))
+            .collect();
+        if !missing.is_empty() {
+            let batch = missing.iter().map(|&ix| reads[ix].clone()).collect();
+            let values = self.state.pre().key_ranges(batch)?;
+            for (ix, values) in missing.into_iter().zip(values) {
+                lock(&entries[ix]).get_or_insert(values);
+            }
+        }
+
+        entries
+            .iter()
+            .zip(reads)
+            .map(|(entry, (contract_addr, key, num_values))| {
+                let values = lock(entry).clone();
+                match values {
+                    Some(values) => Ok(values),
+                    None => self.key_range(contract_addr, key, num_values),
+                }
+            })
+            .collect()
+    }
+}
+
+impl<S: StateReads> StateReads for DedupReads<S> {
+    type Error = S::Error;
+    type Pre = Self;
+    type Post = S::Post;
+
+    fn pre(&self) -> &Self::Pre {
+        self
+    }
+
+    fn post(&self) -> &Self::Post {
+        self.state.post()
+    }
+}
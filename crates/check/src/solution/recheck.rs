@@ -0,0 +1,272 @@
+//! Reuse of node outputs between checks of the same solution set.
+
+use super::Output;
+use crate::{
+    types::{solution::SolutionSet, ContentAddress, Key, Value},
+    vm::{Gas, Memory, Stack, StateRead, StateReads},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+/// The outputs of the nodes run while checking a solution set, retained so
+/// that the set may be rechecked with [`recheck_set_predicates`][super::recheck_set_predicates]
+/// after the state has changed.
+///
+/// Along with its output, the state keys read by each node are recorded so
+/// that only the nodes affected by a state change need to be rerun.
+#[derive(Debug, Default)]
+pub struct RecheckCache {
+    /// The address of the solution set the outputs belong to.
+    set: Option<ContentAddress>,
+    /// The recorded nodes of each solution, indexed by solution index.
+    solutions: Vec<Arc<RecordedNodes>>,
+}
+
+/// The recorded nodes of a single solution, indexed by node index.
+#[derive(Debug, Default)]
+pub(super) struct RecordedNodes(Mutex<HashMap<u16, RecordedNode>>);
+
+/// A node's output along with everything it depended upon.
+#[derive(Debug)]
+struct RecordedNode {
+    /// The outputs of the node's parents.
+    inputs: Vec<Arc<(Stack, Memory)>>,
+    /// The output of the node's program.
+    output: Output,
+    /// The gas spent by the node's program.
+    gas: Gas,
+    /// The pre-state reads performed by the node's program.
+    reads: Vec<Read>,
+}
+
+/// A read of the pre-state.
+#[derive(Clone, Debug)]
+enum Read {
+    /// A read of the given number of values starting at the given key.
+    KeyRange(ContentAddress, Key, usize),
+    /// A read of the keys with the given prefix.
+    KeysWithPrefix(ContentAddress, Key),
+}
+
+/// The reads recorded by [`RecordReads`].
+#[derive(Debug, Default)]
+pub(super) struct Reads {
+    /// The pre-state reads.
+    pre: Vec<Read>,
+    /// Whether or not the post-state was accessed.
+    post: bool,
+}
+
+/// Records the state reads performed by a program.
+#[derive(Clone)]
+pub(super) struct RecordReads<S> {
+    state: S,
+    reads: Arc<Mutex<Reads>>,
+}
+
+impl RecheckCache {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of node outputs currently retained.
+    pub fn len(&self) -> usize {
+        self.solutions.iter().map(|nodes| nodes.lock().len()).sum()
+    }
+
+    /// Whether or not no node outputs are retained.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove all retained node outputs.
+    pub fn clear(&mut self) {
+        self.set = None;
+        self.solutions.clear();
+    }
+
+    /// The recorded nodes for each of the given set's solutions.
+    ///
+    /// Outputs recorded for a different solution set are discarded.
+    pub(super) fn solutions(&mut self, set: &SolutionSet) -> &[Arc<RecordedNodes>] {
+        let addr = essential_hash::content_addr(set);
+        if self.set.as_ref() != Some(&addr) {
+            self.set = Some(addr);
+            self.solutions = (0..set.solutions.len())
+                .map(|_| Default::default())
+                .collect();
+        }
+        &self.solutions
+    }
+
+    /// Discard the outputs of nodes that read any of the changed keys.
+    ///
+    /// The descendants of these nodes are rerun only if the rerun produces
+    /// different outputs.
+    pub(super) fn invalidate(&mut self, changed: &HashMap<ContentAddress, HashSet<Key>>) {
+        if changed.values().all(HashSet::is_empty) {
+            return;
+        }
+        for nodes in &self.solutions {
+            nodes
+                .lock()
+                .retain(|_, node| !node.reads.iter().any(|read| read.affected_by(changed)));
+        }
+    }
+}
+
+impl RecordedNodes {
+    /// The recorded output and gas of the given node, if it was recorded with
+    /// the same inputs.
+    pub(super) fn reuse(
+        &self,
+        node: u16,
+        inputs: &[Arc<(Stack, Memory)>],
+    ) -> Option<(Output, Gas)> {
+        let nodes = self.lock();
+        let recorded = nodes.get(&node)?;
+        let same_inputs = recorded.inputs.len() == inputs.len()
+            && recorded
+                .inputs
+                .iter()
+                .zip(inputs)
+                .all(|(a, b)| Arc::ptr_eq(a, b) || a == b);
+        same_inputs.then(|| (recorded.output.clone(), recorded.gas))
+    }
+
+    /// Record the output of the given node.
+    ///
+    /// Nodes that accessed the post-state are not recorded, as the post-state
+    /// depends upon the outputs of the whole set.
+    pub(super) fn record(
+        &self,
+        node: u16,
+        inputs: Vec<Arc<(Stack, Memory)>>,
+        output: &Output,
+        gas: Gas,
+        reads: Reads,
+    ) {
+        let mut nodes = self.lock();
+        if reads.post {
+            nodes.remove(&node);
+            return;
+        }
+        let recorded = RecordedNode {
+            inputs,
+            output: output.clone(),
+            gas,
+            reads: reads.pre,
+        };
+        nodes.insert(node, recorded);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<u16, RecordedNode>> {
+        // The map is always left in a valid state.
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Read {
+    /// Whether or not any of the changed keys were read.
+    fn affected_by(&self, changed: &HashMap<ContentAddress, HashSet<Key>>) -> bool {
+        match self {
+            Read::KeyRange(contract, key, num_values) => {
+                let Some(keys) = changed.get(contract) else {
+                    return false;
+                };
+                let mut key = Some(key.clone());
+                for _ in 0..*num_values {
+                    let Some(k) = key else {
+                        break;
+                    };
+                    if keys.contains(&k) {
+                        return true;
+                    }
+                    key = k.next_key();
+                }
+                false
+            }
+            Read::KeysWithPrefix(contract, prefix) => changed
+                .get(contract)
+                .is_some_and(|keys| keys.iter().any(|key| key.starts_with(prefix))),
+        }
+    }
+}
+
+impl<S> RecordReads<S> {
+    /// Record the reads performed through the given state.
+    pub(super) fn new(state: S) -> Self {
+        Self {
+            state,
+            reads: Default::default(),
+        }
+    }
+
+    /// Take the reads recorded so far.
+    pub(super) fn take_reads(&self) -> Reads {
+        core::mem::take(&mut *self.lock())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Reads> {
+        // The reads are always left in a valid state.
+        self.reads.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<S: StateReads> StateRead for RecordReads<S> {
+    type Error = S::Error;
+
+    fn key_range(
+        &self,
+        contract_addr: ContentAddress,
+        key: Key,
+        num_values: usize,
+    ) -> Result<Vec<Value>, Self::Error> {
+        let read = Read::KeyRange(contract_addr.clone(), key.clone(), num_values);
+        self.lock().pre.push(read);
+        self.state.pre().key_range(contract_addr, key, num_values)
+    }
+
+    fn keys_with_prefix(
+        &self,
+        contract_addr: ContentAddress,
+        prefix: Key,
+        limit: usize,
+    ) -> Result<Vec<Key>, Self::Error> {
+        let read = Read::KeysWithPrefix(contract_addr.clone(), prefix.clone());
+        self.lock().pre.push(read);
+        self.state
+            .pre()
+            .keys_with_prefix(contract_addr, prefix, limit)
+    }
+
+    fn key_ranges(
+        &self,
+        reads: Vec<(ContentAddress, Key, usize)>,
+    ) -> Result<Vec<Vec<Value>>, Self::Error> {
+        self.lock().pre.extend(
+            reads
+                .iter()
+                .map(|(addr, key, num)| Read::KeyRange(addr.clone(), key.clone(), *num)),
+        );
+        self.state.pre().key_ranges(reads)
+    }
+}
+
+impl<S: StateReads> StateReads for RecordReads<S> {
+    type Error = S::Error;
+    type Pre = Self;
+    type Post = S::Post;
+
+    fn pre(&self) -> &Self::Pre {
+        self
+    }
+
+    fn post(&self) -> &Self::Post {
+        self.lock().post = true;
+        self.state.post()
+    }
+}
@@ -15,26 +15,32 @@ fn p() -> Predicate {
             Node {
                 edge_start: 0,
                 program_address: ContentAddress([0; 32]),
+                max_gas: None,
             },
             Node {
                 edge_start: 1,
                 program_address: ContentAddress([1; 32]),
+                max_gas: None,
             },
             Node {
                 edge_start: 2,
                 program_address: ContentAddress([2; 32]),
+                max_gas: None,
             },
             Node {
                 edge_start: 4,
                 program_address: ContentAddress([3; 32]),
+                max_gas: None,
             },
             Node {
                 edge_start: 5,
                 program_address: ContentAddress([4; 32]),
+                max_gas: None,
             },
             Node {
                 edge_start: Edge::MAX,
                 program_address: ContentAddress([5; 32]),
+                max_gas: None,
             },
         ],
         edges: vec![2, 2, 3, 4, 5, 5],
@@ -54,30 +60,37 @@ fn p3() -> Predicate {
             Node {
                 edge_start: 0,
                 program_address: ContentAddress([0; 32]), // 0
+                max_gas: None,
             },
             Node {
                 edge_start: 2,
                 program_address: ContentAddress([1; 32]), // 1
+                max_gas: None,
             },
             Node {
                 edge_start: 4,
                 program_address: ContentAddress([2; 32]), // 2
+                max_gas: None,
             },
             Node {
                 edge_start: 5,
                 program_address: ContentAddress([4; 32]), // 3
+                max_gas: None,
             },
             Node {
                 edge_start: 6,
                 program_address: ContentAddress([5; 32]), // 4
+                max_gas: None,
             },
             Node {
                 edge_start: Edge::MAX,
                 program_address: ContentAddress([3; 32]), // 5
+                max_gas: None,
             },
             Node {
                 edge_start: Edge::MAX,
                 program_address: ContentAddress([6; 32]), // 6
+                max_gas: None,
             },
         ],
         edges: vec![1, 2, 5, 3, 4, 6, 6],
@@ -106,6 +119,26 @@ fn test_parallel_top_sort() {
     );
 }
 
+/// 0 -> 1 -> 2 -> 1
+#[test]
+fn test_parallel_top_sort_cycle() {
+    let node = |edge_start| Node {
+        edge_start,
+        program_address: ContentAddress([0; 32]),
+        max_gas: None,
+    };
+    let predicate = Predicate {
+        nodes: vec![node(0), node(1), node(2)],
+        edges: vec![1, 2, 1],
+    };
+    let parent_map = create_parent_map::<String>(&predicate).unwrap();
+
+    assert!(matches!(
+        parallel_topo_sort::<String>(&predicate, &parent_map).unwrap_err(),
+        PredicateError::InvalidGraph(crate::predicate::InvalidGraph::Cycle(cycle)) if cycle == [1, 2]
+    ));
+}
+
 /// 0   1
 ///  \ /
 ///   2
@@ -247,34 +280,42 @@ fn p2() -> Predicate {
             Node {
                 edge_start: 0,
                 program_address: ContentAddress([0; 32]),
+                max_gas: None,
             },
             Node {
                 edge_start: 1,
                 program_address: ContentAddress([1; 32]),
+                max_gas: None,
             },
             Node {
                 edge_start: 2,
                 program_address: ContentAddress([2; 32]),
+                max_gas: None,
             },
             Node {
                 edge_start: 4,
                 program_address: ContentAddress([3; 32]),
+                max_gas: None,
             },
             Node {
                 edge_start: 5,
                 program_address: ContentAddress([4; 32]),
+                max_gas: None,
             },
             Node {
                 edge_start: 6,
                 program_address: ContentAddress([5; 32]),
+                max_gas: None,
             },
             Node {
                 edge_start: 7,
                 program_address: ContentAddress([6; 32]),
+                max_gas: None,
             },
             Node {
                 edge_start: Edge::MAX,
                 program_address: ContentAddress([7; 32]),
+                max_gas: None,
             },
         ],
         edges: vec![2, 2, 3, 4, 5, 6, 7, 7],
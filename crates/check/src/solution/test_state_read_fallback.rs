@@ -13,14 +13,20 @@ fn s(post: &[(ContentAddr, &K, &V)], pre: &[(ContentAddr, &K, &V)]) -> (PostStat
         pre_state
             .entry(ContentAddress([*addr; 32]))
             .or_insert_with(HashMap::new)
-            .insert(key.to_vec(), value.to_vec());
+            .insert(
+                key.to_vec().try_into().unwrap(),
+                value.to_vec().try_into().unwrap(),
+            );
     }
     let mut post_state = HashMap::new();
     for (addr, key, value) in post.iter() {
         post_state
             .entry(ContentAddress([*addr; 32]))
             .or_insert_with(HashMap::new)
-            .insert(key.to_vec(), value.to_vec());
+            .insert(
+                key.to_vec().try_into().unwrap(),
+                value.to_vec().try_into().unwrap(),
+            );
     }
     (
         PostState { state: post_state },
@@ -36,7 +42,7 @@ impl StateRead for PreState {
         contract_addr: ContentAddress,
         mut key: Key,
         num_values: usize,
-    ) -> Result<Vec<Vec<Word>>, Self::Error> {
+    ) -> Result<Vec<Value>, Self::Error> {
         let mut result = vec![];
         if let Some(content) = self.state.get(&contract_addr) {
             for _ in 0..num_values {
@@ -44,9 +50,9 @@ impl StateRead for PreState {
                     Some(value) => {
                         result.push(value.clone());
                     }
-                    None => result.push(vec![]),
+                    None => result.push(Value::default()),
                 }
-                match next_key(key) {
+                match key.next_key() {
                     Some(next_key) => key = next_key,
                     None => break,
                 }
@@ -54,6 +60,25 @@ impl StateRead for PreState {
         }
         Ok(result)
     }
+
+    fn keys_with_prefix(
+        &self,
+        contract_addr: ContentAddress,
+        prefix: Key,
+        limit: usize,
+    ) -> Result<Vec<Key>, Self::Error> {
+        let Some(contract) = self.state.get(&contract_addr) else {
+            return Ok(vec![]);
+        };
+        let mut keys: Vec<Key> = contract
+            .iter()
+            .filter(|(key, value)| key.starts_with(&prefix) && !value.is_empty())
+            .map(|(key, _)| key.clone())
+            .collect();
+        keys.sort();
+        keys.truncate(limit);
+        Ok(keys)
+    }
 }
 
 fn c(i: ContentAddr) -> ContentAddress {
@@ -64,19 +89,19 @@ fn c(i: ContentAddr) -> ContentAddress {
 fn test_fallback() {
     let (post, pre) = s(&[], &[(0, &[1], &[42]), (0, &[2], &[43])]);
     assert_eq!(
-        read_or_fallback(&post, &pre, c(0), vec![1], 1).unwrap(),
+        read_or_fallback(&post, &pre, c(0), [1].into(), 1).unwrap(),
         vec![vec![42]]
     );
     assert_eq!(
-        read_or_fallback(&post, &pre, c(0), vec![1], 2).unwrap(),
+        read_or_fallback(&post, &pre, c(0), [1].into(), 2).unwrap(),
         vec![vec![42], vec![43]]
     );
     assert_eq!(
-        read_or_fallback(&post, &pre, c(0), vec![0], 2).unwrap(),
+        read_or_fallback(&post, &pre, c(0), [0].into(), 2).unwrap(),
         vec![vec![], vec![42]]
     );
     assert_eq!(
-        read_or_fallback(&post, &pre, c(0), vec![2], 2).unwrap(),
+        read_or_fallback(&post, &pre, c(0), [2].into(), 2).unwrap(),
         vec![vec![43], vec![]]
     );
 
@@ -85,19 +110,19 @@ fn test_fallback() {
         &[(0, &[1], &[52]), (0, &[2], &[53])],
     );
     assert_eq!(
-        read_or_fallback(&post, &pre, c(0), vec![1], 1).unwrap(),
+        read_or_fallback(&post, &pre, c(0), [1].into(), 1).unwrap(),
         vec![vec![42]]
     );
     assert_eq!(
-        read_or_fallback(&post, &pre, c(0), vec![1], 2).unwrap(),
+        read_or_fallback(&post, &pre, c(0), [1].into(), 2).unwrap(),
         vec![vec![42], vec![43]]
     );
     assert_eq!(
-        read_or_fallback(&post, &pre, c(0), vec![0], 2).unwrap(),
+        read_or_fallback(&post, &pre, c(0), [0].into(), 2).unwrap(),
         vec![vec![], vec![42]]
     );
     assert_eq!(
-        read_or_fallback(&post, &pre, c(0), vec![2], 2).unwrap(),
+        read_or_fallback(&post, &pre, c(0), [2].into(), 2).unwrap(),
         vec![vec![43], vec![]]
     );
 
@@ -106,19 +131,52 @@ fn test_fallback() {
         &[(0, &[1], &[52]), (0, &[2], &[53])],
     );
     assert_eq!(
-        read_or_fallback(&post, &pre, c(0), vec![1], 1).unwrap(),
+        read_or_fallback(&post, &pre, c(0), [1].into(), 1).unwrap(),
         vec![vec![42]]
     );
     assert_eq!(
-        read_or_fallback(&post, &pre, c(0), vec![1], 2).unwrap(),
+        read_or_fallback(&post, &pre, c(0), [1].into(), 2).unwrap(),
         vec![vec![42], vec![53]]
     );
     assert_eq!(
-        read_or_fallback(&post, &pre, c(0), vec![0], 2).unwrap(),
+        read_or_fallback(&post, &pre, c(0), [0].into(), 2).unwrap(),
         vec![vec![], vec![42]]
     );
     assert_eq!(
-        read_or_fallback(&post, &pre, c(0), vec![2], 2).unwrap(),
+        read_or_fallback(&post, &pre, c(0), [2].into(), 2).unwrap(),
         vec![vec![53], vec![43]]
     );
 }
+
+#[test]
+fn test_keys_with_prefix_fallback() {
+    let pre_kvs: &[(ContentAddr, &K, &V)] = &[
+        (0, &[1, 1], &[10]),
+        (0, &[1, 2], &[11]),
+        (0, &[1, 3], &[12]),
+        (0, &[2, 1], &[13]),
+    ];
+    let (post, pre) = s(&[], pre_kvs);
+    assert_eq!(
+        keys_with_prefix_or_fallback(&post, &pre, c(0), [1].into(), 2).unwrap(),
+        vec![vec![1, 1], vec![1, 2]]
+    );
+
+    // Deletions remove pre state keys, while insertions are merged in order.
+    let (post, pre) = s(
+        &[(0, &[1, 1], &[]), (0, &[1, 0], &[42]), (0, &[2, 2], &[43])],
+        pre_kvs,
+    );
+    assert_eq!(
+        keys_with_prefix_or_fallback(&post, &pre, c(0), [1].into(), 3).unwrap(),
+        vec![vec![1, 0], vec![1, 2], vec![1, 3]]
+    );
+    assert_eq!(
+        keys_with_prefix_or_fallback(&post, &pre, c(0), [1].into(), 10).unwrap(),
+        vec![vec![1, 0], vec![1, 2], vec![1, 3]]
+    );
+    assert_eq!(
+        keys_with_prefix_or_fallback(&post, &pre, c(1), [1].into(), 10).unwrap(),
+        Vec::<Key>::new()
+    );
+}
@@ -17,6 +17,7 @@ fn p(i: &[(ContentAddr, &[Edge])]) -> Arc<Predicate> {
                 last_edge_start
             },
             program_address: ContentAddress([*n; 32]),
+            max_gas: None,
         };
         last_edge_start += edges.len() as u16;
         nodes.push(node);
@@ -96,12 +97,15 @@ fn test_check_predicate_inner() {
         };
         (ix, Ok::<_, ProgramError<String>>((o, 0)))
     };
-    let (_, out) = check_predicate_inner(
+    let PredicateOutputs { data: out, .. } = check_predicate_inner(
         run,
         predicate.clone(),
         &Default::default(),
         &get_program,
         ctx,
+        &MemoryBudget::default(),
+        None,
+        None,
     )
     .unwrap();
     assert!(out.is_empty());
@@ -123,12 +127,15 @@ fn test_check_predicate_inner() {
         };
         (ix, Ok::<_, ProgramError<String>>((o, 0)))
     };
-    let (_, out) = check_predicate_inner(
+    let PredicateOutputs { data: out, .. } = check_predicate_inner(
         run,
         predicate.clone(),
         &Default::default(),
         &get_program,
         ctx,
+        &MemoryBudget::default(),
+        None,
+        None,
     )
     .unwrap();
     assert_eq!(out.len(), 1);
@@ -140,12 +147,15 @@ fn test_check_predicate_inner() {
         run_mode: RunMode::Checks,
         cache: &mut cache,
     };
-    let (_, out) = check_predicate_inner(
+    let PredicateOutputs { data: out, .. } = check_predicate_inner(
         run,
         predicate.clone(),
         &Default::default(),
         &get_program,
         ctx,
+        &MemoryBudget::default(),
+        None,
+        None,
     )
     .unwrap();
     assert!(out.is_empty());
@@ -171,6 +181,9 @@ fn test_check_predicate_inner() {
         &Default::default(),
         &get_program,
         ctx,
+        &MemoryBudget::default(),
+        None,
+        None,
     )
     .unwrap_err();
     assert!(cache.is_empty());
@@ -180,12 +193,15 @@ fn test_check_predicate_inner() {
         run_mode: RunMode::Checks,
         cache: &mut cache,
     };
-    let (_, out) = check_predicate_inner(
+    let PredicateOutputs { data: out, .. } = check_predicate_inner(
         run,
         predicate.clone(),
         &Default::default(),
         &get_program,
         ctx,
+        &MemoryBudget::default(),
+        None,
+        None,
     )
     .unwrap();
     assert!(out.is_empty());
@@ -261,12 +277,15 @@ fn test_check_predicate_inner() {
         };
         (ix, Ok::<_, ProgramError<String>>((o, 0)))
     };
-    let (_, out) = check_predicate_inner(
+    let PredicateOutputs { data: out, .. } = check_predicate_inner(
         run,
         predicate.clone(),
         &Default::default(),
         &get_program,
         ctx,
+        &MemoryBudget::default(),
+        None,
+        None,
     )
     .unwrap();
     assert_eq!(out.len(), 1);
@@ -308,12 +327,15 @@ fn test_check_predicate_inner() {
         };
         (ix, Ok::<_, ProgramError<String>>((o, 0)))
     };
-    let (_, out) = check_predicate_inner(
+    let PredicateOutputs { data: out, .. } = check_predicate_inner(
         run,
         predicate.clone(),
         &Default::default(),
         &get_program,
         ctx,
+        &MemoryBudget::default(),
+        None,
+        None,
     )
     .unwrap();
     assert_eq!(out.len(), 1);
@@ -321,3 +343,50 @@ fn test_check_predicate_inner() {
 
     assert_eq!(cache.len(), 2);
 }
+
+#[test]
+fn test_node_memo() {
+    // Program 1 is shared by nodes 0 and 2, program 2 appears once.
+    let predicate = p(&[(1, &[1]), (2, &[]), (1, &[])]);
+    let memo = NodeMemo::new(&predicate);
+    let shared = ContentAddress([1; 32]);
+    let unshared = ContentAddress([2; 32]);
+    assert!(memo.key(&unshared, true, &[]).is_none());
+
+    let inputs = vec![parent(&[1, 2], &[3])];
+    let key = memo.key(&shared, false, &inputs).unwrap();
+    assert!(memo.get(&key, &inputs).is_none());
+    memo.insert(key.clone(), inputs, &Output::Parent(parent(&[4], &[])), 7);
+
+    // Equal inputs reuse the output and gas, regardless of identity.
+    let equal = vec![parent(&[1, 2], &[3])];
+    let key = memo.key(&shared, false, &equal).unwrap();
+    let (out, gas) = memo.get(&key, &equal).unwrap();
+    assert!(matches!(out, Output::Parent(o) if o == parent(&[4], &[])));
+    assert_eq!(gas, 7);
+
+    // Different inputs or a different kind of node are run again.
+    let different = vec![parent(&[1, 2], &[4])];
+    let key = memo.key(&shared, false, &different).unwrap();
+    assert!(memo.get(&key, &different).is_none());
+    let key = memo.key(&shared, true, &equal).unwrap();
+    assert!(memo.get(&key, &equal).is_none());
+}
+
+#[test]
+fn test_hint_sampling() {
+    // The selection is pinned to SHA-256 so validators agree across builds.
+    let program = Program(vec![]);
+    let sampled = |seed| {
+        let hints = HintVerification {
+            sample_percent: 50,
+            seed,
+            verify_state_reads: true,
+        };
+        (0..16)
+            .map(|node| u8::from(hints.verifies(1, node, &program)))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(sampled(0), [1, 0, 1, 0, 1, 0, 1, 0, 0, 1, 0, 0, 1, 0, 0, 1]);
+    assert_eq!(sampled(7), [0, 0, 0, 0, 1, 1, 1, 0, 0, 0, 0, 1, 1, 0, 0, 1]);
+}
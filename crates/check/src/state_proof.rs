@@ -0,0 +1,351 @@
+//! Verification of state reads against a committed state root.
+//!
+//! Light validators that do not hold the full state may instead read state
+//! from a [`ProvenStateRead`] backend, which returns each value along with a
+//! [`StateProof`] against a known state root. [`VerifiedStateRead`] checks each
+//! proof before the values are handed to the VM, and implements [`StateRead`]
+//! so that it may be used with the checker in place of the full state.
+//!
+//! ## State Tree
+//!
+//! State is committed to with a sparse Merkle tree of depth 256:
+//!
+//! - Each `(contract, key)` pair maps to the leaf at [`leaf_path`].
+//! - Empty values are absent from the tree, so a proof for an empty value is
+//!   a proof of non-inclusion.
+//! - The hash of an absent leaf, or of a node with two empty children, is
+//!   all zeros.
+//! - The hash of a present leaf is `sha256(path ++ value_bytes)`, and the hash
+//!   of a node is `sha256(left ++ right)`.
+//!
+//! [`StateTree`] holds a full state in memory, producing its root and proofs.
+
+use crate::{
+    types::{convert::bytes_from_word, ContentAddress, Hash, Key, Value, Word},
+    vm::StateRead,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// The depth of the state tree, i.e. the number of bits in a [`leaf_path`].
+pub const DEPTH: usize = 256;
+
+/// The hash of an empty leaf or subtree.
+pub const EMPTY: Hash = [0; 32];
+
+/// Read-only access to state along with proofs against a state root.
+pub trait ProvenStateRead: Send + Sync {
+    /// An error type describing any cases that might occur during state reading.
+    type Error: core::fmt::Debug + core::fmt::Display;
+
+    /// Read the given number of values from state at the given key associated
+    /// with the given contract address, along with a proof for each.
+    ///
+    /// Exactly `num_values` values must be returned, where absent values are
+    /// empty and accompanied by a proof of non-inclusion.
+    fn key_range_proven(
+        &self,
+        contract_addr: ContentAddress,
+        key: Key,
+        num_values: usize,
+    ) -> Result<Vec<ProvenValue>, Self::Error>;
+}
+
+/// A value read from state along with its proof.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvenValue {
+    /// The value, empty if absent from state.
+    pub value: Value,
+    /// The proof of the value against the state root.
+    pub proof: StateProof,
+}
+
+/// A Merkle proof for a single leaf of the state tree.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateProof {
+    /// A bit for each level of the tree, starting from the leaf, set where
+    /// the sibling at that level is non-empty.
+    pub non_empty: [u8; 32],
+    /// The non-empty siblings, starting from the leaf.
+    pub siblings: Vec<Hash>,
+}
+
+/// A [`StateRead`] implementation that verifies all values read from the
+/// inner [`ProvenStateRead`] against the given state root.
+#[derive(Clone, Debug)]
+pub struct VerifiedStateRead<S> {
+    state: S,
+    root: Hash,
+}
+
+/// An in-memory state tree, producing roots and proofs for its state.
+#[derive(Clone, Debug, Default)]
+pub struct StateTree {
+    /// Leaf path => (value, leaf hash).
+    leaves: BTreeMap<Hash, (Value, Hash)>,
+}
+
+/// A state read through a [`VerifiedStateRead`] failed.
+#[derive(Debug, Error)]
+pub enum ProofError<E> {
+    /// The inner state read failed.
+    #[error("failed to read proven state: {0}")]
+    Read(E),
+    /// The inner state read returned the wrong number of values.
+    #[error("expected {expected} proven values, found {found}")]
+    NumValues {
+        /// The number of values requested.
+        expected: usize,
+        /// The number of values returned.
+        found: usize,
+    },
+    /// A proof did not match the state root.
+    #[error("invalid proof for key {key:?} of contract {contract}")]
+    InvalidProof {
+        /// The contract of the value.
+        contract: ContentAddress,
+        /// The key of the value.
+        key: Key,
+    },
+    /// The key range extends beyond the last key.
+    #[error("key range overflows the key space")]
+    KeyOverflow,
+    /// The set of keys with a prefix cannot be proven by the state tree.
+    #[error("prefix reads cannot be proven against a state root")]
+    PrefixUnsupported,
+}
+
+/// The path of the leaf for the given contract and key within the state tree.
+pub fn leaf_path(contract_addr: &ContentAddress, key: &[Word]) -> Hash {
+    essential_hash::hash(&(contract_addr, key))
+}
+
+/// The hash of the leaf at the given path with the given value.
+pub fn leaf_hash(path: &Hash, value: &[Word]) -> Hash {
+    if value.is_empty() {
+        return EMPTY;
+    }
+    let mut hasher = Sha256::new();
+    hasher.update(path);
+    value
+        .iter()
+        .for_each(|&w| hasher.update(bytes_from_word(w)));
+    hasher.finalize().into()
+}
+
+/// The hash of a node with the given children.
+pub fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    if *left == EMPTY && *right == EMPTY {
+        return EMPTY;
+    }
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// The bit at the given index, from the most significant bit of the first byte.
+fn bit(bytes: &[u8; 32], ix: usize) -> bool {
+    bytes[ix / 8] & (0x80 >> (ix % 8)) != 0
+}
+
+impl StateProof {
+    /// Compute the state root implied by this proof for the given value.
+    ///
+    /// Returns `None` if the number of siblings does not match the bitmap.
+    pub fn root(&self, path: &Hash, value: &[Word]) -> Option<Hash> {
+        let mut node = leaf_hash(path, value);
+        let mut siblings = self.siblings.iter();
+        for level in 0..DEPTH {
+            let sibling = match bit(&self.non_empty, level) {
+                true => *siblings.next()?,
+                false => EMPTY,
+            };
+            node = match bit(path, DEPTH - 1 - level) {
+                true => node_hash(&sibling, &node),
+                false => node_hash(&node, &sibling),
+            };
+        }
+        siblings.next().is_none().then_some(node)
+    }
+
+    /// Whether this proof proves the given value for the given contract and
+    /// key against the given root.
+    pub fn verify(
+        &self,
+        root: &Hash,
+        contract_addr: &ContentAddress,
+        key: &[Word],
+        value: &[Word],
+    ) -> bool {
+        let path = leaf_path(contract_addr, key);
+        self.root(&path, value).as_ref() == Some(root)
+    }
+}
+
+impl<S> VerifiedStateRead<S> {
+    /// Verify all reads from the given state against the given root.
+    pub fn new(state: S, root: Hash) -> Self {
+        Self { state, root }
+    }
+
+    /// The state root that reads are verified against.
+    pub fn root(&self) -> &Hash {
+        &self.root
+    }
+
+    /// The inner proven state read.
+    pub fn inner(&self) -> &S {
+        &self.state
+    }
+}
+
+impl<S> StateRead for VerifiedStateRead<S>
+where
+    S: ProvenStateRead,
+{
+    type Error = ProofError<S::Error>;
+
+    fn key_range(
+        &self,
+        contract_addr: ContentAddress,
+        key: Key,
+        num_values: usize,
+    ) -> Result<Vec<Value>, Self::Error> {
+        let proven = self
+            .state
+            .key_range_proven(contract_addr.clone(), key.clone(), num_values)
+            .map_err(ProofError::Read)?;
+        if proven.len() != num_values {
+            return Err(ProofError::NumValues {
+                expected: num_values,
+                found: proven.len(),
+            });
+        }
+        let mut values = Vec::with_capacity(num_values);
+        let mut key = Some(key);
+        for ProvenValue { value, proof } in proven {
+            let k = key.ok_or(ProofError::KeyOverflow)?;
+            if !proof.verify(&self.root, &contract_addr, &k, &value) {
+                return Err(ProofError::InvalidProof {
+                    contract: contract_addr,
+                    key: k,
+                });
+            }
+            values.push(value);
+            key = k.next_key();
+        }
+        Ok(values)
+    }
+
+    fn keys_with_prefix(
+        &self,
+        _contract_addr: ContentAddress,
+        _prefix: Key,
+        _limit: usize,
+    ) -> Result<Vec<Key>, Self::Error> {
+        Err(ProofError::PrefixUnsupported)
+    }
+}
+
+impl StateTree {
+    /// Create an empty state tree.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the value at the given contract and key, removing it if empty.
+    pub fn insert(&mut self, contract_addr: &ContentAddress, key: &[Word], value: Value) {
+        let path = leaf_path(contract_addr, key);
+        if value.is_empty() {
+            self.leaves.remove(&path);
+        } else {
+            let hash = leaf_hash(&path, &value);
+            self.leaves.insert(path, (value, hash));
+        }
+    }
+
+    /// The value at the given contract and key, if any.
+    pub fn get(&self, contract_addr: &ContentAddress, key: &[Word]) -> Option<&Value> {
+        let path = leaf_path(contract_addr, key);
+        self.leaves.get(&path).map(|(value, _)| value)
+    }
+
+    /// The root of the state tree.
+    pub fn root(&self) -> Hash {
+        let leaves: Vec<_> = self.leaves.iter().map(|(p, (_, h))| (*p, *h)).collect();
+        subtree_hash(&leaves, 0)
+    }
+
+    /// The value at the given contract and key along with its proof.
+    pub fn prove(&self, contract_addr: &ContentAddress, key: &[Word]) -> ProvenValue {
+        let path = leaf_path(contract_addr, key);
+        let value = self
+            .leaves
+            .get(&path)
+            .map(|(value, _)| value.clone())
+            .unwrap_or_default();
+        let leaves: Vec<_> = self.leaves.iter().map(|(p, (_, h))| (*p, *h)).collect();
+        let mut leaves = &leaves[..];
+        let mut proof = StateProof::default();
+        // Collect the siblings from the root down, then reverse.
+        for depth in 0..DEPTH {
+            let split = leaves.partition_point(|(p, _)| !bit(p, depth));
+            let (left, right) = leaves.split_at(split);
+            let (next, sibling) = match bit(&path, depth) {
+                true => (right, left),
+                false => (left, right),
+            };
+            let sibling = subtree_hash(sibling, depth + 1);
+            if sibling != EMPTY {
+                let level = DEPTH - 1 - depth;
+                proof.non_empty[level / 8] |= 0x80 >> (level % 8);
+                proof.siblings.push(sibling);
+            }
+            leaves = next;
+        }
+        proof.siblings.reverse();
+        ProvenValue { value, proof }
+    }
+}
+
+impl ProvenStateRead for StateTree {
+    type Error = core::convert::Infallible;
+
+    fn key_range_proven(
+        &self,
+        contract_addr: ContentAddress,
+        key: Key,
+        num_values: usize,
+    ) -> Result<Vec<ProvenValue>, Self::Error> {
+        let mut values = Vec::with_capacity(num_values);
+        let mut key = Some(key);
+        for _ in 0..num_values {
+            let Some(k) = key else {
+                break;
+            };
+            values.push(self.prove(&contract_addr, &k));
+            key = k.next_key();
+        }
+        Ok(values)
+    }
+}
+
+/// The hash of the subtree at the given depth containing the given leaves,
+/// sorted by path.
+fn subtree_hash(leaves: &[(Hash, Hash)], depth: usize) -> Hash {
+    match leaves {
+        [] => EMPTY,
+        [(_, hash)] if depth == DEPTH => *hash,
+        _ => {
+            let split = leaves.partition_point(|(p, _)| !bit(p, depth));
+            let (left, right) = leaves.split_at(split);
+            node_hash(
+                &subtree_hash(left, depth + 1),
+                &subtree_hash(right, depth + 1),
+            )
+        }
+    }
+}
@@ -47,6 +47,7 @@ fn test_encoding_sig_and_pub_key() {
     let nodes = vec![Node {
         program_address: program_address.clone(),
         edge_start: Edge::MAX,
+        max_gas: None,
     }];
     let edges = vec![];
     let predicate = Predicate { nodes, edges };
@@ -69,7 +70,7 @@ fn test_encoding_sig_and_pub_key() {
     let sig = secp.sign_ecdsa_recoverable(&secp256k1::Message::from_digest(hash), &sk);
     let encoded_sig = essential_sign::encode::signature(&sig);
 
-    let predicate_data = vec![encoded_pk.to_vec(), encoded_sig.to_vec()];
+    let predicate_data = vec![encoded_pk.into(), encoded_sig.into()];
 
     let solution = SolutionSet {
         solutions: vec![Solution {
@@ -1,9 +1,14 @@
-use essential_check::predicate::{self, InvalidPredicate};
+use essential_check::{
+    predicate::{self, InvalidConstraintProgram, InvalidGraph, InvalidPredicate},
+    vm::asm::{self, short::*, Op},
+};
 use essential_types::{
-    predicate::{Node, Predicate},
+    contract::ContractMetadata,
+    predicate::{Edge, Node, Predicate, Program},
     ContentAddress,
 };
-use util::{empty_predicate, random_keypair};
+use std::{collections::HashMap, sync::Arc};
+use util::{empty_contract, empty_predicate, random_keypair};
 
 pub mod util;
 
@@ -37,6 +42,35 @@ fn too_many_predicates() {
     ));
 }
 
+#[test]
+fn contract_metadata() {
+    let metadata = ContractMetadata {
+        name: "counter".into(),
+        version: "1.0.0".into(),
+        abi_hash: [1; 32],
+        source_commit: None,
+    };
+    let addr = essential_hash::content_addr(&metadata);
+    let contract = empty_contract().with_metadata(metadata);
+    predicate::check_contract_metadata(&contract, &addr).unwrap();
+    assert!(matches!(
+        predicate::check_contract_metadata(&contract, &ContentAddress([0; 32])),
+        Err(predicate::InvalidContractMetadata::AddressMismatch { found, .. }) if found == addr
+    ));
+
+    let mut long = contract.clone();
+    let metadata = &mut long.metadata;
+    metadata.source_commit = Some("0".repeat(predicate::MAX_METADATA_FIELD_LEN + 1));
+    let addr = essential_hash::content_addr(&*metadata);
+    assert_eq!(
+        predicate::check_contract_metadata(&long, &addr),
+        Err(predicate::InvalidContractMetadata::FieldTooLong {
+            field: "source_commit",
+            len: predicate::MAX_METADATA_FIELD_LEN + 1,
+        })
+    );
+}
+
 #[test]
 fn too_many_nodes() {
     let mut predicate = empty_predicate();
@@ -44,6 +78,7 @@ fn too_many_nodes() {
         Node {
             edge_start: 0,
             program_address: ContentAddress([0; 32]),
+            max_gas: None,
         };
         usize::from(Predicate::MAX_NODES) + 1
     ];
@@ -64,3 +99,175 @@ fn too_many_edges() {
             if n == usize::from(Predicate::MAX_EDGES) + 1
     ));
 }
+
+#[test]
+fn validate_graph() {
+    let node = |edge_start| Node {
+        edge_start,
+        program_address: ContentAddress([0; 32]),
+        max_gas: None,
+    };
+
+    // A diamond is valid.
+    let predicate = Predicate {
+        nodes: vec![node(0), node(2), node(3), node(Edge::MAX)],
+        edges: vec![1, 2, 3, 3],
+    };
+    predicate::validate_graph(&predicate).unwrap();
+
+    // An edge to a non-existent node.
+    let predicate = Predicate {
+        nodes: vec![node(0), node(Edge::MAX)],
+        edges: vec![2],
+    };
+    assert_eq!(
+        predicate::validate_graph(&predicate).unwrap_err(),
+        InvalidGraph::InvalidEdge { node: 0, edge: 2 },
+    );
+
+    // Edges that are out of bounds.
+    let predicate = Predicate {
+        nodes: vec![node(1), node(Edge::MAX)],
+        edges: vec![],
+    };
+    assert_eq!(
+        predicate::validate_graph(&predicate).unwrap_err(),
+        InvalidGraph::InvalidNodeEdges(0),
+    );
+
+    // A cycle through `1 -> 2 -> 3 -> 1`, reachable from `0`.
+    let predicate = Predicate {
+        nodes: vec![node(0), node(1), node(2), node(3)],
+        edges: vec![1, 2, 3, 1],
+    };
+    assert_eq!(
+        predicate::validate_graph(&predicate).unwrap_err(),
+        InvalidGraph::Cycle(vec![1, 2, 3]),
+    );
+
+    // Cycles are rejected when validating a contract.
+    assert!(matches!(
+        predicate::check_contract(&[predicate]).unwrap_err(),
+        predicate::InvalidContract::Predicate(0, InvalidPredicate::Graph(InvalidGraph::Cycle(_))),
+    ));
+}
+
+fn program(ops: &[Op]) -> Program {
+    Program(asm::to_bytes(ops.iter().copied()).collect())
+}
+
+#[test]
+fn constraint_program() {
+    predicate::check_constraint_program(&program(&[PUSH(1), PUSH(2), JMPIF, HLT]).0).unwrap();
+    predicate::check_constraint_program(&[]).unwrap();
+
+    let ops = [PUSH(1), PUSH(2), PUSH(3), PUSH(4), PUSH(1), PUSH(0), KRNG];
+    assert!(matches!(
+        predicate::check_constraint_program(&program(&ops).0).unwrap_err(),
+        InvalidConstraintProgram::InvalidOp(6, op) if op == KRNG,
+    ));
+    assert!(matches!(
+        predicate::check_constraint_program(&program(&[POP, PKREX]).0).unwrap_err(),
+        InvalidConstraintProgram::InvalidOp(1, _),
+    ));
+    assert!(matches!(
+        predicate::check_constraint_program(&program(&[PUSH(2), COM, COME]).0).unwrap_err(),
+        InvalidConstraintProgram::InvalidOp(1, _),
+    ));
+    assert!(matches!(
+        predicate::check_constraint_program(&[0xFF]).unwrap_err(),
+        InvalidConstraintProgram::Decode(0, _),
+    ));
+}
+
+#[test]
+fn constraint_nodes() {
+    let read = Arc::new(program(&[PUSH(1), PUSH(1), PUSH(0), KRNG]));
+    let constraint = Arc::new(program(&[PUSH(1)]));
+    let read_ca = essential_hash::content_addr(&*read);
+    let constraint_ca = essential_hash::content_addr(&*constraint);
+    let programs: HashMap<_, _> = [(read_ca.clone(), read), (constraint_ca.clone(), constraint)]
+        .into_iter()
+        .collect();
+    let node = |edge_start, program_address| Node {
+        edge_start,
+        program_address,
+        max_gas: None,
+    };
+
+    // A state read parent with a constraint leaf is valid.
+    let predicate = Predicate {
+        nodes: vec![node(0, read_ca.clone()), node(Edge::MAX, constraint_ca)],
+        edges: vec![1],
+    };
+    predicate::check_constraint_nodes(&predicate, &programs).unwrap();
+
+    // A state read leaf is not.
+    let predicate = Predicate {
+        nodes: vec![node(Edge::MAX, read_ca.clone())],
+        edges: vec![],
+    };
+    assert!(matches!(
+        predicate::check_constraint_nodes(&predicate, &programs).unwrap_err(),
+        InvalidPredicate::ConstraintNode(0, InvalidConstraintProgram::InvalidOp(3, _)),
+    ));
+
+    // Nor is a state read leaf with an empty range of edges.
+    let predicate = Predicate {
+        nodes: vec![node(0, read_ca.clone()), node(1, read_ca)],
+        edges: vec![1],
+    };
+    assert_eq!(predicate.node_edges(1), Some(&[][..]));
+    assert!(matches!(
+        predicate::check_constraint_nodes(&predicate, &programs).unwrap_err(),
+        InvalidPredicate::ConstraintNode(1, InvalidConstraintProgram::InvalidOp(3, _)),
+    ));
+}
+
+#[test]
+fn built_predicate() {
+    let read = Arc::new(program(&[PUSH(1), PUSH(1), PUSH(0), KRNG]));
+    let constraint = Arc::new(program(&[PUSH(1)]));
+    let read_ca = essential_hash::content_addr(&*read);
+    let constraint_ca = essential_hash::content_addr(&*constraint);
+    let programs: HashMap<_, _> = [(read_ca.clone(), read), (constraint_ca.clone(), constraint)]
+        .into_iter()
+        .collect();
+
+    // A leaf before a parent is laid out with an empty range of edges.
+    let mut builder = Predicate::build();
+    let a = builder.node(read_ca.clone());
+    let leaf = builder.node(constraint_ca.clone());
+    let b = builder.node(read_ca.clone());
+    let c = builder.node(constraint_ca.clone());
+    builder.edge(a, leaf).edge(a, b).edge(b, c);
+    let predicate = builder.finish().unwrap();
+    predicate::check(&predicate).unwrap();
+    predicate::check_constraint_nodes(&predicate, &programs).unwrap();
+
+    // Such a leaf is still required to be a constraint program.
+    let mut builder = Predicate::build();
+    let a = builder.node(read_ca.clone());
+    let leaf = builder.node(read_ca.clone());
+    let b = builder.node(read_ca);
+    let c = builder.node(constraint_ca);
+    builder.edge(a, leaf).edge(a, b).edge(b, c);
+    let predicate = builder.finish().unwrap();
+    assert!(matches!(
+        predicate::check_constraint_nodes(&predicate, &programs).unwrap_err(),
+        InvalidPredicate::ConstraintNode(1, InvalidConstraintProgram::InvalidOp(3, _)),
+    ));
+}
+
+#[test]
+fn arbitrary_predicates() {
+    use arbitrary::{Arbitrary, Unstructured};
+    use rand::{Rng, SeedableRng};
+    let mut rng = rand::rngs::SmallRng::seed_from_u64(0);
+    let mut bytes = vec![0u8; 4096];
+    for _ in 0..100 {
+        rng.fill(&mut bytes[..]);
+        let predicate = Predicate::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+        predicate::check(&predicate).unwrap();
+    }
+}
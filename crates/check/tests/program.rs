@@ -0,0 +1,57 @@
+use essential_check::{program::ProgramRegistry, solution::GetProgram};
+use essential_hash::content_addr;
+use essential_types::{contract::Contract, predicate::Predicate, ContentAddress, Program};
+use std::sync::Arc;
+
+#[test]
+fn intern_shares_identical_programs() {
+    let mut registry = ProgramRegistry::new();
+    let (a_ca, a) = registry.intern(Program(vec![1, 2, 3]));
+    let (b_ca, b) = registry.intern(Program(vec![4, 5]));
+    let (a2_ca, a2) = registry.intern(Program(vec![1, 2, 3]));
+
+    assert_eq!(a_ca, content_addr(&*a));
+    assert_eq!(a_ca, a2_ca);
+    assert_ne!(a_ca, b_ca);
+    assert!(Arc::ptr_eq(&a, &a2));
+    assert!(Arc::ptr_eq(&b, &registry.get_program(&b_ca)));
+    assert_eq!(registry.len(), 2);
+    assert!(registry.get(&ContentAddress([0; 32])).is_none());
+}
+
+#[test]
+fn prune_removes_unused_programs() {
+    let mut registry: ProgramRegistry = [Program(vec![1]), Program(vec![2]), Program(vec![1])]
+        .into_iter()
+        .collect();
+    assert_eq!(registry.len(), 2);
+
+    let (ca, handle) = registry.intern(Program(vec![1]));
+    assert_eq!(registry.prune(), 1);
+    assert_eq!(registry.len(), 1);
+    assert!(registry.contains(&ca));
+
+    drop(handle);
+    assert_eq!(registry.prune(), 1);
+    assert!(registry.is_empty());
+}
+
+#[test]
+fn dedup_contract_programs() {
+    let programs = [Program(vec![1]), Program(vec![2])];
+    let cas: Vec<_> = programs.iter().map(content_addr).collect();
+    let predicate = |ixs: &[usize]| {
+        let mut builder = Predicate::build();
+        for &ix in ixs {
+            builder.node(cas[ix].clone());
+        }
+        builder.finish().unwrap()
+    };
+    let contract = Contract::without_salt(vec![predicate(&[0, 1, 0]), predicate(&[1])]);
+    assert_eq!(contract.dedup_programs(), [&cas[0], &cas[1]]);
+
+    let registry: ProgramRegistry = programs.into_iter().collect();
+    for ca in contract.dedup_programs() {
+        assert_eq!(content_addr(&*registry.get_program(ca)), *ca);
+    }
+}
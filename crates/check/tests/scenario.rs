@@ -0,0 +1,179 @@
+use essential_check::{
+    scenario::{self, ScenarioConfig, SimRng, SimState, StepOutcome},
+    solution::CheckPredicateConfig,
+    vm::asm,
+};
+use essential_hash::content_addr;
+use essential_types::{
+    contract::Contract,
+    predicate::{Edge, Node, Predicate, Program},
+    solution::{Solution, SolutionSet},
+    ContentAddress, PredicateAddress, Value, Word,
+};
+use std::{collections::HashMap, sync::Arc};
+
+type Programs = Arc<HashMap<ContentAddress, Arc<Program>>>;
+
+// A contract with a single predicate that sets the value at key `[0]` to the
+// word provided in predicate data, constrained to be greater than zero.
+fn setter_contract() -> (PredicateAddress, Arc<Predicate>, Programs) {
+    use essential_vm::asm::short::*;
+    let set_value = Program(
+        asm::to_bytes([
+            // Num mutations, key len, key, value len.
+            PUSH(1),
+            PUSH(1),
+            PUSH(0),
+            PUSH(1),
+            // The value from predicate data.
+            PUSH(0),
+            PUSH(0),
+            PUSH(1),
+            DATA,
+            // Store the encoded mutations in memory.
+            PUSH(5),
+            PUSH(5),
+            ALOC,
+            STOR,
+            PUSH(2),
+        ])
+        .collect(),
+    );
+    let constraint =
+        Program(asm::to_bytes([PUSH(0), PUSH(0), PUSH(1), DATA, PUSH(0), GT]).collect());
+    let set_value_ca = content_addr(&set_value);
+    let constraint_ca = content_addr(&constraint);
+    let node = |program_address| Node {
+        program_address,
+        edge_start: Edge::MAX,
+        max_gas: None,
+    };
+    let predicate = Predicate {
+        nodes: vec![node(set_value_ca.clone()), node(constraint_ca.clone())],
+        edges: vec![],
+    };
+    let contract = Contract::without_salt(vec![predicate]);
+    let pred_addr = PredicateAddress {
+        contract: content_addr(&contract),
+        predicate: content_addr(&contract.predicates[0]),
+    };
+    let programs = vec![
+        (set_value_ca, Arc::new(set_value)),
+        (constraint_ca, Arc::new(constraint)),
+    ]
+    .into_iter()
+    .collect();
+    let predicate = Arc::new(contract.predicates[0].clone());
+    (pred_addr, predicate, Arc::new(programs))
+}
+
+fn run(seed: u64, steps: usize) -> scenario::ScenarioReport {
+    let (pred_addr, predicate, programs) = setter_contract();
+    let get_predicate = |_: &PredicateAddress| predicate.clone();
+    let strategy = |_step: usize, _state: &SimState, rng: &mut SimRng| {
+        Some(SolutionSet {
+            solutions: vec![Solution {
+                predicate_to_solve: pred_addr.clone(),
+                predicate_data: vec![[rng.gen_range(-5..10)].into()],
+                state_mutations: vec![],
+            }],
+        })
+    };
+    let config = ScenarioConfig {
+        seed,
+        steps,
+        check: Arc::new(CheckPredicateConfig::default()),
+    };
+    scenario::run(
+        SimState::default(),
+        strategy,
+        get_predicate,
+        programs,
+        &config,
+    )
+}
+
+#[test]
+fn scenario_is_deterministic() {
+    let a = run(42, 32);
+    let b = run(42, 32);
+    assert_eq!(a.steps.len(), 32);
+    assert_eq!(a.final_state, b.final_state);
+    assert_eq!(a.total_gas(), b.total_gas());
+    let outcomes = |r: &scenario::ScenarioReport| {
+        r.steps
+            .iter()
+            .map(|s| (s.outcome.is_accepted(), s.outcome.gas()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(outcomes(&a), outcomes(&b));
+}
+
+#[test]
+fn scenario_tracks_state_evolution() {
+    let report = run(7, 64);
+    assert!(report.accepted() > 0);
+    assert!(report.accepted() < report.steps.len());
+    assert!(report.total_gas() > 0);
+
+    // Replay the applied mutations and check they chain and match the final state.
+    let mut last: Option<Word> = None;
+    for step in &report.steps {
+        match &step.outcome {
+            StepOutcome::Accepted { gas, mutations } => {
+                assert!(*gas > 0);
+                assert_eq!(mutations.len(), 1);
+                let m = &mutations[0];
+                assert_eq!(m.key, vec![0]);
+                assert_eq!(m.prev, last.map(|w| vec![w]).unwrap_or_default());
+                assert!(m.value[0] > 0);
+                last = Some(m.value[0]);
+            }
+            StepOutcome::Rejected(_) => (),
+            StepOutcome::Invalid(e) => panic!("unexpected invalid set: {e}"),
+        }
+    }
+    let (pred_addr, _, _) = setter_contract();
+    let value = report.final_state.get(&pred_addr.contract, &[0].into());
+    assert_eq!(value, last.map(|w| Value::from([w])).as_ref());
+}
+
+#[test]
+fn scenario_ends_when_strategy_returns_none() {
+    let (_, predicate, programs) = setter_contract();
+    let get_predicate = |_: &PredicateAddress| predicate.clone();
+    let strategy = |step: usize, _: &SimState, _: &mut SimRng| {
+        (step < 3).then(|| SolutionSet { solutions: vec![] })
+    };
+    let config = ScenarioConfig {
+        seed: 0,
+        steps: 10,
+        check: Default::default(),
+    };
+    let report = scenario::run(
+        SimState::default(),
+        strategy,
+        get_predicate,
+        programs,
+        &config,
+    );
+    assert_eq!(report.steps.len(), 3);
+    assert!(report
+        .steps
+        .iter()
+        .all(|s| matches!(s.outcome, StepOutcome::Invalid(_))));
+}
+
+#[test]
+fn sim_rng_ranges() {
+    let mut rng = SimRng::new(1);
+    for _ in 0..1000 {
+        let w = rng.gen_range(-3..3);
+        assert!((-3..3).contains(&w));
+    }
+    assert_eq!(SimRng::new(9).next_u64(), SimRng::new(9).next_u64());
+    assert_ne!(SimRng::new(9).next_u64(), SimRng::new(10).next_u64());
+    assert!(rng.choose::<u8>(&[]).is_none());
+    assert!(!rng.gen_bool(0.0));
+    assert!(rng.gen_bool(1.0));
+}
@@ -7,9 +7,12 @@ use essential_types::{
     contract::Contract,
     predicate::{Edge, Node, Predicate, Program},
     solution::{encode::encode_mutations, Mutation, Solution, SolutionSet},
-    ContentAddress, PredicateAddress, Word,
+    ContentAddress, Key, PredicateAddress, Value, Word,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
 };
-use std::{collections::HashMap, sync::Arc};
 use util::{empty_solution_set, State};
 
 pub mod util;
@@ -31,8 +34,8 @@ fn test_solution() -> Solution {
 
 fn test_mutation(salt: usize) -> Mutation {
     Mutation {
-        key: vec![salt as Word; 4],
-        value: vec![42],
+        key: [salt as Word; 4].into(),
+        value: [42].into(),
     }
 }
 
@@ -64,7 +67,7 @@ fn too_many_predicate_data() {
     let set = SolutionSet {
         solutions: vec![Solution {
             predicate_to_solve: test_predicate_addr(),
-            predicate_data: vec![vec![0]; (solution::MAX_PREDICATE_DATA + 1) as usize],
+            predicate_data: vec![[0].into(); (solution::MAX_PREDICATE_DATA + 1) as usize],
             state_mutations: vec![],
         }],
     };
@@ -93,6 +96,71 @@ fn too_many_state_mutations() {
     ));
 }
 
+#[test]
+fn custom_limits() {
+    let set = SolutionSet {
+        solutions: vec![Solution {
+            predicate_to_solve: test_predicate_addr(),
+            predicate_data: vec![],
+            state_mutations: (0..3).map(test_mutation).collect(),
+        }],
+    };
+    solution::check_set(&set).unwrap();
+
+    let limits = solution::Limits {
+        max_state_mutations: 2,
+        ..Default::default()
+    };
+    assert!(matches!(
+        solution::check_set_with_limits(&set, &limits).unwrap_err(),
+        solution::InvalidSolutionSet::StateMutations(solution::InvalidSetStateMutations::TooMany(
+            3
+        ))
+    ));
+
+    let limits = solution::Limits {
+        max_key_size: 0,
+        ..Default::default()
+    };
+    assert!(matches!(
+        solution::check_set_with_limits(&set, &limits).unwrap_err(),
+        solution::InvalidSolutionSet::Solution(solution::InvalidSolution::StateMutationEntry(
+            solution::KvError::KeyTooLarge(_)
+        ))
+    ));
+
+    let limits = solution::Limits {
+        max_solutions: solution::MAX_SOLUTIONS + 1,
+        ..Default::default()
+    };
+    let set = SolutionSet {
+        solutions: (0..solution::MAX_SOLUTIONS + 1)
+            .map(|_| test_solution())
+            .collect(),
+    };
+    solution::check_set_with_limits(&set, &limits).unwrap();
+}
+
+#[test]
+fn build_checked_set() {
+    let set = SolutionSet::build()
+        .solution(test_predicate_addr())
+        .mutation([0; 4], [42])
+        .try_finish(solution::check_set)
+        .unwrap();
+    assert_eq!(set.solutions[0].state_mutations, vec![test_mutation(0)]);
+
+    let builder = (0..=solution::MAX_STATE_MUTATIONS).map(test_mutation).fold(
+        SolutionSet::build().solution(test_predicate_addr()),
+        |b, m| b.mutation(m.key, m.value),
+    );
+    assert!(matches!(
+        builder.try_finish(solution::check_set).unwrap_err(),
+        solution::InvalidSolutionSet::StateMutations(solution::InvalidSetStateMutations::TooMany(n))
+            if n == solution::MAX_STATE_MUTATIONS + 1
+    ));
+}
+
 #[test]
 fn multiple_mutations_for_slot() {
     let set = SolutionSet {
@@ -101,8 +169,8 @@ fn multiple_mutations_for_slot() {
             predicate_data: vec![],
             state_mutations: vec![
                 Mutation {
-                    key: vec![0; 4],
-                    value: vec![42],
+                    key: [0; 4].into(),
+                    value: [42].into(),
                 };
                 2
             ],
@@ -154,6 +222,7 @@ fn predicate_graph_stack_passing() {
     let node = |program_address, edge_start| Node {
         program_address,
         edge_start,
+        max_gas: None,
     };
     let nodes = vec![
         node(a_ca.clone(), 0),
@@ -295,6 +364,7 @@ fn predicate_graph_memory_passing() {
     let node = |program_address, edge_start| Node {
         program_address,
         edge_start,
+        max_gas: None,
     };
     let nodes = vec![
         node(a_ca.clone(), 0),
@@ -385,7 +455,7 @@ fn predicate_graph_state_read() {
     use essential_vm::asm::short::*;
     let _ = tracing_subscriber::fmt::try_init();
 
-    let key = vec![9, 9, 9, 9];
+    let key = Key::from([9, 9, 9, 9]);
 
     // Push the key and prepare the stack for the key read.
     let a = Program(
@@ -430,6 +500,7 @@ fn predicate_graph_state_read() {
     let node = |program_address, edge_start| Node {
         program_address,
         edge_start,
+        max_gas: None,
     };
     let nodes = vec![
         node(a_ca.clone(), 0),
@@ -448,7 +519,7 @@ fn predicate_graph_state_read() {
     // Create the state. The initial state should be 6.
     let mut pre_state = State::EMPTY;
     pre_state.deploy_namespace(pred_addr.contract.clone());
-    pre_state.set(pred_addr.contract.clone(), &key, vec![6]);
+    pre_state.set(pred_addr.contract.clone(), &key, [6].into());
 
     // Create a solution that "solves" our predicate.
     let set = SolutionSet {
@@ -459,7 +530,7 @@ fn predicate_graph_state_read() {
                 // Set the post state to 7.
                 Mutation {
                     key,
-                    value: vec![7],
+                    value: [7].into(),
                 },
             ],
         }],
@@ -526,6 +597,7 @@ fn solution_outputs() {
     let node = |program_address, edge_start| Node {
         program_address,
         edge_start,
+        max_gas: None,
     };
     let nodes = vec![
         node(bool_only_ca.clone(), Edge::MAX),
@@ -596,136 +668,89 @@ fn solution_outputs() {
 
     assert!(outputs.gas > 0);
     assert_eq!(outputs.data.len(), 2);
+    assert_eq!(outputs.data[0].solution_index, 0);
     assert_eq!(
-        outputs.data[0],
-        DataFromSolution {
-            solution_index: 0,
-            data: vec![DataOutput::Memory(vec![45, 46].try_into().unwrap())]
-        }
+        outputs.data[0].data,
+        vec![DataOutput::Memory(vec![45, 46].try_into().unwrap())]
     );
+    assert_eq!(outputs.data[1].solution_index, 1);
     assert_eq!(
-        outputs.data[1],
-        DataFromSolution {
-            solution_index: 1,
-            data: vec![
-                DataOutput::Memory(vec![42].try_into().unwrap()),
-                DataOutput::Memory(vec![43, 44].try_into().unwrap()),
-            ]
-        }
+        outputs.data[1].data,
+        vec![
+            DataOutput::Memory(vec![42].try_into().unwrap()),
+            DataOutput::Memory(vec![43, 44].try_into().unwrap()),
+        ]
+    );
+
+    // Gas is attributed to each solution and to each node that was run.
+    let solution_gas: u64 = outputs.data.iter().map(|d| d.gas).sum();
+    assert_eq!(solution_gas, outputs.gas);
+    for data in &outputs.data {
+        assert!(data.gas > 0);
+        assert_eq!(data.node_gas.values().sum::<u64>(), data.gas);
+    }
+    assert_eq!(
+        outputs.data[0].node_gas.keys().copied().collect::<Vec<_>>(),
+        vec![0]
+    );
+    assert_eq!(
+        outputs.data[1].node_gas.keys().copied().collect::<Vec<_>>(),
+        vec![0, 1, 2]
     );
 }
 
 #[test]
-fn solution_compute_mutations() {
+fn this_program_address() {
+    use essential_types::convert::word_4_from_u8_32;
     use essential_vm::asm::short::*;
-    let _ = tracing_subscriber::fmt::try_init();
-    let mutations_0 = vec![Mutation {
-        key: vec![1, 2, 3, 4],
-        value: vec![42],
-    }];
-    let mutations_1 = vec![
-        Mutation {
-            key: vec![5, 6, 7, 8],
-            value: vec![43, 44],
-        },
-        Mutation {
-            key: vec![9],
-            value: vec![45, 46],
-        },
-    ];
-    let mutations_2 = vec![
-        Mutation {
-            key: vec![10, 11],
-            value: vec![47, 48, 49],
-        },
-        Mutation {
-            key: vec![12],
-            value: vec![50, 51],
-        },
-    ];
-    let encoded_mutations_0 = encode_mutations(&mutations_0).collect::<Vec<_>>();
-    let encoded_mutations_1 = encode_mutations(&mutations_1).collect::<Vec<_>>();
-    let encoded_mutations_2 = encode_mutations(&mutations_2).collect::<Vec<_>>();
-
-    let make_prog = |encoded_mutations: Vec<Word>| {
-        let encoded_mutations_len = encoded_mutations.len();
-        let mut p = encoded_mutations.into_iter().map(PUSH).collect::<Vec<_>>();
-        p.push(PUSH(encoded_mutations_len as Word));
-        p.push(PUSH(encoded_mutations_len as Word));
-        p.push(ALOC);
-        p.push(STOR);
-        p.push(PUSH(2));
-        Program(asm::to_bytes(p).collect())
+    // Two library programs that output their own address. The leading
+    // `PUSH(n), POP` only serves to give each program a unique address.
+    let program = |n| {
+        Program(
+            asm::to_bytes([PUSH(n), POP, THISP, PUSH(4), PUSH(4), ALOC, STOR, PUSH(2)]).collect(),
+        )
     };
+    let program_0 = program(0);
+    let program_1 = program(1);
+    let program_0_ca = content_addr(&program_0);
+    let program_1_ca = content_addr(&program_1);
+    assert_ne!(program_0_ca, program_1_ca);
 
-    let pred_0_prg_0 = make_prog(encoded_mutations_0.clone());
-    let pred_0_prg_1 = make_prog(encoded_mutations_1.clone());
-    let pred_1_prg_0 = make_prog(encoded_mutations_2.clone());
-
-    let pred_0_prg_0_ca = content_addr(&pred_0_prg_0);
-    let pred_0_prg_1_ca = content_addr(&pred_0_prg_1);
-    let pred_1_prg_0_ca = content_addr(&pred_1_prg_0);
-
-    let node = |program_address, edge_start| Node {
+    let node = |program_address| Node {
         program_address,
-        edge_start,
+        edge_start: Edge::MAX,
+        max_gas: None,
     };
-    let nodes = vec![
-        node(pred_0_prg_0_ca.clone(), Edge::MAX),
-        node(pred_0_prg_1_ca.clone(), Edge::MAX),
-    ];
-    let edges = vec![];
-    let predicate_0 = Predicate { nodes, edges };
-    let contract_0 = Contract::without_salt(vec![predicate_0]);
-    let pred_addr_0 = PredicateAddress {
-        contract: content_addr(&contract_0),
-        predicate: content_addr(&contract_0.predicates[0]),
+    let predicate = Predicate {
+        nodes: vec![node(program_0_ca.clone()), node(program_1_ca.clone())],
+        edges: vec![],
     };
-
-    let nodes = vec![node(pred_1_prg_0_ca.clone(), Edge::MAX)];
-    let edges = vec![];
-    let predicate_1 = Predicate { nodes, edges };
-    let contract_1 = Contract::without_salt(vec![predicate_1]);
-    let pred_addr_1 = PredicateAddress {
-        contract: content_addr(&contract_1),
-        predicate: content_addr(&contract_1.predicates[0]),
+    let contract = Contract::without_salt(vec![predicate]);
+    let pred_addr = PredicateAddress {
+        contract: content_addr(&contract),
+        predicate: content_addr(&contract.predicates[0]),
     };
-
-    // Create a solution that "solves" our predicate.
     let set = SolutionSet {
-        solutions: vec![
-            Solution {
-                predicate_to_solve: pred_addr_1.clone(),
-                predicate_data: Default::default(),
-                state_mutations: vec![],
-            },
-            Solution {
-                predicate_to_solve: pred_addr_0.clone(),
-                predicate_data: Default::default(),
-                state_mutations: vec![],
-            },
-        ],
+        solutions: vec![Solution {
+            predicate_to_solve: pred_addr,
+            predicate_data: Default::default(),
+            state_mutations: vec![],
+        }],
     };
 
-    let predicate_0 = Arc::new(contract_0.predicates[0].clone());
-    let predicate_1 = Arc::new(contract_1.predicates[0].clone());
-    let mut map = HashMap::new();
-    map.insert(pred_addr_0.contract.clone(), predicate_0);
-    map.insert(pred_addr_1.contract.clone(), predicate_1);
-
-    let get_predicate = |addr: &PredicateAddress| map.get(&addr.contract).unwrap().clone();
+    let predicate = Arc::new(contract.predicates[0].clone());
+    let get_predicate = |_: &PredicateAddress| predicate.clone();
     let programs: HashMap<ContentAddress, Arc<Program>> = vec![
-        (pred_0_prg_0_ca, Arc::new(pred_0_prg_0)),
-        (pred_0_prg_1_ca, Arc::new(pred_0_prg_1)),
-        (pred_1_prg_0_ca, Arc::new(pred_1_prg_0)),
+        (program_0_ca.clone(), Arc::new(program_0)),
+        (program_1_ca.clone(), Arc::new(program_1)),
     ]
     .into_iter()
     .collect();
     let get_program: Arc<HashMap<_, _>> = Arc::new(programs);
 
-    let set = solution::check_and_compute_solution_set(
+    let outputs = solution::check_set_predicates(
         &State::EMPTY,
-        set,
+        Arc::new(set),
         get_predicate,
         get_program,
         Arc::new(solution::CheckPredicateConfig::default()),
@@ -734,152 +759,1788 @@ fn solution_compute_mutations() {
     )
     .unwrap();
 
-    let expected = [mutations_0, mutations_1].concat();
-    assert_eq!(set.1.solutions[0].state_mutations, mutations_2);
-    assert_eq!(set.1.solutions[1].state_mutations, expected);
+    let expected = |ca: ContentAddress| {
+        DataOutput::Memory(word_4_from_u8_32(ca.0).to_vec().try_into().unwrap())
+    };
+    // Both programs have the same number of ops, so cost the same gas.
+    let node_gas = outputs.data[0].node_gas[&0];
+    assert_eq!(outputs.data[0].node_gas[&1], node_gas);
+    assert_eq!(
+        outputs.data,
+        vec![DataFromSolution {
+            solution_index: 0,
+            data: vec![expected(program_0_ca), expected(program_1_ca)],
+            gas: node_gas * 2,
+            node_gas: [(0, node_gas), (1, node_gas)].into_iter().collect(),
+        }]
+    );
 }
 
 #[test]
-fn solution_compute_mutations_two_pass() {
+fn node_max_gas() {
     use essential_vm::asm::short::*;
-    let _ = tracing_subscriber::fmt::try_init();
-    let mutations_0 = vec![Mutation {
-        key: vec![1, 2, 3, 4],
-        value: vec![42],
-    }];
-    let mutations_1 = vec![
-        Mutation {
-            key: vec![5, 6, 7, 8],
-            value: vec![43, 44],
-        },
-        Mutation {
-            key: vec![9],
-            value: vec![45, 46],
-        },
-    ];
-    let mutations_2 = vec![
-        Mutation {
-            key: vec![10, 11],
-            value: vec![47, 48, 49],
-        },
-        Mutation {
-            key: vec![12],
-            value: vec![50, 51],
-        },
-    ];
-    let encoded_mutations_0 = encode_mutations(&mutations_0).collect::<Vec<_>>();
-    let encoded_mutations_1 = encode_mutations(&mutations_1).collect::<Vec<_>>();
-    let encoded_mutations_2 = encode_mutations(&mutations_2).collect::<Vec<_>>();
+    // Costs 5 gas, as each op costs 1.
+    let program = Program(asm::to_bytes([PUSH(1), PUSH(1), ADD, PUSH(2), EQ]).collect());
+    let program_ca = content_addr(&program);
+    let get_program: Arc<HashMap<_, _>> = Arc::new(
+        vec![(program_ca.clone(), Arc::new(program))]
+            .into_iter()
+            .collect(),
+    );
 
-    let make_prog = |encoded_mutations: Vec<Word>| {
-        let encoded_mutations_len = encoded_mutations.len();
-        let mut p = encoded_mutations.into_iter().map(PUSH).collect::<Vec<_>>();
-        p.push(PUSH(encoded_mutations_len as Word));
-        p.push(PUSH(encoded_mutations_len as Word));
-        p.push(ALOC);
-        p.push(STOR);
-        p.push(PUSH(2));
-        Program(asm::to_bytes(p).collect())
+    let check = |max_gas| {
+        let predicate = Predicate {
+            nodes: vec![Node {
+                program_address: program_ca.clone(),
+                edge_start: Edge::MAX,
+                max_gas,
+            }],
+            edges: vec![],
+        };
+        let contract = Contract::without_salt(vec![predicate]);
+        let pred_addr = PredicateAddress {
+            contract: content_addr(&contract),
+            predicate: content_addr(&contract.predicates[0]),
+        };
+        let set = SolutionSet {
+            solutions: vec![Solution {
+                predicate_to_solve: pred_addr,
+                predicate_data: Default::default(),
+                state_mutations: vec![],
+            }],
+        };
+        let predicate = Arc::new(contract.predicates[0].clone());
+        solution::check_set_predicates(
+            &State::EMPTY,
+            Arc::new(set),
+            |_: &PredicateAddress| predicate.clone(),
+            get_program.clone(),
+            Arc::new(solution::CheckPredicateConfig::default()),
+            Default::default(),
+            &mut Default::default(),
+        )
     };
 
-    let post_read = Program(
-        asm::to_bytes([
-            // Read the key range into memory.
-            PUSH(9), // Key
-            PUSH(1), // Key Len
-            PUSH(1), // Num to read
-            PUSH(4),
-            ALOC,
-            PKRNG,
-            PUSH(2),
-            PUSH(2),
-            LODR,
-            PUSH(45),
-            PUSH(46),
-            PUSH(2),
-            EQRA,
-        ])
+    assert_eq!(check(None).unwrap().gas, 5);
+    assert_eq!(check(Some(5)).unwrap().gas, 5);
+    assert!(matches!(
+        check(Some(4)).unwrap_err(),
+        solution::PredicatesError::Failed(_)
+    ));
+}
+
+#[test]
+fn node_reports() {
+    use essential_vm::asm::short::*;
+    // `a` passes `[1]` to `b`, which is satisfied. `c` is unsatisfied.
+    let a = Program(asm::to_bytes([PUSH(1), HLT]).collect());
+    let b = Program(asm::to_bytes([PUSH(1), EQ]).collect());
+    let c = Program(asm::to_bytes([PUSH(0)]).collect());
+    let a_ca = content_addr(&a);
+    let b_ca = content_addr(&b);
+    let c_ca = content_addr(&c);
+    let get_program: Arc<HashMap<_, _>> = Arc::new(
+        vec![
+            (a_ca.clone(), Arc::new(a)),
+            (b_ca.clone(), Arc::new(b)),
+            (c_ca.clone(), Arc::new(c)),
+        ]
+        .into_iter()
         .collect(),
     );
 
-    let pred_0_prg_0 = make_prog(encoded_mutations_0.clone());
-    let pred_0_prg_1 = make_prog(encoded_mutations_1.clone());
-    let pred_1_prg_0 = make_prog(encoded_mutations_2.clone());
-
-    let pred_0_prg_0_ca = content_addr(&pred_0_prg_0);
-    let pred_0_prg_1_ca = content_addr(&pred_0_prg_1);
-    let pred_1_prg_0_ca = content_addr(&pred_1_prg_0);
-    let post_read_ca = content_addr(&post_read);
-
     let node = |program_address, edge_start| Node {
         program_address,
         edge_start,
+        max_gas: None,
     };
-    let nodes = vec![
-        node(pred_0_prg_0_ca.clone(), Edge::MAX),
-        node(pred_0_prg_1_ca.clone(), Edge::MAX),
-        node(post_read_ca.clone(), Edge::MAX),
-    ];
-    let edges = vec![];
-    let predicate_0 = Predicate { nodes, edges };
-    let contract_0 = Contract::without_salt(vec![predicate_0]);
-    let pred_addr_0 = PredicateAddress {
-        contract: content_addr(&contract_0),
-        predicate: content_addr(&contract_0.predicates[0]),
+    let predicate = Predicate {
+        nodes: vec![node(a_ca, 0), node(b_ca, Edge::MAX), node(c_ca, Edge::MAX)],
+        edges: vec![1],
     };
-
-    let nodes = vec![node(pred_1_prg_0_ca.clone(), Edge::MAX)];
-    let edges = vec![];
-    let predicate_1 = Predicate { nodes, edges };
-    let contract_1 = Contract::without_salt(vec![predicate_1]);
-    let pred_addr_1 = PredicateAddress {
-        contract: content_addr(&contract_1),
-        predicate: content_addr(&contract_1.predicates[0]),
+    let contract = Contract::without_salt(vec![predicate]);
+    let pred_addr = PredicateAddress {
+        contract: content_addr(&contract),
+        predicate: content_addr(&contract.predicates[0]),
     };
-
-    // Create a solution that "solves" our predicate.
     let set = SolutionSet {
-        solutions: vec![
-            Solution {
-                predicate_to_solve: pred_addr_1.clone(),
-                predicate_data: Default::default(),
-                state_mutations: vec![],
-            },
-            Solution {
-                predicate_to_solve: pred_addr_0.clone(),
-                predicate_data: Default::default(),
-                state_mutations: vec![],
-            },
-        ],
+        solutions: vec![Solution {
+            predicate_to_solve: pred_addr,
+            predicate_data: Default::default(),
+            state_mutations: vec![],
+        }],
     };
 
-    let predicate_0 = Arc::new(contract_0.predicates[0].clone());
-    let predicate_1 = Arc::new(contract_1.predicates[0].clone());
-    let mut map = HashMap::new();
-    map.insert(pred_addr_0.contract.clone(), predicate_0);
-    map.insert(pred_addr_1.contract.clone(), predicate_1);
-
-    let get_predicate = |addr: &PredicateAddress| map.get(&addr.contract).unwrap().clone();
-    let programs: HashMap<ContentAddress, Arc<Program>> = vec![
-        (pred_0_prg_0_ca, Arc::new(pred_0_prg_0)),
-        (pred_0_prg_1_ca, Arc::new(pred_0_prg_1)),
-        (pred_1_prg_0_ca, Arc::new(pred_1_prg_0)),
-        (post_read_ca, Arc::new(post_read)),
-    ]
-    .into_iter()
-    .collect();
-    let get_program: Arc<HashMap<_, _>> = Arc::new(programs);
-
-    let set = solution::check_and_compute_solution_set_two_pass(
+    let (res, reports) = solution::check_predicate_with_report(
         &State::EMPTY,
-        set,
-        get_predicate,
+        Arc::new(set),
+        Arc::new(contract.predicates[0].clone()),
         get_program,
-        Arc::new(solution::CheckPredicateConfig::default()),
-    )
-    .unwrap();
+        0,
+        &solution::CheckPredicateConfig::default(),
+        solution::Ctx {
+            run_mode: solution::RunMode::Outputs,
+            cache: &mut Default::default(),
+        },
+    );
 
-    let expected = [mutations_0, mutations_1].concat();
-    assert_eq!(set.1.solutions[0].state_mutations, mutations_2);
-    assert_eq!(set.1.solutions[1].state_mutations, expected);
+    // The reports explain the failure.
+    assert!(matches!(
+        res.unwrap_err(),
+        solution::PredicateError::ConstraintsUnsatisfied(_)
+    ));
+    assert_eq!(reports.len(), 3);
+    assert_eq!(reports[&0].output, solution::NodeOutputKind::Parent);
+    assert_eq!(
+        reports[&1].output,
+        solution::NodeOutputKind::Constraint { satisfied: true }
+    );
+    assert_eq!(
+        reports[&2].output,
+        solution::NodeOutputKind::Constraint { satisfied: false }
+    );
+    assert!(reports.values().all(|report| report.gas > 0));
+}
+
+#[test]
+fn gas_schedule_and_limit_per_program() {
+    use essential_vm::asm::short::*;
+    // Three stack ops, an ALU op and a predicate op.
+    let program = Program(asm::to_bytes([PUSH(1), PUSH(1), ADD, PUSH(2), EQ]).collect());
+    let program_ca = content_addr(&program);
+    let get_program: Arc<HashMap<_, _>> =
+        Arc::new(HashMap::from([(program_ca.clone(), Arc::new(program))]));
+    let predicate = Predicate {
+        nodes: vec![Node {
+            program_address: program_ca,
+            edge_start: Edge::MAX,
+            max_gas: None,
+        }],
+        edges: vec![],
+    };
+    let contract = Contract::without_salt(vec![predicate]);
+    let set = Arc::new(SolutionSet {
+        solutions: vec![Solution {
+            predicate_to_solve: PredicateAddress {
+                contract: content_addr(&contract),
+                predicate: content_addr(&contract.predicates[0]),
+            },
+            predicate_data: Default::default(),
+            state_mutations: vec![],
+        }],
+    });
+    let predicate = Arc::new(contract.predicates[0].clone());
+
+    let check = |limit_per_program| {
+        let config = solution::CheckPredicateConfig {
+            gas: solution::GasConfig {
+                schedule: essential_vm::GasSchedule {
+                    alu: 10,
+                    ..Default::default()
+                },
+                limit_per_program,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        solution::check_set_predicates(
+            &State::EMPTY,
+            set.clone(),
+            |_: &PredicateAddress| predicate.clone(),
+            get_program.clone(),
+            Arc::new(config),
+            Default::default(),
+            &mut Default::default(),
+        )
+    };
+
+    assert_eq!(check(None).unwrap().gas, 14);
+    assert_eq!(check(Some(14)).unwrap().gas, 14);
+    assert!(matches!(
+        check(Some(13)).unwrap_err(),
+        solution::PredicatesError::Failed(_)
+    ));
+}
+
+#[test]
+fn max_ops_per_program() {
+    use essential_vm::asm::short::*;
+    // Executes 4 + 3 * 3 + 2 = 15 ops.
+    let program = Program(
+        asm::to_bytes([
+            PUSH(0),
+            PUSH(3),
+            PUSH(1),
+            REP,
+            POP,
+            PUSH(1),
+            REPE,
+            PUSH(1),
+            EQ,
+        ])
+        .collect(),
+    );
+    let program_ca = content_addr(&program);
+    let get_program: Arc<HashMap<_, _>> = Arc::new(
+        vec![(program_ca.clone(), Arc::new(program))]
+            .into_iter()
+            .collect(),
+    );
+    let predicate = Predicate {
+        nodes: vec![Node {
+            program_address: program_ca,
+            edge_start: Edge::MAX,
+            max_gas: None,
+        }],
+        edges: vec![],
+    };
+    let contract = Contract::without_salt(vec![predicate]);
+    let pred_addr = PredicateAddress {
+        contract: content_addr(&contract),
+        predicate: content_addr(&contract.predicates[0]),
+    };
+    let set = Arc::new(SolutionSet {
+        solutions: vec![Solution {
+            predicate_to_solve: pred_addr,
+            predicate_data: Default::default(),
+            state_mutations: vec![],
+        }],
+    });
+    let predicate = Arc::new(contract.predicates[0].clone());
+
+    let check = |max_ops_per_program| {
+        let config = solution::CheckPredicateConfig {
+            max_ops_per_program,
+            ..Default::default()
+        };
+        solution::check_set_predicates(
+            &State::EMPTY,
+            set.clone(),
+            |_: &PredicateAddress| predicate.clone(),
+            get_program.clone(),
+            Arc::new(config),
+            Default::default(),
+            &mut Default::default(),
+        )
+    };
+
+    assert_eq!(check(None).unwrap().gas, 15);
+    assert_eq!(check(Some(15)).unwrap().gas, 15);
+    assert!(matches!(
+        check(Some(14)).unwrap_err(),
+        solution::PredicatesError::Failed(_)
+    ));
+}
+
+#[test]
+fn exec_failure_context() {
+    use essential_vm::asm::short::*;
+    // Fails dividing by zero.
+    let program = Program(asm::to_bytes([PUSH(7), PUSH(1), PUSH(0), DIV]).collect());
+    let program_ca = content_addr(&program);
+    let get_program: Arc<HashMap<_, _>> =
+        Arc::new(HashMap::from([(program_ca.clone(), Arc::new(program))]));
+    let predicate = Predicate {
+        nodes: vec![Node {
+            program_address: program_ca.clone(),
+            edge_start: Edge::MAX,
+            max_gas: None,
+        }],
+        edges: vec![],
+    };
+    let contract = Contract::without_salt(vec![predicate]);
+    let set = Arc::new(SolutionSet {
+        solutions: vec![Solution {
+            predicate_to_solve: PredicateAddress {
+                contract: content_addr(&contract),
+                predicate: content_addr(&contract.predicates[0]),
+            },
+            predicate_data: Default::default(),
+            state_mutations: vec![],
+        }],
+    });
+    let predicate = Arc::new(contract.predicates[0].clone());
+
+    let check = |error_trace_len| {
+        let config = solution::CheckPredicateConfig {
+            error_trace_len,
+            ..Default::default()
+        };
+        solution::check_set_predicates(
+            &State::EMPTY,
+            set.clone(),
+            |_: &PredicateAddress| predicate.clone(),
+            get_program.clone(),
+            Arc::new(config),
+            Default::default(),
+            &mut Default::default(),
+        )
+        .unwrap_err()
+        .to_string()
+    };
+
+    // The failing op and program are always reported.
+    let err = check(0);
+    assert!(err.contains(&format!("program: {program_ca}")), "{err}");
+    assert!(err.contains("op:      Alu(Div)"), "{err}");
+    assert!(!err.contains("1: Stack(Push(1))"), "{err}");
+
+    // The trace is bounded by the configured length.
+    let err = check(2);
+    assert!(
+        err.contains("2: Stack(Push(0))\n      3: Alu(Div)"),
+        "{err}"
+    );
+    assert!(!err.contains("1: Stack(Push(1))"), "{err}");
+}
+
+#[test]
+fn run_program() {
+    use essential_vm::asm::short::*;
+    use solution::{Output, ProgramOutput};
+    // The parent outputs `[1, 2]` which the leaf checks sum to `3`.
+    let parent = Arc::new(Program(asm::to_bytes([PUSH(1), PUSH(2), HLT]).collect()));
+    let leaf = Arc::new(Program(asm::to_bytes([ADD, PUSH(3), EQ]).collect()));
+    let node = |program: &Program| Node {
+        program_address: content_addr(program),
+        edge_start: Edge::MAX,
+        max_gas: None,
+    };
+    let solutions = Arc::new(vec![test_solution()]);
+    let config = solution::CheckPredicateConfig::default();
+
+    let (out, parent_gas) = solution::run_program(
+        State::EMPTY,
+        solutions.clone(),
+        0,
+        &node(&parent),
+        parent.clone(),
+        vec![],
+        false,
+        &config,
+    )
+    .unwrap();
+    let Output::Parent(parent_out) = out else {
+        panic!("expected a parent output");
+    };
+    assert_eq!(&parent_out.0[..], &[1, 2]);
+
+    let (out, leaf_gas) = solution::run_program(
+        State::EMPTY,
+        solutions.clone(),
+        0,
+        &node(&leaf),
+        leaf.clone(),
+        vec![parent_out.clone()],
+        true,
+        &config,
+    )
+    .unwrap();
+    assert!(matches!(out, Output::Leaf(ProgramOutput::Satisfied(true))));
+    assert!(parent_gas > 0 && leaf_gas > 0);
+
+    // The node's gas limit is enforced.
+    let limited = Node {
+        max_gas: Some(leaf_gas - 1),
+        ..node(&leaf)
+    };
+    assert!(solution::run_program(
+        State::EMPTY,
+        solutions,
+        0,
+        &limited,
+        leaf,
+        vec![parent_out],
+        true,
+        &config,
+    )
+    .is_err());
+}
+
+#[test]
+fn gas_limit_per_solution() {
+    use essential_vm::asm::short::*;
+    // Two constraints costing 5 gas each, as each op costs 1.
+    let program = Program(asm::to_bytes([PUSH(1), PUSH(1), ADD, PUSH(2), EQ]).collect());
+    let program_ca = content_addr(&program);
+    let get_program: Arc<HashMap<_, _>> = Arc::new(
+        vec![(program_ca.clone(), Arc::new(program))]
+            .into_iter()
+            .collect(),
+    );
+    let node = Node {
+        program_address: program_ca,
+        edge_start: Edge::MAX,
+        max_gas: None,
+    };
+    let predicate = Predicate {
+        nodes: vec![node.clone(), node],
+        edges: vec![],
+    };
+    let contract = Contract::without_salt(vec![predicate]);
+    let pred_addr = PredicateAddress {
+        contract: content_addr(&contract),
+        predicate: content_addr(&contract.predicates[0]),
+    };
+    let set = Arc::new(SolutionSet {
+        solutions: vec![Solution {
+            predicate_to_solve: pred_addr,
+            predicate_data: Default::default(),
+            state_mutations: vec![],
+        }],
+    });
+    let predicate = Arc::new(contract.predicates[0].clone());
+
+    let check = |limit_per_solution| {
+        let config = solution::CheckPredicateConfig {
+            gas: solution::GasConfig {
+                limit_per_solution,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        solution::check_set_predicates(
+            &State::EMPTY,
+            set.clone(),
+            |_: &PredicateAddress| predicate.clone(),
+            get_program.clone(),
+            Arc::new(config),
+            Default::default(),
+            &mut Default::default(),
+        )
+    };
+
+    assert_eq!(check(None).unwrap().gas, 10);
+    assert_eq!(check(Some(10)).unwrap().gas, 10);
+    let Err(solution::PredicatesError::Failed(errs)) = check(Some(9)) else {
+        panic!("expected the predicate to fail");
+    };
+    assert!(matches!(
+        errs.0[..],
+        [(
+            0,
+            solution::PredicateError::OutOfGas {
+                limit: 9,
+                spent: 10
+            }
+        )]
+    ));
+}
+
+#[test]
+fn timeout() {
+    use essential_vm::asm::short::*;
+    let program = Program(asm::to_bytes([PUSH(1)]).collect());
+    let program_ca = content_addr(&program);
+    let get_program: Arc<HashMap<_, _>> = Arc::new(
+        vec![(program_ca.clone(), Arc::new(program))]
+            .into_iter()
+            .collect(),
+    );
+    let predicate = Predicate {
+        nodes: vec![Node {
+            program_address: program_ca,
+            edge_start: Edge::MAX,
+            max_gas: None,
+        }],
+        edges: vec![],
+    };
+    let contract = Contract::without_salt(vec![predicate]);
+    let pred_addr = PredicateAddress {
+        contract: content_addr(&contract),
+        predicate: content_addr(&contract.predicates[0]),
+    };
+    let solution = Solution {
+        predicate_to_solve: pred_addr,
+        predicate_data: Default::default(),
+        state_mutations: vec![],
+    };
+    let set = Arc::new(SolutionSet {
+        solutions: vec![solution.clone(), solution],
+    });
+    let predicate = Arc::new(contract.predicates[0].clone());
+
+    let check = |timeout| {
+        let config = solution::CheckPredicateConfig {
+            timeout,
+            ..Default::default()
+        };
+        solution::check_set_predicates(
+            &State::EMPTY,
+            set.clone(),
+            |_: &PredicateAddress| predicate.clone(),
+            get_program.clone(),
+            Arc::new(config),
+            Default::default(),
+            &mut Default::default(),
+        )
+    };
+
+    assert!(check(None).is_ok());
+    assert!(check(Some(std::time::Duration::from_secs(60))).is_ok());
+
+    // No solution can finish within a zero timeout.
+    let Err(solution::PredicatesError::Timeout(timeout)) = check(Some(Default::default())) else {
+        panic!("expected a timeout");
+    };
+    assert!(timeout.finished.is_empty());
+}
+
+#[test]
+fn thread_pool() {
+    use essential_vm::asm::short::*;
+    let program = Arc::new(Program(asm::to_bytes([PUSH(1)]).collect()));
+    let program_ca = content_addr(&*program);
+    let predicate = Predicate {
+        nodes: vec![Node {
+            program_address: program_ca,
+            edge_start: Edge::MAX,
+            max_gas: None,
+        }],
+        edges: vec![],
+    };
+    let contract = Contract::without_salt(vec![predicate]);
+    let pred_addr = PredicateAddress {
+        contract: content_addr(&contract),
+        predicate: content_addr(&contract.predicates[0]),
+    };
+    let solution = Solution {
+        predicate_to_solve: pred_addr,
+        predicate_data: Default::default(),
+        state_mutations: vec![],
+    };
+    let set = Arc::new(SolutionSet {
+        solutions: vec![solution.clone(), solution],
+    });
+    let predicate = Arc::new(contract.predicates[0].clone());
+
+    // Record the name of the thread on which each program is fetched.
+    let threads = Arc::new(std::sync::Mutex::new(vec![]));
+    let get_program = {
+        let threads = threads.clone();
+        move |_: &ContentAddress| {
+            let name = std::thread::current().name().map(str::to_string);
+            threads.lock().unwrap().push(name);
+            program.clone()
+        }
+    };
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .thread_name(|_| "check".to_string())
+        .build()
+        .unwrap();
+    let config = solution::CheckPredicateConfig {
+        thread_pool: Some(pool.into()),
+        ..Default::default()
+    };
+    solution::check_set_predicates(
+        &State::EMPTY,
+        set,
+        |_: &PredicateAddress| predicate.clone(),
+        get_program,
+        Arc::new(config),
+        Default::default(),
+        &mut Default::default(),
+    )
+    .unwrap();
+    let threads = threads.lock().unwrap();
+    assert!(!threads.is_empty());
+    assert!(threads.iter().all(|name| name.as_deref() == Some("check")));
+}
+
+#[test]
+fn program_cache() {
+    use essential_vm::asm::short::*;
+    let programs: Vec<_> = (1..=3)
+        .map(|i| Arc::new(Program(asm::to_bytes([PUSH(i), PUSH(i), EQ]).collect())))
+        .collect();
+    let addrs: Vec<_> = programs.iter().map(|p| content_addr(&**p)).collect();
+    let get_program: Arc<HashMap<_, _>> = Arc::new(addrs.iter().cloned().zip(programs).collect());
+
+    let cache = solution::ProgramCache::new(2);
+    let config = Arc::new(solution::CheckPredicateConfig {
+        program_cache: Some(cache.clone()),
+        ..Default::default()
+    });
+
+    // Check a solution set for a predicate with a single program.
+    let check = |program_address: &ContentAddress| {
+        let predicate = Predicate {
+            nodes: vec![Node {
+                program_address: program_address.clone(),
+                edge_start: Edge::MAX,
+                max_gas: None,
+            }],
+            edges: vec![],
+        };
+        let contract = Contract::without_salt(vec![predicate]);
+        let set = SolutionSet {
+            solutions: vec![Solution {
+                predicate_to_solve: PredicateAddress {
+                    contract: content_addr(&contract),
+                    predicate: content_addr(&contract.predicates[0]),
+                },
+                predicate_data: Default::default(),
+                state_mutations: vec![],
+            }],
+        };
+        let predicate = Arc::new(contract.predicates[0].clone());
+        solution::check_set_predicates(
+            &State::EMPTY,
+            Arc::new(set),
+            |_: &PredicateAddress| predicate.clone(),
+            get_program.clone(),
+            config.clone(),
+            Default::default(),
+            &mut Default::default(),
+        )
+        .unwrap();
+    };
+
+    // Mapped programs are retained between checks.
+    check(&addrs[0]);
+    check(&addrs[1]);
+    assert_eq!(cache.len(), 2);
+    assert!(cache.contains(&addrs[0]));
+    assert!(cache.contains(&addrs[1]));
+
+    // The least recently used program is evicted.
+    check(&addrs[0]);
+    check(&addrs[2]);
+    assert_eq!(cache.len(), 2);
+    assert!(cache.contains(&addrs[0]));
+    assert!(!cache.contains(&addrs[1]));
+    assert!(cache.contains(&addrs[2]));
+}
+
+#[test]
+fn recheck_set_predicates() {
+    use essential_vm::asm::short::*;
+    // Reads the value at key `[0]` and checks that it is `42`.
+    let reader = Program(
+        asm::to_bytes([
+            PUSH(0), // Key
+            PUSH(1), // Key len
+            PUSH(1), // Num keys
+            PUSH(3),
+            ALOC,
+            KRNG,
+            PUSH(2),
+            LOD,
+            PUSH(42),
+            EQ,
+        ])
+        .collect(),
+    );
+    let pure = Program(asm::to_bytes([PUSH(1)]).collect());
+    let reader_ca = content_addr(&reader);
+    let pure_ca = content_addr(&pure);
+    let get_program: Arc<HashMap<_, _>> = Arc::new(
+        vec![
+            (reader_ca.clone(), Arc::new(reader)),
+            (pure_ca.clone(), Arc::new(pure)),
+        ]
+        .into_iter()
+        .collect(),
+    );
+
+    let node = |program_address| Node {
+        program_address,
+        edge_start: Edge::MAX,
+        max_gas: None,
+    };
+    let predicate = Predicate {
+        nodes: vec![node(reader_ca), node(pure_ca)],
+        edges: vec![],
+    };
+    let contract = Contract::without_salt(vec![predicate]);
+    let contract_ca = content_addr(&contract);
+    let set = Arc::new(SolutionSet {
+        solutions: vec![Solution {
+            predicate_to_solve: PredicateAddress {
+                contract: contract_ca.clone(),
+                predicate: content_addr(&contract.predicates[0]),
+            },
+            predicate_data: Default::default(),
+            state_mutations: vec![],
+        }],
+    });
+    let predicate = Arc::new(contract.predicates[0].clone());
+
+    let mut recheck = solution::RecheckCache::new();
+    let mut check = |value, changed_key: Key| {
+        let state = State::new(vec![(
+            contract_ca.clone(),
+            vec![([0].into(), [value].into())],
+        )]);
+        let changed = HashMap::from([(contract_ca.clone(), HashSet::from([changed_key]))]);
+        solution::recheck_set_predicates(
+            &state,
+            set.clone(),
+            |_: &PredicateAddress| predicate.clone(),
+            get_program.clone(),
+            Default::default(),
+            Default::default(),
+            &mut Default::default(),
+            &changed,
+            &mut recheck,
+        )
+    };
+
+    // The first check runs and records all nodes.
+    let gas = check(42, Key::default()).unwrap().gas;
+
+    // Nodes that did not read a changed key are not rerun.
+    let outputs = check(43, [1].into()).unwrap();
+    assert_eq!(outputs.gas, gas);
+
+    // Nodes that read a changed key are rerun.
+    assert!(matches!(
+        check(43, [0].into()).unwrap_err(),
+        solution::PredicatesError::Failed(_)
+    ));
+    assert_eq!(recheck.len(), 2);
+}
+
+#[test]
+fn dedup_key_range_reads() {
+    use essential_check::vm::{StateRead, StateReads};
+    use essential_vm::asm::short::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Counts the key range reads performed against the state.
+    #[derive(Clone)]
+    struct Counted(State, Arc<AtomicUsize>);
+    impl StateRead for Counted {
+        type Error = util::InvalidStateRead;
+        fn key_range(
+            &self,
+            contract_addr: ContentAddress,
+            key: Key,
+            num_values: usize,
+        ) -> Result<Vec<Value>, Self::Error> {
+            self.1.fetch_add(1, Ordering::Relaxed);
+            self.0.key_range(contract_addr, key, num_values)
+        }
+        fn keys_with_prefix(
+            &self,
+            contract_addr: ContentAddress,
+            prefix: Key,
+            limit: usize,
+        ) -> Result<Vec<Key>, Self::Error> {
+            StateRead::keys_with_prefix(&self.0, contract_addr, prefix, limit)
+        }
+    }
+    impl StateReads for Counted {
+        type Error = util::InvalidStateRead;
+        type Pre = Self;
+        type Post = Self;
+        fn pre(&self) -> &Self::Pre {
+            self
+        }
+        fn post(&self) -> &Self::Post {
+            self
+        }
+    }
+
+    // Reads the value at key `[0]` and checks that it is `42`.
+    let reader = Program(
+        asm::to_bytes([
+            PUSH(0), // Key
+            PUSH(1), // Key len
+            PUSH(1), // Num keys
+            PUSH(3),
+            ALOC,
+            KRNG,
+            PUSH(2),
+            LOD,
+            PUSH(42),
+            EQ,
+        ])
+        .collect(),
+    );
+    let reader_ca = content_addr(&reader);
+    let get_program: Arc<HashMap<_, _>> =
+        Arc::new(HashMap::from([(reader_ca.clone(), Arc::new(reader))]));
+
+    // Two nodes of each solution perform the same read.
+    let node = || Node {
+        program_address: reader_ca.clone(),
+        edge_start: Edge::MAX,
+        max_gas: None,
+    };
+    let predicate = Predicate {
+        nodes: vec![node(), node()],
+        edges: vec![],
+    };
+    let contract = Contract::without_salt(vec![predicate]);
+    let contract_ca = content_addr(&contract);
+    let solution = Solution {
+        predicate_to_solve: PredicateAddress {
+            contract: contract_ca.clone(),
+            predicate: content_addr(&contract.predicates[0]),
+        },
+        predicate_data: Default::default(),
+        state_mutations: vec![],
+    };
+    let set = Arc::new(SolutionSet {
+        solutions: vec![solution.clone(), solution],
+    });
+    let predicate = Arc::new(contract.predicates[0].clone());
+
+    let reads = Arc::new(AtomicUsize::new(0));
+    let state = Counted(
+        State::new(vec![(contract_ca, vec![([0].into(), [42].into())])]),
+        reads.clone(),
+    );
+    solution::check_set_predicates(
+        &state,
+        set,
+        |_: &PredicateAddress| predicate.clone(),
+        get_program,
+        Default::default(),
+        Default::default(),
+        &mut Default::default(),
+    )
+    .unwrap();
+
+    // All four reads are coalesced into one.
+    assert_eq!(reads.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn output_hints() {
+    use essential_check::vm::{Memory, Stack};
+    use essential_vm::asm::short::*;
+    // The parent outputs `[1, 2]` which the leaf checks sum to `3`.
+    let parent = Program(asm::to_bytes([PUSH(1), PUSH(2), HLT]).collect());
+    let leaf = Program(asm::to_bytes([ADD, PUSH(3), EQ]).collect());
+    let parent_ca = content_addr(&parent);
+    let leaf_ca = content_addr(&leaf);
+    let get_program: Arc<HashMap<_, _>> = Arc::new(HashMap::from([
+        (parent_ca.clone(), Arc::new(parent)),
+        (leaf_ca.clone(), Arc::new(leaf)),
+    ]));
+    let node = |program_address, edge_start, max_gas| Node {
+        program_address,
+        edge_start,
+        max_gas,
+    };
+    // The parent's max gas is charged whenever it is hinted.
+    const PARENT_MAX_GAS: u64 = 1_000;
+    let predicate = Predicate {
+        nodes: vec![
+            node(parent_ca, 0, Some(PARENT_MAX_GAS)),
+            node(leaf_ca, Edge::MAX, None),
+        ],
+        edges: vec![1],
+    };
+    let contract = Contract::without_salt(vec![predicate]);
+    let set = Arc::new(SolutionSet {
+        solutions: vec![Solution {
+            predicate_to_solve: PredicateAddress {
+                contract: content_addr(&contract),
+                predicate: content_addr(&contract.predicates[0]),
+            },
+            predicate_data: Default::default(),
+            state_mutations: vec![],
+        }],
+    });
+    let predicate = Arc::new(contract.predicates[0].clone());
+
+    let hints = |stack: Vec<Word>| {
+        let mut hints = solution::OutputHints::new();
+        hints.insert(0, 0, Stack::try_from(stack).unwrap(), Memory::new());
+        hints
+    };
+    let check_predicate =
+        |predicate: &Arc<Predicate>, sample_percent, hints: &solution::OutputHints| {
+            let config = solution::CheckPredicateConfig {
+                hints: solution::HintVerification {
+                    sample_percent,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            solution::check_set_predicates_with_hints(
+                &State::EMPTY,
+                set.clone(),
+                |_: &PredicateAddress| predicate.clone(),
+                get_program.clone(),
+                Arc::new(config),
+                Default::default(),
+                &mut Default::default(),
+                hints,
+            )
+        };
+    let check = |sample_percent, hints: &solution::OutputHints| {
+        check_predicate(&predicate, sample_percent, hints)
+    };
+
+    // Without hints all nodes are run.
+    let gas = check(0, &solution::OutputHints::new()).unwrap().gas;
+    assert!(gas < PARENT_MAX_GAS);
+
+    // Unverified hints are used in place of running the parent.
+    let hinted = check(0, &hints(vec![1, 2])).unwrap();
+    assert!(matches!(
+        check(0, &hints(vec![1, 1])).unwrap_err(),
+        solution::PredicatesError::Failed(_)
+    ));
+
+    // Verified hints are checked against the parent's output.
+    let verified = check(100, &hints(vec![1, 2])).unwrap();
+    let err = check(100, &hints(vec![1, 1])).unwrap_err();
+    assert!(format!("{err:?}").contains("InvalidHint"));
+
+    // The hinted parent is charged its max gas whether or not it is verified.
+    assert_eq!(hinted.gas, verified.gas);
+    assert!(hinted.gas > PARENT_MAX_GAS);
+
+    // Hints for nodes without a max gas are ignored and the node is run.
+    let mut unlimited = (*predicate).clone();
+    unlimited.nodes[0].max_gas = None;
+    let unlimited = Arc::new(unlimited);
+    let outputs = check_predicate(&unlimited, 0, &hints(vec![1, 1])).unwrap();
+    assert_eq!(outputs.gas, gas);
+}
+
+#[test]
+fn progress() {
+    use essential_vm::asm::short::*;
+    let program = Arc::new(Program(asm::to_bytes([PUSH(1)]).collect()));
+    let program_ca = content_addr(&*program);
+    let predicate = Predicate {
+        nodes: vec![Node {
+            program_address: program_ca.clone(),
+            edge_start: Edge::MAX,
+            max_gas: None,
+        }],
+        edges: vec![],
+    };
+    let contract = Contract::without_salt(vec![predicate]);
+    let set = SolutionSet {
+        solutions: vec![Solution {
+            predicate_to_solve: PredicateAddress {
+                contract: content_addr(&contract),
+                predicate: content_addr(&contract.predicates[0]),
+            },
+            predicate_data: Default::default(),
+            state_mutations: vec![],
+        }],
+    };
+    let predicate = Arc::new(contract.predicates[0].clone());
+    let get_program: Arc<HashMap<_, _>> = Arc::new(HashMap::from([(program_ca, program)]));
+
+    // Collect the events.
+    let events = Arc::new(std::sync::Mutex::new(vec![]));
+    let config = solution::CheckPredicateConfig {
+        progress: Some(solution::ProgressSink::new({
+            let events = events.clone();
+            move |event| events.lock().unwrap().push(event.clone())
+        })),
+        ..Default::default()
+    };
+    let (gas, _) = solution::check_and_compute_solution_set_two_pass(
+        &State::EMPTY,
+        set,
+        |_: &PredicateAddress| predicate.clone(),
+        get_program,
+        Arc::new(config),
+    )
+    .unwrap();
+
+    use solution::{Progress, RunMode};
+    let events = events.lock().unwrap();
+    assert_eq!(
+        *events,
+        [
+            Progress::SolutionStarted {
+                run_mode: RunMode::Outputs,
+                solution_index: 0,
+            },
+            Progress::NodeFinished {
+                run_mode: RunMode::Outputs,
+                solution_index: 0,
+                node: 0,
+                ok: true,
+                gas,
+                solution_gas: gas,
+            },
+            Progress::SolutionFinished {
+                run_mode: RunMode::Outputs,
+                solution_index: 0,
+                ok: true,
+                gas,
+            },
+            // No node reads the post state, so the checks pass is skipped.
+        ]
+    );
+}
+
+#[test]
+fn duplicate_solutions() {
+    use essential_vm::asm::short::*;
+    // Checks that the first predicate data word is `0`.
+    let constraint =
+        Program(asm::to_bytes([PUSH(0), PUSH(0), PUSH(1), DATA, PUSH(0), EQ]).collect());
+    // Outputs `[42]` as data.
+    let output =
+        Program(asm::to_bytes([PUSH(42), PUSH(1), PUSH(1), ALOC, STOR, PUSH(2)]).collect());
+    let nodes = [&constraint, &output]
+        .into_iter()
+        .map(|program| Node {
+            program_address: content_addr(program),
+            edge_start: Edge::MAX,
+            max_gas: None,
+        })
+        .collect();
+    let get_program: Arc<HashMap<_, _>> = Arc::new(
+        [constraint, output]
+            .into_iter()
+            .map(|program| (content_addr(&program), Arc::new(program)))
+            .collect(),
+    );
+    let contract = Contract::without_salt(vec![Predicate {
+        nodes,
+        edges: vec![],
+    }]);
+    let predicate = Arc::new(contract.predicates[0].clone());
+    let solution = |predicate_data: Vec<Value>| Solution {
+        predicate_to_solve: PredicateAddress {
+            contract: content_addr(&contract),
+            predicate: content_addr(&contract.predicates[0]),
+        },
+        predicate_data,
+        state_mutations: vec![],
+    };
+    let (a, b, bad) = (
+        solution(vec![[0].into()]),
+        solution(vec![[0].into(), [5].into()]),
+        solution(vec![[1].into()]),
+    );
+
+    // Check the set, sharing the results of duplicate solutions unless
+    // hints are given, counting the solutions checked.
+    let check = |solutions: Vec<Solution>, share: bool| {
+        let started = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let config = solution::CheckPredicateConfig {
+            progress: Some(solution::ProgressSink::new({
+                let started = started.clone();
+                move |event| {
+                    if let solution::Progress::SolutionStarted { .. } = event {
+                        started.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    }
+                }
+            })),
+            ..Default::default()
+        };
+        let set = Arc::new(SolutionSet { solutions });
+        let get_predicate = |_: &PredicateAddress| predicate.clone();
+        let res = if share {
+            solution::check_set_predicates(
+                &State::EMPTY,
+                set,
+                get_predicate,
+                get_program.clone(),
+                Arc::new(config),
+                Default::default(),
+                &mut Default::default(),
+            )
+        } else {
+            solution::check_set_predicates_with_hints(
+                &State::EMPTY,
+                set,
+                get_predicate,
+                get_program.clone(),
+                Arc::new(config),
+                Default::default(),
+                &mut Default::default(),
+                &solution::OutputHints::new(),
+            )
+        };
+        (res, started.load(std::sync::atomic::Ordering::Relaxed))
+    };
+
+    // Duplicates share results, producing the same outputs as checking each.
+    let set = vec![a.clone(), a.clone(), b, a.clone()];
+    let (shared, checked) = check(set.clone(), true);
+    assert_eq!(checked, 2);
+    let (full, checked) = check(set, false);
+    assert_eq!(checked, 4);
+    let shared = shared.unwrap();
+    assert_eq!(shared, full.unwrap());
+    assert_eq!(
+        shared
+            .data
+            .iter()
+            .map(|d| d.solution_index)
+            .collect::<Vec<_>>(),
+        vec![0, 1, 2, 3]
+    );
+
+    // The duplicates of failed solutions report their own errors.
+    let set = vec![a, bad.clone(), bad];
+    let (shared, _) = check(set.clone(), true);
+    let (full, _) = check(set, false);
+    let (shared, full) = (shared.unwrap_err(), full.unwrap_err());
+    assert_eq!(shared.to_string(), full.to_string());
+    assert!(shared.to_string().contains("  2: "), "{shared}");
+}
+
+#[test]
+fn memory_budget() {
+    use essential_vm::asm::short::*;
+    // Allocate `[n, n]` in memory where `n` is the first predicate data word.
+    let parent = Program(
+        asm::to_bytes([
+            PUSH(2),
+            ALOC,
+            POP,
+            PUSH(0),
+            PUSH(0),
+            PUSH(1),
+            DATA,
+            DUP,
+            PUSH(0),
+            STO,
+            PUSH(1),
+            STO,
+            HLT,
+        ])
+        .collect(),
+    );
+    // Sum the parents' memory and output the result.
+    let child = Program(
+        asm::to_bytes([
+            PUSH(0),
+            LOD,
+            PUSH(1),
+            LOD,
+            ADD,
+            PUSH(2),
+            LOD,
+            ADD,
+            PUSH(3),
+            LOD,
+            ADD,
+            PUSH(0),
+            STO,
+            PUSH(1),
+            FREE,
+            // Output memory.
+            PUSH(2),
+            HLT,
+        ])
+        .collect(),
+    );
+    let parent_ca = content_addr(&parent);
+    let child_ca = content_addr(&child);
+    let node = |program_address, edge_start| Node {
+        program_address,
+        edge_start,
+        max_gas: None,
+    };
+    let predicate = Predicate {
+        nodes: vec![
+            node(parent_ca.clone(), 0),
+            node(parent_ca.clone(), 1),
+            node(child_ca.clone(), Edge::MAX),
+        ],
+        edges: vec![2, 2],
+    };
+    let contract = Contract::without_salt(vec![predicate]);
+    let pred_addr = PredicateAddress {
+        contract: content_addr(&contract),
+        predicate: content_addr(&contract.predicates[0]),
+    };
+    let set = SolutionSet {
+        solutions: (0..8)
+            .map(|n| Solution {
+                predicate_to_solve: pred_addr.clone(),
+                predicate_data: vec![[n].into()],
+                state_mutations: vec![],
+            })
+            .collect(),
+    };
+    let set = Arc::new(set);
+    let predicate = Arc::new(contract.predicates[0].clone());
+    let get_program: Arc<HashMap<_, _>> = Arc::new(
+        vec![(parent_ca, Arc::new(parent)), (child_ca, Arc::new(child))]
+            .into_iter()
+            .collect(),
+    );
+
+    let check = |memory_budget| {
+        let config = solution::CheckPredicateConfig {
+            memory_budget,
+            ..Default::default()
+        };
+        solution::check_set_predicates(
+            &State::EMPTY,
+            set.clone(),
+            |_: &PredicateAddress| predicate.clone(),
+            get_program.clone(),
+            Arc::new(config),
+            Default::default(),
+            &mut Default::default(),
+        )
+        .unwrap()
+    };
+
+    // The outputs are the same regardless of the budget.
+    let unbounded = check(None);
+    assert_eq!(unbounded.data.len(), 8);
+    for memory_budget in [Some(0), Some(4), Some(usize::MAX)] {
+        let outputs = check(memory_budget);
+        assert_eq!(outputs.gas, unbounded.gas);
+        assert_eq!(outputs.data, unbounded.data);
+    }
+}
+
+#[test]
+fn solution_compute_mutations() {
+    let _ = tracing_subscriber::fmt::try_init();
+    let mutations_0 = vec![Mutation {
+        key: [1, 2, 3, 4].into(),
+        value: [42].into(),
+    }];
+    let mutations_1 = vec![
+        Mutation {
+            key: [5, 6, 7, 8].into(),
+            value: [43, 44].into(),
+        },
+        Mutation {
+            key: [9].into(),
+            value: [45, 46].into(),
+        },
+    ];
+    let mutations_2 = vec![
+        Mutation {
+            key: [10, 11].into(),
+            value: [47, 48, 49].into(),
+        },
+        Mutation {
+            key: [12].into(),
+            value: [50, 51].into(),
+        },
+    ];
+
+    let pred_0_prg_0 = util::mutations_program(&mutations_0);
+    let pred_0_prg_1 = util::mutations_program(&mutations_1);
+    let pred_1_prg_0 = util::mutations_program(&mutations_2);
+
+    let pred_0_prg_0_ca = content_addr(&pred_0_prg_0);
+    let pred_0_prg_1_ca = content_addr(&pred_0_prg_1);
+    let pred_1_prg_0_ca = content_addr(&pred_1_prg_0);
+
+    let node = |program_address, edge_start| Node {
+        program_address,
+        edge_start,
+        max_gas: None,
+    };
+    let nodes = vec![
+        node(pred_0_prg_0_ca.clone(), Edge::MAX),
+        node(pred_0_prg_1_ca.clone(), Edge::MAX),
+    ];
+    let edges = vec![];
+    let predicate_0 = Predicate { nodes, edges };
+    let contract_0 = Contract::without_salt(vec![predicate_0]);
+    let pred_addr_0 = PredicateAddress {
+        contract: content_addr(&contract_0),
+        predicate: content_addr(&contract_0.predicates[0]),
+    };
+
+    let nodes = vec![node(pred_1_prg_0_ca.clone(), Edge::MAX)];
+    let edges = vec![];
+    let predicate_1 = Predicate { nodes, edges };
+    let contract_1 = Contract::without_salt(vec![predicate_1]);
+    let pred_addr_1 = PredicateAddress {
+        contract: content_addr(&contract_1),
+        predicate: content_addr(&contract_1.predicates[0]),
+    };
+
+    // Create a solution that "solves" our predicate.
+    let set = SolutionSet {
+        solutions: vec![
+            Solution {
+                predicate_to_solve: pred_addr_1.clone(),
+                predicate_data: Default::default(),
+                state_mutations: vec![],
+            },
+            Solution {
+                predicate_to_solve: pred_addr_0.clone(),
+                predicate_data: Default::default(),
+                state_mutations: vec![],
+            },
+        ],
+    };
+
+    let predicate_0 = Arc::new(contract_0.predicates[0].clone());
+    let predicate_1 = Arc::new(contract_1.predicates[0].clone());
+    let mut map = HashMap::new();
+    map.insert(pred_addr_0.contract.clone(), predicate_0);
+    map.insert(pred_addr_1.contract.clone(), predicate_1);
+
+    let get_predicate = |addr: &PredicateAddress| map.get(&addr.contract).unwrap().clone();
+    let programs: HashMap<ContentAddress, Arc<Program>> = vec![
+        (pred_0_prg_0_ca, Arc::new(pred_0_prg_0)),
+        (pred_0_prg_1_ca, Arc::new(pred_0_prg_1)),
+        (pred_1_prg_0_ca, Arc::new(pred_1_prg_0)),
+    ]
+    .into_iter()
+    .collect();
+    let get_program: Arc<HashMap<_, _>> = Arc::new(programs);
+
+    let set = solution::check_and_compute_solution_set(
+        &State::EMPTY,
+        set,
+        get_predicate,
+        get_program,
+        Arc::new(solution::CheckPredicateConfig::default()),
+        Default::default(),
+        &mut Default::default(),
+    )
+    .unwrap();
+
+    let expected = [mutations_0, mutations_1].concat();
+    assert_eq!(set.1.solutions[0].state_mutations, mutations_2);
+    assert_eq!(set.1.solutions[1].state_mutations, expected);
+}
+
+#[test]
+fn tagged_data_outputs() {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    // A custom decoder for log outputs.
+    struct Logs;
+    impl solution::DecodeOutput for Logs {
+        const KIND: Word = DataOutput::LOGS;
+        type Output = Vec<Word>;
+        type Error = core::convert::Infallible;
+        fn decode(memory: &[Word]) -> Result<Self::Output, Self::Error> {
+            Ok(memory.to_vec())
+        }
+    }
+
+    let tagged = vec![Mutation {
+        key: [1].into(),
+        value: [42].into(),
+    }];
+    let untagged = vec![Mutation {
+        key: [2].into(),
+        value: [43].into(),
+    }];
+
+    let programs = [
+        util::store_words_program(encode_mutations(&tagged), &[DataOutput::MUTATIONS, 3]),
+        util::store_words_program(vec![7, 8, 9], &[DataOutput::LOGS, 3]),
+        util::mutations_program(&untagged),
+    ];
+
+    let nodes = programs
+        .iter()
+        .map(|program| Node {
+            program_address: content_addr(program),
+            edge_start: Edge::MAX,
+            max_gas: None,
+        })
+        .collect();
+    let predicate = Predicate {
+        nodes,
+        edges: vec![],
+    };
+    let contract = Contract::without_salt(vec![predicate]);
+    let pred_addr = PredicateAddress {
+        contract: content_addr(&contract),
+        predicate: content_addr(&contract.predicates[0]),
+    };
+    let set = SolutionSet {
+        solutions: vec![Solution {
+            predicate_to_solve: pred_addr,
+            predicate_data: Default::default(),
+            state_mutations: vec![],
+        }],
+    };
+
+    let predicate = Arc::new(contract.predicates[0].clone());
+    let get_predicate = |_: &PredicateAddress| predicate.clone();
+    let get_program: Arc<HashMap<ContentAddress, Arc<Program>>> = Arc::new(
+        programs
+            .into_iter()
+            .map(|program| (content_addr(&program), Arc::new(program)))
+            .collect(),
+    );
+
+    // Each output carries its kind.
+    let outputs = solution::check_set_predicates(
+        &State::EMPTY,
+        Arc::new(set.clone()),
+        get_predicate,
+        get_program.clone(),
+        Arc::new(solution::CheckPredicateConfig::default()),
+        Default::default(),
+        &mut Default::default(),
+    )
+    .unwrap();
+    let data = &outputs.data[0].data;
+    assert_eq!(data.len(), 3);
+    let kinds: Vec<_> = data.iter().map(DataOutput::kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            DataOutput::MUTATIONS,
+            DataOutput::LOGS,
+            DataOutput::MUTATIONS
+        ]
+    );
+    assert_eq!(
+        data[0].decode::<solution::DecodeMutations>(),
+        Some(Ok(tagged.clone()))
+    );
+    assert_eq!(data[0].decode::<Logs>(), None);
+    assert_eq!(data[1].decode::<Logs>(), Some(Ok(vec![7, 8, 9])));
+    assert_eq!(data[1].decode::<solution::DecodeMutations>(), None);
+    assert_eq!(
+        data[2].decode::<solution::DecodeMutations>(),
+        Some(Ok(untagged.clone()))
+    );
+
+    // Only the mutation outputs are applied to the solution.
+    let (_, set) = solution::check_and_compute_solution_set(
+        &State::EMPTY,
+        set,
+        get_predicate,
+        get_program,
+        Arc::new(solution::CheckPredicateConfig::default()),
+        Default::default(),
+        &mut Default::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        set.solutions[0].state_mutations,
+        [tagged, untagged].concat()
+    );
+}
+
+#[test]
+fn mutations_output_trailing_words() {
+    use essential_types::solution::decode::MutationDecodeError;
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let mutations = vec![
+        Mutation {
+            key: [1].into(),
+            value: [42].into(),
+        },
+        Mutation {
+            key: [2].into(),
+            value: [43].into(),
+        },
+    ];
+    let check = |words: Vec<Word>| {
+        let program = util::store_words_program(words, &[2]);
+        let program_ca = content_addr(&program);
+        let predicate = Predicate {
+            nodes: vec![Node {
+                program_address: program_ca.clone(),
+                edge_start: Edge::MAX,
+                max_gas: None,
+            }],
+            edges: vec![],
+        };
+        let contract = Contract::without_salt(vec![predicate]);
+        let set = SolutionSet {
+            solutions: vec![Solution {
+                predicate_to_solve: PredicateAddress {
+                    contract: content_addr(&contract),
+                    predicate: content_addr(&contract.predicates[0]),
+                },
+                predicate_data: Default::default(),
+                state_mutations: vec![],
+            }],
+        };
+        let predicate = Arc::new(contract.predicates[0].clone());
+        let get_program: Arc<HashMap<_, _>> =
+            Arc::new(HashMap::from([(program_ca, Arc::new(program))]));
+        solution::check_and_compute_solution_set(
+            &State::EMPTY,
+            set,
+            |_: &PredicateAddress| predicate.clone(),
+            get_program,
+            Arc::new(solution::CheckPredicateConfig::default()),
+            Default::default(),
+            &mut Default::default(),
+        )
+    };
+    let expect_trailing = |words: Vec<Word>, n: usize| {
+        let Err(solution::PredicatesError::Failed(errs)) = check(words) else {
+            panic!("expected the mutations to fail to decode");
+        };
+        assert!(matches!(
+            errs.0[..],
+            [(
+                0,
+                solution::PredicateError::Mutations(solution::MutationsError::DecodeError(
+                    MutationDecodeError::TrailingWords(t)
+                ))
+            )] if t == n
+        ));
+    };
+
+    let words = encode_mutations(&mutations);
+    let (_, set) = check(words.clone()).unwrap();
+    assert_eq!(set.solutions[0].state_mutations, mutations);
+
+    // Outputs with words beyond the encoded mutations were previously
+    // accepted and are now rejected.
+    let mut trailing = words.clone();
+    trailing.push(0);
+    expect_trailing(trailing, 1);
+
+    // As are outputs encoding more mutations than their count.
+    let mut undercounted = words;
+    undercounted[0] = 1;
+    expect_trailing(undercounted, 4);
+}
+
+#[test]
+fn solution_compute_mutations_two_pass() {
+    use essential_vm::asm::short::*;
+    let _ = tracing_subscriber::fmt::try_init();
+    let mutations_0 = vec![Mutation {
+        key: [1, 2, 3, 4].into(),
+        value: [42].into(),
+    }];
+    let mutations_1 = vec![
+        Mutation {
+            key: [5, 6, 7, 8].into(),
+            value: [43, 44].into(),
+        },
+        Mutation {
+            key: [9].into(),
+            value: [45, 46].into(),
+        },
+    ];
+    let mutations_2 = vec![
+        Mutation {
+            key: [10, 11].into(),
+            value: [47, 48, 49].into(),
+        },
+        Mutation {
+            key: [12].into(),
+            value: [50, 51].into(),
+        },
+    ];
+
+    let post_read = Program(
+        asm::to_bytes([
+            // Read the key range into memory.
+            PUSH(9), // Key
+            PUSH(1), // Key Len
+            PUSH(1), // Num to read
+            PUSH(4),
+            ALOC,
+            PKRNG,
+            PUSH(2),
+            PUSH(2),
+            LODR,
+            PUSH(45),
+            PUSH(46),
+            PUSH(2),
+            EQRA,
+        ])
+        .collect(),
+    );
+
+    let pred_0_prg_0 = util::mutations_program(&mutations_0);
+    let pred_0_prg_1 = util::mutations_program(&mutations_1);
+    let pred_1_prg_0 = util::mutations_program(&mutations_2);
+
+    let pred_0_prg_0_ca = content_addr(&pred_0_prg_0);
+    let pred_0_prg_1_ca = content_addr(&pred_0_prg_1);
+    let pred_1_prg_0_ca = content_addr(&pred_1_prg_0);
+    let post_read_ca = content_addr(&post_read);
+
+    let node = |program_address, edge_start| Node {
+        program_address,
+        edge_start,
+        max_gas: None,
+    };
+    let nodes = vec![
+        node(pred_0_prg_0_ca.clone(), Edge::MAX),
+        node(pred_0_prg_1_ca.clone(), Edge::MAX),
+        node(post_read_ca.clone(), Edge::MAX),
+    ];
+    let edges = vec![];
+    let predicate_0 = Predicate { nodes, edges };
+    let contract_0 = Contract::without_salt(vec![predicate_0]);
+    let pred_addr_0 = PredicateAddress {
+        contract: content_addr(&contract_0),
+        predicate: content_addr(&contract_0.predicates[0]),
+    };
+
+    let nodes = vec![node(pred_1_prg_0_ca.clone(), Edge::MAX)];
+    let edges = vec![];
+    let predicate_1 = Predicate { nodes, edges };
+    let contract_1 = Contract::without_salt(vec![predicate_1]);
+    let pred_addr_1 = PredicateAddress {
+        contract: content_addr(&contract_1),
+        predicate: content_addr(&contract_1.predicates[0]),
+    };
+
+    // Create a solution that "solves" our predicate.
+    let set = SolutionSet {
+        solutions: vec![
+            Solution {
+                predicate_to_solve: pred_addr_1.clone(),
+                predicate_data: Default::default(),
+                state_mutations: vec![],
+            },
+            Solution {
+                predicate_to_solve: pred_addr_0.clone(),
+                predicate_data: Default::default(),
+                state_mutations: vec![],
+            },
+        ],
+    };
+
+    let predicate_0 = Arc::new(contract_0.predicates[0].clone());
+    let predicate_1 = Arc::new(contract_1.predicates[0].clone());
+    let mut map = HashMap::new();
+    map.insert(pred_addr_0.contract.clone(), predicate_0);
+    map.insert(pred_addr_1.contract.clone(), predicate_1);
+
+    let get_predicate = |addr: &PredicateAddress| map.get(&addr.contract).unwrap().clone();
+    let programs: HashMap<ContentAddress, Arc<Program>> = vec![
+        (pred_0_prg_0_ca, Arc::new(pred_0_prg_0)),
+        (pred_0_prg_1_ca, Arc::new(pred_0_prg_1)),
+        (pred_1_prg_0_ca, Arc::new(pred_1_prg_0)),
+        (post_read_ca, Arc::new(post_read)),
+    ]
+    .into_iter()
+    .collect();
+    let get_program: Arc<HashMap<_, _>> = Arc::new(programs);
+
+    // A dry run computes the same mutations without running the post state read.
+    let dry_run = solution::compute_solution_set_outputs(
+        &State::EMPTY,
+        set.clone(),
+        get_predicate,
+        get_program.clone(),
+        Arc::new(solution::CheckPredicateConfig::default()),
+    )
+    .unwrap();
+
+    let set = solution::check_and_compute_solution_set_two_pass(
+        &State::EMPTY,
+        set,
+        get_predicate,
+        get_program,
+        Arc::new(solution::CheckPredicateConfig::default()),
+    )
+    .unwrap();
+
+    let expected = [mutations_0, mutations_1].concat();
+    assert_eq!(set.1.solutions[0].state_mutations, mutations_2);
+    assert_eq!(set.1.solutions[1].state_mutations, expected);
+    assert_eq!(dry_run.1, set.1);
+    assert!(dry_run.0 < set.0);
+}
+
+#[test]
+fn solution_compute_mutations_two_pass_with_diff() {
+    let mutations = vec![
+        Mutation {
+            key: [1, 2].into(),
+            value: [42].into(),
+        },
+        Mutation {
+            key: [3].into(),
+            value: [43, 44].into(),
+        },
+    ];
+
+    // Output the encoded mutations.
+    let program = util::mutations_program(&mutations);
+    let program_ca = content_addr(&program);
+
+    let predicate = Predicate {
+        nodes: vec![Node {
+            program_address: program_ca.clone(),
+            edge_start: Edge::MAX,
+            max_gas: None,
+        }],
+        edges: vec![],
+    };
+    let contract = Contract::without_salt(vec![predicate]);
+    let contract_ca = content_addr(&contract);
+    let set = SolutionSet {
+        solutions: vec![Solution {
+            predicate_to_solve: PredicateAddress {
+                contract: contract_ca.clone(),
+                predicate: content_addr(&contract.predicates[0]),
+            },
+            predicate_data: Default::default(),
+            state_mutations: vec![],
+        }],
+    };
+    let predicate = Arc::new(contract.predicates[0].clone());
+    let get_program: Arc<HashMap<_, _>> =
+        Arc::new(HashMap::from([(program_ca, Arc::new(program))]));
+
+    // Only the first key has a value prior to the set.
+    let state = State::new(vec![(
+        contract_ca.clone(),
+        vec![([1, 2].into(), [7].into())],
+    )]);
+
+    let (_, set, diff) = solution::check_and_compute_solution_set_two_pass_with_diff(
+        &state,
+        set,
+        |_: &PredicateAddress| predicate.clone(),
+        get_program,
+        Arc::new(solution::CheckPredicateConfig::default()),
+    )
+    .unwrap();
+
+    assert_eq!(set.solutions[0].state_mutations, mutations);
+    let expected = [(
+        contract_ca,
+        [
+            (
+                [1, 2].into(),
+                solution::ValueDiff {
+                    pre: [7].into(),
+                    post: [42].into(),
+                },
+            ),
+            (
+                [3].into(),
+                solution::ValueDiff {
+                    pre: Value::default(),
+                    post: [43, 44].into(),
+                },
+            ),
+        ]
+        .into_iter()
+        .collect(),
+    )]
+    .into_iter()
+    .collect::<solution::StateDiff>();
+    assert_eq!(diff, expected);
+}
+
+#[test]
+fn arbitrary_solution_sets() {
+    use arbitrary::{Arbitrary, Unstructured};
+    use rand::{Rng, SeedableRng};
+    let mut rng = rand::rngs::SmallRng::seed_from_u64(0);
+    let mut bytes = vec![0u8; 4096];
+    for _ in 0..100 {
+        rng.fill(&mut bytes[..]);
+        let set = SolutionSet::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+        solution::check_set(&set).unwrap();
+    }
 }
@@ -0,0 +1,149 @@
+use essential_check::{
+    state_proof::{
+        ProofError, ProvenStateRead, ProvenValue, StateProof, StateTree, VerifiedStateRead, EMPTY,
+    },
+    types::{ContentAddress, Key, Value},
+    vm::StateRead,
+};
+use std::convert::Infallible;
+
+fn test_tree() -> StateTree {
+    let mut tree = StateTree::new();
+    for i in 0..16 {
+        tree.insert(&ContentAddress([0; 32]), &[1, i], [i, i * 2].into());
+    }
+    tree.insert(&ContentAddress([1; 32]), &[1, 0], [42].into());
+    tree
+}
+
+/// A prover that returns values from the tree with the given tampering applied.
+struct Tampered<F>(StateTree, F);
+
+impl<F> ProvenStateRead for Tampered<F>
+where
+    F: Fn(&mut Vec<ProvenValue>) + Send + Sync,
+{
+    type Error = Infallible;
+
+    fn key_range_proven(
+        &self,
+        contract_addr: ContentAddress,
+        key: Key,
+        num_values: usize,
+    ) -> Result<Vec<ProvenValue>, Self::Error> {
+        let mut values = self.0.key_range_proven(contract_addr, key, num_values)?;
+        (self.1)(&mut values);
+        Ok(values)
+    }
+}
+
+#[test]
+fn empty_tree() {
+    let tree = StateTree::new();
+    assert_eq!(tree.root(), EMPTY);
+    let proven = tree.prove(&ContentAddress([0; 32]), &[1]);
+    assert_eq!(proven.proof, StateProof::default());
+    assert!(proven
+        .proof
+        .verify(&EMPTY, &ContentAddress([0; 32]), &[1], &[]));
+}
+
+#[test]
+fn proofs_verify_against_root() {
+    let tree = test_tree();
+    let root = tree.root();
+    assert_ne!(root, EMPTY);
+    for (contract, key) in [
+        ([0; 32], vec![1, 3]),
+        ([1; 32], vec![1, 0]),
+        ([2; 32], vec![1]),
+    ] {
+        let contract = ContentAddress(contract);
+        let proven = tree.prove(&contract, &key);
+        assert_eq!(
+            &proven.value,
+            tree.get(&contract, &key).unwrap_or(&Value::default())
+        );
+        assert!(proven.proof.verify(&root, &contract, &key, &proven.value));
+        // The proof does not hold for any other value or key.
+        assert!(!proven.proof.verify(&root, &contract, &key, &[7]));
+        assert!(!proven.proof.verify(&root, &contract, &[9], &proven.value));
+    }
+
+    // Removing a value changes the root, and setting it again restores it.
+    let mut tree = tree;
+    tree.insert(&ContentAddress([1; 32]), &[1, 0], [].into());
+    assert_ne!(tree.root(), root);
+    tree.insert(&ContentAddress([1; 32]), &[1, 0], [42].into());
+    assert_eq!(tree.root(), root);
+}
+
+#[test]
+fn verified_state_read() {
+    let tree = test_tree();
+    let root = tree.root();
+    let state = VerifiedStateRead::new(tree, root);
+    let values = state
+        .key_range(ContentAddress([0; 32]), [1, 14].into(), 3)
+        .unwrap();
+    let expected: Vec<Value> = vec![[14, 28].into(), [15, 30].into(), [].into()];
+    assert_eq!(values, expected);
+
+    // The wrong root fails verification.
+    let state = VerifiedStateRead::new(test_tree(), [1; 32]);
+    let err = state
+        .key_range(ContentAddress([0; 32]), [1, 0].into(), 1)
+        .unwrap_err();
+    assert!(matches!(err, ProofError::InvalidProof { .. }));
+
+    let err = state
+        .keys_with_prefix(ContentAddress([0; 32]), [1].into(), 1)
+        .unwrap_err();
+    assert!(matches!(err, ProofError::PrefixUnsupported));
+}
+
+#[test]
+fn tampered_reads_are_rejected() {
+    let root = test_tree().root();
+    let contract = ContentAddress([0; 32]);
+
+    // A modified value.
+    let state = VerifiedStateRead::new(
+        Tampered(test_tree(), |v: &mut Vec<ProvenValue>| {
+            v[1].value = [2, 2].into()
+        }),
+        root,
+    );
+    let err = state
+        .key_range(contract.clone(), [1, 0].into(), 2)
+        .unwrap_err();
+    assert!(matches!(err, ProofError::InvalidProof { key, .. } if key == [1, 1]));
+
+    // A value hidden as absent.
+    let state = VerifiedStateRead::new(
+        Tampered(test_tree(), |v: &mut Vec<ProvenValue>| {
+            v[0].value = Value::default()
+        }),
+        root,
+    );
+    let err = state
+        .key_range(contract.clone(), [1, 0].into(), 1)
+        .unwrap_err();
+    assert!(matches!(err, ProofError::InvalidProof { .. }));
+
+    // A missing value.
+    let state = VerifiedStateRead::new(
+        Tampered(test_tree(), |v: &mut Vec<ProvenValue>| {
+            v.pop();
+        }),
+        root,
+    );
+    let err = state.key_range(contract, [1, 0].into(), 2).unwrap_err();
+    assert!(matches!(
+        err,
+        ProofError::NumValues {
+            expected: 2,
+            found: 1
+        }
+    ));
+}
@@ -64,6 +64,7 @@ fn test_throughput() {
         let node = |program_address, edge_start| Node {
             program_address,
             edge_start,
+            max_gas: None,
         };
         let nodes = vec![node(p_ca.clone(), Edge::MAX)];
         let edges = vec![];
@@ -77,7 +78,7 @@ fn test_throughput() {
         let set = SolutionSet {
             solutions: vec![Solution {
                 predicate_to_solve: pred_addr_0.clone(),
-                predicate_data: vec![vec![2]],
+                predicate_data: vec![[2].into()],
                 state_mutations: vec![],
             }],
         };
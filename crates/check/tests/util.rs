@@ -1,11 +1,12 @@
 use essential_check::{
     sign::secp256k1::{PublicKey, Secp256k1, SecretKey},
-    types::{solution::SolutionSet, ContentAddress, Key, PredicateAddress, Word},
-    vm::StateRead,
+    types::{solution::SolutionSet, ContentAddress, Key, PredicateAddress, Value, Word},
+    vm::{asm, StateRead},
 };
 use essential_types::{
     contract::{self, Contract},
-    predicate::Predicate,
+    predicate::{Predicate, Program},
+    solution::{encode::encode_mutations, Mutation},
 };
 use essential_vm::StateReads;
 use std::collections::BTreeMap;
@@ -13,13 +14,13 @@ use thiserror::Error;
 
 // A test `StateRead` implementation represented using a map.
 #[derive(Clone, Debug)]
-pub struct State(BTreeMap<ContentAddress, BTreeMap<Key, Vec<Word>>>);
+pub struct State(BTreeMap<ContentAddress, BTreeMap<Key, Value>>);
 
 #[derive(Debug, Error)]
 #[error("no value for the given contract, key pair")]
 pub struct InvalidStateRead;
 
-pub type Kv = (Key, Vec<Word>);
+pub type Kv = (Key, Value);
 
 impl State {
     // Empry state, fine for tests unrelated to reading state.
@@ -39,7 +40,7 @@ impl State {
     }
 
     // Update the value at the given key within the given contract address.
-    pub fn set(&mut self, contract_addr: ContentAddress, key: &Key, value: Vec<Word>) {
+    pub fn set(&mut self, contract_addr: ContentAddress, key: &Key, value: Value) {
         let contract = self.0.entry(contract_addr).or_default();
         if value.is_empty() {
             contract.remove(key);
@@ -58,21 +59,7 @@ impl State {
         contract_addr: ContentAddress,
         mut key: Key,
         num_words: usize,
-    ) -> Result<Vec<Vec<Word>>, InvalidStateRead> {
-        // Get the key that follows this one.
-        fn next_key(mut key: Key) -> Option<Key> {
-            for w in key.iter_mut().rev() {
-                match *w {
-                    Word::MAX => *w = Word::MIN,
-                    _ => {
-                        *w += 1;
-                        return Some(key);
-                    }
-                }
-            }
-            None
-        }
-
+    ) -> Result<Vec<Value>, InvalidStateRead> {
         let contract = match self.get(&contract_addr) {
             None => return Err(InvalidStateRead),
             Some(contract) => contract,
@@ -83,7 +70,7 @@ impl State {
         for _ in 0..num_words {
             let opt = contract.get(&key).cloned().unwrap_or_default();
             words.push(opt);
-            key = next_key(key).ok_or(InvalidStateRead)?;
+            key = key.next_key().ok_or(InvalidStateRead)?;
         }
         Ok(words)
     }
@@ -103,7 +90,7 @@ impl State {
 }
 
 impl core::ops::Deref for State {
-    type Target = BTreeMap<ContentAddress, BTreeMap<Key, Vec<Word>>>;
+    type Target = BTreeMap<ContentAddress, BTreeMap<Key, Value>>;
     fn deref(&self) -> &Self::Target {
         &self.0
     }
@@ -116,9 +103,27 @@ impl StateRead for State {
         contract_addr: ContentAddress,
         key: Key,
         num_words: usize,
-    ) -> Result<Vec<Vec<Word>>, Self::Error> {
+    ) -> Result<Vec<Value>, Self::Error> {
         self.key_range(contract_addr, key, num_words)
     }
+
+    fn keys_with_prefix(
+        &self,
+        contract_addr: ContentAddress,
+        prefix: Key,
+        limit: usize,
+    ) -> Result<Vec<Key>, Self::Error> {
+        let Some(contract) = self.get(&contract_addr) else {
+            return Ok(vec![]);
+        };
+        Ok(contract
+            .range(prefix.clone()..)
+            .map(|(key, _)| key)
+            .take_while(|key| key.starts_with(&prefix))
+            .take(limit)
+            .cloned()
+            .collect())
+    }
 }
 
 impl StateReads for State {
@@ -147,6 +152,22 @@ pub fn empty_contract() -> Contract {
     Contract::without_salt(vec![empty_predicate()])
 }
 
+// A program that stores the given words in memory and halts with the given
+// output stack.
+pub fn store_words_program(words: Vec<Word>, out: &[Word]) -> Program {
+    use asm::short::*;
+    let len = words.len() as Word;
+    let mut ops: Vec<_> = words.into_iter().map(PUSH).collect();
+    ops.extend([PUSH(len), PUSH(len), ALOC, STOR]);
+    ops.extend(out.iter().copied().map(PUSH));
+    Program(asm::to_bytes(ops).collect())
+}
+
+// A program that outputs the given mutations.
+pub fn mutations_program(mutations: &[Mutation]) -> Program {
+    store_words_program(encode_mutations(mutations), &[2])
+}
+
 pub fn random_keypair(seed: [u8; 32]) -> (SecretKey, PublicKey) {
     use rand::SeedableRng;
     let mut rng = rand::rngs::SmallRng::from_seed(seed);
@@ -1,6 +1,6 @@
-use crate::Address;
+use crate::{domain, Address};
 use essential_types::{
-    contract::Contract,
+    contract::{Contract, ContractMetadata},
     predicate::{Predicate, Program},
     solution::{Solution, SolutionSet},
     ContentAddress,
@@ -13,13 +13,13 @@ impl Address for Predicate {
             return ContentAddress([0; 32]);
         };
         let bytes: Vec<_> = bytes.collect();
-        ContentAddress(crate::hash_bytes(&bytes))
+        ContentAddress(domain::hash_bytes(domain::PREDICATE, &bytes))
     }
 }
 
 impl Address for Program {
     fn content_address(&self) -> ContentAddress {
-        ContentAddress(crate::hash_bytes(&self.0))
+        ContentAddress(domain::hash_bytes(domain::PROGRAM, &self.0))
     }
 }
 
@@ -29,9 +29,17 @@ impl Address for Contract {
     }
 }
 
+impl Address for ContractMetadata {
+    fn content_address(&self) -> ContentAddress {
+        let data = crate::serialize(self);
+        ContentAddress(domain::hash_bytes(domain::CONTRACT_METADATA, &data))
+    }
+}
+
 impl Address for Solution {
     fn content_address(&self) -> ContentAddress {
-        ContentAddress(crate::hash(self))
+        let data = crate::serialize(self);
+        ContentAddress(domain::hash_bytes(domain::SOLUTION, &data))
     }
 }
 
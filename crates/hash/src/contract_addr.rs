@@ -3,6 +3,13 @@
 //!
 //! See the [`PredicateAddress`][essential_types::PredicateAddress] documentation for
 //! more information on the requirements behind the contract content address.
+//!
+//! A contract's [`metadata`][essential_types::contract::ContractWithMetadata]
+//! is not part of its content address. The metadata has its own content address, produced by hashing it
+//! with [`content_addr`][crate::content_addr].
+//!
+//! With the `rayon` feature, [`par_from_contract`] hashes the predicates of
+//! large contracts in parallel.
 
 use essential_types::{contract::Contract, ContentAddress, Hash};
 
@@ -16,6 +23,19 @@ pub fn from_contract(contract: &Contract) -> ContentAddress {
     from_predicate_addrs(predicate_addrs, &contract.salt)
 }
 
+/// Equivalent to [`from_contract`], but hashes the predicates in parallel on
+/// the current rayon thread pool.
+#[cfg(feature = "rayon")]
+pub fn par_from_contract(contract: &Contract) -> ContentAddress {
+    use rayon::prelude::*;
+    let mut predicate_addrs: Vec<_> = contract
+        .predicates
+        .par_iter()
+        .map(crate::content_addr)
+        .collect();
+    from_predicate_addrs_slice(&mut predicate_addrs, &contract.salt)
+}
+
 /// Given the predicate content address for each predicate in the contract, produce the
 /// contract's content address.
 ///
@@ -43,7 +63,8 @@ pub fn from_predicate_addrs_slice(
     salt: &Hash,
 ) -> ContentAddress {
     predicate_addrs.sort();
-    ContentAddress(crate::hash_bytes_iter(
+    ContentAddress(crate::domain::hash_bytes_iter(
+        crate::domain::CONTRACT,
         predicate_addrs
             .iter()
             .map(|addr| addr.0.as_slice())
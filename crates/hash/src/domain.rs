@@ -0,0 +1,47 @@
+//! Domain separation tags for content addressing.
+//!
+//! Each kind of addressed item is hashed with a distinct tag, so that
+//! byte-identical pre-hash serializations of different types (e.g. a program
+//! whose bytes happen to be a valid predicate encoding) never share an address.
+//!
+//! An item's content address is the SHA-256 of its tag's [`prefix`] followed
+//! by the item's pre-hash data. As the prefix is itself a hash, it has a fixed
+//! length and no tag's prefix can be a prefix of another's data.
+//!
+//! The version suffix of each tag is incremented whenever the pre-hash data
+//! of the item changes.
+
+use essential_types::Hash;
+
+/// The tag of [`Predicate`][essential_types::predicate::Predicate] addresses.
+pub const PREDICATE: &str = "essential/predicate/v1";
+/// The tag of [`Program`][essential_types::predicate::Program] addresses.
+pub const PROGRAM: &str = "essential/program/v1";
+/// The tag of [`Contract`][essential_types::contract::Contract] addresses.
+pub const CONTRACT: &str = "essential/contract/v1";
+/// The tag of [`ContractMetadata`][essential_types::contract::ContractMetadata] addresses.
+pub const CONTRACT_METADATA: &str = "essential/contract-metadata/v1";
+/// The tag of [`Solution`][essential_types::solution::Solution] addresses.
+pub const SOLUTION: &str = "essential/solution/v1";
+/// The tag of [`SolutionSet`][essential_types::solution::SolutionSet] addresses.
+pub const SOLUTION_SET: &str = "essential/solution-set/v1";
+
+/// The bytes hashed prior to the data of the given tag's domain.
+pub fn prefix(tag: &str) -> Hash {
+    crate::hash_bytes(tag.as_bytes())
+}
+
+/// Hash the bytes yielded by the iterator within the given tag's domain.
+pub fn hash_bytes_iter<'i>(tag: &str, iter: impl IntoIterator<Item = &'i [u8]>) -> Hash {
+    let mut hasher = <sha2::Sha256 as sha2::Digest>::new();
+    sha2::Digest::update(&mut hasher, prefix(tag));
+    for bytes in iter {
+        sha2::Digest::update(&mut hasher, bytes);
+    }
+    sha2::Digest::finalize(hasher).into()
+}
+
+/// Hash the bytes within the given tag's domain.
+pub fn hash_bytes(tag: &str, bytes: &[u8]) -> Hash {
+    hash_bytes_iter(tag, [bytes])
+}
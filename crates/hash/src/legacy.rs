@@ -0,0 +1,127 @@
+//! Content addresses as derived prior to [domain separation][crate::domain].
+//!
+//! These exist solely to migrate data keyed by legacy addresses, e.g. to
+//! re-key stored contracts or to resolve references recorded before the
+//! change. New addresses must always be derived with [`content_addr`][crate::content_addr].
+//!
+//! ```
+//! use essential_hash::legacy;
+//! use essential_types::{contract::Contract, predicate::Predicate};
+//!
+//! let contract = Contract::without_salt(vec![Predicate::default()]);
+//! let migration = legacy::migrate(&contract);
+//! assert_eq!(migration.legacy, legacy::content_addr(&contract));
+//! assert_eq!(migration.current, essential_hash::content_addr(&contract));
+//! assert_ne!(migration.legacy, migration.current);
+//! ```
+
+use crate::Address;
+use essential_types::{
+    contract::{Contract, ContractMetadata},
+    predicate::{Predicate, Program},
+    solution::{Solution, SolutionSet},
+    ContentAddress, PredicateAddress,
+};
+
+/// Produce the legacy content address of a type.
+pub trait LegacyAddress {
+    /// Produce the content address for self without domain separation.
+    fn legacy_content_address(&self) -> ContentAddress;
+}
+
+/// The legacy and current content addresses of an item.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Migration<T = ContentAddress> {
+    /// The address without domain separation.
+    pub legacy: T,
+    /// The address with domain separation.
+    pub current: T,
+}
+
+/// Shorthand for producing the legacy content address of the given value.
+pub fn content_addr<T: LegacyAddress>(t: &T) -> ContentAddress {
+    t.legacy_content_address()
+}
+
+/// The legacy and current content addresses of the given value.
+pub fn migrate<T: Address + LegacyAddress>(t: &T) -> Migration {
+    Migration {
+        legacy: t.legacy_content_address(),
+        current: t.content_address(),
+    }
+}
+
+/// The legacy and current addresses of each of the contract's predicates, in
+/// the order of the contract's predicates.
+pub fn migrate_predicate_addrs(contract: &Contract) -> Vec<Migration<PredicateAddress>> {
+    let contract_addrs = migrate(contract);
+    contract
+        .predicates
+        .iter()
+        .map(|predicate| {
+            let predicate_addrs = migrate(predicate);
+            Migration {
+                legacy: PredicateAddress {
+                    contract: contract_addrs.legacy.clone(),
+                    predicate: predicate_addrs.legacy,
+                },
+                current: PredicateAddress {
+                    contract: contract_addrs.current.clone(),
+                    predicate: predicate_addrs.current,
+                },
+            }
+        })
+        .collect()
+}
+
+impl LegacyAddress for Predicate {
+    fn legacy_content_address(&self) -> ContentAddress {
+        let Ok(bytes) = self.encode() else {
+            // Invalid predicates can't be hashed.
+            return ContentAddress([0; 32]);
+        };
+        let bytes: Vec<_> = bytes.collect();
+        ContentAddress(crate::hash_bytes(&bytes))
+    }
+}
+
+impl LegacyAddress for Program {
+    fn legacy_content_address(&self) -> ContentAddress {
+        ContentAddress(crate::hash_bytes(&self.0))
+    }
+}
+
+impl LegacyAddress for Contract {
+    fn legacy_content_address(&self) -> ContentAddress {
+        let mut predicate_addrs: Vec<_> = self.predicates.iter().map(content_addr).collect();
+        predicate_addrs.sort();
+        ContentAddress(crate::hash_bytes_iter(
+            predicate_addrs
+                .iter()
+                .map(|addr| addr.0.as_slice())
+                .chain(Some(self.salt.as_slice())),
+        ))
+    }
+}
+
+impl LegacyAddress for ContractMetadata {
+    fn legacy_content_address(&self) -> ContentAddress {
+        ContentAddress(crate::hash(self))
+    }
+}
+
+impl LegacyAddress for Solution {
+    fn legacy_content_address(&self) -> ContentAddress {
+        ContentAddress(crate::hash(self))
+    }
+}
+
+impl LegacyAddress for SolutionSet {
+    fn legacy_content_address(&self) -> ContentAddress {
+        let mut solution_addrs: Vec<_> = self.solutions.iter().map(content_addr).collect();
+        solution_addrs.sort();
+        ContentAddress(crate::hash_bytes_iter(
+            solution_addrs.iter().map(|addr| &addr.0[..]),
+        ))
+    }
+}
@@ -1,5 +1,8 @@
 //! A minimal crate containing Essential's [`hash`] function and associated pre-hash
 //! generic serialization implementation [`serialize`] based on [`postcard`].
+//!
+//! The `rayon` feature enables parallel computation of the content addresses
+//! of large contracts and solution sets.
 
 #![deny(missing_docs)]
 #![deny(unsafe_code)]
@@ -10,6 +13,9 @@ use sha2::Digest;
 
 mod address_impl;
 pub mod contract_addr;
+pub mod domain;
+pub mod legacy;
+pub mod merkle;
 pub mod solution_set_addr;
 
 /// Standardized trait for creating content addresses for
@@ -41,7 +47,8 @@ pub fn hash<T: Serialize>(t: &T) -> Hash {
 
 /// Shorthand for hashing the given value in order to produce its content address.
 ///
-/// Commonly useful for solutions, predicates and contracts.
+/// Commonly useful for solutions, predicates and contracts. Each type is
+/// hashed within its own [`domain`].
 pub fn content_addr<T: Address>(t: &T) -> ContentAddress {
     t.content_address()
 }
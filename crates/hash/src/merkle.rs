@@ -0,0 +1,191 @@
+//! A binary Merkle tree for committing to a list of [`Value`]s or
+//! [`Hash`][type@Hash]es, with proofs that match the VM's
+//! `Crypto::VerifyMerkle` operation.
+//!
+//! This allows a large off-chain dataset to be committed to with a single
+//! root, with predicates verifying only the items they require.
+//!
+//! # Construction
+//! - The hash of a [`Value`] leaf is its [`hash_words`], i.e. the same as
+//!   `Crypto::Sha256` of the value's words.
+//! - The leaves are padded with [`EMPTY`] to the next power of two.
+//! - Each parent is the SHA-256 of [`NODE_PREFIX`], the left child and the
+//!   right child. As the hashed values of leaves are a multiple of 8 bytes
+//!   long, the prefix ensures a leaf can never be mistaken for a parent.
+//! - The root of an empty tree is [`EMPTY`] and the root of a single leaf
+//!   tree is the leaf.
+//!
+//! A [`MerkleProof`] holds the siblings of a leaf from the bottom of the tree
+//! up. The bits of the leaf's index select whether each sibling lies to its
+//! left (`1`) or right (`0`).
+//!
+//! ```
+//! use essential_hash::merkle::{self, MerkleTree};
+//! use essential_types::Value;
+//!
+//! let values: Vec<Value> = vec![[1].into(), [2, 3].into(), [4].into()];
+//! let tree = MerkleTree::from_values(&values);
+//! let proof = tree.proof(1).unwrap();
+//! assert!(proof.verify(&tree.root(), &merkle::leaf(&values[1])));
+//! assert!(!proof.verify(&tree.root(), &merkle::leaf(&values[0])));
+//! ```
+//!
+//! [`Value`]: essential_types::Value
+
+use crate::hash_words;
+use essential_types::{convert::word_4_from_u8_32, Hash, Word};
+
+/// The hash of an empty tree, and of the leaves padding a tree to a power of two.
+pub const EMPTY: Hash = [0; 32];
+
+/// The byte prefixed to the children of a parent prior to hashing.
+pub const NODE_PREFIX: u8 = 1;
+
+/// The maximum depth of a proof, such that the leaf index fits in a [`Word`].
+pub const MAX_DEPTH: usize = 63;
+
+/// A binary Merkle tree.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MerkleTree {
+    /// The number of leaves, excluding padding.
+    len: usize,
+    /// Each level of the tree from the padded leaves up to the root.
+    levels: Vec<Vec<Hash>>,
+}
+
+/// A proof that a leaf is included in a [`MerkleTree`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MerkleProof {
+    /// The index of the leaf.
+    pub index: u64,
+    /// The siblings of the leaf and its ancestors, from the bottom of the tree up.
+    pub siblings: Vec<Hash>,
+}
+
+/// The hash of a leaf value.
+pub fn leaf(value: &[Word]) -> Hash {
+    hash_words(value)
+}
+
+/// The hash of a parent with the given children.
+pub fn node(left: &Hash, right: &Hash) -> Hash {
+    crate::hash_bytes_iter([&[NODE_PREFIX][..], left, right])
+}
+
+/// Verify that the `leaf` hash is included in the tree with the given `root`.
+///
+/// Shorthand for [`MerkleProof::verify`].
+pub fn verify(root: &Hash, leaf: &Hash, proof: &MerkleProof) -> bool {
+    proof.verify(root, leaf)
+}
+
+impl MerkleTree {
+    /// Construct a tree from the hashes of its leaves.
+    pub fn new(leaves: impl IntoIterator<Item = Hash>) -> Self {
+        let mut leaves: Vec<Hash> = leaves.into_iter().collect();
+        let len = leaves.len();
+        if len == 0 {
+            return Self::default();
+        }
+        leaves.resize(len.next_power_of_two(), EMPTY);
+        let mut levels = vec![leaves];
+        while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+            let parents = level
+                .chunks_exact(2)
+                .map(|pair| node(&pair[0], &pair[1]))
+                .collect();
+            levels.push(parents);
+        }
+        Self { len, levels }
+    }
+
+    /// Construct a tree from leaf values, hashing each with [`leaf`].
+    pub fn from_values<V: AsRef<[Word]>>(values: impl IntoIterator<Item = V>) -> Self {
+        Self::new(values.into_iter().map(|value| leaf(value.as_ref())))
+    }
+
+    /// The root of the tree.
+    pub fn root(&self) -> Hash {
+        self.levels
+            .last()
+            .and_then(|level| level.first())
+            .copied()
+            .unwrap_or(EMPTY)
+    }
+
+    /// The number of leaves, excluding padding.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the tree has no leaves.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of siblings in each proof.
+    pub fn depth(&self) -> usize {
+        self.levels.len().saturating_sub(1)
+    }
+
+    /// The hash of the leaf at the given index.
+    pub fn leaf(&self, index: usize) -> Option<&Hash> {
+        self.levels.first()?.get(index).filter(|_| index < self.len)
+    }
+
+    /// A proof of the leaf at the given index, or `None` if out of bounds.
+    pub fn proof(&self, index: usize) -> Option<MerkleProof> {
+        if index >= self.len {
+            return None;
+        }
+        let siblings = self.levels[..self.depth()]
+            .iter()
+            .enumerate()
+            .map(|(height, level)| level[(index >> height) ^ 1])
+            .collect();
+        Some(MerkleProof {
+            index: index as u64,
+            siblings,
+        })
+    }
+}
+
+impl MerkleProof {
+    /// The root of the tree containing the `leaf` hash at this proof's index.
+    ///
+    /// Returns `None` if the proof is deeper than [`MAX_DEPTH`] or the index
+    /// does not fit within its depth.
+    pub fn root(&self, leaf: &Hash) -> Option<Hash> {
+        let depth = self.siblings.len();
+        if depth > MAX_DEPTH || self.index >> depth != 0 {
+            return None;
+        }
+        let root = self
+            .siblings
+            .iter()
+            .enumerate()
+            .fold(*leaf, |hash, (height, sibling)| {
+                if self.index >> height & 1 == 0 {
+                    node(&hash, sibling)
+                } else {
+                    node(sibling, &hash)
+                }
+            });
+        Some(root)
+    }
+
+    /// Verify that the `leaf` hash is included in the tree with the given `root`.
+    pub fn verify(&self, root: &Hash, leaf: &Hash) -> bool {
+        self.root(leaf).as_ref() == Some(root)
+    }
+
+    /// The proof as words, in the order expected by `Crypto::VerifyMerkle`
+    /// following the root and leaf: `[index, ...siblings, n]`.
+    pub fn to_words(&self) -> Vec<Word> {
+        let n = self.siblings.len() as Word;
+        core::iter::once(self.index as Word)
+            .chain(self.siblings.iter().copied().flat_map(word_4_from_u8_32))
+            .chain(core::iter::once(n))
+            .collect()
+    }
+}
@@ -12,6 +12,15 @@ pub fn from_set(set: &SolutionSet) -> ContentAddress {
     from_solution_addrs(solution_addrs)
 }
 
+/// Equivalent to [`from_set`], but hashes the solutions in parallel on the
+/// current rayon thread pool.
+#[cfg(feature = "rayon")]
+pub fn par_from_set(set: &SolutionSet) -> ContentAddress {
+    use rayon::prelude::*;
+    let mut solution_addrs: Vec<_> = set.solutions.par_iter().map(crate::content_addr).collect();
+    from_solution_addrs_slice(&mut solution_addrs)
+}
+
 /// Given the content address for each `Solution` in the `SolutionSet`, produce the
 /// solution set's content address.
 ///
@@ -35,7 +44,8 @@ pub fn from_solution_addrs(
 /// of its inner `Solution`s does not matter).
 pub fn from_solution_addrs_slice(solution_addrs: &mut [ContentAddress]) -> ContentAddress {
     solution_addrs.sort();
-    ContentAddress(crate::hash_bytes_iter(
+    ContentAddress(crate::domain::hash_bytes_iter(
+        crate::domain::SOLUTION_SET,
         solution_addrs.iter().map(|addr| &addr.0[..]),
     ))
 }
@@ -1,5 +1,9 @@
-use essential_hash::hash_bytes;
-use essential_types::{contract::Contract, predicate::Predicate, ContentAddress};
+use essential_hash::{domain, hash_bytes};
+use essential_types::{
+    contract::{Contract, ContractMetadata},
+    predicate::Predicate,
+    ContentAddress,
+};
 
 fn test_predicate() -> Predicate {
     Predicate::default()
@@ -26,7 +30,7 @@ fn test_content_addr() {
     let pred = &test_predicate();
     let bytes = pred.encode().unwrap();
     let bytes: Vec<_> = bytes.collect();
-    let addr = ContentAddress(hash_bytes(&bytes));
+    let addr = ContentAddress(domain::hash_bytes(domain::PREDICATE, &bytes));
     let content_addr = essential_hash::content_addr(&test_predicate());
     assert_eq!(content_addr, addr);
 
@@ -38,3 +42,189 @@ fn test_content_addr() {
     let content_addr = essential_hash::content_addr(&contract);
     assert_eq!(content_addr, addr);
 }
+
+#[test]
+fn metadata_excluded_from_contract_addr() {
+    let contract = Contract::without_salt(vec![test_predicate()]);
+    let metadata = ContractMetadata {
+        name: "counter".into(),
+        version: "1.0.0".into(),
+        abi_hash: [1; 32],
+        source_commit: Some("0123abc".into()),
+    };
+    let with_metadata = contract.clone().with_metadata(metadata.clone());
+    assert_eq!(
+        essential_hash::content_addr(&contract),
+        essential_hash::content_addr(&with_metadata.contract)
+    );
+    assert_eq!(
+        essential_hash::hash(&contract),
+        essential_hash::hash(&with_metadata.contract)
+    );
+
+    // The contract's serialized layout is just its predicates and salt.
+    let empty = Contract {
+        predicates: vec![],
+        salt: [7; 32],
+    };
+    let mut bytes = vec![0, 32];
+    bytes.extend([7; 32]);
+    assert_eq!(essential_hash::serialize(&empty), bytes);
+    assert_eq!(postcard::from_bytes::<Contract>(&bytes).unwrap(), empty);
+
+    let mut changed = metadata.clone();
+    changed.version = "1.0.1".into();
+    assert_ne!(
+        essential_hash::content_addr(&metadata),
+        essential_hash::content_addr(&changed)
+    );
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn par_content_addrs() {
+    use essential_types::{
+        predicate::{Node, Predicate},
+        solution::{Solution, SolutionSet},
+        PredicateAddress,
+    };
+
+    let predicates: Vec<_> = (0..64u8)
+        .map(|i| Predicate {
+            nodes: vec![Node {
+                edge_start: essential_types::predicate::Edge::MAX,
+                program_address: ContentAddress([i; 32]),
+                max_gas: None,
+            }],
+            edges: vec![],
+        })
+        .collect();
+    let contract = Contract::without_salt(predicates);
+    assert_eq!(
+        essential_hash::contract_addr::par_from_contract(&contract),
+        essential_hash::contract_addr::from_contract(&contract),
+    );
+
+    let solutions = (0..64)
+        .map(|i| Solution {
+            predicate_to_solve: PredicateAddress {
+                contract: ContentAddress([0; 32]),
+                predicate: ContentAddress([1; 32]),
+            },
+            predicate_data: vec![[i].into()],
+            state_mutations: vec![],
+        })
+        .collect();
+    let set = SolutionSet { solutions };
+    assert_eq!(
+        essential_hash::solution_set_addr::par_from_set(&set),
+        essential_hash::solution_set_addr::from_set(&set),
+    );
+}
+
+#[test]
+fn domain_separated_addrs() {
+    use essential_hash::legacy;
+    use essential_types::predicate::Program;
+
+    // A program whose bytes are a predicate's encoding has a different address.
+    let predicate = test_predicate();
+    let program = Program(predicate.encode().unwrap().collect());
+    assert_eq!(
+        legacy::content_addr(&predicate),
+        legacy::content_addr(&program)
+    );
+    assert_ne!(
+        essential_hash::content_addr(&predicate),
+        essential_hash::content_addr(&program)
+    );
+
+    let migration = legacy::migrate(&program);
+    assert_eq!(migration.legacy, ContentAddress(hash_bytes(&program.0)));
+    assert_eq!(
+        migration.current,
+        ContentAddress(domain::hash_bytes(domain::PROGRAM, &program.0))
+    );
+}
+
+#[test]
+fn migrate_predicate_addrs() {
+    use essential_hash::legacy;
+
+    let contract = Contract::without_salt(vec![test_predicate()]);
+    let migrations = legacy::migrate_predicate_addrs(&contract);
+    assert_eq!(migrations.len(), 1);
+    assert_eq!(
+        migrations[0].legacy.contract,
+        legacy::content_addr(&contract)
+    );
+    assert_eq!(
+        migrations[0].legacy.predicate,
+        legacy::content_addr(&contract.predicates[0])
+    );
+    assert_eq!(
+        migrations[0].current.contract,
+        essential_hash::content_addr(&contract)
+    );
+    assert_eq!(
+        migrations[0].current.predicate,
+        essential_hash::content_addr(&contract.predicates[0])
+    );
+}
+
+#[test]
+fn legacy_addrs_pinned() {
+    use essential_hash::legacy;
+    use essential_types::predicate::Node;
+
+    // Addresses derived before nodes could specify a max gas.
+    let node = |edge_start, program_address| Node {
+        edge_start,
+        program_address: ContentAddress(program_address),
+        max_gas: None,
+    };
+    let predicate = Predicate {
+        nodes: vec![node(0, [1; 32]), node(u16::MAX, [2; 32])],
+        edges: vec![1],
+    };
+    let contract = Contract {
+        predicates: vec![predicate.clone()],
+        salt: [3; 32],
+    };
+    assert_eq!(
+        hex::encode(legacy::content_addr(&predicate).0),
+        "f55633ba6289f548269911318fe08f81d424f95645cf5527e4eb481bab05a5a6"
+    );
+    assert_eq!(
+        hex::encode(legacy::content_addr(&contract).0),
+        "c251d5eab01d600f353e8b4ea6334f6c15e427685512a9f82b06106c17898bbf"
+    );
+
+    // Specifying a max gas changes the address.
+    let mut limited = predicate.clone();
+    limited.nodes[1].max_gas = Some(1_000);
+    assert_ne!(
+        legacy::content_addr(&limited),
+        legacy::content_addr(&predicate)
+    );
+}
+
+#[test]
+fn postcard_roundtrip_predicate() {
+    use essential_types::predicate::Node;
+
+    let node = |edge_start, max_gas| Node {
+        edge_start,
+        program_address: ContentAddress([1; 32]),
+        max_gas,
+    };
+    let predicate = Predicate {
+        nodes: vec![node(0, None), node(u16::MAX, Some(1_000))],
+        edges: vec![1],
+    };
+    let bytes = essential_hash::serialize(&predicate);
+    assert_eq!(
+        postcard::from_bytes::<Predicate>(&bytes).unwrap(),
+        predicate
+    );
+}
@@ -0,0 +1,95 @@
+use essential_hash::merkle::{self, MerkleProof, MerkleTree, EMPTY};
+use essential_types::{Hash, Value};
+
+fn values(n: usize) -> Vec<Value> {
+    (0..n as i64).map(|i| [i, -i].into()).collect()
+}
+
+#[test]
+fn roots() {
+    assert_eq!(MerkleTree::new([]).root(), EMPTY);
+    assert!(MerkleTree::new([]).proof(0).is_none());
+
+    let leaf = merkle::leaf(&[1, 2]);
+    let tree = MerkleTree::new([leaf]);
+    assert_eq!(tree.root(), leaf);
+    assert_eq!(tree.depth(), 0);
+
+    let [a, b, c]: [Hash; 3] = [[1; 32], [2; 32], [3; 32]];
+    let tree = MerkleTree::new([a, b, c]);
+    let expected = merkle::node(&merkle::node(&a, &b), &merkle::node(&c, &EMPTY));
+    assert_eq!(tree.root(), expected);
+    assert_eq!(tree.len(), 3);
+    assert_eq!(tree.depth(), 2);
+    assert_eq!(tree.leaf(2), Some(&c));
+    assert_eq!(tree.leaf(3), None);
+}
+
+#[test]
+fn leaf_is_not_a_node() {
+    // A leaf whose value is the concatenation of two children hashes
+    // differently to their parent.
+    let (a, b) = ([1; 32], [2; 32]);
+    let words: Vec<_> = [a, b]
+        .into_iter()
+        .flat_map(essential_types::convert::word_4_from_u8_32)
+        .collect();
+    assert_ne!(merkle::leaf(&words), merkle::node(&a, &b));
+}
+
+#[test]
+fn proofs() {
+    for n in 1..=9 {
+        let values = values(n);
+        let tree = MerkleTree::from_values(&values);
+        let root = tree.root();
+        for (ix, value) in values.iter().enumerate() {
+            let leaf = merkle::leaf(value);
+            let proof = tree.proof(ix).unwrap();
+            assert_eq!(proof.siblings.len(), tree.depth());
+            assert!(merkle::verify(&root, &leaf, &proof));
+            assert_eq!(proof.root(&leaf), Some(root));
+
+            // The wrong leaf or index fails.
+            let other = merkle::leaf(&values[(ix + 1) % n]);
+            assert_eq!(proof.verify(&root, &other), n == 1);
+            if n > 1 {
+                let moved = MerkleProof {
+                    index: proof.index ^ 1,
+                    ..proof.clone()
+                };
+                assert!(!moved.verify(&root, &leaf));
+            }
+        }
+        assert!(tree.proof(n).is_none());
+    }
+}
+
+#[test]
+fn invalid_proofs() {
+    let proof = MerkleProof {
+        index: 4,
+        siblings: vec![EMPTY; 2],
+    };
+    assert_eq!(proof.root(&EMPTY), None);
+
+    let proof = MerkleProof {
+        index: 0,
+        siblings: vec![EMPTY; merkle::MAX_DEPTH + 1],
+    };
+    assert_eq!(proof.root(&EMPTY), None);
+}
+
+#[test]
+fn to_words() {
+    let proof = MerkleProof {
+        index: 2,
+        siblings: vec![[0; 32], [0xFF; 32]],
+    };
+    let words = proof.to_words();
+    assert_eq!(words.len(), 1 + 2 * 4 + 1);
+    assert_eq!(words[0], 2);
+    assert_eq!(words[1..5], [0; 4]);
+    assert_eq!(words[5..9], [-1; 4]);
+    assert_eq!(words[9], 2);
+}
@@ -0,0 +1,137 @@
+use essential_types::{
+    contract::{Contract, ContractMetadata, ContractWithMetadata, SignedContract},
+    predicate::{Node, Predicate},
+    proto::{self, Message, ProtoError},
+    solution::{Mutation, Solution, SolutionSet},
+    ContentAddress, PredicateAddress, Signature,
+};
+
+fn contract() -> Contract {
+    let node = |edge_start, max_gas| Node {
+        edge_start,
+        program_address: ContentAddress([1; 32]),
+        max_gas,
+    };
+    Contract {
+        predicates: vec![
+            Predicate {
+                nodes: vec![node(0, Some(1_000)), node(u16::MAX, None)],
+                edges: vec![1],
+            },
+            Predicate::default(),
+        ],
+        salt: [2; 32],
+    }
+}
+
+fn metadata() -> ContractMetadata {
+    ContractMetadata {
+        name: "counter".into(),
+        version: "1.0.0".into(),
+        abi_hash: [3; 32],
+        source_commit: None,
+    }
+}
+
+fn solution_set() -> SolutionSet {
+    SolutionSet {
+        solutions: vec![Solution {
+            predicate_to_solve: PredicateAddress {
+                contract: ContentAddress([3; 32]),
+                predicate: ContentAddress([4; 32]),
+            },
+            predicate_data: vec![[1, -2, i64::MIN].into(), [].into()],
+            state_mutations: vec![Mutation {
+                key: [0].into(),
+                value: [i64::MAX].into(),
+            }],
+        }],
+    }
+}
+
+/// Encode to and decode from protobuf bytes.
+fn roundtrip<M: Message + Default>(msg: M) -> M {
+    M::decode(&msg.encode_to_vec()[..]).unwrap()
+}
+
+#[test]
+fn proto_contract_roundtrip() {
+    let contract = contract();
+    let decoded: Contract = roundtrip(proto::Contract::from(contract.clone()))
+        .try_into()
+        .unwrap();
+    assert_eq!(
+        essential_hash::content_addr(&decoded),
+        essential_hash::content_addr(&contract)
+    );
+    assert_eq!(decoded, contract);
+
+    let signed = SignedContract {
+        contract,
+        signature: Signature([5; 64], 1),
+    };
+    let decoded: SignedContract = roundtrip(proto::SignedContract::from(signed.clone()))
+        .try_into()
+        .unwrap();
+    assert_eq!(
+        essential_hash::hash(&decoded),
+        essential_hash::hash(&signed)
+    );
+
+    let with_metadata = signed.contract.with_metadata(metadata());
+    let decoded: ContractWithMetadata =
+        roundtrip(proto::ContractWithMetadata::from(with_metadata.clone()))
+            .try_into()
+            .unwrap();
+    assert_eq!(decoded, with_metadata);
+}
+
+#[test]
+fn proto_solution_set_roundtrip() {
+    let set = solution_set();
+    let decoded: SolutionSet = roundtrip(proto::SolutionSet::from(set.clone()))
+        .try_into()
+        .unwrap();
+    assert_eq!(
+        essential_hash::content_addr(&decoded),
+        essential_hash::content_addr(&set)
+    );
+    assert_eq!(decoded, set);
+}
+
+#[test]
+fn proto_invalid() {
+    let mut msg = proto::Solution::from(solution_set().solutions.remove(0));
+    msg.predicate_to_solve = None;
+    assert_eq!(
+        Solution::try_from(msg),
+        Err(ProtoError::MissingField("predicate_to_solve"))
+    );
+
+    let mut msg = proto::Predicate::from(contract().predicates.remove(0));
+    msg.edges[0] = u32::from(u16::MAX) + 1;
+    assert_eq!(
+        Predicate::try_from(msg),
+        Err(ProtoError::OutOfRange {
+            field: "edges",
+            value: 65536
+        })
+    );
+
+    let mut msg = proto::ContractWithMetadata::from(contract().with_metadata(metadata()));
+    msg.metadata = None;
+    assert_eq!(
+        ContractWithMetadata::try_from(msg),
+        Err(ProtoError::MissingField("metadata"))
+    );
+
+    let mut msg = proto::Contract::from(contract());
+    msg.salt.pop();
+    assert_eq!(
+        Contract::try_from(msg),
+        Err(ProtoError::InvalidLength {
+            field: "salt",
+            len: 31
+        })
+    );
+}
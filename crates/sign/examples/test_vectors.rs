@@ -0,0 +1,37 @@
+//! Generate or verify cross-language test vectors.
+//!
+//! ```text
+//! cargo run -p essential-sign --features test-vectors --example test_vectors -- generate [seed] [count]
+//! cargo run -p essential-sign --features test-vectors --example test_vectors -- verify <path>
+//! ```
+
+use essential_sign::test_vectors::{self, TestVectors};
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(|s| s.as_str()) {
+        Some("generate") => {
+            let seed = args.get(1).map_or(0, |s| s.parse().expect("invalid seed"));
+            let count = args.get(2).map_or(8, |s| s.parse().expect("invalid count"));
+            let vectors = test_vectors::generate(seed, count);
+            let json = serde_json::to_string_pretty(&vectors).expect("failed to serialize");
+            println!("{json}");
+        }
+        Some("verify") => {
+            let path = args.get(1).expect("expected a path to the vectors");
+            let json = std::fs::read_to_string(path).expect("failed to read vectors");
+            let vectors: TestVectors = serde_json::from_str(&json).expect("invalid vectors");
+            match test_vectors::verify(&vectors) {
+                Ok(()) => println!("ok"),
+                Err(err) => {
+                    eprintln!("{err}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        _ => {
+            eprintln!("usage: test_vectors generate [seed] [count] | verify <path>");
+            std::process::exit(1);
+        }
+    }
+}
@@ -8,9 +8,24 @@
 //!
 //! A [`contract::sign`][sign] shorthand function is provided to account for this
 //! special case.
+//!
+//! ## Per-Predicate Signing
+//!
+//! Verifying a contract signature requires every predicate of the contract.
+//! Nodes that only need a single predicate may instead verify a
+//! [`SignedPredicate`], produced by [`prove_predicate`] from a signature over
+//! a [`PredicateCommitment`]. The commitment includes a Merkle root over the
+//! contract's predicate addresses, see [`sign_predicates`].
 
-use essential_types::contract::{self, Contract};
+use crate::signer::Signer;
+use essential_hash::merkle::{MerkleProof, MerkleTree};
+use essential_types::{
+    contract::{self, Contract},
+    predicate::Predicate,
+    ContentAddress, Hash, Signature,
+};
 use secp256k1::{PublicKey, SecretKey};
+use std::fmt;
 
 /// Sign over an contract.
 ///
@@ -29,6 +44,22 @@ pub fn sign(contract: Contract, sk: &SecretKey) -> contract::SignedContract {
     }
 }
 
+/// Sign over a contract with the given [`Signer`].
+///
+/// Equivalent to [`sign`], but delegates signing of the content address to
+/// the signer.
+pub async fn sign_with<S: Signer>(
+    contract: Contract,
+    signer: &S,
+) -> Result<contract::SignedContract, S::Error> {
+    let ca = essential_hash::content_addr(&contract);
+    let signature = signer.sign_hash(ca.0).await?;
+    Ok(contract::SignedContract {
+        contract,
+        signature,
+    })
+}
+
 /// Verifies the signature against the content address of the contract.
 pub fn verify(signed: &contract::SignedContract) -> Result<(), secp256k1::Error> {
     let ca = essential_hash::content_addr(&signed.contract);
@@ -46,3 +77,167 @@ pub fn recover(signed: &contract::SignedContract) -> Result<PublicKey, secp256k1
     let ca = essential_hash::content_addr(&signed.contract);
     crate::recover_hash(ca.0, &signed.signature)
 }
+
+/// The domain separation tag of [`PredicateCommitment`] hashes.
+const PREDICATE_COMMITMENT_TAG: &str = "essential/predicate-commitment/v1";
+
+/// A commitment to a contract and each of its predicates.
+///
+/// Signing the commitment's [`hash`][PredicateCommitment::hash] rather than
+/// the contract's content address allows each predicate to be verified in
+/// isolation, given a [`SignedPredicate`] with its Merkle proof.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PredicateCommitment {
+    /// The content address of the contract.
+    pub contract: ContentAddress,
+    /// The root of the Merkle tree over the contract's sorted predicate
+    /// addresses, see [`predicates_root`].
+    pub predicates_root: Hash,
+}
+
+/// A signature over a [`PredicateCommitment`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SignedCommitment {
+    /// The signed commitment.
+    pub commitment: PredicateCommitment,
+    /// A signature over the commitment's hash.
+    pub signature: Signature,
+}
+
+/// A signed commitment along with a proof that a single predicate belongs
+/// to the committed contract.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignedPredicate {
+    /// The signed commitment.
+    pub commitment: PredicateCommitment,
+    /// A signature over the commitment's hash.
+    pub signature: Signature,
+    /// A proof of the predicate's address within the commitment's
+    /// predicates root.
+    pub proof: MerkleProof,
+}
+
+/// Errors that can occur when verifying a [`SignedPredicate`].
+#[derive(Debug, PartialEq)]
+pub enum PredicateProofError {
+    /// The predicate is not included in the committed contract.
+    NotIncluded,
+    /// The signature is invalid.
+    Secp256k1(secp256k1::Error),
+}
+
+impl PredicateCommitment {
+    /// The hash signed to commit to the contract and its predicates.
+    pub fn hash(&self) -> Hash {
+        essential_hash::domain::hash_bytes_iter(
+            PREDICATE_COMMITMENT_TAG,
+            [&self.contract.0[..], &self.predicates_root[..]],
+        )
+    }
+}
+
+/// The root of the Merkle tree over the contract's predicate addresses.
+///
+/// The addresses are sorted prior to building the tree, so that, like the
+/// contract's content address, the root does not depend on predicate order.
+pub fn predicates_root(contract: &Contract) -> Hash {
+    MerkleTree::new(sorted_predicate_addrs(contract).into_iter().map(|ca| ca.0)).root()
+}
+
+/// Commit to the contract and each of its predicates.
+pub fn commit(contract: &Contract) -> PredicateCommitment {
+    PredicateCommitment {
+        contract: essential_hash::content_addr(contract),
+        predicates_root: predicates_root(contract),
+    }
+}
+
+/// Sign over the commitment to the contract and each of its predicates.
+pub fn sign_predicates(contract: &Contract, sk: &SecretKey) -> SignedCommitment {
+    let commitment = commit(contract);
+    let signature = crate::sign_hash(commitment.hash(), sk);
+    SignedCommitment {
+        commitment,
+        signature,
+    }
+}
+
+/// Produce the proof required to verify the predicate with the given
+/// address in isolation from the rest of the contract.
+///
+/// Returns `None` if the contract has no predicate with the given address.
+pub fn prove_predicate(
+    contract: &Contract,
+    signed: &SignedCommitment,
+    predicate: &ContentAddress,
+) -> Option<SignedPredicate> {
+    let addrs = sorted_predicate_addrs(contract);
+    let index = addrs.binary_search(predicate).ok()?;
+    let tree = MerkleTree::new(addrs.into_iter().map(|ca| ca.0));
+    Some(SignedPredicate {
+        commitment: signed.commitment.clone(),
+        signature: signed.signature.clone(),
+        proof: tree.proof(index)?,
+    })
+}
+
+/// Recovers the public key with which the commitment including the given
+/// predicate was signed.
+pub fn recover_predicate(
+    signed: &SignedPredicate,
+    predicate: &Predicate,
+) -> Result<PublicKey, PredicateProofError> {
+    let leaf = essential_hash::content_addr(predicate).0;
+    if !signed
+        .proof
+        .verify(&signed.commitment.predicates_root, &leaf)
+    {
+        return Err(PredicateProofError::NotIncluded);
+    }
+    crate::recover_hash(signed.commitment.hash(), &signed.signature)
+        .map_err(PredicateProofError::Secp256k1)
+}
+
+/// Verifies that the given predicate is included in the signed commitment
+/// and that the commitment was signed by the given public key.
+pub fn verify_predicate(
+    signed: &SignedPredicate,
+    predicate: &Predicate,
+    pk: &PublicKey,
+) -> Result<(), PredicateProofError> {
+    match recover_predicate(signed, predicate)? {
+        recovered if recovered == *pk => Ok(()),
+        _ => Err(PredicateProofError::Secp256k1(
+            secp256k1::Error::IncorrectSignature,
+        )),
+    }
+}
+
+/// The content addresses of the contract's predicates, sorted.
+fn sorted_predicate_addrs(contract: &Contract) -> Vec<ContentAddress> {
+    let mut addrs: Vec<_> = contract
+        .predicates
+        .iter()
+        .map(essential_hash::content_addr)
+        .collect();
+    addrs.sort();
+    addrs
+}
+
+impl fmt::Display for PredicateProofError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotIncluded => write!(f, "predicate is not included in the commitment"),
+            Self::Secp256k1(err) => write!(f, "invalid commitment signature: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for PredicateProofError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::NotIncluded => None,
+            Self::Secp256k1(err) => Some(err),
+        }
+    }
+}
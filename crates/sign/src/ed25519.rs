@@ -0,0 +1,48 @@
+//! Ed25519 keys and their encoding for the VM's `Crypto::VerifyEd25519`
+//! operation.
+//!
+//! ```
+//! use essential_sign::ed25519;
+//!
+//! let sk = ed25519::signing_key([7; 32]);
+//! let hash = essential_hash::hash_bytes(b"hello");
+//! let signature = essential_sign::sign_hash_ed25519(hash, &sk);
+//! essential_sign::verify_hash_ed25519(hash, &signature, &sk.verifying_key()).unwrap();
+//!
+//! // The words pushed for `Crypto::VerifyEd25519`, following the data.
+//! let sig_words = ed25519::signature(&signature);
+//! let key_words = ed25519::public_key(&sk.verifying_key());
+//! # let _ = (sig_words, key_words);
+//! ```
+
+use essential_types::{
+    convert::{u8_32_from_word_4, u8_64_from_word_8, word_4_from_u8_32, word_8_from_u8_64},
+    Word,
+};
+
+pub use ed25519_dalek::{Signature, SignatureError, SigningKey, VerifyingKey};
+
+/// The signing key with the given 32 byte secret seed.
+pub fn signing_key(seed: [u8; 32]) -> SigningKey {
+    SigningKey::from_bytes(&seed)
+}
+
+/// Encode a public key into 4 words.
+pub fn public_key(pk: &VerifyingKey) -> [Word; 4] {
+    word_4_from_u8_32(pk.to_bytes())
+}
+
+/// Decode a public key from 4 words.
+pub fn public_key_from_words(words: [Word; 4]) -> Result<VerifyingKey, SignatureError> {
+    VerifyingKey::from_bytes(&u8_32_from_word_4(words))
+}
+
+/// Encode a signature into 8 words.
+pub fn signature(sig: &Signature) -> [Word; 8] {
+    word_8_from_u8_64(sig.to_bytes())
+}
+
+/// Decode a signature from 8 words.
+pub fn signature_from_words(words: [Word; 8]) -> Signature {
+    Signature::from_bytes(&u8_64_from_word_8(words))
+}
@@ -1,4 +1,6 @@
 //! Functions for encoding signatures and public keys.
+//!
+//! The [`typed`] module encodes structured typed data for signing.
 
 use essential_types::{
     convert::{bytes_from_word, word_4_from_u8_32, word_8_from_u8_64, word_from_bytes},
@@ -6,6 +8,8 @@ use essential_types::{
 };
 use secp256k1::{ecdsa::RecoverableSignature, PublicKey};
 
+pub mod typed;
+
 /// Encode a secp256k1 public key into 5 words.
 pub fn public_key(pk: &PublicKey) -> [Word; 5] {
     let [start @ .., end] = pk.serialize();
@@ -0,0 +1,410 @@
+//! Structured typed data signing, in the spirit of EIP-712.
+//!
+//! Rather than signing an opaque hash, a signer is presented with a
+//! [`TypedData`] message: a [`Domain`] naming the application it is intended
+//! for, a [`Schema`] describing its structs and the [`Value`] of the message
+//! itself. This allows wallets to display *what* is being signed, while the
+//! [`digest`][TypedData::digest] remains a single hash for [`sign_hash`].
+//!
+//! # Encoding
+//! - The *type string* of a struct is its name followed by its fields as
+//!   `type name` pairs, e.g. `Transfer(b256 to,word amount)`. The type
+//!   strings of all structs it refers to are appended, sorted by name.
+//! - The *type hash* of a struct is the SHA-256 of its type string.
+//! - Each field value is encoded as:
+//!   - `word`: the word's 8 big-endian bytes.
+//!   - `bool`: the word `0` or `1`.
+//!   - `b256`: the 32 bytes.
+//!   - `bytes` and `string`: the SHA-256 of the bytes.
+//!   - `T[]`: the SHA-256 of the concatenated encodings of each element.
+//!   - Structs: the struct hash.
+//! - The *struct hash* of a value is the SHA-256 of its type hash followed by
+//!   the encodings of each of its fields in order.
+//! - The [`Domain::separator`] is the struct hash of the domain.
+//! - The digest is the SHA-256 of the [`DIGEST`] domain tag, followed by
+//!   the domain separator and the struct hash of the message.
+//!
+//! Including the domain separator ensures a signature intended for one
+//! application or contract can not be replayed against another.
+//!
+//! ```
+//! use essential_sign::encode::typed::{Domain, Field, Schema, Type, TypedData, Value};
+//!
+//! let mut schema = Schema::default();
+//! schema.0.insert(
+//!     "Transfer".to_string(),
+//!     vec![Field::new("to", Type::B256), Field::new("amount", Type::Word)],
+//! );
+//! let data = TypedData {
+//!     domain: Domain::new("Token", "1"),
+//!     schema,
+//!     primary_type: "Transfer".to_string(),
+//!     message: Value::Struct(vec![Value::B256([1; 32]), Value::Word(42)]),
+//! };
+//! assert_eq!(
+//!     data.schema.encode_type("Transfer").unwrap(),
+//!     "Transfer(b256 to,word amount)"
+//! );
+//! let digest = data.digest().unwrap();
+//! ```
+//!
+//! [`sign_hash`]: crate::sign_hash
+
+use essential_hash::hash_bytes;
+use essential_types::{ContentAddress, Hash, Signature, Word};
+use secp256k1::{PublicKey, SecretKey};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+/// The domain separation tag of typed data digests.
+pub const DIGEST: &str = "essential/typed-data/v1";
+
+/// The name of the [`Domain`] struct.
+pub const DOMAIN_TYPE: &str = "EssentialDomain";
+
+/// The type of a struct field.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Type {
+    /// A single word.
+    Word,
+    /// A boolean, encoded as a word.
+    Bool,
+    /// 32 bytes, e.g. a hash or content address.
+    B256,
+    /// A dynamically sized byte string.
+    Bytes,
+    /// A UTF-8 string.
+    String,
+    /// A dynamically sized array of the given type.
+    Array(Box<Type>),
+    /// The struct of the given name within the [`Schema`].
+    Struct(String),
+}
+
+/// A named field of a struct.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Field {
+    /// The name of the field.
+    pub name: String,
+    /// The type of the field.
+    pub ty: Type,
+}
+
+/// The structs that may be referred to by typed data, keyed by name.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Schema(pub BTreeMap<String, Vec<Field>>);
+
+/// A typed value.
+///
+/// The types of values are given by the [`Schema`], so struct fields are
+/// positional, in the order of the struct's fields.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Value {
+    /// A [`Type::Word`].
+    Word(Word),
+    /// A [`Type::Bool`].
+    Bool(bool),
+    /// A [`Type::B256`].
+    B256(Hash),
+    /// A [`Type::Bytes`].
+    Bytes(Vec<u8>),
+    /// A [`Type::String`].
+    String(String),
+    /// A [`Type::Array`].
+    Array(Vec<Value>),
+    /// A [`Type::Struct`].
+    Struct(Vec<Value>),
+}
+
+/// The domain of the application the typed data is intended for.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Domain {
+    /// The name of the application.
+    pub name: String,
+    /// The version of the application.
+    pub version: String,
+    /// The contract the typed data is intended for, if any.
+    pub contract: Option<ContentAddress>,
+}
+
+/// A typed message along with its domain and schema.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypedData {
+    /// The domain the message is intended for.
+    pub domain: Domain,
+    /// The structs referred to by the message.
+    pub schema: Schema,
+    /// The name of the message's struct.
+    pub primary_type: String,
+    /// The message.
+    pub message: Value,
+}
+
+/// Errors that can occur when encoding typed data.
+#[derive(Debug, PartialEq)]
+pub enum TypedDataError {
+    /// The schema has no struct with the given name.
+    UnknownType(String),
+    /// A struct or field name is empty, is reserved or contains characters
+    /// that would make the type string ambiguous.
+    InvalidName(String),
+    /// A struct value has the wrong number of fields.
+    FieldCountMismatch {
+        /// The name of the struct.
+        ty: String,
+        /// The number of fields of the struct.
+        expected: usize,
+        /// The number of fields of the value.
+        found: usize,
+    },
+    /// A value does not match its type.
+    TypeMismatch(Type),
+    /// The signature is invalid.
+    Secp256k1(secp256k1::Error),
+}
+
+impl Type {
+    /// The names of the types that are not structs.
+    pub const RESERVED: [&'static str; 5] = ["word", "bool", "b256", "bytes", "string"];
+}
+
+impl Field {
+    /// Construct a field with the given name and type.
+    pub fn new(name: impl Into<String>, ty: Type) -> Self {
+        Self {
+            name: name.into(),
+            ty,
+        }
+    }
+}
+
+impl Schema {
+    /// The fields of the struct with the given name.
+    pub fn fields(&self, ty: &str) -> Result<&[Field], TypedDataError> {
+        self.0
+            .get(ty)
+            .map(Vec::as_slice)
+            .ok_or_else(|| TypedDataError::UnknownType(ty.to_string()))
+    }
+
+    /// The type string of the struct with the given name, followed by those
+    /// of the structs it refers to, sorted by name.
+    pub fn encode_type(&self, ty: &str) -> Result<String, TypedDataError> {
+        let mut deps = BTreeSet::new();
+        self.dependencies(ty, &mut deps)?;
+        deps.remove(ty);
+        let mut out = String::new();
+        for name in core::iter::once(ty).chain(deps.iter().map(String::as_str)) {
+            out.push_str(name);
+            out.push('(');
+            for (ix, field) in self.fields(name)?.iter().enumerate() {
+                if ix > 0 {
+                    out.push(',');
+                }
+                out.push_str(&format!("{} {}", field.ty, field.name));
+            }
+            out.push(')');
+        }
+        Ok(out)
+    }
+
+    /// The SHA-256 of the struct's [type string][Schema::encode_type].
+    pub fn type_hash(&self, ty: &str) -> Result<Hash, TypedDataError> {
+        Ok(hash_bytes(self.encode_type(ty)?.as_bytes()))
+    }
+
+    /// The type hash followed by the encoding of each field of the struct value.
+    pub fn encode_data(&self, ty: &str, value: &Value) -> Result<Vec<u8>, TypedDataError> {
+        let Value::Struct(values) = value else {
+            return Err(TypedDataError::TypeMismatch(Type::Struct(ty.to_string())));
+        };
+        let fields = self.fields(ty)?;
+        if fields.len() != values.len() {
+            return Err(TypedDataError::FieldCountMismatch {
+                ty: ty.to_string(),
+                expected: fields.len(),
+                found: values.len(),
+            });
+        }
+        let mut out = self.type_hash(ty)?.to_vec();
+        for (field, value) in fields.iter().zip(values) {
+            self.encode_value(&field.ty, value, &mut out)?;
+        }
+        Ok(out)
+    }
+
+    /// The SHA-256 of the struct value's [encoding][Schema::encode_data].
+    pub fn hash_struct(&self, ty: &str, value: &Value) -> Result<Hash, TypedDataError> {
+        Ok(hash_bytes(&self.encode_data(ty, value)?))
+    }
+
+    /// Insert the names of the struct and all structs it refers to into `deps`.
+    fn dependencies(&self, ty: &str, deps: &mut BTreeSet<String>) -> Result<(), TypedDataError> {
+        if deps.contains(ty) {
+            return Ok(());
+        }
+        check_name(ty)?;
+        let fields = self.fields(ty)?;
+        deps.insert(ty.to_string());
+        for field in fields {
+            check_name(&field.name)?;
+            let mut field_ty = &field.ty;
+            while let Type::Array(elem) = field_ty {
+                field_ty = elem;
+            }
+            if let Type::Struct(name) = field_ty {
+                self.dependencies(name, deps)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Append the encoding of the value of the given type to `out`.
+    fn encode_value(
+        &self,
+        ty: &Type,
+        value: &Value,
+        out: &mut Vec<u8>,
+    ) -> Result<(), TypedDataError> {
+        match (ty, value) {
+            (Type::Word, Value::Word(w)) => out.extend(w.to_be_bytes()),
+            (Type::Bool, Value::Bool(b)) => out.extend(Word::from(*b).to_be_bytes()),
+            (Type::B256, Value::B256(hash)) => out.extend(hash),
+            (Type::Bytes, Value::Bytes(bytes)) => out.extend(hash_bytes(bytes)),
+            (Type::String, Value::String(s)) => out.extend(hash_bytes(s.as_bytes())),
+            (Type::Array(elem), Value::Array(values)) => {
+                let mut encoded = vec![];
+                for value in values {
+                    self.encode_value(elem, value, &mut encoded)?;
+                }
+                out.extend(hash_bytes(&encoded));
+            }
+            (Type::Struct(name), Value::Struct(_)) => out.extend(self.hash_struct(name, value)?),
+            _ => return Err(TypedDataError::TypeMismatch(ty.clone())),
+        }
+        Ok(())
+    }
+}
+
+impl Domain {
+    /// A domain with the given application name and version.
+    pub fn new(name: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            version: version.into(),
+            contract: None,
+        }
+    }
+
+    /// The domain restricted to the given contract.
+    pub fn with_contract(mut self, contract: ContentAddress) -> Self {
+        self.contract = Some(contract);
+        self
+    }
+
+    /// The schema of the domain's [`DOMAIN_TYPE`] struct.
+    ///
+    /// The `contract` field is only included when present.
+    pub fn schema(&self) -> Schema {
+        let mut fields = vec![
+            Field::new("name", Type::String),
+            Field::new("version", Type::String),
+        ];
+        if self.contract.is_some() {
+            fields.push(Field::new("contract", Type::B256));
+        }
+        Schema(BTreeMap::from([(DOMAIN_TYPE.to_string(), fields)]))
+    }
+
+    /// The domain as a [`DOMAIN_TYPE`] struct value.
+    pub fn value(&self) -> Value {
+        let mut values = vec![
+            Value::String(self.name.clone()),
+            Value::String(self.version.clone()),
+        ];
+        values.extend(self.contract.as_ref().map(|ca| Value::B256(ca.0)));
+        Value::Struct(values)
+    }
+
+    /// The struct hash of the domain.
+    pub fn separator(&self) -> Hash {
+        self.schema()
+            .hash_struct(DOMAIN_TYPE, &self.value())
+            .expect("domain value always matches its schema")
+    }
+}
+
+impl TypedData {
+    /// The digest of the typed data, to be signed with [`sign_hash`][crate::sign_hash].
+    pub fn digest(&self) -> Result<Hash, TypedDataError> {
+        let message = self.schema.hash_struct(&self.primary_type, &self.message)?;
+        let separator = self.domain.separator();
+        Ok(essential_hash::domain::hash_bytes_iter(
+            DIGEST,
+            [&separator[..], &message[..]],
+        ))
+    }
+}
+
+/// Sign over the [digest][TypedData::digest] of the typed data.
+pub fn sign(data: &TypedData, sk: &SecretKey) -> Result<Signature, TypedDataError> {
+    Ok(crate::sign_hash(data.digest()?, sk))
+}
+
+/// Recover the public key with which the typed data was signed.
+pub fn recover(data: &TypedData, signature: &Signature) -> Result<PublicKey, TypedDataError> {
+    let digest = data.digest()?;
+    crate::recover_hash(digest, signature).map_err(TypedDataError::Secp256k1)
+}
+
+/// Check that a struct or field name may appear in a type string.
+fn check_name(name: &str) -> Result<(), TypedDataError> {
+    let valid = !name.is_empty()
+        && !Type::RESERVED.contains(&name)
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    match valid {
+        true => Ok(()),
+        false => Err(TypedDataError::InvalidName(name.to_string())),
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Word => write!(f, "word"),
+            Self::Bool => write!(f, "bool"),
+            Self::B256 => write!(f, "b256"),
+            Self::Bytes => write!(f, "bytes"),
+            Self::String => write!(f, "string"),
+            Self::Array(elem) => write!(f, "{elem}[]"),
+            Self::Struct(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+impl fmt::Display for TypedDataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownType(ty) => write!(f, "unknown struct type `{ty}`"),
+            Self::InvalidName(name) => write!(f, "invalid struct or field name `{name}`"),
+            Self::FieldCountMismatch {
+                ty,
+                expected,
+                found,
+            } => write!(f, "struct `{ty}` expects {expected} fields, found {found}"),
+            Self::TypeMismatch(ty) => write!(f, "value does not match type `{ty}`"),
+            Self::Secp256k1(err) => write!(f, "invalid signature: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for TypedDataError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Secp256k1(err) => Some(err),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,379 @@
+//! Hierarchical deterministic key derivation as specified by
+//! [BIP-32](https://github.com/bitcoin/bips/blob/master/bip-0032.mediawiki).
+//!
+//! A master [`ExtendedSecretKey`] is derived from a seed, from which child
+//! keys are derived along a [`DerivationPath`] such as `m/44'/60'/0'/0/0`.
+//! Hardened child numbers are written with a trailing `'` or `h`.
+//!
+//! Public keys of non-hardened children may also be derived from an
+//! [`ExtendedPublicKey`] without knowledge of the secret key.
+//!
+//! ```
+//! use essential_sign::hd::{DerivationPath, ExtendedSecretKey};
+//!
+//! let seed = [0x42; 32];
+//! let path: DerivationPath = "m/44'/60'/0'/0/0".parse().unwrap();
+//! let master = ExtendedSecretKey::new_master(&seed).unwrap();
+//! let account = master.derive_path(&path).unwrap();
+//! assert_eq!(account.depth, 5);
+//!
+//! // The same key is derived from the public key for non-hardened children.
+//! let parent = master.derive_path(&"m/44'/60'/0'/0".parse().unwrap()).unwrap();
+//! let child = parent.public_key().derive_path(&"m/0".parse().unwrap()).unwrap();
+//! assert_eq!(child.public_key, account.public_key().public_key);
+//! ```
+//!
+//! Serialization of extended keys (i.e. `xprv` and `xpub` strings) is not
+//! provided.
+
+use core::{fmt, str::FromStr};
+use secp256k1::{PublicKey, Scalar, Secp256k1, SecretKey};
+use sha2::{Digest, Sha512};
+
+/// The offset of hardened child numbers.
+pub const HARDENED: u32 = 1 << 31;
+
+/// The HMAC key used to derive the master key from a seed.
+const MASTER_KEY: &[u8] = b"Bitcoin seed";
+
+/// The minimum length of a seed in bytes.
+pub const MIN_SEED_LEN: usize = 16;
+
+/// The maximum length of a seed in bytes.
+pub const MAX_SEED_LEN: usize = 64;
+
+/// The index of a child key, which is hardened if at least [`HARDENED`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChildNumber(pub u32);
+
+/// A sequence of child numbers from the master key to a descendant.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DerivationPath(pub Vec<ChildNumber>);
+
+/// A secret key with the chain code required to derive its children.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExtendedSecretKey {
+    /// The secret key.
+    pub secret_key: SecretKey,
+    /// The chain code.
+    pub chain_code: [u8; 32],
+    /// The number of derivations from the master key.
+    pub depth: u8,
+    /// The child number of this key within its parent.
+    pub child_number: ChildNumber,
+}
+
+/// A public key with the chain code required to derive its non-hardened children.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExtendedPublicKey {
+    /// The public key.
+    pub public_key: PublicKey,
+    /// The chain code.
+    pub chain_code: [u8; 32],
+    /// The number of derivations from the master key.
+    pub depth: u8,
+    /// The child number of this key within its parent.
+    pub child_number: ChildNumber,
+}
+
+/// Errors that can occur during derivation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeriveError {
+    /// The seed is not between [`MIN_SEED_LEN`] and [`MAX_SEED_LEN`] bytes.
+    InvalidSeedLen(usize),
+    /// A hardened child cannot be derived from a public key.
+    HardenedFromPublic(ChildNumber),
+    /// The derived key is invalid. This occurs with negligible probability,
+    /// in which case the next child number should be used.
+    InvalidKey(ChildNumber),
+    /// The depth exceeds 255.
+    DepthOverflow,
+}
+
+/// Errors that can occur when parsing a [`DerivationPath`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathParseError {
+    /// The path does not start with `m`.
+    MissingMaster,
+    /// The child number at the given position is invalid.
+    InvalidChildNumber(usize, String),
+}
+
+impl ChildNumber {
+    /// The non-hardened child with the given index.
+    ///
+    /// Returns `None` if the index is not less than [`HARDENED`].
+    pub const fn normal(index: u32) -> Option<Self> {
+        if index < HARDENED {
+            Some(Self(index))
+        } else {
+            None
+        }
+    }
+
+    /// The hardened child with the given index.
+    ///
+    /// Returns `None` if the index is not less than [`HARDENED`].
+    pub const fn hardened(index: u32) -> Option<Self> {
+        if index < HARDENED {
+            Some(Self(index | HARDENED))
+        } else {
+            None
+        }
+    }
+
+    /// Whether the child is hardened.
+    pub const fn is_hardened(self) -> bool {
+        self.0 >= HARDENED
+    }
+
+    /// The index of the child, excluding the hardened offset.
+    pub const fn index(self) -> u32 {
+        self.0 & !HARDENED
+    }
+}
+
+impl DerivationPath {
+    /// The path of the master key, i.e. `m`.
+    pub fn master() -> Self {
+        Self::default()
+    }
+
+    /// The [BIP-44](https://github.com/bitcoin/bips/blob/master/bip-0044.mediawiki)
+    /// path `m/44'/coin_type'/account'/change/index`.
+    ///
+    /// Returns `None` if any of the indices is not less than [`HARDENED`].
+    pub fn bip44(coin_type: u32, account: u32, change: u32, index: u32) -> Option<Self> {
+        Some(Self(vec![
+            ChildNumber::hardened(44)?,
+            ChildNumber::hardened(coin_type)?,
+            ChildNumber::hardened(account)?,
+            ChildNumber::normal(change)?,
+            ChildNumber::normal(index)?,
+        ]))
+    }
+
+    /// This path extended with the given child.
+    pub fn child(&self, child: ChildNumber) -> Self {
+        let mut path = self.clone();
+        path.0.push(child);
+        path
+    }
+
+    /// Iterate over the child numbers of the path.
+    pub fn iter(&self) -> impl Iterator<Item = &ChildNumber> {
+        self.0.iter()
+    }
+}
+
+impl ExtendedSecretKey {
+    /// Derive the master key from the given seed.
+    pub fn new_master(seed: &[u8]) -> Result<Self, DeriveError> {
+        if !(MIN_SEED_LEN..=MAX_SEED_LEN).contains(&seed.len()) {
+            return Err(DeriveError::InvalidSeedLen(seed.len()));
+        }
+        let (key, chain_code) = split(hmac_sha512(MASTER_KEY, &[seed]));
+        let secret_key = SecretKey::from_byte_array(&key)
+            .map_err(|_| DeriveError::InvalidKey(ChildNumber(0)))?;
+        Ok(Self {
+            secret_key,
+            chain_code,
+            depth: 0,
+            child_number: ChildNumber(0),
+        })
+    }
+
+    /// Derive the child with the given number.
+    pub fn derive_child(&self, child: ChildNumber) -> Result<Self, DeriveError> {
+        let depth = self
+            .depth
+            .checked_add(1)
+            .ok_or(DeriveError::DepthOverflow)?;
+        let index = child.0.to_be_bytes();
+        let mac = if child.is_hardened() {
+            let key = self.secret_key.secret_bytes();
+            hmac_sha512(&self.chain_code, &[&[0], &key, &index])
+        } else {
+            let key = self.public_key().public_key.serialize();
+            hmac_sha512(&self.chain_code, &[&key, &index])
+        };
+        let (tweak, chain_code) = split(mac);
+        let secret_key = Scalar::from_be_bytes(tweak)
+            .ok()
+            .and_then(|tweak| self.secret_key.add_tweak(&tweak).ok())
+            .ok_or(DeriveError::InvalidKey(child))?;
+        Ok(Self {
+            secret_key,
+            chain_code,
+            depth,
+            child_number: child,
+        })
+    }
+
+    /// Derive the descendant at the given path, relative to this key.
+    pub fn derive_path(&self, path: &DerivationPath) -> Result<Self, DeriveError> {
+        path.iter()
+            .try_fold(*self, |key, &child| key.derive_child(child))
+    }
+
+    /// The extended public key of this key.
+    pub fn public_key(&self) -> ExtendedPublicKey {
+        let secp = Secp256k1::signing_only();
+        ExtendedPublicKey {
+            public_key: self.secret_key.public_key(&secp),
+            chain_code: self.chain_code,
+            depth: self.depth,
+            child_number: self.child_number,
+        }
+    }
+}
+
+impl ExtendedPublicKey {
+    /// Derive the non-hardened child with the given number.
+    pub fn derive_child(&self, child: ChildNumber) -> Result<Self, DeriveError> {
+        if child.is_hardened() {
+            return Err(DeriveError::HardenedFromPublic(child));
+        }
+        let depth = self
+            .depth
+            .checked_add(1)
+            .ok_or(DeriveError::DepthOverflow)?;
+        let key = self.public_key.serialize();
+        let mac = hmac_sha512(&self.chain_code, &[&key, &child.0.to_be_bytes()]);
+        let (tweak, chain_code) = split(mac);
+        let secp = Secp256k1::verification_only();
+        let public_key = Scalar::from_be_bytes(tweak)
+            .ok()
+            .and_then(|tweak| self.public_key.add_exp_tweak(&secp, &tweak).ok())
+            .ok_or(DeriveError::InvalidKey(child))?;
+        Ok(Self {
+            public_key,
+            chain_code,
+            depth,
+            child_number: child,
+        })
+    }
+
+    /// Derive the descendant at the given path, relative to this key.
+    ///
+    /// The path must not contain hardened children.
+    pub fn derive_path(&self, path: &DerivationPath) -> Result<Self, DeriveError> {
+        path.iter()
+            .try_fold(*self, |key, &child| key.derive_child(child))
+    }
+}
+
+impl From<Vec<ChildNumber>> for DerivationPath {
+    fn from(children: Vec<ChildNumber>) -> Self {
+        Self(children)
+    }
+}
+
+impl AsRef<[ChildNumber]> for DerivationPath {
+    fn as_ref(&self) -> &[ChildNumber] {
+        &self.0
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = PathParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('/');
+        if parts.next() != Some("m") {
+            return Err(PathParseError::MissingMaster);
+        }
+        parts
+            .enumerate()
+            .map(|(ix, part)| {
+                parse_child_number(part)
+                    .ok_or_else(|| PathParseError::InvalidChildNumber(ix, part.to_string()))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl fmt::Display for ChildNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.is_hardened() {
+            true => write!(f, "{}'", self.index()),
+            false => write!(f, "{}", self.index()),
+        }
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "m")?;
+        self.iter().try_for_each(|child| write!(f, "/{child}"))
+    }
+}
+
+impl fmt::Display for DeriveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSeedLen(len) => write!(
+                f,
+                "seed must be between {MIN_SEED_LEN} and {MAX_SEED_LEN} bytes, found {len}"
+            ),
+            Self::HardenedFromPublic(child) => {
+                write!(f, "cannot derive hardened child {child} from a public key")
+            }
+            Self::InvalidKey(child) => write!(f, "derived key for child {child} is invalid"),
+            Self::DepthOverflow => write!(f, "derivation depth exceeds 255"),
+        }
+    }
+}
+
+impl fmt::Display for PathParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingMaster => write!(f, "derivation path must start with `m`"),
+            Self::InvalidChildNumber(ix, part) => {
+                write!(f, "invalid child number `{part}` at position {ix}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DeriveError {}
+
+impl std::error::Error for PathParseError {}
+
+/// Parse a child number, hardened if suffixed with `'` or `h`.
+fn parse_child_number(s: &str) -> Option<ChildNumber> {
+    match s.strip_suffix(['\'', 'h']) {
+        Some(index) => ChildNumber::hardened(index.parse().ok()?),
+        None => ChildNumber::normal(s.parse().ok()?),
+    }
+}
+
+/// Split the output of the HMAC into the key or tweak and the chain code.
+fn split(mac: [u8; 64]) -> ([u8; 32], [u8; 32]) {
+    let mut left = [0; 32];
+    let mut right = [0; 32];
+    left.copy_from_slice(&mac[..32]);
+    right.copy_from_slice(&mac[32..]);
+    (left, right)
+}
+
+/// HMAC-SHA512 of the concatenation of the given data.
+pub(crate) fn hmac_sha512(key: &[u8], data: &[&[u8]]) -> [u8; 64] {
+    const BLOCK_SIZE: usize = 128;
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..64].copy_from_slice(&Sha512::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha512::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    data.iter().for_each(|bytes| inner.update(bytes));
+
+    let mut outer = Sha512::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
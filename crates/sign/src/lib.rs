@@ -10,19 +10,63 @@
 //! - [`sign_hash`]
 //! - [`verify_hash`]
 //! - [`recover_hash`]
+//!
+//! ## Ed25519
+//!
+//! Hashes may also be signed with Ed25519 keys for verification by the VM's
+//! `Crypto::VerifyEd25519` operation:
+//!
+//! - [`sign_hash_ed25519`]
+//! - [`verify_hash_ed25519`]
+//!
+//! See the [`ed25519`] module for key management and word encodings.
+//!
+//! ## Typed Data
+//!
+//! The [`encode::typed`] module produces domain-separated digests of
+//! structured typed data, allowing wallets to display what is being signed.
+//!
+//! ## Multi-Signatures
+//!
+//! The [`multisig`] module aggregates and verifies threshold signatures of
+//! contracts by multiple signers.
+//!
+//! ## Signers
+//!
+//! The [`signer::Signer`] trait abstracts over signing backends, allowing
+//! remote or hardware signers to be used in place of a local [`SecretKey`].
+//!
+//! ## Key Derivation
+//!
+//! The [`hd`] module derives keys from a seed along BIP-32 derivation paths.
+//! The `mnemonic` feature enables the `mnemonic` module for BIP-39 seed phrases.
+//!
+//! ## Test Vectors
+//!
+//! The `test-vectors` feature enables the [`test_vectors`] module for generating
+//! and verifying cross-language fixtures for signatures and content addresses.
 
 #![deny(missing_docs)]
 #![deny(unsafe_code)]
 
+pub use ed25519_dalek;
 use essential_types::{Hash, Signature};
 pub use secp256k1;
 use secp256k1::{
-    ecdsa::{RecoverableSignature, RecoveryId, Signature as CompactSignature},
+    ecdsa::{RecoverableSignature, Signature as CompactSignature},
     Message, PublicKey, Secp256k1, SecretKey,
 };
 
 pub mod contract;
+pub mod ed25519;
 pub mod encode;
+pub mod hd;
+#[cfg(feature = "mnemonic")]
+pub mod mnemonic;
+pub mod multisig;
+pub mod signer;
+#[cfg(feature = "test-vectors")]
+pub mod test_vectors;
 
 /// Sign directly over a hash with the given secret key using `secp256k1`.
 ///
@@ -35,8 +79,27 @@ pub fn sign_hash(hash: Hash, sk: &SecretKey) -> Signature {
 /// Sign directly over the given [`Message`] with secret key using secp256k1 curve.
 pub fn sign_message(msg: &Message, sk: &SecretKey) -> Signature {
     let secp = Secp256k1::new();
-    let (rec_id, sig) = secp.sign_ecdsa_recoverable(msg, sk).serialize_compact();
-    Signature(sig, i32::from(rec_id).try_into().unwrap())
+    secp.sign_ecdsa_recoverable(msg, sk).into()
+}
+
+/// Sign directly over a hash with the given Ed25519 signing key.
+///
+/// The hash's 32 bytes are the signed message, as expected by the VM's
+/// `Crypto::VerifyEd25519` operation with a data length of 32.
+pub fn sign_hash_ed25519(hash: Hash, sk: &ed25519::SigningKey) -> ed25519::Signature {
+    ed25519_dalek::Signer::sign(sk, &hash)
+}
+
+/// Verify an Ed25519 signature over the given hash against the public key.
+///
+/// Verification is strict, rejecting weak public keys and malleable
+/// signatures. Signatures produced by [`sign_hash_ed25519`] always pass.
+pub fn verify_hash_ed25519(
+    hash: Hash,
+    signature: &ed25519::Signature,
+    pk: &ed25519::VerifyingKey,
+) -> Result<(), ed25519::SignatureError> {
+    pk.verify_strict(&hash, signature)
 }
 
 /// Verify a signature over the given hash.
@@ -73,8 +136,7 @@ pub fn recover_from_message(
     message: &Message,
     signature: &Signature,
 ) -> Result<PublicKey, secp256k1::Error> {
-    let recovery_id = RecoveryId::try_from(i32::from(signature.1))?;
-    let recoverable_signature = RecoverableSignature::from_compact(&signature.0, recovery_id)?;
+    let recoverable_signature = RecoverableSignature::try_from(signature)?;
     let secp = Secp256k1::new();
     let public_key = secp.recover_ecdsa(message, &recoverable_signature)?;
     Ok(public_key)
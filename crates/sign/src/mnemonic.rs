@@ -0,0 +1,210 @@
+//! Mnemonic seed phrases as specified by
+//! [BIP-39](https://github.com/bitcoin/bips/blob/master/bip-0039.mediawiki).
+//!
+//! Together with the [`hd`] module, this covers a basic wallet
+//! flow from a mnemonic to derived keys:
+//!
+//! ```
+//! use essential_sign::{hd::DerivationPath, mnemonic::Mnemonic};
+//!
+//! let phrase = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+//! let mnemonic: Mnemonic = phrase.parse().unwrap();
+//! let master = mnemonic.to_master_key("").unwrap();
+//! let path = DerivationPath::bip44(60, 0, 0, 0).unwrap();
+//! let key = master.derive_path(&path).unwrap();
+//!
+//! let hash = essential_hash::hash_bytes(b"hello");
+//! let signature = essential_sign::sign_hash(hash, &key.secret_key);
+//! let public_key = essential_sign::recover_hash(hash, &signature).unwrap();
+//! assert_eq!(public_key, key.public_key().public_key);
+//! ```
+//!
+//! Only the English wordlist is supported. The passphrase is used as given,
+//! so callers accepting non-ASCII passphrases must apply NFKD normalization
+//! beforehand.
+
+use crate::hd::{self, ExtendedSecretKey};
+use core::{fmt, str::FromStr};
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+use std::sync::OnceLock;
+
+/// The BIP-39 English wordlist.
+const ENGLISH: &str = include_str!("mnemonic/english.txt");
+
+/// The number of bits encoded by each word.
+const BITS_PER_WORD: usize = 11;
+
+/// The number of PBKDF2 iterations used to derive the seed.
+const PBKDF2_ROUNDS: u32 = 2048;
+
+/// The supported numbers of words.
+pub const WORD_COUNTS: [usize; 5] = [12, 15, 18, 21, 24];
+
+/// A valid mnemonic phrase.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Mnemonic {
+    /// The index of each word within the wordlist.
+    indices: Vec<u16>,
+}
+
+/// Errors that can occur when constructing a [`Mnemonic`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MnemonicError {
+    /// The entropy is not 16, 20, 24, 28 or 32 bytes.
+    InvalidEntropyLen(usize),
+    /// The number of words is not one of [`WORD_COUNTS`].
+    InvalidWordCount(usize),
+    /// The word at the given position is not in the wordlist.
+    UnknownWord(usize, String),
+    /// The checksum does not match the entropy.
+    InvalidChecksum,
+}
+
+impl Mnemonic {
+    /// Encode the given entropy as a mnemonic.
+    pub fn from_entropy(entropy: &[u8]) -> Result<Self, MnemonicError> {
+        if !WORD_COUNTS.map(entropy_len).contains(&entropy.len()) {
+            return Err(MnemonicError::InvalidEntropyLen(entropy.len()));
+        }
+        let checksum = Sha256::digest(entropy)[0];
+        let checksum_bits = entropy.len() / 4;
+        let bit = |ix: usize| match entropy.get(ix / 8) {
+            Some(byte) => byte >> (7 - ix % 8) & 1,
+            None => checksum >> (7 - (ix - entropy.len() * 8)) & 1,
+        };
+        let num_words = word_count(entropy.len());
+        debug_assert_eq!(num_words * BITS_PER_WORD, entropy.len() * 8 + checksum_bits);
+        let indices = (0..num_words)
+            .map(|word| {
+                (0..BITS_PER_WORD).fold(0u16, |index, b| {
+                    index << 1 | u16::from(bit(word * BITS_PER_WORD + b))
+                })
+            })
+            .collect();
+        Ok(Self { indices })
+    }
+
+    /// Generate a mnemonic of the given number of words from the random
+    /// number generator.
+    pub fn generate<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        word_count: usize,
+    ) -> Result<Self, MnemonicError> {
+        if !WORD_COUNTS.contains(&word_count) {
+            return Err(MnemonicError::InvalidWordCount(word_count));
+        }
+        let mut entropy = vec![0; entropy_len(word_count)];
+        rng.fill_bytes(&mut entropy);
+        Self::from_entropy(&entropy)
+    }
+
+    /// The entropy encoded by the mnemonic.
+    pub fn entropy(&self) -> Vec<u8> {
+        let entropy_bits = self.indices.len() * BITS_PER_WORD * 32 / 33;
+        let bit = |ix: usize| self.indices[ix / BITS_PER_WORD] >> (10 - ix % BITS_PER_WORD) & 1;
+        (0..entropy_bits / 8)
+            .map(|byte| (0..8).fold(0u8, |acc, b| acc << 1 | bit(byte * 8 + b) as u8))
+            .collect()
+    }
+
+    /// The words of the mnemonic.
+    pub fn words(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.indices.iter().map(|&ix| wordlist()[usize::from(ix)])
+    }
+
+    /// Derive the 64 byte seed from the mnemonic and passphrase.
+    pub fn to_seed(&self, passphrase: &str) -> [u8; 64] {
+        let phrase = self.to_string();
+        let salt = format!("mnemonic{passphrase}");
+
+        // PBKDF2-HMAC-SHA512 with a single output block.
+        let mut block = hd::hmac_sha512(phrase.as_bytes(), &[salt.as_bytes(), &1u32.to_be_bytes()]);
+        let mut seed = block;
+        for _ in 1..PBKDF2_ROUNDS {
+            block = hd::hmac_sha512(phrase.as_bytes(), &[&block]);
+            seed.iter_mut().zip(block).for_each(|(s, b)| *s ^= b);
+        }
+        seed
+    }
+
+    /// Derive the master key from the mnemonic's seed.
+    pub fn to_master_key(&self, passphrase: &str) -> Result<ExtendedSecretKey, hd::DeriveError> {
+        ExtendedSecretKey::new_master(&self.to_seed(passphrase))
+    }
+}
+
+/// The words of the English wordlist, in index order.
+fn wordlist() -> &'static [&'static str] {
+    static WORDLIST: OnceLock<Vec<&'static str>> = OnceLock::new();
+    WORDLIST.get_or_init(|| ENGLISH.lines().collect())
+}
+
+/// The length in bytes of the entropy encoded by the given number of words.
+fn entropy_len(word_count: usize) -> usize {
+    word_count * 4 / 3
+}
+
+/// The number of words encoding entropy of the given length in bytes.
+fn word_count(entropy_len: usize) -> usize {
+    entropy_len * 3 / 4
+}
+
+impl FromStr for Mnemonic {
+    type Err = MnemonicError;
+
+    fn from_str(phrase: &str) -> Result<Self, Self::Err> {
+        let wordlist = wordlist();
+        let indices = phrase
+            .split_whitespace()
+            .enumerate()
+            .map(|(ix, word)| {
+                wordlist
+                    .binary_search(&word)
+                    .map(|index| index as u16)
+                    .map_err(|_| MnemonicError::UnknownWord(ix, word.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if !WORD_COUNTS.contains(&indices.len()) {
+            return Err(MnemonicError::InvalidWordCount(indices.len()));
+        }
+        let mnemonic = Self { indices };
+        match Self::from_entropy(&mnemonic.entropy()) {
+            Ok(expected) if expected == mnemonic => Ok(mnemonic),
+            _ => Err(MnemonicError::InvalidChecksum),
+        }
+    }
+}
+
+impl fmt::Display for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.words()
+            .enumerate()
+            .try_for_each(|(ix, word)| match ix {
+                0 => write!(f, "{word}"),
+                _ => write!(f, " {word}"),
+            })
+    }
+}
+
+impl fmt::Debug for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Avoid leaking the phrase into logs.
+        f.debug_struct("Mnemonic")
+            .field("words", &self.indices.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for MnemonicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntropyLen(len) => write!(f, "invalid entropy length: {len} bytes"),
+            Self::InvalidWordCount(count) => write!(f, "invalid number of words: {count}"),
+            Self::UnknownWord(ix, word) => write!(f, "unknown word `{word}` at position {ix}"),
+            Self::InvalidChecksum => write!(f, "invalid mnemonic checksum"),
+        }
+    }
+}
+
+impl std::error::Error for MnemonicError {}
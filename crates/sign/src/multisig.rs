@@ -0,0 +1,226 @@
+//! Threshold signing of contracts by multiple signers.
+//!
+//! Each signer independently signs the contract's content address, e.g. with
+//! [`contract::sign`][crate::contract::sign] or a [`Signer`][crate::signer::Signer].
+//! The signatures are then [`aggregate`]d into a [`MultiSignedContract`],
+//! which [`verify`] accepts if at least the policy's threshold of distinct
+//! signers have signed.
+//!
+//! The policy carried by a [`MultiSignedContract`] is not itself signed, so
+//! anyone may replace it, e.g. with a 1-of-1 policy of their own key.
+//! [`verify`] therefore requires the expected policy, which must come from a
+//! trusted source rather than the signed contract.
+//!
+//! ```
+//! use essential_sign::{multisig, secp256k1::{Secp256k1, SecretKey}};
+//! use essential_types::contract::{Contract, MultiSigPolicy};
+//!
+//! let secp = Secp256k1::new();
+//! let keys: Vec<_> = (1..=3)
+//!     .map(|i| SecretKey::from_byte_array(&[i; 32]).unwrap())
+//!     .collect();
+//! let policy = MultiSigPolicy {
+//!     threshold: 2,
+//!     signers: keys.iter().map(|sk| sk.public_key(&secp).into()).collect(),
+//! };
+//!
+//! let contract = Contract::default();
+//! let ca = essential_hash::content_addr(&contract);
+//! let signatures = [&keys[2], &keys[0]].map(|sk| essential_sign::sign_hash(ca.0, sk));
+//! let signed = multisig::aggregate(contract, policy.clone(), signatures).unwrap();
+//! assert!(multisig::verify(&signed, &policy).is_ok());
+//! ```
+
+use essential_types::{
+    contract::{Contract, MultiSigPolicy, MultiSignedContract},
+    Hash, Signature,
+};
+use std::fmt;
+
+/// Errors that can occur when aggregating or verifying multi-signatures.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MultiSigError {
+    /// The threshold is zero or exceeds the number of signers.
+    InvalidThreshold {
+        /// The policy's threshold.
+        threshold: u16,
+        /// The policy's number of signers.
+        signers: usize,
+    },
+    /// The policy has more signers than may be indexed.
+    TooManySigners(usize),
+    /// The signer at the given index has the same key as an earlier signer.
+    DuplicateSigner(u16),
+    /// The policy of the signed contract is not the expected policy.
+    PolicyMismatch,
+    /// A signature could not be recovered.
+    Secp256k1(secp256k1::Error),
+    /// A signature was not produced by any of the policy's signers.
+    UnknownSigner,
+    /// A signer index is out of bounds of the policy's signers.
+    SignerOutOfBounds(u16),
+    /// The signature of the signer at the given index was not produced by
+    /// that signer.
+    SignerMismatch(u16),
+    /// The signatures are not sorted by signer index, or a signer has
+    /// signed more than once.
+    UnorderedSigners,
+    /// Fewer distinct signers signed than the threshold requires.
+    BelowThreshold {
+        /// The number of distinct signers.
+        signed: usize,
+        /// The policy's threshold.
+        threshold: u16,
+    },
+}
+
+/// Aggregate signatures over the contract's content address into a
+/// [`MultiSignedContract`].
+///
+/// The signatures may be in any order. Each is matched to its signer within
+/// the policy by recovery, and repeated signatures by the same signer are
+/// ignored. Fails if fewer than the threshold of distinct signers signed.
+pub fn aggregate(
+    contract: Contract,
+    policy: MultiSigPolicy,
+    signatures: impl IntoIterator<Item = Signature>,
+) -> Result<MultiSignedContract, MultiSigError> {
+    check_policy(&policy)?;
+    let ca = essential_hash::content_addr(&contract);
+    let mut indexed = signatures
+        .into_iter()
+        .map(|signature| {
+            let index = signer_index(&policy, ca.0, &signature)?;
+            Ok((index, signature))
+        })
+        .collect::<Result<Vec<_>, MultiSigError>>()?;
+    indexed.sort_by_key(|(ix, _)| *ix);
+    indexed.dedup_by_key(|(ix, _)| *ix);
+    check_threshold(&policy, indexed.len())?;
+    Ok(MultiSignedContract {
+        contract,
+        policy,
+        signatures: indexed,
+    })
+}
+
+/// Verify that at least the threshold of the expected policy's signers have
+/// signed the contract's content address.
+///
+/// Fails with [`MultiSigError::PolicyMismatch`] if the signed contract's
+/// policy is not the expected policy, as the policy is not signed.
+pub fn verify(signed: &MultiSignedContract, policy: &MultiSigPolicy) -> Result<(), MultiSigError> {
+    if signed.policy != *policy {
+        return Err(MultiSigError::PolicyMismatch);
+    }
+    let ca = essential_hash::content_addr(&signed.contract);
+    verify_hash(ca.0, policy, &signed.signatures)
+}
+
+/// Verify that at least the threshold of the policy's signers have signed
+/// the given hash, with signatures indexed and ordered as in
+/// [`MultiSignedContract::signatures`].
+pub fn verify_hash(
+    hash: Hash,
+    policy: &MultiSigPolicy,
+    signatures: &[(u16, Signature)],
+) -> Result<(), MultiSigError> {
+    check_policy(policy)?;
+    if signatures.windows(2).any(|w| w[0].0 >= w[1].0) {
+        return Err(MultiSigError::UnorderedSigners);
+    }
+    for (ix, signature) in signatures {
+        let signer = policy
+            .signers
+            .get(usize::from(*ix))
+            .ok_or(MultiSigError::SignerOutOfBounds(*ix))?;
+        let key = crate::recover_hash(hash, signature).map_err(MultiSigError::Secp256k1)?;
+        if signer.0 != key.serialize() {
+            return Err(MultiSigError::SignerMismatch(*ix));
+        }
+    }
+    check_threshold(policy, signatures.len())
+}
+
+/// Check the threshold is satisfiable and the signers are distinct and indexable.
+fn check_policy(policy: &MultiSigPolicy) -> Result<(), MultiSigError> {
+    let signers = policy.signers.len();
+    if u16::try_from(signers).is_err() {
+        return Err(MultiSigError::TooManySigners(signers));
+    }
+    let mut keys = std::collections::HashSet::with_capacity(signers);
+    if let Some(ix) = policy.signers.iter().position(|key| !keys.insert(key)) {
+        let ix = u16::try_from(ix).expect("signer count checked above");
+        return Err(MultiSigError::DuplicateSigner(ix));
+    }
+    if policy.threshold == 0 || usize::from(policy.threshold) > signers {
+        return Err(MultiSigError::InvalidThreshold {
+            threshold: policy.threshold,
+            signers,
+        });
+    }
+    Ok(())
+}
+
+fn check_threshold(policy: &MultiSigPolicy, signed: usize) -> Result<(), MultiSigError> {
+    if signed < usize::from(policy.threshold) {
+        return Err(MultiSigError::BelowThreshold {
+            signed,
+            threshold: policy.threshold,
+        });
+    }
+    Ok(())
+}
+
+/// The index within the policy of the signer of the signature.
+fn signer_index(
+    policy: &MultiSigPolicy,
+    hash: Hash,
+    signature: &Signature,
+) -> Result<u16, MultiSigError> {
+    let key = crate::recover_hash(hash, signature).map_err(MultiSigError::Secp256k1)?;
+    let key = key.serialize();
+    let ix = policy
+        .signers
+        .iter()
+        .position(|signer| signer.0 == key)
+        .ok_or(MultiSigError::UnknownSigner)?;
+    Ok(u16::try_from(ix).expect("signer count checked by `check_policy`"))
+}
+
+impl fmt::Display for MultiSigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidThreshold { threshold, signers } => {
+                write!(f, "invalid threshold {threshold} for {signers} signers")
+            }
+            Self::TooManySigners(n) => write!(f, "too many signers: {n}"),
+            Self::DuplicateSigner(ix) => {
+                write!(f, "the signer at index {ix} repeats an earlier signer")
+            }
+            Self::PolicyMismatch => write!(f, "the policy is not the expected policy"),
+            Self::Secp256k1(err) => write!(f, "failed to recover signer: {err}"),
+            Self::UnknownSigner => write!(f, "signature is not from any of the policy's signers"),
+            Self::SignerOutOfBounds(ix) => write!(f, "signer index {ix} is out of bounds"),
+            Self::SignerMismatch(ix) => {
+                write!(f, "signature is not from the signer at index {ix}")
+            }
+            Self::UnorderedSigners => {
+                write!(f, "signatures are not sorted by distinct signer index")
+            }
+            Self::BelowThreshold { signed, threshold } => write!(
+                f,
+                "{signed} distinct signers signed, but the threshold is {threshold}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MultiSigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Secp256k1(err) => Some(err),
+            _ => None,
+        }
+    }
+}
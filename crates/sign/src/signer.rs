@@ -0,0 +1,81 @@
+//! An abstraction over signing backends.
+//!
+//! The free functions of this crate require the [`SecretKey`] to be held in
+//! process memory. The [`Signer`] trait allows signing to be delegated to a
+//! remote service, KMS or HSM instead, with [`LocalSigner`] providing the
+//! in-process implementation.
+//!
+//! ```
+//! use essential_sign::signer::{LocalSigner, Signer};
+//! # use essential_sign::secp256k1::SecretKey;
+//!
+//! # futures::executor::block_on(async {
+//! let signer = LocalSigner::new(SecretKey::from_byte_array(&[0xcd; 32]).unwrap());
+//! let hash = essential_hash::hash_bytes(b"hello");
+//! let signature = signer.sign_hash(hash).await.unwrap();
+//! let public_key = essential_sign::recover_hash(hash, &signature).unwrap();
+//! assert_eq!(public_key, signer.public_key());
+//! # });
+//! ```
+
+use core::{convert::Infallible, future::Future};
+use essential_types::{Hash, Signature};
+use secp256k1::{PublicKey, Secp256k1, SecretKey};
+
+/// A backend able to produce recoverable secp256k1 signatures over hashes.
+pub trait Signer {
+    /// The error returned when signing fails, e.g. due to an unreachable
+    /// remote signer.
+    type Error;
+
+    /// Sign directly over the given hash, as with [`sign_hash`][crate::sign_hash].
+    fn sign_hash(&self, hash: Hash) -> impl Future<Output = Result<Signature, Self::Error>> + Send;
+}
+
+/// A [`Signer`] holding its secret key in process memory.
+#[derive(Clone)]
+pub struct LocalSigner {
+    secret_key: SecretKey,
+}
+
+impl LocalSigner {
+    /// Create a signer for the given secret key.
+    pub fn new(secret_key: SecretKey) -> Self {
+        Self { secret_key }
+    }
+
+    /// The public key of the signer.
+    pub fn public_key(&self) -> PublicKey {
+        self.secret_key.public_key(&Secp256k1::signing_only())
+    }
+}
+
+impl core::fmt::Debug for LocalSigner {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("LocalSigner")
+            .field("public_key", &self.public_key())
+            .finish_non_exhaustive()
+    }
+}
+
+impl From<SecretKey> for LocalSigner {
+    fn from(secret_key: SecretKey) -> Self {
+        Self::new(secret_key)
+    }
+}
+
+impl Signer for LocalSigner {
+    type Error = Infallible;
+
+    async fn sign_hash(&self, hash: Hash) -> Result<Signature, Self::Error> {
+        Ok(crate::sign_hash(hash, &self.secret_key))
+    }
+}
+
+impl<S: Signer + Sync> Signer for &S {
+    type Error = S::Error;
+
+    fn sign_hash(&self, hash: Hash) -> impl Future<Output = Result<Signature, Self::Error>> + Send {
+        (**self).sign_hash(hash)
+    }
+}
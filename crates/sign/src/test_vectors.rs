@@ -0,0 +1,249 @@
+//! Cross-language test vectors for signatures and content addresses.
+//!
+//! Generates deterministic fixtures from this crate and [`essential_hash`] that
+//! can be serialized to JSON and used by other implementations (e.g. the JS
+//! or Go SDKs) to validate their signing, recovery and content addressing
+//! against the Rust reference implementation.
+//!
+//! - [`generate`] produces a set of [`TestVectors`] from a seed.
+//! - [`verify`] checks that a set of [`TestVectors`] matches this implementation.
+//!
+//! Byte arrays are encoded as hex strings when serialized to a human-readable
+//! format, matching the `essential-types` serde implementations.
+
+use essential_hash::content_addr;
+use essential_types::{
+    contract::Contract,
+    predicate::{Edge, Node, Predicate},
+    serde::{bytecode, hash},
+    ContentAddress, Hash, PredicateAddress, Signature, Word,
+};
+use secp256k1::{PublicKey, SecretKey};
+use serde::{Deserialize, Serialize};
+
+/// A full set of test vectors.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestVectors {
+    /// Signing and recovery vectors.
+    pub signatures: Vec<SignatureVector>,
+    /// Contract content address vectors.
+    pub contracts: Vec<ContractVector>,
+}
+
+/// A single signing and recovery test vector.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignatureVector {
+    /// The secp256k1 secret key.
+    #[serde(with = "hash")]
+    pub secret_key: [u8; 32],
+    /// The message bytes.
+    #[serde(with = "bytecode")]
+    pub message: Vec<u8>,
+    /// The sha256 hash of the message that is signed.
+    #[serde(with = "hash")]
+    pub hash: Hash,
+    /// The expected signature over the hash.
+    pub signature: Signature,
+    /// The expected public key recovered from the signature, compressed.
+    #[serde(with = "hash")]
+    pub public_key: [u8; 33],
+    /// The expected public key encoded as words (see [`crate::encode::public_key`]).
+    pub public_key_words: [Word; 5],
+}
+
+/// A single contract content address test vector.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractVector {
+    /// The contract.
+    pub contract: Contract,
+    /// The expected content address of the contract.
+    pub content_address: ContentAddress,
+    /// The expected address of each predicate within the contract, in order.
+    pub predicates: Vec<PredicateAddress>,
+}
+
+/// A mismatch between a test vector and this implementation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VerifyError {
+    /// The signature vector at the given index is invalid.
+    Signature(usize, SignatureMismatch),
+    /// The contract vector at the given index is invalid.
+    Contract(usize, ContractMismatch),
+}
+
+/// The field of a [`SignatureVector`] that did not match.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignatureMismatch {
+    /// The secret key is not a valid secp256k1 secret key.
+    SecretKey,
+    /// The hash of the message did not match.
+    Hash,
+    /// The signature did not match.
+    Signature,
+    /// The recovered public key did not match.
+    PublicKey,
+    /// The word encoding of the public key did not match.
+    PublicKeyWords,
+}
+
+/// The field of a [`ContractVector`] that did not match.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContractMismatch {
+    /// The contract content address did not match.
+    ContentAddress,
+    /// The number of predicate addresses did not match.
+    PredicatesLen,
+    /// The predicate address at the given index did not match.
+    Predicate(usize),
+}
+
+impl core::fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Signature(ix, m) => write!(f, "signature vector {ix}: mismatched {m:?}"),
+            Self::Contract(ix, m) => write!(f, "contract vector {ix}: mismatched {m:?}"),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+/// Deterministically generate test vectors from the given seed.
+///
+/// Produces `count` signature vectors and `count` contract vectors.
+pub fn generate(seed: u64, count: usize) -> TestVectors {
+    let signatures = (0..count)
+        .map(|i| signature_vector(seed, i as u64))
+        .collect();
+    let contracts = (0..count)
+        .map(|i| contract_vector(seed, i as u64))
+        .collect();
+    TestVectors {
+        signatures,
+        contracts,
+    }
+}
+
+/// Verify the given test vectors against this implementation.
+pub fn verify(vectors: &TestVectors) -> Result<(), VerifyError> {
+    for (ix, v) in vectors.signatures.iter().enumerate() {
+        verify_signature(v).map_err(|m| VerifyError::Signature(ix, m))?;
+    }
+    for (ix, v) in vectors.contracts.iter().enumerate() {
+        verify_contract(v).map_err(|m| VerifyError::Contract(ix, m))?;
+    }
+    Ok(())
+}
+
+/// Verify a single signature vector.
+pub fn verify_signature(v: &SignatureVector) -> Result<(), SignatureMismatch> {
+    let sk = SecretKey::from_byte_array(&v.secret_key).map_err(|_| SignatureMismatch::SecretKey)?;
+    let hash = essential_hash::hash_bytes(&v.message);
+    if hash != v.hash {
+        return Err(SignatureMismatch::Hash);
+    }
+    if crate::sign_hash(hash, &sk) != v.signature {
+        return Err(SignatureMismatch::Signature);
+    }
+    let pk = crate::recover_hash(hash, &v.signature).map_err(|_| SignatureMismatch::PublicKey)?;
+    if pk.serialize() != v.public_key {
+        return Err(SignatureMismatch::PublicKey);
+    }
+    if crate::encode::public_key(&pk) != v.public_key_words {
+        return Err(SignatureMismatch::PublicKeyWords);
+    }
+    Ok(())
+}
+
+/// Verify a single contract vector.
+pub fn verify_contract(v: &ContractVector) -> Result<(), ContractMismatch> {
+    if content_addr(&v.contract) != v.content_address {
+        return Err(ContractMismatch::ContentAddress);
+    }
+    if v.contract.predicates.len() != v.predicates.len() {
+        return Err(ContractMismatch::PredicatesLen);
+    }
+    for (ix, (pred, addr)) in v.contract.predicates.iter().zip(&v.predicates).enumerate() {
+        let expected = PredicateAddress {
+            contract: v.content_address.clone(),
+            predicate: content_addr(pred),
+        };
+        if &expected != addr {
+            return Err(ContractMismatch::Predicate(ix));
+        }
+    }
+    Ok(())
+}
+
+/// Derive a deterministic 32-byte value from the seed, index and domain.
+fn derive(seed: u64, ix: u64, domain: &str) -> [u8; 32] {
+    essential_hash::hash(&(domain, seed, ix))
+}
+
+fn signature_vector(seed: u64, ix: u64) -> SignatureVector {
+    // Re-derive in the astronomically unlikely case the bytes are not a valid key.
+    let mut bytes = derive(seed, ix, "secret_key");
+    let sk = loop {
+        match SecretKey::from_byte_array(&bytes) {
+            Ok(sk) => break sk,
+            Err(_) => bytes = essential_hash::hash_bytes(&bytes),
+        }
+    };
+    let msg_len = (ix as usize * 7) % 64;
+    let message: Vec<u8> = derive(seed, ix, "message")
+        .into_iter()
+        .cycle()
+        .take(msg_len)
+        .collect();
+    let hash = essential_hash::hash_bytes(&message);
+    let signature = crate::sign_hash(hash, &sk);
+    let pk = PublicKey::from_secret_key(&secp256k1::Secp256k1::signing_only(), &sk);
+    SignatureVector {
+        secret_key: sk.secret_bytes(),
+        message,
+        hash,
+        signature,
+        public_key: pk.serialize(),
+        public_key_words: crate::encode::public_key(&pk),
+    }
+}
+
+fn contract_vector(seed: u64, ix: u64) -> ContractVector {
+    let num_predicates = 1 + (ix % 3);
+    let predicates: Vec<_> = (0..num_predicates)
+        .map(|p| {
+            let num_nodes = 1 + ((ix + p) % 3) as u16;
+            let nodes = (0..num_nodes)
+                .map(|n| Node {
+                    // Every node but the last is a parent of the last.
+                    edge_start: if n + 1 < num_nodes { n } else { Edge::MAX },
+                    program_address: ContentAddress(derive(seed, ix, &format!("program{p}.{n}"))),
+                    // Cover both the presence and absence of a node gas limit.
+                    max_gas: (ix % 2 == 1).then_some(1_000 + u64::from(n)),
+                })
+                .collect();
+            let edges = (0..num_nodes.saturating_sub(1))
+                .map(|_| num_nodes - 1)
+                .collect();
+            Predicate { nodes, edges }
+        })
+        .collect();
+    let contract = Contract {
+        predicates,
+        salt: derive(seed, ix, "salt"),
+    };
+    let content_address = content_addr(&contract);
+    let predicates = contract
+        .predicates
+        .iter()
+        .map(|pred| PredicateAddress {
+            contract: content_address.clone(),
+            predicate: content_addr(pred),
+        })
+        .collect();
+    ContractVector {
+        contract,
+        content_address,
+        predicates,
+    }
+}
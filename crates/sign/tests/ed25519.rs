@@ -0,0 +1,40 @@
+use essential_sign::{ed25519, sign_hash_ed25519, verify_hash_ed25519};
+use essential_types::convert::bytes_from_word;
+
+#[test]
+fn sign_and_verify() {
+    let sk = ed25519::signing_key([0xcd; 32]);
+    let pk = sk.verifying_key();
+    let hash = essential_hash::hash_bytes(b"data");
+    let signature = sign_hash_ed25519(hash, &sk);
+    verify_hash_ed25519(hash, &signature, &pk).unwrap();
+
+    // Signing is deterministic.
+    assert_eq!(sign_hash_ed25519(hash, &sk), signature);
+
+    // A different hash or key fails.
+    assert!(verify_hash_ed25519([0; 32], &signature, &pk).is_err());
+    let other = ed25519::signing_key([0xab; 32]).verifying_key();
+    assert!(verify_hash_ed25519(hash, &signature, &other).is_err());
+}
+
+#[test]
+fn vm_encoding() {
+    let sk = ed25519::signing_key([1; 32]);
+    let pk = sk.verifying_key();
+    let hash = essential_hash::hash_words(&[1, 2, 3]);
+    let signature = sign_hash_ed25519(hash, &sk);
+
+    let key_words = ed25519::public_key(&pk);
+    let sig_words = ed25519::signature(&signature);
+    assert_eq!(ed25519::public_key_from_words(key_words).unwrap(), pk);
+    assert_eq!(ed25519::signature_from_words(sig_words), signature);
+
+    // The VM verifies the bytes of the data words against the decoded key.
+    let hash_words = essential_types::convert::word_4_from_u8_32(hash);
+    let data: Vec<u8> = hash_words.into_iter().flat_map(bytes_from_word).collect();
+    ed25519::public_key_from_words(key_words)
+        .unwrap()
+        .verify_strict(&data, &ed25519::signature_from_words(sig_words))
+        .unwrap();
+}
@@ -0,0 +1,118 @@
+use essential_sign::hd::{
+    ChildNumber, DerivationPath, DeriveError, ExtendedSecretKey, PathParseError,
+};
+
+// BIP-32 test vector 1.
+const SEED: &str = "000102030405060708090a0b0c0d0e0f";
+
+// The path, chain code and secret key of each derived key.
+const VECTOR_1: &[(&str, &str, &str)] = &[
+    (
+        "m",
+        "873dff81c02f525623fd1fe5167eac3a55a049de3d314bb42ee227ffed37d508",
+        "e8f32e723decf4051aefac8e2c93c9c5b214313817cdb01a1494b917c8436b35",
+    ),
+    (
+        "m/0'",
+        "47fdacbd0f1097043b78c63c20c34ef4ed9a111d980047ad16282c7ae6236141",
+        "edb2e14f9ee77d26dd93b4ecede8d16ed408ce149b6cd80b0715a2d911a0afea",
+    ),
+    (
+        "m/0'/1",
+        "2a7857631386ba23dacac34180dd1983734e444fdbf774041578e9b6adb37c19",
+        "3c6cb8d0f6a264c91ea8b5030fadaa8e538b020f0a387421a12de9319dc93368",
+    ),
+    (
+        "m/0'/1/2'",
+        "04466b9cc8e161e966409ca52986c584f07e9dc81f735db683c3ff6ec7b1503f",
+        "cbce0d719ecf7431d88e6a89fa1483e02e35092af60c042b1df2ff59fa424dca",
+    ),
+    (
+        "m/0'/1/2'/2",
+        "cfb71883f01676f587d023cc53a35bc7f88f724b1f8c2892ac1275ac822a3edd",
+        "0f479245fb19a38a1954c5c7c0ebab2f9bdfd96a17563ef28a6a4b1a2a764ef4",
+    ),
+    (
+        "m/0'/1/2'/2/1000000000",
+        "c783e67b921d2beb8f6b389cc646d7263b4145701dadd2161548a8b078e65e9e",
+        "471b76e389e528d6de6d816857e012c5455051cad6660850e58372a6c3e6e7c8",
+    ),
+];
+
+#[test]
+fn bip32_vector_1() {
+    let master = ExtendedSecretKey::new_master(&hex::decode(SEED).unwrap()).unwrap();
+    for (depth, (path, chain_code, secret_key)) in VECTOR_1.iter().enumerate() {
+        let path: DerivationPath = path.parse().unwrap();
+        let key = master.derive_path(&path).unwrap();
+        assert_eq!(hex::encode(key.chain_code), *chain_code, "{path}");
+        assert_eq!(
+            hex::encode(key.secret_key.secret_bytes()),
+            *secret_key,
+            "{path}"
+        );
+        assert_eq!(usize::from(key.depth), depth);
+    }
+    assert_eq!(
+        hex::encode(master.public_key().public_key.serialize()),
+        "0339a36013301597daef41fbe593a02cc513d0b55527ec2df1050e2e8ff49c85c2",
+    );
+}
+
+#[test]
+fn public_derivation() {
+    let master = ExtendedSecretKey::new_master(&[7; 32]).unwrap();
+    let parent = master
+        .derive_path(&"m/44'/60'/0'".parse().unwrap())
+        .unwrap();
+    let path: DerivationPath = "m/0/5".parse().unwrap();
+    assert_eq!(
+        parent.public_key().derive_path(&path).unwrap(),
+        parent.derive_path(&path).unwrap().public_key(),
+    );
+
+    let hardened = ChildNumber::hardened(0).unwrap();
+    assert_eq!(
+        parent.public_key().derive_child(hardened),
+        Err(DeriveError::HardenedFromPublic(hardened)),
+    );
+}
+
+#[test]
+fn invalid_seed() {
+    for len in [15, 65] {
+        assert_eq!(
+            ExtendedSecretKey::new_master(&vec![0; len]),
+            Err(DeriveError::InvalidSeedLen(len)),
+        );
+    }
+}
+
+#[test]
+fn paths() {
+    let path: DerivationPath = "m/44h/60'/0'/0/1".parse().unwrap();
+    assert_eq!(path, DerivationPath::bip44(60, 0, 0, 1).unwrap());
+    assert_eq!(path.to_string(), "m/44'/60'/0'/0/1");
+    assert_eq!("m".parse(), Ok(DerivationPath::master()));
+    assert_eq!(
+        DerivationPath::master().child(ChildNumber::normal(3).unwrap()),
+        "m/3".parse().unwrap(),
+    );
+
+    assert_eq!(
+        "44'/0".parse::<DerivationPath>(),
+        Err(PathParseError::MissingMaster)
+    );
+    for (path, ix, part) in [
+        ("m/x", 0, "x"),
+        ("m/0/", 1, ""),
+        ("m/2147483648", 0, "2147483648"),
+        ("m/1/-1'", 1, "-1'"),
+    ] {
+        assert_eq!(
+            path.parse::<DerivationPath>(),
+            Err(PathParseError::InvalidChildNumber(ix, part.to_string())),
+        );
+    }
+    assert_eq!(DerivationPath::bip44(1 << 31, 0, 0, 0), None);
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "mnemonic")]
+
+use essential_sign::mnemonic::{Mnemonic, MnemonicError, WORD_COUNTS};
+use rand::SeedableRng;
+
+// BIP-39 test vectors with the passphrase "TREZOR".
+const VECTORS: &[(&str, &str, &str)] = &[
+    (
+        "00000000000000000000000000000000",
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+    ),
+    (
+        "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+        "legal winner thank year wave sausage worth useful legal winner thank yellow",
+        "2e8905819b8723fe2c1d161860e5ee1830318dbf49a83bd451cfb8440c28bd6fa457fe1296106559a3c80937a1c1069be3a3a5bd381ee6260e8d9739fce1f607",
+    ),
+    (
+        "ffffffffffffffffffffffffffffffff",
+        "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong",
+        "ac27495480225222079d7be181583751e86f571027b0497b5b5d11218e0a8a13332572917f0f8e5a589620c6f15b11c61dee327651a14c34e18231052e48c069",
+    ),
+];
+
+#[test]
+fn bip39_vectors() {
+    for (entropy, phrase, seed) in VECTORS {
+        let entropy = hex::decode(entropy).unwrap();
+        let mnemonic = Mnemonic::from_entropy(&entropy).unwrap();
+        assert_eq!(mnemonic.to_string(), *phrase);
+        assert_eq!(phrase.parse::<Mnemonic>(), Ok(mnemonic.clone()));
+        assert_eq!(mnemonic.entropy(), entropy);
+        assert_eq!(hex::encode(mnemonic.to_seed("TREZOR")), *seed);
+    }
+
+    let entropy = [0x80; 32];
+    let mnemonic = Mnemonic::from_entropy(&entropy).unwrap();
+    assert_eq!(
+        mnemonic.to_string(),
+        "letter advice cage absurd amount doctor acoustic avoid letter advice cage absurd \
+         amount doctor acoustic avoid letter advice cage absurd amount doctor acoustic bless",
+    );
+    assert_eq!(mnemonic.entropy(), entropy);
+}
+
+#[test]
+fn generate() {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    for word_count in WORD_COUNTS {
+        let mnemonic = Mnemonic::generate(&mut rng, word_count).unwrap();
+        assert_eq!(mnemonic.words().count(), word_count);
+        assert_eq!(mnemonic.to_string().parse(), Ok(mnemonic));
+    }
+    assert_eq!(
+        Mnemonic::generate(&mut rng, 13),
+        Err(MnemonicError::InvalidWordCount(13))
+    );
+}
+
+#[test]
+fn invalid_mnemonics() {
+    assert_eq!(
+        Mnemonic::from_entropy(&[0; 17]),
+        Err(MnemonicError::InvalidEntropyLen(17))
+    );
+    assert_eq!(
+        "abandon abandon".parse::<Mnemonic>(),
+        Err(MnemonicError::InvalidWordCount(2))
+    );
+    assert_eq!(
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon xyz"
+            .parse::<Mnemonic>(),
+        Err(MnemonicError::UnknownWord(11, "xyz".to_string()))
+    );
+    assert_eq!(
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon"
+            .parse::<Mnemonic>(),
+        Err(MnemonicError::InvalidChecksum)
+    );
+}
@@ -0,0 +1,161 @@
+use essential_sign::multisig::{self, MultiSigError};
+use essential_types::contract::{Contract, MultiSigPolicy};
+use secp256k1::{Secp256k1, SecretKey};
+
+fn keys(n: u8) -> Vec<SecretKey> {
+    (1..=n)
+        .map(|i| SecretKey::from_byte_array(&[i; 32]).unwrap())
+        .collect()
+}
+
+fn policy(threshold: u16, keys: &[SecretKey]) -> MultiSigPolicy {
+    let secp = Secp256k1::new();
+    MultiSigPolicy {
+        threshold,
+        signers: keys.iter().map(|sk| sk.public_key(&secp).into()).collect(),
+    }
+}
+
+fn contract() -> Contract {
+    Contract::without_salt(vec![Default::default()])
+}
+
+fn sign(keys: &[&SecretKey]) -> Vec<essential_types::Signature> {
+    let ca = essential_hash::content_addr(&contract());
+    keys.iter()
+        .map(|sk| essential_sign::sign_hash(ca.0, sk))
+        .collect()
+}
+
+#[test]
+fn aggregate_and_verify() {
+    let keys = keys(4);
+    let policy = policy(3, &keys);
+
+    // Out of order and repeated signatures are normalized.
+    let signatures = sign(&[&keys[3], &keys[0], &keys[3], &keys[1]]);
+    let signed = multisig::aggregate(contract(), policy.clone(), signatures).unwrap();
+    let indices: Vec<_> = signed.signatures.iter().map(|(ix, _)| *ix).collect();
+    assert_eq!(indices, [0, 1, 3]);
+    multisig::verify(&signed, &policy).unwrap();
+
+    // Repeats don't count towards the threshold.
+    let signatures = sign(&[&keys[2], &keys[2], &keys[0]]);
+    assert_eq!(
+        multisig::aggregate(contract(), policy.clone(), signatures),
+        Err(MultiSigError::BelowThreshold {
+            signed: 2,
+            threshold: 3
+        })
+    );
+
+    // Signatures by others are rejected.
+    let outsider = SecretKey::from_byte_array(&[9; 32]).unwrap();
+    let signatures = sign(&[&keys[0], &keys[1], &outsider]);
+    assert_eq!(
+        multisig::aggregate(contract(), policy, signatures),
+        Err(MultiSigError::UnknownSigner)
+    );
+}
+
+#[test]
+fn verify_tampered() {
+    let keys = keys(3);
+    let signatures = sign(&[&keys[0], &keys[2]]);
+    let policy = policy(2, &keys);
+    let signed = multisig::aggregate(contract(), policy.clone(), signatures).unwrap();
+
+    let mut tampered = signed.clone();
+    tampered.contract.salt = [1; 32];
+    assert!(multisig::verify(&tampered, &policy).is_err());
+
+    let mut tampered = signed.clone();
+    tampered.signatures[1].0 = 1;
+    assert_eq!(
+        multisig::verify(&tampered, &policy),
+        Err(MultiSigError::SignerMismatch(1))
+    );
+
+    let mut tampered = signed.clone();
+    tampered.signatures.reverse();
+    assert_eq!(
+        multisig::verify(&tampered, &policy),
+        Err(MultiSigError::UnorderedSigners)
+    );
+
+    let mut tampered = signed.clone();
+    tampered.signatures[1].0 = 7;
+    assert_eq!(
+        multisig::verify(&tampered, &policy),
+        Err(MultiSigError::SignerOutOfBounds(7))
+    );
+
+    let mut tampered = signed.clone();
+    tampered.signatures.pop();
+    assert_eq!(
+        multisig::verify(&tampered, &policy),
+        Err(MultiSigError::BelowThreshold {
+            signed: 1,
+            threshold: 2
+        })
+    );
+
+    let mut tampered = signed.clone();
+    tampered.policy.threshold = 4;
+    assert_eq!(
+        multisig::verify(&tampered, &policy),
+        Err(MultiSigError::PolicyMismatch)
+    );
+    assert_eq!(
+        multisig::verify_hash([0; 32], &tampered.policy, &tampered.signatures),
+        Err(MultiSigError::InvalidThreshold {
+            threshold: 4,
+            signers: 3
+        })
+    );
+
+    // The policy is unsigned, so it can't be replaced with the attacker's own.
+    let attacker = SecretKey::from_byte_array(&[9; 32]).unwrap();
+    let mut forged = signed;
+    forged.policy = self::policy(1, &[attacker]);
+    forged.signatures = vec![(0, sign(&[&attacker]).remove(0))];
+    multisig::verify(&forged, &forged.policy).unwrap();
+    assert_eq!(
+        multisig::verify(&forged, &policy),
+        Err(MultiSigError::PolicyMismatch)
+    );
+}
+
+#[test]
+fn duplicate_signers() {
+    let keys = keys(2);
+    let policy = self::policy(2, &[keys[0], keys[0], keys[1]]);
+    let signatures = sign(&[&keys[0], &keys[0]]);
+    assert_eq!(
+        multisig::aggregate(contract(), policy.clone(), signatures.clone()),
+        Err(MultiSigError::DuplicateSigner(1))
+    );
+    let ca = essential_hash::content_addr(&contract());
+    let indexed = [(0, signatures[0].clone()), (1, signatures[1].clone())];
+    assert_eq!(
+        multisig::verify_hash(ca.0, &policy, &indexed),
+        Err(MultiSigError::DuplicateSigner(1))
+    );
+}
+
+#[test]
+fn serde_roundtrip() {
+    let keys = keys(2);
+    let signed = multisig::aggregate(contract(), policy(1, &keys), sign(&[&keys[1]])).unwrap();
+    let json = serde_json::to_string(&signed).unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&json).unwrap()["policy"]["signers"][0]
+            .as_str()
+            .map(str::len),
+        Some(66)
+    );
+    assert_eq!(
+        serde_json::from_str::<essential_types::contract::MultiSignedContract>(&json).unwrap(),
+        signed
+    );
+}
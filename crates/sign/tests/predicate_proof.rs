@@ -0,0 +1,80 @@
+use essential_sign::contract::{self, PredicateProofError};
+use essential_types::{
+    contract::Contract,
+    predicate::{Node, Predicate},
+    ContentAddress,
+};
+use secp256k1::{Secp256k1, SecretKey};
+
+fn predicate(i: u8) -> Predicate {
+    Predicate {
+        nodes: vec![Node {
+            edge_start: Default::default(),
+            program_address: ContentAddress([i; 32]),
+            max_gas: None,
+        }],
+        edges: vec![],
+    }
+}
+
+fn contract() -> Contract {
+    Contract::without_salt((0..5).map(predicate).collect())
+}
+
+#[test]
+fn verify_each_predicate() {
+    let sk = SecretKey::from_byte_array(&[7; 32]).unwrap();
+    let pk = sk.public_key(&Secp256k1::new());
+    let contract = contract();
+    let signed = contract::sign_predicates(&contract, &sk);
+    assert_eq!(
+        signed.commitment.contract,
+        essential_hash::content_addr(&contract)
+    );
+
+    for predicate in &contract.predicates {
+        let ca = essential_hash::content_addr(predicate);
+        let proof = contract::prove_predicate(&contract, &signed, &ca).unwrap();
+        assert_eq!(contract::recover_predicate(&proof, predicate), Ok(pk));
+        contract::verify_predicate(&proof, predicate, &pk).unwrap();
+    }
+}
+
+#[test]
+fn root_is_order_independent() {
+    let contract = contract();
+    let mut reversed = contract.clone();
+    reversed.predicates.reverse();
+    assert_eq!(contract::commit(&contract), contract::commit(&reversed));
+}
+
+#[test]
+fn reject_invalid_proofs() {
+    let sk = SecretKey::from_byte_array(&[7; 32]).unwrap();
+    let pk = sk.public_key(&Secp256k1::new());
+    let contract = contract();
+    let signed = contract::sign_predicates(&contract, &sk);
+
+    // Predicates outside of the contract have no proof.
+    let outsider = predicate(9);
+    let ca = essential_hash::content_addr(&outsider);
+    assert!(contract::prove_predicate(&contract, &signed, &ca).is_none());
+
+    // A proof does not verify a different predicate.
+    let ca = essential_hash::content_addr(&contract.predicates[0]);
+    let proof = contract::prove_predicate(&contract, &signed, &ca).unwrap();
+    assert_eq!(
+        contract::recover_predicate(&proof, &contract.predicates[1]),
+        Err(PredicateProofError::NotIncluded)
+    );
+
+    // A commitment to a different contract does not verify.
+    let mut forged = proof.clone();
+    forged.commitment.contract = ContentAddress([0; 32]);
+    assert!(contract::verify_predicate(&forged, &contract.predicates[0], &pk).is_err());
+
+    // Nor does a signature by a different key.
+    let other = SecretKey::from_byte_array(&[8; 32]).unwrap();
+    let other = other.public_key(&Secp256k1::new());
+    assert!(contract::verify_predicate(&proof, &contract.predicates[0], &other).is_err());
+}
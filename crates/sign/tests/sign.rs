@@ -1,8 +1,8 @@
 use essential_hash::hash_bytes;
 use essential_sign::contract::sign;
-use essential_types::{contract::Contract, predicate::Predicate};
+use essential_types::{contract::Contract, predicate::Predicate, Signature};
 use rand::SeedableRng;
-use secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+use secp256k1::{ecdsa::RecoverableSignature, Message, PublicKey, Secp256k1, SecretKey};
 
 use essential_sign::sign_message;
 
@@ -22,8 +22,8 @@ fn sign_predicate() {
     let contract = Contract::without_salt(vec![test_predicate()]);
     let signed = sign(contract, &sk);
     let expected_signature_hex = concat!(
-        "0b01fc0dffd2fd66b21c1a1d8d426834a131f30faf617a9c5862c0a09c7c217f",
-        "46d155dc91c014918d6c082eca518d9535fd44d5aa849f80c09589a36818f867",
+        "32b336276dbbce1d8d84f9fc639b151e13e311f74ab9d8ed2e6dda8d98ee5304",
+        "7fde39bb0d4bebf7d62566c5d26448f2351c227dd8bec3ad82044651727a684b",
     );
     let hex = hex::encode(signed.signature.0);
     assert_eq!(expected_signature_hex, hex);
@@ -61,3 +61,39 @@ fn verify_pubkey() {
     assert!(essential_sign::verify_message(&msg, &signed_message.0, &pk).is_ok());
     assert!(essential_sign::verify_message(&msg, &signed_message.0, &pk2).is_err());
 }
+
+#[test]
+fn signature_conversions() {
+    let (sk, pk) = random_keypair([0xcd; 32]);
+    let msg = Message::from_digest(hash_bytes(b"Essential"));
+    let recoverable = Secp256k1::new().sign_ecdsa_recoverable(&msg, &sk);
+
+    let signature = Signature::from(recoverable);
+    assert_eq!(signature, sign_message(&msg, &sk));
+    assert_eq!(RecoverableSignature::try_from(&signature), Ok(recoverable));
+
+    // DER omits the recovery ID, so it is provided on import.
+    let der = signature.to_der().unwrap();
+    assert_eq!(der, recoverable.to_standard().serialize_der().to_vec());
+    let imported = Signature::from_der(&der, signature.1).unwrap();
+    assert_eq!(imported, signature);
+    assert_eq!(
+        essential_sign::recover_from_message(&msg, &imported),
+        Ok(pk)
+    );
+
+    assert!(Signature::from_der(&der, 4).is_err());
+    assert!(Signature::from_der(&der[1..], signature.1).is_err());
+    let invalid = Signature(signature.0, 4);
+    assert!(RecoverableSignature::try_from(invalid).is_err());
+}
+
+#[test]
+fn signature_serde() {
+    let (sk, _pk) = random_keypair([0xcd; 32]);
+    let signature = sign_message(&Message::from_digest([1; 32]), &sk);
+    let json = serde_json::to_string(&signature).unwrap();
+    assert_eq!(json, format!("\"{signature}\""));
+    let decoded: Signature = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, signature);
+}
@@ -0,0 +1,51 @@
+use essential_sign::{
+    contract,
+    signer::{LocalSigner, Signer},
+};
+use essential_types::{contract::Contract, predicate::Predicate, Hash, Signature};
+use futures::executor::block_on;
+use secp256k1::SecretKey;
+
+fn secret_key() -> SecretKey {
+    SecretKey::from_byte_array(&[0xcd; 32]).unwrap()
+}
+
+/// A signer standing in for a remote service that is unavailable.
+struct Unavailable;
+
+impl Signer for Unavailable {
+    type Error = &'static str;
+
+    async fn sign_hash(&self, _hash: Hash) -> Result<Signature, Self::Error> {
+        Err("unavailable")
+    }
+}
+
+#[test]
+fn local_signer() {
+    let signer = LocalSigner::new(secret_key());
+    let hash = [7; 32];
+    let signature = block_on(signer.sign_hash(hash)).unwrap();
+    assert_eq!(signature, essential_sign::sign_hash(hash, &secret_key()));
+    assert_eq!(
+        essential_sign::recover_hash(hash, &signature).unwrap(),
+        signer.public_key()
+    );
+
+    // References are signers too.
+    let by_ref = &signer;
+    assert!(block_on(contract::sign_with(Contract::default(), &by_ref)).is_ok());
+}
+
+#[test]
+fn sign_contract_with() {
+    let contract = Contract::without_salt(vec![Predicate::default()]);
+    let signer = LocalSigner::from(secret_key());
+    let signed = block_on(contract::sign_with(contract.clone(), &signer)).unwrap();
+    assert_eq!(signed, contract::sign(contract.clone(), &secret_key()));
+
+    assert_eq!(
+        block_on(contract::sign_with(contract, &Unavailable)),
+        Err("unavailable")
+    );
+}
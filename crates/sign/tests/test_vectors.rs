@@ -0,0 +1,56 @@
+#![cfg(feature = "test-vectors")]
+
+use essential_sign::test_vectors::{
+    self, ContractMismatch, SignatureMismatch, TestVectors, VerifyError,
+};
+
+const VECTORS: &str = include_str!("vectors/vectors.json");
+
+fn fixture() -> TestVectors {
+    serde_json::from_str(VECTORS).unwrap()
+}
+
+#[test]
+fn fixture_verifies() {
+    test_vectors::verify(&fixture()).unwrap();
+}
+
+#[test]
+fn fixture_matches_generated() {
+    // If this fails, the fixture must be regenerated with the `test_vectors`
+    // example and the change must be communicated to SDK maintainers.
+    assert_eq!(fixture(), test_vectors::generate(0, 4));
+}
+
+#[test]
+fn generated_roundtrips_through_json() {
+    let vectors = test_vectors::generate(42, 16);
+    let json = serde_json::to_string(&vectors).unwrap();
+    let decoded: TestVectors = serde_json::from_str(&json).unwrap();
+    assert_eq!(vectors, decoded);
+    test_vectors::verify(&decoded).unwrap();
+}
+
+#[test]
+fn detects_mismatches() {
+    let mut vectors = fixture();
+    vectors.signatures[1].signature.0[0] ^= 1;
+    assert_eq!(
+        test_vectors::verify(&vectors),
+        Err(VerifyError::Signature(1, SignatureMismatch::Signature)),
+    );
+
+    let mut vectors = fixture();
+    vectors.contracts[2].contract.salt[0] ^= 1;
+    assert_eq!(
+        test_vectors::verify(&vectors),
+        Err(VerifyError::Contract(2, ContractMismatch::ContentAddress)),
+    );
+
+    let mut vectors = fixture();
+    vectors.contracts[0].predicates.clear();
+    assert_eq!(
+        test_vectors::verify(&vectors),
+        Err(VerifyError::Contract(0, ContractMismatch::PredicatesLen)),
+    );
+}
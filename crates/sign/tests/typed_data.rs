@@ -0,0 +1,139 @@
+use essential_sign::encode::typed::{
+    self, Domain, Field, Schema, Type, TypedData, TypedDataError, Value,
+};
+use essential_types::ContentAddress;
+use secp256k1::{Secp256k1, SecretKey};
+
+fn transfer() -> TypedData {
+    let schema = Schema(
+        [(
+            "Transfer".to_string(),
+            vec![
+                Field::new("to", Type::B256),
+                Field::new("amount", Type::Word),
+            ],
+        )]
+        .into(),
+    );
+    TypedData {
+        domain: Domain::new("Token", "1"),
+        schema,
+        primary_type: "Transfer".to_string(),
+        message: Value::Struct(vec![Value::B256([1; 32]), Value::Word(42)]),
+    }
+}
+
+#[test]
+fn digest() {
+    let data = transfer();
+    assert_eq!(
+        hex::encode(data.digest().unwrap()),
+        "72c3a7e8fcf264b1bfd4dd459bd58dd9e681bcebe847a892c63de9269d807313"
+    );
+
+    // The domain separates otherwise identical messages.
+    let mut other = data.clone();
+    other.domain = other.domain.with_contract(ContentAddress([2; 32]));
+    assert_ne!(data.digest(), other.digest());
+    let mut other = data.clone();
+    other.domain.version = "2".to_string();
+    assert_ne!(data.digest(), other.digest());
+}
+
+#[test]
+fn encode_type_with_dependencies() {
+    let schema = Schema(
+        [
+            (
+                "Order".to_string(),
+                vec![
+                    Field::new("maker", Type::Struct("Party".to_string())),
+                    Field::new("legs", Type::Array(Box::new(Type::Struct("Leg".into())))),
+                    Field::new("memo", Type::String),
+                ],
+            ),
+            (
+                "Party".to_string(),
+                vec![Field::new("key", Type::Bytes), Field::new("ok", Type::Bool)],
+            ),
+            (
+                "Leg".to_string(),
+                vec![
+                    Field::new("asset", Type::B256),
+                    Field::new("amount", Type::Word),
+                ],
+            ),
+        ]
+        .into(),
+    );
+    assert_eq!(
+        schema.encode_type("Order").unwrap(),
+        "Order(Party maker,Leg[] legs,string memo)\
+         Leg(b256 asset,word amount)\
+         Party(bytes key,bool ok)"
+    );
+    assert_eq!(
+        schema.encode_type("Party").unwrap(),
+        "Party(bytes key,bool ok)"
+    );
+
+    let order = Value::Struct(vec![
+        Value::Struct(vec![Value::Bytes(vec![1, 2, 3]), Value::Bool(true)]),
+        Value::Array(vec![
+            Value::Struct(vec![Value::B256([3; 32]), Value::Word(-1)]),
+            Value::Struct(vec![Value::B256([4; 32]), Value::Word(7)]),
+        ]),
+        Value::String("hello".to_string()),
+    ]);
+    let data = schema.encode_data("Order", &order).unwrap();
+    assert_eq!(data.len(), 4 * 32);
+    assert_eq!(data[..32], schema.type_hash("Order").unwrap());
+}
+
+#[test]
+fn invalid_typed_data() {
+    let mut data = transfer();
+    data.primary_type = "Mint".to_string();
+    assert_eq!(
+        data.digest(),
+        Err(TypedDataError::UnknownType("Mint".to_string()))
+    );
+
+    let mut data = transfer();
+    data.message = Value::Struct(vec![Value::B256([1; 32])]);
+    assert_eq!(
+        data.digest(),
+        Err(TypedDataError::FieldCountMismatch {
+            ty: "Transfer".to_string(),
+            expected: 2,
+            found: 1,
+        })
+    );
+
+    let mut data = transfer();
+    data.message = Value::Struct(vec![Value::Word(1), Value::Word(42)]);
+    assert_eq!(data.digest(), Err(TypedDataError::TypeMismatch(Type::B256)));
+
+    for name in ["", "word", "a b", "a(b)"] {
+        let mut data = transfer();
+        data.schema.0.get_mut("Transfer").unwrap()[0].name = name.to_string();
+        assert_eq!(
+            data.digest(),
+            Err(TypedDataError::InvalidName(name.to_string()))
+        );
+    }
+}
+
+#[test]
+fn sign_and_recover() {
+    let sk = SecretKey::from_byte_array(&[5; 32]).unwrap();
+    let pk = sk.public_key(&Secp256k1::new());
+    let data = transfer();
+    let sig = typed::sign(&data, &sk).unwrap();
+    assert_eq!(typed::recover(&data, &sig), Ok(pk));
+    assert!(essential_sign::verify_hash(data.digest().unwrap(), &sig).is_ok());
+
+    let mut other = data.clone();
+    other.message = Value::Struct(vec![Value::B256([1; 32]), Value::Word(43)]);
+    assert_ne!(typed::recover(&other, &sig), Ok(pk));
+}
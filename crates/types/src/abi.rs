@@ -0,0 +1,306 @@
+//! # ABI
+//! A canonical mapping of typed values to the words of [`Key`][crate::Key]s
+//! and [`Value`][crate::Value]s.
+//!
+//! Values are encoded by concatenating the encodings of their parts:
+//!
+//! | Type | Layout |
+//! |------|--------|
+//! | `Word`, `i32`, `i16`, `i8`, `u32`, `u16`, `u8` | A single word. |
+//! | `u128` | Two words, see [`word_2_from_u128`]. |
+//! | `bool` | A single word, `0` or `1`. |
+//! | `ContentAddress` | Four words, see [`word_4_from_u8_32`]. |
+//! | `[T; N]` | The `N` elements in order. |
+//! | `(A, B, ..)` | The fields in order. |
+//! | `Vec<T>` | The number of elements, followed by the elements in order. |
+//! | `String` | The byte length followed by the packed bytes, see [`words_from_str`]. |
+//! | `Option<T>` | `0` for `None`, or `1` followed by the value for `Some`. |
+//! | structs | The fields in declaration order. |
+//! | enums | The index of the variant, followed by its fields in declaration order. |
+//!
+//! With the `derive` feature, [`Encode`][macro@Encode] and
+//! [`Decode`][macro@Decode] may be derived for structs and enums whose
+//! fields implement the traits.
+//!
+//! ```
+//! use essential_types::abi::{Decode, Encode};
+//!
+//! let value = (42, Some(true), String::from("hi"));
+//! let words = value.to_words();
+//! assert_eq!(words, vec![42, 1, 1, 2, 0x6869 << 48]);
+//! assert_eq!(<(i64, Option<bool>, String)>::from_words(&words), Ok(value));
+//! ```
+
+use crate::{
+    convert::{
+        packed_str_len_words, str_from_words, u128_from_word_2, u8_32_from_word_4,
+        word_2_from_u128, word_4_from_u8_32, words_from_str, StrFromWordsError,
+    },
+    ContentAddress, Word,
+};
+use alloc::{string::String, vec::Vec};
+
+#[cfg(feature = "derive")]
+pub use essential_abi_derive::{Decode, Encode};
+
+/// Types that may be encoded to words.
+pub trait Encode {
+    /// Append the encoding of `self` to the given words.
+    fn encode(&self, words: &mut Vec<Word>);
+
+    /// Encode `self` to words.
+    fn to_words(&self) -> Vec<Word> {
+        let mut words = Vec::new();
+        self.encode(&mut words);
+        words
+    }
+}
+
+/// Types that may be decoded from words.
+pub trait Decode: Sized {
+    /// Decode a value from the front of the given words, advancing past the
+    /// words that were read.
+    fn decode(words: &mut &[Word]) -> Result<Self, DecodeError>;
+
+    /// Decode a value from exactly the given words.
+    fn from_words(mut words: &[Word]) -> Result<Self, DecodeError> {
+        let value = Self::decode(&mut words)?;
+        match words.len() {
+            0 => Ok(value),
+            n => Err(DecodeError::TrailingWords(n)),
+        }
+    }
+}
+
+/// Errors that can occur when decoding a value.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum DecodeError {
+    /// The words ended before the value was decoded.
+    UnexpectedEnd,
+    /// Words remained after the value was decoded.
+    TrailingWords(usize),
+    /// A word is out of range for the type being decoded.
+    OutOfRange(Word),
+    /// A length prefix is negative.
+    NegativeLength(Word),
+    /// An `Option` or enum tag does not refer to a variant.
+    InvalidTag(Word),
+    /// A string could not be unpacked.
+    Str(StrFromWordsError),
+}
+
+impl core::error::Error for DecodeError {}
+
+impl From<StrFromWordsError> for DecodeError {
+    fn from(err: StrFromWordsError) -> Self {
+        Self::Str(err)
+    }
+}
+
+/// Take the next `n` words, advancing past them.
+pub fn take<'a>(words: &mut &'a [Word], n: usize) -> Result<&'a [Word], DecodeError> {
+    if words.len() < n {
+        return Err(DecodeError::UnexpectedEnd);
+    }
+    let (taken, rest) = words.split_at(n);
+    *words = rest;
+    Ok(taken)
+}
+
+/// Take the next word, advancing past it.
+pub fn take_word(words: &mut &[Word]) -> Result<Word, DecodeError> {
+    Ok(take(words, 1)?[0])
+}
+
+/// Take a length prefix, advancing past it.
+pub fn take_len(words: &mut &[Word]) -> Result<usize, DecodeError> {
+    let len = take_word(words)?;
+    usize::try_from(len).map_err(|_| DecodeError::NegativeLength(len))
+}
+
+impl Encode for Word {
+    fn encode(&self, words: &mut Vec<Word>) {
+        words.push(*self);
+    }
+}
+
+impl Decode for Word {
+    fn decode(words: &mut &[Word]) -> Result<Self, DecodeError> {
+        take_word(words)
+    }
+}
+
+macro_rules! impl_int {
+    ($($ty:ty),*) => {$(
+        impl Encode for $ty {
+            fn encode(&self, words: &mut Vec<Word>) {
+                words.push(Word::from(*self));
+            }
+        }
+
+        impl Decode for $ty {
+            fn decode(words: &mut &[Word]) -> Result<Self, DecodeError> {
+                let word = take_word(words)?;
+                <$ty>::try_from(word).map_err(|_| DecodeError::OutOfRange(word))
+            }
+        }
+    )*};
+}
+
+impl_int!(i32, i16, i8, u32, u16, u8);
+
+impl Encode for u128 {
+    fn encode(&self, words: &mut Vec<Word>) {
+        words.extend(word_2_from_u128(*self));
+    }
+}
+
+impl Decode for u128 {
+    fn decode(words: &mut &[Word]) -> Result<Self, DecodeError> {
+        let taken = take(words, 2)?;
+        Ok(u128_from_word_2(taken.try_into().expect("took 2 words")))
+    }
+}
+
+impl Encode for bool {
+    fn encode(&self, words: &mut Vec<Word>) {
+        words.push(Word::from(*self));
+    }
+}
+
+impl Decode for bool {
+    fn decode(words: &mut &[Word]) -> Result<Self, DecodeError> {
+        let word = take_word(words)?;
+        crate::convert::bool_from_word(word).ok_or(DecodeError::OutOfRange(word))
+    }
+}
+
+impl Encode for ContentAddress {
+    fn encode(&self, words: &mut Vec<Word>) {
+        words.extend(word_4_from_u8_32(self.0));
+    }
+}
+
+impl Decode for ContentAddress {
+    fn decode(words: &mut &[Word]) -> Result<Self, DecodeError> {
+        let taken = take(words, 4)?;
+        let words: [Word; 4] = taken.try_into().expect("took 4 words");
+        Ok(ContentAddress(u8_32_from_word_4(words)))
+    }
+}
+
+impl Encode for str {
+    fn encode(&self, words: &mut Vec<Word>) {
+        words.extend(words_from_str(self));
+    }
+}
+
+impl Encode for String {
+    fn encode(&self, words: &mut Vec<Word>) {
+        self.as_str().encode(words)
+    }
+}
+
+impl Decode for String {
+    fn decode(words: &mut &[Word]) -> Result<Self, DecodeError> {
+        let byte_len = take_len(&mut &words[..])?;
+        let packed = take(words, packed_str_len_words(byte_len))?;
+        Ok(str_from_words(packed)?)
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode(&self, words: &mut Vec<Word>) {
+        match self {
+            None => words.push(0),
+            Some(value) => {
+                words.push(1);
+                value.encode(words);
+            }
+        }
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    fn decode(words: &mut &[Word]) -> Result<Self, DecodeError> {
+        match take_word(words)? {
+            0 => Ok(None),
+            1 => T::decode(words).map(Some),
+            tag => Err(DecodeError::InvalidTag(tag)),
+        }
+    }
+}
+
+impl<T: Encode> Encode for [T] {
+    fn encode(&self, words: &mut Vec<Word>) {
+        // Saturating cast
+        words.push(self.len().try_into().unwrap_or(Word::MAX));
+        self.iter().for_each(|elem| elem.encode(words));
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, words: &mut Vec<Word>) {
+        self.as_slice().encode(words)
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode(words: &mut &[Word]) -> Result<Self, DecodeError> {
+        let len = take_len(words)?;
+        // Each element is at least one word, so don't trust larger lengths.
+        if len > words.len() {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+        (0..len).map(|_| T::decode(words)).collect()
+    }
+}
+
+impl<T: Encode, const N: usize> Encode for [T; N] {
+    fn encode(&self, words: &mut Vec<Word>) {
+        self.iter().for_each(|elem| elem.encode(words));
+    }
+}
+
+impl<T: Decode, const N: usize> Decode for [T; N] {
+    fn decode(words: &mut &[Word]) -> Result<Self, DecodeError> {
+        let elems: Vec<T> = (0..N).map(|_| T::decode(words)).collect::<Result<_, _>>()?;
+        Ok(elems
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("decoded N elements")))
+    }
+}
+
+impl<T: Encode + ?Sized> Encode for &T {
+    fn encode(&self, words: &mut Vec<Word>) {
+        (**self).encode(words)
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: Encode),+> Encode for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn encode(&self, words: &mut Vec<Word>) {
+                let ($($name,)+) = self;
+                $($name.encode(words);)+
+            }
+        }
+
+        impl<$($name: Decode),+> Decode for ($($name,)+) {
+            fn decode(words: &mut &[Word]) -> Result<Self, DecodeError> {
+                Ok(($($name::decode(words)?,)+))
+            }
+        }
+    };
+}
+
+impl_tuple!(A);
+impl_tuple!(A, B);
+impl_tuple!(A, B, C);
+impl_tuple!(A, B, C, D);
+impl_tuple!(A, B, C, D, E);
+impl_tuple!(A, B, C, D, E, F);
+impl_tuple!(A, B, C, D, E, F, G);
+impl_tuple!(A, B, C, D, E, F, G, H);
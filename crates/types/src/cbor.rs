@@ -0,0 +1,487 @@
+//! # CBOR
+//! Canonical [CBOR](https://www.rfc-editor.org/rfc/rfc8949) encoding of the
+//! core types.
+//!
+//! The encoding follows the core deterministic encoding requirements of
+//! RFC 8949 §4.2.1: integers and lengths use the shortest form and all
+//! lengths are definite. Structs are encoded as arrays of their fields in
+//! declaration order, so no map key ordering is involved. Each value has
+//! exactly one encoding, and [`from_slice`] rejects any other, so hashes and
+//! commitments over the encoded bytes may be computed consistently off-chain.
+//!
+//! | Type | Encoding |
+//! |------|----------|
+//! | `Word`, `u16`, `u64` | An unsigned or negative integer. |
+//! | `ContentAddress`, `Hash`, `Program` | A byte string. |
+//! | `String` | A text string. |
+//! | `Vec<T>` | An array of the elements. |
+//! | `Key`, `Value` | An array of the words. |
+//! | `Option<T>` | `null` for `None`, or the value for `Some`. |
+//! | `PredicateAddress` | `[contract, predicate]` |
+//! | `Mutation` | `[key, value]` |
+//! | `Solution` | `[predicate_to_solve, predicate_data, state_mutations]` |
+//! | `SolutionSet` | `[solutions]` |
+//! | `Node` | `[edge_start, program_address, max_gas]` |
+//! | `Predicate` | `[nodes, edges]` |
+//! | `ContractMetadata` | `[name, version, abi_hash, source_commit]` |
+//! | `Contract` | `[predicates, salt]` |
+//! | `ContractWithMetadata` | `[contract, metadata]` |
+
+use crate::{
+    contract::{Contract, ContractMetadata, ContractWithMetadata},
+    predicate::{Node, Predicate, Program},
+    solution::{Mutation, Solution, SolutionSet},
+    ContentAddress, Hash, Key, PredicateAddress, Value, Word,
+};
+use alloc::{string::String, vec::Vec};
+
+/// Major type of unsigned integers.
+const UNSIGNED: u8 = 0;
+/// Major type of negative integers.
+const NEGATIVE: u8 = 1;
+/// Major type of byte strings.
+const BYTES: u8 = 2;
+/// Major type of text strings.
+const TEXT: u8 = 3;
+/// Major type of arrays.
+const ARRAY: u8 = 4;
+/// The encoding of `null`.
+const NULL: u8 = 0xf6;
+
+/// Types with a canonical CBOR encoding.
+pub trait Cbor: Sized {
+    /// Append the canonical encoding of `self` to the given bytes.
+    fn encode_cbor(&self, bytes: &mut Vec<u8>);
+
+    /// Decode a value from the front of the given bytes, advancing past the
+    /// bytes that were read.
+    fn decode_cbor(bytes: &mut &[u8]) -> Result<Self, CborDecodeError>;
+}
+
+/// Errors that can occur when decoding CBOR.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum CborDecodeError {
+    /// The bytes ended before the value was decoded.
+    UnexpectedEnd,
+    /// Bytes remained after the value was decoded.
+    TrailingBytes(usize),
+    /// An item's major type is not the type expected.
+    UnexpectedType {
+        /// The expected major type.
+        expected: u8,
+        /// The major type found.
+        found: u8,
+    },
+    /// An item is not in its canonical form, e.g. an integer or length is not
+    /// encoded in its shortest form or a length is indefinite.
+    NonCanonical,
+    /// An integer is out of range for the type being decoded.
+    OutOfRange,
+    /// An array or byte string has an unexpected length.
+    InvalidLength(u64),
+    /// A text string is not valid UTF-8.
+    InvalidUtf8,
+}
+
+impl core::error::Error for CborDecodeError {}
+
+/// Encode a value to its canonical CBOR bytes.
+pub fn to_vec<T: Cbor>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.encode_cbor(&mut bytes);
+    bytes
+}
+
+/// Decode a value from exactly the given canonical CBOR bytes.
+pub fn from_slice<T: Cbor>(mut bytes: &[u8]) -> Result<T, CborDecodeError> {
+    let value = T::decode_cbor(&mut bytes)?;
+    match bytes.len() {
+        0 => Ok(value),
+        n => Err(CborDecodeError::TrailingBytes(n)),
+    }
+}
+
+/// Append an item head with the given major type and argument in its
+/// shortest form.
+fn encode_head(major: u8, arg: u64, bytes: &mut Vec<u8>) {
+    let major = major << 5;
+    match arg {
+        0..=23 => bytes.push(major | arg as u8),
+        24..=0xff => bytes.extend([major | 24, arg as u8]),
+        0x100..=0xffff => {
+            bytes.push(major | 25);
+            bytes.extend((arg as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            bytes.push(major | 26);
+            bytes.extend((arg as u32).to_be_bytes());
+        }
+        _ => {
+            bytes.push(major | 27);
+            bytes.extend(arg.to_be_bytes());
+        }
+    }
+}
+
+/// Take the next `n` bytes, advancing past them.
+fn take<'a>(bytes: &mut &'a [u8], n: usize) -> Result<&'a [u8], CborDecodeError> {
+    if bytes.len() < n {
+        return Err(CborDecodeError::UnexpectedEnd);
+    }
+    let (taken, rest) = bytes.split_at(n);
+    *bytes = rest;
+    Ok(taken)
+}
+
+/// Decode an item head, returning its major type and argument.
+///
+/// Rejects arguments not in their shortest form, and indefinite lengths.
+fn decode_head(bytes: &mut &[u8]) -> Result<(u8, u64), CborDecodeError> {
+    let initial = take(bytes, 1)?[0];
+    let (major, info) = (initial >> 5, initial & 0x1f);
+    let (arg, min) = match info {
+        0..=23 => return Ok((major, u64::from(info))),
+        24 => (u64::from(take(bytes, 1)?[0]), 24),
+        25 => {
+            let arg = take(bytes, 2)?.try_into().expect("took 2 bytes");
+            (u64::from(u16::from_be_bytes(arg)), 0x100)
+        }
+        26 => {
+            let arg = take(bytes, 4)?.try_into().expect("took 4 bytes");
+            (u64::from(u32::from_be_bytes(arg)), 0x1_0000)
+        }
+        27 => {
+            let arg = take(bytes, 8)?.try_into().expect("took 8 bytes");
+            (u64::from_be_bytes(arg), 0x1_0000_0000)
+        }
+        _ => return Err(CborDecodeError::NonCanonical),
+    };
+    if arg < min {
+        return Err(CborDecodeError::NonCanonical);
+    }
+    Ok((major, arg))
+}
+
+/// Decode an item head of the expected major type, returning its argument.
+fn decode_arg(expected: u8, bytes: &mut &[u8]) -> Result<u64, CborDecodeError> {
+    match decode_head(bytes)? {
+        (found, arg) if found == expected => Ok(arg),
+        (found, _) => Err(CborDecodeError::UnexpectedType { expected, found }),
+    }
+}
+
+/// Decode the head of an array of the given number of fields.
+fn decode_fields(len: u64, bytes: &mut &[u8]) -> Result<(), CborDecodeError> {
+    match decode_arg(ARRAY, bytes)? {
+        n if n == len => Ok(()),
+        n => Err(CborDecodeError::InvalidLength(n)),
+    }
+}
+
+/// Decode a byte string.
+fn decode_bytes<'a>(bytes: &mut &'a [u8]) -> Result<&'a [u8], CborDecodeError> {
+    let len = decode_arg(BYTES, bytes)?;
+    let len = usize::try_from(len).map_err(|_| CborDecodeError::InvalidLength(len))?;
+    take(bytes, len)
+}
+
+fn encode_bytes(value: &[u8], bytes: &mut Vec<u8>) {
+    encode_head(BYTES, value.len() as u64, bytes);
+    bytes.extend_from_slice(value);
+}
+
+impl Cbor for Word {
+    fn encode_cbor(&self, bytes: &mut Vec<u8>) {
+        match u64::try_from(*self) {
+            Ok(arg) => encode_head(UNSIGNED, arg, bytes),
+            // The argument of a negative integer `n` is `-1 - n`.
+            Err(_) => encode_head(NEGATIVE, !*self as u64, bytes),
+        }
+    }
+
+    fn decode_cbor(bytes: &mut &[u8]) -> Result<Self, CborDecodeError> {
+        let (major, arg) = decode_head(bytes)?;
+        let arg = Word::try_from(arg).map_err(|_| CborDecodeError::OutOfRange)?;
+        match major {
+            UNSIGNED => Ok(arg),
+            NEGATIVE => Ok(!arg),
+            found => Err(CborDecodeError::UnexpectedType {
+                expected: UNSIGNED,
+                found,
+            }),
+        }
+    }
+}
+
+impl Cbor for u64 {
+    fn encode_cbor(&self, bytes: &mut Vec<u8>) {
+        encode_head(UNSIGNED, *self, bytes);
+    }
+
+    fn decode_cbor(bytes: &mut &[u8]) -> Result<Self, CborDecodeError> {
+        decode_arg(UNSIGNED, bytes)
+    }
+}
+
+impl Cbor for u16 {
+    fn encode_cbor(&self, bytes: &mut Vec<u8>) {
+        encode_head(UNSIGNED, u64::from(*self), bytes);
+    }
+
+    fn decode_cbor(bytes: &mut &[u8]) -> Result<Self, CborDecodeError> {
+        let arg = decode_arg(UNSIGNED, bytes)?;
+        u16::try_from(arg).map_err(|_| CborDecodeError::OutOfRange)
+    }
+}
+
+impl Cbor for Hash {
+    fn encode_cbor(&self, bytes: &mut Vec<u8>) {
+        encode_bytes(self, bytes);
+    }
+
+    fn decode_cbor(bytes: &mut &[u8]) -> Result<Self, CborDecodeError> {
+        let hash = decode_bytes(bytes)?;
+        hash.try_into()
+            .map_err(|_| CborDecodeError::InvalidLength(hash.len() as u64))
+    }
+}
+
+impl Cbor for ContentAddress {
+    fn encode_cbor(&self, bytes: &mut Vec<u8>) {
+        self.0.encode_cbor(bytes);
+    }
+
+    fn decode_cbor(bytes: &mut &[u8]) -> Result<Self, CborDecodeError> {
+        Hash::decode_cbor(bytes).map(ContentAddress)
+    }
+}
+
+impl Cbor for Program {
+    fn encode_cbor(&self, bytes: &mut Vec<u8>) {
+        encode_bytes(&self.0, bytes);
+    }
+
+    fn decode_cbor(bytes: &mut &[u8]) -> Result<Self, CborDecodeError> {
+        decode_bytes(bytes).map(|program| Program(program.to_vec()))
+    }
+}
+
+impl Cbor for String {
+    fn encode_cbor(&self, bytes: &mut Vec<u8>) {
+        encode_head(TEXT, self.len() as u64, bytes);
+        bytes.extend_from_slice(self.as_bytes());
+    }
+
+    fn decode_cbor(bytes: &mut &[u8]) -> Result<Self, CborDecodeError> {
+        let len = decode_arg(TEXT, bytes)?;
+        let len = usize::try_from(len).map_err(|_| CborDecodeError::InvalidLength(len))?;
+        let text = take(bytes, len)?;
+        core::str::from_utf8(text)
+            .map(String::from)
+            .map_err(|_| CborDecodeError::InvalidUtf8)
+    }
+}
+
+impl<T: Cbor> Cbor for Vec<T> {
+    fn encode_cbor(&self, bytes: &mut Vec<u8>) {
+        encode_head(ARRAY, self.len() as u64, bytes);
+        self.iter().for_each(|elem| elem.encode_cbor(bytes));
+    }
+
+    fn decode_cbor(bytes: &mut &[u8]) -> Result<Self, CborDecodeError> {
+        let len = decode_arg(ARRAY, bytes)?;
+        // Each element is at least one byte, so don't trust larger lengths.
+        if len > bytes.len() as u64 {
+            return Err(CborDecodeError::UnexpectedEnd);
+        }
+        (0..len).map(|_| T::decode_cbor(bytes)).collect()
+    }
+}
+
+macro_rules! impl_words {
+    ($($ty:ty),*) => {$(
+        impl Cbor for $ty {
+            fn encode_cbor(&self, bytes: &mut Vec<u8>) {
+                encode_head(ARRAY, self.len() as u64, bytes);
+                self.iter().for_each(|word| word.encode_cbor(bytes));
+            }
+
+            fn decode_cbor(bytes: &mut &[u8]) -> Result<Self, CborDecodeError> {
+                let words = Vec::<Word>::decode_cbor(bytes)?;
+                <$ty>::new(words).map_err(|err| CborDecodeError::InvalidLength(err.size as u64))
+            }
+        }
+    )*};
+}
+
+impl_words!(Key, Value);
+
+impl<T: Cbor> Cbor for Option<T> {
+    fn encode_cbor(&self, bytes: &mut Vec<u8>) {
+        match self {
+            None => bytes.push(NULL),
+            Some(value) => value.encode_cbor(bytes),
+        }
+    }
+
+    fn decode_cbor(bytes: &mut &[u8]) -> Result<Self, CborDecodeError> {
+        match bytes.first() {
+            Some(&NULL) => {
+                take(bytes, 1)?;
+                Ok(None)
+            }
+            _ => T::decode_cbor(bytes).map(Some),
+        }
+    }
+}
+
+impl Cbor for PredicateAddress {
+    fn encode_cbor(&self, bytes: &mut Vec<u8>) {
+        encode_head(ARRAY, 2, bytes);
+        self.contract.encode_cbor(bytes);
+        self.predicate.encode_cbor(bytes);
+    }
+
+    fn decode_cbor(bytes: &mut &[u8]) -> Result<Self, CborDecodeError> {
+        decode_fields(2, bytes)?;
+        Ok(Self {
+            contract: Cbor::decode_cbor(bytes)?,
+            predicate: Cbor::decode_cbor(bytes)?,
+        })
+    }
+}
+
+impl Cbor for Mutation {
+    fn encode_cbor(&self, bytes: &mut Vec<u8>) {
+        encode_head(ARRAY, 2, bytes);
+        self.key.encode_cbor(bytes);
+        self.value.encode_cbor(bytes);
+    }
+
+    fn decode_cbor(bytes: &mut &[u8]) -> Result<Self, CborDecodeError> {
+        decode_fields(2, bytes)?;
+        Ok(Self {
+            key: Cbor::decode_cbor(bytes)?,
+            value: Cbor::decode_cbor(bytes)?,
+        })
+    }
+}
+
+impl Cbor for Solution {
+    fn encode_cbor(&self, bytes: &mut Vec<u8>) {
+        encode_head(ARRAY, 3, bytes);
+        self.predicate_to_solve.encode_cbor(bytes);
+        self.predicate_data.encode_cbor(bytes);
+        self.state_mutations.encode_cbor(bytes);
+    }
+
+    fn decode_cbor(bytes: &mut &[u8]) -> Result<Self, CborDecodeError> {
+        decode_fields(3, bytes)?;
+        Ok(Self {
+            predicate_to_solve: Cbor::decode_cbor(bytes)?,
+            predicate_data: Cbor::decode_cbor(bytes)?,
+            state_mutations: Cbor::decode_cbor(bytes)?,
+        })
+    }
+}
+
+impl Cbor for SolutionSet {
+    fn encode_cbor(&self, bytes: &mut Vec<u8>) {
+        encode_head(ARRAY, 1, bytes);
+        self.solutions.encode_cbor(bytes);
+    }
+
+    fn decode_cbor(bytes: &mut &[u8]) -> Result<Self, CborDecodeError> {
+        decode_fields(1, bytes)?;
+        Ok(Self {
+            solutions: Cbor::decode_cbor(bytes)?,
+        })
+    }
+}
+
+impl Cbor for Node {
+    fn encode_cbor(&self, bytes: &mut Vec<u8>) {
+        encode_head(ARRAY, 3, bytes);
+        self.edge_start.encode_cbor(bytes);
+        self.program_address.encode_cbor(bytes);
+        self.max_gas.encode_cbor(bytes);
+    }
+
+    fn decode_cbor(bytes: &mut &[u8]) -> Result<Self, CborDecodeError> {
+        decode_fields(3, bytes)?;
+        Ok(Self {
+            edge_start: Cbor::decode_cbor(bytes)?,
+            program_address: Cbor::decode_cbor(bytes)?,
+            max_gas: Cbor::decode_cbor(bytes)?,
+        })
+    }
+}
+
+impl Cbor for Predicate {
+    fn encode_cbor(&self, bytes: &mut Vec<u8>) {
+        encode_head(ARRAY, 2, bytes);
+        self.nodes.encode_cbor(bytes);
+        self.edges.encode_cbor(bytes);
+    }
+
+    fn decode_cbor(bytes: &mut &[u8]) -> Result<Self, CborDecodeError> {
+        decode_fields(2, bytes)?;
+        Ok(Self {
+            nodes: Cbor::decode_cbor(bytes)?,
+            edges: Cbor::decode_cbor(bytes)?,
+        })
+    }
+}
+
+impl Cbor for ContractMetadata {
+    fn encode_cbor(&self, bytes: &mut Vec<u8>) {
+        encode_head(ARRAY, 4, bytes);
+        self.name.encode_cbor(bytes);
+        self.version.encode_cbor(bytes);
+        self.abi_hash.encode_cbor(bytes);
+        self.source_commit.encode_cbor(bytes);
+    }
+
+    fn decode_cbor(bytes: &mut &[u8]) -> Result<Self, CborDecodeError> {
+        decode_fields(4, bytes)?;
+        Ok(Self {
+            name: Cbor::decode_cbor(bytes)?,
+            version: Cbor::decode_cbor(bytes)?,
+            abi_hash: Cbor::decode_cbor(bytes)?,
+            source_commit: Cbor::decode_cbor(bytes)?,
+        })
+    }
+}
+
+impl Cbor for Contract {
+    fn encode_cbor(&self, bytes: &mut Vec<u8>) {
+        encode_head(ARRAY, 2, bytes);
+        self.predicates.encode_cbor(bytes);
+        self.salt.encode_cbor(bytes);
+    }
+
+    fn decode_cbor(bytes: &mut &[u8]) -> Result<Self, CborDecodeError> {
+        decode_fields(2, bytes)?;
+        Ok(Self {
+            predicates: Cbor::decode_cbor(bytes)?,
+            salt: Cbor::decode_cbor(bytes)?,
+        })
+    }
+}
+
+impl Cbor for ContractWithMetadata {
+    fn encode_cbor(&self, bytes: &mut Vec<u8>) {
+        encode_head(ARRAY, 2, bytes);
+        self.contract.encode_cbor(bytes);
+        self.metadata.encode_cbor(bytes);
+    }
+
+    fn decode_cbor(bytes: &mut &[u8]) -> Result<Self, CborDecodeError> {
+        decode_fields(2, bytes)?;
+        Ok(Self {
+            contract: Cbor::decode_cbor(bytes)?,
+            metadata: Cbor::decode_cbor(bytes)?,
+        })
+    }
+}
@@ -2,11 +2,12 @@
 //!
 //! Types needed to represent an contract.
 
-use std::ops::{Deref, DerefMut};
+use alloc::{collections::BTreeSet, string::String, vec::Vec};
+use core::ops::{Deref, DerefMut};
 
 use serde::{Deserialize, Serialize};
 
-use crate::{predicate::Predicate, serde::hash, Hash, Signature};
+use crate::{predicate::Predicate, serde::hash, ContentAddress, Hash, Signature};
 
 #[cfg(feature = "schema")]
 use schemars::JsonSchema;
@@ -16,6 +17,7 @@ use schemars::JsonSchema;
 /// For a shorthand constructor, see the downstream
 /// `essential_sign::contract::sign` function.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
 pub struct SignedContract {
     /// The contract of predicates whose content address has been signed.
     pub contract: Contract,
@@ -28,6 +30,51 @@ pub struct SignedContract {
     pub signature: Signature,
 }
 
+/// A contract whose content address has been signed by at least a
+/// threshold of the signers of a [`MultiSigPolicy`].
+///
+/// The policy is not covered by the signatures, so it must be checked
+/// against a policy from a trusted source during verification.
+///
+/// For aggregation and verification, see the downstream
+/// `essential_sign::multisig` module.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct MultiSignedContract {
+    /// The contract of predicates whose content address has been signed.
+    pub contract: Contract,
+    /// The signers that may approve the contract.
+    pub policy: MultiSigPolicy,
+    /// Signatures over the contract's content address, each paired with the
+    /// index of its signer within the policy.
+    ///
+    /// Signatures are sorted by signer index, with at most one per signer.
+    pub signatures: Vec<(u16, Signature)>,
+}
+
+/// The signers that may approve a [`MultiSignedContract`], and how many
+/// of them must do so.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct MultiSigPolicy {
+    /// The number of distinct signers required.
+    pub threshold: u16,
+    /// The public key of each signer.
+    pub signers: Vec<SignerKey>,
+}
+
+/// The compressed secp256k1 public key of a signer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct SignerKey(
+    #[serde(
+        serialize_with = "hash::serialize",
+        deserialize_with = "hash::deserialize"
+    )]
+    #[cfg_attr(feature = "schema", schemars(with = "String"))]
+    pub [u8; 33],
+);
+
 #[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[cfg_attr(feature = "schema", derive(JsonSchema))]
 /// A contract of predicates.
@@ -42,6 +89,42 @@ pub struct Contract {
     pub salt: Hash,
 }
 
+/// A [`Contract`] along with its [`ContractMetadata`].
+///
+/// The metadata is kept outside of the [`Contract`] so that neither the
+/// contract's encoding nor its content address depend on it. As such, the
+/// metadata is **not** covered by a signature over the contract's content
+/// address and may be replaced by anyone. To bind the metadata to the
+/// contract, check its own content address against one obtained from a
+/// trusted source.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct ContractWithMetadata {
+    /// The contract.
+    pub contract: Contract,
+    /// Human-meaningful information about the contract.
+    pub metadata: ContractMetadata,
+}
+
+/// Human-meaningful information about a [`Contract`], e.g. for display by
+/// explorers and wallets.
+#[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct ContractMetadata {
+    /// The name of the contract.
+    pub name: String,
+    /// The version of the contract, e.g. a semver string.
+    pub version: String,
+    #[serde(
+        serialize_with = "hash::serialize",
+        deserialize_with = "hash::deserialize"
+    )]
+    /// The hash of the contract's ABI.
+    pub abi_hash: Hash,
+    /// The commit of the source from which the contract was compiled.
+    pub source_commit: Option<String>,
+}
+
 impl Contract {
     /// Maximum number of predicates in a contract.
     pub const MAX_PREDICATES: usize = 100;
@@ -53,6 +136,46 @@ impl Contract {
             ..Default::default()
         }
     }
+
+    /// The distinct addresses of the programs of the contract's predicates, in
+    /// order of first use.
+    ///
+    /// Predicates commonly share programs, so this allows fetching or
+    /// interning each program once.
+    ///
+    /// ```
+    /// use essential_types::{contract::Contract, predicate::Predicate, ContentAddress};
+    ///
+    /// let predicate = |programs: &[u8]| {
+    ///     let mut builder = Predicate::build();
+    ///     for &p in programs {
+    ///         builder.node(ContentAddress([p; 32]));
+    ///     }
+    ///     builder.finish().unwrap()
+    /// };
+    /// let contract = Contract::without_salt(vec![predicate(&[1, 2]), predicate(&[2, 3, 1])]);
+    /// let programs: Vec<_> = contract.dedup_programs().into_iter().map(|ca| ca.0[0]).collect();
+    /// assert_eq!(programs, [1, 2, 3]);
+    /// ```
+    pub fn dedup_programs(&self) -> Vec<&ContentAddress> {
+        let mut seen = BTreeSet::new();
+        self.predicates
+            .iter()
+            .flat_map(|predicate| &predicate.nodes)
+            .map(|node| &node.program_address)
+            .filter(|ca| seen.insert(*ca))
+            .collect()
+    }
+
+    /// Pair the contract with the given metadata.
+    ///
+    /// The metadata does not affect the contract's content address.
+    pub fn with_metadata(self, metadata: ContractMetadata) -> ContractWithMetadata {
+        ContractWithMetadata {
+            contract: self,
+            metadata,
+        }
+    }
 }
 
 impl From<Vec<Predicate>> for Contract {
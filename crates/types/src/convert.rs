@@ -1,6 +1,27 @@
 //! Helper functions for converting between byte, word and hex string representations.
+//!
+//! ## Big integers
+//!
+//! Unsigned integers wider than a word are split into words most significant
+//! first, each word holding 64 bits of the value as its two's complement bit
+//! pattern:
+//!
+//! ```text
+//! +-----------------+-----------------+-----+-----------------+
+//! | bits n-64..n    | bits n-128..n-64| ... | bits 0..64      |
+//! +-----------------+-----------------+-----+-----------------+
+//! ```
+//!
+//! A `u128` is split with [`word_2_from_u128`]. A 256-bit value is split from
+//! its 32 big-endian bytes with [`word_4_from_u8_32`], e.g. the output of a
+//! `U256::to_big_endian`.
+//!
+//! As words are signed, the words of such values must be compared as unsigned,
+//! see [`cmp_unsigned_words`]. Two values are equal if and only if their words
+//! are equal.
 
 use crate::{ContentAddress, Signature, Word};
+use alloc::{string::String, vec::Vec};
 pub use hex::FromHexError;
 
 /// Convert a hex string slice to a `Vec<Word>`.
@@ -131,6 +152,114 @@ pub fn u8_64_from_word_8(words: [Word; 8]) -> [u8; 64] {
     ]
 }
 
+/// Split a `u128` into words, most significant first.
+pub fn word_2_from_u128(value: u128) -> [Word; 2] {
+    let [hi, lo] = [(value >> 64) as u64, value as u64];
+    [hi as Word, lo as Word]
+}
+
+/// Combine words split with [`word_2_from_u128`] into a `u128`.
+pub fn u128_from_word_2(words: [Word; 2]) -> u128 {
+    let [hi, lo] = words;
+    (u128::from(hi as u64) << 64) | u128::from(lo as u64)
+}
+
+/// Compare two unsigned integers of the same number of words, e.g. as split
+/// with [`word_2_from_u128`] or [`word_4_from_u8_32`].
+///
+/// Words are compared most significant first, each as a `u64`. Within a
+/// program, the same order is found by comparing the first unequal words,
+/// where a negative word is greater than any non-negative word.
+pub fn cmp_unsigned_words(a: &[Word], b: &[Word]) -> core::cmp::Ordering {
+    debug_assert_eq!(a.len(), b.len());
+    a.iter().map(|&w| w as u64).cmp(b.iter().map(|&w| w as u64))
+}
+
+/// Errors that can occur when unpacking a string from words with [`str_from_words`].
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum StrFromWordsError {
+    /// The words are empty and do not contain the byte length prefix.
+    MissingLength,
+    /// The byte length prefix is negative.
+    NegativeLength(Word),
+    /// The number of words does not match the number required by the byte length prefix.
+    LengthMismatch {
+        /// The number of words expected based on the byte length prefix.
+        expected: usize,
+        /// The number of words provided.
+        found: usize,
+    },
+    /// The padding bytes following the string are not zero.
+    NonZeroPadding,
+    /// The bytes are not valid UTF-8.
+    Utf8(#[cfg_attr(feature = "schema", schemars(with = "String"))] core::str::Utf8Error),
+}
+
+impl core::error::Error for StrFromWordsError {}
+
+/// The number of words required to pack a string of `byte_len` bytes with [`words_from_str`].
+///
+/// This includes the leading byte length word.
+pub const fn packed_str_len_words(byte_len: usize) -> usize {
+    1 + byte_len.div_ceil(core::mem::size_of::<Word>())
+}
+
+/// Pack a UTF-8 string into words.
+///
+/// # Layout
+/// ```text
+/// +-----------------+-----------------+-----------------+-----------------+
+/// | byte length     | bytes 0..8      | bytes 8..16     | ...             |
+/// +-----------------+-----------------+-----------------+-----------------+
+/// ```
+///
+/// Bytes are packed big-endian (see [`word_from_bytes`]) and the final word is
+/// right-padded with zero bytes. This padding rule is canonical: each string has
+/// exactly one encoding, so two packed strings are equal if and only if their
+/// words are equal. This allows comparing strings within programs with a
+/// single `EqRange` over the packed words, provided the byte length words are
+/// first checked for equality.
+pub fn words_from_str(s: &str) -> Vec<Word> {
+    let bytes = s.as_bytes();
+    // Saturating cast
+    let len: Word = bytes.len().try_into().unwrap_or(Word::MAX);
+    core::iter::once(len)
+        .chain(
+            bytes
+                .chunks(core::mem::size_of::<Word>())
+                .map(word_from_bytes_slice),
+        )
+        .collect()
+}
+
+/// Unpack a UTF-8 string from words packed with [`words_from_str`].
+///
+/// Returns an error if the words are not the canonical encoding of a string,
+/// i.e. the number of words does not match the byte length prefix, or any of
+/// the padding bytes are non-zero.
+pub fn str_from_words(words: &[Word]) -> Result<String, StrFromWordsError> {
+    let (&len, packed) = words
+        .split_first()
+        .ok_or(StrFromWordsError::MissingLength)?;
+    let byte_len: usize = len
+        .try_into()
+        .map_err(|_| StrFromWordsError::NegativeLength(len))?;
+    let expected = byte_len.div_ceil(core::mem::size_of::<Word>());
+    if packed.len() != expected {
+        return Err(StrFromWordsError::LengthMismatch {
+            expected: expected + 1,
+            found: words.len(),
+        });
+    }
+    let mut bytes: Vec<u8> = packed.iter().copied().flat_map(bytes_from_word).collect();
+    if bytes[byte_len..].iter().any(|&b| b != 0) {
+        return Err(StrFromWordsError::NonZeroPadding);
+    }
+    bytes.truncate(byte_len);
+    String::from_utf8(bytes).map_err(|e| StrFromWordsError::Utf8(e.utf8_error()))
+}
+
 /// Convert a `Word` to its `bool` representation.
 ///
 /// Returns `None` if the given `Word` is not `0` or `1`.
@@ -221,6 +350,66 @@ impl From<[u8; 65]> for Signature {
     }
 }
 
+#[cfg(feature = "secp256k1")]
+impl From<secp256k1::ecdsa::RecoverableSignature> for Signature {
+    fn from(sig: secp256k1::ecdsa::RecoverableSignature) -> Self {
+        let (id, bytes) = sig.serialize_compact();
+        let id = u8::try_from(i32::from(id)).expect("recovery IDs are in the range `0..=3`");
+        Signature(bytes, id)
+    }
+}
+
+#[cfg(feature = "secp256k1")]
+impl TryFrom<&Signature> for secp256k1::ecdsa::RecoverableSignature {
+    type Error = secp256k1::Error;
+    fn try_from(sig: &Signature) -> Result<Self, Self::Error> {
+        let id = secp256k1::ecdsa::RecoveryId::try_from(i32::from(sig.1))?;
+        Self::from_compact(&sig.0, id)
+    }
+}
+
+#[cfg(feature = "secp256k1")]
+impl TryFrom<Signature> for secp256k1::ecdsa::RecoverableSignature {
+    type Error = secp256k1::Error;
+    fn try_from(sig: Signature) -> Result<Self, Self::Error> {
+        Self::try_from(&sig)
+    }
+}
+
+#[cfg(feature = "secp256k1")]
+impl From<secp256k1::PublicKey> for crate::contract::SignerKey {
+    fn from(key: secp256k1::PublicKey) -> Self {
+        Self(key.serialize())
+    }
+}
+
+#[cfg(feature = "secp256k1")]
+impl TryFrom<&crate::contract::SignerKey> for secp256k1::PublicKey {
+    type Error = secp256k1::Error;
+    fn try_from(key: &crate::contract::SignerKey) -> Result<Self, Self::Error> {
+        Self::from_slice(&key.0)
+    }
+}
+
+#[cfg(feature = "secp256k1")]
+impl Signature {
+    /// Import a DER encoded signature along with its recovery ID.
+    ///
+    /// DER signatures do not include the recovery ID, so it must be provided
+    /// separately, e.g. from the `v` of a wallet's signature.
+    pub fn from_der(der: &[u8], recovery_id: u8) -> Result<Self, secp256k1::Error> {
+        secp256k1::ecdsa::RecoveryId::try_from(i32::from(recovery_id))?;
+        let sig = secp256k1::ecdsa::Signature::from_der(der)?;
+        Ok(Signature(sig.serialize_compact(), recovery_id))
+    }
+
+    /// Export the signature as DER, omitting the recovery ID.
+    pub fn to_der(&self) -> Result<Vec<u8>, secp256k1::Error> {
+        let sig = secp256k1::ecdsa::Signature::from_compact(&self.0)?;
+        Ok(sig.serialize_der().to_vec())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -241,6 +430,68 @@ mod tests {
         0x3C, 0x3D, 0x3E, 0x3F,
     ];
 
+    #[test]
+    fn test_words_from_str() {
+        assert_eq!(words_from_str(""), vec![0]);
+        assert_eq!(
+            words_from_str("abc"),
+            vec![3, word_from_bytes([b'a', b'b', b'c', 0, 0, 0, 0, 0])]
+        );
+        assert_eq!(words_from_str("12345678").len(), packed_str_len_words(8));
+        assert_eq!(words_from_str("123456789").len(), packed_str_len_words(9));
+    }
+
+    #[test]
+    fn test_str_words_roundtrip() {
+        for s in [
+            "",
+            "a",
+            "12345678",
+            "123456789",
+            "héllo wörld",
+            "🦀 essential 🦀",
+        ] {
+            let words = words_from_str(s);
+            assert_eq!(words.len(), packed_str_len_words(s.len()));
+            assert_eq!(str_from_words(&words).unwrap(), s);
+        }
+    }
+
+    #[test]
+    fn test_str_from_words_non_canonical() {
+        assert_eq!(str_from_words(&[]), Err(StrFromWordsError::MissingLength));
+        assert_eq!(
+            str_from_words(&[-1]),
+            Err(StrFromWordsError::NegativeLength(-1))
+        );
+        assert_eq!(
+            str_from_words(&[1]),
+            Err(StrFromWordsError::LengthMismatch {
+                expected: 2,
+                found: 1
+            })
+        );
+        let mut words = words_from_str("ab");
+        words.push(0);
+        assert_eq!(
+            str_from_words(&words),
+            Err(StrFromWordsError::LengthMismatch {
+                expected: 2,
+                found: 3
+            })
+        );
+        let words = vec![2, word_from_bytes([b'a', b'b', b'c', 0, 0, 0, 0, 0])];
+        assert_eq!(
+            str_from_words(&words),
+            Err(StrFromWordsError::NonZeroPadding)
+        );
+        let words = vec![1, word_from_bytes([0xFF, 0, 0, 0, 0, 0, 0, 0])];
+        assert!(matches!(
+            str_from_words(&words),
+            Err(StrFromWordsError::Utf8(_))
+        ));
+    }
+
     #[test]
     fn test_bytes_from_word() {
         assert_eq!(bytes_from_word(WORD_SAMPLE), BYTES_SAMPLE);
@@ -302,4 +553,44 @@ mod tests {
         ];
         assert_eq!(u8_64_from_word_8(words), U8_64_SAMPLE);
     }
+
+    #[test]
+    fn test_u128_words() {
+        assert_eq!(word_2_from_u128(0), [0, 0]);
+        assert_eq!(word_2_from_u128(1), [0, 1]);
+        assert_eq!(word_2_from_u128(u64::MAX.into()), [0, -1]);
+        assert_eq!(word_2_from_u128(1 << 64), [1, 0]);
+        assert_eq!(word_2_from_u128(u128::MAX), [-1, -1]);
+        for value in [0, 1, 42, u64::MAX as u128 + 1, u128::MAX / 3, u128::MAX] {
+            assert_eq!(u128_from_word_2(word_2_from_u128(value)), value);
+        }
+    }
+
+    #[test]
+    fn test_cmp_unsigned_words() {
+        let values = [
+            0,
+            1,
+            i64::MAX as u128,
+            u64::MAX as u128,
+            1 << 64,
+            u128::MAX - 1,
+            u128::MAX,
+        ];
+        for a in values {
+            for b in values {
+                let (wa, wb) = (word_2_from_u128(a), word_2_from_u128(b));
+                assert_eq!(cmp_unsigned_words(&wa, &wb), a.cmp(&b), "{a} {b}");
+            }
+        }
+
+        // 256-bit values split from their big-endian bytes.
+        let mut a = [0; 32];
+        let mut b = [0; 32];
+        a[31] = 1;
+        b[0] = 0x80;
+        let (wa, wb) = (word_4_from_u8_32(a), word_4_from_u8_32(b));
+        assert_eq!(cmp_unsigned_words(&wa, &wb), core::cmp::Ordering::Less);
+        assert_eq!(cmp_unsigned_words(&wb, &wb), core::cmp::Ordering::Equal);
+    }
 }
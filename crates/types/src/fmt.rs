@@ -1,12 +1,24 @@
 //! `core::fmt` implementations and related items.
 
 use crate::{
-    predicate::{PredicateDecodeError, PredicateEncodeError},
-    solution::decode::MutationDecodeError,
+    abi::DecodeError,
+    convert::StrFromWordsError,
+    predicate::{PredicateBuildError, PredicateDecodeError, PredicateEncodeError},
+    solution::{
+        decode::{MutationDecodeError, OutputDecodeError},
+        wire::WireDecodeError,
+    },
+    state::SizeError,
+    versioned::{Kind, VersionedDecodeError},
     ContentAddress, PredicateAddress, Signature,
 };
 use core::{fmt, str};
 
+#[cfg(feature = "cbor")]
+use crate::cbor::CborDecodeError;
+#[cfg(feature = "proto")]
+use crate::proto::ProtoError;
+
 impl fmt::LowerHex for ContentAddress {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         for byte in self.0 {
@@ -72,13 +84,18 @@ impl fmt::Display for Signature {
 
 impl fmt::Display for PredicateDecodeError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                PredicateDecodeError::BytesTooShort => "bytes too short",
+        match self {
+            PredicateDecodeError::BytesTooShort => write!(f, "bytes too short"),
+            PredicateDecodeError::InvalidMaxGasFlag(flag) => {
+                write!(f, "invalid node max gas flag: {flag}")
             }
-        )
+            PredicateDecodeError::NonCanonicalNodes => {
+                write!(f, "extended nodes without any max gas")
+            }
+            PredicateDecodeError::NonZeroMaxGas => {
+                write!(f, "non-zero max gas bytes for a node without max gas")
+            }
+        }
     }
 }
 
@@ -95,18 +112,153 @@ impl fmt::Display for PredicateEncodeError {
     }
 }
 
+impl fmt::Display for PredicateBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PredicateBuildError::TooManyNodes(n) => write!(f, "too many nodes: {n}"),
+            PredicateBuildError::TooManyEdges(n) => write!(f, "too many edges: {n}"),
+            PredicateBuildError::InvalidEdge { parent, child } => {
+                write!(f, "edge from node {parent} to non-existent node {child}")
+            }
+            PredicateBuildError::Cycle(node) => write!(f, "cycle through node {node}"),
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::UnexpectedEnd => write!(f, "unexpected end of words"),
+            DecodeError::TrailingWords(n) => write!(f, "{n} trailing words"),
+            DecodeError::OutOfRange(word) => write!(f, "word out of range: {word}"),
+            DecodeError::NegativeLength(len) => write!(f, "negative length: {len}"),
+            DecodeError::InvalidTag(tag) => write!(f, "invalid tag: {tag}"),
+            DecodeError::Str(err) => write!(f, "invalid string: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "cbor")]
+impl fmt::Display for CborDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CborDecodeError::UnexpectedEnd => write!(f, "unexpected end of bytes"),
+            CborDecodeError::TrailingBytes(n) => write!(f, "{n} trailing bytes"),
+            CborDecodeError::UnexpectedType { expected, found } => {
+                write!(f, "expected major type {expected}, found {found}")
+            }
+            CborDecodeError::NonCanonical => write!(f, "non-canonical encoding"),
+            CborDecodeError::OutOfRange => write!(f, "integer out of range"),
+            CborDecodeError::InvalidLength(len) => write!(f, "invalid length: {len}"),
+            CborDecodeError::InvalidUtf8 => write!(f, "invalid UTF-8 in text string"),
+        }
+    }
+}
+
+#[cfg(feature = "proto")]
+impl fmt::Display for ProtoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProtoError::MissingField(field) => write!(f, "missing field `{field}`"),
+            ProtoError::InvalidLength { field, len } => {
+                write!(f, "field `{field}` has invalid length {len}")
+            }
+            ProtoError::OutOfRange { field, value } => {
+                write!(f, "field `{field}` is out of range: {value}")
+            }
+        }
+    }
+}
+
+impl fmt::Display for SizeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Self { size, max } = self;
+        write!(f, "size of {size} words exceeds the maximum of {max}")
+    }
+}
+
+impl fmt::Display for WireDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WireDecodeError::UnexpectedEnd => write!(f, "unexpected end of bytes"),
+            WireDecodeError::InvalidVarint => write!(f, "invalid varint"),
+            WireDecodeError::InvalidAddressIndex(ix) => {
+                write!(f, "address index {ix} out of range of the address table")
+            }
+            WireDecodeError::TrailingBytes(n) => write!(f, "{n} trailing bytes"),
+            WireDecodeError::Size(err) => write!(f, "invalid key or value: {err}"),
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Kind::Predicate => write!(f, "predicate"),
+            Kind::Program => write!(f, "program"),
+            Kind::SolutionSet => write!(f, "solution set"),
+        }
+    }
+}
+
+impl fmt::Display for VersionedDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VersionedDecodeError::MissingHeader => write!(f, "bytes too short for header"),
+            VersionedDecodeError::KindMismatch { expected, found } => {
+                write!(f, "expected {expected} encoding, found kind {found}")
+            }
+            VersionedDecodeError::UnsupportedVersion { kind, version } => write!(
+                f,
+                "unsupported {kind} encoding version {version}, latest supported is {}",
+                kind.latest_version()
+            ),
+            VersionedDecodeError::TrailingBytes(n) => write!(f, "{n} trailing bytes"),
+            VersionedDecodeError::ProgramTooLarge(len) => {
+                write!(f, "program of {len} bytes exceeds the maximum size")
+            }
+            VersionedDecodeError::Predicate(err) => write!(f, "invalid predicate: {err}"),
+            VersionedDecodeError::SolutionSet(err) => write!(f, "invalid solution set: {err}"),
+        }
+    }
+}
+
 impl fmt::Display for MutationDecodeError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                MutationDecodeError::WordsTooShort =>
-                    "bytes too short for lengths given for key or value",
-                MutationDecodeError::NegativeKeyLength => "negative key length",
-                MutationDecodeError::NegativeValueLength => "negative value length",
+        match self {
+            MutationDecodeError::WordsTooShort => {
+                write!(f, "bytes too short for lengths given for key or value")
             }
-        )
+            MutationDecodeError::NegativeKeyLength => write!(f, "negative key length"),
+            MutationDecodeError::NegativeValueLength => write!(f, "negative value length"),
+            MutationDecodeError::KeyTooLarge => {
+                write!(f, "key length exceeds the maximum key size")
+            }
+            MutationDecodeError::ValueTooLarge => {
+                write!(f, "value length exceeds the maximum value size")
+            }
+            MutationDecodeError::NegativeMutationCount => write!(f, "negative mutation count"),
+            MutationDecodeError::TrailingWords(n) => {
+                write!(f, "{n} trailing words after the mutations")
+            }
+        }
+    }
+}
+
+impl fmt::Display for OutputDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OutputDecodeError::WordsTooShort => {
+                write!(f, "words too short for the counts or lengths given")
+            }
+            OutputDecodeError::NegativeLength => write!(f, "negative count or length"),
+            OutputDecodeError::TrailingWords(n) => {
+                write!(f, "{n} trailing words after the outputs")
+            }
+            OutputDecodeError::Mutations(ix, err) => {
+                write!(f, "invalid mutations in output {ix}: {err}")
+            }
+        }
     }
 }
 
@@ -131,3 +283,18 @@ impl str::FromStr for Signature {
         Ok(bytes.into())
     }
 }
+
+impl fmt::Display for StrFromWordsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingLength => write!(f, "missing string byte length"),
+            Self::NegativeLength(len) => write!(f, "negative string byte length: {len}"),
+            Self::LengthMismatch { expected, found } => write!(
+                f,
+                "expected {expected} words for packed string, found {found}"
+            ),
+            Self::NonZeroPadding => write!(f, "non-zero padding bytes in packed string"),
+            Self::Utf8(err) => write!(f, "invalid utf-8 in packed string: {err}"),
+        }
+    }
+}
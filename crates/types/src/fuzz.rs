@@ -0,0 +1,210 @@
+//! # Fuzzing
+//! [`Arbitrary`] implementations for the core types that produce structurally
+//! valid values, so that fuzzing downstream validation and execution reaches
+//! beyond the first size or shape check.
+//!
+//! - [`Predicate`]s are acyclic with correctly laid out edges, and have at most
+//!   [`MAX_NODES`] nodes.
+//! - [`SolutionSet`]s are non-empty and within the default limits of
+//!   `essential-check`, with no duplicate mutation keys within a solution.
+//! - [`Program`]s are at most [`Program::MAX_SIZE`] bytes. Programs of valid
+//!   ops may be generated from the arbitrary ops of `essential-asm`'s `fuzz`
+//!   feature.
+//!
+//! The generated sizes are kept small so that inputs remain fast to check.
+
+use crate::{
+    contract::{
+        Contract, ContractMetadata, ContractWithMetadata, MultiSigPolicy, MultiSignedContract,
+        SignedContract, SignerKey,
+    },
+    predicate::{Predicate, Program},
+    solution::{Mutation, Solution, SolutionSet},
+    ContentAddress, Key, PredicateAddress, Signature, Value, Word,
+};
+use alloc::{collections::BTreeSet, string::String, vec::Vec};
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+/// Maximum number of nodes of a generated predicate.
+pub const MAX_NODES: u16 = 16;
+/// Maximum number of predicates of a generated contract.
+pub const MAX_PREDICATES: usize = 4;
+/// Maximum number of solutions of a generated solution set.
+pub const MAX_SOLUTIONS: usize = 8;
+/// Maximum number of predicate data values and of state mutations of a
+/// generated solution.
+pub const MAX_SOLUTION_ENTRIES: usize = 8;
+/// Maximum number of words of a generated key.
+pub const MAX_KEY_SIZE: usize = 4;
+/// Maximum number of words of a generated value.
+pub const MAX_VALUE_SIZE: usize = 16;
+
+/// Generate a vec of at most `max` elements with the given function.
+fn vec_of<'a, T>(
+    u: &mut Unstructured<'a>,
+    max: usize,
+    mut f: impl FnMut(&mut Unstructured<'a>) -> Result<T>,
+) -> Result<Vec<T>> {
+    let len = u.int_in_range(0..=max)?;
+    (0..len).map(|_| f(u)).collect()
+}
+
+fn key(u: &mut Unstructured) -> Result<Key> {
+    let words = vec_of(u, MAX_KEY_SIZE, Word::arbitrary)?;
+    Ok(Key::new(words).expect("within `Key::MAX_SIZE`"))
+}
+
+fn value(u: &mut Unstructured) -> Result<Value> {
+    let words = vec_of(u, MAX_VALUE_SIZE, Word::arbitrary)?;
+    Ok(Value::new(words).expect("within `Value::MAX_SIZE`"))
+}
+
+impl<'a> Arbitrary<'a> for ContentAddress {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.arbitrary().map(ContentAddress)
+    }
+}
+
+impl<'a> Arbitrary<'a> for PredicateAddress {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            contract: u.arbitrary()?,
+            predicate: u.arbitrary()?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Signature {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        // Recovery IDs are in the range `0..=3`.
+        Ok(Self(u.arbitrary()?, u.int_in_range(0..=3)?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Program {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let len = u.arbitrary_len::<u8>()?.min(usize::from(Program::MAX_SIZE));
+        Ok(Self(u.bytes(len)?.to_vec()))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Predicate {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let len = u.int_in_range(1..=MAX_NODES)?;
+        let mut builder = Predicate::build();
+        for _ in 0..len {
+            let program_address = u.arbitrary()?;
+            match u.arbitrary()? {
+                Some(max_gas) => builder.node_with_max_gas(program_address, max_gas),
+                None => builder.node(program_address),
+            };
+        }
+        // Only allowing edges to later nodes ensures the graph is acyclic.
+        for child in 1..len {
+            for parent in 0..child {
+                if u.ratio(1u8, 4)? {
+                    builder.edge(parent, child);
+                }
+            }
+        }
+        Ok(builder
+            .finish()
+            .expect("acyclic and within limits by construction"))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Contract {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            predicates: vec_of(u, MAX_PREDICATES, Predicate::arbitrary)?,
+            salt: u.arbitrary()?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for ContractWithMetadata {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            contract: u.arbitrary()?,
+            metadata: u.arbitrary()?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for ContractMetadata {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            name: u.arbitrary::<&str>()?.into(),
+            version: u.arbitrary::<&str>()?.into(),
+            abi_hash: u.arbitrary()?,
+            source_commit: u.arbitrary::<Option<&str>>()?.map(String::from),
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for SignedContract {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            contract: u.arbitrary()?,
+            signature: u.arbitrary()?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for SignerKey {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.arbitrary().map(SignerKey)
+    }
+}
+
+impl<'a> Arbitrary<'a> for MultiSigPolicy {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            threshold: u.arbitrary()?,
+            signers: u.arbitrary()?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for MultiSignedContract {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            contract: u.arbitrary()?,
+            policy: u.arbitrary()?,
+            signatures: u.arbitrary()?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Mutation {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            key: key(u)?,
+            value: value(u)?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Solution {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let predicate_to_solve = u.arbitrary()?;
+        let predicate_data = vec_of(u, MAX_SOLUTION_ENTRIES, value)?;
+        let mut keys = BTreeSet::new();
+        let mut state_mutations = vec_of(u, MAX_SOLUTION_ENTRIES, Mutation::arbitrary)?;
+        state_mutations.retain(|mutation| keys.insert(mutation.key.clone()));
+        Ok(Self {
+            predicate_to_solve,
+            predicate_data,
+            state_mutations,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for SolutionSet {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let len = u.int_in_range(1..=MAX_SOLUTIONS)?;
+        Ok(Self {
+            solutions: (0..len).map(|_| u.arbitrary()).collect::<Result<_>>()?,
+        })
+    }
+}
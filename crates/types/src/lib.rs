@@ -1,8 +1,27 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![forbid(unsafe_code)]
 #![deny(missing_docs)]
 //! # Common types for Essential Chain.
+//!
+//! ## Features
+//!
+//! - `std` (default): Enables `std` support for dependencies. Without it, the
+//!   crate only requires `core` and `alloc`.
+//! - `cbor`: Provides a canonical CBOR encoding of the core types.
+//! - `derive`: Provides derive macros for the [`abi`] encoding traits.
+//! - `fuzz`: Implements `Arbitrary` for the types, see [`fuzz`]. Implies `std`.
+//! - `proto`: Provides protobuf messages for the wire types, see [`proto`].
+//! - `schema`: Derives `JsonSchema` for the types and errors. Implies `std`.
+//! - `secp256k1`: Provides conversions between [`Signature`] and `secp256k1`
+//!   recoverable signatures, and DER import and export.
+//!
+//! All features other than `fuzz` and `schema` may be used without `std`,
+//! e.g. by light clients and zkVM guests.
+
+extern crate alloc;
 
 use ::serde::{Deserialize, Serialize};
+use alloc::vec::Vec;
 #[doc(inline)]
 pub use contract::Contract;
 #[doc(inline)]
@@ -11,13 +30,24 @@ pub use predicate::{Predicate, Program};
 use schemars::JsonSchema;
 #[doc(inline)]
 pub use solution::{Solution, SolutionIndex, SolutionSet};
+#[doc(inline)]
+pub use state::{Key, Value};
 
+pub mod abi;
+#[cfg(feature = "cbor")]
+pub mod cbor;
 pub mod contract;
 pub mod convert;
 pub mod fmt;
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
 pub mod predicate;
+#[cfg(feature = "proto")]
+pub mod proto;
 pub mod serde;
 pub mod solution;
+pub mod state;
+pub mod versioned;
 
 /// Essential ASM encoded as bytes.
 pub type Bytecode = Vec<u8>;
@@ -25,12 +55,6 @@ pub type Bytecode = Vec<u8>;
 /// Single unit of data.
 pub type Word = i64;
 
-/// Key for data.
-pub type Key = Vec<Word>;
-
-/// The data at a key.
-pub type Value = Vec<Word>;
-
 /// Hash encoded as a 32 byte array.
 pub type Hash = [u8; 32];
 
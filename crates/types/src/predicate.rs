@@ -2,12 +2,15 @@
 //! Types needed to represent a predicate.
 
 use crate::{serde::bytecode, ContentAddress};
+use alloc::vec::Vec;
+pub use builder::{PredicateBuildError, PredicateBuilder};
 pub use encode::{PredicateDecodeError, PredicateEncodeError};
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "schema")]
 use schemars::JsonSchema;
 
+pub mod builder;
 pub mod encode;
 
 /// A node in the graph.
@@ -20,6 +23,11 @@ pub struct Node {
     pub edge_start: Edge,
     /// The content address of the [`Program`] that this node executes.
     pub program_address: ContentAddress,
+    /// The maximum amount of gas that may be spent executing this node's program.
+    ///
+    /// Specifying `None` indicates that the node is only bound by the gas
+    /// limit of the solution set as a whole.
+    pub max_gas: Option<u64>,
 }
 
 /// An edge in the graph.
@@ -40,11 +48,14 @@ pub struct Predicate {
 
 /// A program to be executed.
 #[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
 pub struct Program(
     #[serde(
         serialize_with = "bytecode::serialize",
         deserialize_with = "bytecode::deserialize"
     )]
+    // Human-readable formats serialize the bytecode as a hex string.
+    #[cfg_attr(feature = "schema", schemars(with = "String"))]
     pub Vec<u8>,
 );
 
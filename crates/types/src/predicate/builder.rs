@@ -0,0 +1,149 @@
+//! A builder for [`Predicate`]s that lays out and validates the graph.
+
+use super::{Edge, Node, Predicate};
+use crate::ContentAddress;
+use alloc::{vec, vec::Vec};
+
+/// Builds a [`Predicate`] node by node, see [`Predicate::build`].
+///
+/// Nodes are referred to by the index returned when they are added. Edges may
+/// be declared in any order and are laid out in the predicate's edge list
+/// when building is finished.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PredicateBuilder {
+    nodes: Vec<(ContentAddress, Option<u64>)>,
+    edges: Vec<(Edge, Edge)>,
+}
+
+/// [`PredicateBuilder::finish`] error.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum PredicateBuildError {
+    /// The number of nodes exceeds [`Predicate::MAX_NODES`].
+    TooManyNodes(usize),
+    /// The number of edges exceeds [`Predicate::MAX_EDGES`].
+    TooManyEdges(usize),
+    /// An edge refers to a node that was never added.
+    InvalidEdge {
+        /// The parent node of the edge.
+        parent: Edge,
+        /// The child node of the edge.
+        child: Edge,
+    },
+    /// The graph contains a cycle through the node at the given index.
+    Cycle(Edge),
+}
+
+impl core::error::Error for PredicateBuildError {}
+
+impl Predicate {
+    /// Start building a predicate.
+    ///
+    /// ```
+    /// # use essential_types::{predicate::Predicate, ContentAddress};
+    /// let mut builder = Predicate::build();
+    /// let read = builder.node(ContentAddress([0; 32]));
+    /// let check = builder.node_with_max_gas(ContentAddress([1; 32]), 1_000);
+    /// builder.edge(read, check);
+    /// let predicate = builder.finish().unwrap();
+    /// assert_eq!(predicate.node_edges(0), Some(&[1][..]));
+    /// ```
+    pub fn build() -> PredicateBuilder {
+        PredicateBuilder::default()
+    }
+}
+
+impl PredicateBuilder {
+    /// Add a node executing the program at the given address, returning its index.
+    pub fn node(&mut self, program_address: ContentAddress) -> Edge {
+        self.push_node(program_address, None)
+    }
+
+    /// Add a node executing the program at the given address with at most
+    /// `max_gas`, returning its index.
+    pub fn node_with_max_gas(&mut self, program_address: ContentAddress, max_gas: u64) -> Edge {
+        self.push_node(program_address, Some(max_gas))
+    }
+
+    /// Declare that the `child` node depends on the `parent` node.
+    pub fn edge(&mut self, parent: Edge, child: Edge) -> &mut Self {
+        self.edges.push((parent, child));
+        self
+    }
+
+    /// Finish building the predicate.
+    ///
+    /// Fails if the predicate exceeds the size limits, an edge refers to a
+    /// node that was never added, or the graph contains a cycle.
+    pub fn finish(self) -> Result<Predicate, PredicateBuildError> {
+        if self.nodes.len() > usize::from(Predicate::MAX_NODES) {
+            return Err(PredicateBuildError::TooManyNodes(self.nodes.len()));
+        }
+        if self.edges.len() > usize::from(Predicate::MAX_EDGES) {
+            return Err(PredicateBuildError::TooManyEdges(self.edges.len()));
+        }
+        let mut children = vec![Vec::new(); self.nodes.len()];
+        for &(parent, child) in &self.edges {
+            match children.get_mut(usize::from(parent)) {
+                Some(edges) if usize::from(child) < self.nodes.len() => edges.push(child),
+                _ => return Err(PredicateBuildError::InvalidEdge { parent, child }),
+            }
+        }
+        if let Some(node) = find_cycle(&children) {
+            return Err(PredicateBuildError::Cycle(node));
+        }
+
+        // A leaf followed by a node with edges is given an empty range of
+        // edges rather than `Edge::MAX`, so that the preceding node's edges
+        // still end where the following node's begin.
+        let last_parent = children.iter().rposition(|edges| !edges.is_empty());
+        let mut edges = Vec::with_capacity(self.edges.len());
+        let nodes = self
+            .nodes
+            .into_iter()
+            .zip(&children)
+            .enumerate()
+            .map(|(ix, ((program_address, max_gas), children))| {
+                let edge_start = if last_parent.is_some_and(|last| ix <= last) {
+                    edges.len() as Edge
+                } else {
+                    Edge::MAX
+                };
+                edges.extend_from_slice(children);
+                Node {
+                    edge_start,
+                    program_address,
+                    max_gas,
+                }
+            })
+            .collect();
+        Ok(Predicate { nodes, edges })
+    }
+
+    fn push_node(&mut self, program_address: ContentAddress, max_gas: Option<u64>) -> Edge {
+        // Too many nodes are reported on `finish`.
+        let ix = Edge::try_from(self.nodes.len()).unwrap_or(Edge::MAX);
+        self.nodes.push((program_address, max_gas));
+        ix
+    }
+}
+
+/// Find a node within a cycle of the graph given by each node's children by
+/// repeatedly removing nodes without parents.
+fn find_cycle(children: &[Vec<Edge>]) -> Option<Edge> {
+    let mut parents = vec![0usize; children.len()];
+    for &child in children.iter().flatten() {
+        parents[usize::from(child)] += 1;
+    }
+    let mut roots: Vec<usize> = (0..children.len()).filter(|&n| parents[n] == 0).collect();
+    while let Some(node) = roots.pop() {
+        for &child in &children[node] {
+            let child = usize::from(child);
+            parents[child] -= 1;
+            if parents[child] == 0 {
+                roots.push(child);
+            }
+        }
+    }
+    parents.iter().position(|&n| n > 0).map(|n| n as Edge)
+}
@@ -4,8 +4,8 @@
 //! ## Predicate
 //! | Field | Size (bytes) | Description |
 //! | --- | --- | --- |
-//! | number_of_nodes | 2 | The number of nodes in the predicate. |
-//! | nodes | 34 * number_of_nodes | The nodes in the predicate. |
+//! | number_of_nodes | 2 | The number of nodes in the predicate. The highest bit is set if the nodes are extended. |
+//! | nodes | (34 or 43) * number_of_nodes | The nodes in the predicate. |
 //! | number_of_edges | 2 | The number of edges in the predicate. |
 //! | edges | 2 * number_of_edges | The edges in the predicate. |
 //!
@@ -15,29 +15,51 @@
 //! | edge_start | 2 | The index of the first edge in the edge list. |
 //! | program_address | 32 | The address of the program. |
 //!
+//! ## Extended Node
+//! Nodes are extended only if at least one node specifies a `max_gas`, so the
+//! encoding and content address of predicates without gas limits is unchanged.
+//!
+//! | Field | Size (bytes) | Description |
+//! | --- | --- | --- |
+//! | edge_start | 2 | The index of the first edge in the edge list. |
+//! | program_address | 32 | The address of the program. |
+//! | has_max_gas | 1 | `1` if the node specifies a `max_gas`, otherwise `0`. |
+//! | max_gas | 8 | The maximum gas for the node, or `0` if not specified. |
+//!
 //! ## Edge
 //! | Field | Size (bytes) | Description |
 //! | --- | --- | --- |
 //! | edge | 2 | The index of the node that this edge points to. |
 
 use super::*;
+use alloc::vec::Vec;
 
 #[cfg(test)]
 mod tests;
 
 const NODE_SIZE_BYTES: usize = 34;
+const EXTENDED_NODE_SIZE_BYTES: usize = NODE_SIZE_BYTES + 1 + core::mem::size_of::<u64>();
+const EXTENDED_NODES_FLAG: u16 = 1 << 15;
 const EDGE_SIZE_BYTES: usize = core::mem::size_of::<u16>();
 const LEN_SIZE_BYTES: usize = core::mem::size_of::<u16>();
 
 /// Errors that can occur when decoding a predicate.
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum PredicateDecodeError {
     /// The bytes are too short to contain the number of nodes.
     BytesTooShort,
+    /// A node's `has_max_gas` flag was neither `0` nor `1`.
+    InvalidMaxGasFlag(u8),
+    /// The nodes are extended but none of them specifies a `max_gas`.
+    NonCanonicalNodes,
+    /// A node without a `max_gas` has non-zero `max_gas` bytes.
+    NonZeroMaxGas,
 }
 
 /// Errors that can occur when encoding a predicate.
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum PredicateEncodeError {
     /// The predicate contains too many nodes.
     TooManyNodes,
@@ -45,9 +67,9 @@ pub enum PredicateEncodeError {
     TooManyEdges,
 }
 
-impl std::error::Error for PredicateDecodeError {}
+impl core::error::Error for PredicateDecodeError {}
 
-impl std::error::Error for PredicateEncodeError {}
+impl core::error::Error for PredicateEncodeError {}
 
 /// Encode a predicate into bytes.
 pub fn encode_predicate(
@@ -63,14 +85,25 @@ pub fn encode_predicate(
     } else {
         return Err(PredicateEncodeError::TooManyEdges);
     };
-    let iter = num_nodes
+    let extended = has_max_gas(predicate);
+    let header = if extended {
+        num_nodes | EXTENDED_NODES_FLAG
+    } else {
+        num_nodes
+    };
+    let iter = header
         .to_be_bytes()
         .into_iter()
-        .chain(predicate.nodes.iter().flat_map(|node| {
+        .chain(predicate.nodes.iter().flat_map(move |node| {
+            let max_gas = extended.then(|| {
+                core::iter::once(node.max_gas.is_some() as u8)
+                    .chain(node.max_gas.unwrap_or(0).to_be_bytes())
+            });
             node.edge_start
                 .to_be_bytes()
                 .into_iter()
                 .chain(node.program_address.0.iter().copied())
+                .chain(max_gas.into_iter().flatten())
         }))
         .chain(num_edges.to_be_bytes())
         .chain(predicate.edges.iter().flat_map(|edge| edge.to_be_bytes()));
@@ -79,37 +112,39 @@ pub fn encode_predicate(
 
 /// The size of the encoded predicate.
 pub fn predicate_encoded_size(predicate: &Predicate) -> usize {
-    predicate.nodes.len() * NODE_SIZE_BYTES + predicate.edges.len() * EDGE_SIZE_BYTES + 2
+    predicate.nodes.len() * node_size(has_max_gas(predicate))
+        + predicate.edges.len() * EDGE_SIZE_BYTES
+        + 2 * LEN_SIZE_BYTES
 }
 
 /// Decode a predicate from bytes.
 pub fn decode_predicate(bytes: &[u8]) -> Result<Predicate, PredicateDecodeError> {
-    let Some(num_nodes) = bytes.get(..LEN_SIZE_BYTES).map(|x| {
+    let Some(header) = bytes.get(..LEN_SIZE_BYTES).map(|x| {
         let mut arr = [0; LEN_SIZE_BYTES];
         arr.copy_from_slice(x);
         u16::from_be_bytes(arr)
     }) else {
         return Err(PredicateDecodeError::BytesTooShort);
     };
+    let extended = header & EXTENDED_NODES_FLAG != 0;
+    let num_nodes = header & !EXTENDED_NODES_FLAG;
+    let node_size = node_size(extended);
 
     let nodes: Vec<_> =
-        match bytes.get(LEN_SIZE_BYTES..(LEN_SIZE_BYTES + num_nodes as usize * NODE_SIZE_BYTES)) {
+        match bytes.get(LEN_SIZE_BYTES..(LEN_SIZE_BYTES + num_nodes as usize * node_size)) {
             Some(bytes) => bytes
-                .chunks_exact(NODE_SIZE_BYTES)
+                .chunks_exact(node_size)
                 .take(num_nodes as usize)
-                .map(|node| Node {
-                    edge_start: u16::from_be_bytes(
-                        node[..2].try_into().expect("safe due to chunks exact"),
-                    ),
-                    program_address: ContentAddress(
-                        node[2..].try_into().expect("safe due to chunks exact"),
-                    ),
-                })
-                .collect(),
+                .map(|node| decode_node(node, extended))
+                .collect::<Result<_, _>>()?,
             None => return Err(PredicateDecodeError::BytesTooShort),
         };
+    // Only one encoding of each predicate may exist.
+    if extended && nodes.iter().all(|node: &Node| node.max_gas.is_none()) {
+        return Err(PredicateDecodeError::NonCanonicalNodes);
+    }
 
-    let num_edges_pos = num_nodes as usize * NODE_SIZE_BYTES + LEN_SIZE_BYTES;
+    let num_edges_pos = num_nodes as usize * node_size + LEN_SIZE_BYTES;
     let Some(num_edges) = bytes.get(num_edges_pos..(num_edges_pos + 2)).map(|x| {
         let mut arr = [0; 2];
         arr.copy_from_slice(x);
@@ -133,3 +168,38 @@ pub fn decode_predicate(bytes: &[u8]) -> Result<Predicate, PredicateDecodeError>
         };
     Ok(Predicate { nodes, edges })
 }
+
+/// Whether any of the predicate's nodes specifies a `max_gas`, requiring
+/// extended nodes.
+fn has_max_gas(predicate: &Predicate) -> bool {
+    predicate.nodes.iter().any(|node| node.max_gas.is_some())
+}
+
+/// The size of each encoded node.
+fn node_size(extended: bool) -> usize {
+    if extended {
+        EXTENDED_NODE_SIZE_BYTES
+    } else {
+        NODE_SIZE_BYTES
+    }
+}
+
+/// Decode a single node from exactly `node_size(extended)` bytes.
+fn decode_node(node: &[u8], extended: bool) -> Result<Node, PredicateDecodeError> {
+    let max_gas = if extended {
+        let max_gas = u64::from_be_bytes(node[35..].try_into().expect("safe due to chunks exact"));
+        match node[34] {
+            0 if max_gas != 0 => return Err(PredicateDecodeError::NonZeroMaxGas),
+            0 => None,
+            1 => Some(max_gas),
+            flag => return Err(PredicateDecodeError::InvalidMaxGasFlag(flag)),
+        }
+    } else {
+        None
+    };
+    Ok(Node {
+        edge_start: u16::from_be_bytes(node[..2].try_into().expect("safe due to chunks exact")),
+        program_address: ContentAddress(node[2..34].try_into().expect("safe due to chunks exact")),
+        max_gas,
+    })
+}
@@ -7,22 +7,27 @@ fn test_encode_predicate() {
             Node {
                 edge_start: 0,
                 program_address: ContentAddress([0; 32]),
+                max_gas: None,
             },
             Node {
                 edge_start: u16::MAX,
                 program_address: ContentAddress([1; 32]),
+                max_gas: None,
             },
             Node {
                 edge_start: 2,
                 program_address: ContentAddress([2; 32]),
+                max_gas: None,
             },
             Node {
                 edge_start: u16::MAX,
                 program_address: ContentAddress([3; 32]),
+                max_gas: None,
             },
             Node {
                 edge_start: u16::MAX,
                 program_address: ContentAddress([4; 32]),
+                max_gas: None,
             },
         ],
         edges: vec![1, 2, 3, 4],
@@ -56,3 +61,120 @@ fn test_encode_predicate() {
     let decoded = decode_predicate(&encoded).unwrap();
     assert_eq!(decoded, predicate);
 }
+
+#[test]
+fn test_encode_predicate_extended() {
+    let predicate = Predicate {
+        nodes: vec![
+            Node {
+                edge_start: 0,
+                program_address: ContentAddress([0; 32]),
+                max_gas: None,
+            },
+            Node {
+                edge_start: u16::MAX,
+                program_address: ContentAddress([1; 32]),
+                max_gas: None,
+            },
+            Node {
+                edge_start: 2,
+                program_address: ContentAddress([2; 32]),
+                max_gas: Some(1000),
+            },
+            Node {
+                edge_start: u16::MAX,
+                program_address: ContentAddress([3; 32]),
+                max_gas: None,
+            },
+            Node {
+                edge_start: u16::MAX,
+                program_address: ContentAddress([4; 32]),
+                max_gas: None,
+            },
+        ],
+        edges: vec![1, 2, 3, 4],
+    };
+    let encoded: Vec<u8> = encode_predicate(&predicate).unwrap().collect();
+    let expected = [
+        (5u16 | EXTENDED_NODES_FLAG).to_be_bytes().to_vec(), // len of nodes
+        // node 0
+        0u16.to_be_bytes().to_vec(), // edge_start
+        vec![0; 32],                 // program_address
+        vec![0; 9],                  // max_gas
+        // node 1
+        u16::MAX.to_be_bytes().to_vec(), // edge_start
+        vec![1; 32],                     // program_address
+        vec![0; 9],                      // max_gas
+        // node 2
+        2u16.to_be_bytes().to_vec(),    // edge_start
+        vec![2; 32],                    // program_address
+        vec![1],                        // has_max_gas
+        1000u64.to_be_bytes().to_vec(), // max_gas
+        // node 3
+        u16::MAX.to_be_bytes().to_vec(), // edge_start
+        vec![3; 32],                     // program_address
+        vec![0; 9],                      // max_gas
+        // node 4
+        u16::MAX.to_be_bytes().to_vec(), // edge_start
+        vec![4; 32],                     // program_address
+        vec![0; 9],                      // max_gas
+        4u16.to_be_bytes().to_vec(),     // len of edges
+        [1u16, 2, 3, 4]
+            .into_iter()
+            .flat_map(|x| x.to_be_bytes())
+            .collect::<Vec<u8>>(),
+    ]
+    .concat();
+    assert_eq!(encoded, expected);
+    assert_eq!(predicate_encoded_size(&predicate), encoded.len());
+    let decoded = decode_predicate(&encoded).unwrap();
+    assert_eq!(decoded, predicate);
+}
+
+#[test]
+fn test_decode_invalid_max_gas_flag() {
+    let predicate = Predicate {
+        nodes: vec![Node {
+            edge_start: Edge::MAX,
+            program_address: ContentAddress([0; 32]),
+            max_gas: Some(1),
+        }],
+        edges: vec![],
+    };
+    let mut encoded: Vec<u8> = encode_predicate(&predicate).unwrap().collect();
+    // The flag follows the length, edge start and program address.
+    encoded[2 + 2 + 32] = 2;
+    assert_eq!(
+        decode_predicate(&encoded).unwrap_err(),
+        PredicateDecodeError::InvalidMaxGasFlag(2)
+    );
+
+    // Extended nodes must specify at least one max gas.
+    encoded[2 + 2 + 32..].fill(0);
+    assert_eq!(
+        decode_predicate(&encoded).unwrap_err(),
+        PredicateDecodeError::NonCanonicalNodes
+    );
+}
+
+#[test]
+fn test_decode_non_zero_max_gas() {
+    let node = |max_gas| Node {
+        edge_start: Edge::MAX,
+        program_address: ContentAddress([0; 32]),
+        max_gas,
+    };
+    let predicate = Predicate {
+        nodes: vec![node(Some(1)), node(None)],
+        edges: vec![],
+    };
+    let mut encoded: Vec<u8> = encode_predicate(&predicate).unwrap().collect();
+    // The max gas of the second node follows its flag.
+    let second = 2 + EXTENDED_NODE_SIZE_BYTES;
+    assert_eq!(encoded[second + 34], 0);
+    encoded[second + 42] = 1;
+    assert_eq!(
+        decode_predicate(&encoded).unwrap_err(),
+        PredicateDecodeError::NonZeroMaxGas
+    );
+}
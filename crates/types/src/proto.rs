@@ -0,0 +1,483 @@
+//! # Protobuf
+//! [`prost`] messages for the wire types along with conversions to and from
+//! the core types.
+//!
+//! The messages mirror the definitions in `proto/essential/types.proto`.
+//! Converting a message to a core type fails if a required message field is
+//! missing, or a field is out of range for the core type.
+//!
+//! ```
+//! use essential_types::proto::{self, Message};
+//! # use essential_types::{predicate::Predicate, contract::Contract};
+//! let contract = Contract::without_salt(vec![Predicate::default()]);
+//! let bytes = proto::Contract::from(contract.clone()).encode_to_vec();
+//! let decoded = proto::Contract::decode(&bytes[..]).unwrap();
+//! assert_eq!(Contract::try_from(decoded), Ok(contract));
+//! ```
+
+use crate::{contract, predicate, solution, state::SizeError, ContentAddress, Hash, Word};
+use alloc::{string::String, vec::Vec};
+
+pub use prost::Message;
+
+/// Errors that can occur when converting a message to a core type.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum ProtoError {
+    /// A required message field is missing.
+    MissingField(&'static str),
+    /// A bytes field does not have the required length, or a repeated field
+    /// exceeds its maximum length.
+    InvalidLength {
+        /// The name of the field.
+        field: &'static str,
+        /// The length of the field.
+        len: usize,
+    },
+    /// An integer field is out of range for the core type.
+    OutOfRange {
+        /// The name of the field.
+        field: &'static str,
+        /// The value of the field.
+        value: u32,
+    },
+}
+
+impl core::error::Error for ProtoError {}
+
+/// The address of a predicate.
+#[derive(Clone, PartialEq, Message)]
+pub struct PredicateAddress {
+    /// The content address of the contract.
+    #[prost(bytes = "vec", tag = "1")]
+    pub contract: Vec<u8>,
+    /// The content address of the predicate.
+    #[prost(bytes = "vec", tag = "2")]
+    pub predicate: Vec<u8>,
+}
+
+/// A sequence of words, e.g. a single predicate data value.
+#[derive(Clone, PartialEq, Message)]
+pub struct Words {
+    /// The words.
+    #[prost(sint64, repeated, tag = "1")]
+    pub words: Vec<Word>,
+}
+
+/// A state mutation.
+#[derive(Clone, PartialEq, Message)]
+pub struct Mutation {
+    /// The key to mutate.
+    #[prost(sint64, repeated, tag = "1")]
+    pub key: Vec<Word>,
+    /// The new value, or empty to delete the key.
+    #[prost(sint64, repeated, tag = "2")]
+    pub value: Vec<Word>,
+}
+
+/// A solution to a single predicate.
+#[derive(Clone, PartialEq, Message)]
+pub struct Solution {
+    /// The predicate that the solution solves.
+    #[prost(message, optional, tag = "1")]
+    pub predicate_to_solve: Option<PredicateAddress>,
+    /// The predicate data.
+    #[prost(message, repeated, tag = "2")]
+    pub predicate_data: Vec<Words>,
+    /// The state mutations.
+    #[prost(message, repeated, tag = "3")]
+    pub state_mutations: Vec<Mutation>,
+}
+
+/// A set of solutions.
+#[derive(Clone, PartialEq, Message)]
+pub struct SolutionSet {
+    /// The solutions in the set.
+    #[prost(message, repeated, tag = "1")]
+    pub solutions: Vec<Solution>,
+}
+
+/// A node in a predicate's graph.
+#[derive(Clone, PartialEq, Message)]
+pub struct Node {
+    /// The start of the node's edges, or `u16::MAX` for a leaf.
+    #[prost(uint32, tag = "1")]
+    pub edge_start: u32,
+    /// The content address of the node's program.
+    #[prost(bytes = "vec", tag = "2")]
+    pub program_address: Vec<u8>,
+    /// The maximum gas the node's program may spend.
+    #[prost(uint64, optional, tag = "3")]
+    pub max_gas: Option<u64>,
+}
+
+/// A predicate's graph of programs.
+#[derive(Clone, PartialEq, Message)]
+pub struct Predicate {
+    /// The nodes of the graph.
+    #[prost(message, repeated, tag = "1")]
+    pub nodes: Vec<Node>,
+    /// The edges of the graph.
+    #[prost(uint32, repeated, tag = "2")]
+    pub edges: Vec<u32>,
+}
+
+/// A program's bytecode.
+#[derive(Clone, PartialEq, Message)]
+pub struct Program {
+    /// The bytecode.
+    #[prost(bytes = "vec", tag = "1")]
+    pub bytecode: Vec<u8>,
+}
+
+/// Human-meaningful information about a contract.
+#[derive(Clone, PartialEq, Message)]
+pub struct ContractMetadata {
+    /// The name of the contract.
+    #[prost(string, tag = "1")]
+    pub name: String,
+    /// The version of the contract.
+    #[prost(string, tag = "2")]
+    pub version: String,
+    /// The hash of the contract's ABI.
+    #[prost(bytes = "vec", tag = "3")]
+    pub abi_hash: Vec<u8>,
+    /// The commit of the source from which the contract was compiled.
+    #[prost(string, optional, tag = "4")]
+    pub source_commit: Option<String>,
+}
+
+/// A contract of predicates.
+#[derive(Clone, PartialEq, Message)]
+pub struct Contract {
+    /// The predicates of the contract.
+    #[prost(message, repeated, tag = "1")]
+    pub predicates: Vec<Predicate>,
+    /// The salt of the contract.
+    #[prost(bytes = "vec", tag = "2")]
+    pub salt: Vec<u8>,
+}
+
+/// A contract along with its metadata.
+#[derive(Clone, PartialEq, Message)]
+pub struct ContractWithMetadata {
+    /// The contract.
+    #[prost(message, optional, tag = "1")]
+    pub contract: Option<Contract>,
+    /// The contract's metadata, which is not part of its content address.
+    #[prost(message, optional, tag = "2")]
+    pub metadata: Option<ContractMetadata>,
+}
+
+/// A recoverable ECDSA signature.
+#[derive(Clone, PartialEq, Message)]
+pub struct Signature {
+    /// The 64-byte compact signature.
+    #[prost(bytes = "vec", tag = "1")]
+    pub compact: Vec<u8>,
+    /// The recovery ID.
+    #[prost(uint32, tag = "2")]
+    pub id: u32,
+}
+
+/// A contract with a signature over its content address.
+#[derive(Clone, PartialEq, Message)]
+pub struct SignedContract {
+    /// The contract.
+    #[prost(message, optional, tag = "1")]
+    pub contract: Option<Contract>,
+    /// The signature over the contract's content address.
+    #[prost(message, optional, tag = "2")]
+    pub signature: Option<Signature>,
+}
+
+fn hash(field: &'static str, bytes: Vec<u8>) -> Result<Hash, ProtoError> {
+    let len = bytes.len();
+    bytes
+        .try_into()
+        .map_err(|_| ProtoError::InvalidLength { field, len })
+}
+
+fn words<T: TryFrom<Vec<Word>, Error = SizeError>>(
+    field: &'static str,
+    words: Vec<Word>,
+) -> Result<T, ProtoError> {
+    T::try_from(words).map_err(|err| ProtoError::InvalidLength {
+        field,
+        len: err.size,
+    })
+}
+
+fn edge(field: &'static str, value: u32) -> Result<predicate::Edge, ProtoError> {
+    value
+        .try_into()
+        .map_err(|_| ProtoError::OutOfRange { field, value })
+}
+
+fn required<T>(field: &'static str, value: Option<T>) -> Result<T, ProtoError> {
+    value.ok_or(ProtoError::MissingField(field))
+}
+
+fn try_collect<T, U: TryFrom<T, Error = ProtoError>>(items: Vec<T>) -> Result<Vec<U>, ProtoError> {
+    items.into_iter().map(U::try_from).collect()
+}
+
+impl From<crate::PredicateAddress> for PredicateAddress {
+    fn from(addr: crate::PredicateAddress) -> Self {
+        Self {
+            contract: addr.contract.0.to_vec(),
+            predicate: addr.predicate.0.to_vec(),
+        }
+    }
+}
+
+impl TryFrom<PredicateAddress> for crate::PredicateAddress {
+    type Error = ProtoError;
+    fn try_from(addr: PredicateAddress) -> Result<Self, Self::Error> {
+        Ok(Self {
+            contract: ContentAddress(hash("contract", addr.contract)?),
+            predicate: ContentAddress(hash("predicate", addr.predicate)?),
+        })
+    }
+}
+
+impl From<solution::Mutation> for Mutation {
+    fn from(mutation: solution::Mutation) -> Self {
+        Self {
+            key: mutation.key.into(),
+            value: mutation.value.into(),
+        }
+    }
+}
+
+impl TryFrom<Mutation> for solution::Mutation {
+    type Error = ProtoError;
+    fn try_from(mutation: Mutation) -> Result<Self, Self::Error> {
+        Ok(Self {
+            key: words("key", mutation.key)?,
+            value: words("value", mutation.value)?,
+        })
+    }
+}
+
+impl From<solution::Solution> for Solution {
+    fn from(solution: solution::Solution) -> Self {
+        Self {
+            predicate_to_solve: Some(solution.predicate_to_solve.into()),
+            predicate_data: solution
+                .predicate_data
+                .into_iter()
+                .map(|value| Words {
+                    words: value.into(),
+                })
+                .collect(),
+            state_mutations: solution
+                .state_mutations
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<Solution> for solution::Solution {
+    type Error = ProtoError;
+    fn try_from(solution: Solution) -> Result<Self, Self::Error> {
+        Ok(Self {
+            predicate_to_solve: required("predicate_to_solve", solution.predicate_to_solve)?
+                .try_into()?,
+            predicate_data: solution
+                .predicate_data
+                .into_iter()
+                .map(|data| words("predicate_data", data.words))
+                .collect::<Result<_, _>>()?,
+            state_mutations: try_collect(solution.state_mutations)?,
+        })
+    }
+}
+
+impl From<solution::SolutionSet> for SolutionSet {
+    fn from(set: solution::SolutionSet) -> Self {
+        Self {
+            solutions: set.solutions.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<SolutionSet> for solution::SolutionSet {
+    type Error = ProtoError;
+    fn try_from(set: SolutionSet) -> Result<Self, Self::Error> {
+        Ok(Self {
+            solutions: try_collect(set.solutions)?,
+        })
+    }
+}
+
+impl From<predicate::Node> for Node {
+    fn from(node: predicate::Node) -> Self {
+        Self {
+            edge_start: node.edge_start.into(),
+            program_address: node.program_address.0.to_vec(),
+            max_gas: node.max_gas,
+        }
+    }
+}
+
+impl TryFrom<Node> for predicate::Node {
+    type Error = ProtoError;
+    fn try_from(node: Node) -> Result<Self, Self::Error> {
+        Ok(Self {
+            edge_start: edge("edge_start", node.edge_start)?,
+            program_address: ContentAddress(hash("program_address", node.program_address)?),
+            max_gas: node.max_gas,
+        })
+    }
+}
+
+impl From<predicate::Predicate> for Predicate {
+    fn from(predicate: predicate::Predicate) -> Self {
+        Self {
+            nodes: predicate.nodes.into_iter().map(Into::into).collect(),
+            edges: predicate.edges.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<Predicate> for predicate::Predicate {
+    type Error = ProtoError;
+    fn try_from(predicate: Predicate) -> Result<Self, Self::Error> {
+        Ok(Self {
+            nodes: try_collect(predicate.nodes)?,
+            edges: predicate
+                .edges
+                .into_iter()
+                .map(|e| edge("edges", e))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl From<predicate::Program> for Program {
+    fn from(program: predicate::Program) -> Self {
+        Self {
+            bytecode: program.0,
+        }
+    }
+}
+
+impl From<Program> for predicate::Program {
+    fn from(program: Program) -> Self {
+        Self(program.bytecode)
+    }
+}
+
+impl From<contract::ContractMetadata> for ContractMetadata {
+    fn from(metadata: contract::ContractMetadata) -> Self {
+        Self {
+            name: metadata.name,
+            version: metadata.version,
+            abi_hash: metadata.abi_hash.to_vec(),
+            source_commit: metadata.source_commit,
+        }
+    }
+}
+
+impl TryFrom<ContractMetadata> for contract::ContractMetadata {
+    type Error = ProtoError;
+    fn try_from(metadata: ContractMetadata) -> Result<Self, Self::Error> {
+        Ok(Self {
+            name: metadata.name,
+            version: metadata.version,
+            abi_hash: hash("abi_hash", metadata.abi_hash)?,
+            source_commit: metadata.source_commit,
+        })
+    }
+}
+
+impl From<contract::Contract> for Contract {
+    fn from(contract: contract::Contract) -> Self {
+        Self {
+            predicates: contract.predicates.into_iter().map(Into::into).collect(),
+            salt: contract.salt.to_vec(),
+        }
+    }
+}
+
+impl TryFrom<Contract> for contract::Contract {
+    type Error = ProtoError;
+    fn try_from(contract: Contract) -> Result<Self, Self::Error> {
+        Ok(Self {
+            predicates: try_collect(contract.predicates)?,
+            salt: hash("salt", contract.salt)?,
+        })
+    }
+}
+
+impl From<contract::ContractWithMetadata> for ContractWithMetadata {
+    fn from(contract: contract::ContractWithMetadata) -> Self {
+        Self {
+            contract: Some(contract.contract.into()),
+            metadata: Some(contract.metadata.into()),
+        }
+    }
+}
+
+impl TryFrom<ContractWithMetadata> for contract::ContractWithMetadata {
+    type Error = ProtoError;
+    fn try_from(contract: ContractWithMetadata) -> Result<Self, Self::Error> {
+        Ok(Self {
+            contract: required("contract", contract.contract)?.try_into()?,
+            metadata: required("metadata", contract.metadata)?.try_into()?,
+        })
+    }
+}
+
+impl From<crate::Signature> for Signature {
+    fn from(signature: crate::Signature) -> Self {
+        Self {
+            compact: signature.0.to_vec(),
+            id: signature.1.into(),
+        }
+    }
+}
+
+impl TryFrom<Signature> for crate::Signature {
+    type Error = ProtoError;
+    fn try_from(signature: Signature) -> Result<Self, Self::Error> {
+        let len = signature.compact.len();
+        let compact = signature
+            .compact
+            .try_into()
+            .map_err(|_| ProtoError::InvalidLength {
+                field: "compact",
+                len,
+            })?;
+        let id = signature
+            .id
+            .try_into()
+            .map_err(|_| ProtoError::OutOfRange {
+                field: "id",
+                value: signature.id,
+            })?;
+        Ok(Self(compact, id))
+    }
+}
+
+impl From<contract::SignedContract> for SignedContract {
+    fn from(signed: contract::SignedContract) -> Self {
+        Self {
+            contract: Some(signed.contract.into()),
+            signature: Some(signed.signature.into()),
+        }
+    }
+}
+
+impl TryFrom<SignedContract> for contract::SignedContract {
+    type Error = ProtoError;
+    fn try_from(signed: SignedContract) -> Result<Self, Self::Error> {
+        Ok(Self {
+            contract: required("contract", signed.contract)?.try_into()?,
+            signature: required("signature", signed.signature)?.try_into()?,
+        })
+    }
+}
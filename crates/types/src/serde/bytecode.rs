@@ -1,5 +1,6 @@
 //! Custom bytecode serialization implementation for better human-friendly format support.
 
+use alloc::vec::Vec;
 use serde::{ser::SerializeSeq, Deserialize, Deserializer, Serialize, Serializer};
 
 /// A type providing custom serialization implementations for a slice of bytecode.
@@ -1,5 +1,6 @@
 //! Custom hash serialization to better support human-readable formats.
 
+use alloc::{format, string::String, vec::Vec};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// Serialize a fixed-size hash value (`ContentAddress`, `Signature`).
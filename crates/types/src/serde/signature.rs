@@ -84,7 +84,7 @@ impl schemars::JsonSchema for crate::Signature {
         true
     }
 
-    fn schema_id() -> std::borrow::Cow<'static, str> {
-        std::borrow::Cow::Owned(Self::schema_name())
+    fn schema_id() -> alloc::borrow::Cow<'static, str> {
+        alloc::borrow::Cow::Owned(Self::schema_name())
     }
 }
@@ -1,12 +1,16 @@
 //! # Solutions
 //! Data types that are used to create solutions to predicates.
 
+use alloc::vec::Vec;
 use serde::{Deserialize, Serialize};
 
 use crate::{Key, PredicateAddress, Value, Word};
 
+pub mod builder;
 pub mod decode;
+pub mod diff;
 pub mod encode;
+pub mod wire;
 
 #[cfg(feature = "schema")]
 use schemars::JsonSchema;
@@ -60,6 +64,23 @@ pub struct Mutation {
     pub value: Value,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+/// A typed output of a leaf program, decoded from its memory with
+/// [`decode::decode_outputs`].
+pub enum Output {
+    /// State mutations proposed by the program.
+    Mutations(Vec<Mutation>),
+    /// Log records, each a sequence of words.
+    Logs(Vec<Vec<Word>>),
+    /// The words of a kind of output without a dedicated variant, left to
+    /// the consumer to interpret. The kind is none of [`Output::MUTATIONS`],
+    /// [`Output::LOGS`] or [`Output::EPHEMERAL`].
+    Raw(Word, Vec<Word>),
+    /// Ephemeral data that is not persisted.
+    Ephemeral(Vec<Word>),
+}
+
 impl SolutionSet {
     /// Get the sum of all state mutations within the set of solutions.
     pub fn state_mutations_len(&self) -> usize {
@@ -67,6 +88,25 @@ impl SolutionSet {
     }
 }
 
+impl Output {
+    /// The kind of output holding encoded state mutations.
+    pub const MUTATIONS: Word = 0;
+    /// The kind of output holding log records.
+    pub const LOGS: Word = 1;
+    /// The kind of output holding ephemeral data that is not persisted.
+    pub const EPHEMERAL: Word = 2;
+
+    /// The kind of the output.
+    pub fn kind(&self) -> Word {
+        match self {
+            Self::Mutations(_) => Self::MUTATIONS,
+            Self::Logs(_) => Self::LOGS,
+            Self::Raw(kind, _) => *kind,
+            Self::Ephemeral(_) => Self::EPHEMERAL,
+        }
+    }
+}
+
 impl Mutation {
     /// Get the size in words of this mutation encoded.
     pub fn encode_size(&self) -> usize {
@@ -0,0 +1,123 @@
+//! A fluent builder for [`SolutionSet`]s.
+
+use super::{Mutation, Solution, SolutionSet};
+use crate::{Key, PredicateAddress, Value};
+use alloc::vec::Vec;
+
+/// Builds a [`SolutionSet`] one solution at a time, see [`SolutionSet::build`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SolutionSetBuilder {
+    solutions: Vec<Solution>,
+}
+
+/// Builds the latest [`Solution`] of a [`SolutionSetBuilder`].
+///
+/// Predicate data and mutations are added to this solution until the next
+/// solution is started with [`solution`][Self::solution].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SolutionBuilder {
+    set: SolutionSetBuilder,
+    solution: Solution,
+}
+
+impl SolutionSet {
+    /// Start building a solution set.
+    ///
+    /// ```
+    /// # use essential_types::{solution::SolutionSet, ContentAddress, PredicateAddress};
+    /// # let addr = PredicateAddress {
+    /// #     contract: ContentAddress([0; 32]),
+    /// #     predicate: ContentAddress([1; 32]),
+    /// # };
+    /// let set = SolutionSet::build()
+    ///     .solution(addr)
+    ///     .predicate_data([1, 2])
+    ///     .mutation([0], [42])
+    ///     .finish();
+    /// assert_eq!(set.solutions[0].state_mutations[0].value, vec![42]);
+    /// ```
+    pub fn build() -> SolutionSetBuilder {
+        SolutionSetBuilder::default()
+    }
+}
+
+impl SolutionSetBuilder {
+    /// Start a new solution for the predicate at the given address.
+    pub fn solution(self, predicate_to_solve: PredicateAddress) -> SolutionBuilder {
+        SolutionBuilder {
+            set: self,
+            solution: Solution {
+                predicate_to_solve,
+                predicate_data: Vec::new(),
+                state_mutations: Vec::new(),
+            },
+        }
+    }
+
+    /// Finish building the solution set.
+    pub fn finish(self) -> SolutionSet {
+        SolutionSet {
+            solutions: self.solutions,
+        }
+    }
+
+    /// Finish building the solution set, validating it with the given function.
+    ///
+    /// E.g. pass `essential_check::solution::check_set` to validate the set
+    /// against the limits enforced by the checker.
+    pub fn try_finish<E>(
+        self,
+        validate: impl FnOnce(&SolutionSet) -> Result<(), E>,
+    ) -> Result<SolutionSet, E> {
+        let set = self.finish();
+        validate(&set)?;
+        Ok(set)
+    }
+}
+
+impl SolutionBuilder {
+    /// Append a value to the solution's predicate data.
+    pub fn predicate_data(mut self, value: impl Into<Value>) -> Self {
+        self.solution.predicate_data.push(value.into());
+        self
+    }
+
+    /// Append a mutation of the given key to the solution's state mutations.
+    ///
+    /// An empty value deletes the key.
+    pub fn mutation(mut self, key: impl Into<Key>, value: impl Into<Value>) -> Self {
+        self.solution.state_mutations.push(Mutation {
+            key: key.into(),
+            value: value.into(),
+        });
+        self
+    }
+
+    /// Finish this solution and start a new solution for the predicate at the
+    /// given address.
+    pub fn solution(self, predicate_to_solve: PredicateAddress) -> SolutionBuilder {
+        self.end().solution(predicate_to_solve)
+    }
+
+    /// Finish building the solution set.
+    pub fn finish(self) -> SolutionSet {
+        self.end().finish()
+    }
+
+    /// Finish building the solution set, validating it with the given function.
+    ///
+    /// See [`SolutionSetBuilder::try_finish`].
+    pub fn try_finish<E>(
+        self,
+        validate: impl FnOnce(&SolutionSet) -> Result<(), E>,
+    ) -> Result<SolutionSet, E> {
+        self.end().try_finish(validate)
+    }
+
+    /// Add the solution to the set.
+    fn end(self) -> SolutionSetBuilder {
+        let Self { mut set, solution } = self;
+        set.solutions.push(solution);
+        set
+    }
+}
@@ -1,15 +1,17 @@
 //! # Decoding
 //! Decoding for solution types.
 
-use crate::Word;
+use crate::{Key, Value, Word};
+use alloc::vec::Vec;
 
-use super::Mutation;
+use super::{Mutation, Output};
 
 #[cfg(test)]
 mod tests;
 
 /// Errors that can occur when decoding a predicate.
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum MutationDecodeError {
     /// The words are too short for the lengths of the key or value.
     WordsTooShort,
@@ -17,9 +19,33 @@ pub enum MutationDecodeError {
     NegativeKeyLength,
     /// The value length is negative.
     NegativeValueLength,
+    /// The key length exceeds [`Key::MAX_SIZE`].
+    KeyTooLarge,
+    /// The value length exceeds [`Value::MAX_SIZE`].
+    ValueTooLarge,
+    /// The number of mutations is negative.
+    NegativeMutationCount,
+    /// The words continue beyond the given number of mutations.
+    TrailingWords(usize),
 }
 
-impl std::error::Error for MutationDecodeError {}
+/// Errors that can occur when decoding outputs.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum OutputDecodeError {
+    /// The words are too short for the counts or lengths they give.
+    WordsTooShort,
+    /// A count or length is negative.
+    NegativeLength,
+    /// The words continue beyond the given number of outputs.
+    TrailingWords(usize),
+    /// The mutations of the output at the given index failed to decode.
+    Mutations(usize, MutationDecodeError),
+}
+
+impl core::error::Error for MutationDecodeError {}
+
+impl core::error::Error for OutputDecodeError {}
 
 /// Decode a mutation from words.
 ///
@@ -41,10 +67,10 @@ pub fn decode_mutation(bytes: &[Word]) -> Result<Mutation, MutationDecodeError>
     // Saturating cast
     let key_len: usize = bytes[0].try_into().unwrap_or(usize::MAX);
     let key_end = 1usize.saturating_add(key_len);
-    if bytes.len() < key_end {
+    if bytes.len() <= key_end {
         return Err(MutationDecodeError::WordsTooShort);
     }
-    let key = bytes[1..key_end].to_vec();
+    let key = Key::try_from(&bytes[1..key_end]).map_err(|_| MutationDecodeError::KeyTooLarge)?;
 
     if bytes[key_end] < 0 {
         return Err(MutationDecodeError::NegativeValueLength);
@@ -59,12 +85,16 @@ pub fn decode_mutation(bytes: &[Word]) -> Result<Mutation, MutationDecodeError>
     if bytes.len() < value_end {
         return Err(MutationDecodeError::WordsTooShort);
     }
-    let value = bytes[value_start..value_end].to_vec();
+    let value = Value::try_from(&bytes[value_start..value_end])
+        .map_err(|_| MutationDecodeError::ValueTooLarge)?;
     Ok(Mutation { key, value })
 }
 
 /// Decode a slice of mutations from words.
 ///
+/// Exactly `num mutations` mutations are decoded. Words following the last
+/// of these are rejected with [`MutationDecodeError::TrailingWords`].
+///
 /// # Layout
 /// ```text
 /// +-----------------+-----------------+-----------------+-----------------+
@@ -76,26 +106,111 @@ pub fn decode_mutations(bytes: &[Word]) -> Result<Vec<Mutation>, MutationDecodeE
         return Err(MutationDecodeError::WordsTooShort);
     }
     if bytes[0] < 0 {
-        return Err(MutationDecodeError::NegativeValueLength);
+        return Err(MutationDecodeError::NegativeMutationCount);
     }
 
     // Saturating cast
     let len: usize = bytes[0].try_into().unwrap_or(usize::MAX);
 
-    // FIXME: Do a max size check to avoid a DoS attack that allocates too much memory.
-    let mut mutations = Vec::with_capacity(len);
-    if len == 0 {
-        return Ok(mutations);
-    }
-    let mut i = 1;
-    while i < bytes.len() {
-        let Some(b) = bytes.get(i..) else {
-            return Err(MutationDecodeError::WordsTooShort);
-        };
-        let mutation = decode_mutation(b)?;
-        let size = mutation.encode_size();
-        i += size;
+    // Each mutation is at least 2 words, so this bounds the allocation by the
+    // size of the input rather than the given length.
+    let mut mutations = Vec::with_capacity(len.min(bytes.len() / 2));
+    let mut rest = &bytes[1..];
+    for _ in 0..len {
+        let mutation = decode_mutation(rest)?;
+        rest = &rest[mutation.encode_size()..];
         mutations.push(mutation);
     }
+    if !rest.is_empty() {
+        return Err(MutationDecodeError::TrailingWords(rest.len()));
+    }
     Ok(mutations)
 }
+
+/// Take a length prefix from the front of the words.
+fn take_len(words: &mut &[Word]) -> Result<usize, OutputDecodeError> {
+    let (&len, rest) = words
+        .split_first()
+        .ok_or(OutputDecodeError::WordsTooShort)?;
+    *words = rest;
+    usize::try_from(len).map_err(|_| OutputDecodeError::NegativeLength)
+}
+
+/// Take the given number of words from the front of the words.
+fn take_words<'a>(words: &mut &'a [Word], len: usize) -> Result<&'a [Word], OutputDecodeError> {
+    if words.len() < len {
+        return Err(OutputDecodeError::WordsTooShort);
+    }
+    let (taken, rest) = words.split_at(len);
+    *words = rest;
+    Ok(taken)
+}
+
+/// Decode log records from words.
+///
+/// # Layout
+/// ```text
+/// +-----------------+-----------------+-----------------+-----------------+
+/// | num logs        | log length      | log             | ...             |
+/// +-----------------+-----------------+-----------------+-----------------+
+/// ```
+pub fn decode_logs(mut words: &[Word]) -> Result<Vec<Vec<Word>>, OutputDecodeError> {
+    let len = take_len(&mut words)?;
+    // Each log is at least 1 word, so bound the allocation by the input.
+    let mut logs = Vec::with_capacity(len.min(words.len()));
+    for _ in 0..len {
+        let log_len = take_len(&mut words)?;
+        logs.push(take_words(&mut words, log_len)?.to_vec());
+    }
+    if !words.is_empty() {
+        return Err(OutputDecodeError::TrailingWords(words.len()));
+    }
+    Ok(logs)
+}
+
+/// Decode a sequence of tagged outputs from the memory of a leaf program.
+///
+/// The words of each output are decoded according to its kind, with
+/// [`decode_mutations`] for [`Output::MUTATIONS`] and [`decode_logs`] for
+/// [`Output::LOGS`]. The words of [`Output::EPHEMERAL`] outputs are returned
+/// as is and outputs of other kinds are returned as [`Output::Raw`].
+///
+/// # Layout
+/// ```text
+/// +-----------------+-----------------+-----------------+-----------------+
+/// | num outputs     | output 1        | output 2        | ...             |
+/// +-----------------+-----------------+-----------------+-----------------+
+/// ```
+///
+/// ## Output
+/// ```text
+/// +-----------------+-----------------+-----------------+
+/// | kind            | length          | words           |
+/// +-----------------+-----------------+-----------------+
+/// ```
+pub fn decode_outputs(mut words: &[Word]) -> Result<Vec<Output>, OutputDecodeError> {
+    let len = take_len(&mut words)?;
+    // Each output is at least 2 words, so bound the allocation by the input.
+    let mut outputs = Vec::with_capacity(len.min(words.len() / 2));
+    for ix in 0..len {
+        let (&kind, rest) = words
+            .split_first()
+            .ok_or(OutputDecodeError::WordsTooShort)?;
+        words = rest;
+        let output_len = take_len(&mut words)?;
+        let output = take_words(&mut words, output_len)?;
+        let output = match kind {
+            Output::MUTATIONS => Output::Mutations(
+                decode_mutations(output).map_err(|e| OutputDecodeError::Mutations(ix, e))?,
+            ),
+            Output::LOGS => Output::Logs(decode_logs(output)?),
+            Output::EPHEMERAL => Output::Ephemeral(output.to_vec()),
+            kind => Output::Raw(kind, output.to_vec()),
+        };
+        outputs.push(output);
+    }
+    if !words.is_empty() {
+        return Err(OutputDecodeError::TrailingWords(words.len()));
+    }
+    Ok(outputs)
+}
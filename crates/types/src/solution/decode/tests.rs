@@ -32,3 +32,103 @@ fn test_decode_mutations() {
     let words = vec![-2, 3, 1, 2, 3, 2, 4, 5, 2, 6, 7, 3, 8, 9, 10];
     decode_mutations(&words).unwrap_err();
 }
+
+#[test]
+fn test_decode_mutations_invalid() {
+    // A key with no value length.
+    assert_eq!(
+        decode_mutations(&[1, 1, 5]),
+        Err(MutationDecodeError::WordsTooShort)
+    );
+    // Fewer mutations than the count.
+    assert_eq!(
+        decode_mutations(&[2, 1, 5, 0]),
+        Err(MutationDecodeError::WordsTooShort)
+    );
+    // More words than the count.
+    assert_eq!(
+        decode_mutations(&[1, 1, 5, 0, 1, 6, 0]),
+        Err(MutationDecodeError::TrailingWords(3))
+    );
+    assert_eq!(
+        decode_mutations(&[0, 1]),
+        Err(MutationDecodeError::TrailingWords(1))
+    );
+    // Complete mutations beyond the count are not decoded.
+    let err = decode_mutations(&[1, 0, 0, 0, 0]).unwrap_err();
+    assert_eq!(err, MutationDecodeError::TrailingWords(2));
+    assert_eq!(err.to_string(), "2 trailing words after the mutations");
+    assert_eq!(
+        decode_mutations(&[-1]),
+        Err(MutationDecodeError::NegativeMutationCount)
+    );
+    // A large count does not allocate up front.
+    assert_eq!(
+        decode_mutations(&[Word::MAX, 0, 0]),
+        Err(MutationDecodeError::WordsTooShort)
+    );
+}
+
+#[test]
+fn test_decode_outputs() {
+    let words = vec![
+        4, // num outputs
+        0, 8, 1, 2, 6, 7, 3, 8, 9, 10, // mutations
+        1, 6, 2, 2, 1, 2, 1, 3, // logs
+        2, 1, 5, // ephemeral
+        7, 2, 42, 43, // raw
+    ];
+    let outputs = decode_outputs(&words).unwrap();
+    assert_eq!(
+        outputs,
+        vec![
+            Output::Mutations(vec![Mutation {
+                key: [6, 7].into(),
+                value: [8, 9, 10].into(),
+            }]),
+            Output::Logs(vec![vec![1, 2], vec![3]]),
+            Output::Ephemeral(vec![5]),
+            Output::Raw(7, vec![42, 43]),
+        ]
+    );
+    assert_eq!(decode_outputs(&[0]).unwrap(), vec![]);
+}
+
+#[test]
+fn test_decode_outputs_invalid() {
+    assert_eq!(decode_outputs(&[]), Err(OutputDecodeError::WordsTooShort));
+    assert_eq!(
+        decode_outputs(&[-1]),
+        Err(OutputDecodeError::NegativeLength)
+    );
+    assert_eq!(
+        decode_outputs(&[1, 7, -1]),
+        Err(OutputDecodeError::NegativeLength)
+    );
+    // The output is shorter than its length.
+    assert_eq!(
+        decode_outputs(&[1, 7, 3, 1, 2]),
+        Err(OutputDecodeError::WordsTooShort)
+    );
+    // Fewer outputs than the count.
+    assert_eq!(
+        decode_outputs(&[2, 7, 0]),
+        Err(OutputDecodeError::WordsTooShort)
+    );
+    assert_eq!(
+        decode_outputs(&[1, 7, 0, 5]),
+        Err(OutputDecodeError::TrailingWords(1))
+    );
+    // The words of an output must match its kind.
+    assert_eq!(
+        decode_outputs(&[2, 7, 0, 0, 1, -1]),
+        Err(OutputDecodeError::Mutations(
+            1,
+            MutationDecodeError::NegativeMutationCount
+        ))
+    );
+    assert_eq!(
+        decode_outputs(&[1, 1, 2, 0, 0]),
+        Err(OutputDecodeError::TrailingWords(1))
+    );
+}
@@ -0,0 +1,207 @@
+//! # Diffing
+//! Structured comparisons of [`Solution`]s and [`SolutionSet`]s.
+//!
+//! Solutions within a set are compared by their index, predicate data by
+//! its index, and state mutations by their key. Only differences are
+//! reported, so an empty diff means the two sides are equal.
+//!
+//! ```
+//! use essential_types::solution::{
+//!     diff::{self, Change},
+//!     Mutation, Solution,
+//! };
+//! # use essential_types::{ContentAddress, PredicateAddress};
+//! # let predicate_to_solve = PredicateAddress {
+//! #     contract: ContentAddress([0; 32]),
+//! #     predicate: ContentAddress([1; 32]),
+//! # };
+//! let old = Solution {
+//!     predicate_to_solve,
+//!     predicate_data: vec![[1].into()],
+//!     state_mutations: vec![Mutation { key: [0].into(), value: [42].into() }],
+//! };
+//! let mut new = old.clone();
+//! new.predicate_data[0] = [2].into();
+//! new.state_mutations.clear();
+//!
+//! let diff = diff::solution(&old, &new);
+//! assert_eq!(
+//!     diff.predicate_data,
+//!     vec![(0, Change::Changed { old: [1].into(), new: [2].into() })],
+//! );
+//! assert_eq!(
+//!     diff.state_mutations,
+//!     vec![([0].into(), Change::Removed([42].into()))],
+//! );
+//! ```
+
+use super::{Solution, SolutionSet};
+use crate::{Key, PredicateAddress, Value};
+use alloc::{collections::BTreeMap, vec::Vec};
+use serde::{Deserialize, Serialize};
+
+/// A difference between an old and a new item.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Change<T> {
+    /// The item is only present in the new side.
+    Added(T),
+    /// The item is only present in the old side.
+    Removed(T),
+    /// The item is present in both sides with different contents.
+    Changed {
+        /// The old item.
+        old: T,
+        /// The new item.
+        new: T,
+    },
+}
+
+/// The differences between two [`Solution`]s.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SolutionDiff {
+    /// The old and new predicates, if the solved predicate changed.
+    pub predicate_to_solve: Option<(PredicateAddress, PredicateAddress)>,
+    /// Changes to the predicate data by index, in index order.
+    pub predicate_data: Vec<(usize, Change<Value>)>,
+    /// Changes to the mutated values by key, in key order.
+    ///
+    /// A key that is mutated by only one side is `Added` or `Removed`,
+    /// regardless of whether its value is empty.
+    pub state_mutations: Vec<(Key, Change<Value>)>,
+}
+
+/// The differences between two [`SolutionSet`]s.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SolutionSetDiff {
+    /// Changes to the solutions by index, in index order.
+    pub solutions: Vec<(usize, SolutionChange)>,
+}
+
+/// A difference between an old and a new solution of a set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SolutionChange {
+    /// The solution is only present in the new set.
+    Added(Solution),
+    /// The solution is only present in the old set.
+    Removed(Solution),
+    /// The solution is present in both sets with different contents.
+    Changed(SolutionDiff),
+}
+
+impl SolutionDiff {
+    /// Whether the two solutions are equal.
+    pub fn is_empty(&self) -> bool {
+        self.predicate_to_solve.is_none()
+            && self.predicate_data.is_empty()
+            && self.state_mutations.is_empty()
+    }
+}
+
+impl SolutionSetDiff {
+    /// Whether the two sets are equal.
+    pub fn is_empty(&self) -> bool {
+        self.solutions.is_empty()
+    }
+}
+
+/// Compare the items of two sequences pairwise by index.
+fn by_index<T: Clone + PartialEq, C>(
+    old: &[T],
+    new: &[T],
+    mut changed: impl FnMut(&T, &T) -> Option<C>,
+    added: impl Fn(T) -> C,
+    removed: impl Fn(T) -> C,
+) -> Vec<(usize, C)> {
+    let len = old.len().max(new.len());
+    (0..len)
+        .filter_map(|ix| {
+            let change = match (old.get(ix), new.get(ix)) {
+                (Some(old), Some(new)) => changed(old, new)?,
+                (None, Some(new)) => added(new.clone()),
+                (Some(old), None) => removed(old.clone()),
+                (None, None) => unreachable!("index is within the longer sequence"),
+            };
+            Some((ix, change))
+        })
+        .collect()
+}
+
+/// The value of each key mutated by the solution.
+fn mutations(solution: &Solution) -> BTreeMap<&Key, &Value> {
+    solution
+        .state_mutations
+        .iter()
+        .map(|m| (&m.key, &m.value))
+        .collect()
+}
+
+/// Compare two solutions.
+///
+/// If a solution mutates the same key more than once, its last mutation of
+/// the key is compared.
+pub fn solution(old: &Solution, new: &Solution) -> SolutionDiff {
+    let predicate_to_solve = (old.predicate_to_solve != new.predicate_to_solve).then(|| {
+        (
+            old.predicate_to_solve.clone(),
+            new.predicate_to_solve.clone(),
+        )
+    });
+
+    let predicate_data = by_index(
+        &old.predicate_data,
+        &new.predicate_data,
+        |old, new| {
+            (old != new).then(|| Change::Changed {
+                old: old.clone(),
+                new: new.clone(),
+            })
+        },
+        Change::Added,
+        Change::Removed,
+    );
+
+    let old_mutations = mutations(old);
+    let mut new_mutations = mutations(new);
+    let mut state_mutations = Vec::new();
+    for (key, old) in old_mutations {
+        let change = match new_mutations.remove(key) {
+            Some(new) if new == old => continue,
+            Some(new) => Change::Changed {
+                old: old.clone(),
+                new: new.clone(),
+            },
+            None => Change::Removed(old.clone()),
+        };
+        state_mutations.push((key.clone(), change));
+    }
+    state_mutations.extend(
+        new_mutations
+            .into_iter()
+            .map(|(key, new)| (key.clone(), Change::Added(new.clone()))),
+    );
+    state_mutations.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    SolutionDiff {
+        predicate_to_solve,
+        predicate_data,
+        state_mutations,
+    }
+}
+
+/// Compare two solution sets.
+///
+/// Solutions are compared by their index within the sets, so reordering the
+/// solutions of a set is reported as a change.
+pub fn solution_set(old: &SolutionSet, new: &SolutionSet) -> SolutionSetDiff {
+    let solutions = by_index(
+        &old.solutions,
+        &new.solutions,
+        |old, new| {
+            let diff = solution(old, new);
+            (!diff.is_empty()).then_some(SolutionChange::Changed(diff))
+        },
+        SolutionChange::Added,
+        SolutionChange::Removed,
+    );
+    SolutionSetDiff { solutions }
+}
@@ -1,8 +1,9 @@
 //! # Encoding
 //! Encoding for solution types.
 use crate::Word;
+use alloc::{vec, vec::Vec};
 
-use super::Mutation;
+use super::{Mutation, Output};
 
 #[cfg(test)]
 mod tests;
@@ -28,22 +29,86 @@ pub fn encode_mutation(mutation: &Mutation) -> impl Iterator<Item = Word> + use<
     let key_len: Word = mutation.key.len().try_into().unwrap_or(Word::MAX);
     // Saturating cast
     let value_len: Word = mutation.value.len().try_into().unwrap_or(Word::MAX);
-    std::iter::once(key_len)
+    core::iter::once(key_len)
         .chain(mutation.key.iter().copied())
-        .chain(std::iter::once(value_len))
+        .chain(core::iter::once(value_len))
         .chain(mutation.value.iter().copied())
 }
 
 /// Encodes a slice of mutations into a sequence of words.
 ///
+/// This is the inverse of [`decode_mutations`][super::decode::decode_mutations].
+///
 /// # Layout
 /// ```text
 /// +-----------------+-----------------+-----------------+-----------------+
 /// | num mutations   | mutation 1      | mutation 2      | ...             |
 /// +-----------------+-----------------+-----------------+-----------------+
 /// ```
-pub fn encode_mutations(mutations: &[Mutation]) -> impl Iterator<Item = Word> + use<'_> {
+pub fn encode_mutations(mutations: &[Mutation]) -> Vec<Word> {
     // Saturating cast
     let len: Word = mutations.len().try_into().unwrap_or(Word::MAX);
-    std::iter::once(len).chain(mutations.iter().flat_map(encode_mutation))
+    let size = 1 + mutations.iter().map(encode_mutation_size).sum::<usize>();
+    let mut words = Vec::with_capacity(size);
+    words.push(len);
+    words.extend(mutations.iter().flat_map(encode_mutation));
+    words
+}
+
+/// Encodes log records into a sequence of words.
+///
+/// This is the inverse of [`decode_logs`][super::decode::decode_logs].
+///
+/// # Layout
+/// ```text
+/// +-----------------+-----------------+-----------------+-----------------+
+/// | num logs        | log length      | log             | ...             |
+/// +-----------------+-----------------+-----------------+-----------------+
+/// ```
+pub fn encode_logs(logs: &[Vec<Word>]) -> Vec<Word> {
+    let size = 1 + logs.iter().map(|log| 1 + log.len()).sum::<usize>();
+    let mut words = Vec::with_capacity(size);
+    words.push(saturating_len(logs.len()));
+    for log in logs {
+        words.push(saturating_len(log.len()));
+        words.extend_from_slice(log);
+    }
+    words
+}
+
+/// Encodes a sequence of tagged outputs into words, e.g. for a leaf program
+/// to store in its memory.
+///
+/// This is the inverse of [`decode_outputs`][super::decode::decode_outputs].
+///
+/// # Layout
+/// ```text
+/// +-----------------+-----------------+-----------------+-----------------+
+/// | num outputs     | output 1        | output 2        | ...             |
+/// +-----------------+-----------------+-----------------+-----------------+
+/// ```
+///
+/// ## Output
+/// ```text
+/// +-----------------+-----------------+-----------------+
+/// | kind            | length          | words           |
+/// +-----------------+-----------------+-----------------+
+/// ```
+pub fn encode_outputs(outputs: &[Output]) -> Vec<Word> {
+    let mut words = vec![saturating_len(outputs.len())];
+    for output in outputs {
+        let encoded = match output {
+            Output::Mutations(mutations) => encode_mutations(mutations),
+            Output::Logs(logs) => encode_logs(logs),
+            Output::Raw(_, words) | Output::Ephemeral(words) => words.clone(),
+        };
+        words.extend([output.kind(), saturating_len(encoded.len())]);
+        words.extend(encoded);
+    }
+    words
+}
+
+/// Saturating cast of a length to a word.
+fn saturating_len(len: usize) -> Word {
+    len.try_into().unwrap_or(Word::MAX)
 }
@@ -1,12 +1,15 @@
-use crate::solution::decode::{decode_mutation, decode_mutations};
+use crate::{
+    solution::decode::{decode_mutation, decode_mutations, decode_outputs},
+    Key, Value,
+};
 
 use super::*;
 
 #[test]
 fn test_encode_mutation() {
     let m = Mutation {
-        key: vec![1, 2, 3],
-        value: vec![4, 5],
+        key: [1, 2, 3].into(),
+        value: [4, 5].into(),
     };
     let words = encode_mutation(&m).collect::<Vec<_>>();
     assert_eq!(words, vec![3, 1, 2, 3, 2, 4, 5]);
@@ -20,19 +23,80 @@ fn test_encode_mutation() {
 fn test_encode_mutations() {
     let m = vec![
         Mutation {
-            key: vec![1, 2, 3],
-            value: vec![4, 5],
+            key: [1, 2, 3].into(),
+            value: [4, 5].into(),
         },
         Mutation {
-            key: vec![6, 7],
-            value: vec![8, 9, 10],
+            key: [6, 7].into(),
+            value: [8, 9, 10].into(),
         },
     ];
 
-    let words = encode_mutations(&m).collect::<Vec<_>>();
+    let words = encode_mutations(&m);
     assert_eq!(words, vec![2, 3, 1, 2, 3, 2, 4, 5, 2, 6, 7, 3, 8, 9, 10]);
 
     // Round trip
     let m2 = decode_mutations(&words).unwrap();
     assert_eq!(m, m2);
 }
+
+#[test]
+fn test_encode_mutations_roundtrip() {
+    let cases = [
+        vec![],
+        vec![Mutation {
+            key: Key::default(),
+            value: Value::default(),
+        }],
+        vec![
+            Mutation {
+                key: [Word::MIN, Word::MAX].into(),
+                value: Value::default(),
+            },
+            Mutation {
+                key: Key::default(),
+                value: [-1; 16].into(),
+            },
+        ],
+        (0..10)
+            .map(|i| Mutation {
+                key: [i; 4].into(),
+                value: Value::new((0..i).collect()).unwrap(),
+            })
+            .collect(),
+    ];
+    for mutations in cases {
+        let words = encode_mutations(&mutations);
+        let size = 1 + mutations.iter().map(Mutation::encode_size).sum::<usize>();
+        assert_eq!(words.len(), size);
+        assert_eq!(decode_mutations(&words).unwrap(), mutations);
+    }
+}
+
+#[test]
+fn test_encode_outputs_roundtrip() {
+    let outputs = vec![
+        Output::Mutations(vec![Mutation {
+            key: [1, 2].into(),
+            value: [3].into(),
+        }]),
+        Output::Logs(vec![vec![], vec![4, 5, 6]]),
+        Output::Mutations(vec![]),
+        Output::Ephemeral(vec![7, 8]),
+        Output::Raw(-1, vec![]),
+    ];
+    let words = encode_outputs(&outputs);
+    assert_eq!(
+        words,
+        vec![
+            5, // num outputs
+            0, 6, 1, 2, 1, 2, 1, 3, // mutations
+            1, 6, 2, 0, 3, 4, 5, 6, // logs
+            0, 1, 0, // no mutations
+            2, 2, 7, 8, // ephemeral
+            -1, 0, // empty raw
+        ]
+    );
+    assert_eq!(decode_outputs(&words).unwrap(), outputs);
+    assert_eq!(decode_outputs(&encode_outputs(&[])).unwrap(), vec![]);
+}
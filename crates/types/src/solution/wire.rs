@@ -0,0 +1,415 @@
+//! # Wire Format
+//! A compact binary encoding of [`SolutionSet`]s for gossip between nodes.
+//!
+//! This is distinct from the encoding used for hashing. Sizes are varints,
+//! words are zigzag varints, and each distinct predicate address is written
+//! once and referred to by index. The whole set is prefixed with its length
+//! so that a frame may be skipped or forwarded without decoding it.
+//!
+//! # Layout
+//! ```text
+//! frame:    | frame length | addresses | solutions |
+//! addresses:| count | contract, predicate (64 bytes) ... |
+//! solutions:| count | solution ... |
+//! solution: | address index | data count | value ... | mutation count | mutation ... |
+//! value:    | word count | word ... |
+//! mutation: | key (as value) | value |
+//! ```
+//!
+//! The address table is a contiguous run of fixed size entries, so it may be
+//! used in place by readers that only route solutions by predicate.
+//!
+//! With the `std` feature, sets may be streamed to a writer with
+//! [`write_set`] and solutions read one at a time with [`SolutionReader`]
+//! without buffering the whole frame.
+
+use super::{Mutation, Solution, SolutionSet};
+use crate::{state::SizeError, ContentAddress, Key, PredicateAddress, Value, Word};
+use alloc::{collections::BTreeMap, vec::Vec};
+
+#[cfg(test)]
+mod tests;
+
+/// Size of an encoded predicate address.
+const ADDRESS_SIZE: usize = 64;
+
+/// Maximum number of bytes of a `u64` varint.
+const MAX_VARINT_SIZE: usize = 10;
+
+/// Errors that can occur when decoding the wire format.
+#[derive(Debug, PartialEq)]
+pub enum WireDecodeError {
+    /// The bytes ended before the frame was decoded.
+    UnexpectedEnd,
+    /// A varint is longer than 10 bytes or overflows a `u64`.
+    InvalidVarint,
+    /// A solution refers to an address beyond the address table.
+    InvalidAddressIndex(u64),
+    /// The frame contains bytes beyond the end of the set.
+    TrailingBytes(u64),
+    /// A key or value exceeds its maximum size.
+    Size(SizeError),
+}
+
+impl core::error::Error for WireDecodeError {}
+
+/// Destination of encoded bytes.
+trait Sink {
+    fn put(&mut self, bytes: &[u8]);
+}
+
+impl Sink for Vec<u8> {
+    fn put(&mut self, bytes: &[u8]) {
+        self.extend_from_slice(bytes);
+    }
+}
+
+/// Counts the bytes that would be written.
+struct Count(usize);
+
+impl Sink for Count {
+    fn put(&mut self, bytes: &[u8]) {
+        self.0 += bytes.len();
+    }
+}
+
+/// Source of encoded bytes.
+trait Source {
+    type Error: From<WireDecodeError>;
+    fn take(&mut self, buf: &mut [u8]) -> Result<(), Self::Error>;
+}
+
+impl Source for &[u8] {
+    type Error = WireDecodeError;
+    fn take(&mut self, buf: &mut [u8]) -> Result<(), Self::Error> {
+        if self.len() < buf.len() {
+            return Err(WireDecodeError::UnexpectedEnd);
+        }
+        let (taken, rest) = self.split_at(buf.len());
+        buf.copy_from_slice(taken);
+        *self = rest;
+        Ok(())
+    }
+}
+
+fn put_varint(sink: &mut impl Sink, mut n: u64) {
+    let mut buf = [0u8; MAX_VARINT_SIZE];
+    let mut len = 0;
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            buf[len] = byte;
+            len += 1;
+            break;
+        }
+        buf[len] = byte | 0x80;
+        len += 1;
+    }
+    sink.put(&buf[..len]);
+}
+
+fn take_varint<S: Source>(source: &mut S) -> Result<u64, S::Error> {
+    let mut n: u64 = 0;
+    for ix in 0..MAX_VARINT_SIZE {
+        let mut byte = [0];
+        source.take(&mut byte)?;
+        let bits = u64::from(byte[0] & 0x7f);
+        let shift = 7 * ix as u32;
+        if shift == 63 && bits > 1 {
+            return Err(WireDecodeError::InvalidVarint.into());
+        }
+        n |= bits << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(n);
+        }
+    }
+    Err(WireDecodeError::InvalidVarint.into())
+}
+
+fn zigzag(word: Word) -> u64 {
+    ((word << 1) ^ (word >> 63)) as u64
+}
+
+fn unzigzag(n: u64) -> Word {
+    ((n >> 1) as Word) ^ -((n & 1) as Word)
+}
+
+fn put_value(sink: &mut impl Sink, value: &[Word]) {
+    put_varint(sink, value.len() as u64);
+    for &word in value {
+        put_varint(sink, zigzag(word));
+    }
+}
+
+/// Take a key or value of at most `max` words.
+fn take_words<S, T>(source: &mut S, max: usize) -> Result<T, S::Error>
+where
+    S: Source,
+    T: TryFrom<Vec<Word>, Error = SizeError>,
+{
+    let len = take_varint(source)?;
+    let size = usize::try_from(len).unwrap_or(usize::MAX);
+    if size > max {
+        return Err(WireDecodeError::Size(SizeError { size, max }).into());
+    }
+    // Each word is at least one byte, so grow with the words read rather
+    // than trusting the length up front.
+    let mut words = Vec::with_capacity(size.min(64));
+    for _ in 0..len {
+        words.push(unzigzag(take_varint(source)?));
+    }
+    Ok(T::try_from(words).map_err(WireDecodeError::Size)?)
+}
+
+/// The predicate addresses of the set in order of first use, and the index
+/// of each solution's address.
+fn address_table(set: &SolutionSet) -> (Vec<&PredicateAddress>, Vec<u64>) {
+    let mut table = Vec::new();
+    let mut indices = BTreeMap::new();
+    let solution_indices = set
+        .solutions
+        .iter()
+        .map(|solution| {
+            let addr = &solution.predicate_to_solve;
+            *indices.entry(addr).or_insert_with(|| {
+                table.push(addr);
+                table.len() as u64 - 1
+            })
+        })
+        .collect();
+    (table, solution_indices)
+}
+
+fn put_body(sink: &mut impl Sink, set: &SolutionSet) {
+    let (table, indices) = address_table(set);
+    put_varint(sink, table.len() as u64);
+    for addr in table {
+        sink.put(&addr.contract.0);
+        sink.put(&addr.predicate.0);
+    }
+    put_varint(sink, set.solutions.len() as u64);
+    for (solution, ix) in set.solutions.iter().zip(indices) {
+        put_varint(sink, ix);
+        put_varint(sink, solution.predicate_data.len() as u64);
+        for value in &solution.predicate_data {
+            put_value(sink, value);
+        }
+        put_varint(sink, solution.state_mutations.len() as u64);
+        for mutation in &solution.state_mutations {
+            put_value(sink, &mutation.key);
+            put_value(sink, &mutation.value);
+        }
+    }
+}
+
+fn put_frame(sink: &mut impl Sink, set: &SolutionSet) {
+    let mut body_len = Count(0);
+    put_body(&mut body_len, set);
+    put_varint(sink, body_len.0 as u64);
+    put_body(sink, set);
+}
+
+fn take_addresses<S: Source>(source: &mut S) -> Result<Vec<PredicateAddress>, S::Error> {
+    let len = take_varint(source)?;
+    let mut table = Vec::with_capacity(len.min(64) as usize);
+    for _ in 0..len {
+        let mut addr = [0u8; ADDRESS_SIZE];
+        source.take(&mut addr)?;
+        let (contract, predicate) = addr.split_at(ADDRESS_SIZE / 2);
+        table.push(PredicateAddress {
+            contract: ContentAddress(contract.try_into().expect("32 bytes")),
+            predicate: ContentAddress(predicate.try_into().expect("32 bytes")),
+        });
+    }
+    Ok(table)
+}
+
+fn take_solution<S: Source>(
+    source: &mut S,
+    table: &[PredicateAddress],
+) -> Result<Solution, S::Error> {
+    let ix = take_varint(source)?;
+    let predicate_to_solve = usize::try_from(ix)
+        .ok()
+        .and_then(|ix| table.get(ix))
+        .ok_or(WireDecodeError::InvalidAddressIndex(ix))?
+        .clone();
+    let data_len = take_varint(source)?;
+    let predicate_data = (0..data_len)
+        .map(|_| take_words::<_, Value>(source, Value::MAX_SIZE))
+        .collect::<Result<_, _>>()?;
+    let mutations_len = take_varint(source)?;
+    let state_mutations = (0..mutations_len)
+        .map(|_| {
+            Ok(Mutation {
+                key: take_words(source, Key::MAX_SIZE)?,
+                value: take_words(source, Value::MAX_SIZE)?,
+            })
+        })
+        .collect::<Result<_, S::Error>>()?;
+    Ok(Solution {
+        predicate_to_solve,
+        predicate_data,
+        state_mutations,
+    })
+}
+
+/// The length in bytes of the set's encoded frame, including its length prefix.
+pub fn encoded_len(set: &SolutionSet) -> usize {
+    let mut count = Count(0);
+    put_frame(&mut count, set);
+    count.0
+}
+
+/// Encode the set as a frame.
+pub fn encode(set: &SolutionSet) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(encoded_len(set));
+    put_frame(&mut bytes, set);
+    bytes
+}
+
+/// Decode a set from the frame at the start of the given bytes.
+///
+/// Returns the set along with the length of the frame, so that consecutive
+/// frames may be decoded from a single buffer.
+pub fn decode(bytes: &[u8]) -> Result<(SolutionSet, usize), WireDecodeError> {
+    let mut source = bytes;
+    let body_len = take_varint(&mut source)?;
+    let prefix_len = bytes.len() - source.len();
+    let body_len = usize::try_from(body_len).map_err(|_| WireDecodeError::UnexpectedEnd)?;
+    let mut body = source
+        .get(..body_len)
+        .ok_or(WireDecodeError::UnexpectedEnd)?;
+    let table = take_addresses(&mut body)?;
+    let len = take_varint(&mut body)?;
+    let solutions = (0..len)
+        .map(|_| take_solution(&mut body, &table))
+        .collect::<Result<_, _>>()?;
+    if !body.is_empty() {
+        return Err(WireDecodeError::TrailingBytes(body.len() as u64));
+    }
+    Ok((SolutionSet { solutions }, prefix_len + body_len))
+}
+
+#[cfg(feature = "std")]
+pub use stream::{write_set, SolutionReader};
+
+#[cfg(feature = "std")]
+mod stream {
+    use super::*;
+    use std::io::{self, Read};
+
+    impl From<WireDecodeError> for io::Error {
+        fn from(err: WireDecodeError) -> Self {
+            match err {
+                WireDecodeError::UnexpectedEnd => io::ErrorKind::UnexpectedEof.into(),
+                err => io::Error::new(io::ErrorKind::InvalidData, err),
+            }
+        }
+    }
+
+    /// Writes to an `io::Write`, recording the first error.
+    struct Writer<W> {
+        writer: W,
+        result: io::Result<()>,
+    }
+
+    impl<W: io::Write> Sink for Writer<W> {
+        fn put(&mut self, bytes: &[u8]) {
+            if self.result.is_ok() {
+                self.result = self.writer.write_all(bytes);
+            }
+        }
+    }
+
+    impl<R: io::Read> Source for io::Take<R> {
+        type Error = io::Error;
+        fn take(&mut self, buf: &mut [u8]) -> Result<(), Self::Error> {
+            self.read_exact(buf)
+        }
+    }
+
+    /// Write the set as a frame to the given writer.
+    ///
+    /// The set is written as it is encoded, without buffering the frame.
+    pub fn write_set(set: &SolutionSet, writer: impl io::Write) -> io::Result<()> {
+        let mut writer = Writer {
+            writer,
+            result: Ok(()),
+        };
+        put_frame(&mut writer, set);
+        writer.result
+    }
+
+    /// Reads the solutions of a single frame one at a time.
+    ///
+    /// The frame's length prefix and address table are read on construction.
+    /// Each solution is then read as it is requested, and reading fails if the
+    /// frame contains bytes beyond its last solution.
+    pub struct SolutionReader<R> {
+        reader: io::Take<R>,
+        table: Vec<PredicateAddress>,
+        remaining: u64,
+    }
+
+    impl<R: io::Read> SolutionReader<R> {
+        /// Begin reading a frame from the given reader.
+        pub fn new(mut reader: R) -> io::Result<Self> {
+            let body_len = take_varint(&mut reader.by_ref().take(MAX_VARINT_SIZE as u64))?;
+            let mut reader = reader.take(body_len);
+            let table = take_addresses(&mut reader)?;
+            let remaining = take_varint(&mut reader)?;
+            Ok(Self {
+                reader,
+                table,
+                remaining,
+            })
+        }
+
+        /// The distinct predicate addresses solved by the frame's solutions.
+        pub fn addresses(&self) -> &[PredicateAddress] {
+            &self.table
+        }
+
+        /// The number of solutions not yet read.
+        pub fn remaining(&self) -> u64 {
+            self.remaining
+        }
+
+        /// Read the remaining solutions into a set.
+        pub fn read_set(self) -> io::Result<SolutionSet> {
+            let solutions = self.collect::<io::Result<_>>()?;
+            Ok(SolutionSet { solutions })
+        }
+
+        /// The underlying reader, positioned after the frame once all
+        /// solutions have been read.
+        pub fn into_inner(self) -> R {
+            self.reader.into_inner()
+        }
+    }
+
+    impl<R: io::Read> Iterator for SolutionReader<R> {
+        type Item = io::Result<Solution>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            if self.remaining == 0 {
+                return match self.reader.limit() {
+                    0 => None,
+                    n => {
+                        self.reader.set_limit(0);
+                        Some(Err(WireDecodeError::TrailingBytes(n).into()))
+                    }
+                };
+            }
+            self.remaining -= 1;
+            let solution = take_solution(&mut self.reader, &self.table);
+            if solution.is_err() {
+                self.remaining = 0;
+                self.reader.set_limit(0);
+            }
+            Some(solution)
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use super::*;
+use std::io::Cursor;
+
+fn addr(contract: u8, predicate: u8) -> PredicateAddress {
+    PredicateAddress {
+        contract: ContentAddress([contract; 32]),
+        predicate: ContentAddress([predicate; 32]),
+    }
+}
+
+fn solution(addr: PredicateAddress, data: impl Into<Value>) -> Solution {
+    Solution {
+        predicate_to_solve: addr,
+        predicate_data: vec![data.into(), [].into()],
+        state_mutations: vec![Mutation {
+            key: [0, -1].into(),
+            value: [Word::MIN, Word::MAX, 300].into(),
+        }],
+    }
+}
+
+fn test_set() -> SolutionSet {
+    SolutionSet {
+        solutions: vec![
+            solution(addr(1, 2), [1, 2, 3]),
+            solution(addr(3, 4), [-4]),
+            solution(addr(1, 2), []),
+        ],
+    }
+}
+
+#[test]
+fn test_varint() {
+    for n in [0, 1, 127, 128, 300, u64::MAX >> 1, u64::MAX] {
+        let mut bytes = vec![];
+        put_varint(&mut bytes, n);
+        assert_eq!(take_varint(&mut &bytes[..]), Ok(n));
+    }
+    let mut bytes = vec![];
+    put_varint(&mut bytes, 300);
+    assert_eq!(bytes, [0xac, 0x02]);
+
+    let overflow = [0xff; 9].into_iter().chain([0x02]).collect::<Vec<_>>();
+    assert_eq!(
+        take_varint(&mut &overflow[..]),
+        Err(WireDecodeError::InvalidVarint)
+    );
+    assert_eq!(
+        take_varint(&mut &[0x80; 11][..]),
+        Err(WireDecodeError::InvalidVarint)
+    );
+}
+
+#[test]
+fn test_zigzag() {
+    for word in [0, 1, -1, 63, -64, Word::MIN, Word::MAX] {
+        assert_eq!(unzigzag(zigzag(word)), word);
+    }
+    assert_eq!(zigzag(-1), 1);
+    assert_eq!(zigzag(1), 2);
+}
+
+#[test]
+fn test_roundtrip() {
+    let set = test_set();
+    let bytes = encode(&set);
+    assert_eq!(bytes.len(), encoded_len(&set));
+    assert_eq!(decode(&bytes), Ok((set, bytes.len())));
+
+    let empty = SolutionSet { solutions: vec![] };
+    assert_eq!(decode(&encode(&empty)), Ok((empty, 3)));
+}
+
+#[test]
+fn test_shared_addresses() {
+    let set = test_set();
+    let bytes = encode(&set);
+    // The shared address is written once.
+    let (table, indices) = address_table(&set);
+    assert_eq!(table, [&addr(1, 2), &addr(3, 4)]);
+    assert_eq!(indices, [0, 1, 0]);
+    let contract = [1; 32];
+    let count = bytes.windows(32).filter(|w| *w == contract).count();
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn test_consecutive_frames() {
+    let a = test_set();
+    let b = SolutionSet {
+        solutions: vec![solution(addr(5, 6), [7])],
+    };
+    let mut bytes = encode(&a);
+    bytes.extend(encode(&b));
+    let (decoded, len) = decode(&bytes).unwrap();
+    assert_eq!(decoded, a);
+    assert_eq!(decode(&bytes[len..]).unwrap().0, b);
+}
+
+#[test]
+fn test_decode_errors() {
+    let bytes = encode(&test_set());
+    for len in 0..bytes.len() {
+        assert_eq!(decode(&bytes[..len]), Err(WireDecodeError::UnexpectedEnd));
+    }
+
+    // Refer to an address beyond the table.
+    let set = SolutionSet {
+        solutions: vec![solution(addr(1, 2), [])],
+    };
+    let mut bytes = encode(&set);
+    let ix = 1 + 1 + ADDRESS_SIZE + 1;
+    assert_eq!(bytes[ix], 0);
+    bytes[ix] = 1;
+    assert_eq!(decode(&bytes), Err(WireDecodeError::InvalidAddressIndex(1)));
+
+    // Claim one more byte than the body holds.
+    let mut bytes = encode(&set);
+    bytes[0] += 1;
+    bytes.push(0);
+    assert_eq!(decode(&bytes), Err(WireDecodeError::TrailingBytes(1)));
+}
+
+#[test]
+fn test_stream_roundtrip() {
+    let set = test_set();
+    let mut bytes = vec![];
+    write_set(&set, &mut bytes).unwrap();
+    assert_eq!(bytes, encode(&set));
+
+    let mut reader = SolutionReader::new(Cursor::new(&bytes)).unwrap();
+    assert_eq!(reader.addresses(), [addr(1, 2), addr(3, 4)]);
+    assert_eq!(reader.remaining(), 3);
+    assert_eq!(reader.next().unwrap().unwrap(), set.solutions[0]);
+    assert_eq!(reader.remaining(), 2);
+    assert_eq!(reader.read_set().unwrap().solutions, set.solutions[1..]);
+}
+
+#[test]
+fn test_stream_consecutive_frames() {
+    let a = test_set();
+    let b = SolutionSet {
+        solutions: vec![solution(addr(5, 6), [7])],
+    };
+    let mut bytes = vec![];
+    write_set(&a, &mut bytes).unwrap();
+    write_set(&b, &mut bytes).unwrap();
+
+    let mut reader = SolutionReader::new(Cursor::new(&bytes)).unwrap();
+    let solutions = reader.by_ref().collect::<std::io::Result<Vec<_>>>();
+    assert_eq!(solutions.unwrap(), a.solutions);
+    let reader = SolutionReader::new(reader.into_inner()).unwrap();
+    assert_eq!(reader.read_set().unwrap(), b);
+}
+
+#[test]
+fn test_stream_errors() {
+    let bytes = encode(&test_set());
+    let truncated = &bytes[..bytes.len() - 1];
+    let err = SolutionReader::new(truncated)
+        .unwrap()
+        .read_set()
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+
+    let set = SolutionSet {
+        solutions: vec![solution(addr(1, 2), [])],
+    };
+    let mut bytes = encode(&set);
+    bytes[0] += 1;
+    bytes.push(0);
+    let mut reader = SolutionReader::new(&bytes[..]).unwrap();
+    assert!(reader.next().unwrap().is_ok());
+    let err = reader.next().unwrap().unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(reader.next().is_none());
+}
@@ -0,0 +1,229 @@
+//! # State Keys and Values
+//! Size checked sequences of words used as state keys, state values and
+//! predicate data.
+//!
+//! A [`Key`] is at most [`Key::MAX_SIZE`] words and a [`Value`] at most
+//! [`Value::MAX_SIZE`] words. The limits are checked on construction and
+//! deserialization, so values of these types are always within them.
+//!
+//! Fixed size arrays convert infallibly, with the size checked at compile
+//! time, while vecs and slices are checked at runtime.
+//!
+//! ```
+//! use essential_types::{Key, Value};
+//!
+//! let key = Key::from([1, 2, 3]);
+//! assert_eq!(key.next_key(), Some(Key::from([1, 2, 4])));
+//!
+//! let value = Value::new(vec![0; Value::MAX_SIZE + 1]);
+//! assert!(value.is_err());
+//! ```
+
+use crate::Word;
+use alloc::vec::{self, Vec};
+use core::{borrow::Borrow, fmt, ops::Deref};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(feature = "schema")]
+use schemars::JsonSchema;
+
+/// Key for data.
+#[derive(Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schema", derive(JsonSchema), schemars(transparent))]
+pub struct Key(Vec<Word>);
+
+/// The data at a key.
+///
+/// An empty value represents the absence of data.
+#[derive(Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schema", derive(JsonSchema), schemars(transparent))]
+pub struct Value(Vec<Word>);
+
+/// A sequence of words exceeds the maximum size of a [`Key`] or [`Value`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct SizeError {
+    /// The number of words.
+    pub size: usize,
+    /// The maximum number of words.
+    pub max: usize,
+}
+
+impl core::error::Error for SizeError {}
+
+fn check_size(size: usize, max: usize) -> Result<(), SizeError> {
+    match size <= max {
+        true => Ok(()),
+        false => Err(SizeError { size, max }),
+    }
+}
+
+macro_rules! impl_words {
+    ($ty:ident) => {
+        impl $ty {
+            /// Create from the given words, checking they are within [`Self::MAX_SIZE`].
+            pub fn new(words: Vec<Word>) -> Result<Self, SizeError> {
+                check_size(words.len(), Self::MAX_SIZE)?;
+                Ok(Self(words))
+            }
+
+            /// The words as a slice.
+            pub fn as_slice(&self) -> &[Word] {
+                &self.0
+            }
+
+            /// Unwrap the words.
+            pub fn into_inner(self) -> Vec<Word> {
+                self.0
+            }
+
+            /// Append a word, checking the result is within [`Self::MAX_SIZE`].
+            pub fn push(&mut self, word: Word) -> Result<(), SizeError> {
+                check_size(self.0.len() + 1, Self::MAX_SIZE)?;
+                self.0.push(word);
+                Ok(())
+            }
+
+            /// Append the given words, checking the result is within [`Self::MAX_SIZE`].
+            pub fn extend_from_slice(&mut self, words: &[Word]) -> Result<(), SizeError> {
+                check_size(self.0.len() + words.len(), Self::MAX_SIZE)?;
+                self.0.extend_from_slice(words);
+                Ok(())
+            }
+        }
+
+        impl Deref for $ty {
+            type Target = [Word];
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl AsRef<[Word]> for $ty {
+            fn as_ref(&self) -> &[Word] {
+                &self.0
+            }
+        }
+
+        impl Borrow<[Word]> for $ty {
+            fn borrow(&self) -> &[Word] {
+                &self.0
+            }
+        }
+
+        impl fmt::Debug for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl<const N: usize> From<[Word; N]> for $ty {
+            fn from(words: [Word; N]) -> Self {
+                const { assert!(N <= $ty::MAX_SIZE, "array exceeds the maximum size") };
+                Self(words.into())
+            }
+        }
+
+        impl TryFrom<Vec<Word>> for $ty {
+            type Error = SizeError;
+            fn try_from(words: Vec<Word>) -> Result<Self, Self::Error> {
+                Self::new(words)
+            }
+        }
+
+        impl TryFrom<&[Word]> for $ty {
+            type Error = SizeError;
+            fn try_from(words: &[Word]) -> Result<Self, Self::Error> {
+                check_size(words.len(), Self::MAX_SIZE)?;
+                Ok(Self(words.to_vec()))
+            }
+        }
+
+        impl From<$ty> for Vec<Word> {
+            fn from(words: $ty) -> Self {
+                words.0
+            }
+        }
+
+        impl PartialEq<[Word]> for $ty {
+            fn eq(&self, other: &[Word]) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<Vec<Word>> for $ty {
+            fn eq(&self, other: &Vec<Word>) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl<const N: usize> PartialEq<[Word; N]> for $ty {
+            fn eq(&self, other: &[Word; N]) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl IntoIterator for $ty {
+            type Item = Word;
+            type IntoIter = vec::IntoIter<Word>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.0.into_iter()
+            }
+        }
+
+        impl<'a> IntoIterator for &'a $ty {
+            type Item = &'a Word;
+            type IntoIter = core::slice::Iter<'a, Word>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.0.iter()
+            }
+        }
+
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                self.0.serialize(serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let words = Vec::<Word>::deserialize(deserializer)?;
+                Self::new(words).map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+impl_words!(Key);
+impl_words!(Value);
+
+impl Key {
+    /// Maximum number of words in a key.
+    pub const MAX_SIZE: usize = 1000;
+
+    /// The next key of the same size in lexicographic order, or `None` if
+    /// every word is [`Word::MAX`].
+    ///
+    /// This is useful for iterating over a range of keys.
+    pub fn next_key(&self) -> Option<Key> {
+        let mut key = self.clone();
+        for w in key.0.iter_mut().rev() {
+            match *w {
+                Word::MAX => *w = Word::MIN,
+                _ => {
+                    *w += 1;
+                    return Some(key);
+                }
+            }
+        }
+        None
+    }
+}
+
+impl Value {
+    /// Maximum number of words in a value.
+    pub const MAX_SIZE: usize = 10_000;
+}
@@ -0,0 +1,195 @@
+//! # Versioned Encodings
+//! Encodings of [`Predicate`]s, [`Program`]s and [`SolutionSet`]s prefixed with
+//! a header identifying the kind of item and the version of its layout, for
+//! storing and exchanging items that must remain decodable as layouts evolve.
+//!
+//! These are distinct from the unversioned encodings used for hashing, so
+//! content addresses do not depend on the version.
+//!
+//! # Layout
+//! | Field | Size (bytes) | Description |
+//! | --- | --- | --- |
+//! | kind | 1 | The [`Kind`] of the encoded item. |
+//! | version | 1 | The version of the payload layout. |
+//! | payload | remaining | The item encoded with the given version's layout. |
+//!
+//! ## Versions
+//! | Kind | Version | Payload |
+//! | --- | --- | --- |
+//! | [`Kind::Predicate`] | 1 | [`Predicate::encode`]. |
+//! | [`Kind::Program`] | 1 | The program's bytes. |
+//! | [`Kind::SolutionSet`] | 1 | A [`wire`] frame. |
+//!
+//! # Upgrading
+//! Encoders always write the latest version of each kind, e.g.
+//! [`PREDICATE_VERSION`]. Changing a layout adds a new version: the constant
+//! is incremented, the table above gains a row and the decoder keeps decoding
+//! every earlier version. A decoder rejects versions newer than it knows of
+//! with [`VersionedDecodeError::UnsupportedVersion`], so that nodes running
+//! older software fail loudly rather than misinterpreting the payload.
+//!
+//! ```
+//! use essential_types::{versioned, Program};
+//!
+//! let program = Program(vec![1, 2, 3]);
+//! let bytes = versioned::encode_program(&program);
+//! assert_eq!(bytes[..2], [1, versioned::PROGRAM_VERSION]);
+//! assert_eq!(versioned::decode_program(&bytes), Ok(program));
+//! ```
+
+use crate::{
+    predicate::{Predicate, PredicateDecodeError, PredicateEncodeError, Program},
+    solution::{
+        wire::{self, WireDecodeError},
+        SolutionSet,
+    },
+};
+use alloc::vec::Vec;
+
+/// Size of the header preceding the payload.
+const HEADER_SIZE: usize = 2;
+
+/// The latest version of the predicate encoding.
+pub const PREDICATE_VERSION: u8 = 1;
+/// The latest version of the program encoding.
+pub const PROGRAM_VERSION: u8 = 1;
+/// The latest version of the solution set encoding.
+pub const SOLUTION_SET_VERSION: u8 = 1;
+
+/// The kind of item in a versioned encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum Kind {
+    /// A [`Predicate`].
+    Predicate = 0,
+    /// A [`Program`].
+    Program = 1,
+    /// A [`SolutionSet`].
+    SolutionSet = 2,
+}
+
+/// Errors that can occur when decoding a versioned encoding.
+#[derive(Debug, PartialEq)]
+pub enum VersionedDecodeError {
+    /// The bytes are too short to contain the header.
+    MissingHeader,
+    /// The header is for a different kind of item.
+    KindMismatch {
+        /// The kind being decoded.
+        expected: Kind,
+        /// The kind byte of the header.
+        found: u8,
+    },
+    /// The version is newer than the latest version known to this decoder,
+    /// or is `0`.
+    UnsupportedVersion {
+        /// The kind being decoded.
+        kind: Kind,
+        /// The version of the header.
+        version: u8,
+    },
+    /// The payload contains bytes beyond the end of the item.
+    TrailingBytes(usize),
+    /// The program exceeds [`Program::MAX_SIZE`].
+    ProgramTooLarge(usize),
+    /// The predicate payload is invalid.
+    Predicate(PredicateDecodeError),
+    /// The solution set payload is invalid.
+    SolutionSet(WireDecodeError),
+}
+
+impl core::error::Error for VersionedDecodeError {}
+
+impl Kind {
+    /// The latest version of the kind's encoding.
+    pub const fn latest_version(self) -> u8 {
+        match self {
+            Kind::Predicate => PREDICATE_VERSION,
+            Kind::Program => PROGRAM_VERSION,
+            Kind::SolutionSet => SOLUTION_SET_VERSION,
+        }
+    }
+}
+
+/// Prefix the payload with the header of the latest version of the kind.
+fn with_header(kind: Kind, payload: impl IntoIterator<Item = u8>) -> Vec<u8> {
+    [kind as u8, kind.latest_version()]
+        .into_iter()
+        .chain(payload)
+        .collect()
+}
+
+/// Split the bytes into the header's version and the payload, checking the
+/// kind and that the version is supported.
+fn split_header(kind: Kind, bytes: &[u8]) -> Result<(u8, &[u8]), VersionedDecodeError> {
+    let Some(([found, version], payload)) = bytes.split_first_chunk::<HEADER_SIZE>() else {
+        return Err(VersionedDecodeError::MissingHeader);
+    };
+    if *found != kind as u8 {
+        return Err(VersionedDecodeError::KindMismatch {
+            expected: kind,
+            found: *found,
+        });
+    }
+    if *version == 0 || *version > kind.latest_version() {
+        return Err(VersionedDecodeError::UnsupportedVersion {
+            kind,
+            version: *version,
+        });
+    }
+    Ok((*version, payload))
+}
+
+/// Encode a predicate with the latest version.
+pub fn encode_predicate(predicate: &Predicate) -> Result<Vec<u8>, PredicateEncodeError> {
+    Ok(with_header(Kind::Predicate, predicate.encode()?))
+}
+
+/// Decode a predicate of any supported version.
+pub fn decode_predicate(bytes: &[u8]) -> Result<Predicate, VersionedDecodeError> {
+    match split_header(Kind::Predicate, bytes)? {
+        (1, payload) => {
+            let predicate = Predicate::decode(payload).map_err(VersionedDecodeError::Predicate)?;
+            match payload.len() - predicate.encoded_size() {
+                0 => Ok(predicate),
+                n => Err(VersionedDecodeError::TrailingBytes(n)),
+            }
+        }
+        _ => unreachable!("version checked by `split_header`"),
+    }
+}
+
+/// Encode a program with the latest version.
+pub fn encode_program(program: &Program) -> Vec<u8> {
+    with_header(Kind::Program, program.0.iter().copied())
+}
+
+/// Decode a program of any supported version.
+pub fn decode_program(bytes: &[u8]) -> Result<Program, VersionedDecodeError> {
+    match split_header(Kind::Program, bytes)? {
+        (1, payload) if payload.len() > usize::from(Program::MAX_SIZE) => {
+            Err(VersionedDecodeError::ProgramTooLarge(payload.len()))
+        }
+        (1, payload) => Ok(Program(payload.to_vec())),
+        _ => unreachable!("version checked by `split_header`"),
+    }
+}
+
+/// Encode a solution set with the latest version.
+pub fn encode_solution_set(set: &SolutionSet) -> Vec<u8> {
+    with_header(Kind::SolutionSet, wire::encode(set))
+}
+
+/// Decode a solution set of any supported version.
+pub fn decode_solution_set(bytes: &[u8]) -> Result<SolutionSet, VersionedDecodeError> {
+    match split_header(Kind::SolutionSet, bytes)? {
+        (1, payload) => {
+            let (set, len) = wire::decode(payload).map_err(VersionedDecodeError::SolutionSet)?;
+            match payload.len() - len {
+                0 => Ok(set),
+                n => Err(VersionedDecodeError::TrailingBytes(n)),
+            }
+        }
+        _ => unreachable!("version checked by `split_header`"),
+    }
+}
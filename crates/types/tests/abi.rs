@@ -0,0 +1,50 @@
+use essential_types::{
+    abi::{Decode, DecodeError, Encode},
+    convert::StrFromWordsError,
+    ContentAddress, Word,
+};
+
+#[test]
+fn abi_roundtrip() {
+    let value = (
+        ContentAddress([1; 32]),
+        [Some(-1i8), None],
+        vec![String::from("hello, world!"), String::new()],
+        u16::MAX,
+        u128::MAX - 1,
+    );
+    let words = value.to_words();
+    assert_eq!(words.len(), 4 + 3 + 1 + 3 + 1 + 1 + 2);
+    assert_eq!(Decode::from_words(&words), Ok(value));
+}
+
+#[test]
+fn abi_invalid() {
+    assert_eq!(bool::from_words(&[2]), Err(DecodeError::OutOfRange(2)));
+    assert_eq!(u8::from_words(&[-1]), Err(DecodeError::OutOfRange(-1)));
+    assert_eq!(
+        Option::<Word>::from_words(&[2, 0]),
+        Err(DecodeError::InvalidTag(2))
+    );
+    assert_eq!(
+        Vec::<Word>::from_words(&[-1]),
+        Err(DecodeError::NegativeLength(-1))
+    );
+    assert_eq!(
+        Vec::<Word>::from_words(&[2, 0]),
+        Err(DecodeError::UnexpectedEnd)
+    );
+    assert_eq!(String::from_words(&[9, 0]), Err(DecodeError::UnexpectedEnd));
+    assert_eq!(
+        String::from_words(&[1, 1]),
+        Err(DecodeError::Str(StrFromWordsError::NonZeroPadding))
+    );
+    assert_eq!(
+        ContentAddress::from_words(&[0; 3]),
+        Err(DecodeError::UnexpectedEnd)
+    );
+    assert_eq!(
+        Word::from_words(&[0, 0]),
+        Err(DecodeError::TrailingWords(1))
+    );
+}
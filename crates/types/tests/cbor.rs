@@ -0,0 +1,116 @@
+#![cfg(feature = "cbor")]
+
+use essential_types::{
+    cbor::{self, CborDecodeError},
+    contract::{Contract, ContractMetadata, ContractWithMetadata},
+    predicate::{Node, Predicate},
+    solution::{Mutation, Solution, SolutionSet},
+    ContentAddress, PredicateAddress, Word,
+};
+
+fn solution_set() -> SolutionSet {
+    SolutionSet {
+        solutions: vec![Solution {
+            predicate_to_solve: PredicateAddress {
+                contract: ContentAddress([1; 32]),
+                predicate: ContentAddress([2; 32]),
+            },
+            predicate_data: vec![[0, -1, 24, Word::MIN, Word::MAX].into(), [].into()],
+            state_mutations: vec![Mutation {
+                key: [1].into(),
+                value: [-25, 256].into(),
+            }],
+        }],
+    }
+}
+
+fn contract() -> Contract {
+    let node = |edge_start, max_gas| Node {
+        edge_start,
+        program_address: ContentAddress([3; 32]),
+        max_gas,
+    };
+    Contract {
+        predicates: vec![Predicate {
+            nodes: vec![node(0, Some(u64::MAX)), node(u16::MAX, None)],
+            edges: vec![1],
+        }],
+        salt: [4; 32],
+    }
+}
+
+fn metadata() -> ContractMetadata {
+    ContractMetadata {
+        name: "counter".into(),
+        version: "1.0.0".into(),
+        abi_hash: [5; 32],
+        source_commit: Some("0123abc".into()),
+    }
+}
+
+#[test]
+fn cbor_roundtrip() {
+    let set = solution_set();
+    let bytes = cbor::to_vec(&set);
+    assert_eq!(cbor::from_slice::<SolutionSet>(&bytes), Ok(set));
+
+    let contract = contract();
+    let bytes = cbor::to_vec(&contract);
+    assert_eq!(cbor::from_slice::<Contract>(&bytes), Ok(contract.clone()));
+
+    let with_metadata = contract.with_metadata(metadata());
+    let bytes = cbor::to_vec(&with_metadata);
+    assert_eq!(
+        cbor::from_slice::<ContractWithMetadata>(&bytes),
+        Ok(with_metadata)
+    );
+}
+
+#[test]
+fn cbor_canonical() {
+    // Shortest form integers.
+    let words: Vec<Word> = vec![0, 23, 24, -1, -24, -25, 256, Word::MIN];
+    let mut expected = vec![0x88, 0x00, 0x17, 0x18, 24, 0x20, 0x37, 0x38, 24, 0x19, 1, 0];
+    expected.extend([0x3b, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]);
+    assert_eq!(cbor::to_vec(&words), expected);
+
+    // A node with no max gas.
+    let node = Node {
+        edge_start: 1,
+        program_address: ContentAddress([0; 32]),
+        max_gas: None,
+    };
+    let mut expected = vec![0x83, 0x01, 0x58, 32];
+    expected.extend([0; 32]);
+    expected.push(0xf6);
+    assert_eq!(cbor::to_vec(&node), expected);
+
+    // Non-shortest forms, indefinite lengths and trailing bytes are rejected.
+    assert_eq!(
+        cbor::from_slice::<Word>(&[0x18, 23]),
+        Err(CborDecodeError::NonCanonical)
+    );
+    assert_eq!(
+        cbor::from_slice::<Vec<Word>>(&[0x9f, 0xff]),
+        Err(CborDecodeError::NonCanonical)
+    );
+    assert_eq!(
+        cbor::from_slice::<Word>(&[0x00, 0x00]),
+        Err(CborDecodeError::TrailingBytes(1))
+    );
+    assert_eq!(
+        cbor::from_slice::<Word>(&[0x40]),
+        Err(CborDecodeError::UnexpectedType {
+            expected: 0,
+            found: 2
+        })
+    );
+    assert_eq!(
+        cbor::from_slice::<Word>(&[0x1b, 0x80, 0, 0, 0, 0, 0, 0, 0]),
+        Err(CborDecodeError::OutOfRange)
+    );
+    assert_eq!(
+        cbor::from_slice::<SolutionSet>(&[0x82, 0x80, 0x80]),
+        Err(CborDecodeError::InvalidLength(2))
+    );
+}
@@ -0,0 +1,84 @@
+use essential_types::{
+    predicate::{Edge, Node, Predicate, PredicateBuildError},
+    ContentAddress,
+};
+
+fn ca(n: u8) -> ContentAddress {
+    ContentAddress([n; 32])
+}
+
+#[test]
+fn build_predicate() {
+    let mut builder = Predicate::build();
+    let a = builder.node(ca(0));
+    let leaf = builder.node(ca(1));
+    let b = builder.node_with_max_gas(ca(2), 10);
+    let c = builder.node(ca(3));
+    builder.edge(b, c).edge(a, b).edge(a, leaf).edge(a, c);
+    let predicate = builder.finish().unwrap();
+
+    let node = |edge_start, n, max_gas| Node {
+        edge_start,
+        program_address: ca(n),
+        max_gas,
+    };
+    let expected = Predicate {
+        nodes: vec![
+            node(0, 0, None),
+            node(3, 1, None),
+            node(3, 2, Some(10)),
+            node(Edge::MAX, 3, None),
+        ],
+        edges: vec![b, leaf, c, c],
+    };
+    assert_eq!(predicate, expected);
+    assert_eq!(predicate.node_edges(0), Some(&[b, leaf, c][..]));
+    assert_eq!(predicate.node_edges(1), Some(&[][..]));
+    assert_eq!(predicate.node_edges(2), Some(&[c][..]));
+    assert_eq!(predicate.node_edges(3), Some(&[][..]));
+}
+
+#[test]
+fn build_invalid_predicate() {
+    let mut builder = Predicate::build();
+    let a = builder.node(ca(0));
+    builder.edge(a, 1);
+    assert_eq!(
+        builder.finish(),
+        Err(PredicateBuildError::InvalidEdge {
+            parent: a,
+            child: 1
+        })
+    );
+
+    let mut builder = Predicate::build();
+    let a = builder.node(ca(0));
+    let b = builder.node(ca(1));
+    let c = builder.node(ca(2));
+    builder.edge(a, b).edge(b, c).edge(c, b);
+    assert_eq!(builder.finish(), Err(PredicateBuildError::Cycle(b)));
+
+    let mut builder = Predicate::build();
+    for _ in 0..=Predicate::MAX_NODES {
+        builder.node(ca(0));
+    }
+    assert_eq!(
+        builder.finish(),
+        Err(PredicateBuildError::TooManyNodes(
+            usize::from(Predicate::MAX_NODES) + 1
+        ))
+    );
+
+    let mut builder = Predicate::build();
+    let a = builder.node(ca(0));
+    let b = builder.node(ca(1));
+    for _ in 0..=Predicate::MAX_EDGES {
+        builder.edge(a, b);
+    }
+    assert_eq!(
+        builder.finish(),
+        Err(PredicateBuildError::TooManyEdges(
+            usize::from(Predicate::MAX_EDGES) + 1
+        ))
+    );
+}
@@ -0,0 +1,36 @@
+#![cfg(feature = "schema")]
+
+use essential_types::{
+    abi::DecodeError,
+    contract::{MultiSignedContract, SignedContract},
+    predicate::{PredicateBuildError, Program},
+    solution::SolutionSet,
+};
+use schemars::{
+    schema::{InstanceType, SingleOrVec},
+    schema_for,
+};
+
+#[test]
+fn schema_coverage() {
+    let set = schema_for!(SolutionSet);
+    assert!(set.definitions.contains_key("Solution"));
+    assert!(set.definitions.contains_key("Mutation"));
+
+    let contract = schema_for!(SignedContract);
+    let multisig = schema_for!(MultiSignedContract);
+    assert!(multisig.definitions.contains_key("SignerKey"));
+    assert!(contract.definitions.contains_key("Signature"));
+    assert!(contract.definitions.contains_key("Predicate"));
+    assert!(contract.definitions.contains_key("Node"));
+
+    // Bytecode is serialized as a hex string by human-readable formats.
+    let program = schema_for!(Program);
+    assert_eq!(
+        program.schema.instance_type,
+        Some(SingleOrVec::Single(Box::new(InstanceType::String)))
+    );
+
+    schema_for!(DecodeError);
+    schema_for!(PredicateBuildError);
+}
@@ -0,0 +1,176 @@
+use essential_types::{
+    solution::{Mutation, Solution, SolutionSet},
+    ContentAddress, PredicateAddress,
+};
+
+fn addr(n: u8) -> PredicateAddress {
+    PredicateAddress {
+        contract: ContentAddress([n; 32]),
+        predicate: ContentAddress([n; 32]),
+    }
+}
+
+#[test]
+fn build_solution_set() {
+    let set = SolutionSet::build()
+        .solution(addr(0))
+        .predicate_data([1])
+        .predicate_data([2, 3])
+        .mutation([0], [42])
+        .solution(addr(1))
+        .mutation([1], [])
+        .finish();
+    let expected = SolutionSet {
+        solutions: vec![
+            Solution {
+                predicate_to_solve: addr(0),
+                predicate_data: vec![[1].into(), [2, 3].into()],
+                state_mutations: vec![Mutation {
+                    key: [0].into(),
+                    value: [42].into(),
+                }],
+            },
+            Solution {
+                predicate_to_solve: addr(1),
+                predicate_data: vec![],
+                state_mutations: vec![Mutation {
+                    key: [1].into(),
+                    value: [].into(),
+                }],
+            },
+        ],
+    };
+    assert_eq!(set, expected);
+    assert_eq!(SolutionSet::build().finish().solutions, vec![]);
+
+    // The set is only returned if valid.
+    let one_mutation = |set: &SolutionSet| match set.state_mutations_len() {
+        1 => Ok(()),
+        n => Err(n),
+    };
+    let builder = SolutionSet::build().solution(addr(0)).mutation([0], [42]);
+    assert_eq!(
+        builder.clone().try_finish(one_mutation),
+        Ok(builder.clone().finish())
+    );
+    assert_eq!(builder.mutation([1], []).try_finish(one_mutation), Err(2));
+}
+
+#[test]
+fn diff_solution() {
+    use essential_types::solution::diff::{self, Change};
+
+    let old = Solution {
+        predicate_to_solve: addr(0),
+        predicate_data: vec![[1].into(), [2].into(), [3].into()],
+        state_mutations: vec![
+            Mutation {
+                key: [2].into(),
+                value: [20].into(),
+            },
+            Mutation {
+                key: [0].into(),
+                value: [0].into(),
+            },
+            Mutation {
+                key: [1].into(),
+                value: [10].into(),
+            },
+        ],
+    };
+    assert!(diff::solution(&old, &old).is_empty());
+
+    let new = Solution {
+        predicate_to_solve: addr(1),
+        predicate_data: vec![[1].into(), [4].into()],
+        state_mutations: vec![
+            Mutation {
+                key: [3].into(),
+                value: [].into(),
+            },
+            Mutation {
+                key: [1].into(),
+                value: [11].into(),
+            },
+            Mutation {
+                key: [0].into(),
+                value: [0].into(),
+            },
+        ],
+    };
+    let diff = diff::solution(&old, &new);
+    assert_eq!(diff.predicate_to_solve, Some((addr(0), addr(1))));
+    assert_eq!(
+        diff.predicate_data,
+        vec![
+            (
+                1,
+                Change::Changed {
+                    old: [2].into(),
+                    new: [4].into()
+                }
+            ),
+            (2, Change::Removed([3].into())),
+        ]
+    );
+    assert_eq!(
+        diff.state_mutations,
+        vec![
+            (
+                [1].into(),
+                Change::Changed {
+                    old: [10].into(),
+                    new: [11].into()
+                }
+            ),
+            ([2].into(), Change::Removed([20].into())),
+            ([3].into(), Change::Added([].into())),
+        ]
+    );
+
+    // Reversing the sides swaps additions and removals.
+    let diff = diff::solution(&new, &old);
+    assert_eq!(diff.predicate_data[1], (2, Change::Added([3].into())));
+    assert_eq!(
+        diff.state_mutations[2],
+        ([3].into(), Change::Removed([].into()))
+    );
+}
+
+#[test]
+fn diff_solution_set() {
+    use essential_types::solution::diff::{self, SolutionChange};
+
+    let old = SolutionSet::build()
+        .solution(addr(0))
+        .predicate_data([1])
+        .solution(addr(1))
+        .mutation([0], [1])
+        .finish();
+    assert!(diff::solution_set(&old, &old).is_empty());
+
+    let new = SolutionSet::build()
+        .solution(addr(0))
+        .predicate_data([1])
+        .solution(addr(1))
+        .mutation([0], [2])
+        .solution(addr(2))
+        .finish();
+    let diff = diff::solution_set(&old, &new);
+    assert_eq!(diff.solutions.len(), 2);
+    let (ix, SolutionChange::Changed(changed)) = &diff.solutions[0] else {
+        panic!("expected a changed solution");
+    };
+    assert_eq!(*ix, 1);
+    assert_eq!(changed.state_mutations.len(), 1);
+    assert_eq!(
+        diff.solutions[1],
+        (2, SolutionChange::Added(new.solutions[2].clone()))
+    );
+
+    let diff = diff::solution_set(&new, &old);
+    assert_eq!(
+        diff.solutions[1],
+        (2, SolutionChange::Removed(new.solutions[2].clone()))
+    );
+}
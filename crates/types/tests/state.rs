@@ -0,0 +1,53 @@
+use essential_types::{state::SizeError, Key, Value, Word};
+
+#[test]
+fn checked_construction() {
+    let max = vec![0; Key::MAX_SIZE];
+    assert_eq!(Key::new(max.clone()).unwrap(), max);
+    assert_eq!(
+        Key::new(vec![0; Key::MAX_SIZE + 1]),
+        Err(SizeError {
+            size: Key::MAX_SIZE + 1,
+            max: Key::MAX_SIZE,
+        })
+    );
+    assert!(Value::try_from(&[0; Value::MAX_SIZE][..]).is_ok());
+    assert!(Value::try_from(vec![0; Value::MAX_SIZE + 1]).is_err());
+}
+
+#[test]
+fn checked_growth() {
+    let mut key = Key::new(vec![0; Key::MAX_SIZE - 2]).unwrap();
+    key.push(1).unwrap();
+    assert_eq!(
+        key.extend_from_slice(&[2, 3]),
+        Err(SizeError {
+            size: Key::MAX_SIZE + 1,
+            max: Key::MAX_SIZE,
+        })
+    );
+    key.extend_from_slice(&[2]).unwrap();
+    assert_eq!(key.len(), Key::MAX_SIZE);
+    assert!(key.push(3).is_err());
+    assert_eq!(key[Key::MAX_SIZE - 2..], [1, 2]);
+}
+
+#[test]
+fn next_key() {
+    assert_eq!(Key::from([1, 2, 3]).next_key(), Some(Key::from([1, 2, 4])));
+    assert_eq!(
+        Key::from([1, Word::MAX]).next_key(),
+        Some(Key::from([2, Word::MIN]))
+    );
+    assert_eq!(Key::from([Word::MAX, Word::MAX]).next_key(), None);
+    assert_eq!(Key::default().next_key(), None);
+}
+
+#[test]
+fn conversions() {
+    let value = Value::from([1, 2, 3]);
+    assert_eq!(value.as_slice(), &[1, 2, 3]);
+    assert_eq!(value.iter().sum::<Word>(), 6);
+    assert_eq!(Vec::<Word>::from(value.clone()), vec![1, 2, 3]);
+    assert_eq!(value.into_inner(), [1, 2, 3]);
+}
@@ -0,0 +1,133 @@
+use essential_types::{
+    predicate::{Edge, Node, Predicate, PredicateDecodeError, Program},
+    solution::{wire::WireDecodeError, Mutation, Solution, SolutionSet},
+    versioned::{self, Kind, VersionedDecodeError},
+    ContentAddress, PredicateAddress,
+};
+
+fn predicate() -> Predicate {
+    Predicate {
+        nodes: vec![
+            Node {
+                edge_start: 0,
+                program_address: ContentAddress([1; 32]),
+                max_gas: Some(100),
+            },
+            Node {
+                edge_start: Edge::MAX,
+                program_address: ContentAddress([2; 32]),
+                max_gas: None,
+            },
+        ],
+        edges: vec![1],
+    }
+}
+
+fn solution_set() -> SolutionSet {
+    SolutionSet {
+        solutions: vec![Solution {
+            predicate_to_solve: PredicateAddress {
+                contract: ContentAddress([3; 32]),
+                predicate: ContentAddress([4; 32]),
+            },
+            predicate_data: vec![[1, 2].into()],
+            state_mutations: vec![Mutation {
+                key: [0].into(),
+                value: [-1].into(),
+            }],
+        }],
+    }
+}
+
+#[test]
+fn roundtrip() {
+    let predicate = predicate();
+    let bytes = versioned::encode_predicate(&predicate).unwrap();
+    assert_eq!(bytes[..2], [0, versioned::PREDICATE_VERSION]);
+    assert_eq!(bytes[2..], predicate.encode().unwrap().collect::<Vec<_>>());
+    assert_eq!(versioned::decode_predicate(&bytes), Ok(predicate));
+
+    let program = Program(vec![0xAB; 16]);
+    let bytes = versioned::encode_program(&program);
+    assert_eq!(bytes[..2], [1, versioned::PROGRAM_VERSION]);
+    assert_eq!(versioned::decode_program(&bytes), Ok(program));
+
+    let set = solution_set();
+    let bytes = versioned::encode_solution_set(&set);
+    assert_eq!(bytes[..2], [2, versioned::SOLUTION_SET_VERSION]);
+    assert_eq!(versioned::decode_solution_set(&bytes), Ok(set));
+}
+
+#[test]
+fn invalid_header() {
+    assert_eq!(
+        versioned::decode_program(&[1]),
+        Err(VersionedDecodeError::MissingHeader)
+    );
+
+    let bytes = versioned::encode_program(&Program(vec![1, 2, 3]));
+    assert_eq!(
+        versioned::decode_predicate(&bytes),
+        Err(VersionedDecodeError::KindMismatch {
+            expected: Kind::Predicate,
+            found: 1,
+        })
+    );
+}
+
+#[test]
+fn unsupported_version() {
+    for version in [0, versioned::SOLUTION_SET_VERSION + 1, u8::MAX] {
+        let mut bytes = versioned::encode_solution_set(&solution_set());
+        bytes[1] = version;
+        let err = versioned::decode_solution_set(&bytes).unwrap_err();
+        assert_eq!(
+            err,
+            VersionedDecodeError::UnsupportedVersion {
+                kind: Kind::SolutionSet,
+                version,
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            format!("unsupported solution set encoding version {version}, latest supported is 1")
+        );
+    }
+}
+
+#[test]
+fn invalid_payload() {
+    let mut bytes = versioned::encode_predicate(&predicate()).unwrap();
+    bytes.push(0);
+    assert_eq!(
+        versioned::decode_predicate(&bytes),
+        Err(VersionedDecodeError::TrailingBytes(1))
+    );
+    bytes.truncate(4);
+    assert_eq!(
+        versioned::decode_predicate(&bytes),
+        Err(VersionedDecodeError::Predicate(
+            PredicateDecodeError::BytesTooShort
+        ))
+    );
+
+    let mut bytes = versioned::encode_solution_set(&solution_set());
+    bytes.push(0);
+    assert_eq!(
+        versioned::decode_solution_set(&bytes),
+        Err(VersionedDecodeError::TrailingBytes(1))
+    );
+    bytes.truncate(3);
+    assert_eq!(
+        versioned::decode_solution_set(&bytes),
+        Err(VersionedDecodeError::SolutionSet(
+            WireDecodeError::UnexpectedEnd
+        ))
+    );
+
+    let program = Program(vec![0; usize::from(Program::MAX_SIZE) + 1]);
+    assert_eq!(
+        versioned::decode_program(&versioned::encode_program(&program)),
+        Err(VersionedDecodeError::ProgramTooLarge(program.0.len()))
+    );
+}
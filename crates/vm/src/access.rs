@@ -7,11 +7,12 @@ use crate::{
     types::{
         convert::{bytes_from_word, u8_32_from_word_4, word_4_from_u8_32},
         solution::{Solution, SolutionIndex},
-        Value, Word,
+        ContentAddress, Value, Word,
     },
     Stack,
 };
-use std::sync::Arc;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
 
 #[cfg(test)]
 mod dec_vars;
@@ -32,6 +33,11 @@ pub struct Access {
     /// Checking is performed for one solution at a time. This index refers to
     /// the checked predicate's associated solution within the `SolutionSet` slice.
     pub index: usize,
+    /// The address of the program currently being executed, if known.
+    ///
+    /// This is supplied by the checker when constructing the execution context
+    /// and is required by the `Access::ThisProgramAddress` operation.
+    pub this_program: Option<ContentAddress>,
 }
 
 impl Access {
@@ -44,9 +50,16 @@ impl Access {
         Self {
             solutions,
             index: solution_index.into(),
+            this_program: None,
         }
     }
 
+    /// Provide the address of the program currently being executed.
+    pub fn with_this_program(mut self, program: ContentAddress) -> Self {
+        self.this_program = Some(program);
+        self
+    }
+
     /// The solution associated with the predicate currently being checked.
     ///
     /// **Panics** in the case that `self.index` is out of range of the `self.solutions` slice.
@@ -108,6 +121,17 @@ pub(crate) fn this_contract_address(solution: &Solution, stack: &mut Stack) -> O
     Ok(())
 }
 
+/// `Access::ThisProgramAddress` implementation.
+pub(crate) fn this_program_address(
+    this_program: Option<&ContentAddress>,
+    stack: &mut Stack,
+) -> OpResult<()> {
+    let program = this_program.ok_or(AccessError::ThisProgramAddressUnavailable)?;
+    let words = word_4_from_u8_32(program.0);
+    stack.extend(words)?;
+    Ok(())
+}
+
 pub(crate) fn repeat_counter(stack: &mut Stack, repeat: &Repeat) -> OpResult<()> {
     let counter = repeat.counter()?;
     Ok(stack.push(counter)?)
@@ -192,7 +216,7 @@ fn sha256(bytes: &[u8]) -> [u8; 32] {
     result
 }
 
-fn range_from_start_len(start: Word, len: Word) -> Option<std::ops::Range<usize>> {
+fn range_from_start_len(start: Word, len: Word) -> Option<core::ops::Range<usize>> {
     let start = usize::try_from(start).ok()?;
     let len = usize::try_from(len).ok()?;
     let end = start.checked_add(len)?;
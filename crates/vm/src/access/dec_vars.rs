@@ -83,7 +83,10 @@ fn test_dec_var(stack: &[Word], dec_vars: &[&[Word]]) -> OpResult<Vec<Word>> {
     let mut s = Stack::default();
     s.extend(stack.to_vec()).unwrap();
 
-    let dec_vars = dec_vars.iter().map(|v| v.to_vec()).collect::<Vec<_>>();
+    let dec_vars = dec_vars
+        .iter()
+        .map(|v| Value::try_from(*v).unwrap())
+        .collect::<Vec<_>>();
     predicate_data(&dec_vars, &mut s).map(|_| s.into())
 }
 
@@ -118,7 +121,10 @@ fn test_dec_var_len(stack: &[Word], dec_vars: &[&[Word]]) -> OpResult<Vec<Word>>
     let mut s = Stack::default();
     s.extend(stack.to_vec()).unwrap();
 
-    let dec_vars = dec_vars.iter().map(|v| v.to_vec()).collect::<Vec<_>>();
+    let dec_vars = dec_vars
+        .iter()
+        .map(|v| Value::try_from(*v).unwrap())
+        .collect::<Vec<_>>();
     predicate_data_len(&dec_vars, &mut s)
         .map(|_| s.into())
         .map_err(From::from)
@@ -153,7 +159,10 @@ fn test_dec_var_len(stack: &[Word], dec_vars: &[&[Word]]) -> OpResult<Vec<Word>>
     ; "sanity dec var len"
 )]
 fn test_dec_var_ops(ops: Vec<Op>, dec_vars: &[&[Word]]) -> OpResult<Vec<Word>, String> {
-    let dec_vars = dec_vars.iter().map(|v| v.to_vec()).collect::<Vec<_>>();
+    let dec_vars = dec_vars
+        .iter()
+        .map(|v| Value::try_from(*v).unwrap())
+        .collect::<Vec<_>>();
     let solutions = vec![Solution {
         predicate_to_solve: PredicateAddress {
             contract: ContentAddress([0; 32]),
@@ -165,6 +174,7 @@ fn test_dec_var_ops(ops: Vec<Op>, dec_vars: &[&[Word]]) -> OpResult<Vec<Word>, S
     let access = Access {
         solutions: Arc::new(solutions),
         index: 0,
+        this_program: None,
     };
     let op_gas_cost = &|_: &Op| 1;
     let mut vm = Vm::default();
@@ -175,7 +185,7 @@ fn test_dec_var_ops(ops: Vec<Op>, dec_vars: &[&[Word]]) -> OpResult<Vec<Word>, S
 
 #[test]
 fn test_dec_var_slots() {
-    let vars = std::iter::repeat_n(vec![-1, -2, -3, 900], 12).collect::<Vec<_>>();
+    let vars = std::iter::repeat_n(Value::from([-1, -2, -3, 900]), 12).collect::<Vec<_>>();
 
     let mut stack = crate::Stack::default();
 
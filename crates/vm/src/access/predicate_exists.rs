@@ -126,7 +126,11 @@ fn setup(input: &[Setup], i: usize) -> (Stack, Vec<Solution>, LazyCache) {
                 contract: ContentAddress(s.contract_addr),
                 predicate: ContentAddress(s.predicate_addr),
             },
-            predicate_data: s.args.clone(),
+            predicate_data: s
+                .args
+                .iter()
+                .map(|arg| Value::try_from(arg.as_slice()).unwrap())
+                .collect(),
             state_mutations: Default::default(),
         })
         .collect();
@@ -16,7 +16,7 @@ macro_rules! check_dec_var {
 
 #[test]
 fn test_predicate_data() {
-    let d = vec![vec![42]];
+    let d = vec![[42].into()];
 
     // Empty stack.
     let mut stack = Stack::default();
@@ -36,7 +36,7 @@ fn test_predicate_data() {
     ));
 
     // Slot index in-bounds but value is empty
-    let d = vec![vec![]];
+    let d = vec![Value::default()];
     let mut stack = Stack::default();
     stack.push(0).unwrap();
     stack.push(0).unwrap();
@@ -47,7 +47,7 @@ fn test_predicate_data() {
     ));
 
     // Slot index in-bounds and value is not empty
-    let d = vec![vec![42]];
+    let d = vec![[42].into()];
     let mut stack = Stack::default();
     stack.push(0).unwrap();
     stack.push(0).unwrap();
@@ -56,7 +56,7 @@ fn test_predicate_data() {
     assert_eq!(stack.pop().unwrap(), 42);
 
     // Get's first word,
-    let d = vec![(0..10).collect()];
+    let d = vec![Value::new((0..10).collect()).unwrap()];
     let mut stack = Stack::default();
     stack.push(0).unwrap();
     stack.push(0).unwrap();
@@ -65,7 +65,10 @@ fn test_predicate_data() {
     assert_eq!(stack.pop().unwrap(), 0);
 
     // Get's first word with multiple slots,
-    let d = vec![(0..10).collect(), (10..20).collect()];
+    let d = vec![
+        Value::new((0..10).collect()).unwrap(),
+        Value::new((10..20).collect()).unwrap(),
+    ];
     let mut stack = Stack::default();
     stack.push(1).unwrap();
     stack.push(0).unwrap();
@@ -76,7 +79,7 @@ fn test_predicate_data() {
 
 #[test]
 fn test_predicate_data_at() {
-    let d = vec![vec![42], vec![9, 20]];
+    let d = vec![[42].into(), [9, 20].into()];
 
     // Missing pred data len.
     let mut stack = Stack::default();
@@ -127,7 +130,7 @@ fn test_predicate_data_at() {
     ));
 
     // Value range out of bounds.
-    let d = vec![vec![]];
+    let d = vec![Value::default()];
     let mut stack = Stack::default();
     stack.push(0).unwrap();
     stack.push(0).unwrap();
@@ -138,7 +141,7 @@ fn test_predicate_data_at() {
     ));
 
     // Slot index in-bounds, value is empty and length is 0
-    let d = vec![vec![]];
+    let d = vec![Value::default()];
     let mut stack = Stack::default();
     stack.push(0).unwrap();
     stack.push(0).unwrap();
@@ -147,7 +150,7 @@ fn test_predicate_data_at() {
     assert!(stack.is_empty());
 
     // Slot index in-bounds and value is not empty
-    let d = vec![vec![42]];
+    let d = vec![[42].into()];
     let mut stack = Stack::default();
     stack.push(0).unwrap();
     stack.push(0).unwrap();
@@ -156,7 +159,7 @@ fn test_predicate_data_at() {
     assert_eq!(stack.pop().unwrap(), 42);
 
     // Get's word,
-    let d = vec![(0..10).collect()];
+    let d = vec![Value::new((0..10).collect()).unwrap()];
     let mut stack = Stack::default();
     stack.push(0).unwrap();
     stack.push(5).unwrap();
@@ -165,7 +168,10 @@ fn test_predicate_data_at() {
     assert_eq!(stack.pop().unwrap(), 5);
 
     // Get's word with multiple slots,
-    let d = vec![(0..10).collect(), (10..20).collect()];
+    let d = vec![
+        Value::new((0..10).collect()).unwrap(),
+        Value::new((10..20).collect()).unwrap(),
+    ];
     let mut stack = Stack::default();
     stack.push(1).unwrap();
     stack.push(5).unwrap();
@@ -176,7 +182,7 @@ fn test_predicate_data_at() {
 
 #[test]
 fn test_predicate_data_range() {
-    let d = vec![vec![42, 43], vec![44, 45, 46]];
+    let d = vec![[42, 43].into(), [44, 45, 46].into()];
 
     // Missing len.
     let mut stack = Stack::default();
@@ -216,7 +222,7 @@ fn test_predicate_data_range() {
     ));
 
     // Slot index in-bounds but value is empty
-    let d = vec![vec![]];
+    let d = vec![Value::default()];
     let mut stack = Stack::default();
     stack.push(0).unwrap();
     stack.push(0).unwrap();
@@ -227,7 +233,7 @@ fn test_predicate_data_range() {
     ));
 
     // Slot index in-bounds and value is not empty
-    let d = vec![vec![42, 43]];
+    let d = vec![[42, 43].into()];
     let mut stack = Stack::default();
     stack.push(0).unwrap();
     stack.push(0).unwrap();
@@ -237,7 +243,7 @@ fn test_predicate_data_range() {
     assert_eq!(stack.pop().unwrap(), 42);
 
     // Get's range,
-    let d = vec![(0..10).collect()];
+    let d = vec![Value::new((0..10).collect()).unwrap()];
     let mut stack = Stack::default();
     stack.push(0).unwrap();
     stack.push(5).unwrap();
@@ -246,7 +252,10 @@ fn test_predicate_data_range() {
     assert_eq!(*stack, vec![5, 6, 7]);
 
     // Get's word with multiple slots,
-    let d = vec![(0..10).collect(), (10..20).collect()];
+    let d = vec![
+        Value::new((0..10).collect()).unwrap(),
+        Value::new((10..20).collect()).unwrap(),
+    ];
     let mut stack = Stack::default();
     stack.push(1).unwrap();
     stack.push(5).unwrap();
@@ -257,7 +266,7 @@ fn test_predicate_data_range() {
 
 #[test]
 fn test_predicate_data_len() {
-    let d = vec![vec![42, 43]];
+    let d = vec![[42, 43].into()];
 
     // Empty stack.
     let mut stack = Stack::default();
@@ -275,21 +284,24 @@ fn test_predicate_data_len() {
     ));
 
     // Slot index in-bounds but value is empty
-    let d = vec![vec![]];
+    let d = vec![Value::default()];
     let mut stack = Stack::default();
     stack.push(0).unwrap();
     check_dec_var!(d.clone(), &mut stack, predicate_data_len).unwrap();
     assert_eq!(stack.pop().unwrap(), 0);
 
     // Slot index in-bounds and value is not empty
-    let d = vec![vec![42, 43]];
+    let d = vec![[42, 43].into()];
     let mut stack = Stack::default();
     stack.push(0).unwrap();
     check_dec_var!(d.clone(), &mut stack, predicate_data_len).unwrap();
     assert_eq!(stack.pop().unwrap(), 2);
 
     // Get's length with multiple slots,
-    let d = vec![(0..10).collect(), (10..20).collect()];
+    let d = vec![
+        Value::new((0..10).collect()).unwrap(),
+        Value::new((10..20).collect()).unwrap(),
+    ];
     let mut stack = Stack::default();
     stack.push(1).unwrap();
     check_dec_var!(d.clone(), &mut stack, predicate_data_len).unwrap();
@@ -301,10 +313,11 @@ fn predicate_data_single_word_ops() {
     let access = Access {
         solutions: Arc::new(vec![Solution {
             predicate_to_solve: TEST_PREDICATE_ADDR,
-            predicate_data: vec![vec![42]],
+            predicate_data: vec![[42].into()],
             state_mutations: Default::default(),
         }]),
         index: 0,
+        this_program: None,
     };
     let ops = &[
         asm::Stack::Push(0).into(), // Slot index.
@@ -324,10 +337,11 @@ fn predicate_data_ops() {
     let access = Access {
         solutions: Arc::new(vec![Solution {
             predicate_to_solve: TEST_PREDICATE_ADDR,
-            predicate_data: vec![vec![7, 8, 9], vec![10, 11, 12]],
+            predicate_data: vec![[7, 8, 9].into(), [10, 11, 12].into()],
             state_mutations: Default::default(),
         }]),
         index: 0,
+        this_program: None,
     };
     let ops = &[
         asm::Stack::Push(0).into(), // Slot.
@@ -347,10 +361,11 @@ fn predicate_data_slot_oob_ops() {
     let access = Access {
         solutions: Arc::new(vec![Solution {
             predicate_to_solve: TEST_PREDICATE_ADDR,
-            predicate_data: vec![vec![42]],
+            predicate_data: vec![[42].into()],
             state_mutations: Default::default(),
         }]),
         index: 0,
+        this_program: None,
     };
     let ops = &[
         asm::Stack::Push(1).into(), // Slot index.
@@ -399,3 +414,33 @@ fn this_contract_address() {
     let expected_words = word_4_from_u8_32(TEST_PREDICATE_ADDR.contract.0);
     assert_eq!(&vm.stack[..], expected_words);
 }
+
+#[test]
+fn this_program_address() {
+    let ops = &[asm::Access::ThisProgramAddress.into()];
+    let op_gas_cost = &|_: &Op| 1;
+    let program = ContentAddress([0x42; 32]);
+    let access = test_access().clone().with_this_program(program.clone());
+    let mut vm = Vm::default();
+    vm.exec_ops(ops, access, &EmptyState, op_gas_cost, GasLimit::UNLIMITED)
+        .unwrap();
+    let expected_words = word_4_from_u8_32(program.0);
+    assert_eq!(&vm.stack[..], expected_words);
+}
+
+#[test]
+fn this_program_address_unavailable() {
+    let ops = &[asm::Access::ThisProgramAddress.into()];
+    let op_gas_cost = &|_: &Op| 1;
+    let res = Vm::default().exec_ops(
+        ops,
+        test_access().clone(),
+        &EmptyState,
+        op_gas_cost,
+        GasLimit::UNLIMITED,
+    );
+    match res {
+        Err(ExecError(_, OpError::Access(AccessError::ThisProgramAddressUnavailable))) => {}
+        _ => panic!("expected program address unavailable error, got {res:?}"),
+    }
+}
@@ -0,0 +1,196 @@
+//! A builder for configuring the VM along with its execution parameters.
+
+use crate::{
+    error::{EvalResult, ExecError, OpError},
+    Access, ExecHooks, Gas, GasLimit, GasSchedule, Memory, Op, OpAccess, OpGasCost, Repeat,
+    RepeatLimits, Stack, StateReads, Vm,
+};
+
+/// A builder for an [`ExecContext`], produced by [`Vm::builder`].
+///
+/// ```
+/// use essential_vm::{GasLimit, GasSchedule, Vm};
+///
+/// let ctx = Vm::builder()
+///     .stack_limit(1024)
+///     .memory_limit(2048)
+///     .max_ops(10_000)
+///     .gas_schedule(GasSchedule::default())
+///     .gas_limit(GasLimit::UNLIMITED)
+///     .build();
+/// assert_eq!(ctx.vm.stack.limit(), 1024);
+/// ```
+#[derive(Clone, Debug)]
+pub struct VmBuilder<G = GasSchedule, H = ()> {
+    stack_limit: usize,
+    memory_limit: usize,
+    repeat_limits: RepeatLimits,
+    max_ops: Option<u64>,
+    op_gas_cost: G,
+    gas_limit: GasLimit,
+    hooks: H,
+}
+
+/// A [`Vm`] along with the parameters with which it executes operations.
+///
+/// Produced by a [`VmBuilder`].
+#[derive(Clone, Debug)]
+pub struct ExecContext<G = GasSchedule, H = ()> {
+    /// The VM state.
+    pub vm: Vm,
+    /// The gas cost of each operation.
+    pub op_gas_cost: G,
+    /// The gas limit of each execution.
+    pub gas_limit: GasLimit,
+    /// The hooks invoked before and after each operation.
+    pub hooks: H,
+}
+
+impl Vm {
+    /// Start building a VM and the parameters with which it executes operations.
+    pub fn builder() -> VmBuilder {
+        VmBuilder::default()
+    }
+}
+
+impl Default for VmBuilder {
+    fn default() -> Self {
+        Self {
+            stack_limit: Stack::SIZE_LIMIT,
+            memory_limit: Memory::SIZE_LIMIT,
+            repeat_limits: RepeatLimits::default(),
+            max_ops: None,
+            op_gas_cost: GasSchedule::default(),
+            gas_limit: GasLimit::UNLIMITED,
+            hooks: (),
+        }
+    }
+}
+
+impl<G, H> VmBuilder<G, H> {
+    /// The stack size limit in words. Defaults to [`Stack::SIZE_LIMIT`].
+    pub fn stack_limit(mut self, limit: usize) -> Self {
+        self.stack_limit = limit;
+        self
+    }
+
+    /// The memory size limit in words. Defaults to [`Memory::SIZE_LIMIT`].
+    pub fn memory_limit(mut self, limit: usize) -> Self {
+        self.memory_limit = limit;
+        self
+    }
+
+    /// The limits on the nesting of repeats. Defaults to [`RepeatLimits::default`].
+    pub fn repeat_limits(mut self, limits: RepeatLimits) -> Self {
+        self.repeat_limits = limits;
+        self
+    }
+
+    /// The maximum number of operations that may be executed. See [`Vm::max_ops`].
+    pub fn max_ops(mut self, max_ops: u64) -> Self {
+        self.max_ops = Some(max_ops);
+        self
+    }
+
+    /// The gas limit of each execution. Defaults to [`GasLimit::UNLIMITED`].
+    pub fn gas_limit(mut self, gas_limit: GasLimit) -> Self {
+        self.gas_limit = gas_limit;
+        self
+    }
+
+    /// The gas cost of each operation. Defaults to the [`GasSchedule::default`].
+    pub fn gas_schedule<G2: OpGasCost>(self, op_gas_cost: G2) -> VmBuilder<G2, H> {
+        VmBuilder {
+            stack_limit: self.stack_limit,
+            memory_limit: self.memory_limit,
+            repeat_limits: self.repeat_limits,
+            max_ops: self.max_ops,
+            op_gas_cost,
+            gas_limit: self.gas_limit,
+            hooks: self.hooks,
+        }
+    }
+
+    /// The hooks invoked before and after each operation. Defaults to none.
+    pub fn hooks<H2: ExecHooks>(self, hooks: H2) -> VmBuilder<G, H2> {
+        VmBuilder {
+            stack_limit: self.stack_limit,
+            memory_limit: self.memory_limit,
+            repeat_limits: self.repeat_limits,
+            max_ops: self.max_ops,
+            op_gas_cost: self.op_gas_cost,
+            gas_limit: self.gas_limit,
+            hooks,
+        }
+    }
+
+    /// Build the execution context with a new VM.
+    pub fn build(self) -> ExecContext<G, H> {
+        let vm = Vm {
+            stack: Stack::with_limit(self.stack_limit),
+            memory: Memory::with_limit(self.memory_limit),
+            repeat: Repeat::with_limits(self.repeat_limits),
+            max_ops: self.max_ops,
+            ..Default::default()
+        };
+        ExecContext {
+            vm,
+            op_gas_cost: self.op_gas_cost,
+            gas_limit: self.gas_limit,
+            hooks: self.hooks,
+        }
+    }
+}
+
+impl<G, H> ExecContext<G, H>
+where
+    G: OpGasCost,
+    H: ExecHooks,
+{
+    /// Execute the given operations from the current state of the VM.
+    ///
+    /// See [`Vm::exec_with_hooks`].
+    pub fn exec<S, OA>(
+        &mut self,
+        access: Access,
+        state_reads: &S,
+        op_access: OA,
+    ) -> Result<Gas, ExecError<S::Error>>
+    where
+        S: StateReads,
+        OA: OpAccess<Op = Op>,
+        OA::Error: Into<OpError<S::Error>>,
+    {
+        self.vm.exec_with_hooks(
+            access,
+            state_reads,
+            op_access,
+            &self.op_gas_cost,
+            self.gas_limit,
+            &mut self.hooks,
+        )
+    }
+
+    /// Execute the given operations and return their boolean result.
+    ///
+    /// See [`Vm::eval`]. Hooks are not invoked.
+    pub fn eval<S, OA>(
+        &mut self,
+        access: Access,
+        state_reads: &S,
+        op_access: OA,
+    ) -> EvalResult<bool, S::Error>
+    where
+        S: StateReads,
+        OA: OpAccess<Op = Op>,
+        OA::Error: Into<OpError<S::Error>>,
+    {
+        self.vm.eval(
+            op_access,
+            access,
+            state_reads,
+            &self.op_gas_cost,
+            self.gas_limit,
+        )
+    }
+}
@@ -1,6 +1,7 @@
 //! Items related to bytecode representation for the VM assembly.
 
 use crate::asm::{opcode::ParseOp, ToBytes, ToOpcode, TryFromBytes};
+use alloc::{sync::Arc, vec::Vec};
 
 /// A memory efficient representation of a sequence of operations parsed from bytecode.
 ///
@@ -65,7 +66,7 @@ where
         Op::Error: From<<Op::Opcode as TryFrom<u8>>::Error> + From<<Op::Opcode as ParseOp>::Error>,
     {
         let bytecode = bytes.deref();
-        let mut op_indices = Vec::with_capacity(bytecode.len() / std::mem::size_of::<Op>());
+        let mut op_indices = Vec::with_capacity(bytecode.len() / core::mem::size_of::<Op>());
         let mut iter_enum = bytecode.iter().enumerate();
         while let Some((ix, &opcode_byte)) = iter_enum.next() {
             let opcode = Op::Opcode::try_from(opcode_byte)?;
@@ -81,7 +82,7 @@ where
     }
 
     /// Borrow the inner bytecode and op_indices slices and return a [`BytecodeMappedSlice`].
-    pub fn as_slice(&self) -> BytecodeMappedSlice<Op> {
+    pub fn as_slice(&self) -> BytecodeMappedSlice<'_, Op> {
         BytecodeMappedSlice {
             bytecode: self.bytecode(),
             op_indices: self.op_indices(),
@@ -99,7 +100,7 @@ where
     /// The returned slice represents the remainder of the program from the given op.
     ///
     /// Returns `None` if `start` is out of range of the `op_indices` slice.
-    pub fn ops_from(&self, start: usize) -> Option<BytecodeMappedSlice<Op>> {
+    pub fn ops_from(&self, start: usize) -> Option<BytecodeMappedSlice<'_, Op>> {
         Some(BytecodeMappedSlice {
             bytecode: self.bytecode(),
             op_indices: self.op_indices.get(start..)?,
@@ -203,6 +204,20 @@ where
     }
 }
 
+/// Allow for consuming and mapping shared bytecode, e.g. to share one mapping
+/// between threads.
+impl<Op> TryFrom<Arc<[u8]>> for BytecodeMapped<Op, Arc<[u8]>>
+where
+    Op: ToOpcode + TryFromBytes,
+    Op::Opcode: ParseOp<Op = Op> + TryFrom<u8>,
+    Op::Error: From<<Op::Opcode as TryFrom<u8>>::Error> + From<<Op::Opcode as ParseOp>::Error>,
+{
+    type Error = Op::Error;
+    fn try_from(bytecode: Arc<[u8]>) -> Result<Self, Self::Error> {
+        Self::try_from_bytes(bytecode)
+    }
+}
+
 /// Given a bytecode slice and an operation mapping that is assumed to have been
 /// previously validated, produce an iterator yielding all associated operations.
 fn expect_ops_from_indices<'a, Op>(
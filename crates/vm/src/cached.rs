@@ -1,18 +1,39 @@
 use crate::access::init_predicate_exists;
-use essential_types::{solution::Solution, Hash};
-use std::{
-    collections::HashSet,
-    sync::{Arc, OnceLock},
-};
+use alloc::{sync::Arc, vec::Vec};
+use essential_types::{solution::Solution, Hash, Key};
+
+#[cfg(feature = "std")]
+use std::{collections::HashSet, sync::OnceLock};
+
+// Without `std`, fall back to the single-threaded `core`/`alloc` equivalents.
+#[cfg(not(feature = "std"))]
+type HashSet<T> = alloc::collections::BTreeSet<T>;
+#[cfg(not(feature = "std"))]
+type OnceLock<T> = core::cell::OnceCell<T>;
 
 #[derive(Default, Debug, PartialEq)]
 /// Lazily cache expensive to compute values.
+///
+/// Values are derived from the solutions of a single solution set. A cache may
+/// be shared between any number of VMs executing against the same solution
+/// set, e.g. all programs checked for a set, so that each value is computed at
+/// most once.
 pub struct LazyCache {
     /// Predicate data and addresses set of hashes.
     /// See [`PredicateExists`][essential_asm] for more details.
     pub pred_data_hashes: OnceLock<HashSet<Hash>>,
+    /// The set of keys mutated by each solution, indexed by solution index.
+    pub mut_keys: OnceLock<MutKeysIndex>,
 }
 
+/// The set of keys mutated by each solution within a solution set, indexed by
+/// solution index.
+///
+/// The solution set is immutable during checking, so the index is built once
+/// per set, e.g. via [`LazyCache::get_mut_keys`], and shared by all programs.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MutKeysIndex(Vec<HashSet<Key>>);
+
 impl LazyCache {
     /// Create a new empty `LazyCache`.
     pub fn new() -> Self {
@@ -26,4 +47,76 @@ impl LazyCache {
         self.pred_data_hashes
             .get_or_init(|| init_predicate_exists(solutions).into_iter().collect())
     }
+
+    /// Get the set of keys mutated by each solution, indexed by solution index.
+    ///
+    /// The first time this is called, it will collect the keys.
+    pub fn get_mut_keys(&self, solutions: &[Solution]) -> &MutKeysIndex {
+        self.mut_keys.get_or_init(|| MutKeysIndex::new(solutions))
+    }
+}
+
+impl MutKeysIndex {
+    /// Collect the keys mutated by each of the given solutions.
+    pub fn new(solutions: &[Solution]) -> Self {
+        Self(
+            solutions
+                .iter()
+                .map(|s| s.state_mutations.iter().map(|m| m.key.clone()).collect())
+                .collect(),
+        )
+    }
+
+    /// Whether the solution at the given index mutates the given key.
+    ///
+    /// Returns `false` if the solution index is out of range.
+    pub fn contains(&self, solution_index: usize, key: &[essential_types::Word]) -> bool {
+        self.0
+            .get(solution_index)
+            .is_some_and(|keys| keys.contains(key))
+    }
+}
+
+impl core::ops::Deref for MutKeysIndex {
+    type Target = [HashSet<Key>];
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use essential_types::{solution::Mutation, ContentAddress, PredicateAddress};
+
+    #[test]
+    fn mut_keys() {
+        let solution = |keys: &[&[i64]]| Solution {
+            predicate_to_solve: PredicateAddress {
+                contract: ContentAddress([0; 32]),
+                predicate: ContentAddress([0; 32]),
+            },
+            predicate_data: vec![],
+            state_mutations: keys
+                .iter()
+                .map(|k| Mutation {
+                    key: Key::try_from(*k).unwrap(),
+                    value: [1].into(),
+                })
+                .collect(),
+        };
+        let solutions = vec![solution(&[&[1], &[2, 3]]), solution(&[])];
+        let cache = LazyCache::new();
+        let mut_keys = cache.get_mut_keys(&solutions);
+        assert_eq!(mut_keys.len(), 2);
+        assert!(mut_keys[0].contains(&Key::from([2, 3])));
+        assert!(mut_keys[1].is_empty());
+
+        assert!(mut_keys.contains(0, &[1]));
+        assert!(!mut_keys.contains(1, &[1]));
+        assert!(!mut_keys.contains(2, &[1]));
+
+        // Subsequent calls return the cached keys.
+        assert_eq!(cache.get_mut_keys(&[]).len(), 2);
+    }
 }
@@ -3,12 +3,17 @@ use crate::{
     Access, Gas, GasLimit, LazyCache, Memory, Op, OpAccess, OpGasCost, Repeat, Stack, StateReads,
     Vm,
 };
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+#[cfg(feature = "std")]
 use rayon::prelude::*;
-use std::sync::Arc;
 
 /// The limit on compute recursion depth.
 pub const MAX_COMPUTE_DEPTH: usize = 1;
 
+/// The gas spent, program counter, memory, halt flag and number of operations
+/// executed resulting from a compute program.
+type ComputeOutput = (Gas, usize, Memory, bool, u64);
+
 /// Inputs for the compute operation execution.
 pub struct ComputeInputs<'a, S, OA, OG> {
     /// Parent VM program counter.
@@ -27,6 +32,10 @@ pub struct ComputeInputs<'a, S, OA, OG> {
     pub repeat: &'a Repeat,
     /// Lazily cached data.
     pub cache: Arc<LazyCache>,
+    /// Parent VM operation count. Incremented by the operations executed by compute programs.
+    pub ops_executed: &'a mut u64,
+    /// Parent VM operation limit.
+    pub max_ops: Option<u64>,
     /// [`Access`] required for VM execution. Cloned for compute programs.
     pub access: Access,
     /// [`StateReads`] for VM execution.
@@ -41,7 +50,8 @@ pub struct ComputeInputs<'a, S, OA, OG> {
 
 /// The Compute op implementation.
 ///
-/// Computes as many programs as is the input to this op in parallel.
+/// Computes as many programs as is the input to this op in parallel (or
+/// sequentially when the `std` feature is disabled).
 /// Each compute program executes on a constructed VM that has
 /// - Read-only access to parent VM memory
 /// - Parent VM stack with an additional value on top that is the compute index
@@ -70,6 +80,8 @@ where
         halt,
         repeat,
         cache,
+        ops_executed,
+        max_ops,
         access,
         state_reads,
         op_access,
@@ -89,14 +101,23 @@ where
 
     // Append parent memory to be read by spawned threads.
     if parent_memory.len() < MAX_COMPUTE_DEPTH {
-        parent_memory.push(Arc::new(memory.to_owned()));
+        parent_memory.push(Arc::new(memory.clone()));
     } else {
         return Err(ComputeError::DepthReached(MAX_COMPUTE_DEPTH).into());
     }
 
-    // Compute in parallel.
-    let results: Result<Vec<(Gas, usize, Memory, bool)>, _> = (0..compute_breadth)
-        .into_par_iter()
+    // Compute programs share the memory size limit of their parent.
+    let memory_limit = memory.limit();
+
+    // Compute in parallel, or sequentially without `std`.
+    #[cfg(feature = "std")]
+    let compute_indices = (0..compute_breadth).into_par_iter();
+    #[cfg(not(feature = "std"))]
+    let compute_indices = 0..compute_breadth;
+    // Compute programs are limited to the operations remaining.
+    let child_max_ops = max_ops.map(|max| max.saturating_sub(*ops_executed));
+
+    let results: Result<Vec<ComputeOutput>, _> = compute_indices
         .map(|compute_index| {
             // Clone stack and push compute program index.
             let mut stack = stack.clone();
@@ -107,10 +128,11 @@ where
             let mut vm = Vm {
                 pc: pc + 1,
                 stack,
-                memory: Memory::new(),
+                memory: Memory::with_limit(memory_limit),
                 parent_memory: parent_memory.clone(),
                 repeat: repeat.clone(),
                 cache: cache.clone(),
+                max_ops: child_max_ops,
                 ..Default::default()
             };
 
@@ -122,12 +144,22 @@ where
                 op_gas_cost,
                 gas_limit,
             )
-            .map(|gas| (gas, vm.pc, vm.memory, vm.halt))
+            .map(|gas| (gas, vm.pc, vm.memory, vm.halt, vm.ops_executed))
         })
         .collect();
 
     let oks = results.map_err(|e| OpError::Compute(ComputeError::Exec(Box::new(e))))?;
 
+    // Account for the operations executed by the compute programs.
+    for (_, _, _, _, ops) in &oks {
+        *ops_executed = ops_executed.saturating_add(*ops);
+    }
+    if let Some(max) = max_ops {
+        if *ops_executed > max {
+            return Err(OpError::OpLimit(max));
+        }
+    }
+
     // Process compute program results.
     let (pc, total_gas, halt) = compute_effects(memory, pc, halt, oks)?;
 
@@ -144,21 +176,21 @@ fn compute_effects(
     memory: &mut Memory,
     mut pc: usize,
     mut halt: bool,
-    compute_results: Vec<(Gas, usize, Memory, bool)>,
+    compute_results: Vec<ComputeOutput>,
 ) -> Result<(usize, Gas, bool), MemoryError> {
     let mut total_gas = 0;
 
     let mut memory_to_alloc = 0;
     compute_results
         .iter()
-        .for_each(|(_, _, mem, _)| memory_to_alloc += mem.len().unwrap_or_default());
+        .for_each(|(_, _, mem, _, _)| memory_to_alloc += mem.len().unwrap_or_default());
     // moving pointer to index in parent memory to store new values at
     let mut memory_pointer = memory.len().expect("memory has to have length");
     // allocate enough space in the parent memory at once
     memory.alloc(memory_to_alloc)?;
     // concat compute memories to parent memory one by one
-    compute_results.iter().for_each(|(gas, c_pc, mem, h)| {
-        pc = std::cmp::max(pc, *c_pc);
+    compute_results.iter().for_each(|(gas, c_pc, mem, h, _)| {
+        pc = core::cmp::max(pc, *c_pc);
         total_gas += gas;
         memory.store_range(memory_pointer, mem).expect("for now");
         memory_pointer += mem.len().unwrap();
@@ -0,0 +1,130 @@
+//! Recording of the operations and branches executed by a program.
+
+use crate::asm::{Op, TotalControlFlow};
+use alloc::vec::Vec;
+
+/// The operations and branches executed by a program.
+///
+/// Produced by [`Vm::exec_with_coverage`][crate::Vm::exec_with_coverage].
+/// Coverage may be accumulated across multiple executions, e.g. across all
+/// test solutions for a predicate, to find the operations no solution reaches.
+///
+/// For the conditional operations `JumpIf`, `HaltIf` and `PanicIf`, both
+/// outcomes of the condition are recorded separately.
+///
+/// The operations executed by the compute programs of a `Compute` operation
+/// are not recorded.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Coverage {
+    /// The program counters executed.
+    executed: Bitmap,
+    /// The program counters of conditional operations executed with a true condition.
+    taken: Bitmap,
+    /// The program counters of conditional operations executed with a false condition.
+    not_taken: Bitmap,
+}
+
+/// A growable set of indices.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Bitmap(Vec<u64>);
+
+impl Coverage {
+    /// Record the execution of the operation at the given program counter.
+    pub fn record(&mut self, pc: usize) {
+        self.executed.insert(pc);
+    }
+
+    /// Record the outcome of the conditional operation at the given program counter.
+    pub fn record_branch(&mut self, pc: usize, condition: bool) {
+        match condition {
+            true => self.taken.insert(pc),
+            false => self.not_taken.insert(pc),
+        }
+    }
+
+    /// Whether the operation at the given program counter was executed.
+    pub fn executed(&self, pc: usize) -> bool {
+        self.executed.contains(pc)
+    }
+
+    /// Whether the conditional operation at the given program counter was
+    /// executed with a true condition.
+    pub fn taken(&self, pc: usize) -> bool {
+        self.taken.contains(pc)
+    }
+
+    /// Whether the conditional operation at the given program counter was
+    /// executed with a false condition.
+    pub fn not_taken(&self, pc: usize) -> bool {
+        self.not_taken.contains(pc)
+    }
+
+    /// Accumulate another coverage into this one.
+    pub fn merge(&mut self, other: &Self) {
+        self.executed.union(&other.executed);
+        self.taken.union(&other.taken);
+        self.not_taken.union(&other.not_taken);
+    }
+
+    /// The program counters of the given operations that were never executed.
+    pub fn unexecuted(&self, ops: &[Op]) -> Vec<usize> {
+        (0..ops.len()).filter(|&pc| !self.executed(pc)).collect()
+    }
+
+    /// The program counters of the given conditional operations for which
+    /// only one outcome of the condition was observed.
+    pub fn partial_branches(&self, ops: &[Op]) -> Vec<usize> {
+        ops.iter()
+            .enumerate()
+            .filter(|(pc, op)| is_conditional(op) && self.taken(*pc) != self.not_taken(*pc))
+            .map(|(pc, _)| pc)
+            .collect()
+    }
+
+    /// The proportion of the given operations executed, from `0.0` to `1.0`.
+    ///
+    /// Returns `1.0` if there are no operations.
+    pub fn ratio(&self, ops: &[Op]) -> f64 {
+        if ops.is_empty() {
+            return 1.0;
+        }
+        let executed = (0..ops.len()).filter(|&pc| self.executed(pc)).count();
+        executed as f64 / ops.len() as f64
+    }
+}
+
+/// Whether the operation is a conditional operation whose outcomes are recorded.
+pub(crate) fn is_conditional(op: &Op) -> bool {
+    matches!(
+        op,
+        Op::TotalControlFlow(
+            TotalControlFlow::JumpIf | TotalControlFlow::HaltIf | TotalControlFlow::PanicIf
+        )
+    )
+}
+
+impl Bitmap {
+    fn insert(&mut self, ix: usize) {
+        let (word, bit) = (ix / 64, ix % 64);
+        if self.0.len() <= word {
+            self.0.resize(word + 1, 0);
+        }
+        self.0[word] |= 1 << bit;
+    }
+
+    fn contains(&self, ix: usize) -> bool {
+        self.0
+            .get(ix / 64)
+            .is_some_and(|word| word & (1 << (ix % 64)) != 0)
+    }
+
+    fn union(&mut self, other: &Self) {
+        if self.0.len() < other.0.len() {
+            self.0.resize(other.0.len(), 0);
+        }
+        self.0
+            .iter_mut()
+            .zip(&other.0)
+            .for_each(|(word, other)| *word |= other);
+    }
+}
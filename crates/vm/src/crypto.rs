@@ -5,6 +5,7 @@ use crate::{
     error::{CryptoError, OpResult, StackError},
     Stack,
 };
+use alloc::vec::Vec;
 use essential_types::convert::{
     bytes_from_word, u8_32_from_word_4, u8_64_from_word_8, word_4_from_u8_32, word_from_bytes,
 };
@@ -49,6 +50,40 @@ pub(crate) fn verify_ed25519(stack: &mut Stack) -> OpResult<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+/// `Crypto::VerifyEd25519Batch` implementation.
+pub(crate) fn verify_ed25519_batch(stack: &mut Stack) -> OpResult<()> {
+    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+    let n = stack.pop()?;
+    let n = usize::try_from(n).map_err(|_| CryptoError::InvalidBatchLen(n))?;
+
+    // Each signature occupies at least 13 words, so bound the capacity by the stack.
+    let cap = n.min(stack.len() / 13);
+    let mut batch = Vec::with_capacity(cap);
+    for _ in 0..n {
+        let pubkey_words = stack.pop4()?;
+        let signature_words = stack.pop8()?;
+        let data = pop_bytes(stack)?;
+
+        let pubkey_bytes = u8_32_from_word_4(pubkey_words);
+        let pubkey = VerifyingKey::from_bytes(&pubkey_bytes).map_err(CryptoError::Ed25519)?;
+        let signature_bytes = u8_64_from_word_8(signature_words);
+        batch.push((data, Signature::from_bytes(&signature_bytes), pubkey));
+    }
+
+    // Each signature is verified exactly as by `VerifyEd25519`, so that a
+    // signature is valid within a batch if and only if it is valid alone.
+    // `ed25519_dalek::verify_batch` can't be used as its equation disagrees
+    // with single verification for points with small order components, and
+    // ruling those out costs a scalar multiplication per point.
+    let valid = batch
+        .iter()
+        .all(|(data, signature, pubkey)| pubkey.verify(data, signature).is_ok());
+    let word = Word::from(valid);
+    stack.push(word)?;
+    Ok(())
+}
+
 #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
 pub(crate) fn recover_secp256k1(stack: &mut Stack) -> OpResult<()> {
     use secp256k1::{
@@ -104,6 +139,124 @@ pub(crate) fn recover_secp256k1(stack: &mut Stack) -> OpResult<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+/// `Crypto::VerifySecp256k1` implementation.
+pub(crate) fn verify_secp256k1(stack: &mut Stack) -> OpResult<()> {
+    use secp256k1::{ecdsa::Signature, Message, PublicKey, Secp256k1};
+
+    // Pop the stack.
+    let end_word = stack.pop()?;
+    let public_key_words = stack.pop4()?;
+    let signature_words = stack.pop8()?;
+    let message_hash = stack.pop4()?;
+
+    // Parse the public key.
+    // Note the public key is 33 bytes long.
+    let mut public_key_bytes = [0u8; 33];
+    public_key_bytes[..32].copy_from_slice(&u8_32_from_word_4(public_key_words));
+    public_key_bytes[32] = bytes_from_word(end_word)[7];
+    let public_key = PublicKey::from_slice(&public_key_bytes).map_err(CryptoError::Secp256k1)?;
+
+    // Parse the signature, normalizing to match recovery.
+    let signature_bytes = u8_64_from_word_8(signature_words);
+    let mut signature =
+        Signature::from_compact(&signature_bytes).map_err(CryptoError::Secp256k1)?;
+    signature.normalize_s();
+
+    #[cfg(feature = "tracing")]
+    tracing::trace!("{:?}, {:?}", signature, public_key);
+
+    // Parse the message hash.
+    let message_hash = u8_32_from_word_4(message_hash);
+    let message = Message::from_digest(message_hash);
+
+    let secp = Secp256k1::verification_only();
+    let valid = secp.verify_ecdsa(&message, &signature, &public_key).is_ok();
+    let word = Word::from(valid);
+    stack.push(word)?;
+    Ok(())
+}
+
+/// The domain separation tag of contract addresses.
+///
+/// Must match `essential_hash::domain::CONTRACT`.
+const CONTRACT_DOMAIN_TAG: &[u8] = b"essential/contract/v1";
+
+/// `Crypto::ContractAddress` implementation.
+pub(crate) fn contract_address(stack: &mut Stack) -> OpResult<()> {
+    use sha2::Digest;
+
+    let n = stack.pop()?;
+    let num_words = usize::try_from(n)
+        .ok()
+        .and_then(|n| n.checked_mul(4))
+        .ok_or(CryptoError::InvalidPredicateAddrsLen(n))?;
+    let mut predicate_addrs: Vec<[u8; 32]> =
+        stack.pop_words::<_, _, StackError>(num_words, |words| {
+            Ok(words
+                .chunks_exact(4)
+                .map(|w| u8_32_from_word_4([w[0], w[1], w[2], w[3]]))
+                .collect())
+        })?;
+    let salt = u8_32_from_word_4(stack.pop4()?);
+
+    // Predicate addresses are sorted so that their order does not matter.
+    predicate_addrs.sort();
+    let domain_prefix: [u8; 32] = sha2::Sha256::digest(CONTRACT_DOMAIN_TAG).into();
+    let mut hasher = sha2::Sha256::new();
+    hasher.update(domain_prefix);
+    predicate_addrs.iter().for_each(|addr| hasher.update(addr));
+    hasher.update(salt);
+    let addr_bytes: [u8; 32] = hasher.finalize().into();
+    stack.extend(word_4_from_u8_32(addr_bytes))?;
+    Ok(())
+}
+
+/// The maximum number of siblings in a Merkle proof, such that every leaf
+/// index fits in a word.
+const MERKLE_MAX_DEPTH: usize = 63;
+
+/// `Crypto::VerifyMerkle` implementation.
+pub(crate) fn verify_merkle(stack: &mut Stack) -> OpResult<()> {
+    use sha2::Digest;
+
+    let n = stack.pop()?;
+    let depth = usize::try_from(n)
+        .ok()
+        .filter(|&depth| depth <= MERKLE_MAX_DEPTH)
+        .ok_or(CryptoError::InvalidMerkleProofLen(n))?;
+    let siblings: Vec<[u8; 32]> = stack.pop_words::<_, _, StackError>(depth * 4, |words| {
+        Ok(words
+            .chunks_exact(4)
+            .map(|w| u8_32_from_word_4([w[0], w[1], w[2], w[3]]))
+            .collect())
+    })?;
+    let index = stack.pop()?;
+    if index < 0 || index >> depth != 0 {
+        return Err(CryptoError::InvalidMerkleIndex(index).into());
+    }
+    let leaf = u8_32_from_word_4(stack.pop4()?);
+    let root = u8_32_from_word_4(stack.pop4()?);
+
+    // Each parent is the hash of the node prefix followed by its children.
+    let computed = siblings
+        .iter()
+        .enumerate()
+        .fold(leaf, |hash, (height, sibling)| {
+            let (left, right) = match index >> height & 1 {
+                0 => (&hash, sibling),
+                _ => (sibling, &hash),
+            };
+            let mut hasher = sha2::Sha256::new();
+            hasher.update([1]);
+            hasher.update(left);
+            hasher.update(right);
+            hasher.finalize().into()
+        });
+    stack.push(Word::from(computed == root))?;
+    Ok(())
+}
+
 /// Pop a length in bytes and that number of bytes from the stack.
 ///
 /// Note that this will pop the words `ceil(bytes_len / 8)` from the stack.
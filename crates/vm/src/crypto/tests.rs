@@ -1,7 +1,9 @@
 use super::pop_bytes;
 use crate::{
     asm::{Crypto, Stack, Word},
-    crypto::{bytes_from_words, recover_secp256k1},
+    crypto::{
+        bytes_from_words, contract_address, recover_secp256k1, verify_merkle, verify_secp256k1,
+    },
     error::{CryptoError, ExecError, OpError},
     sync::test_util::*,
     types::{
@@ -229,6 +231,98 @@ fn ed25519_error() {
     }
 }
 
+// Generate the ops for verifying a batch of the given signatures.
+fn test_ed25519_batch_ops(num_bytes: &[usize]) -> Vec<Op> {
+    let mut ops: Vec<Op> = num_bytes
+        .iter()
+        .flat_map(|&n| {
+            let mut ops = test_ed25519_ops(n);
+            // Remove the `VerifyEd25519` op.
+            ops.pop();
+            ops
+        })
+        .collect();
+    ops.push(Stack::Push(Word::try_from(num_bytes.len()).unwrap()).into());
+    ops.push(Crypto::VerifyEd25519Batch.into());
+    ops
+}
+
+fn eval_ops_ed25519(ops: &[Op]) -> Result<bool, ExecError<String>> {
+    let op_gas_cost = &|_: &Op| 1;
+    let mut vm = Vm::default();
+    vm.exec_ops(
+        ops,
+        test_access().clone(),
+        &EmptyState,
+        op_gas_cost,
+        GasLimit::UNLIMITED,
+    )?;
+    assert_eq!(vm.stack.len(), 1);
+    Ok(vm.stack[0] == 1)
+}
+
+#[test]
+fn verify_ed25519_batch() {
+    let ops = test_ed25519_batch_ops(&[8 * 4, 8 * 3 + 2, 8 * 4]);
+    crate::verify(&ops, 0).unwrap();
+    assert!(eval_ops_ed25519(&ops).unwrap());
+
+    // An empty batch is valid.
+    assert!(eval_ops_ed25519(&test_ed25519_batch_ops(&[])).unwrap());
+
+    // Invalidate the data of the second signature, each taking 17 ops.
+    let mut ops = test_ed25519_batch_ops(&[8 * 4, 8 * 4]);
+    ops[17] = Stack::Push(0).into();
+    assert!(!eval_ops_ed25519(&ops).unwrap());
+}
+
+#[test]
+fn verify_ed25519_batch_matches_single() {
+    // A small order public key and `R` with `s = 0`, which verification
+    // equations may disagree on.
+    let mut identity = [0u8; 32];
+    identity[0] = 1;
+    let mut signature = [0u8; 64];
+    signature[..32].copy_from_slice(&identity);
+    let data: Word = 42;
+    let ops = |op: Crypto| -> Vec<Op> {
+        let mut ops: Vec<Op> = [data, 8]
+            .into_iter()
+            .chain(word_8_from_u8_64(signature))
+            .chain(word_4_from_u8_32(identity))
+            .map(|w| Stack::Push(w).into())
+            .collect();
+        if op == Crypto::VerifyEd25519Batch {
+            ops.push(Stack::Push(1).into());
+        }
+        ops.push(op.into());
+        ops
+    };
+    assert_eq!(
+        eval_ops_ed25519(&ops(Crypto::VerifyEd25519)).unwrap(),
+        eval_ops_ed25519(&ops(Crypto::VerifyEd25519Batch)).unwrap(),
+    );
+}
+
+#[test]
+fn verify_ed25519_batch_errors() {
+    let ops = [Stack::Push(-1).into(), Crypto::VerifyEd25519Batch.into()];
+    match eval_ops_ed25519(&ops) {
+        Err(ExecError(1, OpError::Crypto(CryptoError::InvalidBatchLen(-1)))) => (),
+        res => panic!("expected invalid batch len, got {res:?}"),
+    }
+
+    // More signatures than are on the stack.
+    let mut ops = test_ed25519_batch_ops(&[8 * 4]);
+    let n = ops.len() - 2;
+    ops[n] = Stack::Push(2).into();
+    assert!(matches!(
+        eval_ops_ed25519(&ops),
+        Err(ExecError(_, OpError::Stack(_)))
+    ));
+    assert!(crate::verify(&ops, 0).is_err());
+}
+
 #[test]
 fn test_secp256k1() {
     use rand::SeedableRng;
@@ -278,6 +372,141 @@ fn test_secp256k1() {
     assert_eq!(result, [0u8; 33]);
 }
 
+#[test]
+fn test_verify_secp256k1() {
+    use rand::SeedableRng;
+    use secp256k1::{Message, Secp256k1};
+
+    let secp = Secp256k1::new();
+    let mut rng = rand::rngs::SmallRng::from_seed([0x00; 32]);
+    let (secret_key, public_key) = secp.generate_keypair(&mut rng);
+    let (_, other_key) = secp.generate_keypair(&mut rng);
+
+    let message = Message::from_digest([0; 32]);
+    let sig_bytes = secp.sign_ecdsa(&message, &secret_key).serialize_compact();
+
+    let check = |sig_bytes, message: [u8; 32], public_key: secp256k1::PublicKey| {
+        let [public_key @ .., end] = public_key.serialize();
+        let mut end_word = [0u8; 8];
+        end_word[7] = end;
+        let mut stack = crate::Stack::default();
+        stack.extend(word_4_from_u8_32(message)).unwrap();
+        stack.extend(word_8_from_u8_64(sig_bytes)).unwrap();
+        stack.extend(word_4_from_u8_32(public_key)).unwrap();
+        stack.push(word_from_bytes_slice(&end_word)).unwrap();
+        verify_secp256k1(&mut stack)?;
+        assert_eq!(stack.len(), 1);
+        Ok::<_, OpError>(stack.pop().unwrap() == 1)
+    };
+
+    assert!(check(sig_bytes, *message.as_ref(), public_key).unwrap());
+    // Wrong message.
+    assert!(!check(sig_bytes, [1; 32], public_key).unwrap());
+    // Wrong public key.
+    assert!(!check(sig_bytes, *message.as_ref(), other_key).unwrap());
+    // Invalid signature encoding.
+    assert!(matches!(
+        check([0xFF; 64], *message.as_ref(), public_key),
+        Err(OpError::Crypto(CryptoError::Secp256k1(_)))
+    ));
+
+    // An invalid public key.
+    let mut stack = crate::Stack::default();
+    stack.extend([0; 4 + 8 + 5]).unwrap();
+    assert!(matches!(
+        verify_secp256k1(&mut stack),
+        Err(OpError::Crypto(CryptoError::Secp256k1(_)))
+    ));
+}
+
+#[test]
+fn test_contract_address() {
+    use essential_types::ContentAddress;
+
+    let salt = [9; 32];
+    let addrs = [
+        ContentAddress([3; 32]),
+        ContentAddress([1; 32]),
+        ContentAddress([2; 32]),
+    ];
+    let check = |addrs: &[ContentAddress]| {
+        let mut stack = crate::Stack::default();
+        stack.extend(word_4_from_u8_32(salt)).unwrap();
+        for addr in addrs {
+            stack.extend(word_4_from_u8_32(addr.0)).unwrap();
+        }
+        stack.push(Word::try_from(addrs.len()).unwrap()).unwrap();
+        contract_address(&mut stack).unwrap();
+        assert_eq!(stack.len(), 4);
+        ContentAddress(u8_32_from_word_4(stack.pop4().unwrap()))
+    };
+
+    // Matches the derivation outside of the VM, regardless of order.
+    let expected = essential_hash::contract_addr::from_predicate_addrs(addrs.clone(), &salt);
+    assert_eq!(check(&addrs), expected);
+    let mut sorted = addrs.clone();
+    sorted.sort();
+    assert_eq!(check(&sorted), expected);
+    assert_eq!(
+        check(&[]),
+        essential_hash::contract_addr::from_predicate_addrs([], &salt)
+    );
+
+    // A negative number of addresses.
+    let mut stack = crate::Stack::default();
+    stack.extend([0, 0, 0, 0, -1]).unwrap();
+    assert!(matches!(
+        contract_address(&mut stack),
+        Err(OpError::Crypto(CryptoError::InvalidPredicateAddrsLen(-1)))
+    ));
+}
+
+#[test]
+fn test_verify_merkle() {
+    use essential_hash::merkle::{self, MerkleTree};
+
+    let values: Vec<Vec<Word>> = (0..5).map(|i| vec![i; 3]).collect();
+    let tree = MerkleTree::from_values(&values);
+    let check = |root: Hash, leaf: Hash, proof: &merkle::MerkleProof| {
+        let mut stack = crate::Stack::default();
+        stack.extend(word_4_from_u8_32(root)).unwrap();
+        stack.extend(word_4_from_u8_32(leaf)).unwrap();
+        stack.extend(proof.to_words()).unwrap();
+        verify_merkle(&mut stack).map(|()| {
+            assert_eq!(stack.len(), 1);
+            stack.pop().unwrap()
+        })
+    };
+
+    // Matches the verification outside of the VM.
+    for (ix, value) in values.iter().enumerate() {
+        let proof = tree.proof(ix).unwrap();
+        let leaf = merkle::leaf(value);
+        assert_eq!(check(tree.root(), leaf, &proof).unwrap(), 1);
+        assert_eq!(check(tree.root(), [0; 32], &proof).unwrap(), 0);
+        assert_eq!(check([0; 32], leaf, &proof).unwrap(), 0);
+    }
+
+    // An index out of range of the proof's depth.
+    let mut proof = tree.proof(0).unwrap();
+    proof.index = 8;
+    assert!(matches!(
+        check(tree.root(), [0; 32], &proof),
+        Err(OpError::Crypto(CryptoError::InvalidMerkleIndex(8)))
+    ));
+
+    // An invalid number of siblings.
+    for n in [-1, 64] {
+        let mut stack = crate::Stack::default();
+        stack.extend([0; 9]).unwrap();
+        stack.push(n).unwrap();
+        assert!(matches!(
+            verify_merkle(&mut stack),
+            Err(OpError::Crypto(CryptoError::InvalidMerkleProofLen(len))) if len == n
+        ));
+    }
+}
+
 #[test]
 fn test_pop_bytes() {
     let mut stack = crate::Stack::default();
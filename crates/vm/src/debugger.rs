@@ -0,0 +1,192 @@
+//! A step debugger for driving VM execution interactively.
+//!
+//! The [`Debugger`] wraps a [`Vm`] along with everything required to execute a
+//! program, allowing for executing one operation at a time or running until a
+//! [`Breakpoint`] is reached. Between steps, the full state of the VM
+//! (program counter, stack, memory and repeat counters) may be inspected.
+//!
+//! Breakpoints stop execution *prior* to executing the matching operation,
+//! leaving the VM in the state the operation will observe.
+
+use crate::{
+    asm::{Opcode, ToOpcode},
+    error::{ExecError, OpError},
+    Access, Gas, GasLimit, Op, OpAccess, OpGasCost, StateReads, Vm,
+};
+use alloc::vec::Vec;
+
+/// Drives execution of a [`Vm`] one operation at a time.
+///
+/// See the [module-level documentation][self] for details.
+pub struct Debugger<'a, S, OA, G> {
+    vm: Vm,
+    access: Access,
+    state_reads: &'a S,
+    op_access: OA,
+    op_gas_cost: &'a G,
+    gas_limit: GasLimit,
+    gas_spent: Gas,
+    finished: bool,
+    breakpoints: Vec<Breakpoint>,
+}
+
+/// A condition upon which [`Debugger::resume`] stops prior to executing an operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// Stop when the program counter reaches the given index.
+    Pc(usize),
+    /// Stop prior to executing any operation with the given opcode.
+    Opcode(Opcode),
+}
+
+/// The reason the debugger stopped executing operations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stop {
+    /// A single operation was executed.
+    Step,
+    /// The operation at the program counter matches the given breakpoint.
+    ///
+    /// The operation has not yet been executed.
+    Breakpoint(Breakpoint),
+    /// Execution has completed, having spent the given total gas.
+    Finished(Gas),
+}
+
+impl<'a, S, OA, G> Debugger<'a, S, OA, G>
+where
+    S: StateReads,
+    OA: OpAccess<Op = Op>,
+    OA::Error: Into<OpError<S::Error>>,
+    G: OpGasCost,
+{
+    /// Create a debugger that executes the given operations from the state of the given `Vm`.
+    ///
+    /// The arguments match those of [`Vm::exec`].
+    pub fn new(
+        vm: Vm,
+        access: Access,
+        state_reads: &'a S,
+        op_access: OA,
+        op_gas_cost: &'a G,
+        gas_limit: GasLimit,
+    ) -> Self {
+        Self {
+            vm,
+            access,
+            state_reads,
+            op_access,
+            op_gas_cost,
+            gas_limit,
+            gas_spent: 0,
+            finished: false,
+            breakpoints: Vec::new(),
+        }
+    }
+
+    /// The current state of the VM.
+    pub fn vm(&self) -> &Vm {
+        &self.vm
+    }
+
+    /// Mutable access to the state of the VM, e.g. to alter the stack or memory between steps.
+    pub fn vm_mut(&mut self) -> &mut Vm {
+        &mut self.vm
+    }
+
+    /// Consume the debugger, returning the VM.
+    pub fn into_vm(self) -> Vm {
+        self.vm
+    }
+
+    /// The total gas spent so far.
+    pub fn gas_spent(&self) -> Gas {
+        self.gas_spent
+    }
+
+    /// Whether or not execution has completed.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// The operation at the program counter, i.e. the next operation to be executed.
+    ///
+    /// Returns `None` if there are no more operations.
+    pub fn next_op(&self) -> Option<Result<Op, OA::Error>> {
+        self.op_access.op_access(self.vm.pc)
+    }
+
+    /// Add a breakpoint, returning `false` if it was already present.
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) -> bool {
+        if self.breakpoints.contains(&breakpoint) {
+            return false;
+        }
+        self.breakpoints.push(breakpoint);
+        true
+    }
+
+    /// Remove a breakpoint, returning `false` if it was not present.
+    pub fn remove_breakpoint(&mut self, breakpoint: Breakpoint) -> bool {
+        let len = self.breakpoints.len();
+        self.breakpoints.retain(|b| *b != breakpoint);
+        self.breakpoints.len() != len
+    }
+
+    /// The set breakpoints, in the order they were added.
+    pub fn breakpoints(&self) -> &[Breakpoint] {
+        &self.breakpoints
+    }
+
+    /// Execute the single operation at the program counter.
+    ///
+    /// Breakpoints are ignored. `Compute` operations are executed in full,
+    /// including all of their compute programs.
+    ///
+    /// In the case of an error, the VM is left in the state at which the
+    /// failing operation was executed.
+    pub fn step(&mut self) -> Result<Stop, ExecError<S::Error>> {
+        if self.finished {
+            return Ok(Stop::Finished(self.gas_spent));
+        }
+        let more = self.vm.exec_step(
+            &self.access,
+            self.state_reads,
+            &self.op_access,
+            self.op_gas_cost,
+            self.gas_limit,
+            &mut self.gas_spent,
+        )?;
+        if !more {
+            self.finished = true;
+            return Ok(Stop::Finished(self.gas_spent));
+        }
+        Ok(Stop::Step)
+    }
+
+    /// Execute operations until a breakpoint is reached or execution completes.
+    ///
+    /// The operation at the current program counter is always executed, so
+    /// that resuming from a breakpoint makes progress.
+    pub fn resume(&mut self) -> Result<Stop, ExecError<S::Error>> {
+        loop {
+            if let stop @ Stop::Finished(_) = self.step()? {
+                return Ok(stop);
+            }
+            if let Some(breakpoint) = self.breakpoint() {
+                return Ok(Stop::Breakpoint(breakpoint));
+            }
+        }
+    }
+
+    /// The first breakpoint matching the operation at the program counter.
+    fn breakpoint(&self) -> Option<Breakpoint> {
+        let pc = self.vm.pc;
+        let opcode = match self.next_op() {
+            Some(Ok(op)) => Some(op.to_opcode()),
+            _ => None,
+        };
+        self.breakpoints.iter().copied().find(|b| match *b {
+            Breakpoint::Pc(ix) => ix == pc,
+            Breakpoint::Opcode(op) => Some(op) == opcode,
+        })
+    }
+}
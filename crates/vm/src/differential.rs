@@ -0,0 +1,185 @@
+//! Differential execution of a program across each operation access path.
+//!
+//! The VM may access a program's operations as a slice of [`Op`]s, as
+//! [`BytecodeMapped`] bytecode or, with `std`, as bytecode fetched on demand
+//! with [`LazyBytecode`][crate::LazyBytecode] or mapped on demand with
+//! [`LazyBytecodeMapped`][crate::LazyBytecodeMapped]. Each path must execute a program
+//! identically. [`exec_all`] executes a program via each path from the same
+//! initial VM state, and [`check`] asserts that the resulting VM states, gas
+//! spent and errors are identical.
+//!
+//! This is intended for use within tests and fuzzers.
+
+use crate::{
+    error::ExecError, Access, BytecodeMapped, Gas, GasLimit, Op, OpGasCost, StateReads, Vm,
+};
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+/// A path by which the VM accesses a program's operations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Path {
+    /// Execution via [`Vm::exec_ops`].
+    Ops,
+    /// Execution via [`Vm::exec_bytecode`].
+    Bytecode,
+    /// Execution via [`Vm::exec`] with a [`LazyBytecode`][crate::LazyBytecode]
+    /// fetching [`LAZY_CHUNK_SIZE`] bytes at a time.
+    #[cfg(feature = "std")]
+    LazyBytecode,
+    /// Execution via [`Vm::exec`] with a [`LazyBytecodeMapped`][crate::LazyBytecodeMapped].
+    #[cfg(feature = "std")]
+    LazyBytecodeMapped,
+}
+
+/// The result of executing a program via a single [`Path`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Execution {
+    /// The path by which operations were accessed.
+    pub path: Path,
+    /// The state of the VM following execution.
+    pub vm: Vm,
+    /// The gas spent, or the index of the failed operation and its error message.
+    pub result: Result<Gas, (usize, String)>,
+}
+
+/// Execution via two paths resulted in different outcomes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Divergence {
+    /// The execution via [`Path::Ops`].
+    pub expected: Execution,
+    /// The first execution that differed from the expected execution.
+    pub found: Execution,
+}
+
+/// The number of bytes fetched at a time via [`Path::LazyBytecode`].
+///
+/// This is deliberately small and odd so that operations frequently straddle
+/// chunk boundaries.
+#[cfg(feature = "std")]
+pub const LAZY_CHUNK_SIZE: usize = 3;
+
+impl Path {
+    /// All paths, starting with the reference path [`Path::Ops`].
+    pub const ALL: &'static [Path] = &[
+        Path::Ops,
+        Path::Bytecode,
+        #[cfg(feature = "std")]
+        Path::LazyBytecode,
+        #[cfg(feature = "std")]
+        Path::LazyBytecodeMapped,
+    ];
+}
+
+/// Execute the given operations via each [`Path`] from the given VM state.
+///
+/// Executions are returned in the order of [`Path::ALL`].
+pub fn exec_all<S>(
+    vm: &Vm,
+    ops: &[Op],
+    access: Access,
+    state_reads: &S,
+    op_gas_cost: &impl OpGasCost,
+    gas_limit: GasLimit,
+) -> Vec<Execution>
+where
+    S: StateReads,
+{
+    Path::ALL
+        .iter()
+        .map(|&path| {
+            let mut vm = vm.clone();
+            let access = access.clone();
+            let result = match path {
+                Path::Ops => vm.exec_ops(ops, access, state_reads, op_gas_cost, gas_limit),
+                Path::Bytecode => {
+                    let mapped: BytecodeMapped = ops.iter().copied().collect();
+                    vm.exec_bytecode(&mapped, access, state_reads, op_gas_cost, gas_limit)
+                }
+                #[cfg(feature = "std")]
+                Path::LazyBytecode => {
+                    let source = Source(crate::asm::to_bytes(ops.iter().copied()).collect());
+                    let program = essential_types::ContentAddress([0; 32]);
+                    let lazy =
+                        crate::LazyBytecode::with_chunk_size(source, program, LAZY_CHUNK_SIZE);
+                    vm.exec(access, state_reads, lazy, op_gas_cost, gas_limit)
+                }
+                #[cfg(feature = "std")]
+                Path::LazyBytecodeMapped => {
+                    let bytes: Vec<u8> = crate::asm::to_bytes(ops.iter().copied()).collect();
+                    let mapped = crate::LazyBytecodeMapped::new(bytes);
+                    vm.exec(access, state_reads, mapped, op_gas_cost, gas_limit)
+                }
+            };
+            let result = result.map_err(|ExecError(ix, err)| (ix, err.to_string()));
+            Execution { path, vm, result }
+        })
+        .collect()
+}
+
+/// Execute the given operations via each [`Path`] and check the outcomes are identical.
+///
+/// Returns the execution via [`Path::Ops`], or the first execution that
+/// differs from it.
+pub fn check<S>(
+    vm: &Vm,
+    ops: &[Op],
+    access: Access,
+    state_reads: &S,
+    op_gas_cost: &impl OpGasCost,
+    gas_limit: GasLimit,
+) -> Result<Execution, Box<Divergence>>
+where
+    S: StateReads,
+{
+    let mut execs = exec_all(vm, ops, access, state_reads, op_gas_cost, gas_limit).into_iter();
+    let expected = execs.next().expect("`Path::ALL` is non-empty");
+    for found in execs {
+        if (&found.vm, &found.result) != (&expected.vm, &expected.result) {
+            return Err(Box::new(Divergence { expected, found }));
+        }
+    }
+    Ok(expected)
+}
+
+/// Serves bytecode from memory for [`Path::LazyBytecode`].
+#[cfg(feature = "std")]
+struct Source(Vec<u8>);
+
+#[cfg(feature = "std")]
+impl crate::BytecodeSource for Source {
+    type Error = core::convert::Infallible;
+    fn fetch(
+        &self,
+        _program: &essential_types::ContentAddress,
+        offset: usize,
+        len: usize,
+    ) -> Result<Vec<u8>, Self::Error> {
+        let start = offset.min(self.0.len());
+        let end = self.0.len().min(offset.saturating_add(len));
+        Ok(self.0[start..end].to_vec())
+    }
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { expected, found } = self;
+        write!(
+            f,
+            "execution via {:?} diverged from {:?}: ",
+            found.path, expected.path
+        )?;
+        if expected.result != found.result {
+            write!(f, "result {:?} != {:?}", found.result, expected.result)
+        } else {
+            write!(f, "VM state {:?} != {:?}", found.vm, expected.vm)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Divergence {}
@@ -5,7 +5,13 @@ use crate::{
     asm::{self, Word},
     Gas,
 };
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
 use core::convert::Infallible;
+use essential_types::state::SizeError;
 use thiserror::Error;
 
 /// Shorthand for a `Result` where the error type is a `ExecError`.
@@ -22,6 +28,39 @@ pub type OpResult<T, E = Infallible> = Result<T, OpError<E>>;
 #[error("operation at index {0} failed: {1}")]
 pub struct ExecError<E>(pub usize, pub OpError<E>);
 
+impl core::fmt::Display for InvalidEvaluation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "invalid constraint evaluation result\n  \
+            expected: [0] (false) or [1] (true)\n  \
+            found:    {:?}",
+            self.stack
+        )?;
+        match self.pc {
+            Some(pc) => write!(f, "\n  last op:  {pc}")?,
+            None => write!(f, "\n  last op:  none")?,
+        }
+        for (pc, op) in &self.trace {
+            write!(f, "\n    {pc}: {op:?}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<E> ExecError<E> {
+    /// The out of gas error, if execution may be resumed with [`Vm::resume`][crate::Vm::resume].
+    ///
+    /// Returns `None` for all other errors, including running out of gas
+    /// within a compute program, after which the VM state is unspecified.
+    pub fn out_of_gas(&self) -> Option<&OutOfGasError> {
+        match &self.1 {
+            OpError::OutOfGas(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
 /// Errors that might occur during synchronous evaluation.
 #[derive(Debug, Error)]
 pub enum EvalError<E> {
@@ -30,12 +69,22 @@ pub enum EvalError<E> {
     Exec(#[from] ExecError<E>),
     /// Evaluation should have resulted with a `0` (false) or `1` (true) at the
     /// top of the stack, but did not.
-    #[error(
-        "invalid constraint evaluation result\n  \
-        expected: [0] (false) or [1] (true)\n  \
-        found:    {0:?}"
-    )]
-    InvalidEvaluation(crate::Stack),
+    #[error("{0}")]
+    InvalidEvaluation(Box<InvalidEvaluation>),
+}
+
+/// Context for an evaluation that did not result in a boolean.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InvalidEvaluation {
+    /// The program counter of the last operation executed, or `None` if no
+    /// operations were executed.
+    pub pc: Option<usize>,
+    /// The stack following execution.
+    pub stack: crate::Stack,
+    /// The last operations executed and their program counters, oldest first.
+    ///
+    /// Only recorded by [`Vm::eval_traced`][crate::Vm::eval_traced].
+    pub trace: Vec<(usize, asm::Op)>,
 }
 
 /// An individual operation failed during execution.
@@ -86,6 +135,15 @@ pub enum OpError<E = Infallible> {
     /// The total gas limit was exceeded.
     #[error("{0}")]
     OutOfGas(#[from] OutOfGasError),
+    /// Executing the operation would exceed the limit on the number of operations.
+    #[error("operation limit of {0} exceeded")]
+    OpLimit(u64),
+    /// Accessing the operation failed, e.g. fetching remote bytecode.
+    #[error("op access error: {0}")]
+    OpAccess(String),
+    /// A state read key exceeds the maximum key size.
+    #[error("invalid state read key: {0}")]
+    Key(#[from] SizeError),
 }
 
 /// The gas cost of performing an operation would exceed the gas limit.
@@ -114,6 +172,9 @@ pub enum StateReadArgError {
     /// An error occurred during a `Stack` operation.
     #[error("stack operation error: {0}")]
     Stack(#[from] StackError),
+    /// The key exceeds the maximum key size.
+    #[error("invalid key: {0}")]
+    Key(#[from] SizeError),
 }
 
 /// Errors occuring during `TotalControlFlow` operation.
@@ -165,6 +226,9 @@ pub enum AccessError {
     /// The `which_slots` argument was invalid.
     #[error("invalid `which_slots` argument: {0}")]
     InvalidSlotType(Word),
+    /// The address of the executing program was not supplied with the access.
+    #[error("`ThisProgramAddress` requires the program address to be supplied")]
+    ThisProgramAddressUnavailable,
     /// Missing argument error.
     #[error("missing `Access` argument: {0}")]
     MissingArg(#[from] MissingAccessArgError),
@@ -215,13 +279,39 @@ pub enum AluError {
 pub enum CryptoError {
     /// Failed to verify a ED25519 signature.
     #[error("failed to verify ed25519 signature: {0}")]
-    Ed25519(#[from] ed25519_dalek::ed25519::Error),
+    Ed25519(#[cfg_attr(feature = "std", source)] ed25519_dalek::ed25519::Error),
     /// Failed to recover a SECP256k1 public key.
     #[error("failed to recover secp256k1 public key: {0}")]
-    Secp256k1(#[from] secp256k1::Error),
+    Secp256k1(#[cfg_attr(feature = "std", source)] secp256k1::Error),
     /// Failed to parse SECP256k1 recovery id
     #[error("failed to parse secp256k1 recovery id")]
     Secp256k1RecoveryId,
+    /// The number of signatures in a batch was invalid.
+    #[error("invalid number of signatures in batch: {0}")]
+    InvalidBatchLen(Word),
+    /// The number of predicate addresses was invalid.
+    #[error("invalid number of predicate addresses: {0}")]
+    InvalidPredicateAddrsLen(Word),
+    /// The number of siblings in a Merkle proof was invalid.
+    #[error("invalid number of merkle proof siblings: {0}")]
+    InvalidMerkleProofLen(Word),
+    /// The leaf index of a Merkle proof was out of range for its depth.
+    #[error("invalid merkle proof leaf index: {0}")]
+    InvalidMerkleIndex(Word),
+}
+
+// The crypto errors only implement `Error` with `std`, so `From` is implemented
+// manually rather than with `#[from]`.
+impl From<ed25519_dalek::ed25519::Error> for CryptoError {
+    fn from(err: ed25519_dalek::ed25519::Error) -> Self {
+        Self::Ed25519(err)
+    }
+}
+
+impl From<secp256k1::Error> for CryptoError {
+    fn from(err: secp256k1::Error) -> Self {
+        Self::Secp256k1(err)
+    }
 }
 
 /// Shorthand for a `Result` where the error type is a `StackError`.
@@ -237,7 +327,7 @@ pub enum StackError {
     #[error("indexed stack out of bounds")]
     IndexOutOfBounds,
     /// The stack size exceeded the size limit.
-    #[error("the {}-word stack size limit was exceeded", crate::Stack::SIZE_LIMIT)]
+    #[error("the stack size limit was exceeded")]
     Overflow,
     /// The condition for Select or SelectRange is not `0` (false) or `1` (true).
     #[error(
@@ -283,9 +373,12 @@ pub enum RepeatError {
     /// Repeat counter called with an invalid count direction
     #[error("The count direction must be 0 or 1")]
     InvalidCountDirection,
-    /// The repeat stack size exceeded the size limit.
-    #[error("the {}-word stack size limit was exceeded", crate::Stack::SIZE_LIMIT)]
+    /// The repeat nesting depth exceeded the limit.
+    #[error("the repeat nesting depth limit was exceeded")]
     Overflow,
+    /// Repeat counter accessed at a depth with no active repeat.
+    #[error("no active repeat at depth {0}")]
+    InvalidDepth(Word),
 }
 
 /// Shorthand for a `Result` where the error type is a `TotalControlFlowError`.
@@ -324,10 +417,97 @@ pub enum MemoryError {
     #[error("indexed memory out of bounds")]
     IndexOutOfBounds,
     /// The memory size exceeded the size limit.
-    #[error("the {}-word stack size limit was exceeded", crate::Memory::SIZE_LIMIT)]
+    #[error("the memory size limit was exceeded")]
     Overflow,
 }
 
+/// [`Vm::restore`][crate::Vm::restore] error.
+#[derive(Debug, Error)]
+pub enum RestoreError {
+    /// The snapshot's stack is invalid.
+    #[error("invalid stack: {0}")]
+    Stack(#[from] StackError),
+    /// The snapshot's memory or a parent memory is invalid.
+    #[error("invalid memory: {0}")]
+    Memory(#[from] MemoryError),
+    /// The snapshot's repeat stack is invalid.
+    #[error("invalid repeat stack: {0}")]
+    Repeat(#[from] RepeatError),
+}
+
+/// [`LazyBytecode`][crate::LazyBytecode] operation access error.
+#[derive(Debug, Error)]
+pub enum LazyBytecodeError<E> {
+    /// Fetching bytecode from the source failed.
+    #[error("failed to fetch bytecode: {0}")]
+    Fetch(E),
+    /// The fetched bytecode is invalid.
+    #[error("{0}")]
+    Bytes(asm::FromBytesError),
+}
+
+/// A program failed static verification.
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("verification failed at op {index}: {kind}")]
+pub struct VerifyError {
+    /// The index of the op at which verification failed.
+    pub index: usize,
+    /// The reason verification failed.
+    pub kind: VerifyErrorKind,
+}
+
+/// The reasons a program may fail static verification.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum VerifyErrorKind {
+    /// The op would pop from an empty stack.
+    #[error("stack underflow")]
+    StackUnderflow,
+    /// The op would exceed the stack size limit.
+    #[error(
+        "the {}-word stack size limit would be exceeded",
+        crate::Stack::SIZE_LIMIT
+    )]
+    StackOverflow,
+    /// The op indexes the stack out of bounds.
+    #[error("stack index {0} is out of bounds")]
+    StackIndexOutOfBounds(Word),
+    /// The op was provided an invalid length.
+    #[error("invalid length {0}")]
+    InvalidLength(Word),
+    /// The jump distance could not be determined ahead of time.
+    #[error("the jump distance could not be determined")]
+    UnresolvedJump,
+    /// The jump distance is zero.
+    #[error("the jump distance is zero")]
+    JumpToSelf,
+    /// The jump lands outside of the program.
+    #[error("the jump distance {0} lands outside of the program")]
+    InvalidJump(Word),
+    /// A `Repeat` has no matching `RepeatEnd`.
+    #[error("`Repeat` has no matching `RepeatEnd`")]
+    UnmatchedRepeat,
+    /// A `RepeatEnd` has no matching `Repeat`.
+    #[error("`RepeatEnd` has no matching `Repeat`")]
+    UnmatchedRepeatEnd,
+    /// A `Compute` has no matching `ComputeEnd`.
+    #[error("`Compute` has no matching `ComputeEnd`")]
+    UnmatchedCompute,
+    /// A `ComputeEnd` has no matching `Compute`.
+    #[error("`ComputeEnd` has no matching `Compute`")]
+    UnmatchedComputeEnd,
+}
+
+/// Error initialising a [`Vm`][crate::Vm] from parent program outputs.
+#[derive(Debug, Error)]
+pub enum ParentOutputsError {
+    /// Concatenating the parent stacks exceeded the stack size limit.
+    #[error("concatenating parent stacks caused an overflow: {0}")]
+    Stack(#[from] StackError),
+    /// Concatenating the parent memories exceeded the memory size limit.
+    #[error("concatenating parent memory caused an overflow: {0}")]
+    Memory(#[from] MemoryError),
+}
+
 /// Parent memory operation error.
 #[derive(Debug, Error)]
 pub enum ParentMemoryError {
@@ -387,11 +567,21 @@ impl<E> From<core::convert::Infallible> for OpError<E> {
     }
 }
 
+impl<E, F: core::fmt::Display> From<LazyBytecodeError<F>> for OpError<E> {
+    fn from(err: LazyBytecodeError<F>) -> Self {
+        match err {
+            LazyBytecodeError::Fetch(e) => OpError::OpAccess(e.to_string()),
+            LazyBytecodeError::Bytes(e) => OpError::FromBytes(e),
+        }
+    }
+}
+
 impl<E> From<StateReadArgError> for OpError<E> {
     fn from(err: StateReadArgError) -> Self {
         match err {
             StateReadArgError::Memory(e) => OpError::Memory(e),
             StateReadArgError::Stack(e) => OpError::Stack(e),
+            StateReadArgError::Key(e) => OpError::Key(e),
         }
     }
 }
@@ -416,6 +606,9 @@ impl<E> OpError<E> {
             OpError::StateRead(_) => unreachable!(),
             OpError::FromBytes(from_bytes_error) => OpError::FromBytes(from_bytes_error),
             OpError::OutOfGas(out_of_gas_error) => OpError::OutOfGas(out_of_gas_error),
+            OpError::OpLimit(limit) => OpError::OpLimit(limit),
+            OpError::OpAccess(msg) => OpError::OpAccess(msg),
+            OpError::Key(err) => OpError::Key(err),
             OpError::Compute(_) => unreachable!(),
         }
     }
@@ -0,0 +1,177 @@
+//! A standard, serializable [`OpGasCost`] implementation.
+
+use crate::{asm, Gas, Op, OpGasCost, Stack};
+use essential_types::Word;
+use serde::{Deserialize, Serialize};
+
+/// The gas costs of operations, shared between validators.
+///
+/// Each operation is charged the base cost of its group. Operations whose
+/// work depends on their stack inputs are additionally charged per unit of
+/// work:
+///
+/// - `Memory::Alloc` is charged [`alloc_per_word`][Self::alloc_per_word] for
+///   each word allocated.
+/// - `Memory::LoadRange`, `Memory::StoreRange`, `Memory::Copy` and
+///   `ParentMemory::LoadRange` are charged [`memory_per_word`][Self::memory_per_word] for each word copied.
+///   `Memory::PackBytes` and `Memory::UnpackBytes` are charged for 8 words.
+/// - The `StateRead` operations are charged
+///   [`state_read_per_key`][Self::state_read_per_key] for each key read,
+///   summed over all reads for the batched operations. The prefix reads are
+///   charged for their `limit`.
+/// - `Crypto::VerifyEd25519Batch` is charged
+///   [`signature_per_batch_item`][Self::signature_per_batch_item] for each
+///   signature verified.
+///
+/// Dynamic costs are computed from the stack prior to the operation's
+/// execution. Invalid (e.g. negative) inputs are charged nothing, as the
+/// operation will fail.
+///
+/// The default schedule charges 1 gas per operation.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GasSchedule {
+    /// Base cost of `Access` operations.
+    pub access: Gas,
+    /// Base cost of `Alu` operations.
+    pub alu: Gas,
+    /// Base cost of `Compute` operations.
+    ///
+    /// The gas spent by compute programs is charged in addition.
+    pub compute: Gas,
+    /// Base cost of `Crypto` operations.
+    pub crypto: Gas,
+    /// Base cost of `Memory` operations.
+    pub memory: Gas,
+    /// Base cost of `ParentMemory` operations.
+    pub parent_memory: Gas,
+    /// Base cost of `Pred` operations.
+    pub pred: Gas,
+    /// Base cost of `Stack` operations.
+    pub stack: Gas,
+    /// Base cost of `StateRead` operations.
+    pub state_read: Gas,
+    /// Base cost of `TotalControlFlow` operations.
+    pub total_control_flow: Gas,
+    /// Cost per word allocated by `Memory::Alloc`.
+    pub alloc_per_word: Gas,
+    /// Cost per word copied by memory range operations.
+    pub memory_per_word: Gas,
+    /// Cost per key read by state read operations.
+    pub state_read_per_key: Gas,
+    /// Cost per signature verified by `Crypto::VerifyEd25519Batch`.
+    ///
+    /// Each signature is verified as by `Crypto::VerifyEd25519`, so this
+    /// should reflect the full cost of verifying a single signature.
+    pub signature_per_batch_item: Gas,
+}
+
+impl GasSchedule {
+    /// Charges 1 gas per operation, with no dynamic costs.
+    pub const UNIT: Self = Self {
+        access: 1,
+        alu: 1,
+        compute: 1,
+        crypto: 1,
+        memory: 1,
+        parent_memory: 1,
+        pred: 1,
+        stack: 1,
+        state_read: 1,
+        total_control_flow: 1,
+        alloc_per_word: 0,
+        memory_per_word: 0,
+        state_read_per_key: 0,
+        signature_per_batch_item: 0,
+    };
+}
+
+impl Default for GasSchedule {
+    fn default() -> Self {
+        Self::UNIT
+    }
+}
+
+impl OpGasCost for GasSchedule {
+    fn op_gas_cost(&self, op: &Op) -> Gas {
+        match op {
+            Op::Access(_) => self.access,
+            Op::Alu(_) => self.alu,
+            Op::Compute(_) => self.compute,
+            Op::Crypto(_) => self.crypto,
+            Op::Memory(_) => self.memory,
+            Op::ParentMemory(_) => self.parent_memory,
+            Op::Pred(_) => self.pred,
+            Op::Stack(_) => self.stack,
+            Op::StateRead(_) => self.state_read,
+            Op::TotalControlFlow(_) => self.total_control_flow,
+        }
+    }
+
+    fn op_dynamic_gas_cost(&self, op: &Op, stack: &Stack) -> Gas {
+        // The stack input at the given depth from the top, as a count.
+        let input = |depth: usize| {
+            stack
+                .len()
+                .checked_sub(depth + 1)
+                .and_then(|ix| count(stack[ix]))
+                .unwrap_or(0)
+        };
+        match op {
+            // stack_in: [size]
+            Op::Memory(asm::Memory::Alloc) => input(0).saturating_mul(self.alloc_per_word),
+            // stack_in: [index, len]
+            Op::Memory(asm::Memory::LoadRange) | Op::ParentMemory(asm::ParentMemory::LoadRange) => {
+                input(0).saturating_mul(self.memory_per_word)
+            }
+            // stack_in: [dst, src, len]
+            Op::Memory(asm::Memory::Copy) => input(0).saturating_mul(self.memory_per_word),
+            // stack_in: [values, len, index]
+            Op::Memory(asm::Memory::StoreRange) => input(1).saturating_mul(self.memory_per_word),
+            // Always 8 words.
+            Op::Memory(asm::Memory::PackBytes | asm::Memory::UnpackBytes) => {
+                8u64.saturating_mul(self.memory_per_word)
+            }
+            // stack_in: [...signatures, n]
+            Op::Crypto(asm::Crypto::VerifyEd25519Batch) => {
+                input(0).saturating_mul(self.signature_per_batch_item)
+            }
+            // stack_in: [...reads, n_reads, mem_addr]
+            Op::StateRead(asm::StateRead::KeyRangeBatch | asm::StateRead::PostKeyRangeBatch) => {
+                batch_num_keys(stack).saturating_mul(self.state_read_per_key)
+            }
+            // stack_in: [..., num_keys_to_read | limit, mem_addr]
+            Op::StateRead(_) => input(1).saturating_mul(self.state_read_per_key),
+            _ => 0,
+        }
+    }
+}
+
+/// The total number of keys read by a batched state read.
+///
+/// Walks each `[key..., key_len, num_keys]` read beneath `[n_reads, mem_addr]`,
+/// stopping at the first invalid read.
+fn batch_num_keys(stack: &Stack) -> Gas {
+    let word = |ix: usize| stack.len().checked_sub(ix + 1).map(|ix| stack[ix]);
+    let Some(n_reads) = word(1).and_then(count) else {
+        return 0;
+    };
+    let mut depth = 2;
+    let mut total: Gas = 0;
+    for _ in 0..n_reads {
+        let Some(num_keys) = word(depth).and_then(count) else {
+            break;
+        };
+        let Some(key_len) = word(depth + 1).and_then(|w| usize::try_from(w).ok()) else {
+            break;
+        };
+        total = total.saturating_add(num_keys);
+        depth = depth.saturating_add(2).saturating_add(key_len);
+    }
+    total
+}
+
+/// Interpret a stack input as a count, if valid.
+fn count(word: Word) -> Option<Gas> {
+    Gas::try_from(word).ok()
+}
@@ -0,0 +1,50 @@
+//! Callbacks invoked around the execution of each operation.
+
+use crate::{Memory, Op, Stack};
+
+/// Callbacks invoked before and after each operation executed by
+/// [`Vm::exec_with_hooks`][crate::Vm::exec_with_hooks].
+///
+/// This allows for observing execution one operation at a time, e.g. for
+/// profilers, debuggers and custom invariant checkers, without reimplementing
+/// the execution loop.
+///
+/// The operations executed by the compute programs of a `Compute` operation
+/// are not observed. The `Compute` operation itself is observed as a single
+/// operation.
+pub trait ExecHooks {
+    /// Called prior to executing the operation at the given program counter.
+    ///
+    /// Only called for operations that are within the gas and operation limits.
+    fn before_op(&mut self, _pc: usize, _op: &Op, _stack: &Stack, _memory: &Memory) {}
+
+    /// Called after successfully executing the operation at the given program counter.
+    ///
+    /// The stack and memory reflect the result of the operation.
+    fn after_op(&mut self, _pc: usize, _op: &Op, _stack: &Stack, _memory: &Memory) {}
+}
+
+/// No hooks.
+impl ExecHooks for () {}
+
+impl<H: ExecHooks + ?Sized> ExecHooks for &mut H {
+    fn before_op(&mut self, pc: usize, op: &Op, stack: &Stack, memory: &Memory) {
+        (**self).before_op(pc, op, stack, memory)
+    }
+
+    fn after_op(&mut self, pc: usize, op: &Op, stack: &Stack, memory: &Memory) {
+        (**self).after_op(pc, op, stack, memory)
+    }
+}
+
+impl<A: ExecHooks, B: ExecHooks> ExecHooks for (A, B) {
+    fn before_op(&mut self, pc: usize, op: &Op, stack: &Stack, memory: &Memory) {
+        self.0.before_op(pc, op, stack, memory);
+        self.1.before_op(pc, op, stack, memory);
+    }
+
+    fn after_op(&mut self, pc: usize, op: &Op, stack: &Stack, memory: &Memory) {
+        self.0.after_op(pc, op, stack, memory);
+        self.1.after_op(pc, op, stack, memory);
+    }
+}
@@ -0,0 +1,165 @@
+//! An [`OpAccess`] implementation that fetches bytecode on demand.
+
+use crate::{
+    asm::{FromBytesError, TryFromBytes},
+    error::LazyBytecodeError,
+    Op, OpAccess,
+};
+use essential_types::ContentAddress;
+use std::sync::{Arc, Mutex};
+
+/// A source of program bytecode, e.g. a remote content-addressed store.
+///
+/// Asynchronous sources must block until the requested bytes are available,
+/// as the VM accesses operations synchronously.
+pub trait BytecodeSource: Send + Sync {
+    /// Any error that might occur while fetching bytecode.
+    type Error: core::fmt::Debug + core::fmt::Display + Send;
+
+    /// Fetch up to `len` bytes of the given program's bytecode starting from
+    /// the byte at `offset`.
+    ///
+    /// Fewer than `len` bytes must only be returned at the end of the bytecode.
+    fn fetch(
+        &self,
+        program: &ContentAddress,
+        offset: usize,
+        len: usize,
+    ) -> Result<Vec<u8>, Self::Error>;
+}
+
+/// Program bytecode fetched from a [`BytecodeSource`] as execution requires it.
+///
+/// Bytecode is fetched in chunks of the given size, reading ahead of the
+/// operation currently being accessed. Operations are decoded once and
+/// retained, so jumping backwards never refetches bytecode.
+///
+/// Clones share the fetched bytecode.
+pub struct LazyBytecode<S> {
+    inner: Arc<Inner<S>>,
+}
+
+struct Inner<S> {
+    source: S,
+    program: ContentAddress,
+    chunk_size: usize,
+    fetched: Mutex<Fetched>,
+}
+
+/// The bytecode fetched so far.
+#[derive(Default)]
+struct Fetched {
+    /// The bytecode fetched so far.
+    bytes: Vec<u8>,
+    /// The operations decoded so far.
+    ops: Vec<Op>,
+    /// The offset of the first byte following the decoded operations.
+    decoded: usize,
+    /// Whether the end of the bytecode has been fetched.
+    end: bool,
+}
+
+impl<S> LazyBytecode<S> {
+    /// The default number of bytes fetched at a time.
+    pub const DEFAULT_CHUNK_SIZE: usize = 4096;
+
+    /// Lazily fetch the given program's bytecode from the given source.
+    pub fn new(source: S, program: ContentAddress) -> Self {
+        Self::with_chunk_size(source, program, Self::DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Lazily fetch bytecode, fetching the given number of bytes at a time.
+    ///
+    /// A chunk size of `0` is treated as `1`.
+    pub fn with_chunk_size(source: S, program: ContentAddress, chunk_size: usize) -> Self {
+        let inner = Inner {
+            source,
+            program,
+            chunk_size: chunk_size.max(1),
+            fetched: Mutex::default(),
+        };
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+
+    /// The address of the program being fetched.
+    pub fn program(&self) -> &ContentAddress {
+        &self.inner.program
+    }
+
+    /// The number of bytes fetched so far.
+    pub fn fetched_len(&self) -> usize {
+        self.inner.lock().bytes.len()
+    }
+}
+
+impl<S> Inner<S> {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Fetched> {
+        // The fetched bytecode is always left in a valid state.
+        self.fetched.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<S: BytecodeSource> LazyBytecode<S> {
+    /// Fetch and decode until the operation at the given index is decoded or
+    /// the end of the bytecode is reached.
+    fn fetch_op(&self, index: usize) -> Option<Result<Op, LazyBytecodeError<S::Error>>> {
+        let inner = &*self.inner;
+        let mut guard = inner.lock();
+        let fetched = &mut *guard;
+        while fetched.ops.len() <= index {
+            let mut iter = fetched.bytes[fetched.decoded..].iter().copied();
+            let remaining = iter.len();
+            match Op::try_from_bytes(&mut iter) {
+                Some(Ok(op)) => {
+                    let len = remaining - iter.len();
+                    fetched.decoded += len;
+                    fetched.ops.push(op);
+                    continue;
+                }
+                Some(Err(err @ FromBytesError::InvalidOpcode(_))) => {
+                    return Some(Err(LazyBytecodeError::Bytes(err)));
+                }
+                Some(Err(err)) if fetched.end => return Some(Err(LazyBytecodeError::Bytes(err))),
+                None if fetched.end => return None,
+                // More bytes are required to decode the next operation.
+                Some(Err(FromBytesError::NotEnoughBytes(_))) | None => (),
+            }
+            let offset = fetched.bytes.len();
+            let chunk = match inner.source.fetch(&inner.program, offset, inner.chunk_size) {
+                Ok(chunk) => chunk,
+                Err(err) => return Some(Err(LazyBytecodeError::Fetch(err))),
+            };
+            fetched.end = chunk.len() < inner.chunk_size;
+            fetched.bytes.extend(chunk);
+        }
+        Some(Ok(fetched.ops[index]))
+    }
+}
+
+impl<S> Clone for LazyBytecode<S> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<S> core::fmt::Debug for LazyBytecode<S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("LazyBytecode")
+            .field("program", &self.inner.program)
+            .field("chunk_size", &self.inner.chunk_size)
+            .field("fetched_len", &self.fetched_len())
+            .finish()
+    }
+}
+
+impl<S: BytecodeSource> OpAccess for LazyBytecode<S> {
+    type Op = Op;
+    type Error = LazyBytecodeError<S::Error>;
+    fn op_access(&self, index: usize) -> Option<Result<Self::Op, Self::Error>> {
+        self.fetch_op(index)
+    }
+}
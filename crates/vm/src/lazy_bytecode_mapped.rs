@@ -0,0 +1,132 @@
+//! An [`OpAccess`] implementation over borrowed or memory-mapped bytecode.
+
+use crate::{
+    asm::{FromBytesError, TryFromBytes},
+    Op, OpAccess,
+};
+use core::ops::Deref;
+use std::sync::{Arc, Mutex};
+
+/// Operations accessed directly from bytecode that is already in memory, e.g.
+/// a memory-mapped file such as a `memmap2::Mmap`.
+///
+/// Unlike [`BytecodeMapped`][crate::bytecode::BytecodeMapped], the bytecode
+/// is neither copied nor validated up front. Operation boundaries are
+/// validated as execution first reaches them, and the index of each operation
+/// is retained so that jumping backwards never re-validates bytecode. This
+/// allows for executing programs within huge program sets without first
+/// reading each program into memory.
+///
+/// Clones share the bytecode and the operation indices mapped so far.
+pub struct LazyBytecodeMapped<B> {
+    inner: Arc<Inner<B>>,
+}
+
+struct Inner<B> {
+    bytecode: B,
+    mapped: Mutex<Mapped>,
+}
+
+/// The operations mapped so far.
+#[derive(Default)]
+struct Mapped {
+    /// The index of each op validated so far within the bytecode.
+    op_indices: Vec<usize>,
+    /// The offset of the first byte following the validated operations.
+    validated: usize,
+}
+
+impl<B> LazyBytecodeMapped<B>
+where
+    B: Deref<Target = [u8]>,
+{
+    /// Lazily map the operations of the given bytecode.
+    pub fn new(bytecode: B) -> Self {
+        let inner = Inner {
+            bytecode,
+            mapped: Mutex::default(),
+        };
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+
+    /// The full underlying bytecode.
+    pub fn bytecode(&self) -> &[u8] {
+        &self.inner.bytecode
+    }
+
+    /// The number of operations mapped so far.
+    pub fn mapped_len(&self) -> usize {
+        self.inner.lock().op_indices.len()
+    }
+
+    /// Validate and map operations until the operation at the given index is
+    /// mapped or the end of the bytecode is reached, returning its offset.
+    fn map_op(&self, index: usize) -> Option<Result<usize, FromBytesError>> {
+        let bytecode = &self.inner.bytecode[..];
+        let mut guard = self.inner.lock();
+        let mapped = &mut *guard;
+        while mapped.op_indices.len() <= index {
+            let mut iter = bytecode[mapped.validated..].iter().copied();
+            let remaining = iter.len();
+            match Op::try_from_bytes(&mut iter)? {
+                Ok(_) => {
+                    mapped.op_indices.push(mapped.validated);
+                    mapped.validated += remaining - iter.len();
+                }
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        Some(Ok(mapped.op_indices[index]))
+    }
+}
+
+impl<B> Inner<B> {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Mapped> {
+        // The mapped indices are always left in a valid state.
+        self.mapped.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<B> Clone for LazyBytecodeMapped<B> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<B> core::fmt::Debug for LazyBytecodeMapped<B> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("LazyBytecodeMapped")
+            .field("mapped_len", &self.inner.lock().op_indices.len())
+            .finish()
+    }
+}
+
+impl<B> From<B> for LazyBytecodeMapped<B>
+where
+    B: Deref<Target = [u8]>,
+{
+    fn from(bytecode: B) -> Self {
+        Self::new(bytecode)
+    }
+}
+
+impl<B> OpAccess for LazyBytecodeMapped<B>
+where
+    B: Deref<Target = [u8]> + Send + Sync,
+{
+    type Op = Op;
+    type Error = FromBytesError;
+    fn op_access(&self, index: usize) -> Option<Result<Self::Op, Self::Error>> {
+        let offset = match self.map_op(index)? {
+            Ok(offset) => offset,
+            Err(err) => return Some(Err(err)),
+        };
+        let mut iter = self.inner.bytecode[offset..].iter().copied();
+        // The operation at a mapped offset has already been validated.
+        Op::try_from_bytes(&mut iter)
+    }
+}
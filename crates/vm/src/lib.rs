@@ -19,24 +19,74 @@
 //! Each have slightly different performance implications, so be sure to read
 //! the docs before selecting a method.
 //!
+//! [`Vm::builder`] configures the VM's size limits along with the gas schedule,
+//! gas limit and [`ExecHooks`] used for execution, producing an [`ExecContext`].
+//!
 //! ## Execution Future
 //!
 //! The `Vm::exec_*` functions all return `Future`s that not only yield on
 //! async operations, but yield based on a user-specified gas limit too. See the
 //! [`ExecFuture`] docs for further details on the implementation.
+//!
+//! ## Debugging
+//!
+//! The [`debugger::Debugger`] allows for stepping through execution one
+//! operation at a time, stopping at breakpoints and inspecting the VM state
+//! in between.
+//!
+//! [`Vm::exec_with_coverage`] records the operations and branches executed
+//! into a [`Coverage`], e.g. to check that test solutions exercise all of a
+//! predicate's constraints.
+//!
+//! [`Vm::exec_with_hooks`] invokes the given [`ExecHooks`] before and after
+//! each operation, allowing for custom profilers and invariant checkers.
+//!
+//! The [`differential`] module executes a program via each operation access
+//! path and checks that the outcomes are identical, e.g. from fuzzers.
+//!
+//! ## Features
+//!
+//! - `std` (default): Enables `std` support. Without it, the VM only requires
+//!   `core` and `alloc`, `Compute` programs execute sequentially rather than in
+//!   parallel and the [`LazyCache`] is not thread-safe.
+//! - `tracing`: Traces execution of each operation. Implies `std`.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![deny(missing_docs, unsafe_code)]
 
+extern crate alloc;
+
 pub use access::Access;
-pub use cached::LazyCache;
+use alloc::vec::Vec;
+#[doc(inline)]
+pub use builder::{ExecContext, VmBuilder};
+pub use cached::{LazyCache, MutKeysIndex};
+#[doc(inline)]
+pub use coverage::Coverage;
 #[doc(inline)]
 pub use essential_asm::{self as asm, Op};
 pub use essential_types as types;
 #[doc(inline)]
+pub use gas_schedule::GasSchedule;
+#[doc(inline)]
+pub use hooks::ExecHooks;
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use lazy_bytecode::{BytecodeSource, LazyBytecode};
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use lazy_bytecode_mapped::LazyBytecodeMapped;
+#[doc(inline)]
 pub use memory::Memory;
 #[doc(inline)]
+pub use metrics::ExecMetrics;
+#[doc(inline)]
 pub use op_access::OpAccess;
 #[doc(inline)]
-pub use repeat::Repeat;
+pub use profile::{GasProfile, OpGasStats};
+#[doc(inline)]
+pub use repeat::{Repeat, RepeatLimits};
+#[doc(inline)]
+pub use snapshot::VmSnapshot;
 #[doc(inline)]
 pub use stack::Stack;
 #[doc(inline)]
@@ -46,24 +96,41 @@ pub use state_read::StateReads;
 #[doc(inline)]
 pub use total_control_flow::ProgramControlFlow;
 #[doc(inline)]
+pub use verify::verify;
+#[doc(inline)]
 pub use vm::Vm;
 
 mod access;
 mod alu;
+mod builder;
 pub mod bytecode;
 mod cached;
 mod compute;
+mod coverage;
 mod crypto;
+pub mod debugger;
+pub mod differential;
 pub mod error;
+mod gas_schedule;
+mod hooks;
+#[cfg(feature = "std")]
+mod lazy_bytecode;
+#[cfg(feature = "std")]
+mod lazy_bytecode_mapped;
 mod memory;
+mod metrics;
 mod op_access;
 mod pred;
+mod profile;
 mod repeat;
 mod sets;
+mod snapshot;
 mod stack;
 mod state_read;
+pub mod symbolic;
 pub mod sync;
 mod total_control_flow;
+pub mod verify;
 mod vm;
 
 #[cfg(test)]
@@ -79,7 +146,16 @@ pub(crate) mod utils {
             _contract_addr: essential_types::ContentAddress,
             _key: essential_types::Key,
             _num_values: usize,
-        ) -> Result<Vec<Vec<essential_asm::Word>>, Self::Error> {
+        ) -> Result<Vec<essential_types::Value>, Self::Error> {
+            Ok(vec![])
+        }
+
+        fn keys_with_prefix(
+            &self,
+            _contract_addr: essential_types::ContentAddress,
+            _prefix: essential_types::Key,
+            _limit: usize,
+        ) -> Result<Vec<essential_types::Key>, Self::Error> {
             Ok(vec![])
         }
     }
@@ -120,6 +196,14 @@ pub struct GasLimit {
 pub trait OpGasCost: Send + Sync {
     /// The gas cost associated with the given op.
     fn op_gas_cost(&self, op: &Op) -> Gas;
+
+    /// Additional gas cost dependent on the op's stack inputs.
+    ///
+    /// Called with the stack prior to the op's execution. Charged in addition
+    /// to [`op_gas_cost`][Self::op_gas_cost]. Defaults to `0`.
+    fn op_dynamic_gas_cost(&self, _op: &Op, _stack: &Stack) -> Gas {
+        0
+    }
 }
 
 impl GasLimit {
@@ -155,7 +239,7 @@ pub(crate) fn trace_op_res<OA, T, E>(
     pc: usize,
     stack: &Stack,
     memory: &Memory,
-    parent_memory: &Vec<std::sync::Arc<Memory>>,
+    parent_memory: &Vec<alloc::sync::Arc<Memory>>,
     halt: bool,
     op_res: &Result<T, E>,
 ) where
@@ -1,16 +1,29 @@
-use essential_types::Word;
+use alloc::vec::Vec;
+use essential_types::{
+    convert::{bytes_from_word, word_from_bytes},
+    Word,
+};
 
 use crate::error::MemoryError;
 
 #[cfg(test)]
 mod tests;
 
-#[derive(Clone, Default, Debug, PartialEq)]
+#[derive(Clone, Debug)]
 /// Memory for temporary storage of words.
-pub struct Memory(Vec<Word>);
+///
+/// Memory may not grow beyond its size limit, which defaults to
+/// [`Memory::SIZE_LIMIT`]. Memories are compared by their words alone.
+pub struct Memory {
+    words: Vec<Word>,
+    limit: usize,
+}
+
+/// The number of bytes packed into a word.
+const BYTES_PER_WORD: usize = core::mem::size_of::<Word>();
 
 impl Memory {
-    /// The maximum number of words that can be stored in memory.
+    /// The default maximum number of words that can be stored in memory.
     pub const SIZE_LIMIT: usize = 1024 * 10;
 
     /// Create a new temporary memory instance.
@@ -18,32 +31,59 @@ impl Memory {
         Self::default()
     }
 
+    /// Create a new temporary memory instance with the given size limit in words.
+    ///
+    /// Validators may use this to lower the limit below the default, bounding
+    /// the memory any single program may allocate.
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            words: Vec::new(),
+            limit,
+        }
+    }
+
+    /// Create a memory containing the given words, with the given size limit in words.
+    pub(crate) fn with_words(words: Vec<Word>, limit: usize) -> Result<Self, MemoryError> {
+        if words.len() > limit {
+            return Err(MemoryError::Overflow);
+        }
+        Ok(Self { words, limit })
+    }
+
+    /// The maximum number of words that can be stored in this memory.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
     /// Allocate more memory to the end of this memory.
     pub fn alloc(&mut self, size: Word) -> Result<(), MemoryError> {
         let size = usize::try_from(size).map_err(|_| MemoryError::Overflow)?;
         let new_size = self
-            .0
+            .words
             .len()
             .checked_add(size)
             .ok_or(MemoryError::Overflow)?;
-        if new_size > Self::SIZE_LIMIT {
+        if new_size > self.limit {
             return Err(MemoryError::Overflow);
         }
-        self.0.resize(new_size, 0);
+        self.words.resize(new_size, 0);
         Ok(())
     }
 
     /// Store a word at the given address.
     pub fn store(&mut self, address: Word, value: Word) -> Result<(), MemoryError> {
         let index = usize::try_from(address).map_err(|_| MemoryError::IndexOutOfBounds)?;
-        *self.0.get_mut(index).ok_or(MemoryError::IndexOutOfBounds)? = value;
+        *self
+            .words
+            .get_mut(index)
+            .ok_or(MemoryError::IndexOutOfBounds)? = value;
         Ok(())
     }
 
     /// Load a word from the given address.
     pub fn load(&self, address: Word) -> Result<Word, MemoryError> {
         let index = usize::try_from(address).map_err(|_| MemoryError::IndexOutOfBounds)?;
-        Ok(*self.0.get(index).ok_or(MemoryError::IndexOutOfBounds)?)
+        Ok(*self.words.get(index).ok_or(MemoryError::IndexOutOfBounds)?)
     }
 
     /// Store a range of words starting at the given address.
@@ -52,10 +92,10 @@ impl Memory {
         let end = address
             .checked_add(values.len())
             .ok_or(MemoryError::Overflow)?;
-        if end > self.0.len() {
+        if end > self.words.len() {
             return Err(MemoryError::IndexOutOfBounds);
         }
-        self.0[address..end].copy_from_slice(values);
+        self.words[address..end].copy_from_slice(values);
         Ok(())
     }
 
@@ -64,54 +104,100 @@ impl Memory {
         let address = usize::try_from(address).map_err(|_| MemoryError::IndexOutOfBounds)?;
         let size = usize::try_from(size).map_err(|_| MemoryError::Overflow)?;
         let end = address.checked_add(size).ok_or(MemoryError::Overflow)?;
-        if end > self.0.len() {
+        if end > self.words.len() {
+            return Err(MemoryError::IndexOutOfBounds);
+        }
+        Ok(self.words[address..end].to_vec())
+    }
+
+    /// Copy `len` words from the `src` address to the `dst` address.
+    ///
+    /// The ranges may overlap.
+    pub fn copy(&mut self, dst: Word, src: Word, len: Word) -> Result<(), MemoryError> {
+        let dst = usize::try_from(dst).map_err(|_| MemoryError::IndexOutOfBounds)?;
+        let src = usize::try_from(src).map_err(|_| MemoryError::IndexOutOfBounds)?;
+        let len = usize::try_from(len).map_err(|_| MemoryError::Overflow)?;
+        let src_end = src.checked_add(len).ok_or(MemoryError::Overflow)?;
+        let dst_end = dst.checked_add(len).ok_or(MemoryError::Overflow)?;
+        if src_end > self.words.len() || dst_end > self.words.len() {
             return Err(MemoryError::IndexOutOfBounds);
         }
-        Ok(self.0[address..end].to_vec())
+        self.words.copy_within(src..src_end, dst);
+        Ok(())
+    }
+
+    /// Pack the low byte of each of the 8 words starting at the given address
+    /// into a single word, the first word's byte being the most significant.
+    pub fn pack_bytes(&self, address: Word) -> Result<Word, MemoryError> {
+        let words = self.load_range(address, BYTES_PER_WORD as Word)?;
+        let mut bytes = [0u8; BYTES_PER_WORD];
+        bytes
+            .iter_mut()
+            .zip(words)
+            .for_each(|(byte, word)| *byte = word as u8);
+        Ok(word_from_bytes(bytes))
+    }
+
+    /// Unpack the bytes of the given value into the 8 words starting at the
+    /// given address, the most significant byte being stored first.
+    pub fn unpack_bytes(&mut self, address: Word, value: Word) -> Result<(), MemoryError> {
+        let words = bytes_from_word(value).map(Word::from);
+        self.store_range(address, &words)
     }
 
     /// Truncate memory to the given `new_len`, freeing all memory that follows.
     pub fn free(&mut self, new_len: Word) -> Result<(), MemoryError> {
         let new_len = usize::try_from(new_len).map_err(|_| MemoryError::IndexOutOfBounds)?;
-        if new_len > self.0.len() {
+        if new_len > self.words.len() {
             return Err(MemoryError::IndexOutOfBounds);
         }
-        self.0.truncate(new_len);
-        self.0.shrink_to_fit();
+        self.words.truncate(new_len);
+        self.words.shrink_to_fit();
         Ok(())
     }
 
     /// Current len of the memory.
     pub fn len(&self) -> Result<Word, MemoryError> {
-        self.0.len().try_into().map_err(|_| MemoryError::Overflow)
+        self.words
+            .len()
+            .try_into()
+            .map_err(|_| MemoryError::Overflow)
     }
 
     /// Is the memory empty?
     pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+        self.words.is_empty()
+    }
+}
+
+impl Default for Memory {
+    fn default() -> Self {
+        Self::with_limit(Self::SIZE_LIMIT)
+    }
+}
+
+impl PartialEq for Memory {
+    fn eq(&self, other: &Self) -> bool {
+        self.words == other.words
     }
 }
 
 impl From<Memory> for Vec<Word> {
     fn from(m: Memory) -> Vec<Word> {
-        m.0
+        m.words
     }
 }
 
 impl TryFrom<Vec<Word>> for Memory {
     type Error = MemoryError;
     fn try_from(words: Vec<Word>) -> Result<Self, Self::Error> {
-        if words.len() > Self::SIZE_LIMIT {
-            Err(MemoryError::Overflow)
-        } else {
-            Ok(Self(words))
-        }
+        Self::with_words(words, Self::SIZE_LIMIT)
     }
 }
 
 impl core::ops::Deref for Memory {
     type Target = [Word];
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.words
     }
 }
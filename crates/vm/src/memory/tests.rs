@@ -170,7 +170,7 @@ fn test_free_capacity_reduction() {
     memory.free(index_to_keep).unwrap();
 
     // Verify capacity has been reduced
-    assert_eq!(memory.0.capacity(), index_to_keep as usize);
+    assert_eq!(memory.words.capacity(), index_to_keep as usize);
 }
 
 #[test]
@@ -876,3 +876,164 @@ fn test_memory_load_store_range_with_ops() {
     .unwrap();
     assert_eq!(&vm.stack[..], &[1, 2, 3, 4, 5]);
 }
+
+#[test]
+fn test_memory_limit() {
+    let mut memory = Memory::with_limit(4);
+    assert_eq!(memory.limit(), 4);
+    memory.alloc(3).unwrap();
+    assert!(matches!(memory.alloc(2), Err(MemoryError::Overflow)));
+    memory.alloc(1).unwrap();
+    assert!(matches!(memory.alloc(1), Err(MemoryError::Overflow)));
+
+    // The limit does not affect equality.
+    let mut other = Memory::new();
+    other.alloc(4).unwrap();
+    assert_eq!(memory, other);
+    assert_eq!(Memory::new().limit(), Memory::SIZE_LIMIT);
+}
+
+#[test]
+fn test_memory_limit_with_ops() {
+    // Compute programs inherit the limit, so their combined allocations may
+    // not exceed it either.
+    let ops = &[
+        asm::Stack::Push(2).into(),
+        asm::Compute::Compute.into(),
+        asm::Stack::Push(3).into(),
+        asm::Memory::Alloc.into(),
+        asm::Compute::ComputeEnd.into(),
+    ];
+    let op_gas_cost = &|_: &Op| 1;
+    let mut vm = Vm {
+        memory: Memory::with_limit(4),
+        ..Default::default()
+    };
+    let err = vm
+        .exec_ops(
+            ops,
+            test_access().clone(),
+            &EmptyState,
+            op_gas_cost,
+            GasLimit::UNLIMITED,
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ExecError(1, OpError::Memory(MemoryError::Overflow))
+    ));
+
+    let mut vm = Vm {
+        memory: Memory::with_limit(6),
+        ..Default::default()
+    };
+    vm.exec_ops(
+        ops,
+        test_access().clone(),
+        &EmptyState,
+        op_gas_cost,
+        GasLimit::UNLIMITED,
+    )
+    .unwrap();
+    assert_eq!(vm.memory.len().unwrap(), 6);
+}
+
+#[test]
+fn test_pack_unpack_bytes() {
+    let mut memory = Memory::new();
+    memory.pack_bytes(0).unwrap_err();
+    memory.alloc(9).unwrap();
+    memory.unpack_bytes(2, 0).unwrap_err();
+
+    let word: Word = 0x0102_0304_0506_0708;
+    memory.unpack_bytes(1, word).unwrap();
+    assert_eq!(&memory[..], &[0, 1, 2, 3, 4, 5, 6, 7, 8]);
+    assert_eq!(memory.pack_bytes(1).unwrap(), word);
+
+    // Negative words unpack to their two's complement bytes.
+    memory.unpack_bytes(0, -1).unwrap();
+    assert_eq!(&memory[..8], &[0xFF; 8]);
+    assert_eq!(memory.pack_bytes(0).unwrap(), -1);
+
+    // Only the low byte of each word is packed.
+    memory.store(0, 0x1234).unwrap();
+    let expected =
+        essential_types::convert::word_from_bytes([0x34, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+    assert_eq!(memory.pack_bytes(0).unwrap(), expected);
+}
+
+#[test]
+fn test_pack_unpack_bytes_ops() {
+    let ops = &[
+        asm::Stack::Push(8).into(),
+        asm::Memory::Alloc.into(),
+        asm::Stack::Push(0x0102_0304_0506_0708).into(), // value
+        asm::Stack::Swap.into(),                        // addr
+        asm::Memory::UnpackBytes.into(),
+        asm::Stack::Push(7).into(),
+        asm::Memory::Load.into(),
+        asm::Stack::Push(0).into(),
+        asm::Memory::PackBytes.into(),
+    ];
+    let mut vm = Vm::default();
+    vm.exec_ops(
+        ops,
+        test_access().clone(),
+        &EmptyState,
+        &|_: &Op| 1,
+        GasLimit::UNLIMITED,
+    )
+    .unwrap();
+    assert_eq!(&vm.stack[..], &[8, 0x0102_0304_0506_0708]);
+}
+
+#[test]
+fn test_memory_copy() {
+    let mut memory = Memory::try_from(vec![1, 2, 3, 4, 5]).unwrap();
+    memory.copy(3, 0, 2).unwrap();
+    assert_eq!(&memory[..], &[1, 2, 3, 1, 2]);
+
+    // Overlapping forwards and backwards.
+    memory.copy(1, 0, 4).unwrap();
+    assert_eq!(&memory[..], &[1, 1, 2, 3, 1]);
+    memory.copy(0, 1, 4).unwrap();
+    assert_eq!(&memory[..], &[1, 2, 3, 1, 1]);
+
+    // Out of bounds.
+    memory.copy(0, 1, 5).unwrap_err();
+    memory.copy(1, 0, 5).unwrap_err();
+    memory.copy(-1, 0, 1).unwrap_err();
+    memory.copy(0, 0, -1).unwrap_err();
+    memory.copy(5, 5, 0).unwrap();
+    assert_eq!(&memory[..], &[1, 2, 3, 1, 1]);
+}
+
+#[test]
+fn test_memory_copy_op() {
+    let ops = &[
+        asm::Stack::Push(1).into(),
+        asm::Stack::Push(2).into(),
+        asm::Stack::Push(3).into(),
+        asm::Stack::Push(3).into(), // len
+        asm::Stack::Push(6).into(),
+        asm::Memory::Alloc.into(), // addr
+        asm::Memory::StoreRange.into(),
+        asm::Stack::Push(2).into(), // dst
+        asm::Stack::Push(0).into(), // src
+        asm::Stack::Push(3).into(), // len
+        asm::Memory::Copy.into(),
+        asm::Stack::Push(0).into(), // addr
+        asm::Stack::Push(5).into(), // len
+        asm::Memory::LoadRange.into(),
+    ];
+    let mut vm = Vm::default();
+    vm.exec_ops(
+        ops,
+        test_access().clone(),
+        &EmptyState,
+        &|_: &Op| 1,
+        GasLimit::UNLIMITED,
+    )
+    .unwrap();
+    assert_eq!(&vm.stack[..], &[1, 2, 1, 2, 3]);
+}
@@ -0,0 +1,47 @@
+//! Metrics describing the resources used by an execution.
+
+use crate::{ExecHooks, Memory, Op, Stack};
+
+/// Resources used while executing a program.
+///
+/// Produced by [`Vm::exec_with_metrics`][crate::Vm::exec_with_metrics].
+/// Metrics may be accumulated across multiple executions, in which case the
+/// peaks are the largest observed by any execution.
+///
+/// Useful for calibrating gas schedules and for rejecting programs that use
+/// a disproportionate amount of resources for the gas they spend.
+///
+/// As with [`ExecHooks`], the operations of compute programs executed by a
+/// `Compute` operation are not counted individually.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ExecMetrics {
+    /// The number of operations successfully executed.
+    pub ops_executed: u64,
+    /// The largest number of words on the stack following any operation.
+    pub peak_stack: usize,
+    /// The largest number of words in memory following any operation.
+    pub peak_memory: usize,
+    /// The number of state read operations successfully executed.
+    pub state_reads: u64,
+}
+
+impl ExecMetrics {
+    /// Accumulate another set of metrics into this one.
+    pub fn merge(&mut self, other: &Self) {
+        self.ops_executed = self.ops_executed.saturating_add(other.ops_executed);
+        self.peak_stack = self.peak_stack.max(other.peak_stack);
+        self.peak_memory = self.peak_memory.max(other.peak_memory);
+        self.state_reads = self.state_reads.saturating_add(other.state_reads);
+    }
+}
+
+impl ExecHooks for ExecMetrics {
+    fn after_op(&mut self, _pc: usize, op: &Op, stack: &Stack, memory: &Memory) {
+        self.ops_executed = self.ops_executed.saturating_add(1);
+        self.peak_stack = self.peak_stack.max(stack.len());
+        self.peak_memory = self.peak_memory.max(memory[..].len());
+        if let Op::StateRead(_) = op {
+            self.state_reads = self.state_reads.saturating_add(1);
+        }
+    }
+}
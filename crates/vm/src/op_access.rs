@@ -1,11 +1,13 @@
 //! The `OpAccess` trait declaration and its implementations.
 
 use crate::{asm::TryFromBytes, bytecode::BytecodeMapped};
-use std::sync::Arc;
+use alloc::sync::Arc;
 
 /// Types that provide access to operations.
 ///
-/// Implementations are included for `&[Op]`, [`BytecodeMapped`] and `Arc<T>`.
+/// Implementations are included for `&[Op]`, [`BytecodeMapped`] and `Arc<T>`,
+/// along with `LazyBytecode` for fetching bytecode on demand and
+/// `LazyBytecodeMapped` for memory-mapped bytecode (both require `std`).
 pub trait OpAccess: Clone + Send + Sync {
     /// The operation type being accessed.
     type Op;
@@ -42,6 +44,20 @@ where
     }
 }
 
+/// Allows for sharing a single mapping between executions with
+/// `Arc<BytecodeMapped<Op, Bytes>>`.
+impl<Op, Bytes> OpAccess for BytecodeMapped<Op, Bytes>
+where
+    Op: Clone + TryFromBytes + Send + Sync,
+    Bytes: Clone + core::ops::Deref<Target = [u8]> + Send + Sync,
+{
+    type Op = Op;
+    type Error = core::convert::Infallible;
+    fn op_access(&self, index: usize) -> Option<Result<Self::Op, Self::Error>> {
+        self.op(index).map(Ok)
+    }
+}
+
 impl<T> OpAccess for Arc<T>
 where
     T: OpAccess,
@@ -3,8 +3,8 @@ use crate::{
     sets::decode_set,
     Stack,
 };
+use alloc::collections::BTreeSet;
 use essential_types::Word;
-use std::collections::HashSet;
 
 #[cfg(test)]
 mod tests;
@@ -39,8 +39,8 @@ pub(crate) fn eq_range(stack: &mut Stack) -> OpResult<()> {
 /// `Pred::EqSet` implementation.
 pub(crate) fn eq_set(stack: &mut Stack) -> OpResult<()> {
     let eq = stack.pop_len_words2::<_, _, OpError>(|lhs, rhs| {
-        let lhs = decode_set(lhs).collect::<Result<HashSet<&[Word]>, _>>()?;
-        let rhs = decode_set(rhs).collect::<Result<HashSet<&[Word]>, _>>()?;
+        let lhs = decode_set(lhs).collect::<Result<BTreeSet<&[Word]>, _>>()?;
+        let rhs = decode_set(rhs).collect::<Result<BTreeSet<&[Word]>, _>>()?;
         Ok(lhs == rhs)
     })?;
     stack.push(eq.into())?;
@@ -21,6 +21,24 @@ fn test_eq_range_false() {
     assert_eq!(stack.pop().unwrap(), 0);
 }
 
+#[test]
+fn test_eq_range_packed_str() {
+    use essential_types::convert::words_from_str;
+    let eq = |a: &str, b: &str| {
+        let (a, b) = (words_from_str(a), words_from_str(b));
+        assert_eq!(a.len(), b.len());
+        let mut stack = Stack::default();
+        stack.extend(a.iter().copied()).unwrap();
+        stack.extend(b.iter().copied()).unwrap();
+        stack.push(a.len() as Word).unwrap();
+        eq_range(&mut stack).unwrap();
+        stack.pop().unwrap()
+    };
+    assert_eq!(eq("essential", "essential"), 1);
+    assert_eq!(eq("essential", "essentiaL"), 0);
+    assert_eq!(eq("", ""), 1);
+}
+
 #[test]
 fn test_eq_empty_range() {
     let mut stack = Stack::default();
@@ -0,0 +1,97 @@
+//! Aggregation of gas spent per opcode and per program counter.
+
+use crate::{asm::Opcode, Gas};
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::fmt;
+
+/// The gas spent executing a program, broken down by opcode and by program counter.
+///
+/// Produced by [`Vm::exec_profiled`][crate::Vm::exec_profiled]. Profiles may
+/// be accumulated across multiple executions, e.g. to profile all programs of
+/// a predicate together.
+///
+/// The gas spent by the compute programs of a `Compute` operation is
+/// attributed to the `Compute` operation itself.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GasProfile {
+    /// Gas and invocation counts for each opcode executed.
+    pub opcodes: BTreeMap<Opcode, OpGasStats>,
+    /// Gas and invocation counts for each program counter executed.
+    pub pcs: BTreeMap<usize, OpGasStats>,
+}
+
+/// The number of times an operation was executed and the gas spent doing so.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OpGasStats {
+    /// The number of times the operation was executed.
+    pub count: u64,
+    /// The total gas spent by the operation.
+    pub gas: Gas,
+}
+
+impl GasProfile {
+    /// Record the execution of an operation.
+    pub fn record(&mut self, pc: usize, opcode: Opcode, gas: Gas) {
+        self.opcodes.entry(opcode).or_default().add(1, gas);
+        self.pcs.entry(pc).or_default().add(1, gas);
+    }
+
+    /// Accumulate another profile into this one.
+    pub fn merge(&mut self, other: &Self) {
+        for (&opcode, stats) in &other.opcodes {
+            self.opcodes
+                .entry(opcode)
+                .or_default()
+                .add(stats.count, stats.gas);
+        }
+        for (&pc, stats) in &other.pcs {
+            self.pcs.entry(pc).or_default().add(stats.count, stats.gas);
+        }
+    }
+
+    /// The total number of operations executed and gas spent.
+    pub fn total(&self) -> OpGasStats {
+        let mut total = OpGasStats::default();
+        for stats in self.opcodes.values() {
+            total.add(stats.count, stats.gas);
+        }
+        total
+    }
+
+    /// The opcodes executed, ordered by the gas spent in descending order.
+    pub fn opcodes_by_gas(&self) -> Vec<(Opcode, OpGasStats)> {
+        let mut opcodes: Vec<_> = self.opcodes.iter().map(|(&op, &s)| (op, s)).collect();
+        opcodes.sort_by_key(|(_, stats)| core::cmp::Reverse(stats.gas));
+        opcodes
+    }
+}
+
+impl OpGasStats {
+    fn add(&mut self, count: u64, gas: Gas) {
+        self.count = self.count.saturating_add(count);
+        self.gas = self.gas.saturating_add(gas);
+    }
+}
+
+impl fmt::Display for GasProfile {
+    /// A report with one line per opcode, ordered by the gas spent.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let total = self.total();
+        writeln!(f, "{:<8} {:>12} {:>16} {:>7}", "op", "count", "gas", "%")?;
+        for (opcode, stats) in self.opcodes_by_gas() {
+            let pct = match total.gas {
+                0 => 0.0,
+                t => stats.gas as f64 * 100.0 / t as f64,
+            };
+            writeln!(
+                f,
+                "{:<8} {:>12} {:>16} {:>6.2}%",
+                opcode.short(),
+                stats.count,
+                stats.gas,
+                pct
+            )?;
+        }
+        writeln!(f, "{:<8} {:>12} {:>16}", "total", total.count, total.gas)
+    }
+}
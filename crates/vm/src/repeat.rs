@@ -1,27 +1,41 @@
+use alloc::vec::Vec;
 use essential_types::{convert::bool_from_word, Word};
 
 use crate::{
     error::{OpResult, RepeatError, RepeatResult, StackError},
     Stack,
 };
+use serde::{Deserialize, Serialize};
 
 #[cfg(test)]
 mod tests;
 
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 /// A stack of repeat counters.
+///
+/// Each active `Repeat` operation pushes a counter, so nested repeats form a
+/// stack of counters. The nesting depth is bounded by the [`RepeatLimits`].
 pub struct Repeat {
     stack: Vec<Slot>,
+    #[serde(default)]
+    limits: RepeatLimits,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+/// Limits on the nesting of repeats.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub struct RepeatLimits {
+    /// The maximum number of repeats that may be active at once.
+    pub max_depth: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 struct Slot {
     pub counter: Word,
     pub limit: Direction,
     pub repeat_index: usize,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 enum Direction {
     Up(Word),
     Down,
@@ -40,16 +54,50 @@ pub(crate) fn repeat(pc: usize, stack: &mut Stack, repeat: &mut Repeat) -> OpRes
     Ok(())
 }
 
+/// `Access::RepeatCounterAt` implementation.
+pub(crate) fn counter_at(stack: &mut Stack, repeat: &Repeat) -> OpResult<()> {
+    stack.pop1_push1(|depth| {
+        let ix = usize::try_from(depth).map_err(|_| RepeatError::InvalidDepth(depth))?;
+        Ok(repeat.counter_at(ix)?)
+    })
+}
+
+impl RepeatLimits {
+    /// The default maximum nesting depth.
+    pub const DEFAULT_MAX_DEPTH: usize = Stack::SIZE_LIMIT;
+}
+
+impl Default for RepeatLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: Self::DEFAULT_MAX_DEPTH,
+        }
+    }
+}
+
 impl Repeat {
     /// Create a new repeat stack.
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Create a new repeat stack with the given limits.
+    pub fn with_limits(limits: RepeatLimits) -> Self {
+        Self {
+            stack: Vec::new(),
+            limits,
+        }
+    }
+
+    /// The limits on the nesting of repeats.
+    pub fn limits(&self) -> RepeatLimits {
+        self.limits
+    }
+
     /// Add a new repeat location and counter to the stack.
     /// Counts down to 0.
     pub fn repeat_from(&mut self, location: usize, amount: Word) -> RepeatResult<()> {
-        if self.stack.len() >= super::Stack::SIZE_LIMIT {
+        if self.stack.len() >= self.limits.max_depth {
             return Err(RepeatError::Overflow);
         }
         self.stack.push(Slot {
@@ -63,7 +111,7 @@ impl Repeat {
     /// Add a new repeat location and counter to the stack.
     /// Counts up from 0 to limit - 1.
     pub fn repeat_to(&mut self, location: usize, limit: Word) -> RepeatResult<()> {
-        if self.stack.len() >= super::Stack::SIZE_LIMIT {
+        if self.stack.len() >= self.limits.max_depth {
             return Err(RepeatError::Overflow);
         }
         self.stack.push(Slot {
@@ -84,6 +132,34 @@ impl Repeat {
             .ok_or(RepeatError::NoCounter)
     }
 
+    /// Get the counter of the repeat at the given depth, where `0` is the
+    /// innermost active repeat.
+    ///
+    /// Returns an error if fewer than `depth + 1` repeats are active.
+    pub fn counter_at(&self, depth: usize) -> RepeatResult<Word> {
+        self.stack
+            .iter()
+            .rev()
+            .nth(depth)
+            .map(|s| s.counter)
+            .ok_or(RepeatError::InvalidDepth(depth as Word))
+    }
+
+    /// The number of active repeats, i.e. the repeat nesting depth.
+    pub fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Whether or not there are no active repeats.
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// The counters of all active repeats, from the outermost to the innermost.
+    pub fn counters(&self) -> impl Iterator<Item = Word> + '_ {
+        self.stack.iter().map(|s| s.counter)
+    }
+
     // TODO: Update this comment.
     /// If there is a counter on the stack and the counter
     /// has greater then 1 repeat left then this will decrement
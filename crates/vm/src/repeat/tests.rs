@@ -31,3 +31,32 @@ fn test_repeat_to() {
     assert_eq!(repeat.repeat().unwrap(), None);
     repeat.repeat().unwrap_err();
 }
+
+#[test]
+fn test_counter_at() {
+    let mut repeat = Repeat::new();
+    repeat.counter_at(0).unwrap_err();
+    repeat.repeat_to(0, 3).unwrap();
+    repeat.repeat_from(1, 2).unwrap();
+    assert_eq!(repeat.counter_at(0).unwrap(), 2);
+    assert_eq!(repeat.counter_at(1).unwrap(), 0);
+    assert!(matches!(
+        repeat.counter_at(2),
+        Err(RepeatError::InvalidDepth(2))
+    ));
+    assert_eq!(repeat.repeat().unwrap(), Some(1));
+    assert_eq!(repeat.counter_at(0).unwrap(), 1);
+}
+
+#[test]
+fn test_max_depth() {
+    let mut repeat = Repeat::with_limits(RepeatLimits { max_depth: 2 });
+    assert_eq!(repeat.limits().max_depth, 2);
+    repeat.repeat_to(0, 1).unwrap();
+    repeat.repeat_from(0, 1).unwrap();
+    assert!(matches!(repeat.repeat_to(0, 1), Err(RepeatError::Overflow)));
+    assert_eq!(
+        Repeat::new().limits().max_depth,
+        RepeatLimits::DEFAULT_MAX_DEPTH
+    );
+}
@@ -34,7 +34,7 @@ where
 /// Decode a set, starting from the top of slice.
 pub(crate) fn decode_set(words: &[Word]) -> impl '_ + Iterator<Item = OpResult<&[Word]>> {
     let mut ws = words;
-    std::iter::from_fn(move || {
+    core::iter::from_fn(move || {
         let (len, rest) = ws.split_last()?;
         let ix = match usize::try_from(*len)
             .map_err(|_| StackError::Overflow.into())
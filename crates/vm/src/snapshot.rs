@@ -0,0 +1,129 @@
+//! Serializable snapshots of the VM state.
+
+use crate::{
+    error::{RepeatError, RestoreError},
+    LazyCache, Memory, Repeat, Stack, Vm,
+};
+use alloc::{sync::Arc, vec::Vec};
+use essential_types::{Hash, Word};
+use serde::{Deserialize, Serialize};
+
+/// A serializable snapshot of the full state of a [`Vm`].
+///
+/// Produced by [`Vm::snapshot`] and restored with [`Vm::restore`], allowing
+/// for checkpointing execution, persisting it and resuming it elsewhere. The
+/// `Vm` itself serializes via its snapshot.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VmSnapshot {
+    /// The program counter.
+    pub pc: usize,
+    /// The words on the stack.
+    pub stack: Vec<Word>,
+    /// The stack size limit in words.
+    #[serde(default = "default_stack_limit")]
+    pub stack_limit: usize,
+    /// The words in memory.
+    pub memory: Vec<Word>,
+    /// The memory size limit in words.
+    pub memory_limit: usize,
+    /// The words of each parent memory, from the outermost `Compute` context.
+    pub parent_memory: Vec<Vec<Word>>,
+    /// Whether a `Halt` was encountered in a compute program.
+    pub halt: bool,
+    /// The repeat stack.
+    pub repeat: Repeat,
+    /// The number of operations executed so far.
+    #[serde(default)]
+    pub ops_executed: u64,
+    /// The maximum number of operations that may be executed, if any.
+    #[serde(default)]
+    pub max_ops: Option<u64>,
+    /// The predicate data hashes, if they have been computed.
+    ///
+    /// Sorted, so that snapshots of equal VMs are equal.
+    pub pred_data_hashes: Option<Vec<Hash>>,
+}
+
+impl Vm {
+    /// Take a snapshot of the current state of the VM.
+    pub fn snapshot(&self) -> VmSnapshot {
+        let pred_data_hashes = self.cache.pred_data_hashes.get().map(|hashes| {
+            let mut hashes: Vec<Hash> = hashes.iter().copied().collect();
+            hashes.sort();
+            hashes
+        });
+        VmSnapshot {
+            pc: self.pc,
+            stack: self.stack.to_vec(),
+            stack_limit: self.stack.limit(),
+            memory: self.memory.to_vec(),
+            memory_limit: self.memory.limit(),
+            parent_memory: self.parent_memory.iter().map(|m| m.to_vec()).collect(),
+            halt: self.halt,
+            repeat: self.repeat.clone(),
+            ops_executed: self.ops_executed,
+            max_ops: self.max_ops,
+            pred_data_hashes,
+        }
+    }
+
+    /// Restore a VM from the given snapshot.
+    ///
+    /// Returns an error if the snapshot exceeds any of the VM's size limits.
+    pub fn restore(snapshot: VmSnapshot) -> Result<Self, RestoreError> {
+        let VmSnapshot {
+            pc,
+            stack,
+            stack_limit,
+            memory,
+            memory_limit,
+            parent_memory,
+            halt,
+            repeat,
+            ops_executed,
+            max_ops,
+            pred_data_hashes,
+        } = snapshot;
+        if repeat.len() > repeat.limits().max_depth {
+            return Err(RepeatError::Overflow.into());
+        }
+        let parent_memory = parent_memory
+            .into_iter()
+            .map(|words| Memory::with_words(words, memory_limit).map(Arc::new))
+            .collect::<Result<_, _>>()?;
+        let cache = LazyCache::new();
+        if let Some(hashes) = pred_data_hashes {
+            // The cache was only just created, so cannot have been set.
+            let _ = cache.pred_data_hashes.set(hashes.into_iter().collect());
+        }
+        Ok(Self {
+            pc,
+            stack: Stack::with_words(stack, stack_limit)?,
+            memory: Memory::with_words(memory, memory_limit)?,
+            parent_memory,
+            halt,
+            repeat,
+            cache: Arc::new(cache),
+            ops_executed,
+            max_ops,
+        })
+    }
+}
+
+/// Snapshots predating configurable stack limits use the default limit.
+fn default_stack_limit() -> usize {
+    Stack::SIZE_LIMIT
+}
+
+impl From<Vm> for VmSnapshot {
+    fn from(vm: Vm) -> Self {
+        vm.snapshot()
+    }
+}
+
+impl TryFrom<VmSnapshot> for Vm {
+    type Error = RestoreError;
+    fn try_from(snapshot: VmSnapshot) -> Result<Self, Self::Error> {
+        Self::restore(snapshot)
+    }
+}
@@ -4,6 +4,7 @@ use crate::{
     asm::Word,
     error::{LenWordsError, StackError, StackResult},
 };
+use alloc::vec::Vec;
 use essential_types::convert::bool_from_word;
 
 #[cfg(test)]
@@ -13,21 +14,51 @@ mod frame_tests;
 ///
 /// A light wrapper around `Vec<Word>` providing helper methods specific to
 /// essential VM execution.
-#[derive(Clone, Debug, PartialEq, Default)]
-pub struct Stack(Vec<Word>);
+///
+/// The stack may not grow beyond its size limit, which defaults to
+/// [`Stack::SIZE_LIMIT`]. Stacks are compared by their words alone.
+#[derive(Clone)]
+pub struct Stack {
+    words: Vec<Word>,
+    limit: usize,
+}
 
 impl Stack {
     /// Limit the stack size to 32KB to avoid memory bloat during parallel constraint checking.
     pub const SIZE_LIMIT: usize = 4096;
 
+    /// Create an empty stack with the given size limit in words.
+    ///
+    /// Validators may use this to lower the limit below the default, bounding
+    /// the stack any single program may use.
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            words: Vec::new(),
+            limit,
+        }
+    }
+
+    /// Create a stack containing the given words, with the given size limit in words.
+    pub(crate) fn with_words(words: Vec<Word>, limit: usize) -> StackResult<Self> {
+        if words.len() > limit {
+            return Err(StackError::Overflow);
+        }
+        Ok(Self { words, limit })
+    }
+
+    /// The maximum number of words the stack may hold.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
     /// Push a word to the stack.
     ///
     /// Errors in the case that pushing an element would cause the stack to overflow.
     pub fn push(&mut self, word: Word) -> StackResult<()> {
-        if self.len() >= Self::SIZE_LIMIT {
+        if self.len() >= self.limit {
             return Err(StackError::Overflow);
         }
-        self.0.push(word);
+        self.words.push(word);
         Ok(())
     }
 
@@ -47,10 +78,10 @@ impl Stack {
         let len = usize::try_from(len).map_err(|_| StackError::IndexOutOfBounds)?;
         let start = self.len();
         let new_len = start.saturating_add(len);
-        if new_len > Self::SIZE_LIMIT {
+        if new_len > self.limit {
             return Err(StackError::IndexOutOfBounds);
         }
-        self.0.resize(new_len, 0);
+        self.words.resize(new_len, 0);
         let start = Word::try_from(start).map_err(|_| StackError::IndexOutOfBounds)?;
         self.push(start)?;
         Ok(())
@@ -61,7 +92,7 @@ impl Stack {
         let ix = self.pop()?;
         let ix = usize::try_from(ix).map_err(|_| StackError::IndexOutOfBounds)?;
         let word = self
-            .0
+            .words
             .get(ix)
             .copied()
             .ok_or(StackError::IndexOutOfBounds)?;
@@ -72,7 +103,7 @@ impl Stack {
     pub(crate) fn store(&mut self) -> StackResult<()> {
         let [word, ix] = self.pop2()?;
         let ix = usize::try_from(ix).map_err(|_| StackError::IndexOutOfBounds)?;
-        let Some(w) = self.0.get_mut(ix) else {
+        let Some(w) = self.words.get_mut(ix) else {
             return Err(StackError::IndexOutOfBounds);
         };
         *w = word;
@@ -83,16 +114,53 @@ impl Stack {
     pub(crate) fn dup_from(&mut self) -> StackResult<()> {
         let rev_ix_w = self.pop()?;
         let rev_ix = usize::try_from(rev_ix_w).map_err(|_| StackError::IndexOutOfBounds)?;
-        let ix = self
+        let w = self.peek(rev_ix)?;
+        self.push(w)?;
+        Ok(())
+    }
+
+    /// The DupRange op implementation.
+    pub(crate) fn dup_range(&mut self) -> StackResult<()> {
+        let len = self.pop_len()?;
+        let start = self
             .len()
-            .checked_sub(rev_ix)
-            .and_then(|i| i.checked_sub(1))
+            .checked_sub(len)
             .ok_or(StackError::IndexOutOfBounds)?;
-        let w = *self.get(ix).ok_or(StackError::IndexOutOfBounds)?;
-        self.push(w)?;
+        if self.len().saturating_add(len) > self.limit {
+            return Err(StackError::Overflow);
+        }
+        self.words.extend_from_within(start..);
         Ok(())
     }
 
+    /// The word at the given depth, where `0` is the top of the stack.
+    pub fn peek(&self, depth: usize) -> StackResult<Word> {
+        self.len()
+            .checked_sub(depth)
+            .and_then(|ix| ix.checked_sub(1))
+            .map(|ix| self.words[ix])
+            .ok_or(StackError::IndexOutOfBounds)
+    }
+
+    /// The top `len` words of the stack, ordered from bottom to top.
+    pub fn peek_range(&self, len: usize) -> StackResult<&[Word]> {
+        let start = self
+            .len()
+            .checked_sub(len)
+            .ok_or(StackError::IndexOutOfBounds)?;
+        Ok(&self.words[start..])
+    }
+
+    /// Iterate over the words from the bottom of the stack to the top.
+    pub fn iter(&self) -> core::slice::Iter<'_, Word> {
+        self.words.iter()
+    }
+
+    /// The words of the stack, from the bottom to the top.
+    pub fn as_slice(&self) -> &[Word] {
+        &self.words
+    }
+
     /// The SwapIndex op implementation.
     pub(crate) fn swap_index(&mut self) -> StackResult<()> {
         let rev_ix_w = self.pop()?;
@@ -104,7 +172,7 @@ impl Stack {
         let ix = top_ix
             .checked_sub(rev_ix)
             .ok_or(StackError::IndexOutOfBounds)?;
-        self.0.swap(ix, top_ix);
+        self.words.swap(ix, top_ix);
         Ok(())
     }
 
@@ -141,17 +209,17 @@ impl Stack {
         if cond {
             // copy arr_b to the space arr_a holds
             let arr_a_index = arr_b_index - len;
-            self.0
+            self.words
                 .copy_within(arr_b_index..(arr_b_index + len), arr_a_index);
         }
         // pop the topmost range that is arr_b
-        self.0.truncate(arr_b_index);
+        self.words.truncate(arr_b_index);
         Ok(())
     }
 
     /// A wrapper around `Vec::pop`, producing an error in the case that the stack is empty.
     pub fn pop(&mut self) -> StackResult<Word> {
-        self.0.pop().ok_or(StackError::Empty)
+        self.words.pop().ok_or(StackError::Empty)
     }
 
     /// Pop the top 2 values from the stack.
@@ -278,7 +346,7 @@ impl Stack {
     {
         let (rest, slice) = slice_split_len_words(self).map_err(StackError::LenWords)?;
         let out = f(slice)?;
-        self.0.truncate(rest.len());
+        self.words.truncate(rest.len());
         Ok(out)
     }
 
@@ -291,7 +359,7 @@ impl Stack {
     {
         let (rest, slice) = slice_split_len(self, num_words).map_err(StackError::LenWords)?;
         let out = f(slice)?;
-        self.0.truncate(rest.len());
+        self.words.truncate(rest.len());
         Ok(out)
     }
 
@@ -306,14 +374,14 @@ impl Stack {
         let (rest, rhs) = slice_split_len_words(self).map_err(StackError::LenWords)?;
         let (rest, lhs) = slice_split_len_words(rest).map_err(StackError::LenWords)?;
         let out = f(lhs, rhs)?;
-        self.0.truncate(rest.len());
+        self.words.truncate(rest.len());
         Ok(out)
     }
 
     /// Reserve additional capacity for the stack.
     /// Noop if capacity already exists.
     pub fn reserve(&mut self, additional: usize) {
-        self.0.reserve(additional);
+        self.words.reserve(additional);
     }
 }
 
@@ -338,27 +406,41 @@ fn slice_split_len(slice: &[Word], len: usize) -> Result<(&[Word], &[Word]), Len
     Ok(slice.split_at(ix))
 }
 
+impl Default for Stack {
+    fn default() -> Self {
+        Self::with_limit(Self::SIZE_LIMIT)
+    }
+}
+
+impl PartialEq for Stack {
+    fn eq(&self, other: &Self) -> bool {
+        self.words == other.words
+    }
+}
+
+impl core::fmt::Debug for Stack {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("Stack").field(&self.words).finish()
+    }
+}
+
 impl From<Stack> for Vec<Word> {
     fn from(stack: Stack) -> Self {
-        stack.0
+        stack.words
     }
 }
 
 impl TryFrom<Vec<Word>> for Stack {
     type Error = StackError;
     fn try_from(vec: Vec<Word>) -> Result<Self, Self::Error> {
-        if vec.len() > Self::SIZE_LIMIT {
-            Err(StackError::Overflow)
-        } else {
-            Ok(Self(vec))
-        }
+        Self::with_words(vec, Self::SIZE_LIMIT)
     }
 }
 
 impl core::ops::Deref for Stack {
     type Target = Vec<Word>;
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.words
     }
 }
 
@@ -690,4 +772,86 @@ mod tests {
             _ => panic!("expected index out of bounds stack error"),
         }
     }
+
+    #[test]
+    fn peek() {
+        let stack = crate::Stack::try_from(vec![1, 2, 3]).unwrap();
+        assert_eq!(stack.peek(0).unwrap(), 3);
+        assert_eq!(stack.peek(2).unwrap(), 1);
+        assert!(matches!(stack.peek(3), Err(StackError::IndexOutOfBounds)));
+        assert_eq!(stack.peek_range(2).unwrap(), &[2, 3]);
+        assert_eq!(stack.peek_range(0).unwrap(), &[] as &[i64]);
+        assert!(matches!(
+            stack.peek_range(4),
+            Err(StackError::IndexOutOfBounds)
+        ));
+        assert_eq!(stack.iter().copied().collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(stack.as_slice(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn dup_range() {
+        let ops = &[
+            Stack::Push(1).into(),
+            Stack::Push(2).into(),
+            Stack::Push(3).into(),
+            Stack::Push(2).into(), // len
+            Stack::DupRange.into(),
+            Stack::Push(0).into(), // len
+            Stack::DupRange.into(),
+        ];
+        let op_gas_cost = &|_: &Op| 1;
+        let mut vm = Vm::default();
+        vm.exec_ops(
+            ops,
+            test_access().clone(),
+            &EmptyState,
+            op_gas_cost,
+            GasLimit::UNLIMITED,
+        )
+        .unwrap();
+        assert_eq!(&vm.stack[..], &[1, 2, 3, 2, 3]);
+    }
+
+    #[test]
+    fn dup_range_errors() {
+        let op_gas_cost = &|_: &Op| 1;
+        let ops = &[
+            Stack::Push(1).into(),
+            Stack::Push(2).into(), // len
+            Stack::DupRange.into(),
+        ];
+        match Vm::default().exec_ops(
+            ops,
+            test_access().clone(),
+            &EmptyState,
+            op_gas_cost,
+            GasLimit::UNLIMITED,
+        ) {
+            Err(ExecError(2, OpError::Stack(StackError::IndexOutOfBounds))) => (),
+            _ => panic!("expected index out of bounds stack error"),
+        }
+
+        let mut vm = Vm {
+            stack: crate::Stack::with_limit(3),
+            ..Default::default()
+        };
+        let ops = &[
+            Stack::Push(1).into(),
+            Stack::Push(1).into(), // len
+            Stack::DupRange.into(),
+            Stack::Push(2).into(), // len
+            Stack::DupRange.into(),
+        ];
+        match vm.exec_ops(
+            ops,
+            test_access().clone(),
+            &EmptyState,
+            op_gas_cost,
+            GasLimit::UNLIMITED,
+        ) {
+            Err(ExecError(4, OpError::Stack(StackError::Overflow))) => (),
+            _ => panic!("expected stack overflow error"),
+        }
+    }
 }
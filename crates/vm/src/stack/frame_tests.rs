@@ -13,10 +13,10 @@ fn setup_stack(values: &[Word]) -> Stack {
 fn test_reserve_zeroed_success() {
     let mut stack = setup_stack(&[3]); // Push length 3
     assert!(stack.reserve_zeroed().is_ok());
-    assert_eq!(stack.0, vec![0, 0, 0, 0]);
+    assert_eq!(stack.words, vec![0, 0, 0, 0]);
     stack.push(2).unwrap(); // Push length 2
     assert!(stack.reserve_zeroed().is_ok());
-    assert_eq!(stack.0, vec![0, 0, 0, 0, 0, 0, 4]);
+    assert_eq!(stack.words, vec![0, 0, 0, 0, 0, 0, 4]);
 }
 
 #[test]
@@ -50,7 +50,7 @@ fn test_reserve_zeroed_exceeds_size_limit() {
 fn test_reserve_zero_gives_stack_len() {
     let mut stack = setup_stack(&[1, 2, 3, 0]); // Push length 3
     assert!(stack.reserve_zeroed().is_ok());
-    assert_eq!(stack.0, vec![1, 2, 3, 3]);
+    assert_eq!(stack.words, vec![1, 2, 3, 3]);
 }
 
 #[test]
@@ -58,7 +58,7 @@ fn test_load_success() {
     let mut stack = setup_stack(&[42, 84, 126]);
     stack.push(1).unwrap(); // Push index 1
     assert!(stack.load().is_ok());
-    assert_eq!(stack.0, vec![42, 84, 126, 84]);
+    assert_eq!(stack.words, vec![42, 84, 126, 84]);
     assert_eq!(stack.pop().unwrap(), 84); // Should load value at index 1
 }
 
@@ -80,7 +80,7 @@ fn test_load_negative_index() {
 #[test]
 fn test_load_out_of_bounds() {
     let mut stack = setup_stack(&[5]);
-    stack.0 = vec![1, 2, 3]; // Memory only has 3 elements
+    stack.words = vec![1, 2, 3]; // Memory only has 3 elements
     assert!(matches!(
         stack.load().unwrap_err(),
         StackError::IndexOutOfBounds
@@ -93,7 +93,7 @@ fn test_store_success() {
     stack.push(99).unwrap(); // Value to store
     stack.push(1).unwrap(); // Index to store at
     assert!(stack.store().is_ok());
-    assert_eq!(stack.0, vec![1, 99, 3]);
+    assert_eq!(stack.words, vec![1, 99, 3]);
 }
 
 #[test]
@@ -4,6 +4,7 @@ use crate::{
     error::{MemoryError, OpError, OpResult, StackError, StateReadArgError},
     Memory, Stack,
 };
+use alloc::vec::Vec;
 use essential_types::{convert::u8_32_from_word_4, ContentAddress, Key, Value, Word};
 
 #[cfg(test)]
@@ -21,7 +22,33 @@ pub trait StateRead: Send + Sync {
         contract_addr: ContentAddress,
         key: Key,
         num_values: usize,
-    ) -> Result<Vec<Vec<Word>>, Self::Error>;
+    ) -> Result<Vec<Value>, Self::Error>;
+
+    /// Read the keys with values in state that begin with the given prefix
+    /// associated with the given contract address.
+    ///
+    /// Returns at most `limit` keys in ascending order.
+    fn keys_with_prefix(
+        &self,
+        contract_addr: ContentAddress,
+        prefix: Key,
+        limit: usize,
+    ) -> Result<Vec<Key>, Self::Error>;
+
+    /// Perform multiple key range reads, returning the values of each read in order.
+    ///
+    /// Used by the batched state read operations. Backends with high latency
+    /// reads may override this to perform the reads concurrently. The default
+    /// implementation performs each read in turn with [`key_range`][Self::key_range].
+    fn key_ranges(
+        &self,
+        reads: Vec<(ContentAddress, Key, usize)>,
+    ) -> Result<Vec<Vec<Value>>, Self::Error> {
+        reads
+            .into_iter()
+            .map(|(contract_addr, key, num_values)| self.key_range(contract_addr, key, num_values))
+            .collect()
+    }
 }
 
 /// Pre and post sync state reads.
@@ -95,6 +122,54 @@ where
     Ok(())
 }
 
+/// `StateRead::KeysWithPrefix` operation.
+/// Uses a synchronous state read.
+pub fn keys_with_prefix<S>(
+    state_read: &S,
+    contract_addr: &ContentAddress,
+    stack: &mut Stack,
+    memory: &mut Memory,
+) -> OpResult<(), S::Error>
+where
+    S: StateRead,
+{
+    let mem_addr = pop_memory_address(stack)?;
+    let (prefix, limit) = pop_key_range_args(stack)?;
+    let keys = state_read
+        .keys_with_prefix(contract_addr.clone(), prefix, limit)
+        .map_err(OpError::StateRead)?;
+    write_values_to_memory(mem_addr, keys, memory)?;
+    Ok(())
+}
+
+/// `StateRead::KeyRangeBatch` operation.
+/// Issues all reads to the state read at once via [`StateRead::key_ranges`].
+pub fn key_range_batch<S>(
+    state_read: &S,
+    contract_addr: &ContentAddress,
+    stack: &mut Stack,
+    memory: &mut Memory,
+) -> OpResult<(), S::Error>
+where
+    S: StateRead,
+{
+    let mut mem_addr = pop_memory_address(stack)?;
+    let n_reads = stack.pop()?;
+    let n_reads = usize::try_from(n_reads).map_err(|_| StackError::IndexOutOfBounds)?;
+    let mut reads = Vec::new();
+    for _ in 0..n_reads {
+        let (key, num_keys) = pop_key_range_args(stack)?;
+        reads.push((contract_addr.clone(), key, num_keys));
+    }
+    // Reads are popped from the top of the stack, so the last read comes first.
+    reads.reverse();
+    let values = state_read.key_ranges(reads).map_err(OpError::StateRead)?;
+    for values in values {
+        mem_addr = write_values_to_memory(mem_addr, values, memory)?;
+    }
+    Ok(())
+}
+
 /// Read the length and key from the top of the stack and read the associated words from state.
 /// Uses a synchronous state read.
 fn read_key_range<S>(
@@ -133,19 +208,21 @@ fn pop_memory_address(stack: &mut Stack) -> Result<usize, StateReadArgError> {
 }
 
 /// Pop the key and number of keys from the stack.
-fn pop_key_range_args(stack: &mut Stack) -> Result<(Key, usize), StackError> {
+fn pop_key_range_args(stack: &mut Stack) -> Result<(Key, usize), StateReadArgError> {
     let num_keys = stack.pop()?;
     let num_keys = usize::try_from(num_keys).map_err(|_| StackError::IndexOutOfBounds)?;
-    let key = stack.pop_len_words::<_, _, StackError>(|words| Ok(words.to_vec()))?;
+    let key = stack.pop_len_words::<_, _, StateReadArgError>(|words| Ok(Key::try_from(words)?))?;
     Ok((key, num_keys))
 }
 
 /// Write the given values to memory.
+///
+/// Returns the memory address following the last value written.
 fn write_values_to_memory(
     mem_addr: usize,
-    values: Vec<Vec<Word>>,
+    values: Vec<impl AsRef<[Word]>>,
     memory: &mut Memory,
-) -> Result<(), MemoryError> {
+) -> Result<usize, MemoryError> {
     let values_len = Word::try_from(values.len()).map_err(|_| MemoryError::Overflow)?;
     let index_len_pairs_len = values_len.checked_mul(2).ok_or(MemoryError::Overflow)?;
     let mut mem_addr = Word::try_from(mem_addr).map_err(|_| MemoryError::IndexOutOfBounds)?;
@@ -153,14 +230,15 @@ fn write_values_to_memory(
         .checked_add(index_len_pairs_len)
         .ok_or(MemoryError::Overflow)?;
     for value in values {
+        let value = value.as_ref();
         let value_len = Word::try_from(value.len()).map_err(|_| MemoryError::Overflow)?;
         // Write the [index, len] pair.
         memory.store_range(mem_addr, &[value_addr, value_len])?;
         // Write the value.
-        memory.store_range(value_addr, &value)?;
+        memory.store_range(value_addr, value)?;
         // No need to check addition here as `store_range` would have failed.
         value_addr += value_len;
         mem_addr += 2;
     }
-    Ok(())
+    usize::try_from(value_addr).map_err(|_| MemoryError::IndexOutOfBounds)
 }
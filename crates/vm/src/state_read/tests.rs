@@ -36,7 +36,7 @@ fn test_pop_key_range_args() {
 #[test]
 fn test_write_values_to_memory() {
     let mut memory = Memory::default();
-    write_values_to_memory(0, vec![], &mut memory).unwrap();
+    write_values_to_memory(0, Vec::<Value>::new(), &mut memory).unwrap();
     let expected: &[i64] = &[];
     assert_eq!(memory.get(..).unwrap(), expected);
 
@@ -69,7 +69,7 @@ fn test_read_key_range() {
     memory.alloc(2 + 2).unwrap();
     state.contracts.insert(
         contract_addr.clone(),
-        [(vec![42, 43], vec![1, 2])].into_iter().collect(),
+        [([42, 43].into(), [1, 2].into())].into_iter().collect(),
     );
 
     stack.extend([42, 43, 2, 2, 0]).unwrap();
@@ -95,7 +95,7 @@ fn test_read_key_range_ext() {
     memory.alloc(2 + 2).unwrap();
     state.contracts.insert(
         contract_addr.clone(),
-        [(vec![42, 43], vec![1, 2])].into_iter().collect(),
+        [([42, 43].into(), [1, 2].into())].into_iter().collect(),
     );
 
     stack.extend(contract_words).unwrap();
@@ -105,6 +105,66 @@ fn test_read_key_range_ext() {
     assert_eq!(memory.get(..).unwrap(), expected);
 }
 
+#[test]
+fn test_read_key_range_batch() {
+    let mut state = State::default();
+    let mut stack = Stack::default();
+    let mut memory = Memory::default();
+    let contract_addr = ContentAddress([1; 32]);
+
+    stack.extend([0, 0]).unwrap();
+    key_range_batch(&state, &contract_addr, &mut stack, &mut memory).unwrap();
+    assert!(stack.is_empty());
+
+    memory.alloc(4 + 3).unwrap();
+    state.contracts.insert(
+        contract_addr.clone(),
+        [([42, 43].into(), [1, 2].into()), ([7].into(), [9].into())]
+            .into_iter()
+            .collect(),
+    );
+
+    // The second read is written immediately following the first.
+    stack.extend([42, 43, 2, 2, 7, 1, 1, 2, 0]).unwrap();
+    key_range_batch(&state, &contract_addr, &mut stack, &mut memory).unwrap();
+    assert!(stack.is_empty());
+    let expected: &[i64] = &[2, 2, 1, 2, 6, 1, 9];
+    assert_eq!(memory.get(..).unwrap(), expected);
+}
+
+#[test]
+fn test_keys_with_prefix() {
+    let mut state = State::default();
+    let mut stack = Stack::default();
+    let mut memory = Memory::default();
+    let contract_addr = ContentAddress([1; 32]);
+
+    stack.extend([42, 1, 2, 0]).unwrap();
+    keys_with_prefix(&state, &contract_addr, &mut stack, &mut memory).unwrap();
+    let expected: &[i64] = &[];
+    assert_eq!(memory.get(..).unwrap(), expected);
+
+    memory.alloc(4 + 4).unwrap();
+    state.contracts.insert(
+        contract_addr.clone(),
+        [
+            ([42, 2].into(), [1].into()),
+            ([42, 1].into(), [2].into()),
+            ([42, 3].into(), [3].into()),
+            ([43, 0].into(), [4].into()),
+        ]
+        .into_iter()
+        .collect(),
+    );
+
+    // The first two keys with the prefix in ascending order.
+    stack.extend([42, 1, 2, 0]).unwrap();
+    keys_with_prefix(&state, &contract_addr, &mut stack, &mut memory).unwrap();
+    assert!(stack.is_empty());
+    let expected: &[i64] = &[4, 2, 6, 2, 42, 1, 42, 2];
+    assert_eq!(memory.get(..).unwrap(), expected);
+}
+
 #[derive(Default)]
 struct State {
     contracts: HashMap<ContentAddress, HashMap<Key, Value>>,
@@ -128,23 +188,30 @@ impl StateRead for State {
                 return Ok(values);
             };
             values.push(value);
-            let Some(k) = next_key(key) else {
+            let Some(k) = key.next_key() else {
                 return Ok(values);
             };
             key = k;
         }
         Ok(values)
     }
-}
-fn next_key(mut key: Key) -> Option<Key> {
-    for w in key.iter_mut().rev() {
-        match *w {
-            Word::MAX => *w = Word::MIN,
-            _ => {
-                *w += 1;
-                return Some(key);
-            }
-        }
+
+    fn keys_with_prefix(
+        &self,
+        contract_addr: ContentAddress,
+        prefix: Key,
+        limit: usize,
+    ) -> Result<Vec<Key>, Self::Error> {
+        let Some(contract) = self.contracts.get(&contract_addr) else {
+            return Ok(vec![]);
+        };
+        let mut keys: Vec<Key> = contract
+            .iter()
+            .filter(|(key, value)| key.starts_with(&prefix) && !value.is_empty())
+            .map(|(key, _)| key.clone())
+            .collect();
+        keys.sort();
+        keys.truncate(limit);
+        Ok(keys)
     }
-    None
 }
@@ -0,0 +1,518 @@
+//! Symbolic execution of constraint programs over intervals.
+//!
+//! [`execute`] interprets a program where each stack word is an [`Interval`]
+//! of the values it may hold, rather than a concrete value. Words pushed with
+//! `Push` are exact, while words read from the solution (e.g. with
+//! `PredicateData`) may hold any value. This allows for proving simple
+//! properties of a program ahead of time, e.g. that a constraint can never be
+//! satisfied, or that it always panics.
+//!
+//! ```
+//! use essential_vm::{asm::short::*, symbolic};
+//!
+//! // The length of a slot's data is never negative.
+//! let ops = [PUSH(0), DLEN, PUSH(0), LT];
+//! assert!(symbolic::execute(&ops, &[]).is_unsatisfiable());
+//! ```
+//!
+//! The analysis is sound but incomplete. Execution follows a single path, so
+//! analysis stops with [`Outcome::Unknown`] at the first operation that is not
+//! supported, or whose control flow depends on a word that is not exact.
+//! Supported operations are those of the `Stack` (other than `Repeat`,
+//! `SelectRange` and the reserved stack operations), `Alu`, `Pred` (other than
+//! `EqRange` and `EqSet`), `Access` and `TotalControlFlow` groups.
+
+use crate::{
+    alu,
+    asm::{self, Op, Word},
+    error::OpResult,
+    Stack,
+};
+use alloc::{vec, vec::Vec};
+
+/// The inclusive range of values a word may hold.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Interval {
+    /// The lowest value the word may hold.
+    pub lo: Word,
+    /// The highest value the word may hold.
+    pub hi: Word,
+}
+
+/// The outcome of symbolically executing a program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// Execution always completes with a stack within the given intervals.
+    Complete(Vec<Interval>),
+    /// Execution always panics at the operation with the given index.
+    Panics(usize),
+    /// Analysis stopped at the operation with the given index.
+    ///
+    /// The operation is either unsupported or its effect depends on words
+    /// whose values are not exact.
+    Unknown(usize),
+}
+
+impl Interval {
+    /// The interval of all words.
+    pub const FULL: Self = Self {
+        lo: Word::MIN,
+        hi: Word::MAX,
+    };
+
+    /// The interval of the boolean values `0` and `1`.
+    pub const BOOL: Self = Self { lo: 0, hi: 1 };
+
+    /// The interval containing only the given word.
+    pub const fn exact(word: Word) -> Self {
+        Self { lo: word, hi: word }
+    }
+
+    /// The single word this interval contains, if exact.
+    pub fn as_exact(&self) -> Option<Word> {
+        (self.lo == self.hi).then_some(self.lo)
+    }
+
+    /// Whether the interval contains the given word.
+    pub fn contains(&self, word: Word) -> bool {
+        self.lo <= word && word <= self.hi
+    }
+
+    /// The smallest interval containing both intervals.
+    pub fn hull(self, other: Self) -> Self {
+        Self {
+            lo: self.lo.min(other.lo),
+            hi: self.hi.max(other.hi),
+        }
+    }
+
+    /// The interval of a boolean that is `true` iff `cond` is `Some(true)`,
+    /// or either value when `cond` is `None`.
+    fn bool(cond: Option<bool>) -> Self {
+        match cond {
+            Some(b) => Self::exact(b.into()),
+            None => Self::BOOL,
+        }
+    }
+
+    /// Whether this interval as a condition is always `true` or always `false`.
+    ///
+    /// Returns `Err` if the condition is never a valid boolean.
+    fn as_cond(&self) -> Result<Option<bool>, ()> {
+        match (self.contains(0), self.contains(1)) {
+            (true, true) => Ok(None),
+            (true, false) => Ok(Some(false)),
+            (false, true) => Ok(Some(true)),
+            (false, false) => Err(()),
+        }
+    }
+
+    /// Whether the word is always, never or possibly non-zero.
+    fn non_zero(&self) -> Option<bool> {
+        match (self.contains(0), self.as_exact()) {
+            (_, Some(w)) => Some(w != 0),
+            (false, None) => Some(true),
+            (true, None) => None,
+        }
+    }
+}
+
+impl Outcome {
+    /// Whether this outcome proves the program can never be satisfied as a
+    /// constraint, i.e. it never completes with a stack of exactly `[1]`.
+    pub fn is_unsatisfiable(&self) -> bool {
+        match self {
+            Self::Complete(stack) => !matches!(stack[..], [top] if top.contains(1)),
+            Self::Panics(_) => true,
+            Self::Unknown(_) => false,
+        }
+    }
+
+    /// Whether this outcome proves the program is always satisfied as a
+    /// constraint, i.e. it always completes with a stack of exactly `[1]`.
+    pub fn is_always_satisfied(&self) -> bool {
+        matches!(self, Self::Complete(stack) if stack[..] == [Interval::exact(1)])
+    }
+}
+
+/// The result of symbolically executing a single operation.
+enum Step {
+    Continue,
+    Halt,
+    Panic,
+    Unknown,
+}
+
+/// Symbolically execute the given operations starting from the given stack.
+pub fn execute(ops: &[Op], stack: &[Interval]) -> Outcome {
+    let mut stack = stack.to_vec();
+    for (pc, op) in ops.iter().enumerate() {
+        match step(*op, &mut stack) {
+            Step::Continue => (),
+            Step::Halt => break,
+            Step::Panic => return Outcome::Panics(pc),
+            Step::Unknown => return Outcome::Unknown(pc),
+        }
+        if stack.len() > Stack::SIZE_LIMIT {
+            return Outcome::Panics(pc);
+        }
+    }
+    Outcome::Complete(stack)
+}
+
+/// Pop the top `N` words, or panic on underflow.
+macro_rules! pop {
+    ($stack:ident, $n:literal) => {{
+        let Some(ix) = $stack.len().checked_sub($n) else {
+            return Step::Panic;
+        };
+        let words: [Interval; $n] = $stack[ix..].try_into().expect("len is N");
+        $stack.truncate(ix);
+        words
+    }};
+}
+
+/// Pop an exact word as a non-negative index or length.
+macro_rules! pop_usize {
+    ($stack:ident) => {{
+        let [w] = pop!($stack, 1);
+        if w.hi < 0 {
+            return Step::Panic;
+        }
+        match w.as_exact() {
+            Some(w) => w as usize,
+            None => return Step::Unknown,
+        }
+    }};
+}
+
+fn step(op: Op, stack: &mut Vec<Interval>) -> Step {
+    match op {
+        Op::Stack(op) => step_stack(op, stack),
+        Op::Alu(op) => {
+            let [a, b] = pop!(stack, 2);
+            match alu(op, a, b) {
+                Some(w) => stack.push(w),
+                None => return Step::Panic,
+            }
+            Step::Continue
+        }
+        Op::Pred(op) => step_pred(op, stack),
+        Op::Access(op) => step_access(op, stack),
+        Op::TotalControlFlow(op) => step_control_flow(op, stack),
+        Op::Crypto(_) | Op::Memory(_) | Op::ParentMemory(_) | Op::StateRead(_) | Op::Compute(_) => {
+            Step::Unknown
+        }
+    }
+}
+
+fn step_stack(op: asm::Stack, stack: &mut Vec<Interval>) -> Step {
+    match op {
+        asm::Stack::Push(w) => stack.push(Interval::exact(w)),
+        asm::Stack::Pop => {
+            pop!(stack, 1);
+        }
+        asm::Stack::Dup => {
+            let [a] = pop!(stack, 1);
+            stack.extend([a, a]);
+        }
+        asm::Stack::DupFrom => {
+            let ix = pop_usize!(stack);
+            let Some(&w) = stack.iter().rev().nth(ix) else {
+                return Step::Panic;
+            };
+            stack.push(w);
+        }
+        asm::Stack::Drop => {
+            let len = pop_usize!(stack);
+            let Some(len) = stack.len().checked_sub(len) else {
+                return Step::Panic;
+            };
+            stack.truncate(len);
+        }
+        asm::Stack::DupRange => {
+            let len = pop_usize!(stack);
+            let Some(start) = stack.len().checked_sub(len) else {
+                return Step::Panic;
+            };
+            stack.extend_from_within(start..);
+        }
+        asm::Stack::Swap => {
+            let [a, b] = pop!(stack, 2);
+            stack.extend([b, a]);
+        }
+        asm::Stack::SwapIndex => {
+            let ix = pop_usize!(stack);
+            let len = stack.len();
+            if ix >= len {
+                return Step::Panic;
+            }
+            stack.swap(len - 1, len - 1 - ix);
+        }
+        asm::Stack::Select => {
+            let [a, b, cond] = pop!(stack, 3);
+            let w = match cond.as_cond() {
+                Ok(Some(true)) => b,
+                Ok(Some(false)) => a,
+                Ok(None) => a.hull(b),
+                Err(()) => return Step::Panic,
+            };
+            stack.push(w);
+        }
+        asm::Stack::SelectRange
+        | asm::Stack::Repeat
+        | asm::Stack::RepeatEnd
+        | asm::Stack::Reserve
+        | asm::Stack::Load
+        | asm::Stack::Store => return Step::Unknown,
+    }
+    Step::Continue
+}
+
+/// The interval of the result of the ALU operation, or `None` if it always panics.
+fn alu(op: asm::Alu, a: Interval, b: Interval) -> Option<Interval> {
+    // Evaluate exact operands concretely.
+    if let (Some(a), Some(b)) = (a.as_exact(), b.as_exact()) {
+        let f: fn(Word, Word) -> OpResult<Word> = match op {
+            asm::Alu::Add => alu::add,
+            asm::Alu::Sub => alu::sub,
+            asm::Alu::Mul => alu::mul,
+            asm::Alu::Div => alu::div,
+            asm::Alu::Mod => alu::mod_,
+            asm::Alu::Shl => alu::shl,
+            asm::Alu::Shr => alu::shr,
+            asm::Alu::ShrI => alu::arithmetic_shr,
+        };
+        return f(a, b).ok().map(Interval::exact);
+    }
+    // Bounds that overflow may panic, so any value is possible.
+    let bounds = |ws: &[Option<Word>]| -> Interval {
+        match ws.iter().copied().collect::<Option<Vec<_>>>() {
+            Some(ws) => Interval {
+                lo: ws.iter().copied().min().expect("non-empty"),
+                hi: ws.iter().copied().max().expect("non-empty"),
+            },
+            None => Interval::FULL,
+        }
+    };
+    let res = match op {
+        asm::Alu::Add => bounds(&[a.lo.checked_add(b.lo), a.hi.checked_add(b.hi)]),
+        asm::Alu::Sub => bounds(&[a.lo.checked_sub(b.hi), a.hi.checked_sub(b.lo)]),
+        asm::Alu::Mul => bounds(&[
+            a.lo.checked_mul(b.lo),
+            a.lo.checked_mul(b.hi),
+            a.hi.checked_mul(b.lo),
+            a.hi.checked_mul(b.hi),
+        ]),
+        // Division is monotonic in each operand where the divisor excludes zero.
+        asm::Alu::Div if !b.contains(0) => bounds(&[
+            a.lo.checked_div(b.lo),
+            a.lo.checked_div(b.hi),
+            a.hi.checked_div(b.lo),
+            a.hi.checked_div(b.hi),
+        ]),
+        asm::Alu::Div | asm::Alu::Mod if b.as_exact() == Some(0) => return None,
+        asm::Alu::Mod if a.lo >= 0 && b.lo > 0 => Interval {
+            lo: 0,
+            hi: a.hi.min(b.hi - 1),
+        },
+        _ => Interval::FULL,
+    };
+    Some(res)
+}
+
+fn step_pred(op: asm::Pred, stack: &mut Vec<Interval>) -> Step {
+    if let asm::Pred::Not = op {
+        let [a] = pop!(stack, 1);
+        stack.push(Interval::bool(a.non_zero().map(|nz| !nz)));
+        return Step::Continue;
+    }
+    let [a, b] = pop!(stack, 2);
+    let disjoint = a.hi < b.lo || b.hi < a.lo;
+    let res = match op {
+        asm::Pred::Eq => match (a.as_exact(), b.as_exact()) {
+            (Some(a), Some(b)) => Interval::exact((a == b).into()),
+            _ if disjoint => Interval::exact(0),
+            _ => Interval::BOOL,
+        },
+        asm::Pred::Gt => Interval::bool(cmp(a.lo > b.hi, a.hi <= b.lo)),
+        asm::Pred::Lt => Interval::bool(cmp(a.hi < b.lo, a.lo >= b.hi)),
+        asm::Pred::Gte => Interval::bool(cmp(a.lo >= b.hi, a.hi < b.lo)),
+        asm::Pred::Lte => Interval::bool(cmp(a.hi <= b.lo, a.lo > b.hi)),
+        asm::Pred::And => Interval::bool(match (a.non_zero(), b.non_zero()) {
+            (Some(false), _) | (_, Some(false)) => Some(false),
+            (Some(true), Some(true)) => Some(true),
+            _ => None,
+        }),
+        asm::Pred::Or => Interval::bool(match (a.non_zero(), b.non_zero()) {
+            (Some(true), _) | (_, Some(true)) => Some(true),
+            (Some(false), Some(false)) => Some(false),
+            _ => None,
+        }),
+        asm::Pred::BitAnd | asm::Pred::BitOr => match (a.as_exact(), b.as_exact()) {
+            (Some(a), Some(b)) if op == asm::Pred::BitAnd => Interval::exact(a & b),
+            (Some(a), Some(b)) => Interval::exact(a | b),
+            _ => Interval::FULL,
+        },
+        asm::Pred::Not | asm::Pred::EqRange | asm::Pred::EqSet => return Step::Unknown,
+    };
+    stack.push(res);
+    Step::Continue
+}
+
+/// A comparison that is `Some(true)` if `always`, `Some(false)` if `never`.
+fn cmp(always: bool, never: bool) -> Option<bool> {
+    match (always, never) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    }
+}
+
+fn step_access(op: asm::Access, stack: &mut Vec<Interval>) -> Step {
+    let non_negative = Interval {
+        lo: 0,
+        hi: Word::MAX,
+    };
+    match op {
+        asm::Access::PredicateData => {
+            let len = pop_usize!(stack);
+            pop!(stack, 2);
+            if len > Stack::SIZE_LIMIT {
+                return Step::Panic;
+            }
+            stack.extend(vec![Interval::FULL; len]);
+        }
+        asm::Access::PredicateDataLen => {
+            pop!(stack, 1);
+            stack.push(non_negative);
+        }
+        asm::Access::PredicateDataSlots => stack.push(non_negative),
+        asm::Access::PredicateExists => {
+            pop!(stack, 4);
+            stack.push(Interval::BOOL);
+        }
+        asm::Access::ThisAddress | asm::Access::ThisContractAddress => {
+            stack.extend([Interval::FULL; 4]);
+        }
+        _ => return Step::Unknown,
+    }
+    Step::Continue
+}
+
+fn step_control_flow(op: asm::TotalControlFlow, stack: &mut Vec<Interval>) -> Step {
+    match op {
+        asm::TotalControlFlow::Halt => return Step::Halt,
+        asm::TotalControlFlow::HaltIf => {
+            let [cond] = pop!(stack, 1);
+            match cond.as_cond() {
+                Ok(Some(true)) => return Step::Halt,
+                Ok(Some(false)) => (),
+                Ok(None) => return Step::Unknown,
+                Err(()) => return Step::Panic,
+            }
+        }
+        asm::TotalControlFlow::JumpIf => {
+            let [_dist, cond] = pop!(stack, 2);
+            match cond.as_cond() {
+                Ok(Some(false)) => (),
+                Ok(_) => return Step::Unknown,
+                Err(()) => return Step::Panic,
+            }
+        }
+        // Only executions that do not panic continue, so continuing is sound
+        // unless the condition is always true.
+        asm::TotalControlFlow::PanicIf => {
+            let [cond] = pop!(stack, 1);
+            match cond.as_cond() {
+                Ok(Some(true)) | Err(()) => return Step::Panic,
+                Ok(_) => (),
+            }
+        }
+        asm::TotalControlFlow::Noop => (),
+    }
+    Step::Continue
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asm::short::*;
+
+    fn data(n: usize) -> Vec<Op> {
+        vec![PUSH(0), PUSH(0), PUSH(n as Word), DATA]
+    }
+
+    #[test]
+    fn exact() {
+        let out = execute(&[PUSH(6), PUSH(7), MUL, PUSH(42), EQ], &[]);
+        assert_eq!(out, Outcome::Complete(vec![Interval::exact(1)]));
+        assert!(out.is_always_satisfied());
+
+        let out = execute(&[PUSH(6), PUSH(7), MUL, PUSH(41), EQ], &[]);
+        assert!(out.is_unsatisfiable());
+    }
+
+    #[test]
+    fn panics() {
+        assert_eq!(execute(&[PUSH(1), ADD], &[]), Outcome::Panics(1));
+        assert_eq!(execute(&[PUSH(1), PUSH(0), DIV], &[]), Outcome::Panics(2));
+        assert_eq!(
+            execute(&[PUSH(Word::MAX), PUSH(1), ADD], &[]),
+            Outcome::Panics(2)
+        );
+        assert_eq!(execute(&[PUSH(1), PNCIF], &[]), Outcome::Panics(1));
+        assert_eq!(execute(&[PUSH(2), HLTIF], &[]), Outcome::Panics(1));
+        assert!(execute(&[PUSH(1), PNCIF], &[]).is_unsatisfiable());
+    }
+
+    #[test]
+    fn intervals() {
+        // A word from the solution multiplied by zero is zero.
+        let mut ops = data(1);
+        ops.extend([PUSH(0), MUL, PUSH(0), EQ]);
+        assert!(execute(&ops, &[]).is_always_satisfied());
+
+        // `x > 5 && x < 3` is unsatisfiable given `x` in `[0, 10]`.
+        let x = Interval { lo: 0, hi: 10 };
+        let ops = [DUP, PUSH(5), GT, SWAP, PUSH(3), LT, AND];
+        assert!(!execute(&ops, &[x]).is_unsatisfiable());
+        let x = Interval { lo: 0, hi: 4 };
+        assert!(execute(&ops, &[x]).is_unsatisfiable());
+
+        // Arithmetic over intervals.
+        let out = execute(&[PUSH(1), ADD, PUSH(2), MUL], &[Interval { lo: 0, hi: 4 }]);
+        assert_eq!(out, Outcome::Complete(vec![Interval { lo: 2, hi: 10 }]));
+        let out = execute(&[PUSH(-2), DIV], &[Interval { lo: -4, hi: 8 }]);
+        assert_eq!(out, Outcome::Complete(vec![Interval { lo: -4, hi: 2 }]));
+        let out = execute(&[PUSH(3), MOD], &[Interval { lo: 0, hi: 100 }]);
+        assert_eq!(out, Outcome::Complete(vec![Interval { lo: 0, hi: 2 }]));
+    }
+
+    #[test]
+    fn drop_and_dup_range() {
+        let ops = [PUSH(1), PUSH(2), PUSH(3), PUSH(2), DUPR, PUSH(3), DROP];
+        assert_eq!(
+            execute(&ops, &[]),
+            Outcome::Complete(vec![Interval::exact(1), Interval::exact(2)])
+        );
+        assert_eq!(execute(&[PUSH(1), PUSH(2), DROP], &[]), Outcome::Panics(2));
+        assert_eq!(execute(&[PUSH(1), PUSH(2), DUPR], &[]), Outcome::Panics(2));
+    }
+
+    #[test]
+    fn unknown() {
+        // Control flow depending on a word from the solution.
+        let mut ops = data(1);
+        ops.extend([HLTIF, PUSH(1)]);
+        assert_eq!(execute(&ops, &[]), Outcome::Unknown(4));
+        assert!(!execute(&ops, &[]).is_unsatisfiable());
+
+        // Unsupported operations.
+        assert_eq!(execute(&[PUSH(1), SHA2], &[]), Outcome::Unknown(1));
+
+        // A known-false jump continues.
+        let ops = [PUSH(2), PUSH(0), JMPIF, PUSH(1)];
+        assert!(execute(&ops, &[]).is_always_satisfied());
+    }
+}
@@ -8,9 +8,9 @@ use crate::{
     pred, repeat, total_control_flow, Access, GasLimit, LazyCache, Memory, OpAccess, OpGasCost,
     ProgramControlFlow, Repeat, Stack, StateReads, Vm,
 };
+use alloc::sync::Arc;
 use essential_asm::Op;
 use essential_types::ContentAddress;
-use std::sync::Arc;
 
 /// Step forward execution by the given synchronous operation.
 /// This includes the synchronous state read operation.
@@ -69,6 +69,8 @@ where
                 halt: vm.halt,
                 repeat: &vm.repeat,
                 cache: vm.cache.clone(),
+                ops_executed: &mut vm.ops_executed,
+                max_ops: vm.max_ops,
                 access,
                 state_reads: state,
                 op_access,
@@ -106,6 +108,18 @@ where
         essential_asm::StateRead::PostKeyRangeExtern => {
             crate::state_read::key_range_ext(state.post(), stack, memory)
         }
+        asm::StateRead::KeysWithPrefix => {
+            crate::state_read::keys_with_prefix(state.pre(), contract_addr, stack, memory)
+        }
+        asm::StateRead::PostKeysWithPrefix => {
+            crate::state_read::keys_with_prefix(state.post(), contract_addr, stack, memory)
+        }
+        asm::StateRead::KeyRangeBatch => {
+            crate::state_read::key_range_batch(state.pre(), contract_addr, stack, memory)
+        }
+        asm::StateRead::PostKeyRangeBatch => {
+            crate::state_read::key_range_batch(state.post(), contract_addr, stack, memory)
+        }
     }
 }
 
@@ -151,7 +165,11 @@ pub fn step_op_access(
         asm::Access::ThisContractAddress => {
             access::this_contract_address(access.this_solution(), stack)
         }
+        asm::Access::ThisProgramAddress => {
+            access::this_program_address(access.this_program.as_ref(), stack)
+        }
         asm::Access::RepeatCounter => access::repeat_counter(stack, repeat),
+        asm::Access::RepeatCounterAt => repeat::counter_at(stack, repeat),
         asm::Access::PredicateExists => access::predicate_exists(stack, access.solutions, cache),
     }
 }
@@ -175,7 +193,11 @@ pub fn step_op_crypto(op: asm::Crypto, stack: &mut Stack) -> OpResult<()> {
     match op {
         asm::Crypto::Sha256 => crypto::sha256(stack),
         asm::Crypto::VerifyEd25519 => crypto::verify_ed25519(stack),
+        asm::Crypto::VerifyEd25519Batch => crypto::verify_ed25519_batch(stack),
         asm::Crypto::RecoverSecp256k1 => crypto::recover_secp256k1(stack),
+        asm::Crypto::VerifySecp256k1 => crypto::verify_secp256k1(stack),
+        asm::Crypto::ContractAddress => crypto::contract_address(stack),
+        asm::Crypto::VerifyMerkle => crypto::verify_merkle(stack),
     }
 }
 
@@ -211,6 +233,7 @@ pub fn step_op_stack(
         asm::Stack::Drop => stack.pop_len_words(|_| Ok(())),
         asm::Stack::Dup => stack.pop1_push2(|w| Ok([w, w])),
         asm::Stack::DupFrom => stack.dup_from().map_err(From::from),
+        asm::Stack::DupRange => stack.dup_range().map_err(From::from),
         asm::Stack::Push(word) => stack.push(word).map_err(From::from),
         asm::Stack::Pop => stack.pop().map(|_| ()).map_err(From::from),
         asm::Stack::Swap => stack.pop2_push2(|a, b| Ok([b, a])),
@@ -237,6 +260,7 @@ pub fn step_op_total_control_flow(
         asm::TotalControlFlow::HaltIf => total_control_flow::halt_if(stack),
         asm::TotalControlFlow::Halt => Ok(Some(ProgramControlFlow::Halt)),
         asm::TotalControlFlow::PanicIf => total_control_flow::panic_if(stack).map(|_| None),
+        asm::TotalControlFlow::Noop => Ok(None),
     }
 }
 
@@ -276,6 +300,17 @@ pub fn step_op_memory(op: asm::Memory, stack: &mut Stack, memory: &mut Memory) -
             })?;
             Ok(())
         }
+        asm::Memory::Copy => {
+            let [dst, src, len] = stack.pop3()?;
+            memory.copy(dst, src, len)?;
+            Ok(())
+        }
+        asm::Memory::PackBytes => stack.pop1_push1(|addr| Ok(memory.pack_bytes(addr)?)),
+        asm::Memory::UnpackBytes => {
+            let [w, addr] = stack.pop2()?;
+            memory.unpack_bytes(addr, w)?;
+            Ok(())
+        }
     }
 }
 
@@ -307,7 +342,7 @@ pub(crate) mod test_util {
         types::{solution::Solution, ContentAddress, PredicateAddress},
         *,
     };
-    use std::sync::Arc;
+    use alloc::sync::Arc;
 
     pub(crate) const TEST_SET_CA: ContentAddress = ContentAddress([0xFF; 32]);
     pub(crate) const TEST_PREDICATE_CA: ContentAddress = ContentAddress([0xAA; 32]);
@@ -329,6 +364,7 @@ pub(crate) mod test_util {
         static INSTANCE: std::sync::LazyLock<Access> = std::sync::LazyLock::new(|| Access {
             solutions: test_solutions(),
             index: 0,
+            this_program: None,
         });
         &INSTANCE
     }
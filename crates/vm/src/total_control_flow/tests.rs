@@ -145,3 +145,31 @@ fn test_panic_if() {
         OpError::TotalControlFlow(TotalControlFlowError::InvalidPanicIfCondition)
     ));
 }
+
+#[test]
+fn test_noop() {
+    let access = test_access();
+    // Replacing the jump with noops leaves the positions of the following ops unchanged.
+    let ops = &[
+        asm::Stack::Push(1).into(),
+        asm::TotalControlFlow::Noop.into(),
+        asm::TotalControlFlow::Noop.into(),
+        asm::TotalControlFlow::Noop.into(),
+        asm::Stack::Push(1).into(),
+        asm::Alu::Add.into(),
+    ];
+    let op_gas_cost = &|_: &Op| 1;
+    let mut vm = Vm::default();
+    let spent = vm
+        .exec_ops(
+            ops,
+            access.clone(),
+            &EmptyState,
+            op_gas_cost,
+            GasLimit::UNLIMITED,
+        )
+        .unwrap();
+    assert_eq!(&vm.stack[..], &[2]);
+    assert_eq!(vm.pc, ops.len());
+    assert_eq!(spent, 6);
+}
@@ -0,0 +1,673 @@
+//! Static verification of programs prior to execution.
+//!
+//! [`verify`] walks every path through a program, tracking the depth of the
+//! stack along with any words whose values are known ahead of time (e.g. those
+//! pushed with `Push`). This allows for rejecting malformed programs at deploy
+//! time rather than failing when a solution is checked.
+//!
+//! The following are checked:
+//!
+//! - The stack never underflows and never exceeds [`Stack::SIZE_LIMIT`].
+//! - Stack indices and lengths known ahead of time are within bounds.
+//! - `JumpIf` distances are known ahead of time and land on an operation
+//!   within the program, or the end of the program.
+//! - `Repeat`/`RepeatEnd` and `Compute`/`ComputeEnd` are balanced.
+//!
+//! The verification is conservative. Both outcomes of a condition are
+//! considered unless the condition is known ahead of time. Once an operation
+//! consumes a number of words that is only known at runtime (e.g. `Drop` with
+//! a computed length), the depth of the stack is no longer known and depth
+//! checks are skipped for the remainder of that path.
+
+use crate::{
+    asm::{self, Op, Word},
+    error::{VerifyError, VerifyErrorKind},
+    Stack,
+};
+use alloc::{vec, vec::Vec};
+
+/// The abstract state of the stack at a point within a program.
+#[derive(Clone, Debug, PartialEq)]
+struct AbstractStack {
+    /// The words at the top of the stack, `None` where the value is unknown.
+    words: Vec<Option<Word>>,
+    /// Whether `words` is the entire stack, i.e. the depth is known.
+    exact: bool,
+}
+
+/// The result of verifying a single op.
+type StepResult = Result<Vec<(usize, AbstractStack)>, VerifyErrorKind>;
+
+/// Statically verify the given program.
+///
+/// The `stack_in` is the number of words on the stack at the start of
+/// execution, e.g. the total stack length of a node's parent outputs.
+///
+/// See the [module-level documentation][self] for the checks performed.
+pub fn verify(ops: &[Op], stack_in: usize) -> Result<(), VerifyError> {
+    if stack_in > Stack::SIZE_LIMIT {
+        return Err(VerifyError {
+            index: 0,
+            kind: VerifyErrorKind::StackOverflow,
+        });
+    }
+    let pairs = match_pairs(ops)?;
+    let mut states: Vec<Option<AbstractStack>> = vec![None; ops.len()];
+    let mut worklist = vec![];
+    if !ops.is_empty() {
+        states[0] = Some(AbstractStack {
+            words: vec![None; stack_in],
+            exact: true,
+        });
+        worklist.push(0);
+    }
+    while let Some(index) = worklist.pop() {
+        let stack = states[index].clone().expect("visited ops have a state");
+        let successors =
+            step(ops, index, &pairs, stack).map_err(|kind| VerifyError { index, kind })?;
+        for (next, stack) in successors {
+            // Reaching the end of the program ends execution.
+            let Some(state) = states.get_mut(next) else {
+                continue;
+            };
+            let changed = match state {
+                None => {
+                    *state = Some(stack);
+                    true
+                }
+                Some(state) => state.join(&stack),
+            };
+            if changed {
+                worklist.push(next);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Match each `RepeatEnd` with its `Repeat` and each `Compute` with its `ComputeEnd`.
+///
+/// Returns the index of the matching op for each op in the pair, or `usize::MAX`.
+fn match_pairs(ops: &[Op]) -> Result<Vec<usize>, VerifyError> {
+    let mut pairs = vec![usize::MAX; ops.len()];
+    let mut open: Vec<usize> = vec![];
+    let err = |index, kind| VerifyError { index, kind };
+    for (ix, op) in ops.iter().enumerate() {
+        match op {
+            Op::Stack(asm::Stack::Repeat) | Op::Compute(asm::Compute::Compute) => open.push(ix),
+            Op::Stack(asm::Stack::RepeatEnd) => match open.pop() {
+                Some(start) if ops[start] == Op::Stack(asm::Stack::Repeat) => {
+                    pairs[start] = ix;
+                    pairs[ix] = start;
+                }
+                _ => return Err(err(ix, VerifyErrorKind::UnmatchedRepeatEnd)),
+            },
+            Op::Compute(asm::Compute::ComputeEnd) => match open.pop() {
+                Some(start) if ops[start] == Op::Compute(asm::Compute::Compute) => {
+                    pairs[start] = ix;
+                    pairs[ix] = start;
+                }
+                _ => return Err(err(ix, VerifyErrorKind::UnmatchedComputeEnd)),
+            },
+            _ => (),
+        }
+    }
+    match open.pop() {
+        Some(ix) if ops[ix] == Op::Stack(asm::Stack::Repeat) => {
+            Err(err(ix, VerifyErrorKind::UnmatchedRepeat))
+        }
+        Some(ix) => Err(err(ix, VerifyErrorKind::UnmatchedCompute)),
+        None => Ok(pairs),
+    }
+}
+
+/// Apply the op at the given index to the stack, returning its successors.
+fn step(ops: &[Op], pc: usize, pairs: &[usize], mut s: AbstractStack) -> StepResult {
+    let next = pc + 1;
+    match ops[pc] {
+        Op::Stack(op) => match op {
+            asm::Stack::Push(word) => s.push(Some(word))?,
+            asm::Stack::Pop => s.pop_n(1)?,
+            asm::Stack::Dup => {
+                let w = s.pop()?;
+                s.push(w)?;
+                s.push(w)?;
+            }
+            asm::Stack::DupFrom => {
+                let w = match s.pop_index()? {
+                    Some(ix) => s.get_rev(ix)?,
+                    None => None,
+                };
+                s.push(w)?;
+            }
+            asm::Stack::Swap => {
+                let b = s.pop()?;
+                let a = s.pop()?;
+                s.push(b)?;
+                s.push(a)?;
+            }
+            asm::Stack::SwapIndex => match s.pop_index()? {
+                Some(ix) => {
+                    let w = s.get_rev(ix)?;
+                    let top = s.get_rev(0)?;
+                    s.set_rev(ix, top);
+                    s.set_rev(0, w);
+                }
+                None => s.forget_values(),
+            },
+            asm::Stack::Select => {
+                let cond = s.pop()?;
+                let b = s.pop()?;
+                let a = s.pop()?;
+                let w = match cond {
+                    Some(1) => b,
+                    Some(0) => a,
+                    _ => a.filter(|_| a == b),
+                };
+                s.push(w)?;
+            }
+            asm::Stack::SelectRange => {
+                s.pop_n(1)?;
+                match s.pop_len()? {
+                    Some(len) => {
+                        s.pop_n(len.saturating_mul(2))?;
+                        s.push_n(len)?;
+                    }
+                    None => s.forget(),
+                }
+            }
+            asm::Stack::Repeat => s.pop_n(2)?,
+            asm::Stack::RepeatEnd => {
+                let start = pairs[pc] + 1;
+                return Ok(vec![(start, s.clone()), (next, s)]);
+            }
+            asm::Stack::Reserve => match s.pop_len()? {
+                Some(len) => {
+                    let start = s.exact.then_some(s.words.len() as Word);
+                    s.push_n_with(len, Some(0))?;
+                    s.push(start)?;
+                }
+                None => {
+                    s.forget();
+                    s.push(None)?;
+                }
+            },
+            asm::Stack::Load => {
+                let w = match s.pop_index()? {
+                    Some(ix) => s.get(ix)?,
+                    None => None,
+                };
+                s.push(w)?;
+            }
+            asm::Stack::Store => {
+                let w = s.pop()?;
+                match s.pop_index()? {
+                    Some(ix) if s.exact => {
+                        let slot = s
+                            .words
+                            .get_mut(ix)
+                            .ok_or(VerifyErrorKind::StackIndexOutOfBounds(ix as Word))?;
+                        *slot = w;
+                    }
+                    _ => s.forget_values(),
+                }
+            }
+            asm::Stack::Drop => match s.pop_len()? {
+                Some(len) => s.pop_n(len)?,
+                None => s.forget(),
+            },
+            asm::Stack::DupRange => match s.pop_len()? {
+                Some(len) if len > Stack::SIZE_LIMIT => {
+                    return Err(VerifyErrorKind::StackOverflow);
+                }
+                Some(len) => {
+                    let words = (0..len)
+                        .rev()
+                        .map(|ix| s.get_rev(ix))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    for w in words {
+                        s.push(w)?;
+                    }
+                }
+                None => s.forget(),
+            },
+        },
+        Op::Pred(op) => match op {
+            asm::Pred::Eq
+            | asm::Pred::Gt
+            | asm::Pred::Lt
+            | asm::Pred::Gte
+            | asm::Pred::Lte
+            | asm::Pred::And
+            | asm::Pred::Or
+            | asm::Pred::BitAnd
+            | asm::Pred::BitOr => s.pop_n_push_n(2, 1)?,
+            asm::Pred::Not => s.pop_n_push_n(1, 1)?,
+            asm::Pred::EqRange => {
+                match s.pop_len()? {
+                    Some(len) => s.pop_n(len.saturating_mul(2))?,
+                    None => s.forget(),
+                }
+                s.push(None)?;
+            }
+            asm::Pred::EqSet => {
+                match s.pop_len()? {
+                    Some(len) => {
+                        s.pop_n(len)?;
+                        match s.pop_len()? {
+                            Some(len) => s.pop_n(len)?,
+                            None => s.forget(),
+                        }
+                    }
+                    None => s.forget(),
+                }
+                s.push(None)?;
+            }
+        },
+        Op::Alu(_) => s.pop_n_push_n(2, 1)?,
+        Op::Access(op) => match op {
+            asm::Access::ThisAddress
+            | asm::Access::ThisContractAddress
+            | asm::Access::ThisProgramAddress => s.push_n(4)?,
+            asm::Access::RepeatCounter | asm::Access::PredicateDataSlots => s.push(None)?,
+            asm::Access::RepeatCounterAt => s.pop_n_push_n(1, 1)?,
+            asm::Access::PredicateDataLen => s.pop_n_push_n(1, 1)?,
+            asm::Access::PredicateExists => s.pop_n_push_n(4, 1)?,
+            asm::Access::PredicateData => match s.pop_len()? {
+                Some(len) => s.pop_n_push_n(2, len)?,
+                None => s.forget(),
+            },
+        },
+        Op::Crypto(op) => match op {
+            asm::Crypto::Sha256 => {
+                s.pop_bytes()?;
+                s.push_n(4)?;
+            }
+            asm::Crypto::VerifyEd25519 => {
+                s.pop_n(4 + 8)?;
+                s.pop_bytes()?;
+                s.push(None)?;
+            }
+            asm::Crypto::VerifyEd25519Batch => {
+                match s.pop_len()? {
+                    // Each signature occupies at least 13 words.
+                    Some(n) if n > Stack::SIZE_LIMIT / (1 + 8 + 4) => {
+                        return Err(VerifyErrorKind::StackUnderflow);
+                    }
+                    Some(n) => {
+                        for _ in 0..n {
+                            s.pop_n(4 + 8)?;
+                            s.pop_bytes()?;
+                        }
+                    }
+                    None => s.forget(),
+                }
+                s.push(None)?;
+            }
+            asm::Crypto::RecoverSecp256k1 => s.pop_n_push_n(1 + 8 + 4, 5)?,
+            asm::Crypto::VerifySecp256k1 => s.pop_n_push_n(4 + 8 + 5, 1)?,
+            asm::Crypto::ContractAddress => {
+                match s.pop_len()? {
+                    Some(n) => s.pop_n(n.saturating_mul(4).saturating_add(4))?,
+                    None => s.forget(),
+                }
+                s.push_n(4)?;
+            }
+            asm::Crypto::VerifyMerkle => {
+                match s.pop_len()? {
+                    Some(n) => s.pop_n(n.saturating_mul(4).saturating_add(4 + 4 + 1))?,
+                    None => s.forget(),
+                }
+                s.push(None)?;
+            }
+        },
+        Op::TotalControlFlow(op) => match op {
+            asm::TotalControlFlow::Halt => return Ok(vec![]),
+            asm::TotalControlFlow::HaltIf | asm::TotalControlFlow::PanicIf => {
+                if s.pop()? == Some(1) {
+                    return Ok(vec![]);
+                }
+            }
+            asm::TotalControlFlow::JumpIf => {
+                let cond = s.pop()?;
+                let dist = s.pop()?;
+                if cond == Some(0) {
+                    return Ok(vec![(next, s)]);
+                }
+                let dist = dist.ok_or(VerifyErrorKind::UnresolvedJump)?;
+                if dist == 0 {
+                    return Err(VerifyErrorKind::JumpToSelf);
+                }
+                let target = usize::try_from(dist.unsigned_abs())
+                    .ok()
+                    .and_then(|abs| match dist < 0 {
+                        true => pc.checked_sub(abs),
+                        false => pc.checked_add(abs),
+                    })
+                    .filter(|&target| target <= ops.len())
+                    .ok_or(VerifyErrorKind::InvalidJump(dist))?;
+                if cond == Some(1) {
+                    return Ok(vec![(target, s)]);
+                }
+                return Ok(vec![(target, s.clone()), (next, s)]);
+            }
+            asm::TotalControlFlow::Noop => (),
+        },
+        Op::Memory(op) => match op {
+            asm::Memory::Alloc | asm::Memory::Load | asm::Memory::PackBytes => {
+                s.pop_n_push_n(1, 1)?
+            }
+            asm::Memory::Free => s.pop_n(1)?,
+            asm::Memory::Store | asm::Memory::UnpackBytes => s.pop_n(2)?,
+            asm::Memory::Copy => s.pop_n(3)?,
+            asm::Memory::LoadRange => match s.pop_len()? {
+                Some(len) => s.pop_n_push_n(1, len)?,
+                None => s.forget(),
+            },
+            asm::Memory::StoreRange => {
+                s.pop_n(1)?;
+                match s.pop_len()? {
+                    Some(len) => s.pop_n(len)?,
+                    None => s.forget(),
+                }
+            }
+        },
+        Op::ParentMemory(op) => match op {
+            asm::ParentMemory::Load => s.pop_n_push_n(1, 1)?,
+            asm::ParentMemory::LoadRange => match s.pop_len()? {
+                Some(len) => s.pop_n_push_n(1, len)?,
+                None => s.forget(),
+            },
+        },
+        Op::StateRead(asm::StateRead::KeyRangeBatch | asm::StateRead::PostKeyRangeBatch) => {
+            s.pop_n(1)?;
+            match s.pop_len()? {
+                Some(n_reads) => {
+                    for _ in 0..n_reads {
+                        s.pop_n(1)?;
+                        match s.pop_len()? {
+                            Some(len) => s.pop_n(len)?,
+                            None => {
+                                s.forget();
+                                break;
+                            }
+                        }
+                    }
+                }
+                None => s.forget(),
+            }
+        }
+        Op::StateRead(op) => {
+            s.pop_n(2)?;
+            match s.pop_len()? {
+                Some(len) => s.pop_n(len)?,
+                None => s.forget(),
+            }
+            if let asm::StateRead::KeyRangeExtern | asm::StateRead::PostKeyRangeExtern = op {
+                s.pop_n(4)?;
+            }
+        }
+        Op::Compute(op) => match op {
+            // Each compute program continues with the compute index pushed,
+            // while the parent resumes following the matching `ComputeEnd`.
+            asm::Compute::Compute => {
+                s.pop_n(1)?;
+                let resume = pairs[pc] + 1;
+                let mut child = s.clone();
+                child.push(None)?;
+                return Ok(vec![(resume, s), (next, child)]);
+            }
+            asm::Compute::ComputeEnd => return Ok(vec![]),
+        },
+    }
+    Ok(vec![(next, s)])
+}
+
+impl AbstractStack {
+    fn push(&mut self, word: Option<Word>) -> Result<(), VerifyErrorKind> {
+        self.push_n_with(1, word)
+    }
+
+    fn push_n(&mut self, n: usize) -> Result<(), VerifyErrorKind> {
+        self.push_n_with(n, None)
+    }
+
+    /// Push `n` copies of the given word.
+    ///
+    /// The words tracked never exceed the size limit, even if the depth is unknown.
+    fn push_n_with(&mut self, n: usize, word: Option<Word>) -> Result<(), VerifyErrorKind> {
+        let len = self.words.len().saturating_add(n);
+        if len > Stack::SIZE_LIMIT {
+            return Err(VerifyErrorKind::StackOverflow);
+        }
+        self.words.resize(len, word);
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Result<Option<Word>, VerifyErrorKind> {
+        match self.words.pop() {
+            Some(word) => Ok(word),
+            None if self.exact => Err(VerifyErrorKind::StackUnderflow),
+            None => Ok(None),
+        }
+    }
+
+    fn pop_n(&mut self, n: usize) -> Result<(), VerifyErrorKind> {
+        match self.words.len().checked_sub(n) {
+            Some(len) => self.words.truncate(len),
+            None if self.exact => return Err(VerifyErrorKind::StackUnderflow),
+            None => self.words.clear(),
+        }
+        Ok(())
+    }
+
+    fn pop_n_push_n(&mut self, pop: usize, push: usize) -> Result<(), VerifyErrorKind> {
+        self.pop_n(pop)?;
+        self.push_n(push)
+    }
+
+    /// Pop a length, erroring if known to be invalid.
+    fn pop_len(&mut self) -> Result<Option<usize>, VerifyErrorKind> {
+        self.pop()?
+            .map(|len| usize::try_from(len).map_err(|_| VerifyErrorKind::InvalidLength(len)))
+            .transpose()
+    }
+
+    /// Pop an index, erroring if known to be invalid.
+    fn pop_index(&mut self) -> Result<Option<usize>, VerifyErrorKind> {
+        self.pop()?
+            .map(|ix| usize::try_from(ix).map_err(|_| VerifyErrorKind::StackIndexOutOfBounds(ix)))
+            .transpose()
+    }
+
+    /// Pop a length in bytes followed by the words containing the bytes.
+    fn pop_bytes(&mut self) -> Result<(), VerifyErrorKind> {
+        match self.pop_len()? {
+            Some(len) => self.pop_n(len.div_ceil(core::mem::size_of::<Word>())),
+            None => {
+                self.forget();
+                Ok(())
+            }
+        }
+    }
+
+    /// The word at the given index from the bottom of the stack.
+    fn get(&self, ix: usize) -> Result<Option<Word>, VerifyErrorKind> {
+        match self.words.get(ix) {
+            Some(word) if self.exact => Ok(*word),
+            None if self.exact => Err(VerifyErrorKind::StackIndexOutOfBounds(ix as Word)),
+            _ => Ok(None),
+        }
+    }
+
+    /// The word at the given index from the top of the stack.
+    fn get_rev(&self, ix: usize) -> Result<Option<Word>, VerifyErrorKind> {
+        match self.words.iter().rev().nth(ix) {
+            Some(word) => Ok(*word),
+            None if self.exact => Err(VerifyErrorKind::StackIndexOutOfBounds(ix as Word)),
+            None => Ok(None),
+        }
+    }
+
+    /// Set the word at the given index from the top of the stack, if tracked.
+    fn set_rev(&mut self, ix: usize, word: Option<Word>) {
+        if let Some(slot) = self.words.iter_mut().rev().nth(ix) {
+            *slot = word;
+        }
+    }
+
+    /// Forget the values of all words, retaining the depth.
+    fn forget_values(&mut self) {
+        self.words.iter_mut().for_each(|w| *w = None);
+    }
+
+    /// Forget the depth of the stack.
+    fn forget(&mut self) {
+        self.words.clear();
+        self.exact = false;
+    }
+
+    /// Join the state of another path to the same op into this one.
+    ///
+    /// Returns whether or not this state changed.
+    fn join(&mut self, other: &Self) -> bool {
+        let prev = self.clone();
+        if !other.exact || self.words.len() != other.words.len() {
+            self.exact = false;
+            let len = self.words.len().min(other.words.len());
+            self.words.drain(..self.words.len() - len);
+        }
+        let other = &other.words[other.words.len() - self.words.len()..];
+        for (word, other) in self.words.iter_mut().zip(other) {
+            if *word != *other {
+                *word = None;
+            }
+        }
+        *self != prev
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asm::short::*;
+
+    fn verify_err(ops: &[Op]) -> VerifyError {
+        verify(ops, 0).unwrap_err()
+    }
+
+    #[test]
+    fn valid_programs() {
+        verify(&[], 0).unwrap();
+        verify(&[PUSH(6), PUSH(7), MUL, PUSH(42), EQ], 0).unwrap();
+        // A loop summing 1 to 5.
+        let ops = asm::ops![
+            PUSH(0),
+            PUSH(5),
+            top:
+            DUP,
+            PUSH(2),
+            DUPF,
+            ADD,
+            PUSH(2),
+            SWAPI,
+            POP,
+            PUSH(1),
+            SUB,
+            PUSH(@top),
+            PUSH(1),
+            DUPF,
+            PUSH(0),
+            GT,
+            JMPIF,
+            POP,
+        ]
+        .unwrap();
+        verify(&ops, 0).unwrap();
+        // Parent outputs provide the initial stack.
+        verify(&[ADD], 2).unwrap();
+        // Repeats and computes.
+        verify(&[PUSH(3), PUSH(1), REP, REPC, POP, REPE], 0).unwrap();
+        verify(&[PUSH(2), COM, PUSH(1), ADD, POP, COME, PUSH(1)], 0).unwrap();
+        // Dynamic lengths prevent depth checks, but values pushed afterwards are tracked.
+        let ops = [
+            PUSH(0),
+            PUSH(0),
+            DLEN,
+            DROP,
+            POP,
+            POP,
+            PUSH(1),
+            PUSH(1),
+            JMPIF,
+        ];
+        verify(&ops, 0).unwrap();
+    }
+
+    #[test]
+    fn stack_underflow() {
+        let err = verify_err(&[PUSH(1), ADD]);
+        assert_eq!(err.index, 1);
+        assert_eq!(err.kind, VerifyErrorKind::StackUnderflow);
+        assert_eq!(verify(&[ADD], 1).unwrap_err().index, 0);
+        // Underflow on one branch of a jump.
+        let err = verify_err(&[PUSH(3), PUSH(0), DLEN, JMPIF, PUSH(1), HLT, POP]);
+        assert_eq!(err.kind, VerifyErrorKind::StackUnderflow);
+        assert_eq!(err.index, 6);
+    }
+
+    #[test]
+    fn stack_overflow() {
+        let ops = [PUSH(Stack::SIZE_LIMIT as Word), RES];
+        assert_eq!(verify_err(&ops).kind, VerifyErrorKind::StackOverflow);
+        assert_eq!(
+            verify(&[], Stack::SIZE_LIMIT + 1).unwrap_err().kind,
+            VerifyErrorKind::StackOverflow,
+        );
+        // Unbounded growth within a loop is not reported.
+        verify(&[PUSH(7), PUSH(-3), PUSH(1), JMPIF], 0).unwrap();
+    }
+
+    #[test]
+    fn invalid_indices_and_lengths() {
+        let err = verify_err(&[PUSH(1), PUSH(1), DUPF]);
+        assert_eq!(err.kind, VerifyErrorKind::StackIndexOutOfBounds(1));
+        let err = verify_err(&[PUSH(1), PUSH(-1), DROP]);
+        assert_eq!(err.kind, VerifyErrorKind::InvalidLength(-1));
+        let err = verify_err(&[PUSH(1), PUSH(2), PUSH(3), DROP]);
+        assert_eq!(err.kind, VerifyErrorKind::StackUnderflow);
+        let err = verify_err(&[PUSH(1), PUSH(2), DUPR]);
+        assert_eq!(err.kind, VerifyErrorKind::StackIndexOutOfBounds(1));
+        // Duplicated words are tracked.
+        verify(&[PUSH(1), PUSH(2), PUSH(2), DUPR, DUPF], 0).unwrap();
+    }
+
+    #[test]
+    fn jumps() {
+        let err = verify_err(&[PUSH(0), PUSH(1), JMPIF]);
+        assert_eq!(err.kind, VerifyErrorKind::JumpToSelf);
+        let err = verify_err(&[PUSH(2), PUSH(1), JMPIF]);
+        assert_eq!(err.kind, VerifyErrorKind::InvalidJump(2));
+        let err = verify_err(&[PUSH(-3), PUSH(1), JMPIF]);
+        assert_eq!(err.kind, VerifyErrorKind::InvalidJump(-3));
+        // Jumping to the end of the program is valid.
+        verify(&[PUSH(1), PUSH(1), JMPIF], 0).unwrap();
+        // A known false condition never jumps.
+        verify(&[PUSH(5), PUSH(0), JMPIF], 0).unwrap();
+        let err = verify_err(&[PUSH(0), DLEN, PUSH(1), JMPIF]);
+        assert_eq!(err.kind, VerifyErrorKind::UnresolvedJump);
+    }
+
+    #[test]
+    fn unbalanced_pairs() {
+        let err = verify_err(&[PUSH(1), PUSH(1), REP]);
+        assert_eq!(err.index, 2);
+        assert_eq!(err.kind, VerifyErrorKind::UnmatchedRepeat);
+        let err = verify_err(&[REPE]);
+        assert_eq!(err.kind, VerifyErrorKind::UnmatchedRepeatEnd);
+        let err = verify_err(&[PUSH(1), COM]);
+        assert_eq!(err.kind, VerifyErrorKind::UnmatchedCompute);
+        let err = verify_err(&[PUSH(1), PUSH(1), REP, COME, REPE]);
+        assert_eq!(err.kind, VerifyErrorKind::UnmatchedComputeEnd);
+    }
+}
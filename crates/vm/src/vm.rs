@@ -1,16 +1,27 @@
 //! The VM state machine, used to drive forward execution.
 
 use crate::{
-    error::{EvalError, EvalResult, ExecError, OpError, OutOfGasError},
+    asm::ToOpcode,
+    error::{
+        EvalError, EvalResult, ExecError, InvalidEvaluation, OpError, OutOfGasError,
+        ParentOutputsError,
+    },
     sync::step_op,
-    Access, BytecodeMapped, Gas, GasLimit, LazyCache, Memory, Op, OpAccess, OpGasCost,
-    ProgramControlFlow, Repeat, Stack, StateReads,
+    Access, BytecodeMapped, Coverage, ExecHooks, ExecMetrics, Gas, GasLimit, GasProfile, LazyCache,
+    Memory, Op, OpAccess, OpGasCost, ProgramControlFlow, Repeat, Stack, StateReads,
 };
-use essential_types::convert::bool_from_word;
-use std::sync::Arc;
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use essential_types::{convert::bool_from_word, Word};
+use serde::{Deserialize, Serialize};
 
 /// The operation execution state of the VM.
-#[derive(Clone, Debug, Default, PartialEq)]
+///
+/// Serializes via its [`VmSnapshot`][crate::VmSnapshot].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(into = "crate::VmSnapshot", try_from = "crate::VmSnapshot")]
 pub struct Vm {
     /// The program counter, i.e. index of the current operation within the program.
     pub pc: usize,
@@ -31,9 +42,46 @@ pub struct Vm {
     pub repeat: Repeat,
     /// Lazily cached data for the VM.
     pub cache: Arc<LazyCache>,
+    /// The number of operations executed so far, including those executed
+    /// by compute programs.
+    pub ops_executed: u64,
+    /// The maximum number of operations that may be executed, if any.
+    ///
+    /// This bounds execution independently of gas metering. Like running out
+    /// of gas, exceeding the limit leaves the VM in the state prior to the
+    /// operation that would exceed it.
+    ///
+    /// Compute programs are each limited to the operations remaining when the
+    /// `Compute` operation begins, and the limit is checked again once they
+    /// complete.
+    pub max_ops: Option<u64>,
 }
 
 impl Vm {
+    /// Create a new `Vm` with its stack and memory initialised from the outputs
+    /// of parent programs.
+    ///
+    /// The stacks and memories of the parents are each concatenated in the
+    /// given order. This is how a predicate node receives the outputs of its
+    /// parents during checking.
+    pub fn with_parent_outputs(
+        parents: &[Arc<(Stack, Memory)>],
+    ) -> Result<Self, ParentOutputsError> {
+        let stack: Vec<Word> = parents
+            .iter()
+            .flat_map(|parent| parent.0.iter().copied())
+            .collect();
+        let memory: Vec<Word> = parents
+            .iter()
+            .flat_map(|parent| parent.1.iter().copied())
+            .collect();
+        Ok(Self {
+            stack: stack.try_into()?,
+            memory: memory.try_into()?,
+            ..Default::default()
+        })
+    }
+
     /// Execute the given operations from the current state of the VM.
     ///
     /// This function uses synchronous state reading and is intended for use
@@ -118,78 +166,348 @@ impl Vm {
         let mut gas_spent: u64 = 0;
 
         // Execute each operation
-        while let Some(res) = op_access.op_access(self.pc) {
-            let op = res.map_err(|err| ExecError(self.pc, err.into()))?;
-
-            // Calculate the gas cost of the operation.
-            let op_gas = op_gas_cost.op_gas_cost(&op);
-
-            // Check that the operation wouldn't exceed gas limit.
-            let next_spent = gas_spent
-                .checked_add(op_gas)
-                .filter(|&spent| spent <= gas_limit.total)
-                .ok_or(ExecError(
-                    self.pc,
-                    OutOfGasError {
-                        spent: gas_spent,
-                        op_gas,
-                        limit: gas_limit.total,
-                    }
-                    .into(),
-                ))?;
+        while self.exec_step(
+            &access,
+            state_reads,
+            &op_access,
+            op_gas_cost,
+            gas_limit,
+            &mut gas_spent,
+        )? {}
+        Ok(gas_spent)
+    }
 
-            // Update the gas spent.
-            gas_spent = next_spent;
+    /// Resume execution from the current state of the VM with a fresh gas limit.
+    ///
+    /// When execution runs out of gas (see [`ExecError::out_of_gas`]), the
+    /// operation that would exceed the limit is not executed. The VM is left
+    /// in the state prior to that operation with the `pc` pointing to it, and
+    /// the gas spent up to that point is reported by the error. Resuming with
+    /// the same operations continues execution from that operation, as though
+    /// execution had never been interrupted.
+    ///
+    /// Returns the gas spent since resuming.
+    pub fn resume<S, OA>(
+        &mut self,
+        access: Access,
+        state_reads: &S,
+        op_access: OA,
+        op_gas_cost: &impl OpGasCost,
+        gas_limit: GasLimit,
+    ) -> Result<Gas, ExecError<S::Error>>
+    where
+        S: StateReads,
+        OA: OpAccess<Op = Op>,
+        OA::Error: Into<OpError<S::Error>>,
+    {
+        self.exec(access, state_reads, op_access, op_gas_cost, gas_limit)
+    }
 
-            // Execute the operation.
-            let res = step_op(
-                access.clone(),
-                op,
-                self,
+    /// Execute the given operations, recording the gas spent by each into `profile`.
+    ///
+    /// Behaves the same as [`Vm::exec`], but also aggregates the gas spent and
+    /// number of invocations per opcode and per program counter. The profile
+    /// includes all operations executed prior to any error, allowing for
+    /// profiling programs that run out of gas.
+    ///
+    /// As each operation is accessed twice, this is slower than [`Vm::exec`]
+    /// and is intended for tooling rather than validation.
+    pub fn exec_profiled<S, OA>(
+        &mut self,
+        access: Access,
+        state_reads: &S,
+        op_access: OA,
+        op_gas_cost: &impl OpGasCost,
+        gas_limit: GasLimit,
+        profile: &mut GasProfile,
+    ) -> Result<Gas, ExecError<S::Error>>
+    where
+        S: StateReads,
+        OA: OpAccess<Op = Op>,
+        OA::Error: Into<OpError<S::Error>>,
+    {
+        let mut gas_spent: u64 = 0;
+        loop {
+            let pc = self.pc;
+            let opcode = match op_access.op_access(pc) {
+                Some(Ok(op)) => op.to_opcode(),
+                Some(Err(err)) => return Err(ExecError(pc, err.into())),
+                None => return Ok(gas_spent),
+            };
+            let gas_before = gas_spent;
+            let res = self.exec_step(
+                &access,
                 state_reads,
-                op_access.clone(),
+                &op_access,
                 op_gas_cost,
                 gas_limit,
+                &mut gas_spent,
             );
+            // An operation that would exceed the gas limit is never executed.
+            if !matches!(res, Err(ExecError(_, OpError::OutOfGas(_)))) {
+                profile.record(pc, opcode, gas_spent - gas_before);
+            }
+            if !res? {
+                return Ok(gas_spent);
+            }
+        }
+    }
 
-            #[cfg(feature = "tracing")]
-            crate::trace_op_res(
+    /// Execute the given operations, recording the operations and branches
+    /// executed into `coverage`.
+    ///
+    /// Behaves the same as [`Vm::exec`], but also records each program
+    /// counter executed and, for conditional operations, the outcome of the
+    /// condition. The coverage includes all operations executed prior to any
+    /// error.
+    ///
+    /// As each operation is accessed twice, this is slower than [`Vm::exec`]
+    /// and is intended for tooling rather than validation.
+    pub fn exec_with_coverage<S, OA>(
+        &mut self,
+        access: Access,
+        state_reads: &S,
+        op_access: OA,
+        op_gas_cost: &impl OpGasCost,
+        gas_limit: GasLimit,
+        coverage: &mut Coverage,
+    ) -> Result<Gas, ExecError<S::Error>>
+    where
+        S: StateReads,
+        OA: OpAccess<Op = Op>,
+        OA::Error: Into<OpError<S::Error>>,
+    {
+        let mut gas_spent: u64 = 0;
+        loop {
+            let pc = self.pc;
+            let op = match op_access.op_access(pc) {
+                Some(Ok(op)) => op,
+                Some(Err(err)) => return Err(ExecError(pc, err.into())),
+                None => return Ok(gas_spent),
+            };
+            // The condition is always at the top of the stack.
+            let condition = crate::coverage::is_conditional(&op)
+                .then(|| self.stack.last().copied())
+                .flatten();
+            let res = self.exec_step(
+                &access,
+                state_reads,
                 &op_access,
-                self.pc,
-                &self.stack,
-                &self.memory,
-                &self.parent_memory,
-                self.halt,
-                &res,
+                op_gas_cost,
+                gas_limit,
+                &mut gas_spent,
             );
+            // Operations exceeding the gas or operation limits are never executed.
+            if !matches!(
+                res,
+                Err(ExecError(_, OpError::OutOfGas(_) | OpError::OpLimit(_)))
+            ) {
+                coverage.record(pc);
+                if let Some(condition) = condition {
+                    coverage.record_branch(pc, condition != 0);
+                }
+            }
+            if !res? {
+                return Ok(gas_spent);
+            }
+        }
+    }
 
-            // Handle the result of the operation.
-            let update = match res {
-                Ok(update) => update,
-                Err(err) => return Err(ExecError(self.pc, err)),
-            };
+    /// Execute the given operations, recording the resources used into `metrics`.
+    ///
+    /// Behaves the same as [`Vm::exec`], but also counts the operations and
+    /// state reads executed and tracks the peak stack and memory sizes. The
+    /// metrics include all operations executed prior to any error.
+    pub fn exec_with_metrics<S, OA>(
+        &mut self,
+        access: Access,
+        state_reads: &S,
+        op_access: OA,
+        op_gas_cost: &impl OpGasCost,
+        gas_limit: GasLimit,
+        metrics: &mut ExecMetrics,
+    ) -> Result<Gas, ExecError<S::Error>>
+    where
+        S: StateReads,
+        OA: OpAccess<Op = Op>,
+        OA::Error: Into<OpError<S::Error>>,
+    {
+        self.exec_with_hooks(
+            access,
+            state_reads,
+            op_access,
+            op_gas_cost,
+            gas_limit,
+            metrics,
+        )
+    }
+
+    /// Execute the given operations, invoking the given hooks before and after each.
+    ///
+    /// Behaves the same as [`Vm::exec`]. See [`ExecHooks`] for details on
+    /// when each hook is invoked.
+    pub fn exec_with_hooks<S, OA>(
+        &mut self,
+        access: Access,
+        state_reads: &S,
+        op_access: OA,
+        op_gas_cost: &impl OpGasCost,
+        gas_limit: GasLimit,
+        hooks: &mut impl ExecHooks,
+    ) -> Result<Gas, ExecError<S::Error>>
+    where
+        S: StateReads,
+        OA: OpAccess<Op = Op>,
+        OA::Error: Into<OpError<S::Error>>,
+    {
+        let mut gas_spent: u64 = 0;
+        while self.exec_step_with_hooks(
+            &access,
+            state_reads,
+            &op_access,
+            op_gas_cost,
+            gas_limit,
+            &mut gas_spent,
+            hooks,
+        )? {}
+        Ok(gas_spent)
+    }
+
+    /// Execute the operation at the current program counter.
+    ///
+    /// The gas spent by the operation is added to `gas_spent`. Returns `false`
+    /// in the case that execution has completed, either by reaching the end of
+    /// the operations or by halting.
+    pub(crate) fn exec_step<S, OA>(
+        &mut self,
+        access: &Access,
+        state_reads: &S,
+        op_access: &OA,
+        op_gas_cost: &impl OpGasCost,
+        gas_limit: GasLimit,
+        gas_spent: &mut Gas,
+    ) -> Result<bool, ExecError<S::Error>>
+    where
+        S: StateReads,
+        OA: OpAccess<Op = Op>,
+        OA::Error: Into<OpError<S::Error>>,
+    {
+        self.exec_step_with_hooks(
+            access,
+            state_reads,
+            op_access,
+            op_gas_cost,
+            gas_limit,
+            gas_spent,
+            &mut (),
+        )
+    }
+
+    /// Execute the operation at the current program counter, invoking the
+    /// given hooks before and after.
+    #[allow(clippy::too_many_arguments)]
+    fn exec_step_with_hooks<S, OA>(
+        &mut self,
+        access: &Access,
+        state_reads: &S,
+        op_access: &OA,
+        op_gas_cost: &impl OpGasCost,
+        gas_limit: GasLimit,
+        gas_spent: &mut Gas,
+        hooks: &mut impl ExecHooks,
+    ) -> Result<bool, ExecError<S::Error>>
+    where
+        S: StateReads,
+        OA: OpAccess<Op = Op>,
+        OA::Error: Into<OpError<S::Error>>,
+    {
+        let Some(res) = op_access.op_access(self.pc) else {
+            return Ok(false);
+        };
+        let op = res.map_err(|err| ExecError(self.pc, err.into()))?;
+
+        // Check that the operation wouldn't exceed the operation limit.
+        if let Some(max_ops) = self.max_ops {
+            if self.ops_executed >= max_ops {
+                return Err(ExecError(self.pc, OpError::OpLimit(max_ops)));
+            }
+        }
+
+        // Calculate the gas cost of the operation.
+        let op_gas = op_gas_cost
+            .op_gas_cost(&op)
+            .saturating_add(op_gas_cost.op_dynamic_gas_cost(&op, &self.stack));
 
-            // Update the program counter.
-            match update {
-                Some(ProgramControlFlow::Pc(new_pc)) => self.pc = new_pc,
-                Some(ProgramControlFlow::Halt) => break,
-                Some(ProgramControlFlow::ComputeEnd) => {
-                    self.pc += 1;
-                    break;
+        // Check that the operation wouldn't exceed gas limit.
+        let next_spent = gas_spent
+            .checked_add(op_gas)
+            .filter(|&spent| spent <= gas_limit.total)
+            .ok_or(ExecError(
+                self.pc,
+                OutOfGasError {
+                    spent: *gas_spent,
+                    op_gas,
+                    limit: gas_limit.total,
                 }
-                // TODO: compute gas_spent is not inferrable above
-                Some(ProgramControlFlow::ComputeResult((pc, gas, halt))) => {
-                    gas_spent += gas;
-                    self.pc = pc;
-                    self.halt |= halt;
-                    if self.halt {
-                        break;
-                    }
+                .into(),
+            ))?;
+
+        // Update the gas spent.
+        *gas_spent = next_spent;
+        self.ops_executed += 1;
+
+        let pc = self.pc;
+        hooks.before_op(pc, &op, &self.stack, &self.memory);
+
+        // Execute the operation.
+        let res = step_op(
+            access.clone(),
+            op,
+            self,
+            state_reads,
+            op_access.clone(),
+            op_gas_cost,
+            gas_limit,
+        );
+
+        #[cfg(feature = "tracing")]
+        crate::trace_op_res(
+            op_access,
+            self.pc,
+            &self.stack,
+            &self.memory,
+            &self.parent_memory,
+            self.halt,
+            &res,
+        );
+
+        // Handle the result of the operation.
+        let update = match res {
+            Ok(update) => update,
+            Err(err) => return Err(ExecError(self.pc, err)),
+        };
+        hooks.after_op(pc, &op, &self.stack, &self.memory);
+
+        // Update the program counter.
+        match update {
+            Some(ProgramControlFlow::Pc(new_pc)) => self.pc = new_pc,
+            Some(ProgramControlFlow::Halt) => return Ok(false),
+            Some(ProgramControlFlow::ComputeEnd) => {
+                self.pc += 1;
+                return Ok(false);
+            }
+            // TODO: compute gas_spent is not inferrable above
+            Some(ProgramControlFlow::ComputeResult((pc, gas, halt))) => {
+                *gas_spent += gas;
+                self.pc = pc;
+                self.halt |= halt;
+                if self.halt {
+                    return Ok(false);
                 }
-                None => self.pc += 1,
             }
+            None => self.pc += 1,
         }
-        Ok(gas_spent)
+        Ok(true)
     }
 
     /// Evaluate a slice of synchronous operations and return their boolean result.
@@ -225,12 +543,69 @@ impl Vm {
         OA::Error: Into<OpError<S::Error>>,
         S: StateReads,
     {
-        self.exec(access, state, op_access, op_gas_cost, gas_limit)?;
+        self.eval_traced(op_access, access, state, op_gas_cost, gas_limit, 0)
+    }
 
-        let word = match self.stack.last() {
-            Some(&w) => w,
-            None => return Err(EvalError::InvalidEvaluation(self.stack.clone())),
-        };
-        bool_from_word(word).ok_or_else(|| EvalError::InvalidEvaluation(self.stack.clone()))
+    /// Evaluate the operations of a single synchronous program, recording the
+    /// last `trace_len` operations executed.
+    ///
+    /// This is the same as [`Vm::eval`], but in the case that evaluation does
+    /// not result in a boolean, the [`InvalidEvaluation`] error includes the
+    /// trace of the last operations executed.
+    pub fn eval_traced<OA, S>(
+        &mut self,
+        op_access: OA,
+        access: Access,
+        state: &S,
+        op_gas_cost: &impl OpGasCost,
+        gas_limit: GasLimit,
+        trace_len: usize,
+    ) -> EvalResult<bool, S::Error>
+    where
+        OA: OpAccess<Op = Op>,
+        OA::Error: Into<OpError<S::Error>>,
+        S: StateReads,
+    {
+        let mut gas_spent: u64 = 0;
+        let mut last_pc = None;
+        let mut trace = VecDeque::with_capacity(trace_len);
+        loop {
+            let (pc, ops_executed) = (self.pc, self.ops_executed);
+            let cont = self.exec_step(
+                &access,
+                state,
+                &op_access,
+                op_gas_cost,
+                gas_limit,
+                &mut gas_spent,
+            )?;
+            // Reaching the end of the operations executes nothing.
+            if self.ops_executed != ops_executed {
+                last_pc = Some(pc);
+                if trace_len > 0 {
+                    if let Some(Ok(op)) = op_access.op_access(pc) {
+                        if trace.len() == trace_len {
+                            trace.pop_front();
+                        }
+                        trace.push_back((pc, op));
+                    }
+                }
+            }
+            if !cont {
+                break;
+            }
+        }
+
+        self.stack
+            .last()
+            .and_then(|&word| bool_from_word(word))
+            .ok_or_else(|| {
+                let invalid = InvalidEvaluation {
+                    pc: last_pc,
+                    stack: self.stack.clone(),
+                    trace: trace.into(),
+                };
+                EvalError::InvalidEvaluation(Box::new(invalid))
+            })
     }
 }
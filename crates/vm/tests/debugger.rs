@@ -0,0 +1,103 @@
+//! Tests for stepping through execution with the `Debugger`.
+
+mod util;
+
+use essential_vm::{
+    asm::{short::*, Op, ToOpcode},
+    debugger::{Breakpoint, Debugger, Stop},
+    error::{ExecError, OpError},
+    GasLimit, Vm,
+};
+use util::*;
+
+#[test]
+fn step_and_resume() {
+    let ops: &[Op] = &[PUSH(6), PUSH(7), MUL, PUSH(1), ADD];
+    let gas_cost = |_: &Op| 1;
+    let state = State::EMPTY;
+    let access = test_access().clone();
+    let mut dbg = Debugger::new(
+        Vm::default(),
+        access,
+        &state,
+        ops,
+        &gas_cost,
+        GasLimit::UNLIMITED,
+    );
+    let add = Breakpoint::Opcode(ADD.to_opcode());
+    assert!(dbg.add_breakpoint(add));
+    assert!(!dbg.add_breakpoint(add));
+
+    assert_eq!(dbg.step().unwrap(), Stop::Step);
+    assert_eq!(dbg.vm().pc, 1);
+    assert_eq!(&dbg.vm().stack[..], &[6]);
+    assert_eq!(dbg.gas_spent(), 1);
+
+    // Stops prior to executing the `ADD`.
+    assert_eq!(dbg.resume().unwrap(), Stop::Breakpoint(add));
+    assert_eq!(dbg.vm().pc, 4);
+    assert_eq!(dbg.next_op().unwrap().unwrap(), ADD);
+    assert_eq!(&dbg.vm().stack[..], &[42, 1]);
+
+    // Resuming from a breakpoint makes progress.
+    assert_eq!(dbg.resume().unwrap(), Stop::Finished(5));
+    assert!(dbg.is_finished());
+    assert_eq!(dbg.step().unwrap(), Stop::Finished(5));
+    assert_eq!(&dbg.into_vm().stack[..], &[43]);
+}
+
+#[test]
+fn pc_breakpoints_and_repeats() {
+    // Sum the counter of a repeat counting up to 3.
+    let ops: &[Op] = &[PUSH(0), PUSH(3), PUSH(1), REP, REPC, ADD, REPE];
+    let gas_cost = |_: &Op| 1;
+    let state = State::EMPTY;
+    let mut dbg = Debugger::new(
+        Vm::default(),
+        test_access().clone(),
+        &state,
+        ops,
+        &gas_cost,
+        GasLimit::UNLIMITED,
+    );
+    dbg.add_breakpoint(Breakpoint::Pc(5));
+
+    let mut counters = vec![];
+    while let Stop::Breakpoint(Breakpoint::Pc(5)) = dbg.resume().unwrap() {
+        let repeat = &dbg.vm().repeat;
+        assert_eq!(repeat.len(), 1);
+        counters.push(repeat.counter().unwrap());
+    }
+    assert_eq!(counters, [0, 1, 2]);
+    assert!(dbg.vm().repeat.is_empty());
+    assert_eq!(&dbg.vm().stack[..], &[3]);
+
+    assert!(dbg.remove_breakpoint(Breakpoint::Pc(5)));
+    assert!(dbg.breakpoints().is_empty());
+}
+
+#[test]
+fn errors_and_state_edits() {
+    let ops: &[Op] = &[PUSH(1), ADD, HLT, PUSH(1)];
+    let gas_cost = |_: &Op| 1;
+    let state = State::EMPTY;
+    let mut dbg = Debugger::new(
+        Vm::default(),
+        test_access().clone(),
+        &state,
+        ops,
+        &gas_cost,
+        GasLimit::UNLIMITED,
+    );
+    dbg.step().unwrap();
+    assert!(matches!(
+        dbg.step().unwrap_err(),
+        ExecError(1, OpError::Stack(_))
+    ));
+
+    // Fix up the stack and retry.
+    dbg.vm_mut().stack = vec![1, 41].try_into().unwrap();
+    assert_eq!(dbg.step().unwrap(), Stop::Step);
+    assert_eq!(dbg.resume().unwrap(), Stop::Finished(4));
+    assert_eq!(&dbg.vm().stack[..], &[42]);
+}
@@ -0,0 +1,101 @@
+//! Tests for the differential execution harness.
+
+mod util;
+
+use essential_vm::{
+    asm::{short::*, Op, Word},
+    differential::{self, Divergence, Execution, Path},
+    GasLimit, Vm,
+};
+use util::*;
+
+fn check(ops: &[Op], gas_limit: GasLimit) -> Execution {
+    differential::check(
+        &Vm::default(),
+        ops,
+        test_access().clone(),
+        &State::EMPTY,
+        &|_: &Op| 1,
+        gas_limit,
+    )
+    .unwrap_or_else(|div| panic!("{div}"))
+}
+
+#[test]
+fn paths_agree() {
+    let programs: &[&[Op]] = &[
+        &[],
+        &[PUSH(6), PUSH(7), MUL, PUSH(42), EQ],
+        // Memory and jumps.
+        &[
+            PUSH(2),
+            ALOC,
+            POP,
+            PUSH(Word::MAX),
+            PUSH(1),
+            STO,
+            PUSH(3),
+            PUSH(1),
+            JMPIF,
+            PUSH(0),
+            PUSH(0),
+            PUSH(1),
+            LOD,
+        ],
+        // Repeat.
+        &[PUSH(0), PUSH(5), PUSH(1), REP, PUSH(1), ADD, REPE],
+    ];
+    for ops in programs {
+        let exec = check(ops, GasLimit::UNLIMITED);
+        assert_eq!(exec.path, Path::Ops);
+        assert!(exec.result.is_ok());
+    }
+    let exec = check(&[PUSH(6), PUSH(7), MUL], GasLimit::UNLIMITED);
+    assert_eq!(&exec.vm.stack[..], &[42]);
+    assert_eq!(exec.result, Ok(3));
+}
+
+#[test]
+fn errors_agree() {
+    // An operation error.
+    let exec = check(&[PUSH(1), PUSH(0), DIV], GasLimit::UNLIMITED);
+    assert_eq!(exec.result.unwrap_err().0, 2);
+
+    // Running out of gas.
+    let exec = check(
+        &[PUSH(1), PUSH(2), ADD, PUSH(3)],
+        GasLimit {
+            per_yield: 2,
+            total: 2,
+        },
+    );
+    assert_eq!(exec.result.unwrap_err().0, 2);
+    assert_eq!(&exec.vm.stack[..], &[1, 2]);
+}
+
+#[test]
+fn exec_all_runs_each_path() {
+    let execs = differential::exec_all(
+        &Vm::default(),
+        &[PUSH(1)],
+        test_access().clone(),
+        &State::EMPTY,
+        &|_: &Op| 1,
+        GasLimit::UNLIMITED,
+    );
+    let paths: Vec<_> = execs.iter().map(|exec| exec.path).collect();
+    assert_eq!(paths, Path::ALL);
+}
+
+#[test]
+fn divergence_display() {
+    let expected = check(&[PUSH(1)], GasLimit::UNLIMITED);
+    let mut found = expected.clone();
+    found.path = Path::Bytecode;
+    found.result = Ok(2);
+    let div = Divergence { expected, found };
+    assert_eq!(
+        div.to_string(),
+        "execution via Bytecode diverged from Ops: result Ok(2) != Ok(1)"
+    );
+}
@@ -0,0 +1,149 @@
+//! Tests for executing bytecode fetched on demand with `LazyBytecode`.
+
+mod util;
+
+use essential_vm::{
+    asm::{self, short::*, Op},
+    error::OpError,
+    types::ContentAddress,
+    BytecodeSource, GasLimit, LazyBytecode, Vm,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use util::*;
+
+/// Serves the bytecode from memory, counting the fetches.
+struct Source {
+    bytes: Vec<u8>,
+    fetches: AtomicUsize,
+}
+
+impl Source {
+    fn new(ops: &[Op]) -> Self {
+        Self {
+            bytes: asm::to_bytes(ops.iter().copied()).collect(),
+            fetches: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl BytecodeSource for Source {
+    type Error = String;
+    fn fetch(
+        &self,
+        _program: &ContentAddress,
+        offset: usize,
+        len: usize,
+    ) -> Result<Vec<u8>, Self::Error> {
+        self.fetches.fetch_add(1, Ordering::Relaxed);
+        if offset > self.bytes.len() {
+            return Err("offset out of bounds".to_string());
+        }
+        let end = self.bytes.len().min(offset + len);
+        Ok(self.bytes[offset..end].to_vec())
+    }
+}
+
+impl BytecodeSource for &Source {
+    type Error = String;
+    fn fetch(
+        &self,
+        program: &ContentAddress,
+        offset: usize,
+        len: usize,
+    ) -> Result<Vec<u8>, Self::Error> {
+        (**self).fetch(program, offset, len)
+    }
+}
+
+#[test]
+fn fetches_in_chunks() {
+    // 4 pushes of 9 bytes each followed by 3 single byte ops.
+    let ops = &[PUSH(6), PUSH(7), MUL, PUSH(2), PUSH(21), MUL, EQ];
+    let source = Source::new(ops);
+    let bytecode = LazyBytecode::with_chunk_size(&source, ContentAddress([0; 32]), 8);
+    assert_eq!(bytecode.fetched_len(), 0);
+
+    let mut vm = Vm::default();
+    let state = State::EMPTY;
+    vm.exec(
+        test_access().clone(),
+        &state,
+        bytecode.clone(),
+        &|_: &Op| 1,
+        GasLimit::UNLIMITED,
+    )
+    .unwrap();
+    assert_eq!(&vm.stack[..], &[1]);
+
+    // 39 bytes in chunks of 8, where the final chunk is short.
+    assert_eq!(bytecode.fetched_len(), 39);
+    assert_eq!(source.fetches.load(Ordering::Relaxed), 5);
+
+    // Executing again requires no further fetches.
+    let mut vm = Vm::default();
+    vm.exec(
+        test_access().clone(),
+        &state,
+        bytecode,
+        &|_: &Op| 1,
+        GasLimit::UNLIMITED,
+    )
+    .unwrap();
+    assert_eq!(source.fetches.load(Ordering::Relaxed), 5);
+}
+
+#[test]
+fn only_fetches_what_is_executed() {
+    let mut ops = vec![PUSH(1), HLT];
+    ops.extend(std::iter::repeat_n(PUSH(0), 100));
+    let source = Source::new(&ops);
+    let bytecode = LazyBytecode::with_chunk_size(&source, ContentAddress([0; 32]), 16);
+    let mut vm = Vm::default();
+    vm.exec(
+        test_access().clone(),
+        &State::EMPTY,
+        bytecode.clone(),
+        &|_: &Op| 1,
+        GasLimit::UNLIMITED,
+    )
+    .unwrap();
+    assert_eq!(bytecode.fetched_len(), 16);
+}
+
+#[test]
+fn fetch_and_decode_errors() {
+    struct Failing;
+    impl BytecodeSource for Failing {
+        type Error = &'static str;
+        fn fetch(&self, _: &ContentAddress, _: usize, _: usize) -> Result<Vec<u8>, Self::Error> {
+            Err("unavailable")
+        }
+    }
+    let bytecode = LazyBytecode::new(Failing, ContentAddress([0; 32]));
+    let err = Vm::default()
+        .exec(
+            test_access().clone(),
+            &State::EMPTY,
+            bytecode,
+            &|_: &Op| 1,
+            GasLimit::UNLIMITED,
+        )
+        .unwrap_err();
+    assert!(matches!(err.1, OpError::OpAccess(ref msg) if msg == "unavailable"));
+
+    // A truncated push.
+    let mut source = Source::new(&[PUSH(1), PUSH(2)]);
+    source.bytes.truncate(12);
+    let bytecode = LazyBytecode::with_chunk_size(&source, ContentAddress([0; 32]), 4);
+    let err = Vm::default()
+        .exec(
+            test_access().clone(),
+            &State::EMPTY,
+            bytecode,
+            &|_: &Op| 1,
+            GasLimit::UNLIMITED,
+        )
+        .unwrap_err();
+    assert_eq!(err.0, 1);
+    assert!(matches!(err.1, OpError::FromBytes(_)));
+}
@@ -0,0 +1,65 @@
+//! Tests for executing bytecode mapped on demand with `LazyBytecodeMapped`.
+
+mod util;
+
+use essential_vm::{
+    asm::{self, short::*, Op},
+    error::OpError,
+    GasLimit, LazyBytecodeMapped, Vm,
+};
+use util::*;
+
+#[test]
+fn maps_only_what_is_executed() {
+    let mut ops = vec![PUSH(6), PUSH(7), MUL, PUSH(42), EQ, HLT];
+    ops.extend(std::iter::repeat_n(PUSH(0), 100));
+    let bytes: Vec<u8> = asm::to_bytes(ops.iter().copied()).collect();
+    let bytecode = LazyBytecodeMapped::new(&bytes[..]);
+    assert_eq!(bytecode.mapped_len(), 0);
+    assert_eq!(bytecode.bytecode(), &bytes[..]);
+
+    let mut vm = Vm::default();
+    vm.exec(
+        test_access().clone(),
+        &State::EMPTY,
+        bytecode.clone(),
+        &|_: &Op| 1,
+        GasLimit::UNLIMITED,
+    )
+    .unwrap();
+    assert_eq!(&vm.stack[..], &[1]);
+    // Clones share the mapping.
+    assert_eq!(bytecode.mapped_len(), 6);
+}
+
+#[test]
+fn validates_lazily() {
+    // Invalid trailing bytes are never reached.
+    let mut bytes: Vec<u8> = asm::to_bytes([PUSH(1), HLT]).collect();
+    bytes.extend([0xFF, 0xFF]);
+    let mut vm = Vm::default();
+    vm.exec(
+        test_access().clone(),
+        &State::EMPTY,
+        LazyBytecodeMapped::new(&bytes[..]),
+        &|_: &Op| 1,
+        GasLimit::UNLIMITED,
+    )
+    .unwrap();
+    assert_eq!(&vm.stack[..], &[1]);
+
+    // A truncated push is reported when reached.
+    let mut bytes: Vec<u8> = asm::to_bytes([PUSH(1), PUSH(2)]).collect();
+    bytes.truncate(12);
+    let err = Vm::default()
+        .exec(
+            test_access().clone(),
+            &State::EMPTY,
+            LazyBytecodeMapped::new(bytes),
+            &|_: &Op| 1,
+            GasLimit::UNLIMITED,
+        )
+        .unwrap_err();
+    assert_eq!(err.0, 1);
+    assert!(matches!(err.1, OpError::FromBytes(_)));
+}
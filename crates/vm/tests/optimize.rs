@@ -0,0 +1,89 @@
+//! Check that optimized programs behave the same as the originals under the VM.
+
+mod util;
+
+use essential_vm::{
+    asm::{self, short::*, Op},
+    GasLimit, Vm,
+};
+use util::*;
+
+// Execute the given ops, returning the resulting VM and gas spent.
+fn exec(ops: &[Op]) -> (Vm, u64) {
+    let mut vm = Vm::default();
+    let gas = vm
+        .exec_ops(
+            ops,
+            test_access().clone(),
+            &State::EMPTY,
+            &|_: &Op| 1,
+            GasLimit::UNLIMITED,
+        )
+        .unwrap();
+    (vm, gas)
+}
+
+#[test]
+fn optimized_programs_are_equivalent() {
+    let programs: &[&[Op]] = &[
+        &[PUSH(6), PUSH(7), MUL, PUSH(42), EQ],
+        &[PUSH(1), PUSH(2), SWAP, SWAP, SUB, PUSH(0), NOT, ADD],
+        &[
+            PUSH(-8),
+            PUSH(1),
+            SHR,
+            PUSH(-8),
+            PUSH(1),
+            SHRI,
+            PUSH(3),
+            PUSH(2),
+            SHL,
+        ],
+        &[PUSH(9), DUP, POP, PUSH(4), MOD, PUSH(5), PUSH(2), DIV, BAND],
+        &[
+            PUSH(3),
+            PUSH(4),
+            LT,
+            PUSH(0),
+            OR,
+            PUSH(1),
+            PUSH(1),
+            GTE,
+            AND,
+        ],
+        // Folding within a repeat loop.
+        &[
+            PUSH(0),
+            PUSH(3),
+            PUSH(1),
+            REP,
+            PUSH(1),
+            PUSH(2),
+            ADD,
+            ADD,
+            REPE,
+        ],
+        // Folding adjacent to memory operations.
+        &[
+            PUSH(1),
+            PUSH(1),
+            ADD,
+            ALOC,
+            POP,
+            PUSH(4),
+            PUSH(1),
+            SUB,
+            PUSH(0),
+            STO,
+        ],
+    ];
+    for ops in programs {
+        let optimized = asm::optimize(ops);
+        assert!(optimized.len() < ops.len(), "{ops:?} was not optimized");
+        let (vm, gas) = exec(ops);
+        let (opt_vm, opt_gas) = exec(&optimized);
+        assert_eq!(&vm.stack[..], &opt_vm.stack[..]);
+        assert_eq!(&vm.memory[..], &opt_vm.memory[..]);
+        assert!(opt_gas < gas);
+    }
+}
@@ -1,6 +1,6 @@
 use essential_asm as asm;
 use essential_types::{solution::Solution, ContentAddress, PredicateAddress};
-use essential_vm::{Access, GasLimit, Op, Vm};
+use essential_vm::{Access, GasLimit, Op, RepeatLimits, Vm};
 use std::sync::Arc;
 
 mod util;
@@ -16,10 +16,11 @@ fn test_forall_in_asm() {
                 contract: ContentAddress([0; 32]),
                 predicate: ContentAddress([0; 32]),
             },
-            predicate_data: vec![vec![2], vec![4, 6], vec![8, 12]],
+            predicate_data: vec![[2].into(), [4, 6].into(), [8, 12].into()],
             state_mutations: vec![],
         }]),
         index: 0,
+        this_program: None,
     };
 
     let ops = &[
@@ -63,6 +64,7 @@ fn test_fold_filter_in_asm() {
             state_mutations: vec![],
         }]),
         index: 0,
+        this_program: None,
     };
 
     let ops = &[
@@ -180,3 +182,60 @@ fn test_fold_filter_in_asm() {
         .unwrap();
     assert!(res)
 }
+
+#[test]
+fn nested_repeat_counters() {
+    use asm::short::*;
+
+    // Push `x * 10 + y` for each cell of a 2x3 board.
+    let ops = &[
+        PUSH(2),
+        PUSH(1),
+        REP,
+        PUSH(3),
+        PUSH(1),
+        REP,
+        PUSH(1),
+        REPCA,
+        PUSH(10),
+        MUL,
+        PUSH(0),
+        REPCA,
+        ADD,
+        REPE,
+        REPE,
+    ];
+    let mut vm = Vm::default();
+    vm.exec_ops(
+        ops,
+        test_access().clone(),
+        &State::EMPTY,
+        &|_: &Op| 1,
+        GasLimit::UNLIMITED,
+    )
+    .unwrap();
+    assert_eq!(&vm.stack[..], &[0, 1, 2, 10, 11, 12]);
+
+    // Accessing a depth with no active repeat fails.
+    let ops = &[PUSH(2), PUSH(1), REP, PUSH(1), REPCA, REPE];
+    let err = Vm::default()
+        .exec_ops(
+            ops,
+            test_access().clone(),
+            &State::EMPTY,
+            &|_: &Op| 1,
+            GasLimit::UNLIMITED,
+        )
+        .unwrap_err();
+    assert_eq!(err.0, 4);
+
+    // The nesting depth is limited.
+    let mut vm = Vm::builder()
+        .repeat_limits(RepeatLimits { max_depth: 1 })
+        .build();
+    let ops = &[PUSH(2), PUSH(1), REP, PUSH(2), PUSH(1), REP, REPE, REPE];
+    let err = vm
+        .exec(test_access().clone(), &State::EMPTY, &ops[..])
+        .unwrap_err();
+    assert_eq!(err.0, 5);
+}
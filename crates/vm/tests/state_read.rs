@@ -14,9 +14,9 @@ fn state_read_3_42s() {
     let state = State::new(vec![(
         access.this_solution().predicate_to_solve.contract.clone(),
         vec![
-            (vec![0, 0, 0, 0], vec![42]),
-            (vec![0, 0, 0, 1], vec![42]),
-            (vec![0, 0, 0, 2], vec![42]),
+            ([0, 0, 0, 0].into(), [42].into()),
+            ([0, 0, 0, 1].into(), [42].into()),
+            ([0, 0, 0, 2].into(), [42].into()),
         ],
     )]);
     let mut vm = Vm::default();
@@ -53,7 +53,10 @@ fn state_read_some_none_some() {
     let access = test_access();
     let state = State::new(vec![(
         access.this_solution().predicate_to_solve.contract.clone(),
-        vec![(vec![0, 0, 0, 0], vec![42]), (vec![0, 0, 0, 2], vec![42])],
+        vec![
+            ([0, 0, 0, 0].into(), [42].into()),
+            ([0, 0, 0, 2].into(), [42].into()),
+        ],
     )]);
     let mut vm = Vm::default();
     let num_keys = 3;
@@ -90,9 +93,9 @@ fn state_read_ext() {
     let state = State::new(vec![(
         ext_contract_addr.clone(),
         vec![
-            (vec![1, 2, 3, 4], vec![40]),
-            (vec![1, 2, 3, 5], vec![41]),
-            (vec![1, 2, 3, 6], vec![42]),
+            ([1, 2, 3, 4].into(), [40].into()),
+            ([1, 2, 3, 5].into(), [41].into()),
+            ([1, 2, 3, 6].into(), [42].into()),
         ],
     )]);
     let mut vm = Vm::default();
@@ -173,10 +176,10 @@ fn state_read_various_size_values() {
     let state = State::new(vec![(
         access.this_solution().predicate_to_solve.contract.clone(),
         vec![
-            (vec![0, 0, 0, 0], vec![0; 2]),
-            (vec![0, 0, 0, 1], vec![1; 22]),
-            (vec![0, 0, 0, 2], vec![2; 14]),
-            (vec![0, 0, 0, 4], vec![4; 12]),
+            ([0, 0, 0, 0].into(), [0; 2].into()),
+            ([0, 0, 0, 1].into(), [1; 22].into()),
+            ([0, 0, 0, 2].into(), [2; 14].into()),
+            ([0, 0, 0, 4].into(), [4; 12].into()),
         ],
     )]);
     let mut vm = Vm::default();
@@ -217,11 +220,11 @@ fn state_read_various_size_values() {
 fn state_read_various_key_sizes() {
     let access = test_access();
     let kv_pairs = vec![
-        (vec![0], vec![0; 2]),
-        (vec![0, 1], vec![7; 6]),
-        (vec![1], vec![1; 22]),
-        (vec![0, 0, 0, 0, 0, 2], vec![2; 14]),
-        (vec![4; 1000], vec![4; 12]),
+        ([0].into(), [0; 2].into()),
+        ([0, 1].into(), [7; 6].into()),
+        ([1].into(), [1; 22].into()),
+        ([0, 0, 0, 0, 0, 2].into(), [2; 14].into()),
+        ([4; 1000].into(), [4; 12].into()),
     ];
     let state = State::new(vec![(
         access.this_solution().predicate_to_solve.contract.clone(),
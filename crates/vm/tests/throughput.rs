@@ -45,10 +45,11 @@ fn test_throughput() {
                 contract: ContentAddress([0; 32]),
                 predicate: ContentAddress([0; 32]),
             },
-            predicate_data: vec![vec![2]],
+            predicate_data: vec![[2].into()],
             state_mutations: vec![],
         }]),
         index: 0,
+        this_program: None,
     };
     let mut vm = Vm::default();
 
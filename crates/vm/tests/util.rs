@@ -3,7 +3,7 @@
 #![allow(dead_code)]
 
 use essential_vm::{
-    types::{solution::Solution, ContentAddress, Key, PredicateAddress, Word},
+    types::{solution::Solution, ContentAddress, Key, PredicateAddress, Value, Word},
     Access, StateRead, StateReads,
 };
 use std::{
@@ -38,19 +38,20 @@ pub(crate) fn test_access() -> &'static Access {
     static INSTANCE: std::sync::LazyLock<Access> = std::sync::LazyLock::new(|| Access {
         solutions: test_solutions(),
         index: 0,
+        this_program: None,
     });
     &INSTANCE
 }
 
 // A test `StateRead` implementation represented using a map.
 #[derive(Clone)]
-pub struct State(BTreeMap<ContentAddress, BTreeMap<Key, Vec<Word>>>);
+pub struct State(BTreeMap<ContentAddress, BTreeMap<Key, Value>>);
 
 #[derive(Debug, Error)]
 #[error("no value for the given contract, key pair")]
 pub struct InvalidStateRead;
 
-pub type Kv = (Key, Vec<Word>);
+pub type Kv = (Key, Value);
 
 impl State {
     // Empry state, fine for tests unrelated to reading state.
@@ -70,7 +71,7 @@ impl State {
     }
 
     // Update the value at the given key within the given contract address.
-    pub fn set(&mut self, contract_addr: ContentAddress, key: &Key, value: Vec<Word>) {
+    pub fn set(&mut self, contract_addr: ContentAddress, key: &Key, value: Value) {
         let contract = self.0.entry(contract_addr).or_default();
         if value.is_empty() {
             contract.remove(key);
@@ -85,21 +86,7 @@ impl State {
         contract_addr: ContentAddress,
         mut key: Key,
         num_words: usize,
-    ) -> Result<Vec<Vec<Word>>, InvalidStateRead> {
-        // Get the key that follows this one.
-        fn next_key(mut key: Key) -> Option<Key> {
-            for w in key.iter_mut().rev() {
-                match *w {
-                    Word::MAX => *w = Word::MIN,
-                    _ => {
-                        *w += 1;
-                        return Some(key);
-                    }
-                }
-            }
-            None
-        }
-
+    ) -> Result<Vec<Value>, InvalidStateRead> {
         // Collect the words.
         let mut words = vec![];
         for _ in 0..num_words {
@@ -110,14 +97,14 @@ impl State {
                 .cloned()
                 .unwrap_or_default();
             words.push(opt);
-            key = next_key(key).ok_or(InvalidStateRead)?;
+            key = key.next_key().ok_or(InvalidStateRead)?;
         }
         Ok(words)
     }
 }
 
 impl core::ops::Deref for State {
-    type Target = BTreeMap<ContentAddress, BTreeMap<Key, Vec<Word>>>;
+    type Target = BTreeMap<ContentAddress, BTreeMap<Key, Value>>;
     fn deref(&self) -> &Self::Target {
         &self.0
     }
@@ -131,9 +118,27 @@ impl StateRead for State {
         contract_addr: ContentAddress,
         key: Key,
         num_values: usize,
-    ) -> Result<Vec<Vec<Word>>, Self::Error> {
+    ) -> Result<Vec<Value>, Self::Error> {
         self.key_range(contract_addr, key, num_values)
     }
+
+    fn keys_with_prefix(
+        &self,
+        contract_addr: ContentAddress,
+        prefix: Key,
+        limit: usize,
+    ) -> Result<Vec<Key>, Self::Error> {
+        let Some(contract) = self.get(&contract_addr) else {
+            return Ok(vec![]);
+        };
+        Ok(contract
+            .range(prefix.clone()..)
+            .map(|(key, _)| key)
+            .take_while(|key| key.starts_with(&prefix))
+            .take(limit)
+            .cloned()
+            .collect())
+    }
 }
 
 impl StateReads for State {
@@ -3,9 +3,11 @@
 mod util;
 
 use essential_vm::{
-    asm::{self, short::*, Op},
+    asm::{self, short::*, Op, ToOpcode, Word},
+    error::{EvalError, ExecError, OpError, RestoreError, StackError},
     types::solution::{Mutation, Solution},
-    Access, BytecodeMapped, Gas, GasLimit, Vm,
+    Access, BytecodeMapped, Coverage, ExecHooks, ExecMetrics, Gas, GasLimit, GasProfile,
+    GasSchedule, Memory, OpGasCost, OpGasStats, Stack, Vm,
 };
 use std::sync::Arc;
 use util::*;
@@ -144,7 +146,10 @@ fn read_pre_post_state() {
     // In the pre-state, we have [Some(40), None, Some(42)].
     let pre_state = State::new(vec![(
         predicate_addr.contract.clone(),
-        vec![(vec![0, 0, 0, 0], vec![40]), (vec![0, 0, 0, 2], vec![42])],
+        vec![
+            ([0, 0, 0, 0].into(), [40].into()),
+            ([0, 0, 0, 2].into(), [42].into()),
+        ],
     )]);
 
     // The solutions that we're checking.
@@ -153,8 +158,8 @@ fn read_pre_post_state() {
         predicate_data: vec![],
         // We have one mutation that contracts a missing value to 41.
         state_mutations: vec![Mutation {
-            key: vec![0, 0, 0, 1],
-            value: vec![41],
+            key: [0, 0, 0, 1].into(),
+            value: [41].into(),
         }],
     }]);
 
@@ -221,7 +226,10 @@ fn read_sync_state() {
     // In the pre-state, we have [Some(40), None, Some(42)].
     let pre_state = State::new(vec![(
         predicate_addr.contract.clone(),
-        vec![(vec![0, 0, 0, 0], vec![40]), (vec![0, 0, 0, 2], vec![42])],
+        vec![
+            ([0, 0, 0, 0].into(), [40].into()),
+            ([0, 0, 0, 2].into(), [42].into()),
+        ],
     )]);
 
     // The full solution set that we're checking.
@@ -230,8 +238,8 @@ fn read_sync_state() {
         predicate_data: vec![],
         // We have one mutation that contracts a missing value to 41.
         state_mutations: vec![Mutation {
-            key: vec![0, 0, 0, 1],
-            value: vec![41],
+            key: [0, 0, 0, 1].into(),
+            value: [41].into(),
         }],
     }]);
 
@@ -311,3 +319,614 @@ fn test_halt() {
     .unwrap();
     assert_eq!(&vm.stack[..], &[42]);
 }
+
+// Test that a VM initialised from parent outputs concatenates them in order.
+#[test]
+fn with_parent_outputs() {
+    use essential_vm::{error::ParentOutputsError, Memory, Stack};
+    let parent = |stack: Vec<i64>, memory: Vec<i64>| {
+        Arc::new((
+            Stack::try_from(stack).unwrap(),
+            Memory::try_from(memory).unwrap(),
+        ))
+    };
+    let parents = [
+        parent([1, 2].into(), [10].into()),
+        parent([3].into(), [20, 30].into()),
+    ];
+    let mut vm = Vm::with_parent_outputs(&parents).unwrap();
+    assert_eq!(&vm.stack[..], &[1, 2, 3]);
+    assert_eq!(&vm.memory[..], &[10, 20, 30]);
+
+    // Execution continues from the parents' outputs.
+    let ops = &[ADD, MUL, PUSH(0), LOD, ADD];
+    vm.exec_ops(
+        ops,
+        test_access().clone(),
+        &State::EMPTY,
+        &|_: &Op| 1,
+        GasLimit::UNLIMITED,
+    )
+    .unwrap();
+    assert_eq!(&vm.stack[..], &[15]);
+
+    // Concatenating beyond the stack size limit fails.
+    let full = parent([0; Stack::SIZE_LIMIT].into(), [].into());
+    let err = Vm::with_parent_outputs(&[full.clone(), full]).unwrap_err();
+    assert!(matches!(err, ParentOutputsError::Stack(_)));
+}
+
+#[test]
+fn exec_with_coverage() {
+    let ops = |cond| [PUSH(2), PUSH(cond), JMPIF, PUSH(9), PUSH(0), HLTIF, PUSH(3)];
+    let state = State::EMPTY;
+    let run = |ops: &[Op]| {
+        let mut coverage = Coverage::default();
+        Vm::default()
+            .exec_with_coverage(
+                test_access().clone(),
+                &state,
+                ops,
+                &|_: &Op| 1,
+                GasLimit::UNLIMITED,
+                &mut coverage,
+            )
+            .unwrap();
+        coverage
+    };
+
+    // The jump is taken, skipping the push at 3.
+    let mut coverage = run(&ops(1));
+    assert_eq!(coverage.unexecuted(&ops(1)), [3]);
+    assert!(coverage.taken(2) && !coverage.not_taken(2));
+    assert!(!coverage.taken(5) && coverage.not_taken(5));
+    assert_eq!(coverage.partial_branches(&ops(1)), [2, 5]);
+    assert_eq!(coverage.ratio(&ops(1)), 6.0 / 7.0);
+
+    // Merging in a run where the jump is not taken covers everything but the halt.
+    coverage.merge(&run(&ops(0)));
+    assert!(coverage.unexecuted(&ops(0)).is_empty());
+    assert_eq!(coverage.partial_branches(&ops(0)), [5]);
+}
+
+#[test]
+fn invalid_evaluation() {
+    // Jumps past the end, leaving `2` on the stack.
+    let ops = &[PUSH(1), PUSH(2), PUSH(2), PUSH(1), JMPIF, PUSH(0)];
+    let state = State::EMPTY;
+    let err = Vm::default()
+        .eval_traced(
+            &ops[..],
+            test_access().clone(),
+            &state,
+            &|_: &Op| 1,
+            GasLimit::UNLIMITED,
+            2,
+        )
+        .unwrap_err();
+    let EvalError::InvalidEvaluation(invalid) = err else {
+        panic!("unexpected error: {err}");
+    };
+    assert_eq!(invalid.pc, Some(4));
+    assert_eq!(&invalid.stack[..], &[1, 2]);
+    assert_eq!(invalid.trace, [(3, PUSH(1)), (4, JMPIF)]);
+    let msg = invalid.to_string();
+    assert!(msg.contains("found:    Stack([1, 2])"), "{msg}");
+    assert!(msg.contains("last op:  4"), "{msg}");
+
+    // No trace is recorded by `eval`, nor any op when none are executed.
+    let err = Vm::default()
+        .eval_ops(
+            &[],
+            test_access().clone(),
+            &state,
+            &|_: &Op| 1,
+            GasLimit::UNLIMITED,
+        )
+        .unwrap_err();
+    let EvalError::InvalidEvaluation(invalid) = err else {
+        panic!("unexpected error: {err}");
+    };
+    assert_eq!(invalid.pc, None);
+    assert!(invalid.trace.is_empty());
+}
+
+#[test]
+fn exec_with_hooks() {
+    /// Records the ops executed and checks the stack never exceeds 2 words.
+    #[derive(Default)]
+    struct Hooks {
+        before: Vec<(usize, Op)>,
+        after: Vec<(usize, Vec<Word>)>,
+        max_stack: usize,
+    }
+    impl ExecHooks for Hooks {
+        fn before_op(&mut self, pc: usize, op: &Op, _stack: &Stack, _memory: &Memory) {
+            self.before.push((pc, *op));
+        }
+        fn after_op(&mut self, pc: usize, _op: &Op, stack: &Stack, _memory: &Memory) {
+            self.after.push((pc, stack.to_vec()));
+            self.max_stack = self.max_stack.max(stack.len());
+        }
+    }
+
+    let ops = &[PUSH(1), PUSH(2), ADD, PUSH(0), DIV];
+    let mut hooks = Hooks::default();
+    let err = Vm::default()
+        .exec_with_hooks(
+            test_access().clone(),
+            &State::EMPTY,
+            &ops[..],
+            &|_: &Op| 1,
+            GasLimit::UNLIMITED,
+            &mut hooks,
+        )
+        .unwrap_err();
+    assert_eq!(err.0, 4);
+    // The failed op is observed before but not after.
+    let expected: Vec<_> = ops.iter().copied().enumerate().collect();
+    assert_eq!(hooks.before, expected);
+    assert_eq!(
+        hooks.after,
+        [(0, vec![1]), (1, vec![1, 2]), (2, vec![3]), (3, vec![3, 0]),]
+    );
+    assert_eq!(hooks.max_stack, 2);
+}
+
+#[test]
+fn exec_with_metrics() {
+    // Read a single key from empty state into 2 words of memory.
+    let ops = &[
+        PUSH(2),
+        ALOC,
+        POP,
+        PUSH(0), // key
+        PUSH(1), // key length
+        PUSH(1), // num keys
+        PUSH(0), // mem addr
+        KRNG,
+        PUSH(1),
+    ];
+    let access = test_access().clone();
+    let contract = access.this_solution().predicate_to_solve.contract.clone();
+    let state = State::new(vec![(contract, vec![])]);
+    let mut vm = Vm::default();
+    let mut metrics = ExecMetrics::default();
+    vm.exec_with_metrics(
+        access,
+        &state,
+        &ops[..],
+        &|_: &Op| 1,
+        GasLimit::UNLIMITED,
+        &mut metrics,
+    )
+    .unwrap();
+    assert_eq!(
+        metrics,
+        ExecMetrics {
+            ops_executed: 9,
+            peak_stack: 4,
+            peak_memory: 2,
+            state_reads: 1,
+        }
+    );
+
+    // Metrics include the operations executed prior to an error.
+    let mut vm = Vm::default();
+    let mut failed = ExecMetrics::default();
+    let err = vm
+        .exec_with_metrics(
+            test_access().clone(),
+            &State::EMPTY,
+            &[PUSH(1), PUSH(2), PUSH(0), DIV][..],
+            &|_: &Op| 1,
+            GasLimit::UNLIMITED,
+            &mut failed,
+        )
+        .unwrap_err();
+    assert_eq!(err.0, 3);
+    assert_eq!(failed.ops_executed, 3);
+    assert_eq!(failed.peak_stack, 3);
+
+    metrics.merge(&failed);
+    assert_eq!(metrics.ops_executed, 12);
+    assert_eq!(metrics.peak_stack, 4);
+    assert_eq!(metrics.peak_memory, 2);
+}
+
+#[test]
+fn exec_profiled() {
+    // Sum the counter of a repeat counting up to 3, twice.
+    let ops = &[PUSH(0), PUSH(3), PUSH(1), REP, REPC, ADD, REPE, DUP, ADD];
+    let op_gas_cost = &|op: &Op| match op {
+        Op::Alu(_) => 5,
+        _ => 1,
+    };
+    let mut vm = Vm::default();
+    let mut profile = GasProfile::default();
+    let spent = vm
+        .exec_profiled(
+            test_access().clone(),
+            &State::EMPTY,
+            &ops[..],
+            op_gas_cost,
+            GasLimit::UNLIMITED,
+            &mut profile,
+        )
+        .unwrap();
+    assert_eq!(&vm.stack[..], &[6]);
+    assert_eq!(
+        profile.total(),
+        OpGasStats {
+            count: 15,
+            gas: spent
+        }
+    );
+    assert_eq!(
+        profile.opcodes[&ADD.to_opcode()],
+        OpGasStats { count: 4, gas: 20 }
+    );
+    assert_eq!(profile.pcs[&4], OpGasStats { count: 3, gas: 3 });
+    assert_eq!(profile.opcodes_by_gas()[0].0, ADD.to_opcode());
+
+    // The profile covers all operations up to running out of gas.
+    let mut vm = Vm::default();
+    let mut profile = GasProfile::default();
+    let limit = GasLimit {
+        per_yield: 1,
+        total: 10,
+    };
+    let err = vm
+        .exec_profiled(
+            test_access().clone(),
+            &State::EMPTY,
+            &ops[..],
+            op_gas_cost,
+            limit,
+            &mut profile,
+        )
+        .unwrap_err();
+    assert!(matches!(err, ExecError(6, OpError::OutOfGas(_))));
+    assert_eq!(profile.total(), OpGasStats { count: 6, gas: 10 });
+    assert!(profile.to_string().starts_with("op"));
+}
+
+#[test]
+fn gas_schedule() {
+    let schedule = GasSchedule {
+        alu: 3,
+        alloc_per_word: 100,
+        memory_per_word: 10,
+        ..GasSchedule::default()
+    };
+    let ops = &[
+        PUSH(4),
+        ALOC,
+        POP,
+        PUSH(1),
+        PUSH(2),
+        ADD,
+        PUSH(0),
+        PUSH(3),
+        LODR,
+    ];
+    let mut vm = Vm::default();
+    let spent = vm
+        .exec_ops(
+            ops,
+            test_access().clone(),
+            &State::EMPTY,
+            &schedule,
+            GasLimit::UNLIMITED,
+        )
+        .unwrap();
+    // 8 unit ops, 1 ALU op, 4 words allocated and 3 words loaded.
+    assert_eq!(spent, 8 + 3 + 400 + 30);
+    assert_eq!(&vm.stack[..], &[3, 0, 0, 0]);
+
+    // State reads are charged per key.
+    let schedule = GasSchedule {
+        state_read_per_key: 7,
+        ..GasSchedule::default()
+    };
+    let stack: Stack = vec![42, 1, 5, 0].try_into().unwrap();
+    assert_eq!(schedule.op_dynamic_gas_cost(&KRNG, &stack), 35);
+    assert_eq!(schedule.op_dynamic_gas_cost(&ADD, &stack), 0);
+    let stack: Stack = vec![42, 1, -5, 0].try_into().unwrap();
+    assert_eq!(schedule.op_dynamic_gas_cost(&KRNG, &stack), 0);
+    // Batched reads are charged for the keys of every read.
+    let stack: Stack = vec![42, 43, 2, 2, 7, 1, 3, 2, 0].try_into().unwrap();
+    assert_eq!(schedule.op_dynamic_gas_cost(&KRNGB, &stack), 35);
+
+    // Batched signature verification is charged per signature.
+    let schedule = GasSchedule {
+        signature_per_batch_item: 50,
+        ..GasSchedule::default()
+    };
+    let stack: Stack = vec![1, 2, 3].try_into().unwrap();
+    assert_eq!(schedule.op_dynamic_gas_cost(&VRFYEDB, &stack), 150);
+    assert_eq!(schedule.op_dynamic_gas_cost(&VRFYED, &stack), 0);
+
+    // Serializable, with omitted fields taking their defaults.
+    let json = serde_json::to_string(&schedule).unwrap();
+    assert_eq!(
+        serde_json::from_str::<GasSchedule>(&json).unwrap(),
+        schedule
+    );
+    let partial: GasSchedule = serde_json::from_str(r#"{"crypto": 100}"#).unwrap();
+    assert_eq!(partial.crypto, 100);
+    assert_eq!(partial.alu, GasSchedule::UNIT.alu);
+}
+
+#[test]
+fn vm_builder() {
+    #[derive(Default)]
+    struct Count(usize);
+    impl ExecHooks for Count {
+        fn after_op(&mut self, _: usize, _: &Op, _: &Stack, _: &Memory) {
+            self.0 += 1;
+        }
+    }
+
+    let build = || {
+        Vm::builder()
+            .stack_limit(2)
+            .memory_limit(4)
+            .gas_schedule(|_: &Op| 2)
+            .hooks(Count::default())
+            .build()
+    };
+    let state = State::EMPTY;
+
+    let mut ctx = build();
+    let gas = ctx
+        .exec(test_access().clone(), &state, &[PUSH(1), PUSH(2), ADD][..])
+        .unwrap();
+    assert_eq!(gas, 6);
+    assert_eq!(ctx.hooks.0, 3);
+    assert!(ctx.eval(test_access().clone(), &state, &[][..]).is_err());
+
+    // The stack limit is enforced.
+    let err = build()
+        .exec(
+            test_access().clone(),
+            &state,
+            &[PUSH(1), PUSH(2), PUSH(3)][..],
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ExecError(2, OpError::Stack(StackError::Overflow))
+    ));
+
+    // The memory limit is enforced.
+    let err = build()
+        .exec(test_access().clone(), &state, &[PUSH(5), ALOC][..])
+        .unwrap_err();
+    assert!(matches!(err, ExecError(1, OpError::Memory(_))));
+
+    // The gas and op limits are enforced.
+    let ops = &[PUSH(1), PUSH(2)][..];
+    let mut ctx = Vm::builder().max_ops(1).build();
+    let err = ctx.exec(test_access().clone(), &state, ops).unwrap_err();
+    assert!(matches!(err, ExecError(1, OpError::OpLimit(1))));
+    let limit = GasLimit {
+        per_yield: 1,
+        total: 3,
+    };
+    let mut ctx = build();
+    ctx.gas_limit = limit;
+    let err = ctx.exec(test_access().clone(), &state, ops).unwrap_err();
+    assert_eq!(err.out_of_gas().map(|err| err.spent), Some(2));
+
+    // The stack limit survives a snapshot.
+    let vm = build().vm;
+    let restored = Vm::restore(vm.snapshot()).unwrap();
+    assert_eq!(restored.stack.limit(), 2);
+    assert_eq!(restored.memory.limit(), 4);
+}
+
+#[test]
+fn snapshot_and_restore() {
+    // Sum the counter of a repeat counting up to 4, storing the result in memory.
+    let ops = &[
+        PUSH(1),
+        ALOC,
+        POP,
+        PUSH(0),
+        PUSH(4),
+        PUSH(1),
+        REP,
+        REPC,
+        ADD,
+        REPE,
+        PUSH(0),
+        STO,
+    ];
+    let op_gas_cost = &|_: &Op| 1;
+
+    // Run to completion without interruption.
+    let mut expected = Vm::default();
+    expected
+        .exec_ops(
+            ops,
+            test_access().clone(),
+            &State::EMPTY,
+            op_gas_cost,
+            GasLimit::UNLIMITED,
+        )
+        .unwrap();
+
+    // Run part way, into the repeat.
+    let mut vm = Vm::default();
+    vm.exec_ops(
+        &ops[..9],
+        test_access().clone(),
+        &State::EMPTY,
+        op_gas_cost,
+        GasLimit::UNLIMITED,
+    )
+    .unwrap();
+    assert_eq!(vm.repeat.len(), 1);
+
+    // Persist and restore the VM, then continue.
+    let json = serde_json::to_string(&vm).unwrap();
+    let mut restored: Vm = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, vm);
+    assert_eq!(restored.snapshot(), vm.snapshot());
+    restored.pc = 9;
+    restored
+        .exec_ops(
+            ops,
+            test_access().clone(),
+            &State::EMPTY,
+            op_gas_cost,
+            GasLimit::UNLIMITED,
+        )
+        .unwrap();
+    assert_eq!(restored, expected);
+    assert_eq!(&restored.memory[..], &[6]);
+
+    // Snapshots exceeding the limits are rejected.
+    let mut snapshot = vm.snapshot();
+    snapshot.memory_limit = 0;
+    assert!(matches!(
+        Vm::restore(snapshot),
+        Err(RestoreError::Memory(_))
+    ));
+    let mut snapshot = vm.snapshot();
+    snapshot.stack = vec![0; Stack::SIZE_LIMIT + 1];
+    assert!(matches!(Vm::restore(snapshot), Err(RestoreError::Stack(_))));
+}
+
+#[test]
+fn resume_after_out_of_gas() {
+    let ops = &[
+        PUSH(0),
+        PUSH(10),
+        PUSH(1),
+        REP,
+        REPC,
+        ADD,
+        REPE,
+        PUSH(2),
+        MUL,
+    ];
+    let op_gas_cost = &|op: &Op| match op {
+        Op::Alu(_) => 3,
+        _ => 1,
+    };
+
+    let mut expected = Vm::default();
+    let expected_gas = expected
+        .exec_ops(
+            ops,
+            test_access().clone(),
+            &State::EMPTY,
+            op_gas_cost,
+            GasLimit::UNLIMITED,
+        )
+        .unwrap();
+
+    // Repeatedly run out of gas with a small budget, resuming each time.
+    let limit = GasLimit {
+        per_yield: 1,
+        total: 7,
+    };
+    let mut vm = Vm::default();
+    let mut total = 0;
+    let mut interruptions = 0;
+    let mut res = vm.exec_ops(
+        ops,
+        test_access().clone(),
+        &State::EMPTY,
+        op_gas_cost,
+        limit,
+    );
+    loop {
+        match res {
+            Ok(gas) => {
+                total += gas;
+                break;
+            }
+            Err(err) => {
+                let oog = err.out_of_gas().expect("only runs out of gas");
+                assert_eq!(err.0, vm.pc);
+                total += oog.spent;
+                interruptions += 1;
+            }
+        }
+        res = vm.resume(
+            test_access().clone(),
+            &State::EMPTY,
+            &ops[..],
+            op_gas_cost,
+            limit,
+        );
+    }
+    assert!(interruptions > 1);
+    assert_eq!(total, expected_gas);
+    assert_eq!(vm, expected);
+    assert_eq!(&vm.stack[..], &[90]);
+}
+
+#[test]
+fn max_ops() {
+    let ops = &[PUSH(0), PUSH(10), PUSH(1), REP, REPC, ADD, REPE];
+    let op_gas_cost = &|_: &Op| 1;
+    let mut vm = Vm {
+        max_ops: Some(10),
+        ..Default::default()
+    };
+    let err = vm
+        .exec_ops(
+            ops,
+            test_access().clone(),
+            &State::EMPTY,
+            op_gas_cost,
+            GasLimit::UNLIMITED,
+        )
+        .unwrap_err();
+    assert!(matches!(err, ExecError(4, OpError::OpLimit(10))));
+    assert_eq!(vm.ops_executed, 10);
+
+    // Raising the limit allows execution to continue.
+    vm.max_ops = Some(3 + 1 + 10 * 3);
+    vm.resume(
+        test_access().clone(),
+        &State::EMPTY,
+        &ops[..],
+        op_gas_cost,
+        GasLimit::UNLIMITED,
+    )
+    .unwrap();
+    assert_eq!(&vm.stack[..], &[45]);
+    assert_eq!(vm.ops_executed, 34);
+
+    // Operations executed by compute programs count towards the limit.
+    let ops = &[PUSH(4), COM, PUSH(1), POP, COME, PUSH(1)];
+    let mut vm = Vm::default();
+    vm.exec_ops(
+        ops,
+        test_access().clone(),
+        &State::EMPTY,
+        op_gas_cost,
+        GasLimit::UNLIMITED,
+    )
+    .unwrap();
+    assert_eq!(vm.ops_executed, 2 + 4 * 3 + 1);
+    let mut vm = Vm {
+        max_ops: Some(8),
+        ..Default::default()
+    };
+    let err = vm
+        .exec_ops(
+            ops,
+            test_access().clone(),
+            &State::EMPTY,
+            op_gas_cost,
+            GasLimit::UNLIMITED,
+        )
+        .unwrap_err();
+    assert!(matches!(err, ExecError(1, OpError::OpLimit(8))));
+}